@@ -1,3 +1,5 @@
+use casr::config;
+use casr::known_bugs::known_bugs_arg;
 use casr::triage::{fuzzing_crash_triage_pipeline, CrashInfo};
 use casr::util;
 
@@ -13,6 +15,9 @@ use std::fs;
 use std::path::{Path, PathBuf};
 
 fn main() -> Result<()> {
+    // Load configuration files.
+    config::load_config("casr-afl")?;
+
     let matches = clap::Command::new("casr-afl")
         .version(clap::crate_version!())
         .about("Triage crashes found by AFL++")
@@ -20,6 +25,7 @@ fn main() -> Result<()> {
         .arg(
             Arg::new("log-level")
                 .long("log-level")
+                .env("CASR_LOG_LEVEL")
                 .short('l')
                 .action(ArgAction::Set)
                 .default_value("info")
@@ -28,6 +34,7 @@ fn main() -> Result<()> {
         )
         .arg(Arg::new("jobs")
             .long("jobs")
+            .env("CASR_JOBS")
             .short('j')
             .action(ArgAction::Set)
             .help("Number of parallel jobs for generating CASR reports [default: half of cpu cores]")
@@ -36,6 +43,7 @@ fn main() -> Result<()> {
             Arg::new("timeout")
                 .short('t')
                 .long("timeout")
+                .env("CASR_TIMEOUT")
                 .action(ArgAction::Set)
                 .default_value("0")
                 .value_name("SECONDS")
@@ -49,7 +57,7 @@ fn main() -> Result<()> {
                 .action(ArgAction::Set)
                 .value_name("INPUT_DIR")
                 .required(true)
-                .help("AFL++ work directory")
+                .help("AFL++ work directory or single fuzzer instance directory")
                 .value_parser(move |arg: &str| {
                     let i_dir = Path::new(arg);
                     if !i_dir.exists() {
@@ -102,7 +110,12 @@ fn main() -> Result<()> {
                 .last(true)
                 .help("Add \"-- ./gdb_fuzz_target <arguments>\" to generate additional crash reports with casr-gdb (e.g., test whether program crashes without sanitizers)"),
         )
+        .arg(known_bugs_arg())
+        .args(util::input_args())
+        .args(util::source_args())
+        .args(util::logging_args())
         .get_matches();
+    config::clear_env();
 
     // Init log.
     util::initialize_logging(&matches);
@@ -120,14 +133,26 @@ fn main() -> Result<()> {
         bail!("ARGS is empty, but \"ignore-cmdline\" option is provided.");
     }
 
-    // Get all crashes.
-    let mut crashes: HashMap<String, CrashInfo> = HashMap::new();
-    for node_dir in fs::read_dir(matches.get_one::<PathBuf>("input").unwrap())? {
-        let path = node_dir?.path();
-        if !path.is_dir() {
-            continue;
+    // Input directory is either a single fuzzer instance directory or AFL++
+    // output directory with several instances.
+    let input = matches.get_one::<PathBuf>("input").unwrap();
+    let nodes: Vec<PathBuf> = if is_afl_instance(input) {
+        vec![input.clone()]
+    } else {
+        let mut nodes = Vec::new();
+        for node_dir in fs::read_dir(input)? {
+            let path = node_dir?.path();
+            if path.is_dir() {
+                nodes.push(path);
+            }
         }
+        nodes.sort();
+        nodes
+    };
 
+    // Get all crashes.
+    let mut crashes: HashMap<String, CrashInfo> = HashMap::new();
+    for path in nodes {
         // Get crashes from one node.
         let mut crash_info = casr::triage::CrashInfo {
             casr_tool: casr_gdb.clone(),
@@ -179,7 +204,13 @@ fn main() -> Result<()> {
         {
             let mut info = crash_info.clone();
             info.path = crash.path();
-            crashes.insert(crash.file_name().into_string().unwrap(), info);
+            let mut name = crash.file_name().into_string().unwrap();
+            // Crashes from different instances may have the same name.
+            if crashes.contains_key(&name) {
+                name = format!("{}_{name}", path.file_name().unwrap().to_str().unwrap());
+                info.name = Some(name.clone());
+            }
+            crashes.insert(name, info);
         }
     }
 
@@ -190,3 +221,12 @@ fn main() -> Result<()> {
     // Generate reports
     fuzzing_crash_triage_pipeline(&matches, &crashes, &gdb_args)
 }
+
+/// Check that directory is AFL++ fuzzer instance directory.
+///
+/// # Arguments
+///
+/// * `path` - path to directory
+fn is_afl_instance(path: &Path) -> bool {
+    path.join("crashes").is_dir() || path.join("fuzzer_stats").is_file()
+}
@@ -1,4 +1,4 @@
-use clap::{Arg, ArgAction};
+use clap::{parser::ValueSource, Arg, ArgAction};
 use colored::Colorize;
 use cursive::event::EventTrigger;
 use cursive::View;
@@ -11,6 +11,7 @@ use std::fs::{File, OpenOptions};
 use std::io::prelude::*;
 use std::io::{BufReader, Write as BufWrite};
 use std::path::{Path, PathBuf};
+use std::rc::Rc;
 
 use anyhow::{bail, Context, Result};
 use cursive::align::Align;
@@ -22,38 +23,49 @@ use cursive::theme::Effect;
 use cursive::theme::PaletteColor::*;
 use cursive::theme::Style;
 use cursive::utils::markup::StyledString;
-use cursive::view::{Resizable, SizeConstraint};
+use cursive::view::{Nameable, Resizable, SizeConstraint};
 use cursive::views::{
-    LinearLayout, OnEventView, Panel, ResizedView, ScrollView, SelectView, TextContent, TextView,
+    EditView, LinearLayout, OnEventView, Panel, ResizedView, ScrollView, SelectView, TextContent,
+    TextView,
 };
 use cursive::CursiveRunnable;
 use cursive_tree_view::*;
+use log::{error, warn};
 use walkdir::WalkDir;
 
-use libcasr::report::CrashReport;
+use libcasr::init_ignored_frames;
+use libcasr::report::{upgrade_report, CrashReport};
 use libcasr::sarif::SarifReport;
+use libcasr::schema::{report_schema, validate_report};
+use libcasr::stacktrace::*;
 
-use casr::util::report_from_file;
+use casr::config;
+use casr::reproduce::{report_command, report_tool, rerun_report};
+use casr::util::{self, report_from_file};
 
 fn main() -> Result<()> {
+    // Load configuration files.
+    config::load_config("casr-cli")?;
+
     let matches = clap::Command::new("casr-cli")
         .version(clap::crate_version!())
-        .about("App provides text-based user interface to view CASR reports, prints joint statistics for all reports, and converts CASR reports to SARIF format.")
+        .about("App provides text-based user interface to view CASR reports, prints joint statistics for all reports, and converts CASR reports to SARIF and HTML formats.")
         .term_width(90)
         .arg(
             Arg::new("view")
                 .long("view")
                 .short('v')
+                .env("CASR_CLI_VIEW")
                 .action(ArgAction::Set)
                 .value_name("MODE")
                 .default_value("tree")
-                .help("View mode")
+                .help("View mode, tree view for directory shows clusters with reports")
                 .value_parser(["tree", "slider", "stdout"]),
         )
         .arg(
             Arg::new("target")
                 .action(ArgAction::Set)
-                .required(true)
+                .required_unless_present("schema")
                 .value_name("REPORT|DIR")
                 .value_parser(clap::value_parser!(PathBuf))
                 .help("CASR report file to view or directory with reports"),
@@ -65,6 +77,72 @@ fn main() -> Result<()> {
                 .short('u')
                 .help("Print only unique crash lines in joint statistics"),
         )
+        .arg(
+            Arg::new("format")
+                .long("format")
+                .short('f')
+                .env("CASR_CLI_FORMAT")
+                .action(ArgAction::Set)
+                .value_name("FORMAT")
+                .default_value("text")
+                .value_parser(["text", "markdown", "json", "csv"])
+                .help("Output format: joint statistics supports text and markdown (table of clusters, e.g., for CI comments), --stats and --aggregate support text, json, and csv"),
+        )
+        .arg(
+            Arg::new("known")
+                .long("known")
+                .value_name("DIR")
+                .value_parser(clap::value_parser!(PathBuf))
+                .action(ArgAction::Set)
+                .help("Directory with previously found CASR reports to mark clusters as new or known in markdown table"),
+        )
+        .arg(
+            Arg::new("stats")
+                .long("stats")
+                .value_name("PERIOD")
+                .value_parser(["day", "week", "month"])
+                .action(ArgAction::Set)
+                .help("Print statistics of severity types and crash classes over time periods (by report date)"),
+        )
+        .arg(
+            Arg::new("aggregate")
+                .long("aggregate")
+                .action(ArgAction::SetTrue)
+                .conflicts_with("stats")
+                .help("Print number of reports per execution class, severity type, crash function, and crash source file"),
+        )
+        .arg(
+            Arg::new("since")
+                .long("since")
+                .value_name("DATE")
+                .action(ArgAction::Set)
+                .help("Process only reports with date not earlier than DATE (YYYY-MM-DD or RFC 3339) in directory summary and statistics"),
+        )
+        .arg(
+            Arg::new("until")
+                .long("until")
+                .value_name("DATE")
+                .action(ArgAction::Set)
+                .help("Process only reports with date not later than DATE (YYYY-MM-DD or RFC 3339) in directory summary and statistics"),
+        )
+        .arg(
+            Arg::new("sort-by")
+                .long("sort-by")
+                .value_name("KEY")
+                .value_parser(["severity", "date", "cluster-size"])
+                .conflicts_with("stats")
+                .action(ArgAction::Set)
+                .help("Sort clusters and crashes in directory summary: the most severe, the newest, or the largest first"),
+        )
+        .arg(
+            Arg::new("report-html")
+                .long("report-html")
+                .value_name("DIR")
+                .value_parser(clap::value_parser!(PathBuf))
+                .conflicts_with_all(["stats", "aggregate", "sarif"])
+                .action(ArgAction::Set)
+                .help("Generate standalone HTML page for each CASR report and index page grouped by cluster and severity in DIR"),
+        )
         .arg(
             Arg::new("sarif")
                 .long("sarif")
@@ -91,10 +169,121 @@ fn main() -> Result<()> {
                 .action(ArgAction::Set)
                 .help("Tool name that detected crashes/errors for SARIF report"),
         )
+        .arg(
+            Arg::new("diff")
+                .long("diff")
+                .value_name("OLD")
+                .value_parser(clap::value_parser!(PathBuf))
+                .conflicts_with_all(["stats", "aggregate", "sarif", "report-html"])
+                .action(ArgAction::Set)
+                .help("Compare OLD report (directory with reports) with target report (directory): changed stack frames, execution class, crash line, and whether reports are duplicates"),
+        )
+        .arg(
+            Arg::new("browse")
+                .long("browse")
+                .short('b')
+                .action(ArgAction::SetTrue)
+                .conflicts_with_all(["stats", "aggregate", "sarif", "report-html", "diff"])
+                .help("Browse directory with reports in text-based user interface with search by execution class, severity, stack trace, crash line, and full text"),
+        )
+        .arg(
+            Arg::new("upgrade")
+                .long("upgrade")
+                .action(ArgAction::SetTrue)
+                .conflicts_with_all(["stats", "aggregate", "sarif", "report-html", "diff", "browse"])
+                .help("Upgrade all reports in directory (recursively) to the current report format version in place"),
+        )
+        .arg(
+            Arg::new("validate")
+                .long("validate")
+                .action(ArgAction::SetTrue)
+                .conflicts_with_all(["stats", "aggregate", "sarif", "report-html", "diff", "browse", "upgrade"])
+                .help("Validate report (all reports in directory recursively) against report JSON Schema and print errors"),
+        )
+        .arg(
+            Arg::new("verify")
+                .long("verify")
+                .action(ArgAction::SetTrue)
+                .conflicts_with_all(["stats", "aggregate", "sarif", "report-html", "diff", "browse", "upgrade", "validate"])
+                .help("Re-run recorded command with recorded input for report (all reports in directory recursively) and save verdict whether the same crash reproduces to report"),
+        )
+        .arg(
+            Arg::new("schema")
+                .long("schema")
+                .action(ArgAction::SetTrue)
+                .exclusive(true)
+                .help("Print JSON Schema of current report format version"),
+        )
+        // -v is taken by --view.
+        .args(util::logging_args().map(|arg| {
+            if arg.get_id() == "verbose" {
+                arg.short(None)
+                    .help("Increase logging verbosity (twice prints trace messages)")
+            } else {
+                arg
+            }
+        }))
+        .subcommand_negates_reqs(true)
+        .subcommand(
+            clap::Command::new("export")
+                .about("Export CASR report to another format")
+                .arg(
+                    Arg::new("format")
+                        .long("format")
+                        .short('f')
+                        .action(ArgAction::Set)
+                        .required(true)
+                        .value_name("FORMAT")
+                        .value_parser(["markdown"])
+                        .help("Output format: markdown (bug description for issue tracker)"),
+                )
+                .arg(
+                    Arg::new("report")
+                        .action(ArgAction::Set)
+                        .required(true)
+                        .value_name("REPORT")
+                        .value_parser(clap::value_parser!(PathBuf))
+                        .help("CASR report file to export"),
+                ),
+        )
         .get_matches();
+    config::clear_env();
+
+    util::initialize_logging(&matches);
+
+    if matches.get_flag("schema") {
+        println!("{}", serde_json::to_string_pretty(&report_schema())?);
+        return Ok(());
+    }
+
+    if let Some(("export", matches)) = matches.subcommand() {
+        return export(
+            matches.get_one::<PathBuf>("report").unwrap(),
+            matches.get_one::<String>("format").unwrap(),
+        );
+    }
 
     let report_path = matches.get_one::<PathBuf>("target").unwrap();
 
+    if matches.get_flag("validate") {
+        return validate(report_path);
+    }
+
+    if matches.get_flag("verify") {
+        return verify(report_path);
+    }
+
+    if let Some(old_path) = matches.get_one::<PathBuf>("diff") {
+        return diff(old_path, report_path);
+    }
+
+    if matches.get_flag("upgrade") {
+        if !report_path.is_dir() {
+            bail!("Upgrade mode requires directory with reports");
+        }
+        return upgrade(report_path);
+    }
+
     if let Some(sarif_report) = matches.get_one::<PathBuf>("sarif") {
         let report = sarif(
             report_path,
@@ -119,8 +308,54 @@ fn main() -> Result<()> {
         return Ok(());
     }
 
+    let format = matches.get_one::<String>("format").unwrap();
+    let range = DateRange::new(
+        matches.get_one::<String>("since").map(|s| s.as_str()),
+        matches.get_one::<String>("until").map(|s| s.as_str()),
+    )?;
+    let sort_by = matches.get_one::<String>("sort-by").map(|s| s.as_str());
+    if let Some(output) = matches.get_one::<PathBuf>("report-html") {
+        return report_html(report_path, output, &range, sort_by);
+    }
+    if let Some(period) = matches.get_one::<String>("stats") {
+        if format == "markdown" {
+            bail!("Statistics could be printed in text, json, or csv format");
+        }
+        return print_stats(report_path, period, format, &range);
+    }
+    if matches.get_flag("aggregate") {
+        if format == "markdown" {
+            bail!("Aggregate statistics could be printed in text, json, or csv format");
+        }
+        return print_aggregate_stats(report_path, format, &range);
+    }
+    if matches.get_flag("browse") {
+        if !report_path.is_dir() {
+            bail!("Browse mode requires directory with reports");
+        }
+        return browse(report_path);
+    }
+    if report_path.is_dir() && matches.value_source("view") != Some(ValueSource::DefaultValue) {
+        match matches.get_one::<String>("view").unwrap().as_str() {
+            "tree" => return joint_tree(report_path, sort_by),
+            "slider" => bail!("Slider view is supported only for report file"),
+            _ => {}
+        }
+    }
     if report_path.is_dir() {
-        print_summary(report_path, matches.get_flag("unique"));
+        if format == "json" || format == "csv" {
+            bail!("Joint statistics could be printed in text or markdown format");
+        }
+        if format == "markdown" {
+            print_markdown_summary(
+                report_path,
+                matches.get_one::<PathBuf>("known").map(|p| p.as_path()),
+                &range,
+                sort_by,
+            )?;
+        } else {
+            print_summary(report_path, matches.get_flag("unique"), &range, sort_by);
+        }
         return Ok(());
     }
 
@@ -131,7 +366,7 @@ fn main() -> Result<()> {
     file.read_to_string(&mut report_string)
         .with_context(|| format!("Couldn't read report file: {}", &report_path.display()))?;
 
-    let report: CrashReport = serde_json::from_str(&report_string)
+    let report = CrashReport::from_json(&report_string)
         .with_context(|| format!("Couldn't deserialize report: {}", &report_path.display()))?;
 
     let mut header_string = StyledString::plain("Crash Report for ");
@@ -175,6 +410,9 @@ fn main() -> Result<()> {
     let header_content = TextContent::new(header_string);
     header_content.append("\nSeverity: ");
     header_content.append(styled_severity_string);
+    if report.severity_score > 0.0 {
+        header_content.append(format!(" (score {:.1})", report.severity_score));
+    }
 
     let header = Panel::new(TextView::new_with_content(header_content.clone()));
     let footer = TextView::new("Press q to exit").align(Align::bot_right());
@@ -282,6 +520,20 @@ fn build_tree_report(
             .unwrap();
     }
 
+    for (name, value) in [
+        ("InputPath", &report.input_path),
+        ("InputSha256", &report.input_sha256),
+        ("InputData", &report.input_data),
+    ] {
+        if !value.is_empty() {
+            row = tree
+                .insert_item(name.to_string(), Placement::After, row)
+                .unwrap();
+            tree.insert_item(value.clone(), Placement::LastChild, row)
+                .unwrap();
+        }
+    }
+
     if !report.proc_fd.is_empty() {
         row = tree
             .insert_item("ProcFiles".to_string(), Placement::After, row)
@@ -326,6 +578,40 @@ fn build_tree_report(
         );
     }
 
+    if !report.partial_data.is_empty() {
+        row = tree
+            .insert_item("PartialData".to_string(), Placement::After, row)
+            .unwrap();
+        tree.insert_item(report.partial_data.clone(), Placement::LastChild, row)
+            .unwrap();
+    }
+
+    if !report.resource_usage.is_empty() {
+        row = tree
+            .insert_container_item("ResourceUsage".to_string(), Placement::After, row)
+            .unwrap();
+        report
+            .resource_usage
+            .to_string()
+            .split('\n')
+            .for_each(|line| {
+                tree.insert_item(line.to_string(), Placement::LastChild, row);
+            });
+    }
+
+    if !report.verification.is_empty() {
+        row = tree
+            .insert_container_item("Verification".to_string(), Placement::After, row)
+            .unwrap();
+        report
+            .verification
+            .to_string()
+            .split('\n')
+            .for_each(|line| {
+                tree.insert_item(line.to_string(), Placement::LastChild, row);
+            });
+    }
+
     if !report.proc_maps.is_empty() {
         row = tree
             .insert_container_item("ProcMaps".to_string(), Placement::After, row)
@@ -350,31 +636,58 @@ fn build_tree_report(
         tree.collapse_item(row);
     }
 
-    if !report.proc_status.is_empty() {
+    if let Some(status) = report.exit_status {
         row = tree
-            .insert_container_item("ProcStatus".to_string(), Placement::After, row)
+            .insert_item("ExitStatus".to_string(), Placement::After, row)
             .unwrap();
-        report.proc_status.iter().for_each(|line| {
+        tree.insert_item(status.to_string(), Placement::LastChild, row)
+            .unwrap();
+    }
+
+    if let Some(signal) = report.signal {
+        row = tree
+            .insert_item("Signal".to_string(), Placement::After, row)
+            .unwrap();
+        tree.insert_item(signal.to_string(), Placement::LastChild, row)
+            .unwrap();
+    }
+
+    if report.recovered {
+        row = tree
+            .insert_item("Recovered".to_string(), Placement::After, row)
+            .unwrap();
+        tree.insert_item("true".to_string(), Placement::LastChild, row)
+            .unwrap();
+    }
+
+    if !report.stdout.is_empty() {
+        row = tree
+            .insert_container_item("Stdout".to_string(), Placement::After, row)
+            .unwrap();
+        report.stdout.iter().for_each(|line| {
             tree.insert_item(line.clone(), Placement::LastChild, row);
         });
         tree.collapse_item(row);
     }
 
-    if !report.registers.is_empty() || !report.disassembly.is_empty() {
+    if !report.stderr.is_empty() {
         row = tree
-            .insert_container_item("CrashState".to_string(), Placement::After, row)
+            .insert_container_item("Stderr".to_string(), Placement::After, row)
             .unwrap();
-        report.registers.iter().for_each(|(k, v)| {
-            tree.insert_item(format!("{k}:    0x{v:x}"), Placement::LastChild, row);
+        report.stderr.iter().for_each(|line| {
+            tree.insert_item(line.clone(), Placement::LastChild, row);
         });
+        tree.collapse_item(row);
+    }
 
-        if !report.disassembly.is_empty() && !report.registers.is_empty() {
-            tree.insert_item("".to_string(), Placement::LastChild, row);
-        }
-
-        for line in report.disassembly.iter() {
+    if !report.proc_status.is_empty() {
+        row = tree
+            .insert_container_item("ProcStatus".to_string(), Placement::After, row)
+            .unwrap();
+        report.proc_status.iter().for_each(|line| {
             tree.insert_item(line.clone(), Placement::LastChild, row);
-        }
+        });
+        tree.collapse_item(row);
     }
 
     if !report.stacktrace.is_empty() {
@@ -387,6 +700,57 @@ fn build_tree_report(
         tree.expand_item(row);
     }
 
+    if !report.inline_frames.is_empty() {
+        row = tree
+            .insert_container_item("InlineFrames".to_string(), Placement::After, row)
+            .unwrap();
+        inline_frames(report).into_iter().for_each(|e| {
+            tree.insert_item(e, Placement::LastChild, row);
+        });
+        tree.expand_item(row);
+    }
+
+    if !report.threads.is_empty() {
+        row = tree
+            .insert_container_item("Threads".to_string(), Placement::After, row)
+            .unwrap();
+        for thread in report.threads.iter() {
+            let crashed = if thread.crashed { " (crashed)" } else { "" };
+            let thread_row = tree
+                .insert_container_item(
+                    format!("{}{crashed}", thread.description),
+                    Placement::LastChild,
+                    row,
+                )
+                .unwrap();
+            thread.stacktrace.iter().for_each(|e| {
+                tree.insert_item(e.clone(), Placement::LastChild, thread_row);
+            });
+            tree.collapse_item(thread_row);
+        }
+        tree.collapse_item(row);
+    }
+
+    if !report.alloc_stacktrace.is_empty() {
+        row = tree
+            .insert_container_item("AllocStacktrace".to_string(), Placement::After, row)
+            .unwrap();
+        report.alloc_stacktrace.iter().for_each(|e| {
+            tree.insert_item(e.clone(), Placement::LastChild, row);
+        });
+        tree.collapse_item(row);
+    }
+
+    if !report.free_stacktrace.is_empty() {
+        row = tree
+            .insert_container_item("FreeStacktrace".to_string(), Placement::After, row)
+            .unwrap();
+        report.free_stacktrace.iter().for_each(|e| {
+            tree.insert_item(e.clone(), Placement::LastChild, row);
+        });
+        tree.collapse_item(row);
+    }
+
     if !report.asan_report.is_empty() {
         row = tree
             .insert_container_item("AsanReport".to_string(), Placement::After, row)
@@ -397,6 +761,75 @@ fn build_tree_report(
         tree.expand_item(row);
     }
 
+    if !report.other_errors.is_empty() {
+        row = tree
+            .insert_container_item("OtherErrors".to_string(), Placement::After, row)
+            .unwrap();
+        report.other_errors.iter().for_each(|e| {
+            tree.insert_item(e.clone(), Placement::LastChild, row);
+        });
+        tree.collapse_item(row);
+    }
+
+    if !report.msan_report.is_empty() {
+        row = tree
+            .insert_container_item("MsanReport".to_string(), Placement::After, row)
+            .unwrap();
+        report.msan_report.iter().for_each(|e| {
+            tree.insert_item(e.clone(), Placement::LastChild, row);
+        });
+        tree.expand_item(row);
+    }
+
+    if !report.gwp_asan_report.is_empty() {
+        row = tree
+            .insert_container_item("GwpAsanReport".to_string(), Placement::After, row)
+            .unwrap();
+        report.gwp_asan_report.iter().for_each(|e| {
+            tree.insert_item(e.clone(), Placement::LastChild, row);
+        });
+        tree.expand_item(row);
+    }
+
+    if !report.hwasan_report.is_empty() {
+        row = tree
+            .insert_container_item("HwasanReport".to_string(), Placement::After, row)
+            .unwrap();
+        report.hwasan_report.iter().for_each(|e| {
+            tree.insert_item(e.clone(), Placement::LastChild, row);
+        });
+        tree.expand_item(row);
+    }
+
+    if !report.valgrind_report.is_empty() {
+        row = tree
+            .insert_container_item("ValgrindReport".to_string(), Placement::After, row)
+            .unwrap();
+        report.valgrind_report.iter().for_each(|e| {
+            tree.insert_item(e.clone(), Placement::LastChild, row);
+        });
+        tree.expand_item(row);
+    }
+
+    if !report.kernel_report.is_empty() {
+        row = tree
+            .insert_container_item("KernelReport".to_string(), Placement::After, row)
+            .unwrap();
+        report.kernel_report.iter().for_each(|e| {
+            tree.insert_item(e.clone(), Placement::LastChild, row);
+        });
+        tree.expand_item(row);
+    }
+
+    if !report.reproducer.is_empty() {
+        row = tree
+            .insert_container_item("Reproducer".to_string(), Placement::After, row)
+            .unwrap();
+        report.reproducer.iter().for_each(|e| {
+            tree.insert_item(e.clone(), Placement::LastChild, row);
+        });
+    }
+
     if !report.ubsan_report.is_empty() {
         row = tree
             .insert_container_item("UbsanReport".to_string(), Placement::After, row)
@@ -407,6 +840,16 @@ fn build_tree_report(
         tree.expand_item(row);
     }
 
+    if !report.ubsan_warnings.is_empty() {
+        row = tree
+            .insert_container_item("UbsanWarnings".to_string(), Placement::After, row)
+            .unwrap();
+        report.ubsan_warnings.iter().for_each(|e| {
+            tree.insert_item(e.clone(), Placement::LastChild, row);
+        });
+        tree.expand_item(row);
+    }
+
     if !report.python_report.is_empty() {
         row = tree
             .insert_container_item("PythonReport".to_string(), Placement::After, row)
@@ -467,6 +910,18 @@ fn build_tree_report(
         tree.expand_item(row);
     }
 
+    if !report.source_contexts.is_empty() {
+        row = tree
+            .insert_container_item("SourceContexts".to_string(), Placement::After, row)
+            .unwrap();
+        report.source_contexts.iter().for_each(|context| {
+            context.to_string().split('\n').for_each(|line| {
+                tree.insert_item(line.to_string(), Placement::LastChild, row);
+            });
+        });
+        tree.collapse_item(row);
+    }
+
     if !report.package.is_empty() {
         row = tree
             .insert_container_item("Package".to_string(), Placement::After, row)
@@ -501,7 +956,17 @@ fn build_tree_report(
 
     let scroll = ScrollView::new(tree).scroll_x(true);
     layout.add_child(header);
-    layout.add_child(scroll);
+    // Registers and disassembly are shown in dedicated pane.
+    let state = crash_state(report);
+    if state.is_empty() {
+        layout.add_child(scroll);
+    } else {
+        layout.add_child(
+            LinearLayout::horizontal()
+                .child(scroll.full_width())
+                .child(Panel::new(ScrollView::new(TextView::new(state))).title("CrashState")),
+        );
+    }
     layout.add_child(footer);
     siv.add_fullscreen_layer(layout);
 
@@ -510,6 +975,45 @@ fn build_tree_report(
     siv.run();
 }
 
+/// Get crash frame inline chain ("function at file:line:column") for pretty view
+///
+/// # Arguments
+///
+/// * 'report' - casr report
+fn inline_frames(report: &CrashReport) -> Vec<String> {
+    report
+        .inline_frames
+        .iter()
+        .map(|frame| {
+            let debug = StacktraceEntry::from(frame).debug;
+            format!("{} at {}", frame.function, CrashLine::Source(debug))
+        })
+        .collect()
+}
+
+/// Get crash state (registers and disassembly) for pretty view
+///
+/// # Arguments
+///
+/// * 'report' - casr report
+fn crash_state(report: &CrashReport) -> String {
+    let mut state = report
+        .sorted_registers()
+        .iter()
+        .fold(String::new(), |mut output, (k, v)| {
+            let _ = writeln!(output, "{k}:    0x{v:x}");
+            output
+        });
+
+    if !report.disassembly.is_empty() {
+        if !state.is_empty() {
+            state.push('\n');
+        }
+        state.push_str(&report.disassembly.join("\n"));
+    }
+    state
+}
+
 /// Creates slider view for casr report
 ///
 /// # Arguments
@@ -561,6 +1065,18 @@ fn build_slider_report(
         select.add_item("Stdin", report.stdin.clone());
     }
 
+    if !report.input_path.is_empty() {
+        select.add_item("InputPath", report.input_path.clone());
+    }
+
+    if !report.input_sha256.is_empty() {
+        select.add_item("InputSha256", report.input_sha256.clone());
+    }
+
+    if !report.input_data.is_empty() {
+        select.add_item("InputData", report.input_data.clone());
+    }
+
     if !report.proc_fd.is_empty() {
         select.add_item("ProcFiles", report.proc_fd.join("\n"));
     }
@@ -584,29 +1100,51 @@ fn build_slider_report(
         ),
     );
 
-    if !report.proc_maps.is_empty() {
-        select.add_item("ProcMaps", report.proc_maps.join("\n"));
+    if !report.partial_data.is_empty() {
+        select.add_item("PartialData", report.partial_data.clone());
     }
 
-    if !report.proc_environ.is_empty() {
-        select.add_item("ProcEnviron", report.proc_environ.join("\n"));
+    if !report.resource_usage.is_empty() {
+        select.add_item("ResourceUsage", report.resource_usage.to_string());
     }
 
-    if !report.proc_status.is_empty() {
-        select.add_item("ProcStatus", report.proc_status.join("\n"));
+    if !report.verification.is_empty() {
+        select.add_item("Verification", report.verification.to_string());
     }
 
-    let mut state = report
-        .registers
-        .iter()
-        .fold(String::new(), |mut output, (k, v)| {
-            let _ = writeln!(output, "{k}:    0x{v:x}");
-            output
-        });
+    if !report.proc_maps.is_empty() {
+        select.add_item("ProcMaps", report.proc_maps.join("\n"));
+    }
 
-    if !report.disassembly.is_empty() {
-        state.push_str(&format!("\n{}", &report.disassembly.join("\n")));
+    if !report.proc_environ.is_empty() {
+        select.add_item("ProcEnviron", report.proc_environ.join("\n"));
+    }
+
+    if let Some(status) = report.exit_status {
+        select.add_item("ExitStatus", status.to_string());
     }
+
+    if let Some(signal) = report.signal {
+        select.add_item("Signal", signal.to_string());
+    }
+
+    if report.recovered {
+        select.add_item("Recovered", "true".to_string());
+    }
+
+    if !report.stdout.is_empty() {
+        select.add_item("Stdout", report.stdout.join("\n"));
+    }
+
+    if !report.stderr.is_empty() {
+        select.add_item("Stderr", report.stderr.join("\n"));
+    }
+
+    if !report.proc_status.is_empty() {
+        select.add_item("ProcStatus", report.proc_status.join("\n"));
+    }
+
+    let state = crash_state(report);
     if !state.is_empty() {
         select.add_item("CrashState", state);
     }
@@ -615,14 +1153,74 @@ fn build_slider_report(
         select.add_item("Stacktrace", report.stacktrace.join("\n"));
     }
 
+    if !report.inline_frames.is_empty() {
+        select.add_item("InlineFrames", inline_frames(report).join("\n"));
+    }
+
+    if !report.threads.is_empty() {
+        let threads: Vec<String> = report
+            .threads
+            .iter()
+            .map(|thread| {
+                let crashed = if thread.crashed { " (crashed)" } else { "" };
+                format!(
+                    "{}{crashed}\n{}",
+                    thread.description,
+                    thread.stacktrace.join("\n")
+                )
+            })
+            .collect();
+        select.add_item("Threads", threads.join("\n\n"));
+    }
+
+    if !report.alloc_stacktrace.is_empty() {
+        select.add_item("AllocStacktrace", report.alloc_stacktrace.join("\n"));
+    }
+
+    if !report.free_stacktrace.is_empty() {
+        select.add_item("FreeStacktrace", report.free_stacktrace.join("\n"));
+    }
+
     if !report.asan_report.is_empty() {
         select.add_item("AsanReport", report.asan_report.join("\n"));
     }
 
+    if !report.other_errors.is_empty() {
+        select.add_item("OtherErrors", report.other_errors.join("\n"));
+    }
+
+    if !report.msan_report.is_empty() {
+        select.add_item("MsanReport", report.msan_report.join("\n"));
+    }
+
+    if !report.gwp_asan_report.is_empty() {
+        select.add_item("GwpAsanReport", report.gwp_asan_report.join("\n"));
+    }
+
+    if !report.hwasan_report.is_empty() {
+        select.add_item("HwasanReport", report.hwasan_report.join("\n"));
+    }
+
+    if !report.valgrind_report.is_empty() {
+        select.add_item("ValgrindReport", report.valgrind_report.join("\n"));
+    }
+
+    if !report.kernel_report.is_empty() {
+        select.add_item("KernelReport", report.kernel_report.join("\n"));
+    }
+
+    if !report.reproducer.is_empty() {
+        select.add_item("Reproducer", report.reproducer.join("\n"));
+    }
+
     if !report.ubsan_report.is_empty() {
         select.add_item("UbsanReport", report.ubsan_report.join("\n"));
     }
 
+    if !report.ubsan_warnings.is_empty() {
+        select.add_item("UbsanWarnings", report.ubsan_warnings.join("\n"));
+    }
+
     if !report.python_report.is_empty() {
         select.add_item("PythonReport", report.python_report.join("\n"));
     }
@@ -647,6 +1245,15 @@ fn build_slider_report(
         select.add_item("Source", report.source.join("\n"));
     }
 
+    if !report.source_contexts.is_empty() {
+        let contexts: Vec<String> = report
+            .source_contexts
+            .iter()
+            .map(|context| context.to_string())
+            .collect();
+        select.add_item("SourceContexts", contexts.join("\n\n"));
+    }
+
     if !report.package.is_empty() {
         select.add_item("Package", report.package.clone());
     }
@@ -713,6 +1320,656 @@ fn build_slider_report(
     siv.run();
 }
 
+/// CASR report in search index. Fields for search are lowercase.
+struct IndexEntry {
+    /// Path to report
+    path: PathBuf,
+    /// Results list item
+    title: String,
+    /// Severity type
+    severity: String,
+    /// Execution class short description
+    class: String,
+    /// Crash line
+    crashline: String,
+    /// Stack trace
+    stacktrace: String,
+    /// Full report text
+    text: String,
+}
+
+impl IndexEntry {
+    /// Read CASR report and index it
+    ///
+    /// # Arguments
+    ///
+    /// * 'path' - path to report
+    ///
+    /// * 'dir' - directory with reports, report path is shown relative to it
+    fn new(path: &Path, dir: &Path) -> Result<Self> {
+        let report = report_from_file(path)?;
+        let class = &report.execution_class;
+        let title = format!(
+            "{:<20} {:<24} {}  {}",
+            class.severity,
+            class.short_description,
+            report.crashline,
+            path.strip_prefix(dir).unwrap_or(path).display()
+        );
+        Ok(IndexEntry {
+            path: path.to_path_buf(),
+            title,
+            severity: class.severity.to_lowercase(),
+            class: class.short_description.to_lowercase(),
+            crashline: report.crashline.to_lowercase(),
+            stacktrace: report.stacktrace.join("\n").to_lowercase(),
+            text: report.to_string().to_lowercase(),
+        })
+    }
+
+    /// Check that report matches all query terms. Term may be restricted to
+    /// one field via prefix: `class:`, `severity:`, `crashline:`, or `stack:`.
+    /// Other terms are searched in full report text. Search is case-insensitive.
+    ///
+    /// # Arguments
+    ///
+    /// * 'query' - search query
+    fn matches(&self, query: &str) -> bool {
+        query.split_whitespace().all(|term| {
+            let term = term.to_lowercase();
+            let (field, value) = match term.split_once(':') {
+                Some(("class", value)) => (&self.class, value),
+                Some(("severity", value)) => (&self.severity, value),
+                Some(("crashline", value)) => (&self.crashline, value),
+                Some(("stack", value)) => (&self.stacktrace, value),
+                _ => (&self.text, term.as_str()),
+            };
+            field.contains(value)
+        })
+    }
+}
+
+/// Upgrade reports in directory (recursively) to the current report format
+/// version. Reports are rewritten in place, reports of the current version are
+/// left untouched.
+///
+/// # Arguments
+///
+/// * 'dir' - directory with reports
+fn upgrade(dir: &Path) -> Result<()> {
+    let (mut upgraded, mut current, mut failed) = (0, 0, 0);
+    for entry in WalkDir::new(dir).sort_by_file_name().into_iter().flatten() {
+        let path = entry.path();
+        if !path.is_file() || path.extension().map_or(true, |ext| ext != "casrep") {
+            continue;
+        }
+        let result = fs::read_to_string(path)
+            .map_err(anyhow::Error::from)
+            .and_then(|json| {
+                let mut value: Value = serde_json::from_str(&json)?;
+                if !upgrade_report(&mut value)? {
+                    return Ok(false);
+                }
+                let report: CrashReport = serde_json::from_value(value)?;
+                fs::write(path, serde_json::to_string_pretty(&report)?)?;
+                Ok(true)
+            });
+        match result {
+            Ok(true) => upgraded += 1,
+            Ok(false) => current += 1,
+            Err(e) => {
+                error!("Couldn't upgrade report {}: {e}", path.display());
+                failed += 1;
+            }
+        }
+    }
+    println!("Number of upgraded reports: {upgraded}");
+    println!("Number of up-to-date reports: {current}");
+    if failed != 0 {
+        bail!("Couldn't upgrade {failed} reports");
+    }
+    Ok(())
+}
+
+/// Export report to another format and print it
+///
+/// # Arguments
+///
+/// * `path` - report file
+///
+/// * `format` - output format
+fn export(path: &Path, format: &str) -> Result<()> {
+    let report = report_from_file(path)?;
+    match format {
+        "markdown" => print!("{}", report.to_markdown()),
+        _ => bail!("Unsupported export format: {format}"),
+    }
+    Ok(())
+}
+
+/// Validate reports against report JSON Schema and print errors
+///
+/// # Arguments
+///
+/// * `path` - report file or directory with reports
+fn validate(path: &Path) -> Result<()> {
+    let (mut valid, mut invalid) = (0, 0);
+    for report in report_files(path) {
+        let errors = fs::read_to_string(&report)
+            .map_err(anyhow::Error::from)
+            .and_then(|json| Ok(serde_json::from_str::<Value>(&json)?))
+            .map_or_else(|e| vec![e.to_string()], |value| validate_report(&value));
+        if errors.is_empty() {
+            valid += 1;
+            continue;
+        }
+        for error in errors {
+            println!("{}: {error}", report.display());
+        }
+        invalid += 1;
+    }
+    println!("Number of valid reports: {valid}");
+    println!("Number of invalid reports: {invalid}");
+    if invalid != 0 {
+        bail!("{invalid} reports don't match report schema");
+    }
+    Ok(())
+}
+
+/// Re-run recorded commands with recorded inputs and save verification
+/// verdicts to reports
+///
+/// # Arguments
+///
+/// * `path` - report file or directory with reports
+fn verify(path: &Path) -> Result<()> {
+    let (mut reproduced, mut changed, mut not_reproduced, mut failed) = (0, 0, 0, 0);
+    for report in report_files(path) {
+        match verify_report(&report) {
+            Ok(verdict) => {
+                println!("{}: {verdict}", report.display());
+                match verdict.as_str() {
+                    "Reproduced" => reproduced += 1,
+                    "Changed" => changed += 1,
+                    _ => not_reproduced += 1,
+                }
+            }
+            Err(e) => {
+                error!("Couldn't verify report {}: {e}", report.display());
+                failed += 1;
+            }
+        }
+    }
+    println!("Number of reproduced crashes: {reproduced}");
+    println!("Number of changed crashes: {changed}");
+    println!("Number of not reproduced crashes: {not_reproduced}");
+    if failed != 0 {
+        bail!("Couldn't verify {failed} reports");
+    }
+    Ok(())
+}
+
+/// Re-run target from report with casr tool that is chosen by report content
+/// and save verification verdict to report. Crashing input is restored from
+/// report if it is missing or changed.
+///
+/// # Arguments
+///
+/// * `path` - path to report
+///
+/// # Return value
+///
+/// Verification verdict
+fn verify_report(path: &Path) -> Result<String> {
+    let mut report = report_from_file(path)?;
+    let tool = report_tool(&report)?;
+
+    // Restore crashing input if it is missing or changed.
+    let rerun = if report.input_sha256.is_empty() {
+        let (argv, stdin) = report_command(&report, None)?;
+        rerun_report(&tool, &argv, stdin.as_deref(), 0)
+    } else {
+        // Directory is created with random name and removed when dropped.
+        let dir = tempfile::Builder::new()
+            .prefix("casr_verify_")
+            .tempdir()
+            .with_context(|| "Couldn't create temporary directory")?;
+        let input = report.restore_input(dir.path())?;
+        let (argv, stdin) = report_command(&report, Some(&input))?;
+        rerun_report(&tool, &argv, stdin.as_deref(), 0)
+    }?;
+    report.set_verification(rerun.as_ref());
+    fs::write(path, serde_json::to_string_pretty(&report)?)?;
+    Ok(report.verification.verdict)
+}
+
+/// Get report files: report itself or all reports in directory recursively
+///
+/// # Arguments
+///
+/// * `path` - report file or directory with reports
+fn report_files(path: &Path) -> Vec<PathBuf> {
+    if path.is_dir() {
+        WalkDir::new(path)
+            .sort_by_file_name()
+            .into_iter()
+            .flatten()
+            .map(|entry| entry.into_path())
+            .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "casrep"))
+            .collect()
+    } else {
+        vec![path.to_path_buf()]
+    }
+}
+
+/// Browse directory with reports in TUI: search query pane, results pane,
+/// and selected report pane
+///
+/// # Arguments
+///
+/// * 'dir' - directory with reports
+fn browse(dir: &Path) -> Result<()> {
+    let index: Vec<IndexEntry> = WalkDir::new(dir)
+        .sort_by_file_name()
+        .into_iter()
+        .flatten()
+        .map(|entry| entry.path().to_path_buf())
+        .filter(|path| path.is_file())
+        .filter(|path| path.extension().is_some() && path.extension().unwrap() == "casrep")
+        .filter_map(|path| match IndexEntry::new(&path, dir) {
+            Ok(entry) => Some(entry),
+            Err(e) => {
+                warn!("{e}");
+                None
+            }
+        })
+        .collect();
+    if index.is_empty() {
+        bail!("There are no CASR reports in {}", dir.display());
+    }
+    let index = Rc::new(index);
+
+    // Fill results pane with reports that match query.
+    let fill = |index: &[IndexEntry], query: &str, results: &mut SelectView<usize>| {
+        results.clear();
+        for (i, entry) in index.iter().enumerate() {
+            if entry.matches(query) {
+                results.add_item(entry.title.clone(), i);
+            }
+        }
+        format!("Reports: {}/{}", results.len(), index.len())
+    };
+
+    let mut results = SelectView::<usize>::new();
+    let count = fill(&index, "", &mut results);
+    let select_index = index.clone();
+    results.set_on_select(move |s, i| {
+        let text = match report_from_file(&select_index[*i].path) {
+            Ok(report) => format!("{}\n\n{}", select_index[*i].path.display(), report),
+            Err(e) => e.to_string(),
+        };
+        s.call_on_name("report", |view: &mut TextView| view.set_content(text));
+    });
+    let edit_index = index.clone();
+    let query = EditView::new().on_edit(move |s, query, _| {
+        let count = s
+            .call_on_name("results", |results: &mut SelectView<usize>| {
+                fill(&edit_index, query, results)
+            })
+            .unwrap_or_default();
+        s.call_on_name("count", |view: &mut TextView| view.set_content(count));
+    });
+
+    let mut theme = cursive::theme::load_default();
+    theme.palette[Background] = TerminalDefault;
+    theme.palette[View] = TerminalDefault;
+    theme.palette[Primary] = TerminalDefault;
+    let mut siv = cursive::default();
+    siv.set_theme(theme);
+
+    let panes = LinearLayout::horizontal()
+        .child(
+            Panel::new(ScrollView::new(results.with_name("results")).scroll_x(true))
+                .title("Reports")
+                .full_height()
+                .min_width(60),
+        )
+        .child(
+            Panel::new(ScrollView::new(TextView::new("").with_name("report")).scroll_x(true))
+                .title("Report")
+                .full_screen(),
+        );
+    let layout = LinearLayout::vertical()
+        .child(Panel::new(query).title("Search"))
+        .child(panes)
+        .child(
+            LinearLayout::horizontal()
+                .child(TextView::new(count).with_name("count").full_width())
+                .child(TextView::new(
+                    "Filters: class: severity: crashline: stack:  Tab to switch panes, Esc to exit",
+                )),
+        );
+    siv.add_fullscreen_layer(layout);
+    siv.add_global_callback(cursive::event::Key::Esc, |s| s.quit());
+    siv.run();
+
+    Ok(())
+}
+
+/// Joint tree view item: cluster or report
+#[derive(Debug)]
+struct JointItem {
+    /// Row text
+    title: String,
+    /// Severity type
+    severity: String,
+    /// Path to report, None for cluster
+    report: Option<PathBuf>,
+    /// Path to crash input (reproducer)
+    input: Option<PathBuf>,
+}
+
+impl std::fmt::Display for JointItem {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.title)
+    }
+}
+
+/// Cluster of reports in joint tree view
+struct JointCluster {
+    /// Cluster name
+    name: String,
+    /// Cluster sort key
+    key: SortKey,
+    /// Reports with sort keys
+    reports: Vec<(SortKey, JointItem)>,
+}
+
+/// Read clusters (cl* directories) and reports for joint tree view. If there
+/// are no clusters, directory is considered as one cluster.
+///
+/// # Arguments
+///
+/// * 'dir' - directory with reports
+fn joint_clusters(dir: &Path) -> Result<Vec<JointCluster>> {
+    let mut cluster_dirs: Vec<(PathBuf, i32)> = fs::read_dir(dir)
+        .with_context(|| format!("Couldn't read directory: {}", dir.display()))?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir())
+        .filter_map(|path| {
+            let name = path.file_name()?.to_str()?;
+            let number = name.strip_prefix("cl")?.parse::<i32>().ok()?;
+            Some((path, number))
+        })
+        .collect();
+    cluster_dirs.sort_by_key(|(_, number)| *number);
+    if cluster_dirs.is_empty() {
+        cluster_dirs.push((dir.to_path_buf(), 0));
+    }
+
+    let mut clusters = Vec::new();
+    for (cluster_dir, _) in cluster_dirs {
+        let mut reports: Vec<(SortKey, JointItem)> = Vec::new();
+        for path in WalkDir::new(&cluster_dir)
+            .max_depth(1)
+            .sort_by_file_name()
+            .into_iter()
+            .flatten()
+            .map(|entry| entry.path().to_path_buf())
+            .filter(|path| path.is_file())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "casrep"))
+        {
+            let report = match report_from_file(&path) {
+                Ok(report) => report,
+                Err(e) => {
+                    warn!("{e}");
+                    continue;
+                }
+            };
+            // crash.gdb.casrep and crash.casrep are created for crash input.
+            let mut input = path.with_extension("");
+            if input.extension().is_some_and(|ext| ext == "gdb") {
+                input.set_extension("");
+            }
+            let input = input.is_file().then_some(input);
+            let size = input
+                .as_ref()
+                .and_then(|input| fs::metadata(input).ok())
+                .map(|metadata| metadata.len().to_string())
+                .unwrap_or_default();
+            let class = &report.execution_class;
+            let title = format!(
+                "{:<20} {:<24} {:<40} {:>8}  {}",
+                class.severity,
+                class.short_description,
+                report.crashline,
+                size,
+                path.file_name().unwrap().to_str().unwrap()
+            );
+            reports.push((
+                SortKey::new(&report),
+                JointItem {
+                    title,
+                    severity: class.severity.clone(),
+                    report: Some(path),
+                    input,
+                },
+            ));
+        }
+        if reports.is_empty() {
+            continue;
+        }
+        let mut key = reports
+            .iter()
+            .fold(SortKey::default(), |key, (report_key, _)| {
+                key.merge(report_key)
+            });
+        key.size = reports.len();
+        clusters.push(JointCluster {
+            name: cluster_dir
+                .strip_prefix(dir)
+                .ok()
+                .filter(|name| !name.as_os_str().is_empty())
+                .unwrap_or(&cluster_dir)
+                .display()
+                .to_string(),
+            key,
+            reports,
+        });
+    }
+    if clusters.is_empty() {
+        bail!("There are no CASR reports in {}", dir.display());
+    }
+    Ok(clusters)
+}
+
+/// Fill joint tree view with clusters and reports sorted by key
+///
+/// # Arguments
+///
+/// * 'tree' - tree view
+///
+/// * 'clusters' - clusters of reports
+///
+/// * 'sort_by' - sort clusters and reports by severity or cluster size
+fn fill_joint_tree(tree: &mut TreeView<JointItem>, clusters: &mut [JointCluster], sort_by: &str) {
+    tree.clear();
+    clusters.sort_by(|a, b| a.key.cmp_by(&b.key, sort_by));
+    let mut row = 0;
+    for (i, cluster) in clusters.iter_mut().enumerate() {
+        cluster.reports.sort_by(|a, b| a.0.cmp_by(&b.0, "severity"));
+        // Reports are sorted, so the first one is the most severe.
+        let severity = cluster.reports[0].1.severity.clone();
+        let item = JointItem {
+            title: format!(
+                "{} ({} reports, {})",
+                cluster.name,
+                cluster.reports.len(),
+                severity
+            ),
+            severity,
+            report: None,
+            input: None,
+        };
+        let placement = if i == 0 {
+            Placement::Parent
+        } else {
+            Placement::After
+        };
+        row = tree.insert_container_item(item, placement, row).unwrap();
+        for (_, report) in cluster.reports.iter() {
+            tree.insert_item(
+                JointItem {
+                    title: report.title.clone(),
+                    severity: report.severity.clone(),
+                    report: report.report.clone(),
+                    input: report.input.clone(),
+                },
+                Placement::LastChild,
+                row,
+            );
+        }
+        tree.collapse_item(row);
+    }
+}
+
+/// Show joint tree view for directory with reports: clusters with reports
+/// (execution class, severity, crash line, and crash input size), and
+/// preview of selected report. Clusters are sorted by severity or cluster
+/// size, vi-style keys are supported, and crash input may be opened in
+/// $EDITOR.
+///
+/// # Arguments
+///
+/// * 'dir' - directory with reports
+///
+/// * 'sort_by' - initial sort key: severity or cluster-size
+fn joint_tree(dir: &Path, sort_by: Option<&str>) -> Result<()> {
+    let mut clusters = joint_clusters(dir)?;
+    let sort_by = match sort_by {
+        Some("cluster-size") => "cluster-size",
+        _ => "severity",
+    };
+    let mut tree = TreeView::<JointItem>::new();
+    fill_joint_tree(&mut tree, &mut clusters, sort_by);
+    let clusters = Rc::new(std::cell::RefCell::new(clusters));
+
+    tree.set_on_select(|s, row| {
+        let report = s
+            .call_on_name("tree", |tree: &mut TreeView<JointItem>| {
+                tree.borrow_item(row).and_then(|item| item.report.clone())
+            })
+            .flatten();
+        let text = match report {
+            Some(path) => match report_from_file(&path) {
+                Ok(report) => format!("{}\n\n{}", path.display(), report),
+                Err(e) => e.to_string(),
+            },
+            None => String::new(),
+        };
+        s.call_on_name("preview", |view: &mut TextView| view.set_content(text));
+    });
+
+    // Vi-style navigation.
+    let key = |key: cursive::event::Key| cursive::event::Event::Key(key);
+    let tree = OnEventView::new(tree.with_name("tree"))
+        .on_pre_event_inner('j', move |tree, _| {
+            Some(tree.on_event(key(cursive::event::Key::Down)))
+        })
+        .on_pre_event_inner('k', move |tree, _| {
+            Some(tree.on_event(key(cursive::event::Key::Up)))
+        })
+        .on_pre_event_inner('g', move |tree, _| {
+            Some(tree.on_event(key(cursive::event::Key::Home)))
+        })
+        .on_pre_event_inner('G', move |tree, _| {
+            Some(tree.on_event(key(cursive::event::Key::End)))
+        })
+        .on_pre_event_inner('h', |tree, _| {
+            let mut tree = tree.get_mut();
+            let row = tree.row()?;
+            let row = tree.item_parent(row).unwrap_or(row);
+            tree.collapse_item(row);
+            tree.set_selected_row(row);
+            Some(EventResult::Consumed(None))
+        })
+        .on_pre_event_inner('l', |tree, _| {
+            let mut tree = tree.get_mut();
+            let row = tree.row()?;
+            tree.expand_item(row);
+            Some(EventResult::Consumed(None))
+        });
+
+    let mut theme = cursive::theme::load_default();
+    theme.palette[Background] = TerminalDefault;
+    theme.palette[View] = TerminalDefault;
+    theme.palette[Primary] = TerminalDefault;
+    let mut siv = cursive::default();
+    siv.set_theme(theme);
+
+    let panes = LinearLayout::horizontal()
+        .child(
+            Panel::new(ScrollView::new(tree).scroll_x(true))
+                .title("Clusters")
+                .full_height()
+                .min_width(100),
+        )
+        .child(
+            Panel::new(ScrollView::new(TextView::new("").with_name("preview")).scroll_x(true))
+                .title("Report")
+                .full_screen(),
+        );
+    let layout = LinearLayout::vertical().child(panes).child(
+        TextView::new(
+            "j/k: down/up  h/l: collapse/expand  g/G: top/bottom  s: sort by severity  c: sort by cluster size  e: open crash input in $EDITOR  Tab: switch panes  q: exit",
+        )
+        .align(Align::bot_right()),
+    );
+    siv.add_fullscreen_layer(layout);
+
+    for (trigger, sort_by) in [('s', "severity"), ('c', "cluster-size")] {
+        let clusters = clusters.clone();
+        siv.add_global_callback(trigger, move |s| {
+            s.call_on_name("tree", |tree: &mut TreeView<JointItem>| {
+                fill_joint_tree(tree, &mut clusters.borrow_mut(), sort_by)
+            });
+        });
+    }
+    siv.add_global_callback('e', |s| {
+        let input = s
+            .call_on_name("tree", |tree: &mut TreeView<JointItem>| {
+                tree.row()
+                    .and_then(|row| tree.borrow_item(row))
+                    .and_then(|item| item.input.clone())
+            })
+            .flatten();
+        if let Some(input) = input {
+            // Editor is run after terminal is restored.
+            s.set_user_data(input);
+            s.quit();
+        }
+    });
+    siv.add_global_callback('q', |s| s.quit());
+
+    loop {
+        siv.run();
+        let Some(input) = siv.take_user_data::<PathBuf>() else {
+            break;
+        };
+        let editor = std::env::var("EDITOR").unwrap_or_else(|_| "vi".to_string());
+        let status = std::process::Command::new(&editor)
+            .arg(&input)
+            .status()
+            .with_context(|| format!("Couldn't run {editor}"))?;
+        if !status.success() {
+            warn!("{editor} exited with {status}");
+        }
+    }
+
+    Ok(())
+}
+
 enum Action {
     Arrow(i32),
     Mouse(cursive::event::Event),
@@ -779,7 +2036,11 @@ fn change_text_view(layout1: &mut LinearLayout, act: Action) -> Option<EventResu
 ///
 /// * 'unique_crash_line' - print summary only for unique crash lines
 ///
-fn print_summary(dir: &Path, unique_crash_line: bool) {
+/// * 'range' - date range of reports to print
+///
+/// * 'sort_by' - sort clusters and crashes by severity, date, or cluster size
+///
+fn print_summary(dir: &Path, unique_crash_line: bool, range: &DateRange, sort_by: Option<&str>) {
     // Hash each class in whole casr directory
     let mut casr_classes: BTreeMap<String, i32> = BTreeMap::new();
 
@@ -827,6 +2088,8 @@ fn print_summary(dir: &Path, unique_crash_line: bool) {
         clusters.push((dir.to_path_buf(), 0));
     }
 
+    // Cluster sort key and summary
+    let mut summaries: Vec<(SortKey, String)> = Vec::new();
     for (clpath, _) in clusters {
         let cluster = clpath.as_path();
         let filename = cluster.file_name().unwrap().to_str().unwrap();
@@ -835,7 +2098,7 @@ fn print_summary(dir: &Path, unique_crash_line: bool) {
         let mut ubsan = true;
 
         // Hash each crash in cluster
-        let mut cluster_hash: BTreeMap<String, (Vec<String>, i32)> = BTreeMap::new();
+        let mut cluster_hash: BTreeMap<String, (Vec<String>, i32, SortKey)> = BTreeMap::new();
         // Hash each class in cluster
         let mut cluster_classes: BTreeMap<String, i32> = BTreeMap::new();
         // Hash files
@@ -874,6 +2137,18 @@ fn print_summary(dir: &Path, unique_crash_line: bool) {
             let mut report = input.to_str().unwrap().to_string();
             report.push_str(".casrep");
 
+            // Severity and date of crash
+            let mut key = SortKey::default();
+            if let Some(casrep) = [report.clone(), report.replace(".casrep", ".gdb.casrep")]
+                .iter()
+                .find_map(|path| report_from_file(Path::new(path)).ok())
+            {
+                if !range.contains(&casrep.date) {
+                    continue;
+                }
+                key = SortKey::new(&casrep);
+            }
+
             let (san_desc, san_line) = if let Some((report_sum, san_desc, san_line, ubsan_flag)) =
                 process_report(&report, "casrep")
             {
@@ -927,9 +2202,9 @@ fn print_summary(dir: &Path, unique_crash_line: bool) {
             }
 
             let value = if let Some(res) = cluster_hash.get(&hash) {
-                (res.0.clone(), res.1 + 1)
+                (res.0.clone(), res.1 + 1, res.2.merge(&key))
             } else {
-                (result, 1)
+                (result, 1, key)
             };
             cluster_hash.insert(hash, value);
         }
@@ -938,23 +2213,36 @@ fn print_summary(dir: &Path, unique_crash_line: bool) {
             continue;
         }
 
-        println!("==> <{}>", filename.magenta());
-        for info in cluster_hash.values() {
+        let mut crashes: Vec<_> = cluster_hash.values().collect();
+        if let Some(sort_by) = sort_by {
+            crashes.sort_by(|a, b| {
+                let (mut a_key, mut b_key) = (a.2.clone(), b.2.clone());
+                a_key.size = a.1 as usize;
+                b_key.size = b.1 as usize;
+                a_key.cmp_by(&b_key, sort_by)
+            });
+        }
+        let mut cluster_key = SortKey::default();
+        let mut summary = String::new();
+        let _ = writeln!(summary, "==> <{}>", filename.magenta());
+        for info in crashes {
+            cluster_key = cluster_key.merge(&info.2);
+            cluster_key.size += info.1 as usize;
             if ubsan {
                 // /path/to/report.casrep: Description: crashline (path:line:column)
-                println!("{}: {}", info.0.last().unwrap(), info.0[0]);
+                let _ = writeln!(summary, "{}: {}", info.0.last().unwrap(), info.0[0]);
                 continue;
             }
             // Crash: /path/to/input or /path/to/report.casrep
-            println!("{}: {}", "Crash".green(), info.0.last().unwrap());
+            let _ = writeln!(summary, "{}: {}", "Crash".green(), info.0.last().unwrap());
             // casrep: SeverityType: Description: crashline (path:line:column) or /path/to/report.casrep
-            println!("  {}", info.0[0]);
+            let _ = writeln!(summary, "  {}", info.0[0]);
             if info.0.len() == 3 {
                 // gdb.casrep: SeverityType: Description: crashline (path:line:column) or /path/to/report.casrep
-                println!("  {}", info.0[1]);
+                let _ = writeln!(summary, "  {}", info.0[1]);
             }
             // Number of crashes with the same hash
-            println!("  Similar crashes: {}", info.1);
+            let _ = writeln!(summary, "  Similar crashes: {}", info.1);
         }
         let mut classes = String::new();
         cluster_classes.iter().for_each(|(class, number)| {
@@ -965,8 +2253,15 @@ fn print_summary(dir: &Path, unique_crash_line: bool) {
             );
         });
         if !ubsan {
-            println!("Cluster summary ->{classes}");
+            let _ = writeln!(summary, "Cluster summary ->{classes}");
         }
+        summaries.push((cluster_key, summary));
+    }
+    if let Some(sort_by) = sort_by {
+        summaries.sort_by(|a, b| a.0.cmp_by(&b.0, sort_by));
+    }
+    for (_, summary) in summaries {
+        print!("{summary}");
     }
     let mut classes = String::new();
     casr_classes
@@ -979,6 +2274,1107 @@ fn print_summary(dir: &Path, unique_crash_line: bool) {
     }
 }
 
+/// Difference between two CASR reports
+struct ReportDiff {
+    /// Execution class in old and new reports
+    class: (String, String),
+    /// Crash line in old and new reports
+    crashline: (String, String),
+    /// Stack frames diff: ' ' - common frame, '-' - removed, '+' - added
+    frames: Vec<(char, String)>,
+    /// Similarity of filtered stack traces
+    similarity: f64,
+    /// Reports are duplicates (filtered stack traces are equal)
+    duplicates: bool,
+    /// Reports are put in one cluster
+    same_cluster: bool,
+}
+
+impl ReportDiff {
+    /// Compare two CASR reports
+    ///
+    /// # Arguments
+    ///
+    /// * 'old' - path to old report
+    ///
+    /// * 'new' - path to new report
+    fn new(old: &Path, new: &Path) -> Result<Self> {
+        let old_report = report_from_file(old)?;
+        let new_report = report_from_file(new)?;
+        let old_trace = old_report
+            .normalized_stacktrace()
+            .with_context(|| format!("Couldn't get stack trace from {}", old.display()))?;
+        let new_trace = new_report
+            .normalized_stacktrace()
+            .with_context(|| format!("Couldn't get stack trace from {}", new.display()))?;
+        let similarity = similarity(&old_trace, &new_trace);
+        Ok(ReportDiff {
+            class: (
+                old_report.execution_class.short_description,
+                new_report.execution_class.short_description,
+            ),
+            crashline: (old_report.crashline, new_report.crashline),
+            frames: diff_frames(&old_trace, &new_trace),
+            similarity,
+            duplicates: old_trace == new_trace,
+            same_cluster: 1.0 - similarity < cluster_threshold(),
+        })
+    }
+
+    /// Print report diff
+    fn print(&self) {
+        let changed = |(old, new): &(String, String)| {
+            if old == new {
+                format!("{old} (unchanged)")
+            } else {
+                format!("{} -> {}", old.red(), new.green())
+            }
+        };
+        let yes_no = |value: bool| if value { "yes" } else { "no" };
+        println!("Execution class: {}", changed(&self.class));
+        println!("Crash line: {}", changed(&self.crashline));
+        println!("Similarity: {:.2}", self.similarity);
+        println!("Duplicates: {}", yes_no(self.duplicates));
+        println!("Same cluster: {}", yes_no(self.same_cluster));
+        println!("Stack trace:");
+        for (mark, frame) in &self.frames {
+            match mark {
+                '-' => println!("{}", format!("- {frame}").red()),
+                '+' => println!("{}", format!("+ {frame}").green()),
+                _ => println!("  {frame}"),
+            }
+        }
+    }
+}
+
+/// Compute stack frames diff by the longest common subsequence
+///
+/// # Arguments
+///
+/// * 'old' - old stack trace
+///
+/// * 'new' - new stack trace
+///
+/// # Return value
+///
+/// Frames with marks: ' ' - common frame, '-' - removed, '+' - added
+fn diff_frames(old: &Stacktrace, new: &Stacktrace) -> Vec<(char, String)> {
+    let frame = |entry: &StacktraceEntry| {
+        let location = if !entry.debug.file.is_empty() {
+            format!("{}:{}", entry.debug.file, entry.debug.line)
+        } else if !entry.module.is_empty() {
+            format!("({}+{:#x})", entry.module, entry.offset)
+        } else {
+            format!("{:#x}", entry.address)
+        };
+        format!("{} {}", entry.function, location)
+    };
+    // lcs[i][j] - length of common subsequence of old[i..] and new[j..]
+    let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+    let mut frames = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            frames.push((' ', frame(&old[i])));
+            i += 1;
+            j += 1;
+        } else if j == new.len() || (i < old.len() && lcs[i + 1][j] >= lcs[i][j + 1]) {
+            frames.push(('-', frame(&old[i])));
+            i += 1;
+        } else {
+            frames.push(('+', frame(&new[j])));
+            j += 1;
+        }
+    }
+    frames
+}
+
+/// Compare CASR reports or directories with reports (e.g., before and after
+/// fix). Reports in directories are matched by file name.
+///
+/// # Arguments
+///
+/// * 'old' - old report or directory with reports
+///
+/// * 'new' - new report or directory with reports
+fn diff(old: &Path, new: &Path) -> Result<()> {
+    init_ignored_frames!("cpp", "rust", "python", "go", "java", "js");
+
+    if old.is_file() && new.is_file() {
+        ReportDiff::new(old, new)?.print();
+        return Ok(());
+    }
+    if !old.is_dir() || !new.is_dir() {
+        bail!(
+            "Couldn't compare {} and {}: both paths should be reports or directories",
+            old.display(),
+            new.display()
+        );
+    }
+
+    // Reports by file name
+    let read_reports = |dir: &Path| -> BTreeMap<String, PathBuf> {
+        WalkDir::new(dir)
+            .sort_by_file_name()
+            .into_iter()
+            .flatten()
+            .map(|entry| entry.path().to_path_buf())
+            .filter(|path| path.is_file())
+            .filter(|path| path.extension().is_some() && path.extension().unwrap() == "casrep")
+            .map(|path| {
+                (
+                    path.file_name().unwrap().to_string_lossy().to_string(),
+                    path,
+                )
+            })
+            .collect()
+    };
+    let old_reports = read_reports(old);
+    let new_reports = read_reports(new);
+
+    let removed: Vec<&String> = old_reports
+        .keys()
+        .filter(|name| !new_reports.contains_key(*name))
+        .collect();
+    let added: Vec<&String> = new_reports
+        .keys()
+        .filter(|name| !old_reports.contains_key(*name))
+        .collect();
+    let mut duplicates = 0;
+    let mut changed = Vec::new();
+    for (name, old_path) in &old_reports {
+        let Some(new_path) = new_reports.get(name) else {
+            continue;
+        };
+        match ReportDiff::new(old_path, new_path) {
+            Ok(diff) if diff.duplicates && diff.class.0 == diff.class.1 => duplicates += 1,
+            Ok(diff) => changed.push((name, diff)),
+            Err(e) => warn!("{e}"),
+        }
+    }
+
+    println!("Removed reports: {}", removed.len());
+    for name in removed {
+        println!("{}", format!("- {name}").red());
+    }
+    println!("Added reports: {}", added.len());
+    for name in added {
+        println!("{}", format!("+ {name}").green());
+    }
+    println!("Unchanged reports: {duplicates}");
+    println!("Changed reports: {}", changed.len());
+    for (name, diff) in changed {
+        println!("==> <{}>", name.magenta());
+        diff.print();
+    }
+    Ok(())
+}
+
+/// Print markdown table of clusters (e.g., for pull request comment by CI)
+///
+/// # Arguments
+///
+/// * 'dir' - directory with reports or clusters
+///
+/// * 'known' - directory with previously found reports to mark clusters as new or known
+///
+/// * 'range' - date range of reports to print
+///
+/// * 'sort_by' - sort clusters by severity, date, or cluster size
+fn print_markdown_summary(
+    dir: &Path,
+    known: Option<&Path>,
+    range: &DateRange,
+    sort_by: Option<&str>,
+) -> Result<()> {
+    init_ignored_frames!("cpp", "rust", "python", "go", "java", "js");
+
+    // Read casreps from directory
+    let read_reports = |dir: &Path, max_depth: usize| -> Vec<CrashReport> {
+        WalkDir::new(dir)
+            .max_depth(max_depth)
+            .sort_by_file_name()
+            .into_iter()
+            .flatten()
+            .map(|entry| entry.path().to_path_buf())
+            .filter(|path| path.is_file())
+            .filter(|path| path.extension().is_some() && path.extension().unwrap() == "casrep")
+            .filter_map(|path| report_from_file(&path).ok())
+            .collect()
+    };
+
+    let mut clusters: Vec<(String, Vec<CrashReport>)> = Vec::new();
+    let mut cluster_dirs: Vec<(u32, PathBuf)> = fs::read_dir(dir)
+        .with_context(|| format!("Couldn't read directory: {}", dir.display()))?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir())
+        .filter_map(|path| {
+            let name = path.file_name().unwrap().to_str().unwrap();
+            let number = name.strip_prefix("cl")?.parse::<u32>().ok()?;
+            Some((number, path))
+        })
+        .collect();
+    cluster_dirs.sort();
+    for (_, path) in cluster_dirs {
+        let mut reports = read_reports(&path, 1);
+        reports.retain(|report| range.contains(&report.date));
+        if !reports.is_empty() {
+            let name = path.file_name().unwrap().to_str().unwrap().to_string();
+            clusters.push((name, reports));
+        }
+    }
+    if clusters.is_empty() {
+        // Directory without clusters, e.g. after deduplication.
+        for (i, report) in read_reports(dir, 1)
+            .into_iter()
+            .filter(|report| range.contains(&report.date))
+            .enumerate()
+        {
+            clusters.push((format!("{}", i + 1), vec![report]));
+        }
+    }
+    if let Some(sort_by) = sort_by {
+        let cluster_key = |reports: &[CrashReport]| {
+            let mut key = reports.iter().fold(SortKey::default(), |key, report| {
+                key.merge(&SortKey::new(report))
+            });
+            key.size = reports.len();
+            key
+        };
+        clusters.sort_by(|a, b| cluster_key(&a.1).cmp_by(&cluster_key(&b.1), sort_by));
+    }
+
+    // Stack traces of known reports
+    let known_traces: Option<Vec<Stacktrace>> = known.map(|known| {
+        read_reports(known, usize::MAX)
+            .iter()
+            .filter_map(|report| report.normalized_stacktrace().ok())
+            .collect()
+    });
+
+    let rank = |report: &CrashReport| match report.execution_class.severity.as_str() {
+        "EXPLOITABLE" => 0,
+        "PROBABLY_EXPLOITABLE" => 1,
+        "NOT_EXPLOITABLE" => 2,
+        _ => 3,
+    };
+    let escape = |text: &str| text.replace('|', "\\|");
+
+    println!("## CASR summary\n");
+    if known_traces.is_some() {
+        println!("| Cluster | Severity | Class | Crash line | Reports | Status |");
+        println!("|---|---|---|---|---:|---|");
+    } else {
+        println!("| Cluster | Severity | Class | Crash line | Reports |");
+        println!("|---|---|---|---|---:|");
+    }
+    let (mut total, mut new) = (0, 0);
+    for (name, reports) in clusters.iter() {
+        total += reports.len();
+        // The most severe report represents cluster
+        let report = reports.iter().min_by_key(|report| rank(report)).unwrap();
+        let crashline = if report.crashline.is_empty() {
+            "-".to_string()
+        } else {
+            format!("`{}`", escape(&report.crashline))
+        };
+        let row = format!(
+            "| {} | {} | {} | {} | {} |",
+            name,
+            report.execution_class.severity,
+            escape(&report.execution_class.short_description),
+            crashline,
+            reports.len()
+        );
+        let Some(known_traces) = &known_traces else {
+            println!("{row}");
+            continue;
+        };
+        // Cluster is new if it has report that isn't a duplicate of known one
+        let traces: Vec<Stacktrace> = reports
+            .iter()
+            .filter_map(|report| report.normalized_stacktrace().ok())
+            .collect();
+        let mut all_traces = known_traces.clone();
+        all_traces.extend(traces.iter().cloned());
+        let is_new = dedup_stacktraces(&all_traces)[known_traces.len()..]
+            .iter()
+            .any(|unique| *unique);
+        if is_new {
+            new += 1;
+        }
+        println!("{row} {} |", if is_new { "**new**" } else { "known" });
+    }
+    print!(
+        "\n**Total:** {} clusters, {} reports",
+        clusters.len(),
+        total
+    );
+    if known_traces.is_some() {
+        print!(", {new} new clusters");
+    }
+    println!();
+
+    Ok(())
+}
+
+/// Print statistics of severity types and crash classes over time periods
+///
+/// # Arguments
+///
+/// * 'dir' - directory with reports
+///
+/// * 'period' - time period: day, week, or month
+///
+/// * 'format' - output format: text, json, or csv
+///
+/// * 'range' - date range of reports
+fn print_stats(dir: &Path, period: &str, format: &str, range: &DateRange) -> Result<()> {
+    let date_format = match period {
+        "day" => "%Y-%m-%d",
+        "week" => "%G-W%V",
+        _ => "%Y-%m",
+    };
+    // Period -> (severity type -> count, crash class -> count)
+    type Counters = (BTreeMap<String, usize>, BTreeMap<String, usize>);
+    let mut periods: BTreeMap<String, Counters> = BTreeMap::new();
+    let mut skipped = 0;
+    for path in WalkDir::new(dir)
+        .into_iter()
+        .flatten()
+        .map(|entry| entry.path().to_path_buf())
+        .filter(|path| path.extension().is_some() && path.extension().unwrap() == "casrep")
+    {
+        let Ok(report) = report_from_file(&path) else {
+            skipped += 1;
+            continue;
+        };
+        let Ok(date) = chrono::DateTime::parse_from_rfc3339(&report.date) else {
+            skipped += 1;
+            continue;
+        };
+        if !range.contains(&report.date) {
+            continue;
+        }
+        let (severities, classes) = periods
+            .entry(date.format(date_format).to_string())
+            .or_default();
+        *severities
+            .entry(report.execution_class.severity.clone())
+            .or_default() += 1;
+        *classes
+            .entry(report.execution_class.short_description.clone())
+            .or_default() += 1;
+    }
+    if skipped != 0 {
+        warn!("Skipped {skipped} reports without valid date");
+    }
+
+    match format {
+        "json" => {
+            let stats: Vec<Value> = periods
+                .iter()
+                .map(|(period, (severities, classes))| {
+                    serde_json::json!({
+                        "Period": period,
+                        "Total": severities.values().sum::<usize>(),
+                        "Severity": severities,
+                        "Classes": classes,
+                    })
+                })
+                .collect();
+            println!("{}", serde_json::to_string_pretty(&stats)?);
+        }
+        "csv" => {
+            println!("period,severity,class,count");
+            for (period, (severities, classes)) in periods.iter() {
+                for (severity, count) in severities {
+                    println!("{period},{severity},,{count}");
+                }
+                for (class, count) in classes {
+                    println!("{period},,{class},{count}");
+                }
+            }
+        }
+        _ => {
+            if periods.is_empty() {
+                println!("{}", "No reports found".red());
+                return Ok(());
+            }
+            // Bar chart: one symbol per severity type.
+            let width = 50;
+            let max = periods
+                .values()
+                .map(|(severities, _)| severities.values().sum::<usize>())
+                .max()
+                .unwrap();
+            println!(
+                "{} EXPLOITABLE  {} PROBABLY_EXPLOITABLE  {} NOT_EXPLOITABLE  {} other",
+                "#".red(),
+                "#".yellow(),
+                "#".white(),
+                ".".white()
+            );
+            for (period, (severities, _)) in periods.iter() {
+                let total: usize = severities.values().sum();
+                let count = |severity: &str| severities.get(severity).copied().unwrap_or(0);
+                let bar = |count: usize| (count * width).div_ceil(max);
+                let exploitable = count("EXPLOITABLE");
+                let probably = count("PROBABLY_EXPLOITABLE");
+                let not_exploitable = count("NOT_EXPLOITABLE");
+                let other = total - exploitable - probably - not_exploitable;
+                println!(
+                    "{:<10} {}{}{}{} {} ({:.0}% exploitable)",
+                    period,
+                    "#".repeat(bar(exploitable)).red(),
+                    "#".repeat(bar(probably)).yellow(),
+                    "#".repeat(bar(not_exploitable)).white(),
+                    ".".repeat(bar(other)),
+                    total,
+                    (exploitable + probably) as f64 * 100.0 / total as f64
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Print number of reports per execution class, severity type, crash function
+/// (top frame after filtering), and crash source file
+///
+/// # Arguments
+///
+/// * 'dir' - directory with reports
+///
+/// * 'format' - output format: text, json, or csv
+///
+/// * 'range' - date range of reports
+fn print_aggregate_stats(dir: &Path, format: &str, range: &DateRange) -> Result<()> {
+    let mut classes: BTreeMap<String, usize> = BTreeMap::new();
+    let mut severities: BTreeMap<String, usize> = BTreeMap::new();
+    let mut functions: BTreeMap<String, usize> = BTreeMap::new();
+    let mut files: BTreeMap<String, usize> = BTreeMap::new();
+    let mut total = 0;
+    let mut skipped = 0;
+    for path in WalkDir::new(dir)
+        .into_iter()
+        .flatten()
+        .map(|entry| entry.path().to_path_buf())
+        .filter(|path| path.extension().is_some() && path.extension().unwrap() == "casrep")
+    {
+        let Ok(report) = report_from_file(&path) else {
+            skipped += 1;
+            continue;
+        };
+        if !range.contains(&report.date) {
+            continue;
+        }
+        total += 1;
+        *classes
+            .entry(report.execution_class.short_description.clone())
+            .or_default() += 1;
+        *severities
+            .entry(report.execution_class.severity.clone())
+            .or_default() += 1;
+        let trace = report.normalized_stacktrace().unwrap_or_default();
+        let function = match trace.first() {
+            Some(entry) if !entry.function.is_empty() => entry.function.clone(),
+            Some(entry) if !entry.module.is_empty() => {
+                format!("{}+{:#x}", entry.module, entry.offset)
+            }
+            _ => "??".to_string(),
+        };
+        *functions.entry(function).or_default() += 1;
+        let file = match trace.crash_line() {
+            Ok(CrashLine::Source(debug)) => debug.file,
+            _ => "??".to_string(),
+        };
+        *files.entry(file).or_default() += 1;
+    }
+    if skipped != 0 {
+        warn!("Skipped {skipped} invalid reports");
+    }
+
+    let groups = [
+        ("Execution classes", "class", &classes),
+        ("Severity types", "severity", &severities),
+        ("Crash functions", "function", &functions),
+        ("Crash source files", "file", &files),
+    ];
+    match format {
+        "json" => {
+            let stats = serde_json::json!({
+                "Total": total,
+                "Classes": classes,
+                "Severity": severities,
+                "Functions": functions,
+                "Files": files,
+            });
+            println!("{}", serde_json::to_string_pretty(&stats)?);
+        }
+        "csv" => {
+            println!("category,name,count");
+            for (_, category, counts) in groups {
+                for (name, count) in counts {
+                    let name = if name.contains([',', '"', '\n']) {
+                        format!("\"{}\"", name.replace('"', "\"\""))
+                    } else {
+                        name.clone()
+                    };
+                    println!("{category},{name},{count}");
+                }
+            }
+        }
+        _ => {
+            if total == 0 {
+                println!("{}", "No reports found".red());
+                return Ok(());
+            }
+            println!("Total reports: {total}");
+            for (title, _, counts) in groups {
+                println!("\n{}:", title.bold());
+                // The most frequent first.
+                let mut counts: Vec<(&String, &usize)> = counts.iter().collect();
+                counts.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+                for (name, count) in counts {
+                    println!("{count:>8}  {name}");
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Style of generated HTML pages
+const HTML_STYLE: &str = "body { font-family: sans-serif; margin: 2em; }
+table { border-collapse: collapse; }
+td, th { border: 1px solid #ccc; padding: 4px 8px; text-align: left; vertical-align: top; }
+pre { background: #f6f8fa; padding: 8px; overflow-x: auto; }
+.badge { color: #fff; border-radius: 4px; padding: 2px 8px; font-weight: bold; }
+.EXPLOITABLE { background: #d73a49; }
+.PROBABLY_EXPLOITABLE { background: #e36209; }
+.NOT_EXPLOITABLE { background: #28a745; }
+.UNKNOWN { background: #6a737d; }
+.crashline { background: #fff5b1; font-weight: bold; }";
+
+/// Escape special HTML characters
+///
+/// # Arguments
+///
+/// * 'text' - text to escape
+fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Percent-encode relative URL path: all bytes except unreserved characters
+/// are encoded, so report names with spaces, '#', '?', or '%' are linked
+/// correctly
+///
+/// # Arguments
+///
+/// * 'path' - relative path to encode
+fn url_encode(path: &str) -> String {
+    path.bytes()
+        .map(|b| {
+            if b.is_ascii_alphanumeric() || b"-._~".contains(&b) {
+                (b as char).to_string()
+            } else {
+                format!("%{b:02X}")
+            }
+        })
+        .collect()
+}
+
+/// Get HTML severity badge for report
+///
+/// # Arguments
+///
+/// * 'report' - CASR report
+fn html_badge(report: &CrashReport) -> String {
+    let severity = match report.execution_class.severity.as_str() {
+        "EXPLOITABLE" | "PROBABLY_EXPLOITABLE" | "NOT_EXPLOITABLE" => {
+            report.execution_class.severity.as_str()
+        }
+        _ => "UNKNOWN",
+    };
+    format!(
+        "<span class=\"badge {severity}\">{}: {}</span>",
+        html_escape(&report.execution_class.severity),
+        html_escape(&report.execution_class.short_description)
+    )
+}
+
+/// Render CASR report into standalone HTML page
+///
+/// # Arguments
+///
+/// * 'report' - CASR report
+///
+/// * 'name' - report name
+///
+/// * 'cluster' - cluster name (may be empty)
+fn html_report_page(report: &CrashReport, name: &str, cluster: &str) -> String {
+    let mut page = String::new();
+    let _ = writeln!(
+        page,
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>\n{HTML_STYLE}\n</style>\n</head>\n<body>",
+        html_escape(name)
+    );
+    let _ = writeln!(page, "<p><a href=\"index.html\">&larr; All reports</a></p>");
+    let _ = writeln!(
+        page,
+        "<h1>Crash report for {}</h1>\n<p>{}</p>",
+        html_escape(&report.executable_path),
+        html_badge(report)
+    );
+    if !report.execution_class.description.is_empty() {
+        let _ = writeln!(
+            page,
+            "<p><b>{}</b></p>",
+            html_escape(&report.execution_class.description)
+        );
+    }
+    if !report.execution_class.explanation.is_empty() {
+        let _ = writeln!(
+            page,
+            "<p>{}</p>",
+            html_escape(&report.execution_class.explanation)
+        );
+    }
+
+    let _ = writeln!(page, "<table>");
+    for (key, value) in [
+        ("Report", name),
+        ("Cluster", cluster),
+        ("Date", report.date.as_str()),
+        ("Command line", report.proc_cmdline.as_str()),
+        ("Stdin", report.stdin.as_str()),
+        ("Input", report.input_path.as_str()),
+        ("Input SHA-256", report.input_sha256.as_str()),
+        ("OS", report.os.as_str()),
+        ("Architecture", report.architecture.as_str()),
+    ] {
+        if !value.is_empty() {
+            let _ = writeln!(
+                page,
+                "<tr><th>{key}</th><td>{}</td></tr>",
+                html_escape(value)
+            );
+        }
+    }
+    if !report.crashline.is_empty() {
+        let _ = writeln!(
+            page,
+            "<tr><th>Crash line</th><td><code class=\"crashline\">{}</code></td></tr>",
+            html_escape(&report.crashline)
+        );
+    }
+    let _ = writeln!(page, "</table>");
+
+    // Source code around crash line, which is marked with "--->"
+    if !report.source.is_empty() {
+        let _ = writeln!(page, "<h2>Source</h2>\n<pre>");
+        for line in report.source.iter() {
+            if line.trim_start().starts_with("--->") {
+                let _ = writeln!(
+                    page,
+                    "<span class=\"crashline\">{}</span>",
+                    html_escape(line)
+                );
+            } else {
+                let _ = writeln!(page, "{}", html_escape(line));
+            }
+        }
+        let _ = writeln!(page, "</pre>");
+    }
+
+    // Stack trace: the first frames are shown, others are collapsed
+    if !report.stacktrace.is_empty() {
+        const SHOWN_FRAMES: usize = 10;
+        let _ = writeln!(page, "<h2>Stack trace</h2>\n<pre>");
+        for line in report.stacktrace.iter().take(SHOWN_FRAMES) {
+            let _ = writeln!(page, "{}", html_escape(line));
+        }
+        let _ = writeln!(page, "</pre>");
+        if report.stacktrace.len() > SHOWN_FRAMES {
+            let _ = writeln!(
+                page,
+                "<details><summary>{} more frames</summary>\n<pre>",
+                report.stacktrace.len() - SHOWN_FRAMES
+            );
+            for line in report.stacktrace.iter().skip(SHOWN_FRAMES) {
+                let _ = writeln!(page, "{}", html_escape(line));
+            }
+            let _ = writeln!(page, "</pre></details>");
+        }
+    }
+
+    // Other threads are collapsed
+    for thread in report.threads.iter().filter(|thread| !thread.crashed) {
+        let _ = writeln!(
+            page,
+            "<details><summary>{}</summary>\n<pre>",
+            html_escape(&thread.description)
+        );
+        for line in thread.stacktrace.iter() {
+            let _ = writeln!(page, "{}", html_escape(line));
+        }
+        let _ = writeln!(page, "</pre></details>");
+    }
+
+    // Source code around the top frames is collapsed
+    if !report.source_contexts.is_empty() {
+        let _ = writeln!(page, "<h2>Source contexts</h2>");
+        for context in report.source_contexts.iter() {
+            let _ = writeln!(
+                page,
+                "<details><summary>{} at {}:{}</summary>\n<pre>",
+                html_escape(&context.function),
+                html_escape(&context.file),
+                context.line
+            );
+            for line in context.source.iter() {
+                let _ = writeln!(page, "{}", html_escape(line));
+            }
+            let _ = writeln!(page, "</pre></details>");
+        }
+    }
+
+    // Registers and disassembly
+    let state = crash_state(report);
+    if !state.is_empty() {
+        let _ = writeln!(
+            page,
+            "<h2>Crash state</h2>\n<pre>{}</pre>",
+            html_escape(&state)
+        );
+    }
+
+    // Sanitizer and language specific output
+    for (title, lines) in [
+        ("AddressSanitizer report", &report.asan_report),
+        ("Other sanitizer errors", &report.other_errors),
+        ("Allocation stack trace", &report.alloc_stacktrace),
+        ("Deallocation stack trace", &report.free_stacktrace),
+        ("MemorySanitizer report", &report.msan_report),
+        ("GWP-ASan report", &report.gwp_asan_report),
+        ("HWAddressSanitizer report", &report.hwasan_report),
+        ("Valgrind report", &report.valgrind_report),
+        ("Kernel report", &report.kernel_report),
+        ("Reproducer", &report.reproducer),
+        ("UndefinedBehaviorSanitizer report", &report.ubsan_report),
+        (
+            "UndefinedBehaviorSanitizer warnings",
+            &report.ubsan_warnings,
+        ),
+        ("ThreadSanitizer report", &report.tsan_report),
+        ("Python report", &report.python_report),
+        ("Go report", &report.go_report),
+        ("Java report", &report.java_report),
+        ("Rust report", &report.rust_report),
+        ("JavaScript report", &report.js_report),
+    ] {
+        if lines.is_empty() {
+            continue;
+        }
+        let _ = writeln!(page, "<h2>{title}</h2>\n<pre>");
+        for line in lines.iter() {
+            let _ = writeln!(page, "{}", html_escape(line));
+        }
+        let _ = writeln!(page, "</pre>");
+    }
+
+    let _ = writeln!(
+        page,
+        "<details><summary>Full report</summary>\n<pre>\n{}</pre></details>",
+        html_escape(&report.to_string())
+    );
+    let _ = writeln!(page, "</body>\n</html>");
+    page
+}
+
+/// Generate standalone HTML page for each CASR report and index page that
+/// groups reports by cluster and severity
+///
+/// # Arguments
+///
+/// * 'target' - CASR report or directory with reports or clusters
+///
+/// * 'output' - directory to save HTML pages
+///
+/// * 'range' - date range of reports
+///
+/// * 'sort_by' - sort clusters by severity, date, or cluster size
+fn report_html(
+    target: &Path,
+    output: &Path,
+    range: &DateRange,
+    sort_by: Option<&str>,
+) -> Result<()> {
+    // Read casreps from directory
+    let read_reports = |dir: &Path| -> Vec<(PathBuf, CrashReport)> {
+        WalkDir::new(dir)
+            .max_depth(1)
+            .sort_by_file_name()
+            .into_iter()
+            .flatten()
+            .map(|entry| entry.path().to_path_buf())
+            .filter(|path| path.is_file())
+            .filter(|path| path.extension().is_some() && path.extension().unwrap() == "casrep")
+            .filter_map(|path| Some((path.clone(), report_from_file(&path).ok()?)))
+            .filter(|(_, report)| range.contains(&report.date))
+            .collect()
+    };
+
+    let mut clusters: Vec<(String, Vec<(PathBuf, CrashReport)>)> = Vec::new();
+    if target.is_file() {
+        clusters.push((
+            String::new(),
+            vec![(target.to_path_buf(), report_from_file(target)?)],
+        ));
+    } else {
+        let mut cluster_dirs: Vec<(u32, PathBuf)> = fs::read_dir(target)
+            .with_context(|| format!("Couldn't read directory: {}", target.display()))?
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.is_dir())
+            .filter_map(|path| {
+                let name = path.file_name().unwrap().to_str().unwrap();
+                let number = name.strip_prefix("cl")?.parse::<u32>().ok()?;
+                Some((number, path))
+            })
+            .collect();
+        cluster_dirs.sort();
+        for (_, path) in cluster_dirs {
+            let reports = read_reports(&path);
+            if !reports.is_empty() {
+                let name = path.file_name().unwrap().to_str().unwrap().to_string();
+                clusters.push((name, reports));
+            }
+        }
+        if clusters.is_empty() {
+            // Directory without clusters, e.g. after deduplication.
+            for report in read_reports(target) {
+                clusters.push((String::new(), vec![report]));
+            }
+        }
+    }
+    if clusters.is_empty() {
+        bail!("No CASR reports found in {}", target.display());
+    }
+    if let Some(sort_by) = sort_by {
+        let cluster_key = |reports: &[(PathBuf, CrashReport)]| {
+            let mut key = reports.iter().fold(SortKey::default(), |key, (_, report)| {
+                key.merge(&SortKey::new(report))
+            });
+            key.size = reports.len();
+            key
+        };
+        clusters.sort_by(|a, b| cluster_key(&a.1).cmp_by(&cluster_key(&b.1), sort_by));
+    }
+
+    fs::create_dir_all(output)
+        .with_context(|| format!("Couldn't create directory: {}", output.display()))?;
+
+    // Clusters are grouped by the highest severity of their reports
+    let mut sections: BTreeMap<u8, (String, String)> = BTreeMap::new();
+    let mut total = 0;
+    for (cluster, reports) in clusters.iter() {
+        let mut rows = String::new();
+        for (path, report) in reports.iter() {
+            let name = path.file_stem().unwrap().to_string_lossy().to_string();
+            let page = if cluster.is_empty() {
+                format!("{name}.html")
+            } else {
+                format!("{cluster}-{name}.html")
+            };
+            fs::write(output.join(&page), html_report_page(report, &name, cluster))
+                .with_context(|| format!("Couldn't write HTML page: {page}"))?;
+            let _ = writeln!(
+                rows,
+                "<tr><td>{}</td><td><a href=\"{}\">{}</a></td><td>{}</td><td><code>{}</code></td><td>{}</td></tr>",
+                html_escape(cluster),
+                url_encode(&page),
+                html_escape(&name),
+                html_badge(report),
+                html_escape(&report.crashline),
+                html_escape(&report.date)
+            );
+        }
+        total += reports.len();
+        let rank = reports
+            .iter()
+            .map(|(_, report)| SortKey::new(report).rank)
+            .min()
+            .unwrap();
+        let severity = reports
+            .iter()
+            .find(|(_, report)| SortKey::new(report).rank == rank)
+            .map(|(_, report)| report.execution_class.severity.clone())
+            .filter(|_| rank < 3)
+            .unwrap_or_else(|| "UNKNOWN".to_string());
+        sections
+            .entry(rank)
+            .or_insert_with(|| (severity, String::new()))
+            .1
+            .push_str(&rows);
+    }
+
+    let mut index = String::new();
+    let _ = writeln!(
+        index,
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>CASR reports</title>\n<style>\n{HTML_STYLE}\n</style>\n</head>\n<body>"
+    );
+    let _ = writeln!(
+        index,
+        "<h1>CASR reports</h1>\n<p><b>Total:</b> {} clusters, {} reports</p>",
+        clusters.iter().filter(|(name, _)| !name.is_empty()).count(),
+        total
+    );
+    for (severity, rows) in sections.values() {
+        let _ = writeln!(
+            index,
+            "<h2>{}</h2>\n<table>\n<tr><th>Cluster</th><th>Report</th><th>Class</th><th>Crash line</th><th>Date</th></tr>\n{rows}</table>",
+            html_escape(severity)
+        );
+    }
+    let _ = writeln!(index, "</body>\n</html>");
+    fs::write(output.join("index.html"), index)
+        .with_context(|| format!("Couldn't write index page to {}", output.display()))?;
+
+    Ok(())
+}
+
+/// Report date range from `--since` and `--until` options (Unix timestamps)
+#[derive(Default)]
+struct DateRange {
+    since: Option<i64>,
+    until: Option<i64>,
+}
+
+impl DateRange {
+    /// Create date range from dates in YYYY-MM-DD or RFC 3339 format. Both
+    /// boundaries are included.
+    fn new(since: Option<&str>, until: Option<&str>) -> Result<Self> {
+        let parse = |date: &str, end_of_day: bool| -> Result<i64> {
+            if let Ok(date) = chrono::DateTime::parse_from_rfc3339(date) {
+                return Ok(date.timestamp());
+            }
+            let Ok(day) = chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d") else {
+                bail!("Couldn't parse date {date}, expected YYYY-MM-DD or RFC 3339 format");
+            };
+            let day = if end_of_day {
+                day.succ_opt().unwrap()
+            } else {
+                day
+            };
+            let Some(date) = day
+                .and_hms_opt(0, 0, 0)
+                .and_then(|day| day.and_local_timezone(chrono::Local).earliest())
+            else {
+                bail!("Couldn't convert date {date} to local time");
+            };
+            Ok(if end_of_day {
+                date.timestamp() - 1
+            } else {
+                date.timestamp()
+            })
+        };
+        Ok(DateRange {
+            since: since.map(|date| parse(date, false)).transpose()?,
+            until: until.map(|date| parse(date, true)).transpose()?,
+        })
+    }
+
+    /// Check that report date is in range. Reports without valid date are
+    /// filtered out when any boundary is set.
+    fn contains(&self, date: &str) -> bool {
+        if self.since.is_none() && self.until.is_none() {
+            return true;
+        }
+        let Ok(date) = chrono::DateTime::parse_from_rfc3339(date) else {
+            return false;
+        };
+        let date = date.timestamp();
+        self.since.map_or(true, |since| date >= since)
+            && self.until.map_or(true, |until| date <= until)
+    }
+}
+
+/// Key to sort crashes and clusters in directory summary
+#[derive(Clone)]
+struct SortKey {
+    /// The highest severity rank (0 is EXPLOITABLE)
+    rank: u8,
+    /// The newest report date (Unix timestamp)
+    date: i64,
+    /// Number of crashes
+    size: usize,
+}
+
+impl Default for SortKey {
+    fn default() -> Self {
+        SortKey {
+            rank: u8::MAX,
+            date: i64::MIN,
+            size: 0,
+        }
+    }
+}
+
+impl SortKey {
+    /// Create sort key from report
+    fn new(report: &CrashReport) -> Self {
+        SortKey {
+            rank: match report.execution_class.severity.as_str() {
+                "EXPLOITABLE" => 0,
+                "PROBABLY_EXPLOITABLE" => 1,
+                "NOT_EXPLOITABLE" => 2,
+                _ => 3,
+            },
+            date: chrono::DateTime::parse_from_rfc3339(&report.date)
+                .map(|date| date.timestamp())
+                .unwrap_or(i64::MIN),
+            size: 1,
+        }
+    }
+
+    /// Combine keys: the highest severity and the newest date. Size is kept.
+    fn merge(&self, other: &SortKey) -> SortKey {
+        SortKey {
+            rank: self.rank.min(other.rank),
+            date: self.date.max(other.date),
+            size: self.size,
+        }
+    }
+
+    /// Compare keys so that the most severe, the newest, or the largest goes first.
+    /// Ties are broken by other keys in the same order.
+    fn cmp_by(&self, other: &SortKey, sort_by: &str) -> std::cmp::Ordering {
+        let severity = self.rank.cmp(&other.rank);
+        let date = other.date.cmp(&self.date);
+        let size = other.size.cmp(&self.size);
+        match sort_by {
+            "severity" => severity.then(date).then(size),
+            "date" => date.then(severity).then(size),
+            _ => size.then(severity).then(date),
+        }
+    }
+}
+
 /// Function processes report and returns summary
 ///
 /// # Arguments
@@ -1095,7 +3491,7 @@ fn sarif(report_path: &Path, root: &str, tool: &str) -> Result<SarifReport> {
     for (path, report) in reports {
         let result = sarif.add_casr_report(&report, root);
         if let Err(e) = result {
-            eprintln!("Error while converting {} to SARIF: {}", path.display(), e);
+            error!("Error while converting {} to SARIF: {}", path.display(), e);
         }
     }
 
@@ -1,16 +1,73 @@
+use casr::config;
+use casr::known_bugs::{known_bugs_arg, KnownBugs};
 use casr::util;
-use libcasr::{init_ignored_frames, stacktrace::*};
+use libcasr::{init_ignored_frames, report::CrashReport, stacktrace::*};
 
 use anyhow::{bail, Context, Result};
-use clap::{builder::FalseyValueParser, Arg, ArgAction};
+use chrono::{DateTime, FixedOffset, Local};
+use clap::{builder::FalseyValueParser, parser::ValueSource, Arg, ArgAction};
+use log::{info, warn};
 use rayon::iter::{IndexedParallelIterator, ParallelIterator};
 use rayon::iter::{IntoParallelIterator, IntoParallelRefIterator};
 
-use std::collections::HashSet;
+use std::collections::{BTreeMap, BTreeSet, HashSet, VecDeque};
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::RwLock;
 
+/// Cluster summary file name
+const CLUSTER_SUMMARY: &str = "cluster_summary.json";
+
+/// Compare stack traces of all threads instead of crashed thread only.
+static ALL_THREADS: AtomicBool = AtomicBool::new(false);
+
+/// Print dendrogram cut statistics while clustering.
+static DENDROGRAM_STATS: AtomicBool = AtomicBool::new(false);
+
+/// Directory for DBSCAN noise reports in clusters directory
+const NOISE_DIR: &str = "noise";
+
+/// Directory for reports of known bugs (`--known-bugs`)
+const KNOWN_DIR: &str = "known";
+
+/// Known bugs that are excluded from deduplication and clustering.
+static KNOWN_BUGS: RwLock<Option<KnownBugs>> = RwLock::new(None);
+
+/// Number of reports of known bugs.
+static KNOWN_REPORTS: AtomicUsize = AtomicUsize::new(0);
+
+/// Method of report clustering
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ClusterMethod {
+    /// Hierarchical clustering by stack trace similarity
+    Hierarchical,
+    /// Density-based clustering by stack trace similarity, reports without
+    /// enough similar neighbours are noise
+    Dbscan {
+        /// Minimum number of reports in dense neighbourhood
+        min_size: usize,
+    },
+    /// Bucketing by crash line
+    Crashline,
+}
+
+/// Get filtered stack trace that is used for deduplication and clustering:
+/// crashed thread stack trace or stack traces of all threads (`--all-threads`).
+/// Addresses are normalized to module offsets.
+///
+/// # Arguments
+///
+/// * `report` - casr report
+fn report_stacktrace(report: &CrashReport) -> libcasr::error::Result<Stacktrace> {
+    if !ALL_THREADS.load(Ordering::Relaxed) {
+        return report.normalized_stacktrace();
+    }
+    let mut stacktrace = report.filtered_threads_stacktrace()?;
+    normalize_addresses(&mut stacktrace);
+    Ok(stacktrace)
+}
+
 /// Extract stack trace from casr report
 ///
 /// # Arguments
@@ -21,12 +78,394 @@ use std::sync::RwLock;
 ///
 /// Stack trace as a `Stacktrace` struct
 fn stacktrace(path: &Path) -> Result<Stacktrace> {
-    match util::report_from_file(path)?.filtered_stacktrace() {
+    match report_stacktrace(&util::report_from_file(path)?) {
         Ok(trace) => Ok(trace),
         Err(e) => bail!("{}. File {}", e, path.display()),
     }
 }
 
+/// Get stack hash of casr report: `StackHash` field or hash computed from
+/// stack trace for reports without it (e.g., created by older CASR versions)
+/// and with `--all-threads`
+///
+/// # Arguments
+///
+/// * `path` - path to the casrep
+fn report_stack_hash(path: &Path) -> Result<String> {
+    let report = util::report_from_file(path)?;
+    if !report.stack_hash.is_empty() && !ALL_THREADS.load(Ordering::Relaxed) {
+        return Ok(report.stack_hash);
+    }
+    match report_stacktrace(&report) {
+        Ok(trace) => Ok(stack_hash(&trace)),
+        Err(e) => bail!("{}. File {}", e, path.display()),
+    }
+}
+
+/// Print stack trace frames of casr report. Frames that are filtered out
+/// before deduplication and clustering are marked with "-".
+///
+/// # Arguments
+///
+/// * `path` - path to the casrep
+fn show_filtered(path: &Path) -> Result<()> {
+    let trace = match util::report_from_file(path)?.parsed_stacktrace() {
+        Ok(trace) => trace,
+        Err(e) => bail!("{}. File {}", e, path.display()),
+    };
+    let removed = filtered_out_frames(&trace);
+    println!("{}:", path.display());
+    for (i, entry) in trace.iter().enumerate() {
+        let mark = if removed.contains(&i) { '-' } else { '+' };
+        let location = if !entry.debug.file.is_empty() {
+            format!("{}:{}", entry.debug.file, entry.debug.line)
+        } else if !entry.module.is_empty() {
+            format!("({}+{:#x})", entry.module, entry.offset)
+        } else {
+            String::new()
+        };
+        println!(
+            "{mark} #{i} {:#x} in {} {}",
+            entry.address, entry.function, location
+        );
+    }
+    Ok(())
+}
+
+/// Crash inputs that are placed next to casreps
+struct CrashInputs<'a> {
+    /// Directory with crash inputs
+    dir: &'a Path,
+    /// Move inputs instead of creating hard links
+    move_files: bool,
+}
+
+impl CrashInputs<'_> {
+    /// Place crash input next to casrep. Input is matched by casrep name
+    /// without `.casrep` (`.gdb.casrep`) extension.
+    ///
+    /// # Arguments
+    ///
+    /// * `casrep` - path to casrep
+    ///
+    /// * `outdir` - directory where casrep is placed
+    fn place(&self, casrep: &Path, outdir: &Path) -> Result<()> {
+        let mut name = casrep.with_extension("");
+        if name.extension().is_some() && name.extension().unwrap() == "gdb" {
+            name = name.with_extension("");
+        }
+        let name = name.file_name().unwrap();
+        let input = self.dir.join(name);
+        let target = outdir.join(name);
+        if !input.is_file() || target.exists() {
+            return Ok(());
+        }
+        if self.move_files {
+            if fs::rename(&input, &target).is_err() {
+                // Different file systems.
+                fs::copy(&input, &target)?;
+                fs::remove_file(&input)?;
+            }
+        } else if fs::hard_link(&input, &target).is_err() {
+            fs::copy(&input, &target)?;
+        }
+        Ok(())
+    }
+}
+
+/// Exclude reports of known bugs (`--known-bugs`) and put them into `known`
+/// directory
+///
+/// # Arguments
+///
+/// * `casreps` - paths to casreps, known ones are removed
+///
+/// * `outdir` - directory where `known` directory is created
+///
+/// * `move_files` - move casreps instead of copying
+///
+/// * `inputs` - crash inputs to place next to known casreps
+fn separate_known(
+    casreps: &mut Vec<PathBuf>,
+    outdir: &Path,
+    move_files: bool,
+    inputs: Option<&CrashInputs>,
+) -> Result<()> {
+    let known_bugs = KNOWN_BUGS.read().unwrap();
+    let Some(known_bugs) = known_bugs.as_ref() else {
+        return Ok(());
+    };
+    let known: HashSet<PathBuf> = casreps
+        .par_iter()
+        .filter(|casrep| {
+            let Ok(report) = util::report_from_file(casrep) else {
+                return false;
+            };
+            let Some(pattern) = known_bugs.find(&report) else {
+                return false;
+            };
+            info!("{} matches known bug \"{pattern}\"", casrep.display());
+            true
+        })
+        .cloned()
+        .collect();
+    if known.is_empty() {
+        return Ok(());
+    }
+    let known_dir = outdir.join(KNOWN_DIR);
+    fs::create_dir_all(&known_dir)?;
+    for casrep in &known {
+        let target = known_dir.join(casrep.file_name().unwrap());
+        if move_files {
+            fs::rename(casrep, &target)?;
+        } else {
+            fs::copy(casrep, &target)?;
+        }
+        if let Some(inputs) = inputs {
+            inputs.place(casrep, &known_dir)?;
+        }
+    }
+    casreps.retain(|casrep| !known.contains(casrep));
+    KNOWN_REPORTS.fetch_add(known.len(), Ordering::Relaxed);
+    Ok(())
+}
+
+/// Select reports that are retained in cluster: the most severe report
+/// (representative) and severity-stratified sample, i.e. reports are taken
+/// in turn from groups with different severity types and crash classes.
+///
+/// # Arguments
+///
+/// * `casreps` - paths to casreps
+///
+/// * `members` - indices of cluster casreps
+///
+/// * `max` - maximum number of retained reports
+///
+/// # Return value
+///
+/// Indices of retained casreps
+fn sample_reports(casreps: &[PathBuf], members: &[usize], max: usize) -> HashSet<usize> {
+    // (severity rank, crash class) -> casreps
+    let mut groups: BTreeMap<(usize, String), VecDeque<usize>> = BTreeMap::new();
+    for &i in members {
+        let key = if let Ok(report) = util::report_from_file(&casreps[i]) {
+            let rank = match report.execution_class.severity.as_str() {
+                "EXPLOITABLE" => 0,
+                "PROBABLY_EXPLOITABLE" => 1,
+                "NOT_EXPLOITABLE" => 2,
+                _ => 3,
+            };
+            (rank, report.execution_class.short_description)
+        } else {
+            (4, String::new())
+        };
+        groups.entry(key).or_default().push_back(i);
+    }
+    let mut retained = HashSet::new();
+    while retained.len() < max {
+        for group in groups.values_mut() {
+            if retained.len() == max {
+                break;
+            }
+            if let Some(i) = group.pop_front() {
+                retained.insert(i);
+            }
+        }
+    }
+    retained
+}
+
+/// Save summary of clusters to cluster_summary.json in clusters directory. For
+/// each cluster it contains size, representative report (medoid by stack trace
+/// similarity), dominant execution class, unique crashlines, and severity
+/// histogram.
+///
+/// # Arguments
+///
+/// * `dir` - path to clusters
+fn save_cluster_summary(dir: &Path) -> Result<()> {
+    let mut clusters: Vec<(usize, PathBuf)> = fs::read_dir(dir)?
+        .flatten()
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| {
+            let name = entry.file_name();
+            let num = name.to_str()?.strip_prefix("cl")?.parse::<usize>().ok()?;
+            Some((num, entry.path()))
+        })
+        .collect();
+    clusters.sort();
+
+    let mut summary = Vec::new();
+    for (num, path) in clusters {
+        let mut casreps: Vec<PathBuf> = fs::read_dir(&path)?
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "casrep"))
+            .collect();
+        casreps.sort();
+
+        let mut traces: Vec<(&PathBuf, Stacktrace)> = Vec::new();
+        let mut classes: BTreeMap<String, usize> = BTreeMap::new();
+        let mut severities: BTreeMap<String, usize> = BTreeMap::new();
+        let mut crashlines: BTreeSet<String> = BTreeSet::new();
+        for casrep in &casreps {
+            let Ok(report) = util::report_from_file(casrep) else {
+                continue;
+            };
+            *classes
+                .entry(report.execution_class.short_description.clone())
+                .or_default() += 1;
+            *severities
+                .entry(report.execution_class.severity.clone())
+                .or_default() += 1;
+            if !report.crashline.is_empty() {
+                crashlines.insert(report.crashline.clone());
+            }
+            if let Ok(trace) = report_stacktrace(&report) {
+                traces.push((casrep, trace));
+            }
+        }
+
+        // Medoid has minimal sum of dissimilarities to other cluster reports.
+        let representative = traces
+            .iter()
+            .map(|(casrep, trace)| {
+                let sum: f64 = traces
+                    .iter()
+                    .map(|(_, other)| 1.0 - similarity(trace, other))
+                    .sum();
+                (sum, casrep)
+            })
+            .min_by(|a, b| a.0.total_cmp(&b.0))
+            .map(|(_, casrep)| casrep.strip_prefix(dir).unwrap_or(casrep))
+            .map(|casrep| casrep.display().to_string())
+            .unwrap_or_default();
+        let class = classes
+            .iter()
+            .max_by(|a, b| a.1.cmp(b.1).then(b.0.cmp(a.0)))
+            .map(|(class, _)| class.clone())
+            .unwrap_or_default();
+
+        summary.push(serde_json::json!({
+            "Cluster": format!("cl{num}"),
+            "Size": casreps.len(),
+            "Representative": representative,
+            "ExecutionClass": class,
+            "CrashLines": crashlines,
+            "Severity": severities,
+        }));
+    }
+
+    fs::write(
+        dir.join(CLUSTER_SUMMARY),
+        serde_json::to_string_pretty(&serde_json::json!({ "Clusters": summary }))?,
+    )?;
+    Ok(())
+}
+
+/// Print number of clusters for dendrogram cuts at thresholds from 0.05 to 1.0,
+/// so that clustering threshold may be calibrated for target
+///
+/// # Arguments
+///
+/// * `steps` - dissimilarities of merge steps in ascending order
+///
+/// * `len` - number of stack traces
+fn print_dendrogram_stats(steps: &[f64], len: usize) {
+    println!(
+        "Dendrogram cut statistics ({} linkage, threshold {}):",
+        cluster_linkage(),
+        cluster_threshold()
+    );
+    println!("{:>10} {:>9}", "Threshold", "Clusters");
+    for i in 1..=20 {
+        let threshold = i as f64 / 20.0;
+        let merged = steps.iter().filter(|&&d| d < threshold).count();
+        println!("{:>10.2} {:>9}", threshold, len - merged);
+    }
+}
+
+/// Perform hierarchical clustering of stack traces in parallel. Stack traces are
+/// clustered block by block (see `cluster_blocks`), so memory is bounded by
+/// dissimilarity matrix of `CLUSTER_BLOCK_SIZE` stack traces.
+///
+/// # Arguments
+///
+/// * `stacktraces` - stack traces to cluster
+///
+/// * `pool` - thread pool for dissimilarity computation
+///
+/// # Return value
+///
+/// Flat cluster numbers of stack traces
+fn cluster_parallel(stacktraces: &[Stacktrace], pool: &rayon::ThreadPool) -> Result<Vec<usize>> {
+    Ok(cluster_blocks(
+        stacktraces,
+        CLUSTER_BLOCK_SIZE,
+        |traces, matrix| {
+            pool.install(|| {
+                condensed_rows(matrix, traces.len())
+                    .into_par_iter()
+                    .enumerate()
+                    .for_each(|(i, row)| dissimilarity_row(traces, i, row))
+            })
+        },
+        |cluster, clusters| {
+            pool.install(|| {
+                clusters
+                    .par_iter()
+                    .map(|other| cluster_dissimilarity(stacktraces, cluster, other))
+                    .collect()
+            })
+        },
+    )?)
+}
+
+/// Perform density-based clustering of stack traces in parallel. Dissimilarity
+/// matrix is used only for `CLUSTER_BLOCK_SIZE` stack traces at most, larger
+/// sets are clustered without matrix by computing neighbours on demand.
+///
+/// # Arguments
+///
+/// * `stacktraces` - stack traces to cluster
+///
+/// * `min_size` - minimum number of stack traces in dense neighbourhood
+///
+/// * `pool` - thread pool for dissimilarity computation
+///
+/// # Return value
+///
+/// Flat cluster numbers of stack traces, 0 for noise
+fn dbscan_parallel(
+    stacktraces: &[Stacktrace],
+    min_size: usize,
+    pool: &rayon::ThreadPool,
+) -> Result<Vec<usize>> {
+    let len = stacktraces.len();
+    if len <= CLUSTER_BLOCK_SIZE {
+        let mut matrix = vec![0.0; len * len.saturating_sub(1) / 2];
+        pool.install(|| {
+            condensed_rows(&mut matrix, len)
+                .into_par_iter()
+                .enumerate()
+                .for_each(|(i, row)| dissimilarity_row(stacktraces, i, row))
+        });
+        return Ok(dbscan_dissimilarity_matrix(&matrix, len, min_size)?);
+    }
+    let threshold = cluster_threshold();
+    Ok(dbscan(len, min_size, |i| {
+        pool.install(|| {
+            (0..len)
+                .into_par_iter()
+                .filter(|&j| {
+                    j != i && 1.0 - similarity(&stacktraces[i], &stacktraces[j]) < threshold
+                })
+                .collect()
+        })
+    }))
+}
+
 /// Perform the clustering of casreps
 ///
 /// # Arguments
@@ -39,25 +478,36 @@ fn stacktrace(path: &Path) -> Result<Stacktrace> {
 ///
 /// * `dedup` - deduplicate casrep by crashline for each cluster, if true
 ///
+/// * `method` - clustering method
+///
+/// * `inputs` - crash inputs to place into clusters
+///
+/// * `max_reports` - maximum number of reports retained in each cluster
+///
 /// # Return value
 ///
 /// * Number of clusters
 /// * Number of valid casrep before crashiline deduplication
 /// * Number of valid casrep after crashiline deduplication
+/// * Number of noise casreps (DBSCAN)
 fn make_clusters(
     inpath: &Path,
     outpath: Option<&Path>,
     jobs: usize,
     dedup: bool,
-) -> Result<(usize, usize, usize)> {
+    method: ClusterMethod,
+    inputs: Option<&CrashInputs>,
+    max_reports: Option<usize>,
+) -> Result<(usize, usize, usize, usize)> {
     // if outpath is "None" we consider that outpath and inpath are the same
     let outpath = outpath.unwrap_or(inpath);
     let dir = fs::read_dir(inpath).with_context(|| format!("File: {}", inpath.display()))?;
 
-    let casreps: Vec<PathBuf> = dir
+    let mut casreps: Vec<PathBuf> = dir
         .map(|path| path.unwrap().path())
         .filter(|s| s.extension().is_some() && s.extension().unwrap() == "casrep")
         .collect();
+    separate_known(&mut casreps, outpath, false, inputs)?;
     let len = casreps.len();
     if len < 2 {
         bail!("{} reports, nothing to cluster...", len);
@@ -76,7 +526,7 @@ fn make_clusters(
     custom_pool.install(|| {
         (0..len).into_par_iter().for_each(|i| {
             if let Ok(report) = util::report_from_file(casreps[i].as_path()) {
-                if let Ok(trace) = report.filtered_stacktrace() {
+                if let Ok(trace) = report_stacktrace(&report) {
                     casrep_info
                         .write()
                         .unwrap()
@@ -123,13 +573,48 @@ fn make_clusters(
     }
 
     // Get clusters
-    let mut clusters = cluster_stacktraces(&stacktraces)?;
+    let mut clusters = if method == ClusterMethod::Crashline {
+        cluster_crashlines(&stacktraces)
+    } else if let ClusterMethod::Dbscan { min_size } = method {
+        dbscan_parallel(&stacktraces, min_size, &custom_pool)?
+    } else {
+        if DENDROGRAM_STATS.load(Ordering::Relaxed) {
+            // Dendrogram of all stack traces requires the whole matrix.
+            let len = stacktraces.len();
+            if len > CLUSTER_BLOCK_SIZE {
+                bail!(
+                    "--dendrogram-stats supports at most {CLUSTER_BLOCK_SIZE} reports, got {len}. \
+                     Deduplicate reports or compute statistics on a sample"
+                );
+            }
+            let mut matrix = vec![0.0; len * (len - 1) / 2];
+            custom_pool.install(|| {
+                condensed_rows(&mut matrix, len)
+                    .into_par_iter()
+                    .enumerate()
+                    .for_each(|(i, row)| dissimilarity_row(&stacktraces, i, row))
+            });
+            print_dendrogram_stats(&merge_dissimilarities(&mut matrix, len)?, len);
+        }
+        cluster_parallel(&stacktraces, &custom_pool)?
+    };
 
     // Cluster formation
     let cluster_cnt: usize = *clusters.iter().max().unwrap();
     for i in 1..=cluster_cnt {
         fs::create_dir_all(format!("{}/cl{}", &outpath.display(), i))?;
     }
+    // Noise is numbered after clusters, so that it is deduplicated by crash
+    // lines like clusters.
+    let noise = cluster_cnt + 1;
+    let noise_cnt = clusters.iter().filter(|&&cluster| cluster == 0).count();
+    if noise_cnt != 0 {
+        fs::create_dir_all(outpath.join(NOISE_DIR))?;
+        clusters
+            .iter_mut()
+            .filter(|cluster| **cluster == 0)
+            .for_each(|cluster| *cluster = noise);
+    }
 
     // Init before and after dedup counters
     let before_cnt = casreps.len();
@@ -140,22 +625,248 @@ fn make_clusters(
         after_cnt = dedup_crashlines(&crashlines, &mut clusters);
     }
 
+    // Cap number of reports in each cluster
+    let mut discarded: BTreeMap<usize, usize> = BTreeMap::new();
+    if let Some(max) = max_reports {
+        for cluster in 1..=cluster_cnt {
+            let members: Vec<usize> = (0..clusters.len())
+                .filter(|&i| clusters[i] == cluster)
+                .collect();
+            if members.len() <= max {
+                continue;
+            }
+            let retained = sample_reports(&casreps, &members, max);
+            for &i in members.iter().filter(|i| !retained.contains(i)) {
+                clusters[i] = 0;
+            }
+            discarded.insert(cluster, members.len() - max);
+        }
+    }
+
     for i in 0..clusters.len() {
         // Skip casreps with duplicate crashlines
         if clusters[i] == 0 {
             continue;
         }
+        let cluster_dir = if clusters[i] == noise {
+            outpath.join(NOISE_DIR)
+        } else {
+            outpath.join(format!("cl{}", clusters[i]))
+        };
         fs::copy(
             &casreps[i],
-            format!(
-                "{}/cl{}/{}",
-                &outpath.display(),
-                &clusters[i],
-                &casreps[i].file_name().unwrap().to_str().unwrap()
-            ),
+            cluster_dir.join(casreps[i].file_name().unwrap()),
         )?;
+        if let Some(inputs) = inputs {
+            inputs.place(&casreps[i], &cluster_dir)?;
+        }
     }
-    Ok((cluster_cnt, before_cnt, after_cnt))
+
+    // Save number of discarded reports to cluster metadata
+    for (cluster, count) in discarded {
+        info!("Cluster cl{cluster}: {count} reports are discarded");
+        let metadata = serde_json::json!({
+            "Retained": max_reports.unwrap(),
+            "Discarded": count,
+        });
+        fs::write(
+            outpath.join(format!("cl{cluster}")).join("cluster.json"),
+            serde_json::to_string_pretty(&metadata)?,
+        )?;
+    }
+    save_cluster_summary(outpath)?;
+    Ok((cluster_cnt, before_cnt, after_cnt, noise_cnt))
+}
+
+/// Add new casreps into existing clusters. Each new casrep is compared only
+/// with cluster representatives (unique stack traces of cluster casreps).
+/// Casrep joins the closest cluster if it doesn't exceed clustering threshold.
+/// Remaining casreps form new clusters that are numbered after existing ones.
+///
+/// # Arguments
+///
+/// * `newpath` - path to new casreps
+///
+/// * `oldpath` - path to existing clusters
+///
+/// * `jobs` - number of jobs for clustering process
+///
+/// * `dedup` - deduplicate casrep by crashline for each cluster, if true
+///
+/// * `inputs` - crash inputs to place into clusters
+///
+/// # Return value
+///
+/// * Number of casreps added to existing clusters
+/// * Number of new clusters
+/// * Number of duplicate casreps
+fn update_clusters(
+    newpath: &Path,
+    oldpath: &Path,
+    jobs: usize,
+    dedup: bool,
+    inputs: Option<&CrashInputs>,
+) -> Result<(usize, usize, usize)> {
+    // Existing clusters: number -> (unique stack traces, crashlines)
+    let mut clusters: BTreeMap<usize, (HashSet<Stacktrace>, HashSet<String>)> = BTreeMap::new();
+    for entry in fs::read_dir(oldpath)
+        .with_context(|| format!("File: {}", oldpath.display()))?
+        .flatten()
+    {
+        let name = entry.file_name();
+        let Some(num) = name
+            .to_str()
+            .and_then(|name| name.strip_prefix("cl"))
+            .and_then(|num| num.parse::<usize>().ok())
+        else {
+            continue;
+        };
+        if !entry.path().is_dir() {
+            continue;
+        }
+        let cluster = clusters.entry(num).or_default();
+        for casrep in fs::read_dir(entry.path())?.flatten().map(|e| e.path()) {
+            if casrep.extension().is_none() || casrep.extension().unwrap() != "casrep" {
+                continue;
+            }
+            let Ok(report) = util::report_from_file(&casrep) else {
+                continue;
+            };
+            if let Ok(trace) = report_stacktrace(&report) {
+                cluster.0.insert(trace);
+                cluster.1.insert(report.crashline);
+            }
+        }
+    }
+    if clusters.is_empty() {
+        bail!("No clusters in {}", oldpath.display());
+    }
+
+    let mut casreps: Vec<PathBuf> = fs::read_dir(newpath)
+        .with_context(|| format!("File: {}", newpath.display()))?
+        .map(|path| path.unwrap().path())
+        .filter(|s| s.extension().is_some() && s.extension().unwrap() == "casrep")
+        .collect();
+    casreps.sort_by(|a, b| a.file_name().unwrap().cmp(b.file_name().unwrap()));
+    if casreps.is_empty() {
+        bail!("No reports in {}", newpath.display());
+    }
+    separate_known(&mut casreps, oldpath, false, inputs)?;
+
+    // Start thread pool.
+    let custom_pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(jobs.min(casreps.len()))
+        .build()
+        .unwrap();
+
+    // New casreps: (casrep, trace, crashline, closest cluster)
+    // Casrep is a duplicate if its stack trace equals any cluster representative.
+    let representatives: Vec<(usize, Vec<&Stacktrace>)> = clusters
+        .iter()
+        .map(|(num, (traces, _))| (*num, traces.iter().collect()))
+        .collect();
+    let linkage = cluster_linkage();
+    let threshold = cluster_threshold();
+    let mut new_casreps: RwLock<Vec<(PathBuf, Stacktrace, String, Option<usize>)>> =
+        RwLock::new(Vec::new());
+    let duplicates: RwLock<usize> = RwLock::new(0);
+    let badreports: RwLock<Vec<PathBuf>> = RwLock::new(Vec::new());
+    custom_pool.install(|| {
+        casreps.par_iter().for_each(|casrep| {
+            let Ok(report) = util::report_from_file(casrep) else {
+                badreports.write().unwrap().push(casrep.clone());
+                return;
+            };
+            let Ok(trace) = report_stacktrace(&report) else {
+                badreports.write().unwrap().push(casrep.clone());
+                return;
+            };
+            if clusters.values().any(|(traces, _)| traces.contains(&trace)) {
+                *duplicates.write().unwrap() += 1;
+                return;
+            }
+            // Distance to cluster is computed from distances to its
+            // representatives by linkage strategy.
+            let closest = representatives
+                .iter()
+                .filter_map(|(num, traces)| {
+                    let distances: Vec<f64> = traces
+                        .iter()
+                        .map(|rep| 1.0 - similarity(&trace, rep))
+                        .collect();
+                    Some((*num, linkage.cluster_distance(&distances)?))
+                })
+                .filter(|(_, distance)| *distance < threshold)
+                .min_by(|a, b| a.1.total_cmp(&b.1))
+                .map(|(num, _)| num);
+            new_casreps
+                .write()
+                .unwrap()
+                .push((casrep.clone(), trace, report.crashline, closest));
+        })
+    });
+    let new_casreps = new_casreps.get_mut().unwrap();
+    new_casreps.sort_by(|a, b| a.0.file_name().unwrap().cmp(b.0.file_name().unwrap()));
+
+    let badreports = badreports.read().unwrap();
+    if !badreports.is_empty() {
+        let clerr = oldpath.join("clerr");
+        fs::create_dir_all(&clerr)?;
+        for report in badreports.iter() {
+            fs::copy(report, clerr.join(report.file_name().unwrap()))?;
+        }
+    }
+
+    // Duplicate stack traces among new casreps are dropped as well
+    let len = new_casreps.len();
+    let mut seen = HashSet::new();
+    new_casreps.retain(|(_, trace, _, _)| seen.insert(trace.clone()));
+    let duplicates = *duplicates.read().unwrap() + len - new_casreps.len();
+
+    // Cluster remaining casreps among themselves
+    let unassigned: Vec<usize> = (0..new_casreps.len())
+        .filter(|&i| new_casreps[i].3.is_none())
+        .collect();
+    let next = *clusters.keys().max().unwrap() + 1;
+    let new_clusters = match unassigned.len() {
+        0 => Vec::new(),
+        1 => vec![1],
+        _ => {
+            let traces: Vec<Stacktrace> = unassigned
+                .iter()
+                .map(|&i| new_casreps[i].1.clone())
+                .collect();
+            cluster_parallel(&traces, &custom_pool)?
+        }
+    };
+    for (&i, cluster) in unassigned.iter().zip(&new_clusters) {
+        new_casreps[i].3 = Some(next + cluster - 1);
+    }
+
+    let mut added = 0;
+    for (casrep, _, crashline, cluster) in new_casreps.iter() {
+        let num = cluster.unwrap();
+        let crashlines = &mut clusters.entry(num).or_default().1;
+        if dedup && !crashline.is_empty() && !crashlines.insert(crashline.clone()) {
+            continue;
+        }
+        let cluster_dir = oldpath.join(format!("cl{num}"));
+        fs::create_dir_all(&cluster_dir)?;
+        fs::copy(casrep, cluster_dir.join(casrep.file_name().unwrap()))?;
+        if let Some(inputs) = inputs {
+            inputs.place(casrep, &cluster_dir)?;
+        }
+        if num < next {
+            added += 1;
+        }
+    }
+    save_cluster_summary(oldpath)?;
+
+    Ok((
+        added,
+        new_clusters.iter().max().copied().unwrap_or(0),
+        duplicates,
+    ))
 }
 
 /// Remove duplicate casreps
@@ -168,10 +879,21 @@ fn make_clusters(
 ///
 /// * `jobs` - number of jobs for deduplication process
 ///
+/// * `inputs` - crash inputs to place next to deduplicated reports
+///
+/// * `by_hash` - deduplicate casreps by stack hash instead of stack trace
+///   comparison, if true
+///
 /// # Return value
 ///
 /// Number of reports before/after deduplication
-fn deduplication(indir: &Path, outdir: Option<PathBuf>, jobs: usize) -> Result<(usize, usize)> {
+fn deduplication(
+    indir: &Path,
+    outdir: Option<PathBuf>,
+    jobs: usize,
+    inputs: Option<&CrashInputs>,
+    by_hash: bool,
+) -> Result<(usize, usize)> {
     let dir = fs::read_dir(indir).with_context(|| {
         format!(
             "Error occurred while opening directory with CASR reports. File: {}",
@@ -182,12 +904,17 @@ fn deduplication(indir: &Path, outdir: Option<PathBuf>, jobs: usize) -> Result<(
     let (mut before, mut after) = (0usize, 0usize);
     for entry in dir.flatten() {
         if entry.metadata()?.is_dir() {
+            if entry.file_name() == KNOWN_DIR {
+                continue;
+            }
             let res = deduplication(
                 entry.path().as_path(),
                 outdir
                     .as_ref()
                     .map(|outdir| Path::new(&outdir).join(entry.file_name())),
                 jobs,
+                inputs,
+                by_hash,
             )?;
             before += res.0;
             after += res.1;
@@ -200,6 +927,12 @@ fn deduplication(indir: &Path, outdir: Option<PathBuf>, jobs: usize) -> Result<(
     }
 
     paths.sort_by(|a, b| a.file_name().unwrap().cmp(b.file_name().unwrap()));
+    separate_known(
+        &mut paths,
+        outdir.as_deref().unwrap_or(indir),
+        outdir.is_none(),
+        inputs,
+    )?;
 
     // Start thread pool.
     let custom_pool = rayon::ThreadPoolBuilder::new()
@@ -209,49 +942,83 @@ fn deduplication(indir: &Path, outdir: Option<PathBuf>, jobs: usize) -> Result<(
 
     let badrepidxs: RwLock<HashSet<usize>> = RwLock::new(HashSet::new());
     let stacktraces: RwLock<Vec<Stacktrace>> = RwLock::new(vec![Default::default(); paths.len()]);
+    let hashes: RwLock<Vec<String>> = RwLock::new(vec![String::new(); paths.len()]);
     custom_pool.install(|| {
         paths.par_iter().enumerate().for_each(|(index, report)| {
-            if let Ok(trace) = stacktrace(report.as_path()) {
-                stacktraces.write().unwrap()[index] = trace;
+            let parsed = if by_hash {
+                report_stack_hash(report.as_path())
+                    .map(|hash| hashes.write().unwrap()[index] = hash)
             } else {
+                stacktrace(report.as_path())
+                    .map(|trace| stacktraces.write().unwrap()[index] = trace)
+            };
+            if parsed.is_err() {
                 badrepidxs.write().unwrap().insert(index);
             }
         })
     });
 
     let badrepidxs = badrepidxs.read().unwrap();
-    let stacktraces = stacktraces.read().unwrap();
+    let mut stacktraces = stacktraces.into_inner().unwrap();
+    let hashes = hashes.read().unwrap();
 
-    let result = dedup_stacktraces(&stacktraces);
+    let result = if by_hash {
+        // Equal stack hashes are found in linear time.
+        let mut unique = HashSet::new();
+        hashes
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| !badrepidxs.contains(index))
+            .map(|(_, hash)| unique.insert(hash))
+            .collect()
+    } else {
+        // Stack traces are hashed in parallel and compared only on hash collision.
+        let mut index = 0;
+        stacktraces.retain(|_| {
+            index += 1;
+            !badrepidxs.contains(&(index - 1))
+        });
+        let trace_hashes: Vec<u64> =
+            custom_pool.install(|| stacktraces.par_iter().map(stacktrace_hash).collect());
+        dedup_hashed(&stacktraces, &trace_hashes)
+    };
+
+    // Indices of valid reports and deduplication results
+    let results: Vec<(usize, bool)> = (0..paths.len())
+        .filter(|x| !badrepidxs.contains(x))
+        .zip(result)
+        .collect();
 
     if let Some(ref outdir) = outdir {
         fs::create_dir_all(outdir)?;
-        (0..paths.len())
-            .filter(|x| !badrepidxs.contains(x))
-            .enumerate()
-            .try_for_each(|(res_idx, true_idx)| {
-                if result[res_idx] {
+        custom_pool.install(|| {
+            results
+                .par_iter()
+                .filter(|(_, unique)| *unique)
+                .try_for_each(|&(idx, _)| {
                     fs::copy(
-                        &paths[true_idx],
-                        Path::new(&outdir).join(paths[true_idx].file_name().unwrap()),
+                        &paths[idx],
+                        Path::new(&outdir).join(paths[idx].file_name().unwrap()),
                     )?;
-                    after += 1;
-                }
-                Ok::<(), anyhow::Error>(())
-            })?;
+                    if let Some(inputs) = inputs {
+                        inputs.place(&paths[idx], outdir)?;
+                    }
+                    Ok::<(), anyhow::Error>(())
+                })
+        })?;
     } else {
-        (0..paths.len())
-            .filter(|x| !badrepidxs.contains(x))
-            .enumerate()
-            .try_for_each(|(res_idx, true_idx)| {
-                if !result[res_idx] {
-                    fs::remove_file(&paths[true_idx])
-                } else {
-                    after += 1;
-                    Ok(())
+        custom_pool.install(|| {
+            results.par_iter().try_for_each(|&(idx, unique)| {
+                if !unique {
+                    fs::remove_file(&paths[idx])?;
+                } else if let Some(inputs) = inputs {
+                    inputs.place(&paths[idx], indir)?;
                 }
-            })?;
+                Ok::<(), anyhow::Error>(())
+            })
+        })?;
     }
+    after += results.iter().filter(|(_, unique)| *unique).count();
 
     if !badrepidxs.is_empty() {
         let clerr = outdir
@@ -279,6 +1046,96 @@ fn deduplication(indir: &Path, outdir: Option<PathBuf>, jobs: usize) -> Result<(
     Ok((before, after))
 }
 
+/// Prune reports by retention rules. Each directory (e.g., cluster) is
+/// processed separately. Crash input and all its reports (`.casrep`,
+/// `.gdb.casrep`) are removed together.
+///
+/// # Arguments
+///
+/// * `dir` - path to directory with reports or clusters
+///
+/// * `max_age` - remove crashes older than `max_age` days
+///
+/// * `per_dir` - retain at most `per_dir` newest crashes in each directory
+///
+/// * `keep` - severity types of crashes that are never removed
+///
+/// # Return value
+///
+/// Number of crashes before pruning and number of removed crashes
+fn prune(
+    dir: &Path,
+    max_age: Option<u64>,
+    per_dir: Option<usize>,
+    keep: &[String],
+) -> Result<(usize, usize)> {
+    let (mut before, mut removed) = (0, 0);
+    // Crash stem -> (is kept, date)
+    let mut crashes: BTreeMap<PathBuf, (bool, Option<DateTime<FixedOffset>>)> = BTreeMap::new();
+    for entry in fs::read_dir(dir)
+        .with_context(|| format!("File: {}", dir.display()))?
+        .flatten()
+    {
+        let path = entry.path();
+        if path.is_dir() {
+            let res = prune(&path, max_age, per_dir, keep)?;
+            before += res.0;
+            removed += res.1;
+            continue;
+        }
+        if path.extension().is_none() || path.extension().unwrap() != "casrep" {
+            continue;
+        }
+        let mut stem = path.with_extension("");
+        if stem.extension().is_some() && stem.extension().unwrap() == "gdb" {
+            stem = stem.with_extension("");
+        }
+        let Ok(report) = util::report_from_file(&path) else {
+            warn!("Couldn't read report {}", path.display());
+            continue;
+        };
+        let date = DateTime::parse_from_rfc3339(&report.date).ok();
+        let crash = crashes.entry(stem).or_insert((false, None));
+        crash.0 |= keep.contains(&report.execution_class.severity);
+        crash.1 = crash.1.max(date);
+    }
+    before += crashes.len();
+
+    let mut to_remove: Vec<&PathBuf> = Vec::new();
+    if let Some(max_age) = max_age {
+        let border = (Local::now() - chrono::Duration::days(max_age as i64)).timestamp();
+        to_remove.extend(crashes.iter().filter_map(|(stem, (kept, date))| {
+            (!kept && matches!(date, Some(date) if date.timestamp() < border)).then_some(stem)
+        }));
+    }
+    if let Some(per_dir) = per_dir {
+        // Newest crashes go first.
+        let mut candidates: Vec<(&PathBuf, &Option<DateTime<FixedOffset>>)> = crashes
+            .iter()
+            .filter(|(stem, (kept, _))| !kept && !to_remove.contains(stem))
+            .map(|(stem, (_, date))| (stem, date))
+            .collect();
+        candidates.sort_by(|a, b| b.1.cmp(a.1));
+        to_remove.extend(candidates.iter().skip(per_dir).map(|(stem, _)| *stem));
+    }
+
+    for stem in to_remove {
+        let name = stem.file_name().unwrap().to_str().unwrap();
+        for file in [
+            stem.to_path_buf(),
+            stem.with_file_name(format!("{name}.casrep")),
+            stem.with_file_name(format!("{name}.gdb.casrep")),
+        ] {
+            if file.is_file() {
+                fs::remove_file(&file)?;
+            }
+        }
+        removed += 1;
+    }
+
+    Ok((before, removed))
+}
+
 /// Merge unique reports from `input` directory into `output` directory.
 /// If `diff` directory is set, unique (`input` \ `output`) reports are saved
 /// in `diff` directory.
@@ -334,7 +1191,7 @@ fn merge_or_diff(input: &Path, output: &Path, diff: Option<&Path>) -> Result<u64
                 if mainhash.insert(trace) {
                     let target = Path::new(&save_dir).join(entry.file_name());
                     if target.exists() {
-                        eprintln!(
+                        warn!(
                             "File with name {} already exists in OUTPUT_DIR.",
                             target.file_name().unwrap().to_str().unwrap()
                         );
@@ -344,7 +1201,7 @@ fn merge_or_diff(input: &Path, output: &Path, diff: Option<&Path>) -> Result<u64
                     }
                 }
             } else {
-                eprintln!(
+                warn!(
                     "Cannot extract stack trace from {}. Skipping this report.",
                     entry.file_name().into_string().unwrap()
                 );
@@ -355,6 +1212,9 @@ fn merge_or_diff(input: &Path, output: &Path, diff: Option<&Path>) -> Result<u64
 }
 
 fn main() -> Result<()> {
+    // Load configuration files.
+    config::load_config("casr-cluster")?;
+
     let matches = clap::Command::new("casr-cluster")
         .version(clap::crate_version!())
         .about("Tool for clustering CASR reports")
@@ -384,6 +1244,88 @@ fn main() -> Result<()> {
                     reports in this directory will not be deleted.",
                 ),
         )
+        .arg(
+            Arg::new("update")
+                .short('u')
+                .long("update")
+                .action(ArgAction::Set)
+                .num_args(2)
+                .value_parser(clap::value_parser!(PathBuf))
+                .value_names(["NEW_DIR", "CLUSTERS_DIR"])
+                .help(
+                    "Add CASR reports from NEW_DIR into existing clusters in \
+                    CLUSTERS_DIR. Reports are compared with cluster representatives \
+                    only. Reports that don't fit existing clusters form new clusters \
+                    numbered after existing ones.",
+                ),
+        )
+        .arg(
+            Arg::new("threshold")
+                .long("threshold")
+                .env("CASR_CLUSTER_THRESHOLD")
+                .action(ArgAction::Set)
+                .value_name("DISSIMILARITY")
+                .default_value("0.3")
+                .value_parser(|arg: &str| match arg.parse::<f64>() {
+                    Ok(threshold) if threshold > 0.0 && threshold <= 1.0 => Ok(threshold),
+                    _ => Err(format!("{arg} is not in (0, 1]")),
+                })
+                .help(
+                    "Maximum dissimilarity (0, 1] between stack traces in one cluster \
+                    (-c, -u)",
+                ),
+        )
+        .arg(
+            Arg::new("linkage")
+                .long("linkage")
+                .env("CASR_CLUSTER_LINKAGE")
+                .action(ArgAction::Set)
+                .value_name("METHOD")
+                .default_value("complete")
+                .value_parser(["single", "complete", "average"])
+                .help(
+                    "Linkage strategy for clustering: dissimilarity between clusters is \
+                    minimum (single), maximum (complete), or average dissimilarity between \
+                    their stack traces",
+                ),
+        )
+        .arg(
+            Arg::new("method")
+                .long("method")
+                .env("CASR_CLUSTER_METHOD")
+                .action(ArgAction::Set)
+                .value_name("METHOD")
+                .default_value("hierarchical")
+                .value_parser(["hierarchical", "dbscan"])
+                .help(
+                    "Clustering method (-c): hierarchical or density-based (dbscan). \
+                    DBSCAN doesn't merge dissimilar reports and puts reports without \
+                    enough similar neighbours into noise directory",
+                ),
+        )
+        .arg(
+            Arg::new("min-cluster-size")
+                .long("min-cluster-size")
+                .env("CASR_CLUSTER_MIN_CLUSTER_SIZE")
+                .action(ArgAction::Set)
+                .value_name("N")
+                .default_value("2")
+                .value_parser(clap::value_parser!(u32).range(1..))
+                .help(
+                    "Minimum number of similar reports (including report itself) that \
+                    form DBSCAN cluster",
+                ),
+        )
+        .arg(
+            Arg::new("dendrogram-stats")
+                .long("dendrogram-stats")
+                .action(ArgAction::SetTrue)
+                .requires("clustering")
+                .help(
+                    "Print number of clusters for dendrogram cuts at different thresholds \
+                    to calibrate --threshold",
+                ),
+        )
         .arg(
             Arg::new("unique-crashline")
                 .long("unique-crashline")
@@ -392,6 +1334,30 @@ fn main() -> Result<()> {
                 .value_parser(FalseyValueParser::new())
                 .help("Leave reports with unique crash lines in each cluster")
         )
+        .arg(
+            Arg::new("by-crashline")
+                .long("by-crashline")
+                .env("CASR_CLUSTER_BY_CRASHLINE")
+                .action(ArgAction::SetTrue)
+                .value_parser(FalseyValueParser::new())
+                .help(
+                    "Cluster reports by crash source file and line (top user frame \
+                    if there is no source line) instead of stack trace similarity",
+                ),
+        )
+        .arg(
+            Arg::new("max-reports")
+                .long("max-reports")
+                .env("CASR_CLUSTER_MAX_REPORTS")
+                .action(ArgAction::Set)
+                .value_name("N")
+                .value_parser(clap::value_parser!(u32).range(1..))
+                .help(
+                    "Maximum number of reports retained in each cluster: the most severe \
+                    report and severity-stratified sample. Number of discarded reports is \
+                    saved to cluster.json in cluster directory",
+                ),
+        )
         .arg(
             Arg::new("deduplication")
                 .short('d')
@@ -406,6 +1372,17 @@ fn main() -> Result<()> {
                     If one directory is provided, duplicated reports are deleted.",
                 ),
         )
+        .arg(
+            Arg::new("by-hash")
+                .long("by-hash")
+                .env("CASR_CLUSTER_BY_HASH")
+                .action(ArgAction::SetTrue)
+                .value_parser(FalseyValueParser::new())
+                .help(
+                    "Deduplicate reports (-d) by stack hash (StackHash report field) in \
+                    linear time instead of stack trace comparison",
+                ),
+        )
         .arg(
             Arg::new("merge")
                 .short('m')
@@ -431,24 +1408,109 @@ fn main() -> Result<()> {
                     Copy new CASR reports from NEW_DIR into DIFF_DIR.",
                 ),
         )
+        .arg(
+            Arg::new("inputs")
+                .long("inputs")
+                .env("CASR_CLUSTER_INPUTS")
+                .action(ArgAction::Set)
+                .value_parser(clap::value_parser!(PathBuf))
+                .value_name("DIR")
+                .help(
+                    "Directory with crash inputs. Inputs are hard-linked next to \
+                    deduplicated and clustered reports with the same name \
+                    (without .casrep extension)",
+                ),
+        )
+        .arg(
+            Arg::new("move-inputs")
+                .long("move-inputs")
+                .action(ArgAction::SetTrue)
+                .requires("inputs")
+                .help("Move crash inputs instead of creating hard links"),
+        )
+        .arg(
+            Arg::new("prune")
+                .long("prune")
+                .action(ArgAction::Set)
+                .value_parser(clap::value_parser!(PathBuf))
+                .value_name("DIR")
+                .help(
+                    "Remove crash inputs and their reports from DIR (and its \
+                    subdirectories, e.g., clusters) by retention rules: \
+                    --prune-age, --prune-per-cluster, and --keep-severity",
+                ),
+        )
+        .arg(
+            Arg::new("prune-age")
+                .long("prune-age")
+                .env("CASR_CLUSTER_PRUNE_AGE")
+                .action(ArgAction::Set)
+                .value_name("DAYS")
+                .value_parser(clap::value_parser!(u64))
+                .help("Remove crashes with reports older than DAYS"),
+        )
+        .arg(
+            Arg::new("prune-per-cluster")
+                .long("prune-per-cluster")
+                .env("CASR_CLUSTER_PRUNE_PER_CLUSTER")
+                .action(ArgAction::Set)
+                .value_name("N")
+                .value_parser(clap::value_parser!(u32).range(1..))
+                .help("Retain only N newest crashes in each cluster (directory)"),
+        )
+        .arg(
+            Arg::new("keep-severity")
+                .long("keep-severity")
+                .env("CASR_CLUSTER_KEEP_SEVERITY")
+                .action(ArgAction::Set)
+                .value_name("TYPE")
+                .value_delimiter(',')
+                .default_value("EXPLOITABLE")
+                .value_parser(["EXPLOITABLE", "PROBABLY_EXPLOITABLE", "NOT_EXPLOITABLE"])
+                .help("Severity types of crashes that are never removed while pruning"),
+        )
+        .arg(known_bugs_arg())
         .arg(
             Arg::new("ignore")
                 .long("ignore")
+                .env("CASR_IGNORE")
                 .action(ArgAction::Set)
                 .value_parser(clap::value_parser!(PathBuf))
                 .value_name("FILE")
                 .help("File with regular expressions for functions and file paths that should be ignored"),
         )
+        .arg(
+            Arg::new("all-threads")
+                .long("all-threads")
+                .action(ArgAction::SetTrue)
+                .help("Compare stack traces of all threads (if they are present in reports) instead of crashed thread only"),
+        )
+        .arg(
+            Arg::new("show-filtered")
+                .long("show-filtered")
+                .action(ArgAction::Set)
+                .num_args(1..)
+                .value_parser(clap::value_parser!(PathBuf))
+                .value_name("CASREP")
+                .help("Print stack trace frames of CASR reports marking frames that are filtered out before deduplication and clustering (\"-\")"),
+        )
         .arg(
             Arg::new("jobs")
                 .long("jobs")
+                .env("CASR_JOBS")
                 .short('j')
                 .value_name("N")
                 .action(ArgAction::Set)
-                .help("Number of parallel jobs to collect CASR reports")
+                .help("Number of parallel jobs to collect CASR reports and compare stack traces")
                 .value_parser(clap::value_parser!(u32).range(1..))
         )
+        .args(util::logging_args())
         .get_matches();
+    config::clear_env();
+
+    // Init log.
+    util::initialize_logging(&matches);
+
     init_ignored_frames!("cpp", "rust", "python", "go", "java", "js");
 
     // Get number of threads
@@ -463,9 +1525,41 @@ fn main() -> Result<()> {
         util::add_custom_ignored_frames(path)?;
     }
 
+    ALL_THREADS.store(matches.get_flag("all-threads"), Ordering::Relaxed);
+    *KNOWN_BUGS.write().unwrap() = KnownBugs::from_matches(&matches)?;
+    DENDROGRAM_STATS.store(matches.get_flag("dendrogram-stats"), Ordering::Relaxed);
+    set_cluster_threshold(*matches.get_one::<f64>("threshold").unwrap());
+    set_cluster_linkage(
+        matches
+            .get_one::<String>("linkage")
+            .unwrap()
+            .parse::<Linkage>()?,
+    );
+
+    // Retention rules may be set in configuration file (environment), so only
+    // command line options require --prune.
+    if !matches.contains_id("prune") {
+        for id in ["prune-age", "prune-per-cluster", "keep-severity"] {
+            if matches.value_source(id) == Some(ValueSource::CommandLine) {
+                bail!("--{id} requires --prune");
+            }
+        }
+    }
+
     // Get env var
     let dedup_crashlines = matches.get_flag("unique-crashline");
 
+    // Get crash inputs directory
+    let inputs = matches.get_one::<PathBuf>("inputs").map(|dir| CrashInputs {
+        dir,
+        move_files: matches.get_flag("move-inputs"),
+    });
+    if let Some(inputs) = &inputs {
+        if !inputs.dir.is_dir() {
+            bail!("{} isn't a directory", inputs.dir.display());
+        }
+    }
+
     if matches.contains_id("similarity") {
         let casreps: Vec<&PathBuf> = matches.get_many::<PathBuf>("similarity").unwrap().collect();
         println!(
@@ -475,24 +1569,54 @@ fn main() -> Result<()> {
     } else if matches.contains_id("clustering") {
         let paths: Vec<&PathBuf> = matches.get_many::<PathBuf>("clustering").unwrap().collect();
 
-        let (result, before, after) = make_clusters(
+        let method = if matches.get_flag("by-crashline") {
+            ClusterMethod::Crashline
+        } else if matches.get_one::<String>("method").unwrap() == "dbscan" {
+            ClusterMethod::Dbscan {
+                min_size: *matches.get_one::<u32>("min-cluster-size").unwrap() as usize,
+            }
+        } else {
+            ClusterMethod::Hierarchical
+        };
+        let (result, before, after, noise) = make_clusters(
             paths[0],
             paths.get(1).map(|x| x.as_path()),
             jobs,
             dedup_crashlines,
+            method,
+            inputs.as_ref(),
+            matches
+                .get_one::<u32>("max-reports")
+                .map(|max| *max as usize),
         )?;
         println!("Number of clusters: {result}");
+        if noise != 0 {
+            println!("Number of noise reports: {noise}");
+        }
         // Print crashline dedup summary
         if before != after {
             println!("Number of reports before crashline deduplication: {before}");
             println!("Number of reports after crashline deduplication: {after}");
         }
+    } else if matches.contains_id("update") {
+        let paths: Vec<&PathBuf> = matches.get_many::<PathBuf>("update").unwrap().collect();
+        let (added, clusters, duplicates) =
+            update_clusters(paths[0], paths[1], jobs, dedup_crashlines, inputs.as_ref())?;
+        println!("Number of reports added to existing clusters: {added}");
+        println!("Number of new clusters: {clusters}");
+        println!("Number of duplicate reports: {duplicates}");
     } else if matches.contains_id("deduplication") {
         let paths: Vec<&PathBuf> = matches
             .get_many::<PathBuf>("deduplication")
             .unwrap()
             .collect();
-        let (before, after) = deduplication(paths[0], paths.get(1).map(|x| x.to_path_buf()), jobs)?;
+        let (before, after) = deduplication(
+            paths[0],
+            paths.get(1).map(|x| x.to_path_buf()),
+            jobs,
+            inputs.as_ref(),
+            matches.get_flag("by-hash"),
+        )?;
         println!("Number of reports before deduplication: {before}");
         println!("Number of reports after deduplication: {after}");
     } else if matches.contains_id("merge") {
@@ -503,6 +1627,26 @@ fn main() -> Result<()> {
             new,
             paths[1].display()
         );
+    } else if let Some(dir) = matches.get_one::<PathBuf>("prune") {
+        let max_age = matches.get_one::<u64>("prune-age").copied();
+        let per_cluster = matches
+            .get_one::<u32>("prune-per-cluster")
+            .map(|n| *n as usize);
+        if max_age.is_none() && per_cluster.is_none() {
+            bail!("--prune-age or --prune-per-cluster should be specified for pruning");
+        }
+        let keep: Vec<String> = matches
+            .get_many::<String>("keep-severity")
+            .unwrap()
+            .cloned()
+            .collect();
+        let (before, removed) = prune(dir, max_age, per_cluster, &keep)?;
+        println!("Number of crashes before pruning: {before}");
+        println!("Number of removed crashes: {removed}");
+    } else if let Some(casreps) = matches.get_many::<PathBuf>("show-filtered") {
+        for casrep in casreps {
+            show_filtered(casrep)?;
+        }
     } else if matches.contains_id("diff") {
         let paths: Vec<&PathBuf> = matches.get_many::<PathBuf>("diff").unwrap().collect();
         let new = merge_or_diff(paths[0], paths[1], Some(paths[2]))?;
@@ -513,5 +1657,10 @@ fn main() -> Result<()> {
         );
     }
 
+    let known = KNOWN_REPORTS.load(Ordering::Relaxed);
+    if known != 0 {
+        println!("Number of known reports: {known}");
+    }
+
     Ok(())
 }
@@ -4,24 +4,40 @@ use gdb_command::mappings::{MappedFiles, MappedFilesExt};
 use gdb_command::memory::*;
 use gdb_command::registers::{Registers, RegistersExt};
 use gdb_command::siginfo::Siginfo;
-use gdb_command::{ExecType, GdbCommand};
-use goblin::container::Endian;
-use goblin::elf::{header, note, Elf};
-use log::{error, warn};
+use gdb_command::ExecType;
+use goblin::elf::header;
+use log::{debug, error, info, warn};
+#[cfg(unix)]
 use nix::fcntl::{flock, FlockArg};
+use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
 use simplelog::*;
+use std::collections::HashMap;
 use std::fs::{File, OpenOptions};
 use std::io::prelude::*;
 use std::io::{self, Read};
+#[cfg(unix)]
 use std::os::unix::io::AsRawFd;
 use std::path::{Path, PathBuf};
-
+use std::process::Command;
+use std::sync::RwLock;
+use tempfile::TempPath;
+
+use casr::config;
+use casr::elf_core::ElfCore;
+use casr::gdb::GdbCommand;
+use casr::macos::{analyze_mac_crash, is_mac_crash};
+use casr::minidump::{analyze_minidump, is_minidump};
+use casr::util;
 use libcasr::error::Error;
-use libcasr::gdb::exploitable::{GdbContext, MachineInfo};
+use libcasr::gdb::exploitable::GdbContext;
+use libcasr::gdb::extract_threads;
 use libcasr::report::*;
 use libcasr::severity::Severity;
 
 fn main() -> Result<()> {
+    // Load configuration files.
+    config::load_config("casr-core")?;
+
     let matches = clap::Command::new("casr-core")
         .version(clap::crate_version!())
         .about("Analyze coredump for security goals and provide detailed report with severity estimation")
@@ -40,20 +56,34 @@ fn main() -> Result<()> {
             .long("file")
             .value_name("FILE")
             .value_parser(clap::value_parser!(PathBuf))
-            .help("Path to input core file")
-            .required_if_eq("mode","offline")
-            .required_unless_present("mode")
+            .help("Path to input core file, minidump, or macOS crash report (.ips/.crash)")
+            .conflicts_with("input")
+            .action(ArgAction::Set))
+        .arg(Arg::new("input")
+            .short('i')
+            .long("input")
+            .value_name("DIR")
+            .value_parser(clap::value_parser!(PathBuf))
+            .help("Directory with core files to analyze in parallel (batch mode)")
+            .action(ArgAction::Set))
+        .arg(Arg::new("crash-input")
+            .long("crash-input")
+            .value_name("FILE")
+            .value_parser(clap::value_parser!(PathBuf))
+            .conflicts_with("input")
+            .help("Crashing input: registers and faulting address that contain its bytes upgrade execution class (e.g., SourceAv to SourceAvTainted)")
             .action(ArgAction::Set))
         .arg(Arg::new("output")
             .short('o')
             .long("output")
             .value_name("FILE")
             .value_parser(clap::value_parser!(PathBuf))
-            .help("Path to save report in JSON format")
+            .help("Path to save report in JSON format (output directory in batch mode)")
             .action(ArgAction::Set))
         .arg(Arg::new("stdout")
             .long("stdout")
             .action(ArgAction::SetTrue)
+            .conflicts_with("input")
             .help("Print CASR report to stdout")
             )
         .arg(Arg::new("core")
@@ -69,6 +99,21 @@ fn main() -> Result<()> {
             .value_name("FILE")
             .help("Path to executable")
             .action(ArgAction::Set))
+        .arg(Arg::new("executable-map")
+            .long("executable-map")
+            .env("CASR_EXECUTABLE_MAP")
+            .value_name("FILE")
+            .value_parser(clap::value_parser!(PathBuf))
+            .help("File with lines \"<executable path in core> <local executable path>\" to find executables for core files")
+            .action(ArgAction::Set))
+        .arg(Arg::new("jobs")
+            .short('j')
+            .long("jobs")
+            .env("CASR_JOBS")
+            .value_name("JOBS")
+            .value_parser(clap::value_parser!(u32).range(1..))
+            .help("Number of parallel jobs for batch mode [default: half of cpu cores]")
+            .action(ArgAction::Set))
         .arg(Arg::new("uid")
             .short('u')
             .long("uid")
@@ -106,73 +151,112 @@ fn main() -> Result<()> {
             .multiple(true)
             .conflicts_with_all(["offline_analysis"]))
         .group(ArgGroup::new("offline_analysis")
-            .args(["file","output"])
+            .args(["file","input","crash-input","output","executable-map"])
             .arg("stdout")
             .multiple(true)
             .conflicts_with_all(["online_analysis"]))
+        .arg(util::debugger_timeout_arg())
+        .arg(util::near_null_arg())
+        .args(util::logging_args())
         .get_matches();
+    config::clear_env();
 
     let mode = matches.get_one::<String>("mode").unwrap();
+    let debugger_timeout = *matches.get_one::<u64>("debugger-timeout").unwrap();
+    util::init_near_null_threshold(&matches);
     if *mode == "offline" {
+        // Init log.
+        util::initialize_logging(&matches);
+
+        if !matches.contains_id("file") && !matches.contains_id("input") {
+            bail!("--file or --input should be specified in offline mode.");
+        }
         if !matches.contains_id("output") && !matches.contains_id("stdout") {
             bail!("--stdout or --output should be specified in offline mode.");
         }
 
-        let core_path = matches.get_one::<PathBuf>("file").unwrap();
-        if !core_path.exists() {
-            bail!("{} doesn't exist", core_path.to_str().unwrap());
-        }
-
-        let mut core: Vec<u8> = Vec::new();
-        let mut file = File::open(core_path)
-            .with_context(|| format!("Couldn't open core: {}", core_path.display()))?;
-        file.read_to_end(&mut core)
-            .with_context(|| format!("Couldn't read core: {}", core_path.display()))?;
-        let mut report = CrashReport::new();
-
-        if matches.contains_id("executable") {
-            let executable_path = PathBuf::from(matches.get_one::<String>("executable").unwrap());
+        let executable = if let Some(executable) = matches.get_one::<String>("executable") {
+            let executable_path = PathBuf::from(executable);
             if !executable_path.exists() {
                 bail!("{} doesn't exist", executable_path.to_str().unwrap());
             }
-            report
-                .executable_path
-                .push_str(executable_path.to_str().unwrap());
-        }
-
-        let result = analyze_coredump(&mut report, &core, core_path);
+            Some(executable_path)
+        } else {
+            None
+        };
+        let executable_map = if let Some(map_path) = matches.get_one::<PathBuf>("executable-map") {
+            read_executable_map(map_path)?
+        } else {
+            HashMap::new()
+        };
 
-        if result.is_ok() {
-            if let Some(result_path) = matches.get_one::<PathBuf>("output") {
-                let mut file = File::create(result_path).with_context(|| {
-                    format!("Couldn't create report: {}", result_path.display())
-                })?;
-                file.write_all(serde_json::to_string_pretty(&report).unwrap().as_bytes())
-                    .with_context(|| format!("Couldn't write report: {}", result_path.display()))?;
-            }
+        if let Some(input_dir) = matches.get_one::<PathBuf>("input") {
+            let Some(output_dir) = matches.get_one::<PathBuf>("output") else {
+                bail!("--output directory should be specified in batch mode.");
+            };
+            let jobs = if let Some(jobs) = matches.get_one::<u32>("jobs") {
+                *jobs as usize
+            } else {
+                std::cmp::max(1, num_cpus::get() / 2)
+            };
+            return analyze_core_dir(
+                input_dir,
+                output_dir,
+                executable.as_ref(),
+                &executable_map,
+                jobs,
+                debugger_timeout,
+            );
+        }
 
-            if matches.get_flag("stdout") {
-                println!("{}\n", serde_json::to_string_pretty(&report).unwrap());
-            }
+        let crash_input = if let Some(path) = matches.get_one::<PathBuf>("crash-input") {
+            Some(
+                std::fs::read(path)
+                    .with_context(|| format!("Couldn't read input: {}", path.display()))?,
+            )
         } else {
-            bail!("Coredump analysis error: {}", result.err().unwrap());
+            None
+        };
+        let core_path = matches.get_one::<PathBuf>("file").unwrap();
+        let report = analyze_core_file(
+            core_path,
+            executable.as_ref(),
+            &executable_map,
+            crash_input.as_deref(),
+            debugger_timeout,
+        )?;
+
+        if let Some(result_path) = matches.get_one::<PathBuf>("output") {
+            let mut file = File::create(result_path)
+                .with_context(|| format!("Couldn't create report: {}", result_path.display()))?;
+            file.write_all(serde_json::to_string_pretty(&report).unwrap().as_bytes())
+                .with_context(|| format!("Couldn't write report: {}", result_path.display()))?;
+        }
+
+        if matches.get_flag("stdout") {
+            println!("{}\n", serde_json::to_string_pretty(&report).unwrap());
         }
         return Ok(());
     }
 
     // Online mode.
-    WriteLogger::init(
-        LevelFilter::Info,
-        ConfigBuilder::new()
-            .set_time_offset_to_local()
-            .unwrap()
-            .build(),
-        OpenOptions::new()
-            .append(true)
-            .create(true)
-            .open("/var/log/casr.log")?,
-    )
-    .unwrap();
+    let log_file = OpenOptions::new()
+        .append(true)
+        .create(true)
+        .open("/var/log/casr.log")?;
+    if util::is_json_log(&matches) {
+        util::JsonLogger::init(util::log_level(&matches), Box::new(log_file))?;
+    } else {
+        WriteLogger::init(
+            util::log_level(&matches),
+            ConfigBuilder::new()
+                .set_time_offset_to_local()
+                .unwrap()
+                .build(),
+            log_file,
+        )
+        .unwrap();
+    }
 
     let casr_cmd = std::env::args().collect::<Vec<String>>().join(" ");
 
@@ -206,8 +290,6 @@ fn main() -> Result<()> {
         .unwrap_or("0")
         .parse::<i32>()
         .unwrap_or(-1);
-    let uid = *matches.get_one::<u32>("uid").unwrap();
-    let gid = *matches.get_one::<u32>("gid").unwrap();
     let mut file_name_to_save = matches
         .get_one::<String>("executable")
         .unwrap()
@@ -249,9 +331,10 @@ fn main() -> Result<()> {
     }
 
     // Drop privileges.
+    #[cfg(unix)]
     unsafe {
-        libc::setuid(uid);
-        libc::setgid(gid);
+        libc::setuid(*matches.get_one::<u32>("uid").unwrap());
+        libc::setgid(*matches.get_one::<u32>("gid").unwrap());
     }
 
     if culimit == 0 {
@@ -284,7 +367,7 @@ fn main() -> Result<()> {
         File::create(&core_path)?.write_all(&core)?;
         core
     };
-    let result = analyze_coredump(&mut report, &core, &core_path);
+    let result = analyze_coredump(&mut report, &core, &core_path, None, debugger_timeout);
     if result.is_err() {
         error!(
             "Coredump analysis error: {}. Casr command line: {}",
@@ -321,177 +404,458 @@ fn check_lock() -> Result<File> {
         .create(true)
         .write(true)
         .open(project_dir)?;
-    let fd = file.as_raw_fd();
-    flock(fd, FlockArg::LockExclusive).unwrap();
+    #[cfg(unix)]
+    flock(file.as_raw_fd(), FlockArg::LockExclusive).unwrap();
     Ok(file)
 }
 
-/// Analyze coredump and put information to report.
+/// Analyze core file in offline mode
 ///
 /// # Arguments
 ///
-/// * `report` - Crash report.
+/// * `core_path` - path to core file
 ///
-/// * `core` - binary coredump.
+/// * `executable` - path to executable, if None it is found by executable path from core
 ///
-/// * `core_path` - path to core file.
-fn analyze_coredump(
-    report: &mut CrashReport,
-    core: &[u8],
+/// * `executable_map` - map from executable path in core to local executable path
+///
+/// * `input` - crashing input to refine execution class
+///
+/// * `debugger_timeout` - gdb timeout (in seconds)
+///
+/// # Return value
+///
+/// CASR report
+fn analyze_core_file(
     core_path: &Path,
-) -> libcasr::error::Result<()> {
-    let mut machine = MachineInfo {
-        arch: header::EM_X86_64,
-        endianness: Endian::Little,
-        byte_width: 8,
-    };
-
-    // Parse coredump.
-    let elf = Elf::parse(core)?;
-
-    // Type should be CORE.
-    if elf.header.e_type != header::ET_CORE {
-        return Err(Error::Casr("Core should be an ELF file.".to_string()));
+    executable: Option<&PathBuf>,
+    executable_map: &HashMap<String, PathBuf>,
+    input: Option<&[u8]>,
+    debugger_timeout: u64,
+) -> Result<CrashReport> {
+    if !core_path.exists() {
+        bail!("{} doesn't exist", core_path.display());
     }
 
-    let notes_iter = elf.iter_note_headers(core);
+    let mut core: Vec<u8> = Vec::new();
+    let mut file = File::open(core_path)
+        .with_context(|| format!("Couldn't open core: {}", core_path.display()))?;
+    file.read_to_end(&mut core)
+        .with_context(|| format!("Couldn't read core: {}", core_path.display()))?;
+    // Decompress core (e.g. stored by systemd-coredump).
+    let decompressed = decompress_core(core_path, &core)?;
+    let core_path: &Path = if let Some(path) = &decompressed {
+        core = std::fs::read(path)
+            .with_context(|| format!("Couldn't read core: {}", path.display()))?;
+        path
+    } else {
+        core_path
+    };
+    let mut report = CrashReport::new();
 
-    if notes_iter.is_none() {
-        return Err(Error::Casr(
-            "Notes section are empty in coredump.".to_string(),
-        ));
+    if is_minidump(&core) {
+        if let Some(executable_path) = executable {
+            report.executable_path = executable_path.to_str().unwrap().to_string();
+        }
+        analyze_minidump(&mut report, &core)
+            .with_context(|| format!("Minidump analysis error: {}", core_path.display()))?;
+        return Ok(report);
+    }
+    if is_mac_crash(&core) {
+        if let Some(executable_path) = executable {
+            report.executable_path = executable_path.to_str().unwrap().to_string();
+        }
+        analyze_mac_crash(&mut report, &core)
+            .with_context(|| format!("Crash report analysis error: {}", core_path.display()))?;
+        return Ok(report);
     }
 
-    match elf.header.e_ident[4] {
-        1 => machine.byte_width = 4,
-        2 => machine.byte_width = 8,
-        _ => {
-            return Err(Error::Casr(format!(
-                "Couldn't determine byte_width: {}",
-                elf.header.e_ident[4]
-            )))
+    let executable = executable.cloned().or_else(|| {
+        let name = core_executable(&core)?;
+        let path = executable_map
+            .get(&name)
+            .cloned()
+            .unwrap_or_else(|| PathBuf::from(&name));
+        if path.exists() {
+            Some(path)
+        } else {
+            warn!(
+                "Couldn't find executable {} for core {}",
+                path.display(),
+                core_path.display()
+            );
+            None
         }
+    });
+    if let Some(executable_path) = executable {
+        report
+            .executable_path
+            .push_str(executable_path.to_str().unwrap());
     }
 
-    if let Ok(endianness) = elf.header.endianness() {
-        machine.endianness = endianness;
-    } else {
-        return Err(Error::Casr("Couldn't get endianness from core".to_string()));
+    if let Err(error) = analyze_coredump(&mut report, &core, core_path, input, debugger_timeout) {
+        bail!("Coredump analysis error: {}", error);
     }
+    Ok(report)
+}
 
-    match elf.header.e_machine {
-        header::EM_386
-        | header::EM_ARM
-        | header::EM_X86_64
-        | header::EM_AARCH64
-        | header::EM_RISCV => machine.arch = elf.header.e_machine,
-        _ => {
-            return Err(Error::Casr(format!(
-                "Unsupported architecture: {}",
-                elf.header.e_machine
-            )))
-        }
+/// Analyze all core files from directory in parallel
+///
+/// # Arguments
+///
+/// * `input_dir` - directory with core files
+///
+/// * `output_dir` - directory to save reports
+///
+/// * `executable` - path to executable for all core files
+///
+/// * `executable_map` - map from executable path in core to local executable path
+///
+/// * `jobs` - number of parallel jobs
+///
+/// * `debugger_timeout` - gdb timeout (in seconds)
+fn analyze_core_dir(
+    input_dir: &Path,
+    output_dir: &Path,
+    executable: Option<&PathBuf>,
+    executable_map: &HashMap<String, PathBuf>,
+    jobs: usize,
+    debugger_timeout: u64,
+) -> Result<()> {
+    let mut cores: Vec<PathBuf> = std::fs::read_dir(input_dir)
+        .with_context(|| format!("Couldn't read directory: {}", input_dir.display()))?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file())
+        .collect();
+    cores.sort();
+    if cores.is_empty() {
+        bail!("No core files found in {}", input_dir.display());
+    }
+    if !output_dir.exists() {
+        std::fs::create_dir_all(output_dir).with_context(|| {
+            format!("Couldn't create output directory {}", output_dir.display())
+        })?;
     }
 
-    let notes_iter = notes_iter.unwrap();
-
-    for note in notes_iter.flatten() {
-        if note.n_type == note::NT_PRPSINFO {
-            // Get run command.
-            let mut run_line = String::new();
-            match machine.byte_width {
-                4 => {
-                    if note.desc.len() < 45 {
-                        warn!("Prpsinfo is less than 45 bytes.");
-                        break;
-                    }
-                    for b in &note.desc[44..note.desc.len()] {
-                        if *b != 0x0 {
-                            run_line.push(*b as char);
-                        } else {
-                            break;
-                        }
-                    }
-                }
-                8 => {
-                    if note.desc.len() < 57 {
-                        warn!("Prpsinfo is less than 57 bytes.");
-                        break;
-                    }
-                    for b in &note.desc[56..note.desc.len()] {
-                        if *b != 0x0 {
-                            run_line.push(*b as char);
-                        } else {
-                            break;
-                        }
+    let num_of_threads = jobs.min(cores.len()).max(1) + 1;
+    let custom_pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(num_of_threads)
+        .build()
+        .unwrap();
+
+    info!("Analyzing {} core files...", cores.len());
+    info!("Using {} threads", num_of_threads - 1);
+    let counter = RwLock::new(0_usize);
+    let total = cores.len();
+    let (analyzed, _): (usize, _) = custom_pool.join(
+        || {
+            cores
+                .par_iter()
+                .filter(|core_path| {
+                    let result = analyze_core_file(
+                        core_path,
+                        executable,
+                        executable_map,
+                        None,
+                        debugger_timeout,
+                    )
+                    .and_then(|report| {
+                        let report_path = output_dir.join(format!(
+                            "{}.casrep",
+                            core_path.file_name().unwrap().to_string_lossy()
+                        ));
+                        std::fs::write(&report_path, serde_json::to_string_pretty(&report).unwrap())
+                            .with_context(|| {
+                                format!("Couldn't write report: {}", report_path.display())
+                            })
+                    });
+                    *counter.write().unwrap() += 1;
+                    if let Err(error) = &result {
+                        warn!("{}: {}", core_path.display(), error);
                     }
-                }
-                _ => {}
-            };
+                    result.is_ok()
+                })
+                .count()
+        },
+        || util::log_progress(&counter, total),
+    );
+    info!("Created {} reports from {} core files", analyzed, total);
 
-            if report.proc_cmdline.is_empty() {
-                report.proc_cmdline = run_line.clone();
-            }
+    Ok(())
+}
+
+/// Read map from executable paths in core files to local executable paths
+///
+/// # Arguments
+///
+/// * `path` - path to map file with lines "<executable path in core> <local executable path>"
+///
+/// # Return value
+///
+/// Map from executable path in core to local executable path
+fn read_executable_map(path: &Path) -> Result<HashMap<String, PathBuf>> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Couldn't read executable map: {}", path.display()))?;
+    let mut map = HashMap::new();
+    for line in content.lines().map(|l| l.trim()) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
         }
+        let Some((core_name, local_path)) = line.split_once(char::is_whitespace) else {
+            bail!("Malformed line in executable map: {}", line);
+        };
+        map.insert(core_name.to_string(), PathBuf::from(local_path.trim()));
     }
+    Ok(map)
+}
 
-    let result = GdbCommand::new(&ExecType::Core {
-        target: &report.executable_path,
-        core: core_path.to_str().unwrap(),
-    })
-    .bt()
-    .siginfo()
-    .mappings()
-    .regs()
-    // We need 2 disassembles: one for severity analysis
-    // and another for the report.
-    .mem("$pc", 64)
-    .disassembly()
-    .launch()?;
-
-    report.stacktrace = result[0].split('\n').map(|x| x.to_string()).collect();
-    if report.proc_maps.is_empty() {
-        report.proc_maps = result[2]
-            .split('\n')
-            .skip(3)
-            .map(|x| x.to_string())
-            .collect();
-    }
+/// Get executable path from NT_FILE note of core file
+///
+/// # Arguments
+///
+/// * `core` - core file content
+///
+/// # Return value
+///
+/// Path of the first mapped file (executable is mapped at the lowest address)
+fn core_executable(core: &[u8]) -> Option<String> {
+    ElfCore::parse(core)
+        .ok()?
+        .mappings
+        .first()
+        .map(|file| file.name.clone())
+        .filter(|name| !name.is_empty())
+}
 
-    let mut context = GdbContext {
-        siginfo: Siginfo::from_gdb(&result[1])?,
-        mappings: MappedFiles::from_gdb(&result[2])?,
-        registers: Registers::from_gdb(&result[3])?,
-        pc_memory: MemoryObject::from_gdb(&result[4])?,
-        machine,
-        stacktrace: report.stacktrace.clone(),
+/// Decompress core file if it is compressed with zstd, lz4, or xz
+///
+/// # Arguments
+///
+/// * `core_path` - path to core file
+///
+/// * `core` - core file content
+///
+/// # Return value
+///
+/// Temporary decompressed core file that is removed when dropped, None if
+/// core isn't compressed
+fn decompress_core(core_path: &Path, core: &[u8]) -> Result<Option<TempPath>> {
+    let tool = if core.starts_with(&[0x28, 0xb5, 0x2f, 0xfd]) {
+        "zstd"
+    } else if core.starts_with(&[0x04, 0x22, 0x4d, 0x18]) {
+        "lz4"
+    } else if core.starts_with(&[0xfd, 0x37, 0x7a, 0x58, 0x5a, 0x00]) {
+        "xz"
+    } else {
+        return Ok(None);
     };
+    debug!("Decompressing {} core with {}", core_path.display(), tool);
+    let output = Command::new(tool)
+        .args(["-d", "-c"])
+        .arg(core_path)
+        .output()
+        .with_context(|| format!("Couldn't launch {tool} to decompress core"))?;
+    if !output.status.success() {
+        bail!(
+            "Couldn't decompress core {}: {}",
+            core_path.display(),
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+    // File is created exclusively with random name, so that cores with the
+    // same name don't collide.
+    let mut file = tempfile::Builder::new()
+        .prefix("casr-core-")
+        .suffix(".core")
+        .tempfile()
+        .with_context(|| "Couldn't create temporary file for decompressed core")?;
+    file.write_all(&output.stdout).with_context(|| {
+        format!(
+            "Couldn't write decompressed core: {}",
+            file.path().display()
+        )
+    })?;
+    Ok(Some(file.into_temp_path()))
+}
 
-    report.set_disassembly(&result[5]);
+/// Replace executable path in memory mappings
+///
+/// # Arguments
+///
+/// * `mappings` - memory mappings
+///
+/// * `executable_path` - local executable path
+fn replace_executable(mappings: &MappedFiles, executable_path: &str) -> MappedFiles {
+    let path = PathBuf::from(executable_path);
+    let name = path.file_name().unwrap().to_str().unwrap();
+    mappings
+        .iter()
+        .cloned()
+        .map(|f| {
+            if f.name.contains(name) {
+                gdb_command::mappings::File::new(f.start, f.end, f.offset, executable_path)
+            } else {
+                f
+            }
+        })
+        .collect()
+}
 
+/// Analyze coredump and put information to report.
+///
+/// # Arguments
+///
+/// * `report` - Crash report.
+///
+/// * `core` - binary coredump.
+///
+/// * `core_path` - path to core file.
+///
+/// * `input` - crashing input to refine execution class.
+///
+/// * `debugger_timeout` - gdb timeout (in seconds).
+fn analyze_coredump(
+    report: &mut CrashReport,
+    core: &[u8],
+    core_path: &Path,
+    input: Option<&[u8]>,
+    debugger_timeout: u64,
+) -> libcasr::error::Result<()> {
+    // Parse coredump notes: registers, signal, and mapped files of crashed
+    // thread. Gdb is needed only for symbolized stack traces then.
+    let mut elf_core = ElfCore::parse(core).map_err(|error| Error::Casr(error.to_string()))?;
+    let machine = elf_core.machine.clone();
+    if report.proc_cmdline.is_empty() {
+        if let Some(cmdline) = elf_core.cmdline.take() {
+            report.proc_cmdline = cmdline;
+        }
+    }
     // Set executable path from user.
     if !report.executable_path.is_empty() {
-        let path = PathBuf::from(report.executable_path.clone());
-        // Change executable path in mfiles.
-        let name = path.file_name().unwrap().to_str().unwrap();
-        context.mappings = context
-            .mappings
-            .iter()
-            .cloned()
-            .map(|f| {
-                if f.name.contains(name) {
-                    gdb_command::mappings::File::new(
-                        f.start,
-                        f.end,
-                        f.offset,
-                        &report.executable_path,
-                    )
-                } else {
-                    f
+        elf_core.mappings = replace_executable(&elf_core.mappings, &report.executable_path);
+    }
+    let native = elf_core.siginfo.is_some() && !elf_core.registers.is_empty();
+
+    let exectype = ExecType::Core {
+        target: &report.executable_path,
+        core: core_path.to_str().unwrap(),
+    };
+    let mut gdb_command = GdbCommand::new(&exectype);
+    gdb_command.bt().ex("thread apply all bt").disassembly();
+    if !native {
+        gdb_command
+            .siginfo()
+            .mappings()
+            .regs()
+            // We need 2 disassembles: one for severity analysis
+            // and another for the report.
+            .mem("$pc", 64);
+    }
+    let gdb_error = |error: anyhow::Error| Error::Casr(error.to_string());
+    let result = match util::gdb_output(&gdb_command, debugger_timeout) {
+        Ok(Some(output)) => Some(
+            gdb_command
+                .parse(String::from_utf8_lossy(&output))
+                .map_err(gdb_error)?,
+        ),
+        Ok(None) => {
+            // Retry to get stack trace only.
+            let mut gdb_command = GdbCommand::new(&exectype);
+            gdb_command.bt();
+            match util::gdb_output(&gdb_command, debugger_timeout).map_err(gdb_error)? {
+                Some(output) => {
+                    let result = gdb_command
+                        .parse(String::from_utf8_lossy(&output))
+                        .map_err(gdb_error)?;
+                    report.stacktrace = result[0].split('\n').map(|x| x.to_string()).collect();
                 }
-            })
-            .collect();
+                None if native => report.stacktrace = elf_core.stacktrace(),
+                None => return Err(Error::Casr(format!("gdb timeout ({debugger_timeout} s)"))),
+            }
+            if !native {
+                report.partial_data =
+                    format!("gdb timeout ({debugger_timeout} s), only stack trace is collected");
+                return Ok(());
+            }
+            report.partial_data = format!(
+                "gdb timeout ({debugger_timeout} s), threads and disassembly aren't collected"
+            );
+            None
+        }
+        Err(error) if native => {
+            warn!("Couldn't get stack trace from gdb. {error}");
+            report.stacktrace = elf_core.stacktrace();
+            report.partial_data =
+                "gdb is unavailable, stack trace is unwound by frame pointers".to_string();
+            None
+        }
+        Err(error) => return Err(gdb_error(error)),
+    };
+
+    if let Some(result) = &result {
+        report.stacktrace = result[0].split('\n').map(|x| x.to_string()).collect();
+        // Stack traces of all threads are saved only for multi-threaded programs.
+        let threads = extract_threads(&result[1], &report.stacktrace);
+        if threads.len() > 1 {
+            report.threads = threads;
+        }
+        report.set_disassembly(&result[2]);
     }
+    if report.proc_maps.is_empty() {
+        report.proc_maps = match &result {
+            Some(result) if !native => result[4]
+                .split('\n')
+                .skip(3)
+                .map(|x| x.to_string())
+                .collect(),
+            _ => util::proc_maps(&elf_core.mappings),
+        };
+    }
+    report.symbolize_stacktrace();
+
+    let context = match (elf_core.siginfo.take(), &result) {
+        (Some(siginfo), _) if native => {
+            let pc = match machine.arch {
+                header::EM_X86_64 => "rip",
+                header::EM_386 => "eip",
+                _ => "pc",
+            };
+            let pc_memory = elf_core
+                .registers
+                .get(pc)
+                .and_then(|pc| elf_core.memory(*pc, 64))
+                .unwrap_or_default();
+            GdbContext {
+                siginfo,
+                mappings: elf_core.mappings,
+                registers: elf_core.registers,
+                pc_memory,
+                machine,
+                stacktrace: report.stacktrace.clone(),
+            }
+        }
+        (_, Some(result)) => {
+            let mut context = GdbContext {
+                siginfo: Siginfo::from_gdb(&result[3])?,
+                mappings: MappedFiles::from_gdb(&result[4])?,
+                registers: Registers::from_gdb(&result[5])?,
+                pc_memory: MemoryObject::from_gdb(&result[6])?,
+                machine,
+                stacktrace: report.stacktrace.clone(),
+            };
+            // Set executable path from user.
+            if !report.executable_path.is_empty() {
+                context.mappings = replace_executable(&context.mappings, &report.executable_path);
+            }
+            context
+        }
+        _ => {
+            return Err(Error::Casr(
+                "Couldn't get crash context from core".to_string(),
+            ))
+        }
+    };
 
     let severity = context.severity();
 
@@ -500,6 +864,19 @@ fn analyze_coredump(
     } else {
         warn!("Couldn't estimate severity. {}", severity.err().unwrap());
     }
+    if let Some(input) = input {
+        report.input_registers = context
+            .input_registers(input)
+            .iter()
+            .map(|(name, value)| format!("{name}: 0x{value:x}"))
+            .collect();
+        if let Ok(class) = context.refine_with_input(report.execution_class.clone(), input) {
+            report.execution_class = class;
+        }
+    }
+    report.severity_score = report.execution_class.score();
+    report.set_frames();
+    report.set_inline_frames();
 
     report.registers = context.registers;
 
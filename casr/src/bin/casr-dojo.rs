@@ -1,3 +1,4 @@
+use casr::config;
 use casr::util;
 use libcasr::{init_ignored_frames, report::CrashReport, stacktrace::*};
 
@@ -267,6 +268,12 @@ impl DefectDojoClient {
             "severity".to_string(),
             serde_json::Value::String(severity.to_string()),
         );
+        if report.severity_score > 0.0 {
+            finding.insert(
+                "cvssv3_score".to_string(),
+                serde_json::Value::from(report.severity_score),
+            );
+        }
         let mut reproduce = report.proc_cmdline.clone();
         if !report.stdin.is_empty() {
             reproduce += &format!(" < {}", report.stdin);
@@ -466,7 +473,7 @@ fn compute_report_hash(report: &CrashReport, name: &str) -> Result<ReportHash> {
     if report.stacktrace.is_empty() {
         bail!("Empty stack trace for CASR report {}", name);
     }
-    let stacktrace = report.filtered_stacktrace();
+    let stacktrace = report.normalized_stacktrace();
     if let Err(e) = stacktrace {
         bail!(
             "Failed to parse stack trace for CASR report {}: {}",
@@ -554,6 +561,9 @@ fn get_report_description(
 
 #[tokio::main(flavor = "current_thread")]
 async fn main() -> Result<()> {
+    // Load configuration files.
+    config::load_config("casr-dojo")?;
+
     let options = clap::Command::new("casr-dojo")
         .version(clap::crate_version!())
         .about("Tool for uploading new and unique CASR reports to DefectDojo")
@@ -561,6 +571,7 @@ async fn main() -> Result<()> {
         .arg(
             Arg::new("log-level")
                 .long("log-level")
+                .env("CASR_LOG_LEVEL")
                 .short('l')
                 .action(ArgAction::Set)
                 .default_value("info")
@@ -590,31 +601,33 @@ async fn main() -> Result<()> {
             Arg::new("input")
                 .short('i')
                 .long("input")
-                .action(ArgAction::Set)
-                .value_name("INPUT_DIR")
+                .action(ArgAction::Append)
+                .value_name("INPUT")
                 .required(true)
-                .help("Directory that is recursively searched for CASR reports (also, crash seeds and CASR GDB reports if they are present)")
+                .help("Directory that is recursively searched for CASR reports (also, crash seeds and CASR GDB reports if they are present), or selected CASR report. Option may be specified several times")
                 .value_parser(move |arg: &str| {
-                    let i_dir = Path::new(arg);
-                    if !i_dir.exists() {
+                    let input = Path::new(arg);
+                    if !input.exists() {
                         let mut err = clap::Error::new(ErrorKind::ValueValidation);
                         err.insert(
                             ContextKind::InvalidValue,
                             ContextValue::String(
-                                "Directory with CASR reports doesn't exist.".to_owned(),
+                                "Directory or file with CASR reports doesn't exist.".to_owned(),
                             ),
                         );
                         return Err(err);
                     }
-                    if !i_dir.is_dir() {
+                    if !input.is_dir() && input.extension().map_or(true, |ext| ext != "casrep") {
                         let mut err = clap::Error::new(ErrorKind::ValueValidation);
                         err.insert(
                             ContextKind::InvalidValue,
-                            ContextValue::String("Input path should be a directory.".to_owned()),
+                            ContextValue::String(
+                                "Input path should be a directory or .casrep file.".to_owned(),
+                            ),
                         );
                         return Err(err);
                     }
-                    Ok(i_dir.to_owned())
+                    Ok(input.to_owned())
                 }),
         )
         .arg(
@@ -625,7 +638,9 @@ async fn main() -> Result<()> {
                 .value_parser(clap::value_parser!(PathBuf))
                 .help("TOML file with parameters for DefectDojo product, engagement, and test"),
         )
+        .args(util::logging_args())
         .get_matches();
+    config::clear_env();
 
     // Init stack trace filtering.
     init_ignored_frames!("cpp", "rust", "python", "go");
@@ -634,24 +649,34 @@ async fn main() -> Result<()> {
     util::initialize_logging(&options);
 
     // Get new CASR reports.
-    let mut new_casr_reports: Vec<(PathBuf, CrashReport)> =
-        WalkDir::new(options.get_one::<PathBuf>("input").unwrap())
-            .into_iter()
-            .flatten()
-            .map(|e| e.into_path())
-            .filter(|e| e.is_file())
-            .filter(|e| e.parent().unwrap().file_name().unwrap() != "clerr")
-            .filter(|e| e.extension().is_some() && e.extension().unwrap() == "casrep")
-            .filter(|e| {
-                !e.to_str().unwrap().ends_with(".gdb.casrep")
-                    || !e.with_extension("").with_extension("casrep").exists()
-            })
-            .map(|e| {
-                let r = util::report_from_file(e.as_path())?;
-                Ok((e, r))
-            })
-            .collect::<Result<Vec<_>>>()?;
+    // Selected reports are uploaded as is, directories are searched recursively.
+    let mut new_casr_reports: Vec<(PathBuf, CrashReport)> = options
+        .get_many::<PathBuf>("input")
+        .unwrap()
+        .flat_map(|input| {
+            if input.is_file() {
+                return vec![input.clone()];
+            }
+            WalkDir::new(input)
+                .into_iter()
+                .flatten()
+                .map(|e| e.into_path())
+                .filter(|e| e.is_file())
+                .filter(|e| e.parent().unwrap().file_name().unwrap() != "clerr")
+                .filter(|e| e.extension().is_some() && e.extension().unwrap() == "casrep")
+                .filter(|e| {
+                    !e.to_str().unwrap().ends_with(".gdb.casrep")
+                        || !e.with_extension("").with_extension("casrep").exists()
+                })
+                .collect()
+        })
+        .map(|e| {
+            let r = util::report_from_file(e.as_path())?;
+            Ok((e, r))
+        })
+        .collect::<Result<Vec<_>>>()?;
     new_casr_reports.sort_by(|(e1, _), (e2, _)| e1.cmp(e2));
+    new_casr_reports.dedup_by(|(e1, _), (e2, _)| e1 == e2);
 
     // Create DefectDojo client.
     let client = DefectDojoClient::new(
@@ -1,9 +1,14 @@
+use casr::config;
+use casr::debugger::{self, Debugger};
+use casr::gdb::GdbCommand;
+use casr::lldb;
+use casr::qemu::{self, QemuUser};
+use casr::sandbox::{self, Sandbox};
 use casr::util;
 use libcasr::{
     cpp::CppException,
     exception::Exception,
-    gdb::exploitable::{GdbContext, MachineInfo},
-    gdb::GdbStacktrace,
+    gdb::{exploitable::GdbContext, GdbStacktrace},
     init_ignored_frames,
     report::CrashReport,
     rust::RustPanic,
@@ -19,14 +24,40 @@ use gdb_command::registers::*;
 use gdb_command::siginfo::Siginfo;
 use gdb_command::stacktrace::StacktraceExt;
 use gdb_command::*;
-use goblin::container::Endian;
-use goblin::elf::{header, Elf};
+use log::{debug, warn};
 use regex::Regex;
-use std::fs::File;
-use std::io::prelude::*;
 use std::path::PathBuf;
 
+/// Extract target output from gdb output. Target stdout and stderr are
+/// interleaved with gdb messages until target is stopped.
+///
+/// # Arguments
+///
+/// * `output` - gdb output
+///
+/// # Return value
+///
+/// Target output lines
+fn target_output(output: &str) -> Vec<String> {
+    let gdb_message = Regex::new(
+        r#"^(\[(New |Thread |Inferior |Detaching )|Using host libthread_db|warning: Error disabling address space randomization|\$[0-9]+ = ")"#,
+    )
+    .unwrap();
+    output
+        .lines()
+        .take_while(|line| {
+            !line.starts_with("Program received signal")
+                && !line.starts_with("Program terminated with signal")
+        })
+        .filter(|line| !gdb_message.is_match(line))
+        .map(|line| line.to_string())
+        .collect()
+}
+
 fn main() -> Result<()> {
+    // Load configuration files.
+    config::load_config("casr-gdb")?;
+
     let matches = clap::Command::new("casr-gdb")
         .version(clap::crate_version!())
         .about("Create CASR reports (.casrep) from gdb execution")
@@ -65,6 +96,7 @@ fn main() -> Result<()> {
             Arg::new("timeout")
                 .short('t')
                 .long("timeout")
+                .env("CASR_TIMEOUT")
                 .action(ArgAction::Set)
                 .default_value("0")
                 .value_name("SECONDS")
@@ -74,6 +106,7 @@ fn main() -> Result<()> {
         .arg(
             Arg::new("ignore")
                 .long("ignore")
+                .env("CASR_IGNORE")
                 .action(ArgAction::Set)
                 .value_name("FILE")
                 .value_parser(clap::value_parser!(PathBuf))
@@ -86,7 +119,38 @@ fn main() -> Result<()> {
                 .last(true)
                 .help("Add \"-- ./binary <arguments>\" to run executable"),
         )
+        .args(util::target_env_args())
+        .args(qemu::qemu_args())
+        .arg(
+            Arg::new("retries")
+                .long("retries")
+                .env("CASR_RETRIES")
+                .action(ArgAction::Set)
+                .default_value("0")
+                .value_name("N")
+                .value_parser(clap::value_parser!(u32))
+                .help("Re-run target up to N times if it doesn't crash (e.g., flaky crashes), report contains observed crash probability"),
+        )
+        .arg(
+            Arg::new("toggle-aslr")
+                .long("toggle-aslr")
+                .action(ArgAction::SetTrue)
+                .help("Toggle ASLR on every second run with --retries"),
+        )
+        .arg(util::aslr_arg())
+        .args(sandbox::sandbox_args())
+        .arg(debugger::debugger_arg())
+        .arg(util::debugger_timeout_arg())
+        .arg(util::near_null_arg())
+        .args(util::input_args())
+        .args(util::source_args())
+        .args(util::logging_args())
         .get_matches();
+    config::clear_env();
+
+    // Init log.
+    util::initialize_logging(&matches);
+    util::init_near_null_threshold(&matches);
 
     // Get program args.
     let argv: Vec<&str> = if let Some(argvs) = matches.get_many::<String>("ARGS") {
@@ -94,9 +158,17 @@ fn main() -> Result<()> {
     } else {
         bail!("Wrong arguments for starting program");
     };
+    if let Some(sandbox) = Sandbox::from_matches(&matches)? {
+        return sandbox.reexec(&matches);
+    }
 
     // Get timeout
     let timeout = *matches.get_one::<u64>("timeout").unwrap();
+    // Debugger is launched with target, so target timeout is added.
+    let debugger_timeout = match *matches.get_one::<u64>("debugger-timeout").unwrap() {
+        0 => 0,
+        debugger_timeout => debugger_timeout + timeout,
+    };
 
     init_ignored_frames!("cpp", "rust");
     if let Some(path) = matches.get_one::<PathBuf>("ignore") {
@@ -104,145 +176,206 @@ fn main() -> Result<()> {
     }
     // Get stdin for target program.
     let stdin_file = util::stdin_from_matches(&matches)?;
+    // Foreign architecture target is run under QEMU user-mode emulator.
+    let qemu = QemuUser::from_matches(&matches)?;
+    let target_env = util::TargetEnv::from_matches(&matches);
 
     let target_path = PathBuf::from(argv[0]);
     if !target_path.exists() {
         bail!("{} doesn't exist", target_path.to_str().unwrap());
     }
 
-    let mut header = vec![0u8; 64];
-    // The ELF header is 52 or 64 bytes long for 32-bit and 64-bit binaries respectively.
-    let mut file = File::open(&target_path)
-        .with_context(|| format!("Couldn't open target binary: {}", target_path.display()))?;
-    file.read_exact(&mut header).with_context(|| {
-        format!(
-            "Couldn't read target binary header: {}",
-            target_path.display()
-        )
-    })?;
-
     let mut report = CrashReport::new();
     report.executable_path = argv[0].to_string();
     report.proc_cmdline = argv.join(" ");
     let _ = report.add_os_info();
     let _ = report.add_proc_environ();
+    target_env.update_environ(&mut report.proc_environ);
     if let Some(mut file_path) = stdin_file.clone() {
         file_path = file_path.canonicalize().unwrap_or(file_path);
         report.stdin = file_path.display().to_string();
     }
-    let elf_h = Elf::parse_header(&header).with_context(|| {
-        format!(
-            "Couldn't header for target binary: {}",
-            target_path.display()
-        )
-    })?;
-
-    let mut machine = MachineInfo {
-        arch: header::EM_X86_64,
-        endianness: Endian::Little,
-        byte_width: 8,
-    };
-
-    // Type should be executable or shared object.
-    if elf_h.e_type != header::ET_EXEC && elf_h.e_type != header::ET_DYN {
-        bail!("Target binary type should be executable or shared object");
+    // ASLR is disabled by default like in gdb.
+    let aslr = util::aslr_from_matches(&matches).unwrap_or(false);
+    // gdb is unavailable on macOS, so target is run under lldb. On Linux
+    // lldb is used for targets that behave differently under gdb.
+    if Debugger::from_matches(&matches) == Debugger::Lldb {
+        if qemu.is_some() {
+            bail!("QEMU user mode is supported only with gdb");
+        }
+        let timeout = if debugger_timeout != 0 {
+            debugger_timeout
+        } else {
+            timeout
+        };
+        let stacktrace =
+            lldb::lldb_report(&mut report, &argv, &stdin_file, &target_env, timeout, aslr)?;
+        report.aslr = if aslr { "on" } else { "off" }.to_string();
+        if let Ok(crash_line) = stacktrace.crash_line() {
+            report.set_crash_line(crash_line);
+        }
+        return util::output_report(&report, &matches, &argv);
     }
+    let machine = util::machine_info(&target_path)?;
+    let exectype = ExecType::Local(argv.as_slice());
+    // Get gdb results (stack trace, siginfo, mappings, registers, memory at pc,
+    // and disassembly), whole output for exception parsing, and target output.
+    let run = |aslr: bool| -> Result<Option<(Vec<String>, String, Vec<String>)>> {
+        if let Some(qemu) = &qemu {
+            return Ok(qemu
+                .gdb_output(&argv, &stdin_file, debugger_timeout, |gdb_command| {
+                    gdb_command
+                        .bt()
+                        .siginfo()
+                        .mappings()
+                        .regs()
+                        .mem("$pc", 64)
+                        .disassembly();
+                })?
+                .map(|(result, output)| {
+                    let stdout = output.lines().map(|line| line.to_string()).collect();
+                    (result, output, stdout)
+                }));
+        }
+        let mut gdb_command = GdbCommand::new(&exectype);
+        gdb_command.timeout(timeout).stdin(&stdin_file);
+        let prefix =
+            target_env.set_gdb_env(&mut gdb_command) + util::set_gdb_aslr(&mut gdb_command, aslr);
+        let gdb_command = gdb_command
+            .r()
+            .bt()
+            .siginfo()
+            .mappings()
+            .regs()
+            // We need 2 disassembles: one for severity analysis
+            // and another for the report.
+            .mem("$pc", 64)
+            .disassembly();
 
-    match elf_h.e_ident[4] {
-        1 => machine.byte_width = 4,
-        2 => machine.byte_width = 8,
-        _ => {
-            bail!("Couldn't determine byte_width: {}", elf_h.e_ident[4]);
+        let stdout = util::gdb_output(gdb_command, debugger_timeout)
+            .with_context(|| "Unable to get results from gdb")?;
+        if let Some(stdout) = stdout {
+            let output = String::from_utf8_lossy(&stdout).to_string();
+            let mut result = gdb_command.parse(&output)?;
+            result.drain(..prefix);
+            let stdout = target_output(&output);
+            Ok(Some((result, output, stdout)))
+        } else {
+            Ok(None)
+        }
+    };
+    // Flaky crashes are reproduced by re-running target, ASLR is toggled on
+    // every second run with --toggle-aslr.
+    let retries = *matches.get_one::<u32>("retries").unwrap();
+    let toggle_aslr = matches.get_flag("toggle-aslr");
+    let mut runs = 0;
+    let mut run_aslr;
+    let results = loop {
+        runs += 1;
+        run_aslr = aslr != (toggle_aslr && runs % 2 == 0);
+        let results = run(run_aslr)?;
+        // Siginfo is empty if program terminated normally, gdb timeout is
+        // handled below.
+        let crashed = match &results {
+            Some((result, _, _)) => Siginfo::from_gdb(&result[1]).is_ok(),
+            None => true,
+        };
+        if crashed || runs > retries {
+            break results;
         }
+        debug!("Program terminated (no crash), retrying ({runs}/{retries})");
+    };
+    if retries != 0 {
+        report.crash_probability = Some(1.0 / runs as f64);
     }
-
-    if let Ok(endianness) = elf_h.endianness() {
-        machine.endianness = endianness;
-    } else {
-        bail!("Couldn't get endianness from target binary");
+    if qemu.is_none() {
+        report.aslr = if run_aslr { "on" } else { "off" }.to_string();
     }
 
-    match elf_h.e_machine {
-        header::EM_386
-        | header::EM_ARM
-        | header::EM_X86_64
-        | header::EM_AARCH64
-        | header::EM_RISCV => machine.arch = elf_h.e_machine,
-        _ => {
-            bail!("Unsupported architecture: {}", elf_h.e_machine);
-        }
-    }
-    let exectype = ExecType::Local(argv.as_slice());
-    let mut gdb_command = GdbCommand::new(&exectype);
-    let gdb_command = gdb_command
-        .timeout(timeout)
-        .stdin(&stdin_file)
-        .r()
-        .bt()
-        .siginfo()
-        .mappings()
-        .regs()
-        // We need 2 disassembles: one for severity analysis
-        // and another for the report.
-        .mem("$pc", 64)
-        .disassembly();
+    if let Some((result, output, stdout)) = results {
+        report.stacktrace = GdbStacktrace::extract_stacktrace(&result[0])?;
+        report.proc_maps = result[2]
+            .split('\n')
+            .skip(3)
+            .map(|x| x.to_string())
+            .collect();
 
-    let stdout = gdb_command
-        .raw()
-        .with_context(|| "Unable to get results from gdb")?;
+        report.stdout = stdout;
 
-    let output = String::from_utf8_lossy(&stdout);
+        let siginfo = Siginfo::from_gdb(&result[1]);
 
-    let result = gdb_command.parse(&output)?;
-    report.stacktrace = GdbStacktrace::extract_stacktrace(&result[0])?;
-    report.proc_maps = result[2]
-        .split('\n')
-        .skip(3)
-        .map(|x| x.to_string())
-        .collect();
+        if let Err(error) = siginfo {
+            let err_str = error.to_string();
+            let re = Regex::new(r"\$\d+ = (0x0|void) doesn't match regex template").unwrap();
+            if err_str.contains(":  doesn't match") || re.is_match(&err_str) {
+                // Normal termination.
+                bail!("Program terminated (no crash)");
+            } else {
+                return Err(error.into());
+            }
+        }
 
-    let siginfo = Siginfo::from_gdb(&result[1]);
+        let siginfo = siginfo.unwrap();
+        report.signal = Some(siginfo.si_signo as i32);
+        let context = GdbContext {
+            siginfo,
+            // QEMU gdb stub may not provide process mappings.
+            mappings: match MappedFiles::from_gdb(&result[2]) {
+                Err(_) if qemu.is_some() => MappedFiles::new(),
+                mappings => mappings?,
+            },
+            registers: Registers::from_gdb(&result[3])?,
+            pc_memory: MemoryObject::from_gdb(&result[4])?,
+            machine,
+            stacktrace: report.stacktrace.clone(),
+        };
 
-    if let Err(error) = siginfo {
-        let err_str = error.to_string();
-        let re = Regex::new(r"\$\d+ = (0x0|void) doesn't match regex template").unwrap();
-        if err_str.contains(":  doesn't match") || re.is_match(&err_str) {
-            // Normal termination.
-            bail!("Program terminated (no crash)");
-        } else {
-            return Err(error.into());
-        }
-    }
+        report.set_disassembly(&result[5]);
 
-    let context = GdbContext {
-        siginfo: siginfo.unwrap(),
-        mappings: MappedFiles::from_gdb(&result[2])?,
-        registers: Registers::from_gdb(&result[3])?,
-        pc_memory: MemoryObject::from_gdb(&result[4])?,
-        machine,
-        stacktrace: report.stacktrace.clone(),
-    };
+        let severity = context.severity();
 
-    report.set_disassembly(&result[5]);
+        if let Ok(severity) = severity {
+            report.execution_class = severity;
+        } else {
+            warn!("Couldn't estimate severity. {}", severity.err().unwrap());
+        }
 
-    let severity = context.severity();
+        // Check for exceptions
+        if let Some(class) = [CppException::parse_exception, RustPanic::parse_exception]
+            .iter()
+            .find_map(|parse| parse(&output))
+        {
+            report.execution_class = class;
+        }
 
-    if let Ok(severity) = severity {
-        report.execution_class = severity;
+        report.registers = context.registers;
     } else {
-        eprintln!("Couldn't estimate severity. {}", severity.err().unwrap());
-    }
-
-    // Check for exceptions
-    if let Some(class) = [CppException::parse_exception, RustPanic::parse_exception]
-        .iter()
-        .find_map(|parse| parse(&output))
-    {
-        report.execution_class = class;
+        // Retry to get stack trace only.
+        let result = if let Some(qemu) = &qemu {
+            qemu.gdb_output(&argv, &stdin_file, debugger_timeout, |gdb_command| {
+                gdb_command.bt();
+            })?
+            .map(|(result, _)| result)
+        } else {
+            let mut gdb_command = GdbCommand::new(&exectype);
+            gdb_command.timeout(timeout).stdin(&stdin_file);
+            let env_commands = target_env.set_gdb_env(&mut gdb_command);
+            let gdb_command = gdb_command.r().bt();
+            util::gdb_output(gdb_command, debugger_timeout)
+                .with_context(|| "Unable to get results from gdb")?
+                .map(|stdout| gdb_command.parse(String::from_utf8_lossy(&stdout)))
+                .transpose()?
+                .map(|mut result| result.split_off(env_commands))
+        };
+        let Some(result) = result else {
+            bail!("gdb timeout ({} s)", debugger_timeout);
+        };
+        report.stacktrace = GdbStacktrace::extract_stacktrace(&result[0])?;
+        report.partial_data =
+            format!("gdb timeout ({debugger_timeout} s), only stack trace is collected");
     }
 
-    report.registers = context.registers;
-
+    report.symbolize_stacktrace();
     let mut parsed_stacktrace = GdbStacktrace::parse_stacktrace(&report.stacktrace)?;
     if let Ok(mfiles) = MappedFiles::from_gdb(report.proc_maps.join("\n")) {
         parsed_stacktrace.compute_module_offsets(&mfiles);
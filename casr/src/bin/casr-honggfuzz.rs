@@ -0,0 +1,286 @@
+use casr::config;
+use casr::known_bugs::known_bugs_arg;
+use casr::triage::{fuzzing_crash_triage_pipeline, CrashInfo};
+use casr::util;
+
+use anyhow::{bail, Result};
+use clap::{
+    error::{ContextKind, ContextValue, ErrorKind},
+    Arg, ArgAction,
+};
+use log::{debug, warn};
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Honggfuzz report file name.
+const REPORT_NAME: &str = "HONGGFUZZ.REPORT.TXT";
+
+/// Honggfuzz sanitizer log file name prefix.
+const SANITIZER_LOG_NAME: &str = "HF.sanitizer.log";
+
+/// Information from honggfuzz report.
+#[derive(Debug, Default)]
+struct HonggfuzzReport {
+    /// Target command line (fuzzTarget).
+    target_args: Vec<String>,
+    /// Input is passed to target via stdin (fuzzStdin).
+    stdin: bool,
+    /// Crash file names and PIDs of crashed processes.
+    crashes: HashMap<String, String>,
+}
+
+impl HonggfuzzReport {
+    /// Parse honggfuzz report. Report may contain several fuzzing sessions,
+    /// the last target command line is used.
+    ///
+    /// # Arguments
+    ///
+    /// * `report` - report file content
+    fn parse(report: &str) -> Self {
+        let mut hf_report = HonggfuzzReport::default();
+        let mut fname = None;
+        for line in report.lines() {
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            let value = value.trim();
+            match key.trim() {
+                "fuzzTarget" => {
+                    hf_report.target_args =
+                        value.split_whitespace().map(|s| s.to_string()).collect()
+                }
+                "fuzzStdin" => hf_report.stdin = value == "TRUE",
+                "FUZZ_FNAME" => {
+                    fname = Path::new(value)
+                        .file_name()
+                        .map(|name| name.to_string_lossy().to_string());
+                    if let Some(fname) = &fname {
+                        hf_report.crashes.insert(fname.clone(), String::new());
+                    }
+                }
+                "PID" => {
+                    if let Some(fname) = fname.take() {
+                        hf_report.crashes.insert(fname, value.to_string());
+                    }
+                }
+                _ => {}
+            }
+        }
+        hf_report
+    }
+}
+
+fn main() -> Result<()> {
+    // Load configuration files.
+    config::load_config("casr-honggfuzz")?;
+
+    let matches = clap::Command::new("casr-honggfuzz")
+        .version(clap::crate_version!())
+        .about("Triage crashes found by Honggfuzz")
+        .term_width(90)
+        .arg(
+            Arg::new("log-level")
+                .long("log-level")
+                .env("CASR_LOG_LEVEL")
+                .short('l')
+                .action(ArgAction::Set)
+                .default_value("info")
+                .value_parser(["info", "debug"])
+                .help("Logging level")
+        )
+        .arg(Arg::new("jobs")
+            .long("jobs")
+            .env("CASR_JOBS")
+            .short('j')
+            .action(ArgAction::Set)
+            .help("Number of parallel jobs for generating CASR reports [default: half of cpu cores]")
+            .value_parser(clap::value_parser!(u32).range(1..)))
+        .arg(
+            Arg::new("timeout")
+                .short('t')
+                .long("timeout")
+                .env("CASR_TIMEOUT")
+                .action(ArgAction::Set)
+                .default_value("0")
+                .value_name("SECONDS")
+                .help("Timeout (in seconds) for target execution, 0 value means that timeout is disabled")
+                .value_parser(clap::value_parser!(u64).range(0..))
+        )
+        .arg(
+            Arg::new("input")
+                .short('i')
+                .long("input")
+                .action(ArgAction::Set)
+                .value_name("INPUT_DIR")
+                .required(true)
+                .help("Honggfuzz workspace directory with crashes, HONGGFUZZ.REPORT.TXT, and HF.sanitizer.log files")
+                .value_parser(move |arg: &str| {
+                    let i_dir = Path::new(arg);
+                    if !i_dir.exists() {
+                        let mut err = clap::Error::new(ErrorKind::ValueValidation);
+                        err.insert(ContextKind::InvalidValue, ContextValue::String("Input directory doesn't exist.".to_owned()));
+                        return Err(err);
+                    }
+                    if !i_dir.is_dir() {
+                        let mut err = clap::Error::new(ErrorKind::ValueValidation);
+                        err.insert(ContextKind::InvalidValue, ContextValue::String("Input path should be a Honggfuzz workspace directory.".to_owned()));
+                        return Err(err);
+                    }
+                    Ok(i_dir.to_path_buf())
+                })
+        )
+        .arg(
+            Arg::new("output")
+                .short('o')
+                .long("output")
+                .action(ArgAction::Set)
+                .required(true)
+                .value_name("OUTPUT_DIR")
+                .value_parser(clap::value_parser!(PathBuf))
+                .help("Output directory with triaged reports")
+        )
+        .arg(
+            Arg::new("force-remove")
+                .short('f')
+                .long("force-remove")
+                .action(ArgAction::SetTrue)
+                .help("Remove output project directory if it exists")
+        )
+        .arg(
+            Arg::new("extension")
+                .short('e')
+                .long("extension")
+                .action(ArgAction::Set)
+                .default_value("fuzz")
+                .value_name("EXT")
+                .help("Extension of crash files (honggfuzz --extension option)")
+        )
+        .arg(
+            Arg::new("stdin")
+                .short('s')
+                .long("stdin")
+                .action(ArgAction::SetTrue)
+                .help("Pass crash input to target via stdin (honggfuzz -s option)")
+        )
+        .arg(
+            Arg::new("no-cluster")
+                .action(ArgAction::SetTrue)
+                .long("no-cluster")
+                .help("Do not cluster CASR reports")
+        )
+        .arg(
+            Arg::new("casr-gdb-args")
+                .long("casr-gdb-args")
+                .action(ArgAction::Set)
+                .help("Add \"--casr-gdb-args \'./gdb_fuzz_target <arguments>\'\" to generate additional crash reports with casr-gdb (e.g., test whether program crashes without sanitizers)"),
+        )
+        .arg(
+            Arg::new("ARGS")
+                .action(ArgAction::Set)
+                .num_args(1..)
+                .last(true)
+                .help("Add \"-- ./fuzz_target <arguments>\" (___FILE___ or @@ is replaced with crash path) to override target command line from HONGGFUZZ.REPORT.TXT"),
+        )
+        .arg(known_bugs_arg())
+        .args(util::input_args())
+        .args(util::source_args())
+        .args(util::logging_args())
+        .get_matches();
+    config::clear_env();
+
+    // Init log.
+    util::initialize_logging(&matches);
+
+    let input_dir = matches.get_one::<PathBuf>("input").unwrap().as_path();
+
+    // Parse honggfuzz report.
+    let report_path = input_dir.join(REPORT_NAME);
+    let hf_report = if let Ok(report) = fs::read_to_string(&report_path) {
+        HonggfuzzReport::parse(&report)
+    } else {
+        warn!("Couldn't read {}.", report_path.display());
+        HonggfuzzReport::default()
+    };
+    debug!("{:?}", hf_report);
+
+    // Get fuzz target args.
+    let mut argv: Vec<String> = if let Some(argvs) = matches.get_many::<String>("ARGS") {
+        argvs.cloned().collect()
+    } else {
+        hf_report.target_args.clone()
+    };
+    if argv.is_empty() {
+        bail!("Couldn't get target command line from {REPORT_NAME}, specify it in ARGS");
+    }
+    for arg in argv.iter_mut() {
+        *arg = arg.replace("___FILE___", "@@");
+    }
+    let at_index = if let Some(idx) = argv.iter().skip(1).position(|s| s.contains("@@")) {
+        Some(idx + 1)
+    } else if matches.get_flag("stdin") || hf_report.stdin {
+        None
+    } else {
+        // Persistent mode target reads input from file when it is run
+        // without honggfuzz.
+        argv.push("@@".to_string());
+        Some(argv.len() - 1)
+    };
+
+    // Choose casr tool. Sanitizer logs mean that target is built with sanitizers.
+    let casr_san = util::get_path("casr-san")?;
+    let casr_gdb = util::get_path("casr-gdb")?;
+    let has_asan = util::symbols_list(Path::new(&argv[0]))?.contains("__asan");
+    let has_log = |pid: &str| -> bool {
+        !pid.is_empty()
+            && input_dir
+                .join(format!("{SANITIZER_LOG_NAME}.{pid}"))
+                .exists()
+    };
+
+    // Get all crashes.
+    let ext = matches.get_one::<String>("extension").unwrap();
+    let crashes: HashMap<String, CrashInfo> = fs::read_dir(input_dir)?
+        .flatten()
+        .map(|p| p.path())
+        .filter(|p| p.is_file())
+        .map(|p| (p.file_name().unwrap().to_str().unwrap().to_string(), p))
+        .filter(|(fname, p)| {
+            hf_report.crashes.contains_key(fname)
+                || (fname.starts_with("SIG") && p.extension().map_or(false, |e| e == ext.as_str()))
+        })
+        .map(|(fname, p)| {
+            let pid = hf_report
+                .crashes
+                .get(&fname)
+                .map(|pid| pid.as_str())
+                .unwrap_or_default();
+            let casr_tool = if has_asan || has_log(pid) {
+                casr_san.clone()
+            } else {
+                casr_gdb.clone()
+            };
+            (
+                fname,
+                CrashInfo {
+                    path: p,
+                    target_args: argv.clone(),
+                    envs: HashMap::new(),
+                    at_index,
+                    casr_tool,
+                    name: None,
+                },
+            )
+        })
+        .collect();
+
+    let gdb_args = if let Some(argv) = matches.get_one::<String>("casr-gdb-args") {
+        shell_words::split(argv)?
+    } else {
+        Vec::new()
+    };
+
+    // Generate reports
+    fuzzing_crash_triage_pipeline(&matches, &crashes, &gdb_args)
+}
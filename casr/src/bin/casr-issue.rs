@@ -0,0 +1,586 @@
+use casr::config;
+use casr::util;
+use libcasr::report::CrashReport;
+
+use anyhow::{bail, Context, Result};
+use clap::{Arg, ArgAction};
+use log::{debug, info, warn};
+use reqwest::header::{HeaderMap, HeaderValue, ACCEPT, AUTHORIZATION, USER_AGENT};
+use reqwest::{Client, Method, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+
+use std::collections::BTreeSet;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const GET: Method = Method::GET;
+const POST: Method = Method::POST;
+
+/// Default state file name in cluster directory.
+const STATE_NAME: &str = "casr-issues.json";
+
+/// Maximum size of crash seed that is embedded into GitHub issue.
+const MAX_EMBEDDED_SEED: usize = 8 * 1024;
+
+/// Issue tracker type.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Tracker {
+    GitHub,
+    GitLab,
+}
+
+/// Issue filed for cluster.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+struct IssueRecord {
+    /// Cluster directory name.
+    #[serde(rename = "Cluster")]
+    cluster: String,
+    /// Issue number (iid for GitLab).
+    #[serde(rename = "Number")]
+    number: u64,
+    /// Issue web URL.
+    #[serde(rename = "Url")]
+    url: String,
+    /// Crash lines of cluster reports used to recognize the same cluster
+    /// after reclustering.
+    #[serde(rename = "Crashlines")]
+    crashlines: Vec<String>,
+}
+
+/// Issues filed by previous runs.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct IssueState {
+    #[serde(rename = "Issues")]
+    issues: Vec<IssueRecord>,
+}
+
+impl IssueState {
+    /// Load state file. Missing file means empty state.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - state file path
+    fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(IssueState::default());
+        }
+        let state = fs::read_to_string(path)
+            .with_context(|| format!("Couldn't read state file {}", path.display()))?;
+        serde_json::from_str(&state)
+            .with_context(|| format!("Couldn't parse state file {}", path.display()))
+    }
+
+    /// Save state file.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - state file path
+    fn save(&self, path: &Path) -> Result<()> {
+        fs::write(path, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("Couldn't write state file {}", path.display()))
+    }
+
+    /// Find issue recorded for cluster with the same name or with common
+    /// crash lines.
+    ///
+    /// # Arguments
+    ///
+    /// * `cluster` - cluster directory name
+    ///
+    /// * `crashlines` - crash lines of cluster reports
+    fn find(&self, cluster: &str, crashlines: &BTreeSet<String>) -> Option<&IssueRecord> {
+        self.issues.iter().rev().find(|issue| {
+            if issue.crashlines.is_empty() || crashlines.is_empty() {
+                issue.cluster == cluster
+            } else {
+                issue
+                    .crashlines
+                    .iter()
+                    .any(|line| crashlines.contains(line))
+            }
+        })
+    }
+}
+
+/// HTTP client for sending REST API requests to GitHub or GitLab.
+struct IssueClient {
+    /// Asynchronous HTTP client.
+    client: Client,
+    /// Issue tracker type.
+    tracker: Tracker,
+    /// Project API URL.
+    project_url: Url,
+    /// HTTP headers.
+    headers: HeaderMap,
+}
+
+impl IssueClient {
+    /// Construct a new issue tracker client.
+    ///
+    /// # Arguments
+    ///
+    /// * `tracker` - issue tracker type.
+    /// * `api_url` - REST API base URL.
+    /// * `project` - project path (owner/repo) or GitLab project id.
+    /// * `token` - API access token.
+    fn new(tracker: Tracker, api_url: &Url, project: &str, token: &str) -> Result<Self> {
+        let mut api_url = api_url.clone();
+        if !api_url.path().ends_with('/') {
+            api_url.set_path(&format!("{}/", api_url.path()));
+        }
+        let project_url = match tracker {
+            Tracker::GitHub => api_url.join(&format!("repos/{}/", project.trim_matches('/')))?,
+            Tracker::GitLab => api_url.join(&format!(
+                "projects/{}/",
+                project.trim_matches('/').replace('/', "%2F")
+            ))?,
+        };
+        let mut headers = HeaderMap::new();
+        headers.insert(USER_AGENT, HeaderValue::from_static("casr-issue"));
+        match tracker {
+            Tracker::GitHub => {
+                headers.insert(AUTHORIZATION, format!("Bearer {token}").parse()?);
+                headers.insert(
+                    ACCEPT,
+                    HeaderValue::from_static("application/vnd.github+json"),
+                );
+            }
+            Tracker::GitLab => {
+                headers.insert("PRIVATE-TOKEN", token.parse()?);
+            }
+        }
+        Ok(IssueClient {
+            client: Client::new(),
+            tracker,
+            project_url,
+            headers,
+        })
+    }
+
+    /// Create HTTP request to project API.
+    ///
+    /// # Arguments
+    ///
+    /// * `method` - HTTP method.
+    /// * `url` - relative project API URL.
+    fn request(&self, method: Method, url: &str) -> Result<RequestBuilder> {
+        Ok(self
+            .client
+            .request(method, self.project_url.join(url)?)
+            .headers(self.headers.clone()))
+    }
+
+    /// Send request and return JSON response.
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - HTTP request.
+    async fn send(request: RequestBuilder) -> Result<serde_json::Value> {
+        let response = request.send().await?;
+        if let Err(e) = response.error_for_status_ref() {
+            bail!("{}: {}", e, response.text().await?);
+        }
+        Ok(response.json().await?)
+    }
+
+    /// Check whether issue is open.
+    ///
+    /// # Arguments
+    ///
+    /// * `number` - issue number (iid for GitLab).
+    async fn is_open(&self, number: u64) -> Result<bool> {
+        let issue = Self::send(self.request(GET, &format!("issues/{number}"))?).await?;
+        let Some(state) = issue["state"].as_str() else {
+            bail!("Failed to get state of issue {number}");
+        };
+        Ok(state == "open" || state == "opened")
+    }
+
+    /// Upload file to project and return Markdown link to it. GitHub REST
+    /// API doesn't support issue attachments, so None is returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - file path.
+    async fn upload_file(&self, path: &Path) -> Result<Option<String>> {
+        if self.tracker == Tracker::GitHub {
+            return Ok(None);
+        }
+        let file = reqwest::multipart::Part::bytes(fs::read(path)?)
+            .file_name(path.file_name().unwrap().to_string_lossy().to_string());
+        let form = reqwest::multipart::Form::new().part("file", file);
+        let upload = Self::send(self.request(POST, "uploads")?.multipart(form)).await?;
+        let Some(markdown) = upload["markdown"].as_str() else {
+            bail!(
+                "Failed to get Markdown link for uploaded {}",
+                path.display()
+            );
+        };
+        Ok(Some(markdown.to_string()))
+    }
+
+    /// Create issue and return its number and web URL.
+    ///
+    /// # Arguments
+    ///
+    /// * `title` - issue title.
+    /// * `body` - issue description in Markdown.
+    /// * `labels` - issue labels.
+    async fn create_issue(
+        &self,
+        title: &str,
+        body: &str,
+        labels: &[String],
+    ) -> Result<(u64, String)> {
+        let mut issue = serde_json::Map::new();
+        issue.insert("title".to_string(), title.into());
+        match self.tracker {
+            Tracker::GitHub => {
+                issue.insert("body".to_string(), body.into());
+                if !labels.is_empty() {
+                    issue.insert("labels".to_string(), labels.into());
+                }
+            }
+            Tracker::GitLab => {
+                issue.insert("description".to_string(), body.into());
+                if !labels.is_empty() {
+                    issue.insert("labels".to_string(), labels.join(",").into());
+                }
+            }
+        }
+        let issue = Self::send(self.request(POST, "issues")?.json(&issue)).await?;
+        let number = match self.tracker {
+            Tracker::GitHub => issue["number"].as_u64(),
+            Tracker::GitLab => issue["iid"].as_u64(),
+        };
+        let url = match self.tracker {
+            Tracker::GitHub => issue["html_url"].as_str(),
+            Tracker::GitLab => issue["web_url"].as_str(),
+        };
+        let (Some(number), Some(url)) = (number, url) else {
+            bail!("Failed to get number and URL of created issue");
+        };
+        Ok((number, url.to_string()))
+    }
+}
+
+/// Get CASR reports from cluster directory (additional GDB reports are skipped)
+///
+/// # Arguments
+///
+/// * `cluster` - cluster directory
+fn cluster_reports(cluster: &Path) -> Result<Vec<(PathBuf, CrashReport)>> {
+    let mut paths: Vec<PathBuf> = fs::read_dir(cluster)?
+        .flatten()
+        .map(|e| e.path())
+        .filter(|e| e.is_file())
+        .filter(|e| e.extension().map_or(false, |ext| ext == "casrep"))
+        .filter(|e| !e.to_string_lossy().ends_with(".gdb.casrep"))
+        .collect();
+    paths.sort();
+    paths
+        .into_iter()
+        .map(|path| {
+            let report = util::report_from_file(&path)?;
+            Ok((path, report))
+        })
+        .collect()
+}
+
+/// Render CASR report as Markdown issue description: bug description from
+/// `CrashReport::to_markdown`, cluster, and full tool reports
+///
+/// # Arguments
+///
+/// * `report` - CASR report
+///
+/// * `cluster` - cluster directory name
+///
+/// * `reports` - number of reports in cluster
+fn report_markdown(report: &CrashReport, cluster: &str, reports: usize) -> String {
+    let mut d = report.to_markdown();
+    let _ = write!(d, "\n**Cluster:** {cluster} ({reports} reports)\n\n");
+    let mut section = |title: &str, lines: &[String]| {
+        if !lines.is_empty() {
+            let _ = write!(d, "### {title}\n\n```\n{}\n```\n\n", lines.join("\n"));
+        }
+    };
+    section("Source", &report.source);
+    section("Allocation stack trace", &report.alloc_stacktrace);
+    section("Deallocation stack trace", &report.free_stacktrace);
+    for (title, lines) in [
+        ("AddressSanitizer report", &report.asan_report),
+        ("MemorySanitizer report", &report.msan_report),
+        ("GWP-ASan report", &report.gwp_asan_report),
+        ("HWAddressSanitizer report", &report.hwasan_report),
+        ("Valgrind report", &report.valgrind_report),
+        ("UndefinedBehaviorSanitizer report", &report.ubsan_report),
+        ("ThreadSanitizer report", &report.tsan_report),
+        ("Kernel report", &report.kernel_report),
+        ("Python report", &report.python_report),
+        ("Go report", &report.go_report),
+        ("Java report", &report.java_report),
+        ("Rust report", &report.rust_report),
+        ("JavaScript report", &report.js_report),
+        ("Reproducer", &report.reproducer),
+    ] {
+        section(title, lines);
+    }
+    d
+}
+
+/// Render crash seed as Markdown for GitHub issue: hex dump that can be
+/// restored with `xxd -r -p`
+///
+/// # Arguments
+///
+/// * `path` - crash seed path
+fn embedded_seed(path: &Path) -> Result<String> {
+    let data = fs::read(path)?;
+    let name = path.file_name().unwrap().to_string_lossy();
+    if data.len() > MAX_EMBEDDED_SEED {
+        return Ok(format!(
+            "Crash seed `{name}` ({} bytes) is too large to be attached.\n",
+            data.len()
+        ));
+    }
+    let hex: Vec<String> = data
+        .chunks(32)
+        .map(|chunk| chunk.iter().map(|b| format!("{b:02x}")).collect())
+        .collect();
+    Ok(format!(
+        "<details><summary>Crash seed <code>{name}</code> (restore with <code>xxd -r -p</code>)</summary>\n\n```\n{}\n```\n\n</details>\n",
+        hex.join("\n")
+    ))
+}
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() -> Result<()> {
+    // Load configuration files.
+    config::load_config("casr-issue")?;
+
+    let options = clap::Command::new("casr-issue")
+        .version(clap::crate_version!())
+        .about("Create GitHub or GitLab issues for new clusters of CASR reports")
+        .term_width(90)
+        .arg(
+            Arg::new("input")
+                .short('i')
+                .long("input")
+                .action(ArgAction::Set)
+                .value_name("INPUT_DIR")
+                .required(true)
+                .value_parser(clap::value_parser!(PathBuf))
+                .help("Directory with clusters of CASR reports (cl1, cl2, ...) and crash seeds"),
+        )
+        .arg(
+            Arg::new("tracker")
+                .long("tracker")
+                .env("CASR_ISSUE_TRACKER")
+                .action(ArgAction::Set)
+                .default_value("github")
+                .value_parser(["github", "gitlab"])
+                .help("Issue tracker type"),
+        )
+        .arg(
+            Arg::new("url")
+                .short('u')
+                .long("url")
+                .env("CASR_ISSUE_URL")
+                .action(ArgAction::Set)
+                .value_name("URL")
+                .value_parser(Url::parse)
+                .help("REST API base URL [default: https://api.github.com or https://gitlab.com/api/v4]"),
+        )
+        .arg(
+            Arg::new("project")
+                .short('p')
+                .long("project")
+                .env("CASR_ISSUE_PROJECT")
+                .action(ArgAction::Set)
+                .value_name("PROJECT")
+                .required(true)
+                .help("Project path (owner/repo for GitHub, group/project or project id for GitLab)"),
+        )
+        .arg(
+            Arg::new("token")
+                .short('t')
+                .long("token")
+                .env("CASR_ISSUE_TOKEN")
+                .action(ArgAction::Set)
+                .value_name("TOKEN")
+                .required(true)
+                .hide_env_values(true)
+                .help("API access token"),
+        )
+        .arg(
+            Arg::new("state")
+                .short('s')
+                .long("state")
+                .action(ArgAction::Set)
+                .value_name("FILE")
+                .value_parser(clap::value_parser!(PathBuf))
+                .help("JSON file with issues filed for clusters [default: INPUT_DIR/casr-issues.json]"),
+        )
+        .arg(
+            Arg::new("label")
+                .long("label")
+                .action(ArgAction::Append)
+                .value_name("LABEL")
+                .help("Label for created issues (option may be specified several times)"),
+        )
+        .arg(
+            Arg::new("dry-run")
+                .long("dry-run")
+                .action(ArgAction::SetTrue)
+                .help("Print issues to stdout instead of creating them"),
+        )
+        .args(util::logging_args())
+        .get_matches();
+    config::clear_env();
+
+    // Init log.
+    util::initialize_logging(&options);
+
+    let input = options.get_one::<PathBuf>("input").unwrap();
+    if !input.is_dir() {
+        bail!("Input directory {} doesn't exist", input.display());
+    }
+    let tracker = match options.get_one::<String>("tracker").unwrap().as_str() {
+        "gitlab" => Tracker::GitLab,
+        _ => Tracker::GitHub,
+    };
+    let api_url = if let Some(url) = options.get_one::<Url>("url") {
+        url.clone()
+    } else if tracker == Tracker::GitHub {
+        Url::parse("https://api.github.com/")?
+    } else {
+        Url::parse("https://gitlab.com/api/v4/")?
+    };
+    let client = IssueClient::new(
+        tracker,
+        &api_url,
+        options.get_one::<String>("project").unwrap(),
+        options.get_one::<String>("token").unwrap(),
+    )?;
+    let labels: Vec<String> = options
+        .get_many::<String>("label")
+        .map(|labels| labels.cloned().collect())
+        .unwrap_or_default();
+    let dry_run = options.get_flag("dry-run");
+    let state_path = options
+        .get_one::<PathBuf>("state")
+        .cloned()
+        .unwrap_or_else(|| input.join(STATE_NAME));
+    let mut state = IssueState::load(&state_path)?;
+
+    // Get clusters.
+    let mut clusters: Vec<PathBuf> = fs::read_dir(input)
+        .with_context(|| format!("Couldn't read directory {}", input.display()))?
+        .flatten()
+        .map(|e| e.path())
+        .filter(|e| e.is_dir())
+        .filter(|e| {
+            e.file_name()
+                .unwrap()
+                .to_str()
+                .and_then(|name| name.strip_prefix("cl"))
+                .map_or(false, |num| num.parse::<u32>().is_ok())
+        })
+        .collect();
+    clusters.sort_by_key(|cluster| {
+        cluster.file_name().unwrap().to_str().unwrap()[2..]
+            .parse::<u32>()
+            .unwrap()
+    });
+
+    let (mut created, mut skipped) = (0, 0);
+    for cluster in clusters {
+        let name = cluster.file_name().unwrap().to_string_lossy().to_string();
+        let reports = cluster_reports(&cluster)?;
+        if reports.is_empty() {
+            continue;
+        }
+        let crashlines: BTreeSet<String> = reports
+            .iter()
+            .map(|(_, report)| report.crashline.clone())
+            .filter(|crashline| !crashline.is_empty())
+            .collect();
+
+        // Skip clusters with open issues.
+        if let Some(issue) = state.find(&name, &crashlines) {
+            if dry_run || client.is_open(issue.number).await? {
+                debug!("Cluster {} has open issue {}", name, issue.url);
+                skipped += 1;
+                continue;
+            }
+            info!(
+                "Issue {} for cluster {} is closed, crash is reproduced again",
+                issue.url, name
+            );
+        }
+
+        // The most severe report represents cluster.
+        let (path, report) = reports
+            .iter()
+            .max_by(|(p1, r1), (p2, r2)| {
+                r1.severity_score
+                    .total_cmp(&r2.severity_score)
+                    .then_with(|| p2.cmp(p1))
+            })
+            .unwrap();
+        let mut executable = "";
+        if let Some(fname) = Path::new(&report.executable_path).file_name() {
+            executable = fname.to_str().unwrap();
+        }
+        let title: String = format!(
+            "[{executable}] {} in {}",
+            report.execution_class.short_description, report.crashline
+        )
+        .chars()
+        .take(250)
+        .collect();
+        let mut body = report_markdown(report, &name, reports.len());
+
+        // Attach crash seed.
+        let seed = path.with_extension("");
+        if seed.is_file() {
+            if dry_run {
+                let _ = writeln!(body, "Crash seed: `{}`", seed.display());
+            } else if let Some(link) = client.upload_file(&seed).await? {
+                let _ = writeln!(body, "**Crash seed:** {link}");
+            } else {
+                body += &embedded_seed(&seed)?;
+            }
+        }
+
+        if dry_run {
+            println!("# {title}\n\n{body}");
+            created += 1;
+            continue;
+        }
+        let (number, url) = match client.create_issue(&title, &body, &labels).await {
+            Ok(issue) => issue,
+            Err(e) => {
+                warn!("Couldn't create issue for cluster {}: {}", name, e);
+                continue;
+            }
+        };
+        info!("Created issue {} for cluster {}", url, name);
+        state.issues.push(IssueRecord {
+            cluster: name,
+            number,
+            url,
+            crashlines: crashlines.into_iter().collect(),
+        });
+        // Save state after each issue, so that next run doesn't duplicate
+        // issues if this one fails.
+        state.save(&state_path)?;
+        created += 1;
+    }
+
+    info!("Number of created issues: {}", created);
+    info!("Number of clusters with open issues: {}", skipped);
+    Ok(())
+}
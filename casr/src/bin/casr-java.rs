@@ -1,19 +1,25 @@
+use casr::config;
 use casr::util;
 use libcasr::{
-    exception::Exception, init_ignored_frames, java::*, report::CrashReport, stacktrace::*,
+    exception::Exception, init_ignored_frames, java::*, report::CrashReport, severity::Severity,
+    stacktrace::*,
 };
 
-use anyhow::{bail, Result};
+use anyhow::{bail, Context, Result};
 use clap::{Arg, ArgAction, ArgGroup};
 use regex::Regex;
+use std::fs;
 use std::path::PathBuf;
 use std::process::Command;
 use walkdir::WalkDir;
 
 fn main() -> Result<()> {
+    // Load configuration files.
+    config::load_config("casr-java")?;
+
     let matches = clap::Command::new("casr-java")
         .version(clap::crate_version!())
-        .about("Create CASR reports (.casrep) from java reports")
+        .about("Create CASR reports (.casrep) from java reports and JVM fatal error logs")
         .term_width(90)
         .arg(
             Arg::new("output")
@@ -60,6 +66,7 @@ fn main() -> Result<()> {
             Arg::new("timeout")
                 .short('t')
                 .long("timeout")
+                .env("CASR_TIMEOUT")
                 .action(ArgAction::Set)
                 .default_value("0")
                 .value_name("SECONDS")
@@ -69,6 +76,7 @@ fn main() -> Result<()> {
         .arg(
             Arg::new("ignore")
                 .long("ignore")
+                .env("CASR_IGNORE")
                 .action(ArgAction::Set)
                 .value_parser(clap::value_parser!(PathBuf))
                 .value_name("FILE")
@@ -81,7 +89,16 @@ fn main() -> Result<()> {
                 .last(true)
                 .help("Add \"-- <path> <arguments>\" to run"),
         )
+        .arg(util::aslr_arg())
+        .arg(util::near_null_arg())
+        .args(util::source_args())
+        .args(util::logging_args())
         .get_matches();
+    config::clear_env();
+
+    // Init log.
+    util::initialize_logging(&matches);
+    util::init_near_null_threshold(&matches);
 
     init_ignored_frames!("java", "cpp"); //TODO
     if let Some(path) = matches.get_one::<PathBuf>("ignore") {
@@ -108,12 +125,19 @@ fn main() -> Result<()> {
     if argv.len() > 1 {
         java_cmd.args(&argv[1..]);
     }
+    let aslr = util::aslr_from_matches(&matches);
+    if aslr == Some(false) {
+        util::disable_aslr(&mut java_cmd);
+    }
     let java_result = util::get_output(&mut java_cmd, timeout, true)?;
 
     let java_stderr = String::from_utf8_lossy(&java_result.stderr);
 
     // Create report.
     let mut report = CrashReport::new();
+    if let Some(aslr) = aslr {
+        report.aslr = if aslr { "on" } else { "off" }.to_string();
+    }
     // Set executable path (java class path)
     if let Some(pos) = argv.iter().position(|x| {
         x.starts_with("-cp")
@@ -138,6 +162,8 @@ fn main() -> Result<()> {
     // Get java report.
     let java_stderr_list: Vec<String> = java_stderr.split('\n').map(|l| l.to_string()).collect();
     let re = Regex::new(r"Exception in thread .*? |== Java Exception: ").unwrap();
+    let rsan = Regex::new(r"==\d+==\s*ERROR: (LeakSanitizer|AddressSanitizer|libFuzzer):").unwrap();
+    let stacktrace: Stacktrace;
     if let Some(start) = java_stderr_list.iter().position(|x| re.is_match(x)) {
         report.java_report = java_stderr_list[start..].to_vec();
         if let Some(end) = report
@@ -153,12 +179,30 @@ fn main() -> Result<()> {
         if let Some(exception) = JavaException::parse_exception(&report_str) {
             report.execution_class = exception;
         }
+        stacktrace = JavaStacktrace::parse_stacktrace(&report.stacktrace)?;
+    } else if let Some(hs_err_path) =
+        extract_hs_err_path(&String::from_utf8_lossy(&java_result.stdout))
+            .or_else(|| extract_hs_err_path(&java_stderr))
+            .filter(|_| !rsan.is_match(&java_stderr))
+    {
+        // JVM crashed in native code or in JVM itself.
+        let hs_err = fs::read_to_string(&hs_err_path)
+            .with_context(|| format!("Couldn't read JVM fatal error log {hs_err_path}"))?;
+        // Omit process information (e.g., heap and dynamic libraries).
+        report.java_report = hs_err
+            .split('\n')
+            .map(|l| l.trim_end().to_string())
+            .take_while(|l| !l.starts_with("---------------  P R O C E S S"))
+            .collect();
+        report.stacktrace = HsErrStacktrace::extract_stacktrace(&hs_err)?;
+        report.execution_class = HsErrContext(report.java_report.clone()).severity()?;
+        stacktrace = HsErrStacktrace::parse_stacktrace(&report.stacktrace)?;
     } else {
         // Call casr-san
         return util::call_casr_san(&matches, &argv, "casr-java");
     }
 
-    if let Ok(crash_line) = JavaStacktrace::parse_stacktrace(&report.stacktrace)?.crash_line() {
+    if let Ok(crash_line) = stacktrace.crash_line() {
         report.crashline = crash_line.to_string();
         if let CrashLine::Source(mut debug) = crash_line {
             // Modify DebugInfo to find sources
@@ -1,3 +1,4 @@
+use casr::config;
 use casr::util;
 use libcasr::{
     exception::Exception, init_ignored_frames, js::*, report::CrashReport, stacktrace::*,
@@ -10,6 +11,9 @@ use std::path::{Path, PathBuf};
 use std::process::Command;
 
 fn main() -> Result<()> {
+    // Load configuration files.
+    config::load_config("casr-js")?;
+
     let matches = clap::Command::new("casr-js")
         .version(clap::crate_version!())
         .about("Create CASR reports (.casrep) from JavaScript crash reports")
@@ -48,6 +52,7 @@ fn main() -> Result<()> {
             Arg::new("timeout")
                 .short('t')
                 .long("timeout")
+                .env("CASR_TIMEOUT")
                 .action(ArgAction::Set)
                 .default_value("0")
                 .value_name("SECONDS")
@@ -57,6 +62,7 @@ fn main() -> Result<()> {
         .arg(
             Arg::new("ignore")
                 .long("ignore")
+                .env("CASR_IGNORE")
                 .action(ArgAction::Set)
                 .value_parser(clap::value_parser!(PathBuf))
                 .value_name("FILE")
@@ -69,7 +75,16 @@ fn main() -> Result<()> {
                 .last(true)
                 .help("Add \"-- <path> <arguments>\" to run"),
         )
+        .arg(util::aslr_arg())
+        .arg(util::near_null_arg())
+        .args(util::source_args())
+        .args(util::logging_args())
         .get_matches();
+    config::clear_env();
+
+    // Init log.
+    util::initialize_logging(&matches);
+    util::init_near_null_threshold(&matches);
 
     init_ignored_frames!("js", "cpp");
     if let Some(path) = matches.get_one::<PathBuf>("ignore") {
@@ -96,12 +111,19 @@ fn main() -> Result<()> {
     if argv.len() > 1 {
         js_cmd.args(&argv[1..]);
     }
+    let aslr = util::aslr_from_matches(&matches);
+    if aslr == Some(false) {
+        util::disable_aslr(&mut js_cmd);
+    }
     let js_result = util::get_output(&mut js_cmd, timeout, true)?;
 
     let js_stderr = String::from_utf8_lossy(&js_result.stderr);
 
     // Create report.
     let mut report = CrashReport::new();
+    if let Some(aslr) = aslr {
+        report.aslr = if aslr { "on" } else { "off" }.to_string();
+    }
     // Set executable path.
     report.executable_path = argv[0].to_string();
     let mut path_to_tool = PathBuf::new();
@@ -0,0 +1,179 @@
+use casr::config;
+use casr::util;
+use libcasr::{
+    init_ignored_frames,
+    kernel::{extract_kernel_report, KernelContext, KernelStacktrace},
+    report::CrashReport,
+    severity::Severity,
+    stacktrace::*,
+};
+
+use anyhow::{bail, Context, Result};
+use clap::{Arg, ArgAction, ArgGroup};
+use log::warn;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Kernel log files in syzkaller crash directory in order of preference.
+const SYZKALLER_LOGS: &[&str] = &["repro.report", "report0", "repro.log", "log0"];
+
+/// Reproducer files in syzkaller crash directory in order of preference.
+const SYZKALLER_REPROS: &[&str] = &["repro.cprog", "repro.prog"];
+
+/// Read file lines lossily
+///
+/// # Arguments
+///
+/// * `path` - path to file
+fn read_lines(path: &Path) -> Result<Vec<String>> {
+    let data = fs::read(path).with_context(|| format!("Couldn't read {}", path.display()))?;
+    Ok(String::from_utf8_lossy(&data)
+        .lines()
+        .map(|l| l.to_string())
+        .collect())
+}
+
+fn main() -> Result<()> {
+    // Load configuration files.
+    config::load_config("casr-kernel")?;
+
+    let matches = clap::Command::new("casr-kernel")
+        .version(clap::crate_version!())
+        .about("Create CASR reports (.casrep) from Linux kernel logs (dmesg, serial console, or syzkaller crash log) with KASAN, KCSAN, KMSAN reports, BUG, oops, or WARNING")
+        .term_width(90)
+        .arg(
+            Arg::new("input")
+                .short('i')
+                .long("input")
+                .action(ArgAction::Set)
+                .required(true)
+                .value_parser(clap::value_parser!(PathBuf))
+                .value_name("LOG")
+                .help("Kernel log file or syzkaller crash directory (with report0, log0, repro.prog, etc.)"),
+        )
+        .arg(
+            Arg::new("repro")
+                .long("repro")
+                .action(ArgAction::Set)
+                .value_parser(clap::value_parser!(PathBuf))
+                .value_name("FILE")
+                .help("Reproducer program (syzkaller C or syz program) to save in report"),
+        )
+        .arg(
+            Arg::new("output")
+                .short('o')
+                .long("output")
+                .action(ArgAction::Set)
+                .value_parser(clap::value_parser!(PathBuf))
+                .value_name("REPORT")
+                .help(
+                    "Path to save report. Path can be a directory, then report name is generated",
+                ),
+        )
+        .arg(
+            Arg::new("stdout")
+                .action(ArgAction::SetTrue)
+                .long("stdout")
+                .help("Print CASR report to stdout"),
+        )
+        .group(
+            ArgGroup::new("out")
+                .args(["stdout", "output"])
+                .required(true),
+        )
+        .arg(
+            Arg::new("ignore")
+                .long("ignore")
+                .env("CASR_IGNORE")
+                .action(ArgAction::Set)
+                .value_parser(clap::value_parser!(PathBuf))
+                .value_name("FILE")
+                .help("File with regular expressions for functions and file paths that should be ignored"),
+        )
+        .arg(util::near_null_arg())
+        .args(util::source_args())
+        .args(util::logging_args())
+        .get_matches();
+    config::clear_env();
+
+    // Init log.
+    util::initialize_logging(&matches);
+    util::init_near_null_threshold(&matches);
+
+    init_ignored_frames!("cpp");
+    if let Some(path) = matches.get_one::<PathBuf>("ignore") {
+        util::add_custom_ignored_frames(path)?;
+    }
+
+    // Read kernel log and reproducer.
+    let input = matches.get_one::<PathBuf>("input").unwrap();
+    let mut repro = matches.get_one::<PathBuf>("repro").cloned();
+    let log_path = if input.is_dir() {
+        if repro.is_none() {
+            repro = SYZKALLER_REPROS
+                .iter()
+                .map(|name| input.join(name))
+                .find(|path| path.exists());
+        }
+        let Some(log_path) = SYZKALLER_LOGS
+            .iter()
+            .map(|name| input.join(name))
+            .find(|path| path.exists())
+        else {
+            bail!("Couldn't find kernel log in {}", input.display());
+        };
+        log_path
+    } else {
+        input.clone()
+    };
+    let Some(kernel_report) = extract_kernel_report(&read_lines(&log_path)?) else {
+        bail!(
+            "Couldn't find kernel crash report in {}",
+            log_path.display()
+        );
+    };
+
+    // Create report.
+    let mut report = CrashReport::new();
+    report.executable_path = "vmlinux".to_string();
+    report.os = "Linux".to_string();
+    let context = KernelContext(kernel_report);
+    if let Some(release) = context.kernel_release() {
+        report.os_release = release;
+    }
+    report.registers = context.registers();
+    match context.severity() {
+        Ok(severity) => report.execution_class = severity,
+        Err(error) => warn!("Couldn't estimate severity. {}", error),
+    }
+    report.stacktrace = context.extract_stacktrace()?;
+    report.kernel_report = context.0;
+    if let Some(repro) = repro {
+        report.reproducer = read_lines(&repro)?;
+    }
+
+    // Kernel frames without source locations are identified by function
+    // offsets rather than by module offsets.
+    let mut stacktrace = KernelStacktrace::parse_stacktrace(&report.stacktrace)?;
+    match stacktrace.crash_line() {
+        Ok(CrashLine::Module { .. }) => {
+            stacktrace.filter();
+            let entry = &stacktrace[0];
+            report.crashline = format!("{}+{:#x}", entry.function, entry.offset);
+        }
+        Ok(crash_line) => {
+            report.crashline = crash_line.to_string();
+            if let CrashLine::Source(debug) = crash_line {
+                if let Some(sources) = CrashReport::sources(&debug) {
+                    report.source = sources;
+                }
+            }
+        }
+        Err(_) => {}
+    }
+
+    //Output report
+    let input = input.to_string_lossy();
+    util::output_report(&report, &matches, &["vmlinux", &input])
+}
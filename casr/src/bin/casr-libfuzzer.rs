@@ -1,3 +1,5 @@
+use casr::config;
+use casr::known_bugs::known_bugs_arg;
 use casr::triage::{fuzzing_crash_triage_pipeline, CrashInfo};
 use casr::util;
 
@@ -12,6 +14,9 @@ use std::fs;
 use std::path::{Path, PathBuf};
 
 fn main() -> Result<()> {
+    // Load configuration files.
+    config::load_config("casr-libfuzzer")?;
+
     let matches = clap::Command::new("casr-libfuzzer")
         .version(clap::crate_version!())
         .about("Triage crashes found by libFuzzer based fuzzer (C/C++/go-fuzz/Atheris/Jazzer/Jazzer.js/jsfuzz)")
@@ -19,6 +24,7 @@ fn main() -> Result<()> {
         .arg(
             Arg::new("log-level")
                 .long("log-level")
+                .env("CASR_LOG_LEVEL")
                 .short('l')
                 .action(ArgAction::Set)
                 .default_value("info")
@@ -27,6 +33,7 @@ fn main() -> Result<()> {
         )
         .arg(Arg::new("jobs")
             .long("jobs")
+            .env("CASR_JOBS")
             .short('j')
             .action(ArgAction::Set)
             .help("Number of parallel jobs for generating CASR reports [default: half of cpu cores]")
@@ -35,12 +42,33 @@ fn main() -> Result<()> {
             Arg::new("timeout")
                 .short('t')
                 .long("timeout")
+                .env("CASR_TIMEOUT")
                 .action(ArgAction::Set)
                 .default_value("0")
                 .value_name("SECONDS")
                 .help("Timeout (in seconds) for target execution, 0 means that timeout is disabled")
                 .value_parser(clap::value_parser!(u64).range(0..))
         )
+        .arg(
+            Arg::new("unit-timeout")
+                .long("unit-timeout")
+                .env("CASR_UNIT_TIMEOUT")
+                .action(ArgAction::Set)
+                .default_value("10")
+                .value_name("SECONDS")
+                .help("libFuzzer -timeout value for running target on timeout-* artifacts")
+                .value_parser(clap::value_parser!(u64).range(1..))
+        )
+        .arg(
+            Arg::new("rss-limit-mb")
+                .long("rss-limit-mb")
+                .env("CASR_RSS_LIMIT_MB")
+                .action(ArgAction::Set)
+                .default_value("2048")
+                .value_name("MB")
+                .help("libFuzzer -rss_limit_mb value for running target on oom-* artifacts")
+                .value_parser(clap::value_parser!(u64).range(1..))
+        )
         .arg(
             Arg::new("input")
                 .short('i')
@@ -48,7 +76,7 @@ fn main() -> Result<()> {
                 .action(ArgAction::Set)
                 .default_value(".")
                 .value_name("INPUT_DIR")
-                .help("Directory containing crashes found by libFuzzer")
+                .help("Directory containing crash-*, leak-*, oom-*, and timeout-* artifacts found by libFuzzer")
                 .value_parser(move |arg: &str| {
                     let i_dir = Path::new(arg);
                     if !i_dir.exists() {
@@ -100,7 +128,12 @@ fn main() -> Result<()> {
                 .last(true)
                 .help("Add \"-- ./fuzz_target <arguments>\""),
         )
+        .arg(known_bugs_arg())
+        .args(util::input_args())
+        .args(util::source_args())
+        .args(util::logging_args())
         .get_matches();
+    config::clear_env();
 
     // Init log.
     util::initialize_logging(&matches);
@@ -144,22 +177,48 @@ fn main() -> Result<()> {
     };
     let tool = util::get_path(tool)?;
 
+    // libFuzzer flags to reproduce oom and timeout artifacts.
+    let unit_timeout = *matches.get_one::<u64>("unit-timeout").unwrap();
+    let rss_limit_mb = *matches.get_one::<u64>("rss-limit-mb").unwrap();
+    let artifact_flag = |fname: &str| -> Option<String> {
+        let (flag, value) = if fname.starts_with("oom-") {
+            ("-rss_limit_mb=", rss_limit_mb)
+        } else if fname.starts_with("timeout-") {
+            ("-timeout=", unit_timeout)
+        } else {
+            return None;
+        };
+        // Do not override flag that is specified by user.
+        (!argv.iter().any(|arg| arg.starts_with(flag))).then(|| format!("{flag}{value}"))
+    };
+
     // Get all crashes.
     let crashes: HashMap<String, CrashInfo> = fs::read_dir(input_dir)?
         .flatten()
         .map(|p| p.path())
         .filter(|p| p.is_file())
         .map(|p| (p.file_name().unwrap().to_str().unwrap().to_string(), p))
-        .filter(|(fname, _)| fname.starts_with("crash-") || fname.starts_with("leak-"))
+        .filter(|(fname, _)| {
+            ["crash-", "leak-", "oom-", "timeout-"]
+                .iter()
+                .any(|prefix| fname.starts_with(prefix))
+        })
         .map(|(fname, p)| {
+            let mut target_args: Vec<String> = argv.iter().map(|x| x.to_string()).collect();
+            let mut at_index = at_index;
+            if let Some(flag) = artifact_flag(&fname) {
+                target_args.insert(at_index, flag);
+                at_index += 1;
+            }
             (
                 fname,
                 CrashInfo {
                     path: p,
-                    target_args: argv.iter().map(|x| x.to_string()).collect(),
+                    target_args,
                     envs: envs.clone(),
                     at_index: Some(at_index),
                     casr_tool: tool.clone(),
+                    name: None,
                 },
             )
         })
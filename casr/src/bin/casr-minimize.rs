@@ -0,0 +1,291 @@
+use casr::config;
+use casr::reproduce::{report_command, report_input, report_tool, rerun_report};
+use casr::util;
+
+use anyhow::{bail, Context, Result};
+use clap::{Arg, ArgAction};
+use libcasr::report::CrashReport;
+use log::{debug, info, warn};
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Number of libFuzzer fuzzing runs in one minimization step.
+const LIBFUZZER_RUNS: u64 = 10000;
+
+/// Number of failed libFuzzer minimization steps in a row after which
+/// minimization stops.
+const LIBFUZZER_ATTEMPTS: usize = 3;
+
+/// Input minimizer that checks on each step that target crashes the same way.
+struct Minimizer {
+    /// Report of crash to minimize input for.
+    report: CrashReport,
+    /// Casr tool that creates report on target re-run.
+    tool: PathBuf,
+    /// Target argument vector with input replaced by candidate.
+    argv: Vec<String>,
+    /// Target stdin file (candidate if input is passed via stdin).
+    stdin: Option<PathBuf>,
+    /// Candidate input path.
+    candidate: PathBuf,
+    /// Target timeout (in seconds).
+    timeout: u64,
+    /// Number of target runs.
+    runs: u64,
+    /// Maximum number of target runs.
+    max_runs: u64,
+}
+
+impl Minimizer {
+    /// Check that target crashes the same way on input (see
+    /// `CrashReport::is_same_crash`)
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - candidate input
+    fn check(&mut self, data: &[u8]) -> Result<bool> {
+        self.runs += 1;
+        fs::write(&self.candidate, data)?;
+        let rerun = rerun_report(&self.tool, &self.argv, self.stdin.as_deref(), self.timeout)?;
+        Ok(rerun.is_some_and(|rerun| self.report.is_same_crash(&rerun)))
+    }
+
+    /// Check whether run limit is reached
+    fn exhausted(&self) -> bool {
+        self.max_runs != 0 && self.runs >= self.max_runs
+    }
+
+    /// Remove input blocks of decreasing size (from half of input to one
+    /// byte) like afl-tmin while the same crash reproduces. Rounds are
+    /// repeated until input can't be trimmed further.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - crashing input
+    ///
+    /// # Return value
+    ///
+    /// Minimized input
+    fn trim(&mut self, mut data: Vec<u8>) -> Result<Vec<u8>> {
+        loop {
+            let size = data.len();
+            let mut block = (size / 2).max(1);
+            while block > 0 && !self.exhausted() {
+                let mut start = 0;
+                while start < data.len() && !self.exhausted() {
+                    let end = (start + block).min(data.len());
+                    let candidate = [&data[..start], &data[end..]].concat();
+                    if self.check(&candidate)? {
+                        debug!("Removed {} bytes at offset {start}", end - start);
+                        data = candidate;
+                    } else {
+                        start += block;
+                    }
+                }
+                block /= 2;
+            }
+            info!("Trimming round: {size} -> {} bytes", data.len());
+            if data.len() == size || self.exhausted() {
+                return Ok(data);
+            }
+        }
+    }
+
+    /// Minimize input with libFuzzer `-minimize_crash_internal_step`: each
+    /// step libFuzzer fuzz target searches for smaller crashing input that is
+    /// accepted only if the same crash reproduces
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - crashing input
+    ///
+    /// * `artifact` - path to save input found by libFuzzer
+    ///
+    /// # Return value
+    ///
+    /// Minimized input
+    fn libfuzzer(&mut self, mut data: Vec<u8>, artifact: &Path) -> Result<Vec<u8>> {
+        let mut attempts = 0;
+        while attempts < LIBFUZZER_ATTEMPTS && !data.is_empty() && !self.exhausted() {
+            fs::write(&self.candidate, &data)?;
+            let _ = fs::remove_file(artifact);
+            let mut cmd = Command::new(&self.argv[0]);
+            cmd.arg("-minimize_crash_internal_step=1")
+                .arg(format!("-runs={LIBFUZZER_RUNS}"))
+                .arg(format!("-exact_artifact_path={}", artifact.display()))
+                .args(&self.argv[1..]);
+            if self.timeout != 0 {
+                cmd.arg(format!("-timeout={}", self.timeout));
+            }
+            debug!("{:?}", cmd);
+            cmd.output()
+                .with_context(|| format!("Couldn't launch {cmd:?}"))?;
+            let smaller = fs::read(artifact)
+                .ok()
+                .filter(|smaller| smaller.len() < data.len());
+            match smaller {
+                Some(smaller) if self.check(&smaller)? => {
+                    info!("libFuzzer step: {} -> {} bytes", data.len(), smaller.len());
+                    data = smaller;
+                    attempts = 0;
+                }
+                _ => attempts += 1,
+            }
+        }
+        Ok(data)
+    }
+}
+
+fn main() -> Result<()> {
+    // Load configuration files.
+    config::load_config("casr-minimize")?;
+
+    let matches = clap::Command::new("casr-minimize")
+        .version(clap::crate_version!())
+        .about("Minimize crashing input from CASR report checking that the same crash (execution class and crash line) reproduces on each step")
+        .term_width(90)
+        .arg(
+            Arg::new("report")
+                .action(ArgAction::Set)
+                .required(true)
+                .value_name("REPORT")
+                .value_parser(clap::value_parser!(PathBuf))
+                .help("CASR report with target command line and crashing input (see casr-san --attach-input)"),
+        )
+        .arg(
+            Arg::new("output")
+                .short('o')
+                .long("output")
+                .action(ArgAction::Set)
+                .value_name("FILE")
+                .value_parser(clap::value_parser!(PathBuf))
+                .help("Path to save minimized input [default: report path with .min extension]"),
+        )
+        .arg(
+            Arg::new("mode")
+                .long("mode")
+                .env("CASR_MINIMIZE_MODE")
+                .action(ArgAction::Set)
+                .default_value("auto")
+                .value_parser(["auto", "trim", "libfuzzer"])
+                .help("Minimization mode: trim removes input blocks like afl-tmin, libfuzzer runs libFuzzer fuzz target with -minimize_crash_internal_step, auto chooses libfuzzer for libFuzzer fuzz targets and trim otherwise"),
+        )
+        .arg(
+            Arg::new("timeout")
+                .short('t')
+                .long("timeout")
+                .env("CASR_TIMEOUT")
+                .action(ArgAction::Set)
+                .default_value("0")
+                .value_name("SECONDS")
+                .value_parser(clap::value_parser!(u64))
+                .help("Timeout (in seconds) for target execution, 0 value means that timeout is disabled"),
+        )
+        .arg(
+            Arg::new("max-runs")
+                .long("max-runs")
+                .env("CASR_MINIMIZE_MAX_RUNS")
+                .action(ArgAction::Set)
+                .default_value("1000")
+                .value_name("N")
+                .value_parser(clap::value_parser!(u64))
+                .help("Maximum number of target runs, 0 value means that number of runs isn't limited"),
+        )
+        .args(util::logging_args())
+        .get_matches();
+    config::clear_env();
+
+    util::initialize_logging(&matches);
+
+    let report_path = matches.get_one::<PathBuf>("report").unwrap();
+    let report = util::report_from_file(report_path)?;
+    let output = matches
+        .get_one::<PathBuf>("output")
+        .cloned()
+        .unwrap_or_else(|| report_path.with_extension("min"));
+
+    // Directory is created with random name and removed when dropped.
+    let dir = tempfile::Builder::new()
+        .prefix("casr_minimize_")
+        .tempdir()
+        .with_context(|| "Couldn't create temporary directory")?;
+    let (data, size, runs) = minimize(&matches, report, dir.path())?;
+
+    fs::write(&output, &data)?;
+    info!(
+        "Minimized input ({size} -> {} bytes, {runs} runs) is saved to {}",
+        data.len(),
+        output.display()
+    );
+    Ok(())
+}
+
+/// Minimize crashing input from report
+///
+/// # Arguments
+///
+/// * `matches` - casr-minimize options
+///
+/// * `report` - report of crash to minimize input for
+///
+/// * `dir` - directory for temporary files
+///
+/// # Return value
+///
+/// Minimized input, original input size, and number of target runs
+fn minimize(
+    matches: &clap::ArgMatches,
+    report: CrashReport,
+    dir: &Path,
+) -> Result<(Vec<u8>, usize, u64)> {
+    // Attached input is restored if it is missing or changed.
+    let input = if report.input_sha256.is_empty() {
+        let Some(input) = report_input(&report) else {
+            bail!("Report has no crashing input");
+        };
+        input
+    } else {
+        report.restore_input(dir)?
+    };
+    let data = fs::read(&input)?;
+    let candidate = dir.join(input.file_name().unwrap_or("input".as_ref()));
+    let (argv, stdin) = report_command(&report, Some(&candidate))?;
+    let libfuzzer = match matches.get_one::<String>("mode").unwrap().as_str() {
+        "libfuzzer" => true,
+        "trim" => false,
+        _ => {
+            stdin.is_none()
+                && util::symbols_list(Path::new(&argv[0]))
+                    .is_ok_and(|symbols| symbols.contains("LLVMFuzzerTestOneInput"))
+        }
+    };
+    let mut minimizer = Minimizer {
+        tool: report_tool(&report)?,
+        report,
+        argv,
+        stdin,
+        candidate,
+        timeout: *matches.get_one::<u64>("timeout").unwrap(),
+        runs: 0,
+        max_runs: *matches.get_one::<u64>("max-runs").unwrap(),
+    };
+
+    info!("Checking that crash reproduces on {}", input.display());
+    if !minimizer.check(&data)? {
+        bail!("Crash isn't reproduced on {}", input.display());
+    }
+    let size = data.len();
+    let data = if libfuzzer {
+        info!("Minimizing {size} bytes with libFuzzer");
+        minimizer.libfuzzer(data, &dir.join("minimized"))?
+    } else {
+        info!("Trimming {size} bytes");
+        minimizer.trim(data)?
+    };
+    if minimizer.exhausted() {
+        warn!("Run limit is reached, input may be minimized further");
+    }
+    Ok((data, size, minimizer.runs))
+}
@@ -1,3 +1,4 @@
+use casr::config;
 use casr::util;
 use libcasr::{
     exception::Exception,
@@ -14,6 +15,9 @@ use std::path::{Path, PathBuf};
 use std::process::Command;
 
 fn main() -> Result<()> {
+    // Load configuration files.
+    config::load_config("casr-python")?;
+
     let matches = clap::Command::new("casr-python")
         .version(clap::crate_version!())
         .about("Create CASR reports (.casrep) from python reports")
@@ -52,6 +56,7 @@ fn main() -> Result<()> {
             Arg::new("timeout")
                 .short('t')
                 .long("timeout")
+                .env("CASR_TIMEOUT")
                 .action(ArgAction::Set)
                 .default_value("0")
                 .value_name("SECONDS")
@@ -61,6 +66,7 @@ fn main() -> Result<()> {
         .arg(
             Arg::new("ignore")
                 .long("ignore")
+                .env("CASR_IGNORE")
                 .action(ArgAction::Set)
                 .value_parser(clap::value_parser!(PathBuf))
                 .value_name("FILE")
@@ -73,7 +79,16 @@ fn main() -> Result<()> {
                 .last(true)
                 .help("Add \"-- <path> <arguments>\" to run"),
         )
+        .arg(util::aslr_arg())
+        .arg(util::near_null_arg())
+        .args(util::source_args())
+        .args(util::logging_args())
         .get_matches();
+    config::clear_env();
+
+    // Init log.
+    util::initialize_logging(&matches);
+    util::init_near_null_threshold(&matches);
 
     init_ignored_frames!("python", "cpp");
     if let Some(path) = matches.get_one::<PathBuf>("ignore") {
@@ -100,12 +115,19 @@ fn main() -> Result<()> {
     if argv.len() > 1 {
         python_cmd.args(&argv[1..]);
     }
+    let aslr = util::aslr_from_matches(&matches);
+    if aslr == Some(false) {
+        util::disable_aslr(&mut python_cmd);
+    }
     let python_result = util::get_output(&mut python_cmd, timeout, true)?;
 
     let python_stderr = String::from_utf8_lossy(&python_result.stderr);
 
     // Create report.
     let mut report = CrashReport::new();
+    if let Some(aslr) = aslr {
+        report.aslr = if aslr { "on" } else { "off" }.to_string();
+    }
     report.executable_path = argv[0].to_string();
     if argv.len() > 1 {
         if let Some(fname) = Path::new(argv[0]).file_name() {
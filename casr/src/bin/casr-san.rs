@@ -1,39 +1,467 @@
-use casr::util;
+use casr::config;
+#[cfg(unix)]
+use casr::gdb::GdbCommand;
+#[cfg(target_os = "macos")]
+use casr::lldb;
+use casr::qemu::{self, QemuUser};
+use casr::sandbox::{self, Sandbox};
+use casr::util::{self, TargetEnv};
+#[cfg(windows)]
+use libcasr::cdb::{CdbContext, CdbStacktrace};
+#[cfg(all(unix, not(target_os = "macos")))]
+use libcasr::constants::{
+    SIGINFO_SIGABRT, SIGINFO_SIGBUS, SIGINFO_SIGILL, SIGINFO_SIGSEGV, SIGINFO_SIGSYS,
+    SIGINFO_SIGTRAP,
+};
+#[cfg(unix)]
+use libcasr::gdb::exploitable::GdbContext;
 use libcasr::{
-    asan::{AsanContext, AsanStacktrace},
-    constants::{
-        SIGINFO_SIGABRT, SIGINFO_SIGBUS, SIGINFO_SIGILL, SIGINFO_SIGSEGV, SIGINFO_SIGSYS,
-        SIGINFO_SIGTRAP,
-    },
-    cpp::CppException,
-    exception::Exception,
     execution_class::*,
-    gdb::*,
-    go::*,
+    gdb::GdbStacktrace,
     init_ignored_frames,
-    report::CrashReport,
-    rust::{RustPanic, RustStacktrace},
+    report::{CrashReport, ResourceUsage},
     severity::Severity,
     stacktrace::*,
+    ubsan,
 };
 
 use anyhow::{bail, Context, Result};
-use clap::{Arg, ArgAction, ArgGroup};
-use gdb_command::mappings::{MappedFiles, MappedFilesExt};
-use gdb_command::stacktrace::StacktraceExt;
-use gdb_command::*;
-use linux_personality::personality;
+use clap::{builder::FalseyValueParser, Arg, ArgAction, ArgGroup, ArgMatches};
+#[cfg(unix)]
+use gdb_command::{mappings::MappedFiles, memory::*, registers::*, siginfo::Siginfo, *};
+#[cfg(all(unix, not(target_os = "macos")))]
+use gdb_command::{mappings::MappedFilesExt, stacktrace::StacktraceExt};
+use log::{debug, warn};
 use regex::Regex;
 
-use std::env;
-use std::os::unix::process::{CommandExt, ExitStatusExt};
-use std::path::PathBuf;
-use std::process::Command;
+#[cfg(target_os = "linux")]
+use std::os::unix::process::CommandExt;
+#[cfg(unix)]
+use std::os::unix::process::ExitStatusExt;
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitStatus};
+use std::time::Instant;
+
+/// Replace missing module paths in sanitizer stack trace frames with local
+/// binary path when their file names match (e.g., log is captured on another
+/// host), so that frames can be symbolized
+///
+/// # Arguments
+///
+/// * `output` - sanitizer log
+///
+/// * `binary` - local binary path
+fn relocate_modules(output: &str, binary: &Path) -> String {
+    let Some(name) = binary.file_name() else {
+        return output.to_string();
+    };
+    let rmodule = Regex::new(r"\(([^()\s]+)\+0x[0-9a-fA-F]+\)").unwrap();
+    rmodule
+        .replace_all(output, |caps: &regex::Captures| {
+            let frame = caps.get(0).unwrap().as_str();
+            let module = Path::new(caps.get(1).unwrap().as_str());
+            if module.exists() || module.file_name() != Some(name) {
+                frame.to_string()
+            } else {
+                frame.replacen(
+                    caps.get(1).unwrap().as_str(),
+                    &binary.display().to_string(),
+                    1,
+                )
+            }
+        })
+        .to_string()
+}
+
+/// Create report from captured sanitizer or Valgrind log without running target
+///
+/// # Arguments
+///
+/// * `log` - sanitizer log path
+///
+/// * `argv` - target program argument vector (may be empty), binary is used
+///   for symbolization
+///
+/// * `matches` - casr-san options
+fn parse_log(log: &Path, argv: &[&str], matches: &ArgMatches) -> Result<()> {
+    let data =
+        std::fs::read(log).with_context(|| format!("Couldn't read log {}", log.display()))?;
+    let mut output = String::from_utf8_lossy(&data).to_string();
+    let mut report = CrashReport::new();
+    if let Some(binary) = argv.first() {
+        output = relocate_modules(&output, Path::new(binary));
+        report.executable_path = binary.to_string();
+        report.proc_cmdline = argv.join(" ");
+    }
+    if let Some(mut file_path) = util::stdin_from_matches(matches)? {
+        file_path = file_path.canonicalize().unwrap_or(file_path);
+        report.stdin = file_path.display().to_string();
+    }
+    report.stderr = output.lines().map(|l| l.to_string()).collect();
+
+    // Target is not run, so UBSAN runtime errors are considered to be the
+    // reason of failure.
+    let Some(stacktrace) = report.set_from_san_log(&output, false)? else {
+        bail!(
+            "Couldn't find sanitizer report or runtime error in {}",
+            log.display()
+        );
+    };
+    report.complete_from_output(&stacktrace, &output);
+
+    let log = log.to_string_lossy();
+    let log = log.as_ref();
+    let executable = argv.first().copied().unwrap_or(log);
+    util::output_report(&report, matches, &[executable, log])
+}
+
+/// Re-run target under gdb to refine execution class of libFuzzer deadly
+/// signal crash with signal info, registers and disassembly
+///
+/// # Arguments
+///
+/// * `report` - report with libFuzzer deadly signal error
+///
+/// * `argv` - target program argument vector
+///
+/// * `stdin` - stdin file for target program
+///
+/// * `qemu` - QEMU user-mode emulator to run target under
+///
+/// * `target_env` - target environment
+///
+/// * `timeout` - target program timeout
+///
+/// * `debugger_timeout` - gdb timeout
+///
+/// * `aslr` - enable ASLR for target
+#[cfg(unix)]
+#[allow(clippy::too_many_arguments)]
+fn gdb_followup(
+    report: &mut CrashReport,
+    argv: &[&str],
+    stdin: &Option<PathBuf>,
+    qemu: Option<&QemuUser>,
+    target_env: &TargetEnv,
+    timeout: u64,
+    debugger_timeout: u64,
+    aslr: bool,
+) -> Result<()> {
+    let machine = util::machine_info(Path::new(argv[0]))?;
+    let result = if let Some(qemu) = qemu {
+        qemu.gdb_output(argv, stdin, debugger_timeout, |gdb_command| {
+            gdb_command
+                .siginfo()
+                .mappings()
+                .regs()
+                .mem("$pc", 64)
+                .disassembly();
+        })?
+        .map(|(result, _)| result)
+    } else {
+        let exectype = ExecType::Local(argv);
+        let mut gdb_command = GdbCommand::new(&exectype);
+        gdb_command.timeout(timeout).stdin(stdin);
+        let env_commands =
+            target_env.set_gdb_env(&mut gdb_command) + util::set_gdb_aslr(&mut gdb_command, aslr);
+        let gdb_command = gdb_command
+            .r()
+            .siginfo()
+            .mappings()
+            .regs()
+            .mem("$pc", 64)
+            .disassembly();
+        util::gdb_output(gdb_command, debugger_timeout)
+            .with_context(|| "Unable to get results from gdb")?
+            .map(|output| gdb_command.parse(String::from_utf8_lossy(&output)))
+            .transpose()?
+            .map(|mut result| result.split_off(env_commands))
+    };
+    let Some(result) = result else {
+        bail!("gdb timeout ({} s)", debugger_timeout);
+    };
+
+    let context = GdbContext {
+        siginfo: Siginfo::from_gdb(&result[0])?,
+        // QEMU gdb stub may not provide process mappings.
+        mappings: match MappedFiles::from_gdb(&result[1]) {
+            Err(_) if qemu.is_some() => MappedFiles::new(),
+            mappings => mappings?,
+        },
+        registers: Registers::from_gdb(&result[2])?,
+        pc_memory: MemoryObject::from_gdb(&result[3])?,
+        machine,
+        stacktrace: report.stacktrace.clone(),
+    };
+    report.execution_class = context.severity()?;
+    report.registers = context.registers;
+    report.set_disassembly(&result[4]);
+    Ok(())
+}
+
+/// Get execution class and stack trace from gdb when there is no sanitizer
+/// report
+///
+/// # Arguments
+///
+/// * `report` - report to fill
+///
+/// * `argv` - target program argument vector
+///
+/// * `stdin` - stdin file for target program
+///
+/// * `qemu` - QEMU user-mode emulator to run target under
+///
+/// * `target_env` - target environment
+///
+/// * `timeout` - target program timeout
+///
+/// * `debugger_timeout` - debugger timeout
+///
+/// * `status` - target program exit status
+///
+/// * `aslr` - enable ASLR for target
+///
+/// # Return value
+///
+/// Parsed stack trace
+#[cfg(all(unix, not(target_os = "macos")))]
+#[allow(clippy::too_many_arguments)]
+fn debugger_fallback(
+    report: &mut CrashReport,
+    argv: &[&str],
+    stdin: &Option<PathBuf>,
+    qemu: Option<&QemuUser>,
+    target_env: &TargetEnv,
+    timeout: u64,
+    debugger_timeout: u64,
+    status: &ExitStatus,
+    aslr: bool,
+) -> Result<Stacktrace> {
+    // Get termination signal.
+    let Some(signal) = status.signal() else {
+        // Normal termination.
+        bail!("Program terminated (no crash)");
+    };
+    match signal as u32 {
+        SIGINFO_SIGILL | SIGINFO_SIGSYS => {
+            report.execution_class = ExecutionClass::find("BadInstruction").unwrap();
+        }
+        SIGINFO_SIGTRAP => {
+            report.execution_class = ExecutionClass::find("TrapSignal").unwrap();
+        }
+        SIGINFO_SIGABRT => {
+            report.execution_class = ExecutionClass::find("AbortSignal").unwrap();
+        }
+        SIGINFO_SIGBUS | SIGINFO_SIGSEGV => {
+            warn!("Segmentation fault occurred, but there is not enough information available to determine \
+            exploitability. Try using casr-gdb instead.");
+            report.execution_class = ExecutionClass::find("AccessViolation").unwrap();
+        }
+        _ => {
+            // "Undefined" is by default in report.
+        }
+    }
+
+    // Get stack trace and mappings from gdb.
+    debug!("No sanitizer report found, getting stack trace from gdb");
+    let exectype = ExecType::Local(argv);
+    let gdb_results = |with_mappings: bool| -> Result<Option<Vec<String>>> {
+        if let Some(qemu) = qemu {
+            return Ok(qemu
+                .gdb_output(argv, stdin, debugger_timeout, |gdb_command| {
+                    gdb_command.bt();
+                    if with_mappings {
+                        gdb_command.mappings();
+                    }
+                })?
+                .map(|(result, _)| result));
+        }
+        let mut gdb_command = GdbCommand::new(&exectype);
+        gdb_command.timeout(timeout).stdin(stdin);
+        let env_commands =
+            target_env.set_gdb_env(&mut gdb_command) + util::set_gdb_aslr(&mut gdb_command, aslr);
+        let gdb_command = gdb_command.r().bt();
+        if with_mappings {
+            gdb_command.mappings();
+        }
+        Ok(util::gdb_output(gdb_command, debugger_timeout)
+            .with_context(|| "Unable to get results from gdb")?
+            .map(|output| gdb_command.parse(String::from_utf8_lossy(&output)))
+            .transpose()?
+            .map(|mut result| result.split_off(env_commands)))
+    };
+    let gdb_result = if let Some(result) = gdb_results(true)? {
+        result
+    } else {
+        // Retry to get stack trace only.
+        let Some(result) = gdb_results(false)? else {
+            bail!("gdb timeout ({} s)", debugger_timeout);
+        };
+        report.partial_data =
+            format!("gdb timeout ({debugger_timeout} s), only stack trace is collected");
+        result
+    };
+
+    let frame = Regex::new(r"^ *#[0-9]+").unwrap();
+    report.stacktrace = gdb_result[0]
+        .split('\n')
+        .filter(|x| frame.is_match(x))
+        .map(|x| x.to_string())
+        .collect::<Vec<String>>();
+    if let Some(mappings) = gdb_result.get(1) {
+        report.proc_maps = mappings
+            .split('\n')
+            .skip(4)
+            .map(|x| x.to_string())
+            .collect::<Vec<String>>();
+    }
+
+    report.symbolize_stacktrace();
+    let mut parsed_stacktrace = GdbStacktrace::parse_stacktrace(&report.stacktrace)?;
+    if let Ok(mfiles) = MappedFiles::from_gdb(report.proc_maps.join("\n")) {
+        parsed_stacktrace.compute_module_offsets(&mfiles);
+    }
+    Ok(parsed_stacktrace)
+}
+
+/// Get execution class and stack trace from lldb when there is no sanitizer
+/// report
+///
+/// # Arguments
+///
+/// * `report` - report to fill
+///
+/// * `argv` - target program argument vector
+///
+/// * `stdin` - stdin file for target program
+///
+/// * `_qemu` - QEMU user-mode emulator (not supported)
+///
+/// * `target_env` - target environment
+///
+/// * `timeout` - target program timeout
+///
+/// * `debugger_timeout` - debugger timeout
+///
+/// * `status` - target program exit status
+///
+/// * `aslr` - enable ASLR for target
+///
+/// # Return value
+///
+/// Parsed stack trace
+#[cfg(target_os = "macos")]
+#[allow(clippy::too_many_arguments)]
+fn debugger_fallback(
+    report: &mut CrashReport,
+    argv: &[&str],
+    stdin: &Option<PathBuf>,
+    _qemu: Option<&QemuUser>,
+    target_env: &TargetEnv,
+    timeout: u64,
+    debugger_timeout: u64,
+    status: &ExitStatus,
+    aslr: bool,
+) -> Result<Stacktrace> {
+    if status.signal().is_none() {
+        // Normal termination.
+        bail!("Program terminated (no crash)");
+    }
+    debug!("No sanitizer report found, getting stack trace from lldb");
+    let timeout = if debugger_timeout != 0 {
+        debugger_timeout
+    } else {
+        timeout
+    };
+    lldb::lldb_report(report, argv, stdin, target_env, timeout, aslr)
+}
+
+/// Get execution class and stack trace from cdb when there is no sanitizer
+/// report
+///
+/// # Arguments
+///
+/// * `report` - report to fill
+///
+/// * `argv` - target program argument vector
+///
+/// * `stdin` - stdin file for target program
+///
+/// * `_qemu` - QEMU user-mode emulator (not supported)
+///
+/// * `target_env` - target environment
+///
+/// * `timeout` - target program timeout
+///
+/// * `debugger_timeout` - debugger timeout
+///
+/// * `status` - target program exit status
+///
+/// * `_aslr` - enable ASLR for target (not supported)
+///
+/// # Return value
+///
+/// Parsed stack trace
+#[cfg(windows)]
+#[allow(clippy::too_many_arguments)]
+fn debugger_fallback(
+    report: &mut CrashReport,
+    argv: &[&str],
+    stdin: &Option<PathBuf>,
+    _qemu: Option<&QemuUser>,
+    target_env: &TargetEnv,
+    timeout: u64,
+    debugger_timeout: u64,
+    status: &ExitStatus,
+    _aslr: bool,
+) -> Result<Stacktrace> {
+    // Exception codes (NTSTATUS) have severity bits set.
+    let Some(code) = status.code().filter(|code| *code as u32 & 0x80000000 != 0) else {
+        // Normal termination.
+        bail!("Program terminated (no crash)");
+    };
+    debug!(
+        "No sanitizer report found, exception code {:#x}, getting stack trace from cdb",
+        code as u32
+    );
+    let mut cdb_cmd = Command::new("cdb");
+    cdb_cmd.args(["-g", "-c", ".exr -1; kn 100; q"]).args(argv);
+    // Target inherits cdb environment.
+    target_env.apply(&mut cdb_cmd);
+    if let Some(file) = stdin {
+        cdb_cmd.stdin(std::fs::File::open(file)?);
+    }
+    let timeout = if debugger_timeout != 0 {
+        debugger_timeout
+    } else {
+        timeout
+    };
+    let cdb_result = util::get_output(&mut cdb_cmd, timeout, true)
+        .with_context(|| "Unable to get results from cdb")?;
+    let cdb_stdout = String::from_utf8_lossy(&cdb_result.stdout);
+
+    let context = CdbContext(
+        cdb_stdout
+            .split('\n')
+            .map(|l| l.trim_end().to_string())
+            .collect(),
+    );
+    match context.severity() {
+        Ok(class) => report.execution_class = class,
+        Err(error) => warn!("Couldn't estimate severity. {error}"),
+    }
+    report.stacktrace = CdbStacktrace::extract_stacktrace(&cdb_stdout)?;
+    Ok(CdbStacktrace::parse_stacktrace(&report.stacktrace)?)
+}
 
 fn main() -> Result<()> {
+    // Load configuration files.
+    config::load_config("casr-san")?;
+
     let matches = clap::Command::new("casr-san")
         .version(clap::crate_version!())
-        .about("Create CASR reports (.casrep) from AddressSanitizer reports")
+        .about("Create CASR reports (.casrep) from AddressSanitizer, ThreadSanitizer, MemorySanitizer, and UndefinedBehaviorSanitizer reports")
         .term_width(90)
         .arg(
             Arg::new("output")
@@ -65,10 +493,19 @@ fn main() -> Result<()> {
                 .value_parser(clap::value_parser!(PathBuf))
                 .help("Stdin file for program"),
         )
+        .arg(
+            Arg::new("parse")
+                .long("parse")
+                .action(ArgAction::Set)
+                .value_name("LOG")
+                .value_parser(clap::value_parser!(PathBuf))
+                .help("Create report from captured sanitizer or Valgrind Memcheck (text or XML) log instead of running target (binary from ARGS is optional and used for symbolization)"),
+        )
         .arg(
             Arg::new("timeout")
                 .short('t')
                 .long("timeout")
+                .env("CASR_TIMEOUT")
                 .action(ArgAction::Set)
                 .default_value("0")
                 .value_name("SECONDS")
@@ -78,11 +515,65 @@ fn main() -> Result<()> {
         .arg(
             Arg::new("ignore")
                 .long("ignore")
+                .env("CASR_IGNORE")
                 .action(ArgAction::Set)
                 .value_name("FILE")
                 .value_parser(clap::value_parser!(PathBuf))
                 .help("File with regular expressions for functions and file paths that should be ignored"),
         )
+        .arg(
+            Arg::new("gdb-followup")
+                .long("gdb-followup")
+                .env("CASR_GDB_FOLLOWUP")
+                .action(ArgAction::SetTrue)
+                .value_parser(FalseyValueParser::new())
+                .help("Re-run target under gdb for libFuzzer deadly signal to refine execution class"),
+        )
+        .arg(
+            Arg::new("allow-recovered")
+                .long("allow-recovered")
+                .env("CASR_ALLOW_RECOVERED")
+                .action(ArgAction::SetTrue)
+                .value_parser(FalseyValueParser::new())
+                .help("Create report from recoverable sanitizer warnings (e.g., with halt_on_error=0) when target isn't terminated"),
+        )
+        .arg(
+            Arg::new("hang-stack")
+                .long("hang-stack")
+                .env("CASR_HANG_STACK")
+                .action(ArgAction::SetTrue)
+                .value_parser(FalseyValueParser::new())
+                .help("On timeout, get stack trace of the hung target with gdb and create timeout report"),
+        )
+        .arg(
+            Arg::new("memory-limit")
+                .long("memory-limit")
+                .env("CASR_MEMORY_LIMIT")
+                .action(ArgAction::Set)
+                .default_value("0")
+                .value_name("MB")
+                .help("Memory limit (in MB) for target execution: hard_rss_limit_mb for sanitized targets, address space limit otherwise, 0 value means default limit (2048 MB of rss for sanitized targets, no limit otherwise)")
+                .value_parser(clap::value_parser!(u64).range(0..))
+        )
+        .arg(
+            Arg::new("core-limit")
+                .long("core-limit")
+                .env("CASR_CORE_LIMIT")
+                .action(ArgAction::Set)
+                .value_name("MB")
+                .help("Core file size limit (in MB) for target execution, 0 value disables core dumps [default: inherited]")
+                .value_parser(clap::value_parser!(u64).range(0..))
+        )
+        .arg(
+            Arg::new("max-stderr-size")
+                .long("max-stderr-size")
+                .env("CASR_MAX_STDERR_SIZE")
+                .action(ArgAction::Set)
+                .default_value("0")
+                .value_name("MB")
+                .help("Maximum size (in MB) of target stdout and stderr tails kept in memory, the first sanitizer report is kept as well, 0 value means that output isn't limited")
+                .value_parser(clap::value_parser!(u64).range(0..))
+        )
         .arg(
             Arg::new("ARGS")
                 .action(ArgAction::Set)
@@ -90,11 +581,28 @@ fn main() -> Result<()> {
                 .last(true)
                 .help("Add \"-- ./binary <arguments>\" to run executable"),
         )
+        .args(util::target_env_args())
+        .args(qemu::qemu_args())
+        .args(sandbox::sandbox_args())
+        .arg(util::aslr_arg())
+        .arg(util::debugger_timeout_arg())
+        .arg(util::near_null_arg())
+        .args(util::input_args())
+        .args(util::source_args())
+        .args(util::logging_args())
         .get_matches();
+    config::clear_env();
+
+    // Init log.
+    util::initialize_logging(&matches);
+    util::init_near_null_threshold(&matches);
 
     // Get program args.
+    let log = matches.get_one::<PathBuf>("parse");
     let argv: Vec<&str> = if let Some(argvs) = matches.get_many::<String>("ARGS") {
         argvs.map(|s| s.as_str()).collect()
+    } else if log.is_some() {
+        Vec::new()
     } else {
         bail!("Wrong arguments for starting program");
     };
@@ -104,44 +612,185 @@ fn main() -> Result<()> {
     if let Some(path) = matches.get_one::<PathBuf>("ignore") {
         util::add_custom_ignored_frames(path)?;
     }
+
+    if let Some(log) = log {
+        return parse_log(log, &argv, &matches);
+    }
+    if let Some(sandbox) = Sandbox::from_matches(&matches)? {
+        return sandbox.reexec(&matches);
+    }
     // Get stdin for target program.
     let stdin_file = util::stdin_from_matches(&matches)?;
+    // Foreign architecture target is run under QEMU user-mode emulator.
+    let mut qemu = QemuUser::from_matches(&matches)?;
+    let mut target_env = TargetEnv::from_matches(&matches);
+    #[cfg(any(target_os = "macos", windows))]
+    if qemu.is_some() {
+        bail!("QEMU user mode is not supported on this platform");
+    }
 
     // Get timeout
     let timeout = *matches.get_one::<u64>("timeout").unwrap();
+    // Debugger is launched with target, so target timeout is added.
+    let debugger_timeout = match *matches.get_one::<u64>("debugger-timeout").unwrap() {
+        0 => 0,
+        debugger_timeout => debugger_timeout + timeout,
+    };
+
+    // Sanitizers reserve huge shadow memory, so their memory usage is limited
+    // by rss instead of address space.
+    let memory_limit = *matches.get_one::<u64>("memory-limit").unwrap();
+    // Target output is read while target is running, so that targets that
+    // print gigabytes before crash don't exhaust memory.
+    let max_output_size = (*matches.get_one::<u64>("max-stderr-size").unwrap() << 20) as usize;
+    #[cfg(unix)]
+    let sanitized = util::symbols_list(Path::new(argv[0])).is_ok_and(|symbols| {
+        ["__asan", "__msan", "__tsan"]
+            .iter()
+            .any(|symbol| symbols.contains(symbol))
+    });
+    #[cfg(not(unix))]
+    let sanitized = true;
+    let rss_limit = if sanitized && memory_limit != 0 {
+        memory_limit
+    } else {
+        2048
+    };
 
     // Set rss limit.
-    if let Ok(asan_options_str) = env::var("ASAN_OPTIONS") {
+    if let Some(asan_options_str) = target_env.var("ASAN_OPTIONS") {
         let mut asan_options = asan_options_str.clone();
         if !asan_options_str.contains("hard_rss_limit_mb") {
-            asan_options = [asan_options.as_str(), "hard_rss_limit_mb=2048"].join(",");
+            asan_options = format!("{asan_options},hard_rss_limit_mb={rss_limit}");
         }
         if asan_options.starts_with(',') {
             asan_options.remove(0);
         }
         asan_options = asan_options.replace("symbolize=0", "symbolize=1");
-        std::env::set_var("ASAN_OPTIONS", asan_options);
+        target_env.set_var("ASAN_OPTIONS", &asan_options);
     } else {
-        std::env::set_var("ASAN_OPTIONS", "hard_rss_limit_mb=2048");
+        target_env.set_var("ASAN_OPTIONS", &format!("hard_rss_limit_mb={rss_limit}"));
+    }
+    // Report UBSAN error types in summary to estimate execution class.
+    if let Some(mut ubsan_options) = target_env.var("UBSAN_OPTIONS") {
+        if ubsan_options.contains("report_error_type=0") {
+            ubsan_options = ubsan_options.replace("report_error_type=0", "report_error_type=1");
+        } else if !ubsan_options.contains("report_error_type=1") {
+            ubsan_options.push_str(",report_error_type=1");
+        }
+        if ubsan_options.starts_with(',') {
+            ubsan_options.remove(0);
+        }
+        target_env.set_var("UBSAN_OPTIONS", &ubsan_options);
+    } else {
+        target_env.set_var("UBSAN_OPTIONS", "report_error_type=1");
+    }
+    if let Some(qemu) = qemu.as_mut() {
+        qemu.env = target_env.clone();
     }
 
     // Run program with sanitizers.
-    let mut sanitizers_cmd = Command::new(argv[0]);
+    let mut sanitizers_cmd = if let Some(qemu) = &qemu {
+        qemu.command(&argv, None)
+    } else {
+        let mut cmd = Command::new(argv[0]);
+        cmd.args(&argv[1..]);
+        cmd
+    };
+    target_env.apply(&mut sanitizers_cmd);
     if let Some(ref file) = stdin_file {
         sanitizers_cmd.stdin(std::fs::File::open(file).unwrap());
     }
-    if argv.len() > 1 {
-        sanitizers_cmd.args(&argv[1..]);
+    let mut hang_stack = matches.get_flag("hang-stack");
+    if hang_stack && qemu.is_some() {
+        warn!("Getting stack trace of hung target is not supported under QEMU");
+        hang_stack = false;
+    }
+    if hang_stack && timeout == 0 {
+        warn!("--hang-stack has no effect without --timeout");
+        hang_stack = false;
     }
-    let sanitizers_cmd = unsafe {
-        sanitizers_cmd.pre_exec(|| {
-            if personality(linux_personality::ADDR_NO_RANDOMIZE).is_err() {
-                panic!("Cannot set personality");
+    // ASLR is disabled by default, so that addresses are reproducible (on
+    // macOS it is disabled via posix_spawn below).
+    let aslr = util::aslr_from_matches(&matches).unwrap_or(false);
+    if !aslr && cfg!(any(target_os = "linux", target_os = "freebsd")) {
+        util::disable_aslr(&mut sanitizers_cmd);
+    }
+    #[cfg(target_os = "linux")]
+    if hang_stack {
+        unsafe {
+            sanitizers_cmd.pre_exec(|| {
+                // Allow gdb to attach to the hung target.
+                libc::prctl(libc::PR_SET_PTRACER, libc::PR_SET_PTRACER_ANY, 0, 0, 0);
+                Ok(())
+            });
+        }
+    }
+    #[cfg(unix)]
+    let address_space_limit = if sanitized { 0 } else { memory_limit };
+    #[cfg(unix)]
+    util::set_resource_limits(
+        &mut sanitizers_cmd,
+        address_space_limit,
+        matches.get_one::<u64>("core-limit").copied(),
+    );
+    debug!("Running {:?}", sanitizers_cmd);
+    #[cfg(unix)]
+    let usage_before = util::children_resource_usage();
+    let start = Instant::now();
+    // There is no personality syscall on macOS, so ASLR is disabled via posix_spawn.
+    #[cfg(target_os = "macos")]
+    let (sanitizers_result, hang_stacktrace) = {
+        if hang_stack {
+            warn!("Getting stack trace of hung target is not supported on macOS");
+        }
+        // Target is spawned with environment of current process.
+        if target_env.clear {
+            warn!("Clearing target environment is not supported on macOS");
+        }
+        for (key, value) in &target_env.vars {
+            std::env::set_var(key, value);
+        }
+        if aslr && max_output_size != 0 {
+            (
+                util::get_capped_output(&mut sanitizers_cmd, timeout, max_output_size)?,
+                None,
+            )
+        } else if aslr {
+            (util::get_output(&mut sanitizers_cmd, timeout, true)?, None)
+        } else {
+            if max_output_size != 0 {
+                warn!("Limiting target output is supported on macOS only with --aslr on");
             }
-            Ok(())
-        })
+            (
+                util::get_output_without_aslr(&argv, &stdin_file, timeout)?,
+                None,
+            )
+        }
+    };
+    #[cfg(not(target_os = "macos"))]
+    let (sanitizers_result, hang_stacktrace) = if hang_stack {
+        util::get_output_with_hang_stack(&mut sanitizers_cmd, timeout, max_output_size)?
+    } else if max_output_size != 0 {
+        (
+            util::get_capped_output(&mut sanitizers_cmd, timeout, max_output_size)?,
+            None,
+        )
+    } else {
+        (util::get_output(&mut sanitizers_cmd, timeout, true)?, None)
+    };
+    let mut resource_usage = ResourceUsage {
+        wall_time: start.elapsed().as_millis() as u64,
+        ..Default::default()
     };
-    let sanitizers_result = util::get_output(sanitizers_cmd, timeout, true)?;
+    #[cfg(unix)]
+    {
+        let usage_after = util::children_resource_usage();
+        resource_usage.user_time = usage_after.0.saturating_sub(usage_before.0);
+        resource_usage.system_time = usage_after.1.saturating_sub(usage_before.1);
+        resource_usage.max_rss = usage_after.2;
+    }
+    debug!("Resource usage: {:?}", resource_usage);
     let sanitizers_stderr = String::from_utf8_lossy(&sanitizers_result.stderr);
 
     if sanitizers_stderr.contains("Cannot set personality") {
@@ -155,6 +804,13 @@ fn main() -> Result<()> {
     if sanitizers_stderr.contains("AddressSanitizer: out-of-memory") {
         bail!("Out of memory");
     }
+    #[cfg(unix)]
+    if address_space_limit != 0
+        && (sanitizers_stderr.contains("std::bad_alloc")
+            || sanitizers_stderr.contains("memory allocation of"))
+    {
+        bail!("Out of memory: memory limit exceeded");
+    }
 
     // Create report.
     let mut report = CrashReport::new();
@@ -162,129 +818,83 @@ fn main() -> Result<()> {
     report.proc_cmdline = argv.join(" ");
     let _ = report.add_os_info();
     let _ = report.add_proc_environ();
+    target_env.update_environ(&mut report.proc_environ);
+    report.resource_usage = resource_usage;
+    if cfg!(unix) {
+        report.aslr = if aslr { "on" } else { "off" }.to_string();
+    }
+    report.stdout = String::from_utf8_lossy(&sanitizers_result.stdout)
+        .lines()
+        .map(|l| l.to_string())
+        .collect();
+    report.stderr = sanitizers_stderr.lines().map(|l| l.to_string()).collect();
+    report.exit_status = sanitizers_result.status.code();
+    #[cfg(unix)]
+    {
+        report.signal = sanitizers_result.status.signal();
+    }
     if let Some(mut file_path) = stdin_file.clone() {
         file_path = file_path.canonicalize().unwrap_or(file_path);
         report.stdin = file_path.display().to_string();
     }
 
+    // Target may crash after UBSAN warnings (e.g., on null pointer dereference).
+    #[cfg(unix)]
+    let crashed = sanitizers_result.status.signal().is_some();
+    #[cfg(not(unix))]
+    let crashed = false;
     let stacktrace: Stacktrace;
-    if let Ok(raw_stacktrace) = GoStacktrace::extract_stacktrace(&sanitizers_stderr) {
-        // If it is possible to extract Go stacktrace, it is Go.
+    if let Some(raw_stacktrace) = hang_stacktrace {
+        // Target is hung, use stack trace obtained from gdb before killing it.
+        debug!("Found stack trace of hung target");
+        report.execution_class = ExecutionClass::find("timeout").unwrap();
         report.stacktrace = raw_stacktrace;
-        stacktrace = GoStacktrace::parse_stacktrace(&report.stacktrace)?;
-        report.go_report = sanitizers_stderr
-            .split('\n')
-            .map(|l| l.trim_end().to_string())
-            .collect();
-        if let Some(exception) = GoPanic::parse_exception(&sanitizers_stderr) {
-            report.execution_class = exception;
+        stacktrace = GdbStacktrace::parse_stacktrace(&report.stacktrace)?;
+    } else if let Some(parsed_stacktrace) = report.set_from_san_log(&sanitizers_stderr, crashed)? {
+        if report.recovered && !matches.get_flag("allow-recovered") {
+            bail!("Program terminated (no crash): found recoverable sanitizer warnings, use --allow-recovered to analyze them");
         }
-    } else if let Ok(raw_stacktrace) = RustStacktrace::extract_stacktrace(&sanitizers_stderr) {
-        // If it is possible to extract Rust stacktrace, it is Rust.
-        report.stacktrace = raw_stacktrace;
-        stacktrace = RustStacktrace::parse_stacktrace(&report.stacktrace)?;
-        report.rust_report = sanitizers_stderr
-            .split('\n')
-            .map(|l| l.trim_end().to_string())
-            .collect();
-    } else {
-        // Get ASAN report.
-        let san_stderr_list: Vec<String> = sanitizers_stderr
-            .split('\n')
-            .map(|l| l.trim_end().to_string())
-            .collect();
-        let rasan_start =
-            Regex::new(r"==\d+==\s*ERROR: (LeakSanitizer|AddressSanitizer|libFuzzer):").unwrap();
-        if let Some(report_start) = san_stderr_list
-            .iter()
-            .position(|line| rasan_start.is_match(line))
+        #[cfg(unix)]
+        if matches.get_flag("gdb-followup")
+            && report
+                .asan_report
+                .first()
+                .is_some_and(|line| line.contains("ERROR: libFuzzer: deadly signal"))
         {
-            // Set ASAN report in casr report.
-            let report_end = san_stderr_list.iter().rposition(|s| !s.is_empty()).unwrap() + 1;
-            report.asan_report = Vec::from(&san_stderr_list[report_start..report_end]);
-            let context = AsanContext(report.asan_report.clone());
-            report.execution_class = context.severity()?;
-            report.stacktrace = AsanStacktrace::extract_stacktrace(&report.asan_report.join("\n"))?;
-        } else {
-            // Get termination signal.
-            if let Some(signal) = sanitizers_result.status.signal() {
-                // Get stack trace and mappings from gdb.
-                match signal as u32 {
-                    SIGINFO_SIGILL | SIGINFO_SIGSYS => {
-                        report.execution_class = ExecutionClass::find("BadInstruction").unwrap();
-                    }
-                    SIGINFO_SIGTRAP => {
-                        report.execution_class = ExecutionClass::find("TrapSignal").unwrap();
-                    }
-                    SIGINFO_SIGABRT => {
-                        report.execution_class = ExecutionClass::find("AbortSignal").unwrap();
-                    }
-                    SIGINFO_SIGBUS | SIGINFO_SIGSEGV => {
-                        eprintln!("Segmentation fault occurred, but there is not enough information available to determine \
-                        exploitability. Try using casr-gdb instead.");
-                        report.execution_class = ExecutionClass::find("AccessViolation").unwrap();
-                    }
-                    _ => {
-                        // "Undefined" is by default in report.
-                    }
-                }
-
-                // Get stack trace and mappings from gdb.
-                let gdb_result = GdbCommand::new(&ExecType::Local(&argv))
-                    .timeout(timeout)
-                    .stdin(&stdin_file)
-                    .r()
-                    .bt()
-                    .mappings()
-                    .launch()
-                    .with_context(|| "Unable to get results from gdb")?;
-
-                let frame = Regex::new(r"^ *#[0-9]+").unwrap();
-                report.stacktrace = gdb_result[0]
-                    .split('\n')
-                    .filter(|x| frame.is_match(x))
-                    .map(|x| x.to_string())
-                    .collect::<Vec<String>>();
-                report.proc_maps = gdb_result[1]
-                    .split('\n')
-                    .skip(4)
-                    .map(|x| x.to_string())
-                    .collect::<Vec<String>>();
-            } else {
-                // Normal termination.
-                bail!("Program terminated (no crash)");
-            }
-        }
-
-        // Get stacktrace to find crash line.
-        stacktrace = if !report.asan_report.is_empty() {
-            AsanStacktrace::parse_stacktrace(&report.stacktrace)?
-        } else {
-            let mut parsed_stacktrace = GdbStacktrace::parse_stacktrace(&report.stacktrace)?;
-            if let Ok(mfiles) = MappedFiles::from_gdb(report.proc_maps.join("\n")) {
-                parsed_stacktrace.compute_module_offsets(&mfiles);
-            }
-            parsed_stacktrace
-        };
-    }
-
-    // Check for exceptions
-    if let Some(class) = [CppException::parse_exception, RustPanic::parse_exception]
-        .iter()
-        .find_map(|parse| parse(&sanitizers_stderr))
-    {
-        report.execution_class = class;
-    }
-
-    // Get crash line.
-    if let Ok(crash_line) = stacktrace.crash_line() {
-        report.crashline = crash_line.to_string();
-        if let CrashLine::Source(debug) = crash_line {
-            if let Some(sources) = CrashReport::sources(&debug) {
-                report.source = sources;
+            debug!("Running gdb to refine execution class of deadly signal");
+            if let Err(error) = gdb_followup(
+                &mut report,
+                &argv,
+                &stdin_file,
+                qemu.as_ref(),
+                &target_env,
+                timeout,
+                debugger_timeout,
+                aslr,
+            ) {
+                warn!("Couldn't refine execution class with gdb: {error}");
             }
         }
+        stacktrace = parsed_stacktrace;
+    } else {
+        stacktrace = debugger_fallback(
+            &mut report,
+            &argv,
+            &stdin_file,
+            qemu.as_ref(),
+            &target_env,
+            timeout,
+            debugger_timeout,
+            &sanitizers_result.status,
+            aslr,
+        )?;
+        // Attach UBSAN warnings printed before crash as secondary findings.
+        report.ubsan_warnings = ubsan::extract_ubsan_warnings(&sanitizers_stderr)
+            .iter()
+            .flat_map(|warning| warning.ubsan_report())
+            .collect();
     }
+    report.complete_from_output(&stacktrace, &sanitizers_stderr);
 
     util::output_report(&report, &matches, &argv)
 }
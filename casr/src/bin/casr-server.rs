@@ -0,0 +1,378 @@
+use casr::config;
+use casr::util;
+use libcasr::{init_ignored_frames, report::CrashReport, stacktrace::*};
+
+use anyhow::Context;
+use clap::{Arg, ArgAction};
+use log::{debug, error, info};
+use serde_json::json;
+use tiny_http::{Header, Method, Request, Response, Server};
+
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::thread;
+
+/// Server settings shared by workers.
+struct ServerConfig {
+    /// Maximum request body size in bytes.
+    max_size: u64,
+    /// Path to casr-core.
+    casr_core: PathBuf,
+    /// Path to casr-kernel.
+    casr_kernel: PathBuf,
+    /// Timeout (in seconds) for casr-core and casr-kernel.
+    timeout: u64,
+}
+
+/// HTTP error with status code and message.
+struct HttpError(u16, String);
+
+impl From<anyhow::Error> for HttpError {
+    fn from(error: anyhow::Error) -> Self {
+        HttpError(500, format!("{error:#}"))
+    }
+}
+
+type HttpResult<T> = std::result::Result<T, HttpError>;
+
+/// Decode percent-encoded query string component
+///
+/// # Arguments
+///
+/// * `value` - query string component
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let byte = match bytes[i] {
+            b'+' => b' ',
+            b'%' => {
+                if let Some(byte) = value
+                    .get(i + 1..i + 3)
+                    .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+                {
+                    i += 2;
+                    byte
+                } else {
+                    b'%'
+                }
+            }
+            byte => byte,
+        };
+        decoded.push(byte);
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).to_string()
+}
+
+/// Split request URL into path and query parameters
+///
+/// # Arguments
+///
+/// * `url` - request URL, e.g. "/san?executable=fuzzer"
+fn parse_url(url: &str) -> (&str, HashMap<String, String>) {
+    let (path, query) = url.split_once('?').unwrap_or((url, ""));
+    let params = query
+        .split('&')
+        .filter(|param| !param.is_empty())
+        .map(|param| {
+            let (name, value) = param.split_once('=').unwrap_or((param, ""));
+            (percent_decode(name), percent_decode(value))
+        })
+        .collect();
+    (path, params)
+}
+
+/// Read request body
+///
+/// # Arguments
+///
+/// * `request` - HTTP request
+///
+/// * `max_size` - maximum body size
+fn read_body(request: &mut Request, max_size: u64) -> HttpResult<Vec<u8>> {
+    let mut body = Vec::new();
+    request
+        .as_reader()
+        .take(max_size + 1)
+        .read_to_end(&mut body)
+        .map_err(|e| HttpError(400, format!("Couldn't read request body: {e}")))?;
+    if body.len() as u64 > max_size {
+        return Err(HttpError(
+            413,
+            format!("Request body is larger than {max_size} bytes"),
+        ));
+    }
+    if body.is_empty() {
+        return Err(HttpError(400, "Request body is empty".to_string()));
+    }
+    Ok(body)
+}
+
+/// Create CASR report from sanitizer log or raw program stderr
+///
+/// # Arguments
+///
+/// * `body` - program stderr
+///
+/// * `params` - query parameters: `executable`, `cmdline`, and `signal`
+fn san_report(body: &[u8], params: &HashMap<String, String>) -> HttpResult<serde_json::Value> {
+    let output = String::from_utf8_lossy(body);
+    let mut report = CrashReport::new();
+    if let Some(executable) = params.get("executable") {
+        report.executable_path = executable.clone();
+    }
+    if let Some(cmdline) = params.get("cmdline") {
+        report.proc_cmdline = cmdline.clone();
+    }
+    report.signal = params.get("signal").and_then(|signal| signal.parse().ok());
+    report.stderr = output.lines().map(|l| l.to_string()).collect();
+    let crashed = report.signal.is_some();
+    let Some(stacktrace) = report
+        .set_from_san_log(&output, crashed)
+        .context("Couldn't analyze log")?
+    else {
+        return Err(HttpError(
+            422,
+            "Couldn't find sanitizer report or runtime error in log".to_string(),
+        ));
+    };
+    report.complete_from_output(&stacktrace, &output);
+    report.severity_score = report.execution_class.score();
+    report.set_frames();
+    Ok(serde_json::to_value(&report).context("Couldn't serialize report")?)
+}
+
+/// Create CASR report with casr tool from data saved in temporary file
+///
+/// # Arguments
+///
+/// * `tool` - casr tool path
+///
+/// * `input_arg` - casr tool option for input file
+///
+/// * `body` - input file content
+///
+/// * `args` - additional casr tool arguments
+///
+/// * `timeout` - casr tool timeout (in seconds), 0 value means that timeout is
+///   disabled
+fn tool_report(
+    tool: &Path,
+    input_arg: &str,
+    body: &[u8],
+    args: &[&str],
+    timeout: u64,
+) -> HttpResult<serde_json::Value> {
+    // File is created exclusively with random name and removed when dropped.
+    let mut file = tempfile::Builder::new()
+        .prefix("casr-server-")
+        .tempfile()
+        .context("Couldn't create temporary file")?;
+    file.write_all(body)
+        .with_context(|| format!("Couldn't write {}", file.path().display()))?;
+    let mut cmd = Command::new(tool);
+    cmd.arg(input_arg)
+        .arg(file.path())
+        .arg("--stdout")
+        .args(args);
+    debug!("Running {:?}", cmd);
+    let output = util::get_output(&mut cmd, timeout, true)?;
+    if !output.status.success() {
+        return Err(HttpError(
+            422,
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
+    }
+    Ok(serde_json::from_slice(&output.stdout)
+        .with_context(|| format!("Couldn't parse output of {cmd:?}"))?)
+}
+
+/// Parse CASR reports from JSON array and get their stack traces for
+/// deduplication and clustering
+///
+/// # Arguments
+///
+/// * `body` - JSON array of CASR reports
+fn report_stacktraces(body: &[u8]) -> HttpResult<Vec<Stacktrace>> {
+    let reports: Vec<serde_json::Value> = serde_json::from_slice(body)
+        .map_err(|e| HttpError(400, format!("Expected JSON array of reports: {e}")))?;
+    reports
+        .into_iter()
+        .enumerate()
+        .map(|(i, report)| {
+            CrashReport::from_json(&report.to_string())
+                .and_then(|report| report.normalized_stacktrace())
+                .map_err(|e| HttpError(422, format!("Report {i}: {e}")))
+        })
+        .collect()
+}
+
+/// Handle HTTP request
+///
+/// # Arguments
+///
+/// * `request` - HTTP request
+///
+/// * `config` - server settings
+fn handle(request: &mut Request, config: &ServerConfig) -> HttpResult<serde_json::Value> {
+    let url = request.url().to_string();
+    let (path, params) = parse_url(&url);
+    let method = request.method().clone();
+    match (&method, path) {
+        (Method::Get, "/health") => Ok(json!({ "Version": clap::crate_version!() })),
+        (Method::Post, "/san") => {
+            let body = read_body(request, config.max_size)?;
+            san_report(&body, &params)
+        }
+        (Method::Post, "/core") => {
+            let body = read_body(request, config.max_size)?;
+            let mut args = Vec::new();
+            if let Some(executable) = params.get("executable") {
+                args.extend(["-e", executable.as_str()]);
+            }
+            tool_report(&config.casr_core, "-f", &body, &args, config.timeout)
+        }
+        (Method::Post, "/kernel") => {
+            let body = read_body(request, config.max_size)?;
+            tool_report(&config.casr_kernel, "-i", &body, &[], config.timeout)
+        }
+        (Method::Post, "/dedup") => {
+            let body = read_body(request, config.max_size)?;
+            let stacktraces = report_stacktraces(&body)?;
+            Ok(json!({ "Unique": dedup_stacktraces(&stacktraces) }))
+        }
+        (Method::Post, "/cluster") => {
+            let body = read_body(request, config.max_size)?;
+            let stacktraces = report_stacktraces(&body)?;
+            let clusters = if stacktraces.len() < 2 {
+                vec![1; stacktraces.len()]
+            } else {
+                cluster_stacktraces(&stacktraces).context("Couldn't cluster reports")?
+            };
+            Ok(json!({ "Clusters": clusters }))
+        }
+        (_, "/health" | "/san" | "/core" | "/kernel" | "/dedup" | "/cluster") => {
+            Err(HttpError(405, "Method not allowed".to_string()))
+        }
+        _ => Err(HttpError(404, format!("Unknown endpoint {path}"))),
+    }
+}
+
+fn main() -> anyhow::Result<()> {
+    // Load configuration files.
+    config::load_config("casr-server")?;
+
+    let matches = clap::Command::new("casr-server")
+        .version(clap::crate_version!())
+        .about("HTTP service that creates, deduplicates, and clusters CASR reports")
+        .term_width(90)
+        .arg(
+            Arg::new("listen")
+                .short('l')
+                .long("listen")
+                .env("CASR_SERVER_LISTEN")
+                .action(ArgAction::Set)
+                .default_value("127.0.0.1:8080")
+                .value_name("ADDR")
+                .help("Address and port to listen on"),
+        )
+        .arg(
+            Arg::new("jobs")
+                .short('j')
+                .long("jobs")
+                .env("CASR_JOBS")
+                .action(ArgAction::Set)
+                .value_name("JOBS")
+                .help("Number of requests that are processed in parallel [default: half of cpu cores]")
+                .value_parser(clap::value_parser!(u32).range(1..)),
+        )
+        .arg(
+            Arg::new("max-size")
+                .long("max-size")
+                .env("CASR_SERVER_MAX_SIZE")
+                .action(ArgAction::Set)
+                .default_value("1024")
+                .value_name("MB")
+                .help("Maximum request body size (in MB)")
+                .value_parser(clap::value_parser!(u64).range(1..)),
+        )
+        .arg(
+            Arg::new("timeout")
+                .short('t')
+                .long("timeout")
+                .env("CASR_SERVER_TIMEOUT")
+                .action(ArgAction::Set)
+                .default_value("300")
+                .value_name("SECONDS")
+                .help("Timeout (in seconds) for casr-core and casr-kernel, 0 value means that timeout is disabled")
+                .value_parser(clap::value_parser!(u64)),
+        )
+        .arg(
+            Arg::new("ignore")
+                .long("ignore")
+                .env("CASR_IGNORE")
+                .action(ArgAction::Set)
+                .value_name("FILE")
+                .value_parser(clap::value_parser!(PathBuf))
+                .help("File with regular expressions for functions and file paths that should be ignored"),
+        )
+        .arg(util::near_null_arg())
+        .args(util::logging_args())
+        .get_matches();
+    config::clear_env();
+
+    // Init log.
+    util::initialize_logging(&matches);
+    util::init_near_null_threshold(&matches);
+
+    init_ignored_frames!("cpp", "rust", "python", "go", "java", "js");
+    if let Some(path) = matches.get_one::<PathBuf>("ignore") {
+        util::add_custom_ignored_frames(path)?;
+    }
+
+    let config = ServerConfig {
+        max_size: *matches.get_one::<u64>("max-size").unwrap() * 1024 * 1024,
+        casr_core: util::get_path("casr-core")?,
+        casr_kernel: util::get_path("casr-kernel")?,
+        timeout: *matches.get_one::<u64>("timeout").unwrap(),
+    };
+    let jobs = if let Some(jobs) = matches.get_one::<u32>("jobs") {
+        *jobs as usize
+    } else {
+        std::cmp::max(1, num_cpus::get() / 2)
+    };
+
+    let listen = matches.get_one::<String>("listen").unwrap();
+    let server = match Server::http(listen) {
+        Ok(server) => server,
+        Err(e) => anyhow::bail!("Couldn't listen on {}: {}", listen, e),
+    };
+    info!("Listening on {} with {} workers", listen, jobs);
+
+    let content_type = Header::from_bytes("Content-Type", "application/json").unwrap();
+    thread::scope(|s| {
+        for _ in 0..jobs {
+            s.spawn(|| {
+                for mut request in server.incoming_requests() {
+                    let (code, body) = match handle(&mut request, &config) {
+                        Ok(value) => (200, value),
+                        Err(HttpError(code, message)) => (code, json!({ "Error": message })),
+                    };
+                    info!("{} {} {}", request.method(), request.url(), code);
+                    let response = Response::from_string(body.to_string())
+                        .with_status_code(code)
+                        .with_header(content_type.clone());
+                    if let Err(e) = request.respond(response) {
+                        error!("Couldn't send response: {}", e);
+                    }
+                }
+            });
+        }
+    });
+
+    Ok(())
+}
@@ -0,0 +1,171 @@
+use casr::config;
+use casr::known_bugs::known_bugs_arg;
+use casr::triage::{fuzzing_crash_triage_pipeline, CrashInfo};
+use casr::util;
+
+use anyhow::{bail, Result};
+use clap::{
+    error::{ContextKind, ContextValue, ErrorKind},
+    Arg, ArgAction,
+};
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+fn main() -> Result<()> {
+    // Load configuration files.
+    config::load_config("casr-triage")?;
+
+    let matches = clap::Command::new("casr-triage")
+        .version(clap::crate_version!())
+        .about("Run target on each input from directory (e.g., fuzzing corpus or crash archive), create CASR reports for crashes, deduplicate and cluster them")
+        .term_width(90)
+        .arg(Arg::new("jobs")
+            .long("jobs")
+            .env("CASR_JOBS")
+            .short('j')
+            .action(ArgAction::Set)
+            .help("Number of parallel jobs for running target and generating CASR reports [default: half of cpu cores]")
+            .value_parser(clap::value_parser!(u32).range(1..)))
+        .arg(
+            Arg::new("timeout")
+                .short('t')
+                .long("timeout")
+                .env("CASR_TIMEOUT")
+                .action(ArgAction::Set)
+                .default_value("0")
+                .value_name("SECONDS")
+                .help("Timeout (in seconds) for target execution, 0 means that timeout is disabled")
+                .value_parser(clap::value_parser!(u64).range(0..))
+        )
+        .arg(
+            Arg::new("input")
+                .short('i')
+                .long("input")
+                .action(ArgAction::Set)
+                .required(true)
+                .value_name("INPUT_DIR")
+                .help("Directory with inputs to run target on")
+                .value_parser(move |arg: &str| {
+                    let i_dir = Path::new(arg);
+                    if !i_dir.exists() {
+                        let mut err = clap::Error::new(ErrorKind::ValueValidation);
+                        err.insert(ContextKind::InvalidValue, ContextValue::String("Input directory doesn't exist.".to_owned()));
+                        return Err(err);
+                    }
+                    if !i_dir.is_dir() {
+                        let mut err = clap::Error::new(ErrorKind::ValueValidation);
+                        err.insert(ContextKind::InvalidValue, ContextValue::String("Input path should be a directory.".to_owned()));
+                        return Err(err);
+                    }
+                    Ok(i_dir.to_owned())
+                })
+        )
+        .arg(
+            Arg::new("output")
+                .short('o')
+                .long("output")
+                .action(ArgAction::Set)
+                .value_parser(clap::value_parser!(PathBuf))
+                .required(true)
+                .value_name("OUTPUT_DIR")
+                .help("Output directory with triaged reports")
+        )
+        .arg(
+            Arg::new("force-remove")
+                .short('f')
+                .long("force-remove")
+                .action(ArgAction::SetTrue)
+                .help("Remove output project directory if it exists")
+        )
+        .arg(
+            Arg::new("no-cluster")
+                .action(ArgAction::SetTrue)
+                .long("no-cluster")
+                .help("Do not cluster CASR reports")
+        )
+        .arg(
+            Arg::new("casr-gdb-args")
+                .long("casr-gdb-args")
+                .action(ArgAction::Set)
+                .help("Add \"--casr-gdb-args \'./gdb_fuzz_target <arguments>\'\" to generate additional crash reports with casr-gdb (e.g., test whether program crashes without sanitizers)"),
+        )
+        .arg(
+            Arg::new("ARGS")
+                .action(ArgAction::Set)
+                .num_args(1..)
+                .last(true)
+                .required(true)
+                .help("Add \"-- ./target <arguments>\". Input file is substituted for \"@@\", otherwise input is passed via stdin"),
+        )
+        .arg(known_bugs_arg())
+        .args(util::input_args())
+        .args(util::source_args())
+        .args(util::logging_args())
+        .get_matches();
+    config::clear_env();
+
+    // Init log.
+    util::initialize_logging(&matches);
+
+    // Get input dir
+    let input_dir = matches.get_one::<PathBuf>("input").unwrap().as_path();
+
+    // Get target args.
+    let argv: Vec<&str> = matches
+        .get_many::<String>("ARGS")
+        .unwrap()
+        .map(|v| v.as_str())
+        .collect();
+    let at_index = argv.iter().skip(1).position(|s| s.contains("@@"));
+    let at_index = at_index.map(|idx| idx + 1);
+
+    let tool = if argv[0].ends_with(".py") {
+        "casr-python"
+    } else if argv[0].ends_with("java") {
+        "casr-java"
+    } else if argv[0].ends_with(".js") || argv[0].ends_with("node") {
+        "casr-js"
+    } else {
+        let sym_list = util::symbols_list(Path::new(argv[0]))?;
+        if sym_list.contains("__asan") || sym_list.contains("runtime.go") {
+            "casr-san"
+        } else {
+            "casr-gdb"
+        }
+    };
+    let tool = util::get_path(tool)?;
+
+    // Get all inputs.
+    let crashes: HashMap<String, CrashInfo> = fs::read_dir(input_dir)?
+        .flatten()
+        .map(|p| p.path())
+        .filter(|p| p.is_file())
+        .map(|p| {
+            (
+                p.file_name().unwrap().to_str().unwrap().to_string(),
+                CrashInfo {
+                    path: p,
+                    target_args: argv.iter().map(|x| x.to_string()).collect(),
+                    envs: HashMap::new(),
+                    at_index,
+                    casr_tool: tool.clone(),
+                    name: None,
+                },
+            )
+        })
+        .collect();
+    if crashes.is_empty() {
+        bail!("No inputs found in {}", input_dir.display());
+    }
+
+    let gdb_args = if let Some(argv) = matches.get_one::<String>("casr-gdb-args") {
+        shell_words::split(argv)?
+    } else {
+        Vec::new()
+    };
+
+    // Generate reports
+    fuzzing_crash_triage_pipeline(&matches, &crashes, &gdb_args)
+}
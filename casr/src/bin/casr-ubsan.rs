@@ -1,3 +1,4 @@
+use casr::config;
 use casr::util;
 use libcasr::report::CrashReport;
 use libcasr::severity::Severity;
@@ -12,7 +13,6 @@ use clap::{
 };
 use log::{debug, info, warn};
 use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
-use regex::Regex;
 use walkdir::WalkDir;
 
 use std::collections::HashSet;
@@ -162,8 +162,11 @@ fn gen_report(
 /// * `output_dir` - report saving directory
 ///
 /// * `input` - input file path
-fn save_report(report: CrashReport, output_dir: &Path, input: &Path) -> Result<()> {
-    // Convert report to string.
+fn save_report(mut report: CrashReport, output_dir: &Path, input: &Path) -> Result<()> {
+    // Compute severity score and convert report to string.
+    report.severity_score = report.execution_class.score();
+    report.set_frames();
+    report.set_inline_frames();
     let repstr = serde_json::to_string_pretty(&report).unwrap();
 
     let dir_name = input.parent().unwrap().file_name().unwrap();
@@ -209,6 +212,9 @@ fn save_report(report: CrashReport, output_dir: &Path, input: &Path) -> Result<(
 }
 
 fn main() -> Result<()> {
+    // Load configuration files.
+    config::load_config("casr-ubsan")?;
+
     let matches = clap::Command::new("casr-ubsan")
         .version(clap::crate_version!())
         .about("Triage errors found by UndefinedBehaviorSanitizer and create CASR reports (.casrep)")
@@ -216,6 +222,7 @@ fn main() -> Result<()> {
         .arg(
             Arg::new("log-level")
                 .long("log-level")
+                .env("CASR_LOG_LEVEL")
                 .short('l')
                 .action(ArgAction::Set)
                 .default_value("info")
@@ -225,6 +232,7 @@ fn main() -> Result<()> {
         .arg(
             Arg::new("jobs")
                 .long("jobs")
+                .env("CASR_JOBS")
                 .short('j')
                 .action(ArgAction::Set)
                 .help("Number of parallel jobs for generating CASR reports [default: half of cpu cores]")
@@ -234,6 +242,7 @@ fn main() -> Result<()> {
             Arg::new("timeout")
                 .short('t')
                 .long("timeout")
+                .env("CASR_TIMEOUT")
                 .action(ArgAction::Set)
                 .default_value("0")
                 .value_name("SECONDS")
@@ -289,7 +298,9 @@ fn main() -> Result<()> {
                 .last(true)
                 .help("Add \"-- <path> <arguments>\" to run"),
         )
+        .args(util::logging_args())
         .get_matches();
+    config::clear_env();
 
     // Init log.
     util::initialize_logging(&matches);
@@ -418,20 +429,14 @@ fn main() -> Result<()> {
     let _ = pre_report.add_proc_environ();
 
     info!("Deduplicating CASR reports...");
-    // Init dedup crashline list
-    let mut crashlines: HashSet<String> = HashSet::new();
+    // Init dedup list of UB sites (source location and error kind)
+    let mut sites: HashSet<String> = HashSet::new();
     let mut to_gen: Vec<(PathBuf, UbsanWarning, CrashLine)> = vec![];
-    // Dedup warnings by crashline
+    // Dedup warnings by UB site
     // Do without paralleling to preserve the specified order
-    let re = Regex::new(r"(.+:\d+):\d").unwrap();
     for (input, input_warnings) in warnings {
         for (warning, crashline) in input_warnings {
-            // Drop column number
-            let mut line = crashline.to_string();
-            if let Some(cap) = re.captures(&line) {
-                line = cap.get(1).unwrap().as_str().to_string();
-            }
-            if crashlines.insert(line) {
+            if sites.insert(warning.dedup_key(&crashline)) {
                 to_gen.push((input.clone(), warning, crashline));
             }
         }
@@ -439,7 +444,7 @@ fn main() -> Result<()> {
 
     info!(
         "Number of UBSAN warnings after deduplication: {}",
-        crashlines.len()
+        sites.len()
     );
 
     // Rebuild thread pool (different number of threads)
@@ -0,0 +1,195 @@
+use casr::config;
+use casr::util::{self, TargetEnv};
+use libcasr::{
+    cdb::{CdbContext, CdbStacktrace},
+    init_ignored_frames,
+    report::CrashReport,
+    severity::Severity,
+    stacktrace::*,
+};
+
+use anyhow::{bail, Context, Result};
+use clap::{Arg, ArgAction, ArgGroup};
+use log::{debug, warn};
+use std::path::PathBuf;
+use std::process::Command;
+
+fn main() -> Result<()> {
+    // Load configuration files.
+    config::load_config("casr-windbg")?;
+
+    let matches = clap::Command::new("casr-windbg")
+        .version(clap::crate_version!())
+        .about("Create CASR reports (.casrep) from cdb (WinDbg console debugger) execution on Windows")
+        .term_width(90)
+        .arg(
+            Arg::new("output")
+                .short('o')
+                .long("output")
+                .action(ArgAction::Set)
+                .value_name("REPORT")
+                .value_parser(clap::value_parser!(PathBuf))
+                .help(
+                    "Path to save report. Path can be a directory, then report name is generated",
+                ),
+        )
+        .arg(
+            Arg::new("stdout")
+                .action(ArgAction::SetTrue)
+                .long("stdout")
+                .help("Print CASR report to stdout"),
+        )
+        .group(
+            ArgGroup::new("out")
+                .args(["stdout", "output"])
+                .required(true),
+        )
+        .arg(
+            Arg::new("stdin")
+                .long("stdin")
+                .action(ArgAction::Set)
+                .value_name("FILE")
+                .value_parser(clap::value_parser!(PathBuf))
+                .help("Stdin file for program"),
+        )
+        .arg(
+            Arg::new("timeout")
+                .short('t')
+                .long("timeout")
+                .env("CASR_TIMEOUT")
+                .action(ArgAction::Set)
+                .default_value("0")
+                .value_name("SECONDS")
+                .help("Timeout (in seconds) for target execution, 0 value means that timeout is disabled")
+                .value_parser(clap::value_parser!(u64).range(0..))
+        )
+        .arg(
+            Arg::new("ignore")
+                .long("ignore")
+                .env("CASR_IGNORE")
+                .action(ArgAction::Set)
+                .value_name("FILE")
+                .value_parser(clap::value_parser!(PathBuf))
+                .help("File with regular expressions for functions and file paths that should be ignored"),
+        )
+        .arg(
+            Arg::new("analyze")
+                .long("analyze")
+                .env("CASR_ANALYZE")
+                .action(ArgAction::SetTrue)
+                .value_parser(clap::builder::FalseyValueParser::new())
+                .help("Run \"!analyze -v\" to get exception information (slow, symbols may be downloaded from symbol server)"),
+        )
+        .arg(
+            Arg::new("ARGS")
+                .action(ArgAction::Set)
+                .num_args(1..)
+                .last(true)
+                .help("Add \"-- ./binary.exe <arguments>\" to run executable"),
+        )
+        .args(util::target_env_args())
+        .arg(util::debugger_timeout_arg())
+        .arg(util::near_null_arg())
+        .args(util::source_args())
+        .args(util::logging_args())
+        .get_matches();
+    config::clear_env();
+
+    // Init log.
+    util::initialize_logging(&matches);
+    util::init_near_null_threshold(&matches);
+
+    if !cfg!(windows) {
+        bail!("casr-windbg is supported only on Windows");
+    }
+
+    // Get program args.
+    let argv: Vec<&str> = if let Some(argvs) = matches.get_many::<String>("ARGS") {
+        argvs.map(|s| s.as_str()).collect()
+    } else {
+        bail!("Wrong arguments for starting program");
+    };
+
+    // Get timeout
+    let timeout = *matches.get_one::<u64>("timeout").unwrap();
+    // Debugger is launched with target, so target timeout is added.
+    let debugger_timeout = match *matches.get_one::<u64>("debugger-timeout").unwrap() {
+        0 => 0,
+        debugger_timeout => debugger_timeout + timeout,
+    };
+
+    init_ignored_frames!("cpp", "rust");
+    if let Some(path) = matches.get_one::<PathBuf>("ignore") {
+        util::add_custom_ignored_frames(path)?;
+    }
+    // Get stdin for target program.
+    let stdin_file = util::stdin_from_matches(&matches)?;
+    let target_env = TargetEnv::from_matches(&matches);
+
+    let target_path = PathBuf::from(argv[0]);
+    if !target_path.exists() {
+        bail!("{} doesn't exist", target_path.to_str().unwrap());
+    }
+
+    let mut report = CrashReport::new();
+    report.executable_path = argv[0].to_string();
+    report.proc_cmdline = argv.join(" ");
+    let _ = report.add_os_info();
+    let _ = report.add_proc_environ();
+    target_env.update_environ(&mut report.proc_environ);
+    if let Some(mut file_path) = stdin_file.clone() {
+        file_path = file_path.canonicalize().unwrap_or(file_path);
+        report.stdin = file_path.display().to_string();
+    }
+
+    // Run target under cdb: skip initial (-g) and final (-G) breakpoints, so
+    // that cdb breaks only on exception. Then get exception record,
+    // registers, and stack trace.
+    let mut commands = ".exr -1; r; kn 100; ".to_string();
+    if matches.get_flag("analyze") {
+        commands.push_str("!analyze -v; ");
+    }
+    commands.push('q');
+    let mut cdb_cmd = Command::new("cdb");
+    cdb_cmd.args(["-g", "-G", "-c", &commands]).args(&argv);
+    // Target inherits cdb environment.
+    target_env.apply(&mut cdb_cmd);
+    if let Some(file) = &stdin_file {
+        cdb_cmd.stdin(std::fs::File::open(file)?);
+    }
+    debug!("Running {:?}", cdb_cmd);
+    let cdb_result = util::get_output(&mut cdb_cmd, debugger_timeout, true)
+        .with_context(|| "Unable to get results from cdb (should be in PATH)")?;
+    let cdb_stdout = String::from_utf8_lossy(&cdb_result.stdout);
+
+    // Target and cdb output precedes exception record.
+    let lines: Vec<String> = cdb_stdout
+        .split('\n')
+        .map(|l| l.trim_end().to_string())
+        .collect();
+    let Some(start) = lines.iter().position(|l| l.contains("ExceptionAddress:")) else {
+        // Normal termination.
+        bail!("Program terminated (no crash)");
+    };
+    let context = CdbContext(lines[start..].to_vec());
+    match context.severity() {
+        Ok(class) => report.execution_class = class,
+        Err(error) => warn!("Couldn't estimate severity. {error}"),
+    }
+    report.registers = context.registers();
+
+    report.stacktrace = CdbStacktrace::extract_stacktrace(&context.0.join("\n"))?;
+    let parsed_stacktrace = CdbStacktrace::parse_stacktrace(&report.stacktrace)?;
+    // Get crash line.
+    if let Ok(crash_line) = parsed_stacktrace.crash_line() {
+        report.crashline = crash_line.to_string();
+        if let CrashLine::Source(debug) = crash_line {
+            if let Some(sources) = CrashReport::sources(&debug) {
+                report.source = sources;
+            }
+        }
+    }
+
+    //Output report
+    util::output_report(&report, &matches, &argv)
+}
@@ -0,0 +1,233 @@
+//! Layered configuration files for CASR tools.
+//!
+//! Configuration files are read in the following order (later files override
+//! earlier ones):
+//!
+//! 1. `/etc/casr/casr.toml`
+//! 2. `$XDG_CONFIG_HOME/casr/casr.toml` (`~/.config/casr/casr.toml` by default)
+//! 3. `.casr.toml` in the current working directory
+//! 4. File specified by `CASR_CONFIG` environment variable
+//!
+//! Top-level keys apply to all tools, keys from `[casr-<tool>]` table apply
+//! only to the corresponding tool. Each setting `some-option` is exported as
+//! `CASR_SOME_OPTION` environment variable unless it is already set. Thus,
+//! command line options override environment variables, and environment
+//! variables override configuration files. Exported variables are removed
+//! after command line parsing (see [`clear_env`]), so that they don't reach
+//! targets.
+use anyhow::{bail, Context, Result};
+
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Environment variables exported from configuration files.
+static EXPORTED: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+/// Get configuration file paths ordered by increasing priority
+pub fn config_paths() -> Vec<PathBuf> {
+    let mut paths = vec![PathBuf::from("/etc/casr/casr.toml")];
+    if let Some(dir) = env::var_os("XDG_CONFIG_HOME").filter(|dir| !dir.is_empty()) {
+        paths.push(PathBuf::from(dir).join("casr/casr.toml"));
+    } else if let Some(home) = env::var_os("HOME") {
+        paths.push(PathBuf::from(home).join(".config/casr/casr.toml"));
+    }
+    paths.push(PathBuf::from(".casr.toml"));
+    if let Some(path) = env::var_os("CASR_CONFIG") {
+        paths.push(PathBuf::from(path));
+    }
+    paths
+}
+
+/// Load settings from configuration files and export them as environment
+/// variables for the tool
+///
+/// # Arguments
+///
+/// * `tool` - tool name, e.g. "casr-san"
+pub fn load_config(tool: &str) -> Result<()> {
+    let explicit = env::var_os("CASR_CONFIG").map(PathBuf::from);
+    let settings = read_settings(&config_paths(), explicit.as_deref(), tool)?;
+    export_settings(settings);
+    Ok(())
+}
+
+/// Remove environment variables exported by [`load_config`]. It should be
+/// called after command line parsing, so that settings don't reach targets
+/// and sub tools (they load configuration files themselves).
+pub fn clear_env() {
+    for name in EXPORTED.lock().unwrap().drain(..) {
+        env::remove_var(name);
+    }
+}
+
+/// Read settings from configuration files
+///
+/// # Arguments
+///
+/// * `paths` - configuration file paths ordered by increasing priority
+///
+/// * `explicit` - configuration file that must exist
+///
+/// * `tool` - tool name
+///
+/// # Return value
+///
+/// Environment variable names mapped to their values
+fn read_settings(
+    paths: &[PathBuf],
+    explicit: Option<&Path>,
+    tool: &str,
+) -> Result<BTreeMap<String, String>> {
+    let mut settings = BTreeMap::new();
+    for path in paths {
+        if !path.is_file() {
+            if explicit == Some(path.as_path()) {
+                bail!("Config file {} doesn't exist", path.display());
+            }
+            continue;
+        }
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("Couldn't read config file {}", path.display()))?;
+        let table = content
+            .parse::<toml::Table>()
+            .with_context(|| format!("Couldn't parse config file {}", path.display()))?;
+        merge_settings(&table, tool, &mut settings)
+            .with_context(|| format!("Bad config file {}", path.display()))?;
+    }
+    Ok(settings)
+}
+
+/// Export settings as environment variables unless they are already set
+///
+/// # Arguments
+///
+/// * `settings` - environment variable names mapped to their values
+fn export_settings(settings: BTreeMap<String, String>) {
+    let mut exported = EXPORTED.lock().unwrap();
+    for (name, value) in settings {
+        if env::var_os(&name).is_none() {
+            env::set_var(&name, value);
+            exported.push(name);
+        }
+    }
+}
+
+/// Merge common and tool-specific settings from configuration table
+///
+/// # Arguments
+///
+/// * `table` - parsed configuration file
+///
+/// * `tool` - tool name
+///
+/// * `settings` - environment variable names mapped to their values
+fn merge_settings(
+    table: &toml::Table,
+    tool: &str,
+    settings: &mut BTreeMap<String, String>,
+) -> Result<()> {
+    for (key, value) in table.iter().filter(|(_, value)| !value.is_table()) {
+        settings.insert(env_name(key), setting_value(key, value)?);
+    }
+    let Some(section) = table.get(tool) else {
+        return Ok(());
+    };
+    let Some(section) = section.as_table() else {
+        bail!("[{tool}] must be a table");
+    };
+    for (key, value) in section {
+        settings.insert(env_name(key), setting_value(key, value)?);
+    }
+    Ok(())
+}
+
+/// Get environment variable name for configuration key
+fn env_name(key: &str) -> String {
+    format!("CASR_{}", key.to_uppercase().replace('-', "_"))
+}
+
+/// Convert configuration value to environment variable value
+///
+/// Arrays are joined with ':', e.g. for source directories lists.
+fn setting_value(key: &str, value: &toml::Value) -> Result<String> {
+    match value {
+        toml::Value::String(s) => Ok(s.clone()),
+        toml::Value::Integer(i) => Ok(i.to_string()),
+        toml::Value::Float(f) => Ok(f.to_string()),
+        toml::Value::Boolean(b) => Ok(b.to_string()),
+        toml::Value::Array(values) => Ok(values
+            .iter()
+            .map(|value| match value {
+                toml::Value::Array(_) | toml::Value::Table(_) => {
+                    bail!("Nested values are not supported for {key}")
+                }
+                _ => setting_value(key, value),
+            })
+            .collect::<Result<Vec<_>>>()?
+            .join(":")),
+        _ => bail!("Unsupported value type for {key}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_precedence() {
+        let dir = tempfile::tempdir().unwrap();
+        let files = [
+            (
+                "system.toml",
+                "timeout = 10\njobs = 1\nignore = \"system\"\n",
+            ),
+            ("user.toml", "timeout = 20\njobs = 2\n"),
+            (
+                "project.toml",
+                "timeout = 30\n[casr-san]\ntimeout = 35\ncli-format = \"json\"\n",
+            ),
+            ("explicit.toml", "jobs = 4\n"),
+        ];
+        let paths: Vec<PathBuf> = files
+            .iter()
+            .map(|(name, content)| {
+                let path = dir.path().join(name);
+                fs::write(&path, content).unwrap();
+                path
+            })
+            .collect();
+
+        // system < user < project < $CASR_CONFIG
+        let settings = read_settings(&paths, Some(&paths[3]), "casr-san").unwrap();
+        assert_eq!(settings["CASR_TIMEOUT"], "35");
+        assert_eq!(settings["CASR_JOBS"], "4");
+        assert_eq!(settings["CASR_IGNORE"], "system");
+        assert_eq!(settings["CASR_CLI_FORMAT"], "json");
+        let settings = read_settings(&paths, Some(&paths[3]), "casr-gdb").unwrap();
+        assert_eq!(settings["CASR_TIMEOUT"], "30");
+        assert!(!settings.contains_key("CASR_CLI_FORMAT"));
+
+        // Missing user file is skipped, missing $CASR_CONFIG file is an error.
+        let missing = dir.path().join("missing.toml");
+        let paths = [paths[0].clone(), missing.clone()];
+        assert!(read_settings(&paths, None, "casr-san").is_ok());
+        assert!(read_settings(&paths, Some(&missing), "casr-san").is_err());
+
+        // $CASR_CONFIG < environment, exported variables are removed.
+        env::set_var("CASR_CONFIG_TEST_ENV", "env");
+        let settings = BTreeMap::from([
+            ("CASR_CONFIG_TEST_ENV".to_string(), "file".to_string()),
+            ("CASR_CONFIG_TEST_FILE".to_string(), "file".to_string()),
+        ]);
+        export_settings(settings);
+        assert_eq!(env::var("CASR_CONFIG_TEST_ENV").unwrap(), "env");
+        assert_eq!(env::var("CASR_CONFIG_TEST_FILE").unwrap(), "file");
+        clear_env();
+        assert_eq!(env::var("CASR_CONFIG_TEST_ENV").unwrap(), "env");
+        assert!(env::var_os("CASR_CONFIG_TEST_FILE").is_none());
+        env::remove_var("CASR_CONFIG_TEST_ENV");
+    }
+}
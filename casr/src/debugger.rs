@@ -0,0 +1,40 @@
+//! Debugger module selects debugger that runs target to crash and collects
+//! crash context: gdb (default on Linux and FreeBSD) or lldb (default on
+//! macOS, optional on Linux for targets that behave differently under gdb).
+use clap::{Arg, ArgAction, ArgMatches};
+
+/// Debugger that runs target.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Debugger {
+    /// GNU debugger.
+    Gdb,
+    /// LLVM debugger.
+    Lldb,
+}
+
+impl Debugger {
+    /// Get debugger from tool arguments
+    ///
+    /// # Arguments
+    ///
+    /// * `matches` - tool arguments
+    pub fn from_matches(matches: &ArgMatches) -> Self {
+        match matches.get_one::<String>("debugger").map(|s| s.as_str()) {
+            Some("lldb") => Debugger::Lldb,
+            Some("gdb") => Debugger::Gdb,
+            _ if cfg!(target_os = "macos") => Debugger::Lldb,
+            _ => Debugger::Gdb,
+        }
+    }
+}
+
+/// Get debugger selection option: `--debugger`.
+pub fn debugger_arg() -> Arg {
+    Arg::new("debugger")
+        .long("debugger")
+        .env("CASR_DEBUGGER")
+        .action(ArgAction::Set)
+        .value_name("DEBUGGER")
+        .value_parser(["gdb", "lldb"])
+        .help("Debugger that runs target [default: lldb on macOS, gdb otherwise]")
+}
@@ -0,0 +1,425 @@
+//! Elf core module parses notes of Linux ELF core files (NT_PRSTATUS,
+//! NT_SIGINFO, and NT_FILE) to get registers, signal, fault address, and
+//! memory mappings of crashed thread without gdb.
+use anyhow::{bail, Context, Result};
+use gdb_command::mappings::{File, MappedFiles};
+use gdb_command::memory::MemoryObject;
+use gdb_command::registers::Registers;
+use gdb_command::siginfo::Siginfo;
+use goblin::container::Endian;
+use goblin::elf::{header, note, program_header, Elf};
+
+use libcasr::gdb::exploitable::MachineInfo;
+
+use std::io::{Read, Seek, SeekFrom};
+
+/// NT_SIGINFO note type ("SIGI")
+const NT_SIGINFO: u32 = 0x53494749;
+
+/// Maximum number of stack frames unwound without gdb
+const MAX_FRAMES: usize = 100;
+
+/// Crash context from ELF core file notes
+pub struct ElfCore<'a> {
+    /// Core file content
+    data: &'a [u8],
+    /// Loaded segments: virtual address, file offset, and size in core file
+    segments: Vec<(u64, usize, usize)>,
+    /// Target machine
+    pub machine: MachineInfo,
+    /// Command line from NT_PRPSINFO note
+    pub cmdline: Option<String>,
+    /// Signal information of crashed thread from NT_SIGINFO note
+    pub siginfo: Option<Siginfo>,
+    /// Registers of crashed thread from the first NT_PRSTATUS note
+    pub registers: Registers,
+    /// Mapped files from NT_FILE note
+    pub mappings: MappedFiles,
+}
+
+impl<'a> ElfCore<'a> {
+    /// Parse ELF core file
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - core file content
+    pub fn parse(data: &'a [u8]) -> Result<Self> {
+        let elf = Elf::parse(data).with_context(|| "Couldn't parse ELF core file")?;
+        if elf.header.e_type != header::ET_CORE {
+            bail!("Core should be an ELF file.");
+        }
+        let byte_width = match elf.header.e_ident[4] {
+            1 => 4,
+            2 => 8,
+            class => bail!("Couldn't determine byte_width: {}", class),
+        };
+        let Ok(endianness) = elf.header.endianness() else {
+            bail!("Couldn't get endianness from core");
+        };
+        let arch = match elf.header.e_machine {
+            header::EM_386
+            | header::EM_ARM
+            | header::EM_X86_64
+            | header::EM_AARCH64
+            | header::EM_RISCV => elf.header.e_machine,
+            arch => bail!("Unsupported architecture: {}", arch),
+        };
+        let Some(notes) = elf.iter_note_headers(data) else {
+            bail!("Notes section are empty in coredump.");
+        };
+        let mut core = ElfCore {
+            data,
+            segments: elf
+                .program_headers
+                .iter()
+                // Segments that exceed address space are corrupted and skipped.
+                .filter(|ph| {
+                    ph.p_type == program_header::PT_LOAD
+                        && ph.p_filesz > 0
+                        && ph.p_offset.checked_add(ph.p_filesz).is_some()
+                        && ph.p_vaddr.checked_add(ph.p_filesz).is_some()
+                })
+                .filter_map(|ph| {
+                    Some((
+                        ph.p_vaddr,
+                        usize::try_from(ph.p_offset).ok()?,
+                        usize::try_from(ph.p_filesz).ok()?,
+                    ))
+                })
+                .collect(),
+            machine: MachineInfo {
+                arch,
+                endianness,
+                byte_width,
+            },
+            cmdline: None,
+            siginfo: None,
+            registers: Registers::new(),
+            mappings: MappedFiles::new(),
+        };
+
+        for note in notes.flatten() {
+            // Thread status, signal information, and mapped files are parsed
+            // for Linux cores only.
+            let linux = note.name == "CORE";
+            match note.n_type {
+                note::NT_PRPSINFO if core.cmdline.is_none() => {
+                    core.cmdline = prpsinfo_args_offset(note.name, byte_width)
+                        .and_then(|offset| note.desc.get(offset..))
+                        .map(|args| {
+                            args.iter()
+                                .take_while(|b| **b != 0x0)
+                                .map(|b| *b as char)
+                                .collect()
+                        });
+                }
+                // The first NT_PRSTATUS note belongs to crashed thread.
+                note::NT_PRSTATUS if linux && core.registers.is_empty() => {
+                    core.registers = core.prstatus_registers(note.desc);
+                }
+                NT_SIGINFO if linux && core.siginfo.is_none() => {
+                    core.siginfo = core.siginfo(note.desc);
+                }
+                note::NT_FILE if linux && core.mappings.is_empty() => {
+                    core.mappings = core.mapped_files(note.desc).unwrap_or_default();
+                }
+                _ => {}
+            }
+        }
+        Ok(core)
+    }
+
+    /// Read target word
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - memory
+    ///
+    /// * `offset` - word offset
+    fn word(&self, data: &[u8], offset: usize) -> Option<u64> {
+        let width = self.machine.byte_width as usize;
+        let bytes = data.get(offset..offset.checked_add(width)?)?;
+        let mut value = 0_u64;
+        for i in 0..width {
+            let byte = if self.machine.endianness == Endian::Little {
+                bytes[width - 1 - i]
+            } else {
+                bytes[i]
+            };
+            value = (value << 8) | byte as u64;
+        }
+        Some(value)
+    }
+
+    /// Read 32-bit integer
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - memory
+    ///
+    /// * `offset` - integer offset
+    fn int(&self, data: &[u8], offset: usize) -> Option<u32> {
+        let bytes: [u8; 4] = data.get(offset..offset + 4)?.try_into().ok()?;
+        Some(if self.machine.endianness == Endian::Little {
+            u32::from_le_bytes(bytes)
+        } else {
+            u32::from_be_bytes(bytes)
+        })
+    }
+
+    /// Get registers from NT_PRSTATUS note (`pr_reg` field). Registers are
+    /// named like in gdb.
+    ///
+    /// # Arguments
+    ///
+    /// * `desc` - note content
+    fn prstatus_registers(&self, desc: &[u8]) -> Registers {
+        let names: &[&str] = match self.machine.arch {
+            header::EM_X86_64 => &[
+                "r15", "r14", "r13", "r12", "rbp", "rbx", "r11", "r10", "r9", "r8", "rax", "rcx",
+                "rdx", "rsi", "rdi", "orig_rax", "rip", "cs", "eflags", "rsp", "ss", "fs_base",
+                "gs_base", "ds", "es", "fs", "gs",
+            ],
+            header::EM_386 => &[
+                "ebx", "ecx", "edx", "esi", "edi", "ebp", "eax", "ds", "es", "fs", "gs",
+                "orig_eax", "eip", "cs", "eflags", "esp", "ss",
+            ],
+            header::EM_ARM => &[
+                "r0", "r1", "r2", "r3", "r4", "r5", "r6", "r7", "r8", "r9", "r10", "r11", "r12",
+                "sp", "lr", "pc", "cpsr", "orig_r0",
+            ],
+            header::EM_AARCH64 => &[
+                "x0", "x1", "x2", "x3", "x4", "x5", "x6", "x7", "x8", "x9", "x10", "x11", "x12",
+                "x13", "x14", "x15", "x16", "x17", "x18", "x19", "x20", "x21", "x22", "x23", "x24",
+                "x25", "x26", "x27", "x28", "x29", "x30", "sp", "pc", "cpsr",
+            ],
+            header::EM_RISCV => &[
+                "pc", "ra", "sp", "gp", "tp", "t0", "t1", "t2", "fp", "s1", "a0", "a1", "a2", "a3",
+                "a4", "a5", "a6", "a7", "s2", "s3", "s4", "s5", "s6", "s7", "s8", "s9", "s10",
+                "s11", "t3", "t4", "t5", "t6",
+            ],
+            _ => &[],
+        };
+        // pr_reg follows signal info, pending and held signals, process ids,
+        // and 4 time values.
+        let start = if self.machine.byte_width == 8 {
+            112
+        } else {
+            72
+        };
+        let width = self.machine.byte_width as usize;
+        let mut registers = Registers::new();
+        for (i, name) in names.iter().enumerate() {
+            // gdb doesn't show original syscall registers.
+            if name.starts_with("orig_") {
+                continue;
+            }
+            let Some(value) = self.word(desc, start + i * width) else {
+                break;
+            };
+            registers.insert(name.to_string(), value);
+        }
+        registers
+    }
+
+    /// Get signal information from NT_SIGINFO note
+    ///
+    /// # Arguments
+    ///
+    /// * `desc` - note content
+    fn siginfo(&self, desc: &[u8]) -> Option<Siginfo> {
+        // si_signo, si_errno, si_code, and fault address that is aligned to
+        // word size.
+        let addr_offset = if self.machine.byte_width == 8 { 16 } else { 12 };
+        Some(Siginfo {
+            si_signo: self.int(desc, 0)?,
+            si_errno: self.int(desc, 4)?,
+            si_code: self.int(desc, 8)?,
+            si_addr: self.word(desc, addr_offset)?,
+        })
+    }
+
+    /// Get mapped files from NT_FILE note
+    ///
+    /// # Arguments
+    ///
+    /// * `desc` - note content
+    fn mapped_files(&self, desc: &[u8]) -> Option<MappedFiles> {
+        let width = self.machine.byte_width as usize;
+        // count, page size, count * (start, end, file offset in pages), file names.
+        let count = self.word(desc, 0)? as usize;
+        let page_size = self.word(desc, width)?;
+        let names_offset = count.checked_mul(3)?.checked_add(2)?.checked_mul(width)?;
+        let mut names = desc
+            .get(names_offset..)?
+            .split(|b| *b == 0x0)
+            .map(|name| String::from_utf8_lossy(name).to_string());
+        let mut mappings = MappedFiles::new();
+        for i in 0..count {
+            let entry = (2 + i * 3) * width;
+            let start = self.word(desc, entry)?;
+            let end = self.word(desc, entry + width)?;
+            let offset = self.word(desc, entry + 2 * width)?.checked_mul(page_size);
+            let name = names.next()?;
+            // Corrupted entries are skipped.
+            let Some(offset) = offset.filter(|_| start < end) else {
+                continue;
+            };
+            mappings.push(File::new(start, end, offset, &name));
+        }
+        Some(mappings)
+    }
+
+    /// Read target memory from core file. Memory of file mappings that isn't
+    /// dumped to core file is read from mapped files.
+    ///
+    /// # Arguments
+    ///
+    /// * `address` - memory address
+    ///
+    /// * `size` - maximum size to read
+    pub fn memory(&self, address: u64, size: usize) -> Option<MemoryObject> {
+        if let Some((vaddr, offset, filesz)) = self
+            .segments
+            .iter()
+            .find(|(vaddr, _, filesz)| address >= *vaddr && address - *vaddr < *filesz as u64)
+        {
+            let start = offset.checked_add(usize::try_from(address - vaddr).ok()?)?;
+            let end = start
+                .saturating_add(size)
+                .min(offset.checked_add(*filesz)?)
+                .min(self.data.len());
+            return Some(MemoryObject {
+                address,
+                data: self.data.get(start..end)?.to_vec(),
+            });
+        }
+        let file = self
+            .mappings
+            .iter()
+            .find(|file| address >= file.start && address < file.end)?;
+        let mut data = Vec::new();
+        let mut mapped = std::fs::File::open(&file.name).ok()?;
+        mapped
+            .seek(SeekFrom::Start(
+                file.offset.checked_add(address - file.start)?,
+            ))
+            .ok()?;
+        mapped
+            .take((size as u64).min(file.end - address))
+            .read_to_end(&mut data)
+            .ok()?;
+        Some(MemoryObject { address, data })
+    }
+
+    /// Unwind crashed thread stack by frame pointers. When frame pointers
+    /// are unavailable, stack is scanned for addresses from mapped files.
+    ///
+    /// # Return value
+    ///
+    /// Frame addresses
+    pub fn unwind(&self) -> Vec<u64> {
+        let (pc, sp, fp, record) = match self.machine.arch {
+            header::EM_X86_64 => ("rip", "rsp", Some("rbp"), 0),
+            header::EM_386 => ("eip", "esp", Some("ebp"), 0),
+            header::EM_AARCH64 => ("pc", "sp", Some("x29"), 0),
+            // Frame record is below frame pointer.
+            header::EM_RISCV => ("pc", "sp", Some("fp"), 2 * self.machine.byte_width as u64),
+            _ => ("pc", "sp", None, 0),
+        };
+        let Some(pc) = self.registers.get(pc).copied() else {
+            return Vec::new();
+        };
+        let mut frames = vec![pc];
+        let width = self.machine.byte_width as u64;
+        let read = |address: u64| {
+            self.memory(address, width as usize)
+                .and_then(|memory| self.word(&memory.data, 0))
+        };
+        let in_file = |address: u64| {
+            self.mappings
+                .iter()
+                .any(|file| address >= file.start && address < file.end)
+        };
+
+        // Frame record: previous frame pointer and return address.
+        let mut fp = fp
+            .and_then(|fp| self.registers.get(fp).copied())
+            .unwrap_or_default();
+        while fp >= record && frames.len() < MAX_FRAMES {
+            let Some(ret_address) = (fp - record).checked_add(width) else {
+                break;
+            };
+            let (Some(next), Some(ret)) = (read(fp - record), read(ret_address)) else {
+                break;
+            };
+            if !in_file(ret) {
+                break;
+            }
+            frames.push(ret);
+            if next <= fp {
+                break;
+            }
+            fp = next;
+        }
+
+        if frames.len() == 1 {
+            let Some(mut address) = self.registers.get(sp).copied() else {
+                return frames;
+            };
+            while frames.len() < MAX_FRAMES {
+                let Some(value) = read(address) else {
+                    break;
+                };
+                if in_file(value) {
+                    frames.push(value);
+                }
+                let Some(next) = address.checked_add(width) else {
+                    break;
+                };
+                address = next;
+            }
+        }
+        frames
+    }
+
+    /// Get stack trace of crashed thread in gdb format without gdb
+    pub fn stacktrace(&self) -> Vec<String> {
+        self.unwind()
+            .iter()
+            .enumerate()
+            .map(|(i, address)| {
+                if let Some(file) = self
+                    .mappings
+                    .iter()
+                    .find(|file| *address >= file.start && *address < file.end)
+                {
+                    format!("#{i}  {address:#018x} in ?? () from {}", file.name)
+                } else {
+                    format!("#{i}  {address:#018x} in ?? ()")
+                }
+            })
+            .collect()
+    }
+}
+
+/// Get offset of process arguments in NT_PRPSINFO note
+///
+/// # Arguments
+///
+/// * `name` - note name ("CORE" for Linux, "FreeBSD" for FreeBSD)
+///
+/// * `byte_width` - target byte width
+///
+/// # Return value
+///
+/// Offset of `pr_psargs` field
+fn prpsinfo_args_offset(name: &str, byte_width: u8) -> Option<usize> {
+    match (name, byte_width) {
+        ("FreeBSD", 4) => Some(25),
+        ("FreeBSD", 8) => Some(33),
+        (_, 4) => Some(44),
+        (_, 8) => Some(56),
+        _ => None,
+    }
+}
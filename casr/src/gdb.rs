@@ -0,0 +1,226 @@
+//! Gdb module builds gdb command line for CASR tools. It mirrors the API of
+//! `gdb_command::GdbCommand`, but gdb is launched with C locale and without
+//! gdbinit files and auto-loaded scripts (e.g., plugins like GEF or pwndbg),
+//! because their output breaks parsing.
+use anyhow::{bail, Result};
+use gdb_command::ExecType;
+use std::path::PathBuf;
+use std::process::Command;
+
+/// Variables that are set to C locale for gdb and restored for target.
+const LOCALE_VARS: [&str; 2] = ["LC_ALL", "LANG"];
+
+/// Gdb command line builder.
+pub struct GdbCommand<'a> {
+    /// Gdb execution type.
+    exec_type: &'a ExecType<'a>,
+    /// Gdb arguments (-ex).
+    args: Vec<String>,
+    /// Stdin file for target.
+    stdin: Option<&'a PathBuf>,
+    /// Number of commands whose results are returned by `parse`.
+    commands_cnt: usize,
+    /// Target timeout (in seconds), 0 value means that timeout is disabled.
+    timeout: u64,
+}
+
+impl<'a> GdbCommand<'a> {
+    /// Create new gdb command
+    ///
+    /// # Arguments
+    ///
+    /// * `exec_type` - gdb execution type
+    pub fn new(exec_type: &'a ExecType<'a>) -> Self {
+        GdbCommand {
+            exec_type,
+            args: Vec::new(),
+            stdin: None,
+            commands_cnt: 0,
+            timeout: 0,
+        }
+    }
+
+    /// Set stdin file for target, it should be set before `r`
+    ///
+    /// # Arguments
+    ///
+    /// * `file` - stdin file
+    pub fn stdin<T: Into<Option<&'a PathBuf>>>(&mut self, file: T) -> &mut Self {
+        self.stdin = file.into();
+        self
+    }
+
+    /// Set target timeout
+    ///
+    /// # Arguments
+    ///
+    /// * `timeout` - timeout (in seconds), 0 value means that timeout is disabled
+    pub fn timeout(&mut self, timeout: u64) -> &mut Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Get target timeout (in seconds), 0 value means that timeout is disabled
+    pub fn get_timeout(&self) -> u64 {
+        self.timeout
+    }
+
+    /// Run target
+    pub fn r(&mut self) -> &mut Self {
+        self.args.push("-ex".to_string());
+        self.args.push(match self.stdin {
+            Some(file) => format!("r < {}", shell_quote(&file.to_string_lossy())),
+            None => "r".to_string(),
+        });
+        self
+    }
+
+    /// Add command, its result is returned by `parse`
+    ///
+    /// # Arguments
+    ///
+    /// * `cmd` - gdb command
+    pub fn ex<T: Into<String>>(&mut self, cmd: T) -> &mut Self {
+        self.args.push("-ex".to_string());
+        self.args
+            .push(format!("p \"{}\"", start_marker(self.commands_cnt)));
+        self.args.push("-ex".to_string());
+        self.args.push(cmd.into());
+        self.args.push("-ex".to_string());
+        self.args
+            .push(format!("p \"{}\"", end_marker(self.commands_cnt)));
+        self.commands_cnt += 1;
+        self
+    }
+
+    /// Get stack trace
+    pub fn bt(&mut self) -> &mut Self {
+        self.ex("bt")
+    }
+
+    /// Get signal info
+    pub fn siginfo(&mut self) -> &mut Self {
+        self.ex("p/x $_siginfo")
+    }
+
+    /// Get memory mappings
+    pub fn mappings(&mut self) -> &mut Self {
+        self.ex("info proc mappings")
+    }
+
+    /// Get registers
+    pub fn regs(&mut self) -> &mut Self {
+        self.ex("info registers")
+    }
+
+    /// Get memory contents
+    ///
+    /// # Arguments
+    ///
+    /// * `expr` - address expression
+    ///
+    /// * `size` - number of bytes
+    pub fn mem<T: AsRef<str>>(&mut self, expr: T, size: u32) -> &mut Self {
+        self.ex(format!("x/{size}bx {}", expr.as_ref()))
+    }
+
+    /// Get disassembly at pc
+    pub fn disassembly(&mut self) -> &mut Self {
+        self.ex("x/16i $pc")
+    }
+
+    /// Build gdb process command. Gdb runs in batch mode with C locale,
+    /// without gdbinit files and auto-loaded scripts. Locale of the target is
+    /// restored from the current environment.
+    pub fn command(&self) -> Command {
+        let mut gdb = Command::new("gdb");
+        gdb.args(["--batch", "-nx", "-iex", "set auto-load off"]);
+        for var in LOCALE_VARS {
+            gdb.arg("-iex");
+            gdb.arg(match std::env::var(var) {
+                Ok(value) => format!("set environment {var}={value}"),
+                Err(_) => format!("unset environment {var}"),
+            });
+            gdb.env(var, "C");
+        }
+        gdb.args([
+            "-ex",
+            "set backtrace limit 2000",
+            "-ex",
+            "set disassembly-flavor intel",
+            "-ex",
+            "set filename-display absolute",
+        ]);
+        match self.exec_type {
+            ExecType::Local(argv) => {
+                gdb.args(&self.args).arg("--args").args(argv.iter());
+            }
+            ExecType::Remote(pid) => {
+                gdb.args(["-p", pid]).args(&self.args);
+            }
+            ExecType::Core { target, core } => {
+                gdb.args(&self.args).args([target, core]);
+            }
+        }
+        gdb
+    }
+
+    /// Split gdb output into results of added commands
+    ///
+    /// # Arguments
+    ///
+    /// * `output` - raw gdb output
+    ///
+    /// # Return value
+    ///
+    /// Results of commands in the order they were added
+    pub fn parse<T: AsRef<str>>(&self, output: T) -> Result<Vec<String>> {
+        let output = output.as_ref();
+        let mut results = Vec::with_capacity(self.commands_cnt);
+        let mut rest = output;
+        for i in 0..self.commands_cnt {
+            let (start, end) = (
+                format!("\"{}\"\n", start_marker(i)),
+                format!("\"{}\"\n", end_marker(i)),
+            );
+            let Some((_, tail)) = rest.split_once(&start) else {
+                bail!("Couldn't find results of gdb command {i} in output");
+            };
+            let Some((result, tail)) = tail.split_once(&end) else {
+                bail!("Couldn't find results of gdb command {i} in output");
+            };
+            // Drop value history prefix (e.g., "$2 = ") of end marker.
+            let result = result.rsplit_once('\n').map_or("", |(result, _)| result);
+            results.push(result.to_string());
+            rest = tail;
+        }
+        Ok(results)
+    }
+}
+
+/// Get marker that is printed before command result
+///
+/// # Arguments
+///
+/// * `i` - command index
+fn start_marker(i: usize) -> String {
+    format!("casr-gdb-start-{i}")
+}
+
+/// Get marker that is printed after command result
+///
+/// # Arguments
+///
+/// * `i` - command index
+fn end_marker(i: usize) -> String {
+    format!("casr-gdb-end-{i}")
+}
+
+/// Quote string for shell that gdb uses to start target
+///
+/// # Arguments
+///
+/// * `s` - string to quote
+fn shell_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', r"'\''"))
+}
@@ -0,0 +1,160 @@
+//! Known bugs module matches CASR reports against suppression file with
+//! already filed bugs, so that old bugs that keep reproducing during
+//! continuous fuzzing aren't reported as new ones.
+//!
+//! Each line of suppression file is a pattern (empty lines and lines starting
+//! with `#` are skipped):
+//!
+//! * `hash:<HASH>` - report stack hash (`StackHash` field)
+//! * `frame:<REGEX>` - function or source file of any stack frame after
+//!   filtering
+//! * `crashline:<GLOB>` - report crash line, `*` matches any characters and
+//!   `?` matches one character
+use anyhow::{bail, Context, Result};
+use clap::{Arg, ArgAction, ArgMatches};
+use libcasr::report::CrashReport;
+use libcasr::stacktrace::stack_hash;
+use regex::Regex;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Get `--known-bugs` option.
+pub fn known_bugs_arg() -> Arg {
+    Arg::new("known-bugs")
+        .long("known-bugs")
+        .env("CASR_KNOWN_BUGS")
+        .action(ArgAction::Set)
+        .value_name("FILE")
+        .value_parser(clap::value_parser!(PathBuf))
+        .help("File with stack hashes, frame regexes, and crash line globs of known bugs. Matching reports are moved to \"known\" directory instead of deduplication and clustering")
+}
+
+/// Known bug pattern.
+#[derive(Clone, Debug)]
+enum Pattern {
+    /// Stack hash.
+    Hash(String),
+    /// Regular expression for stack frame function or source file.
+    Frame(Regex),
+    /// Crash line glob converted to regular expression.
+    Crashline(Regex),
+}
+
+/// Known bugs from suppression file.
+#[derive(Clone, Debug, Default)]
+pub struct KnownBugs {
+    /// Patterns with their suppression file lines.
+    patterns: Vec<(String, Pattern)>,
+}
+
+impl KnownBugs {
+    /// Parse suppression file content
+    ///
+    /// # Arguments
+    ///
+    /// * `content` - suppression file content
+    pub fn parse(content: &str) -> Result<Self> {
+        let mut patterns = Vec::new();
+        for (number, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let Some((kind, value)) = line.split_once(':') else {
+                bail!("Line {}: expected KIND:PATTERN, got {line}", number + 1);
+            };
+            let value = value.trim();
+            let pattern = match kind.trim() {
+                "hash" => Pattern::Hash(value.to_lowercase()),
+                "frame" => Pattern::Frame(
+                    Regex::new(value)
+                        .with_context(|| format!("Line {}: bad regex {value}", number + 1))?,
+                ),
+                "crashline" => Pattern::Crashline(glob_regex(value)?),
+                kind => bail!(
+                    "Line {}: unknown pattern kind {kind} (hash, frame, or crashline)",
+                    number + 1
+                ),
+            };
+            patterns.push((line.to_string(), pattern));
+        }
+        Ok(KnownBugs { patterns })
+    }
+
+    /// Read suppression file
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - path to suppression file
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Couldn't read known bugs file {}", path.display()))?;
+        Self::parse(&content).with_context(|| format!("Bad known bugs file {}", path.display()))
+    }
+
+    /// Read suppression file from `--known-bugs` option
+    ///
+    /// # Arguments
+    ///
+    /// * `matches` - tool arguments
+    ///
+    /// # Return value
+    ///
+    /// Known bugs or None if `--known-bugs` is not specified
+    pub fn from_matches(matches: &ArgMatches) -> Result<Option<Self>> {
+        matches
+            .get_one::<PathBuf>("known-bugs")
+            .map(|path| Self::from_file(path))
+            .transpose()
+    }
+
+    /// Find known bug pattern that matches report
+    ///
+    /// # Arguments
+    ///
+    /// * `report` - CASR report
+    ///
+    /// # Return value
+    ///
+    /// Matching suppression file line or None if report is not a known bug
+    pub fn find(&self, report: &CrashReport) -> Option<&str> {
+        let stacktrace = report.normalized_stacktrace().unwrap_or_default();
+        let hash = if report.stack_hash.is_empty() {
+            stack_hash(&stacktrace)
+        } else {
+            report.stack_hash.to_lowercase()
+        };
+        self.patterns
+            .iter()
+            .find(|(_, pattern)| match pattern {
+                Pattern::Hash(known) => *known == hash,
+                Pattern::Frame(re) => stacktrace.iter().any(|entry| {
+                    (!entry.function.is_empty() && re.is_match(&entry.function))
+                        || (!entry.debug.file.is_empty() && re.is_match(&entry.debug.file))
+                }),
+                Pattern::Crashline(re) => {
+                    !report.crashline.is_empty() && re.is_match(&report.crashline)
+                }
+            })
+            .map(|(line, _)| line.as_str())
+    }
+}
+
+/// Convert glob to regular expression that matches the whole string
+///
+/// # Arguments
+///
+/// * `glob` - glob with `*` and `?` wildcards
+fn glob_regex(glob: &str) -> Result<Regex> {
+    let mut re = String::from("^");
+    for c in glob.chars() {
+        match c {
+            '*' => re.push_str(".*"),
+            '?' => re.push('.'),
+            c => re.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    re.push('$');
+    Ok(Regex::new(&re)?)
+}
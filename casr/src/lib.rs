@@ -6,6 +6,21 @@
 //!
 //! Enable `dojo` feature to build `casr-dojo` that can upload new and unique
 //! CASR reports to [DefectDojo](https://github.com/DefectDojo/django-DefectDojo).
+//! Enable `issue` feature to build `casr-issue` that creates GitHub or GitLab
+//! issues for new clusters of CASR reports. Enable `server` feature to build
+//! `casr-server` that provides HTTP API for creating, deduplicating, and
+//! clustering CASR reports.
 
+pub mod config;
+pub mod debugger;
+pub mod elf_core;
+pub mod gdb;
+pub mod known_bugs;
+pub mod lldb;
+pub mod macos;
+pub mod minidump;
+pub mod qemu;
+pub mod reproduce;
+pub mod sandbox;
 pub mod triage;
 pub mod util;
@@ -0,0 +1,167 @@
+//! Lldb module runs target under lldb (on macOS, where gdb is unavailable, or
+//! on Linux with `--debugger lldb`) and fills CASR report from lldb output.
+use crate::util::{self, TargetEnv};
+
+use anyhow::{bail, Context, Result};
+use libcasr::cpp::CppException;
+use libcasr::exception::Exception;
+use libcasr::gdb::exploitable::GdbContext;
+use libcasr::lldb::{LldbContext, LldbStacktrace};
+use libcasr::report::CrashReport;
+use libcasr::rust::RustPanic;
+use libcasr::severity::Severity;
+use libcasr::stacktrace::*;
+use log::{debug, warn};
+
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+/// Run target under lldb in batch mode and get lldb output
+///
+/// # Arguments
+///
+/// * `argv` - target program argument vector
+///
+/// * `stdin` - stdin file for target program
+///
+/// * `target_env` - target environment
+///
+/// * `timeout` - lldb timeout (in seconds), 0 value means that timeout is
+///   disabled
+///
+/// * `commands` - lldb commands that are executed when target crashes
+///
+/// * `aslr` - enable ASLR (lldb disables it by default)
+///
+/// # Return value
+///
+/// Lldb output, target output goes to lldb stdout and stderr as well
+pub fn lldb_output(
+    argv: &[&str],
+    stdin: &Option<PathBuf>,
+    target_env: &TargetEnv,
+    timeout: u64,
+    commands: &[&str],
+    aslr: bool,
+) -> Result<Output> {
+    let launch = if let Some(file) = stdin {
+        format!("process launch -i '{}'", file.display())
+    } else {
+        "process launch".to_string()
+    };
+    let mut lldb_cmd = Command::new("lldb");
+    lldb_cmd.arg("--batch");
+    if aslr {
+        lldb_cmd.args(["-o", "settings set target.disable-aslr false"]);
+    }
+    lldb_cmd.args(["-o", &launch]);
+    for command in commands {
+        lldb_cmd.args(["-k", command]);
+    }
+    lldb_cmd.arg("--").args(argv);
+    // Target inherits lldb environment.
+    target_env.apply(&mut lldb_cmd);
+    debug!("Running {:?}", lldb_cmd);
+    util::get_output(&mut lldb_cmd, timeout, true)
+        .with_context(|| "Unable to get results from lldb")
+}
+
+/// Run target under lldb and fill report with execution class, registers
+/// (including exception state on macOS), disassembly at pc, and stack trace.
+/// On Linux memory mappings and memory at pc are also collected, so that
+/// severity is estimated by faulting instruction like for gdb.
+///
+/// # Arguments
+///
+/// * `report` - report to fill
+///
+/// * `argv` - target program argument vector
+///
+/// * `stdin` - stdin file for target program
+///
+/// * `target_env` - target environment
+///
+/// * `timeout` - lldb timeout (in seconds), 0 value means that timeout is
+///   disabled
+///
+/// * `aslr` - enable ASLR (lldb disables it by default)
+///
+/// # Return value
+///
+/// Parsed stack trace
+pub fn lldb_report(
+    report: &mut CrashReport,
+    argv: &[&str],
+    stdin: &Option<PathBuf>,
+    target_env: &TargetEnv,
+    timeout: u64,
+    aslr: bool,
+) -> Result<Stacktrace> {
+    let commands: &[&str] = if cfg!(target_os = "macos") {
+        &["bt", "register read --all", "disassemble -p -c 16"]
+    } else {
+        &[
+            "bt",
+            "register read",
+            "memory region --all",
+            "memory read -s1 -fx -c64 $pc",
+            "disassemble -p -c 16",
+        ]
+    };
+    let result = lldb_output(argv, stdin, target_env, timeout, commands, aslr)?;
+    let output = String::from_utf8_lossy(&result.stdout);
+    let lines: Vec<String> = output
+        .split('\n')
+        .map(|l| l.trim_end().to_string())
+        .collect();
+    if !lines.iter().any(|l| l.contains("stop reason = ")) {
+        // Normal termination.
+        bail!("Program terminated (no crash)");
+    }
+
+    report.disassembly = lines
+        .iter()
+        .skip_while(|l| !l.starts_with("(lldb) disassemble"))
+        .skip(1)
+        .take_while(|l| !l.starts_with("(lldb)"))
+        .filter(|l| !l.is_empty())
+        .cloned()
+        .collect();
+    report.stacktrace = LldbStacktrace::extract_stacktrace(&output)?;
+    let context = LldbContext(lines);
+    report.registers = context.registers();
+    // Signal information is available on Linux, while macOS lldb reports
+    // Mach exceptions.
+    let severity = match (context.siginfo(), util::machine_info(Path::new(argv[0]))) {
+        (Some(siginfo), Ok(machine)) => {
+            report.signal = Some(siginfo.si_signo as i32);
+            let mappings = context.mappings();
+            report.proc_maps = util::proc_maps(&mappings);
+            GdbContext {
+                siginfo,
+                registers: report.registers.clone(),
+                mappings,
+                machine,
+                pc_memory: context.pc_memory().unwrap_or_default(),
+                stacktrace: report.stacktrace.clone(),
+            }
+            .severity()
+        }
+        _ => context.severity(),
+    };
+    match severity {
+        Ok(class) => report.execution_class = class,
+        Err(error) => warn!("Couldn't estimate severity. {error}"),
+    }
+
+    // Check for exceptions
+    let stderr = String::from_utf8_lossy(&result.stderr);
+    if let Some(class) = [CppException::parse_exception, RustPanic::parse_exception]
+        .iter()
+        .find_map(|parse| parse(&output).or_else(|| parse(&stderr)))
+    {
+        report.execution_class = class;
+    }
+
+    Ok(LldbStacktrace::parse_stacktrace(&report.stacktrace)?)
+}
@@ -0,0 +1,76 @@
+//! Macos module fills CASR report from macOS ReportCrash crash reports (`.ips`
+//! and `.crash`), so that crashes collected on macOS can be triaged without
+//! re-running target.
+use anyhow::{Context, Result};
+use log::warn;
+
+use libcasr::macos::MacCrash;
+use libcasr::report::CrashReport;
+use libcasr::severity::Severity;
+use libcasr::stacktrace::CrashLineExt;
+
+/// Check that data is macOS crash report
+///
+/// # Arguments
+///
+/// * `data` - file content
+pub fn is_mac_crash(data: &[u8]) -> bool {
+    std::str::from_utf8(data).is_ok_and(MacCrash::is_mac_crash)
+}
+
+/// Analyze macOS crash report and put information to report: OS, images,
+/// registers, stack trace of crashed thread, and severity.
+///
+/// # Arguments
+///
+/// * `report` - crash report
+///
+/// * `data` - crash report content
+pub fn analyze_mac_crash(report: &mut CrashReport, data: &[u8]) -> Result<()> {
+    let data = std::str::from_utf8(data).with_context(|| "Crash report is not UTF-8")?;
+    let crash = MacCrash::parse(data)?;
+
+    let (os, release) = crash
+        .os_version
+        .split_once(' ')
+        .unwrap_or((crash.os_version.as_str(), ""));
+    report.os = os.to_string();
+    report.os_release = release.to_string();
+    report.architecture = crash.architecture.clone();
+    if report.executable_path.is_empty() {
+        report.executable_path = crash.process_path.clone();
+    }
+
+    // Images are saved in gdb proc mappings format.
+    let mut images = crash.images.clone();
+    images.sort_by_key(|image| image.base);
+    report.proc_maps =
+        vec!["          Start Addr           End Addr       Size     Offset objfile".to_string()];
+    for image in images {
+        report.proc_maps.push(format!(
+            "{:#20x} {:#18x} {:#10x} {:#10x} {}",
+            image.base,
+            image.base + image.size,
+            image.size,
+            0,
+            image.path
+        ));
+    }
+
+    report.stacktrace = crash.stacktrace.clone();
+    match crash.severity() {
+        Ok(severity) => report.execution_class = severity,
+        Err(error) => warn!("Couldn't estimate severity. {}", error),
+    }
+    report.registers = crash.registers;
+    if let Ok(crash_line) = report
+        .parsed_stacktrace()
+        .and_then(|trace| trace.crash_line())
+    {
+        report.set_crash_line(crash_line);
+    }
+    report.severity_score = report.execution_class.score();
+    report.set_frames();
+
+    Ok(())
+}
@@ -0,0 +1,312 @@
+//! Minidump module reconstructs crash context and stack trace from
+//! Breakpad/Crashpad minidumps (`.dmp`) for severity estimation.
+use anyhow::{bail, Context, Result};
+use gdb_command::mappings::{File, MappedFiles};
+use gdb_command::memory::MemoryObject;
+use gdb_command::registers::Registers;
+use gdb_command::siginfo::Siginfo;
+use goblin::container::Endian;
+use goblin::elf::header;
+use log::warn;
+use minidump::system_info::{Cpu, Os};
+use minidump::{
+    Minidump, MinidumpException, MinidumpMemory, MinidumpMemoryList, MinidumpMiscInfo,
+    MinidumpModuleList, MinidumpSystemInfo, MinidumpThreadList, Module,
+};
+
+use libcasr::cdb::CdbContext;
+use libcasr::constants::SIGINFO_SIGSEGV;
+use libcasr::execution_class::ExecutionClass;
+use libcasr::gdb::exploitable::{GdbContext, MachineInfo};
+use libcasr::report::CrashReport;
+use libcasr::severity::Severity;
+
+/// Maximum number of reconstructed stack frames
+const MAX_FRAMES: usize = 100;
+
+/// Windows STATUS_ACCESS_VIOLATION exception code
+const STATUS_ACCESS_VIOLATION: u32 = 0xc0000005;
+
+/// Check that data is a minidump
+///
+/// # Arguments
+///
+/// * `data` - file content
+pub fn is_minidump(data: &[u8]) -> bool {
+    data.starts_with(b"MDMP")
+}
+
+/// Analyze minidump and put information to report: modules, registers, stack
+/// trace of crashed thread, and severity.
+///
+/// # Arguments
+///
+/// * `report` - crash report
+///
+/// * `data` - minidump content
+pub fn analyze_minidump(report: &mut CrashReport, data: &[u8]) -> Result<()> {
+    let dump = Minidump::read(data).with_context(|| "Couldn't parse minidump")?;
+    let system_info = dump
+        .get_stream::<MinidumpSystemInfo>()
+        .with_context(|| "Couldn't get system info from minidump")?;
+    let exception = dump
+        .get_stream::<MinidumpException>()
+        .with_context(|| "Couldn't get exception stream from minidump")?;
+    let modules = dump.get_stream::<MinidumpModuleList>().unwrap_or_default();
+    let memory = dump.get_stream::<MinidumpMemoryList>().unwrap_or_default();
+    let threads = dump.get_stream::<MinidumpThreadList>().ok();
+    let misc = dump.get_stream::<MinidumpMiscInfo>().ok();
+
+    let (arch, byte_width, fp_name) = match system_info.cpu {
+        Cpu::X86_64 => (header::EM_X86_64, 8, "rbp"),
+        Cpu::X86 => (header::EM_386, 4, "ebp"),
+        Cpu::Arm64 => (header::EM_AARCH64, 8, "fp"),
+        Cpu::Arm => (header::EM_ARM, 4, "fp"),
+        cpu => bail!("Unsupported minidump architecture: {:?}", cpu),
+    };
+    let machine = MachineInfo {
+        arch,
+        endianness: Endian::Little,
+        byte_width,
+    };
+    report.os = format!("{:?}", system_info.os);
+    report.architecture = format!("{:?}", system_info.cpu);
+
+    // Modules are saved in gdb proc mappings format.
+    let mut mappings = MappedFiles::new();
+    let mut modules_sorted: Vec<_> = modules.iter().collect();
+    modules_sorted.sort_by_key(|module| module.base_address());
+    report.proc_maps =
+        vec!["          Start Addr           End Addr       Size     Offset objfile".to_string()];
+    for module in modules_sorted {
+        let start = module.base_address();
+        // Modules that exceed address space are corrupted and skipped.
+        let Some(end) = start.checked_add(module.size()) else {
+            continue;
+        };
+        let name = module.code_file();
+        report.proc_maps.push(format!(
+            "{:#20x} {:#18x} {:#10x} {:#10x} {}",
+            start,
+            end,
+            module.size(),
+            0,
+            name
+        ));
+        mappings.push(File::new(start, end, 0, &name));
+    }
+    if report.executable_path.is_empty() {
+        if let Some(main) = modules.main_module() {
+            report.executable_path = main.code_file().to_string();
+        }
+    }
+
+    // Crashed thread context.
+    let Some(context) = exception.context(&system_info, misc.as_ref()) else {
+        bail!("Couldn't get crashed thread context from minidump");
+    };
+    let mut registers = Registers::new();
+    for (name, value) in context.valid_registers() {
+        registers.insert(name.to_string(), value);
+    }
+    if arch == header::EM_AARCH64 {
+        // Aliases used by severity analysis.
+        for (alias, name) in [("x29", "fp"), ("x30", "lr")] {
+            if let Some(value) = registers.get(name).copied() {
+                registers.insert(alias.to_string(), value);
+            }
+        }
+    }
+    let pc = context.get_instruction_pointer();
+    let sp = context.get_stack_pointer();
+    let fp = registers.get(fp_name).copied().unwrap_or_default();
+
+    // Stack trace of crashed thread.
+    let stack = threads
+        .as_ref()
+        .and_then(|threads| threads.get_thread(exception.thread_id))
+        .and_then(|thread| thread.stack.as_ref());
+    let frames = walk_stack(pc, sp, fp, byte_width, stack, &modules);
+    report.stacktrace = frames
+        .iter()
+        .enumerate()
+        .map(|(i, address)| {
+            if let Some(module) = modules.module_at_address(*address) {
+                format!("#{i}  {address:#018x} in ?? () from {}", module.code_file())
+            } else {
+                format!("#{i}  {address:#018x} in ?? ()")
+            }
+        })
+        .collect();
+    report.symbolize_stacktrace();
+
+    // Memory at pc for disassembly.
+    let pc_memory = memory
+        .memory_at_address(pc)
+        .map(|region| {
+            let start = (pc - region.base_address) as usize;
+            let end = region.bytes.len().min(start.saturating_add(64));
+            MemoryObject {
+                address: pc,
+                data: region.bytes[start..end].to_vec(),
+            }
+        })
+        .unwrap_or_default();
+
+    let record = &exception.raw.exception_record;
+    let code = record.exception_code;
+    let siginfo = match system_info.os {
+        Os::Windows if code == STATUS_ACCESS_VIOLATION => Some(Siginfo {
+            si_signo: SIGINFO_SIGSEGV,
+            si_code: 1,
+            si_errno: 0,
+            si_addr: record.exception_information[1],
+        }),
+        // Breakpad stores signal number in exception code and si_code in
+        // exception flags.
+        Os::Linux | Os::Android => Some(Siginfo {
+            si_signo: code,
+            si_code: record.exception_flags,
+            si_errno: 0,
+            si_addr: record.exception_address,
+        }),
+        _ => None,
+    };
+
+    let severity = if let Some(siginfo) = siginfo {
+        GdbContext {
+            siginfo,
+            registers: registers.clone(),
+            mappings,
+            machine,
+            pc_memory,
+            stacktrace: report.stacktrace.clone(),
+        }
+        .severity()
+        .or_else(|error| windows_severity(&system_info.os, record).ok_or(error))
+    } else if let Some(severity) = windows_severity(&system_info.os, record) {
+        Ok(severity)
+    } else {
+        Err(libcasr::error::Error::Casr(format!(
+            "Unsupported exception code {code:#x} on {:?}",
+            system_info.os
+        )))
+    };
+    match severity {
+        Ok(severity) => report.execution_class = severity,
+        Err(error) => warn!("Couldn't estimate severity. {}", error),
+    }
+    report.severity_score = report.execution_class.score();
+    report.set_frames();
+    report.registers = registers;
+
+    Ok(())
+}
+
+/// Estimate severity by Windows exception record
+///
+/// # Arguments
+///
+/// * `os` - minidump operating system
+///
+/// * `record` - exception record
+fn windows_severity(
+    os: &Os,
+    record: &minidump::format::MINIDUMP_EXCEPTION,
+) -> Option<ExecutionClass> {
+    if *os != Os::Windows {
+        return None;
+    }
+    let mut lines = vec![format!("ExceptionCode: {:08x}", record.exception_code)];
+    if record.exception_code == STATUS_ACCESS_VIOLATION && record.number_parameters >= 2 {
+        let access = match record.exception_information[0] {
+            0 => "read from",
+            1 => "write to",
+            _ => "execute non-executable",
+        };
+        lines.push(format!(
+            "Attempt to {access} address {:016x}",
+            record.exception_information[1]
+        ));
+    }
+    CdbContext(lines).severity().ok()
+}
+
+/// Reconstruct stack trace by frame pointers chain. If it fails, stack is
+/// scanned for words that point into loaded modules (like Breakpad does).
+///
+/// # Arguments
+///
+/// * `pc` - program counter
+///
+/// * `sp` - stack pointer
+///
+/// * `fp` - frame pointer
+///
+/// * `byte_width` - word size
+///
+/// * `stack` - stack memory of crashed thread
+///
+/// * `modules` - loaded modules
+///
+/// # Return value
+///
+/// Frame addresses
+fn walk_stack(
+    pc: u64,
+    sp: u64,
+    mut fp: u64,
+    byte_width: u8,
+    stack: Option<&MinidumpMemory>,
+    modules: &MinidumpModuleList,
+) -> Vec<u64> {
+    let mut frames = vec![pc];
+    let Some(stack) = stack else {
+        return frames;
+    };
+    let read = |address: u64| -> Option<u64> {
+        if byte_width == 8 {
+            stack.get_memory_at_address::<u64>(address)
+        } else {
+            stack
+                .get_memory_at_address::<u32>(address)
+                .map(|value| value as u64)
+        }
+    };
+    let in_module = |address: u64| modules.module_at_address(address).is_some();
+
+    // Frame record: previous frame pointer and return address.
+    while frames.len() < MAX_FRAMES {
+        let Some(ret_address) = fp.checked_add(byte_width as u64) else {
+            break;
+        };
+        let (Some(next), Some(ret)) = (read(fp), read(ret_address)) else {
+            break;
+        };
+        if !in_module(ret) {
+            break;
+        }
+        frames.push(ret);
+        if next <= fp {
+            break;
+        }
+        fp = next;
+    }
+
+    if frames.len() == 1 {
+        let mut address = sp;
+        while frames.len() < MAX_FRAMES {
+            let Some(value) = read(address) else {
+                break;
+            };
+            if in_module(value) {
+                frames.push(value);
+            }
+            let Some(next) = address.checked_add(byte_width as u64) else {
+                break;
+            };
+            address = next;
+        }
+    }
+    frames
+}
@@ -0,0 +1,199 @@
+//! QEMU user-mode module runs foreign architecture targets (e.g., cross-compiled
+//! fuzz targets) under qemu-user and debugs them via QEMU gdb stub.
+use crate::gdb::GdbCommand;
+use crate::util::{self, TargetEnv};
+
+use anyhow::{bail, Context, Result};
+use clap::{parser::ValueSource, Arg, ArgAction, ArgMatches};
+use gdb_command::ExecType;
+use log::debug;
+
+use std::io::Read;
+use std::net::TcpListener;
+use std::path::PathBuf;
+use std::process::{Child, Command, Stdio};
+
+/// Get QEMU user-mode options: `--qemu` and `-L/--sysroot`.
+pub fn qemu_args() -> [Arg; 2] {
+    [
+        Arg::new("qemu")
+            .long("qemu")
+            .env("CASR_QEMU")
+            .action(ArgAction::Set)
+            .value_name("QEMU")
+            .value_parser(clap::value_parser!(PathBuf))
+            .help("Run target under QEMU user-mode emulator (e.g., qemu-aarch64), gdb is connected to QEMU gdb stub"),
+        Arg::new("sysroot")
+            .short('L')
+            .long("sysroot")
+            .env("CASR_QEMU_SYSROOT")
+            .action(ArgAction::Set)
+            .value_name("DIR")
+            .value_parser(clap::value_parser!(PathBuf))
+            .help("Sysroot with target architecture libraries for QEMU (-L) and gdb (set sysroot)"),
+    ]
+}
+
+/// QEMU user-mode emulator settings.
+#[derive(Clone, Debug)]
+pub struct QemuUser {
+    /// Path to qemu-user binary, e.g., qemu-aarch64.
+    pub qemu: PathBuf,
+    /// Sysroot with target architecture libraries.
+    pub sysroot: Option<PathBuf>,
+    /// Target environment, QEMU passes it to target.
+    pub env: TargetEnv,
+}
+
+impl QemuUser {
+    /// Get QEMU settings from tool arguments
+    ///
+    /// # Arguments
+    ///
+    /// * `matches` - tool arguments
+    ///
+    /// # Return value
+    ///
+    /// QEMU settings or None if `--qemu` is not specified
+    pub fn from_matches(matches: &ArgMatches) -> Result<Option<Self>> {
+        let Some(qemu) = matches.get_one::<PathBuf>("qemu") else {
+            // Sysroot may be set in configuration file (environment) for all
+            // runs, so only command line option requires --qemu.
+            if matches.value_source("sysroot") == Some(ValueSource::CommandLine) {
+                bail!("--sysroot requires --qemu");
+            }
+            return Ok(None);
+        };
+        Ok(Some(QemuUser {
+            qemu: qemu.clone(),
+            sysroot: matches.get_one::<PathBuf>("sysroot").cloned(),
+            env: TargetEnv::from_matches(matches),
+        }))
+    }
+
+    /// Get command that runs target under QEMU
+    ///
+    /// # Arguments
+    ///
+    /// * `argv` - target program argument vector
+    ///
+    /// * `port` - QEMU gdb stub port, QEMU waits for gdb connection before
+    ///   running target
+    pub fn command(&self, argv: &[&str], port: Option<u16>) -> Command {
+        let mut command = Command::new(&self.qemu);
+        if let Some(sysroot) = &self.sysroot {
+            command.arg("-L").arg(sysroot);
+        }
+        if let Some(port) = port {
+            command.arg("-g").arg(port.to_string());
+        }
+        command.args(argv);
+        self.env.apply(&mut command);
+        command
+    }
+
+    /// Run target under QEMU gdb stub and get gdb results. Gdb loads target
+    /// binary, connects to QEMU, and continues execution, then `build` adds
+    /// commands that are executed when target is stopped.
+    ///
+    /// # Arguments
+    ///
+    /// * `argv` - target program argument vector
+    ///
+    /// * `stdin` - stdin file for target program
+    ///
+    /// * `debugger_timeout` - gdb timeout (in seconds), 0 value means that
+    ///   timeout is disabled
+    ///
+    /// * `build` - function that adds gdb commands
+    ///
+    /// # Return value
+    ///
+    /// Results of commands added by `build` and target output (stdout and
+    /// stderr), None if gdb was killed by timeout
+    pub fn gdb_output(
+        &self,
+        argv: &[&str],
+        stdin: &Option<PathBuf>,
+        debugger_timeout: u64,
+        build: impl FnOnce(&mut GdbCommand),
+    ) -> Result<Option<(Vec<String>, String)>> {
+        let port = TcpListener::bind("127.0.0.1:0")
+            .and_then(|listener| listener.local_addr())
+            .with_context(|| "Couldn't get free port for QEMU gdb stub")?
+            .port();
+        let mut qemu = self.command(argv, Some(port));
+        if let Some(file) = stdin {
+            qemu.stdin(std::fs::File::open(file)?);
+        } else {
+            qemu.stdin(Stdio::null());
+        }
+        qemu.stdout(Stdio::piped()).stderr(Stdio::piped());
+        debug!("Running {:?}", qemu);
+        let mut child = qemu
+            .spawn()
+            .with_context(|| format!("Couldn't launch {qemu:?}"))?;
+        // Read target output in background, so that target is not blocked on
+        // full pipe while gdb is waiting for it.
+        let readers: Vec<_> = [
+            child
+                .stdout
+                .take()
+                .map(|r| Box::new(r) as Box<dyn Read + Send>),
+            child
+                .stderr
+                .take()
+                .map(|r| Box::new(r) as Box<dyn Read + Send>),
+        ]
+        .into_iter()
+        .flatten()
+        .map(|mut reader| {
+            std::thread::spawn(move || {
+                let mut data = Vec::new();
+                let _ = reader.read_to_end(&mut data);
+                data
+            })
+        })
+        .collect();
+
+        let target = [argv[0]];
+        let exectype = ExecType::Local(&target);
+        let mut gdb_command = GdbCommand::new(&exectype);
+        // Gdb connects to QEMU (retrying while QEMU is starting) and continues
+        // target execution. Results of these commands are skipped.
+        let mut prefix = 2;
+        if let Some(sysroot) = &self.sysroot {
+            gdb_command.ex(format!("set sysroot {}", sysroot.display()));
+            prefix += 1;
+        }
+        gdb_command
+            .ex(format!("target remote 127.0.0.1:{port}"))
+            .ex("c");
+        build(&mut gdb_command);
+        let output = util::gdb_output(&gdb_command, debugger_timeout);
+        kill(&mut child);
+        let target_output: String = readers
+            .into_iter()
+            .map(|reader| String::from_utf8_lossy(&reader.join().unwrap_or_default()).to_string())
+            .collect::<Vec<_>>()
+            .join("\n");
+        let Some(output) = output.with_context(|| "Unable to get results from gdb")? else {
+            return Ok(None);
+        };
+        let mut results = gdb_command.parse(String::from_utf8_lossy(&output))?;
+        results.drain(..prefix.min(results.len()));
+        Ok(Some((results, target_output)))
+    }
+}
+
+/// Kill QEMU process if it is still running and wait for it
+///
+/// # Arguments
+///
+/// * `child` - QEMU process
+fn kill(child: &mut Child) {
+    if let Ok(None) = child.try_wait() {
+        let _ = child.kill();
+    }
+    let _ = child.wait();
+}
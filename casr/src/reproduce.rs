@@ -0,0 +1,205 @@
+//! Reproduce module re-runs target from CASR report with the same casr tool
+//! that created report, so that it is possible to check whether crash still
+//! reproduces (`casr-cli --verify`) or reproduces on smaller input
+//! (`casr-minimize`).
+use crate::util::get_path;
+
+use anyhow::{bail, Context, Result};
+use libcasr::report::CrashReport;
+use log::debug;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Get casr tool that collects the same kind of report: casr-san for
+/// sanitizer and Go reports, casr-python, casr-java, or casr-js for
+/// interpreted languages, and casr-gdb otherwise
+///
+/// # Arguments
+///
+/// * `report` - CASR report
+///
+/// # Return value
+///
+/// Path to casr tool
+pub fn report_tool(report: &CrashReport) -> Result<PathBuf> {
+    get_path(report_tool_name(report))
+}
+
+/// Get name of casr tool that collects the same kind of report
+///
+/// # Arguments
+///
+/// * `report` - CASR report
+fn report_tool_name(report: &CrashReport) -> &'static str {
+    if !report.python_report.is_empty() {
+        "casr-python"
+    } else if !report.java_report.is_empty() {
+        "casr-java"
+    } else if !report.js_report.is_empty() {
+        "casr-js"
+    } else if [
+        &report.asan_report,
+        &report.msan_report,
+        &report.hwasan_report,
+        &report.gwp_asan_report,
+        &report.ubsan_report,
+        &report.tsan_report,
+        &report.go_report,
+    ]
+    .iter()
+    .any(|lines| !lines.is_empty())
+    {
+        "casr-san"
+    } else {
+        "casr-gdb"
+    }
+}
+
+/// Get crashing input recorded in report: `InputPath`, `Stdin`, or the first
+/// target argument that is an existing file
+///
+/// # Arguments
+///
+/// * `report` - CASR report
+pub fn report_input(report: &CrashReport) -> Option<PathBuf> {
+    if !report.input_path.is_empty() {
+        Some(PathBuf::from(&report.input_path))
+    } else if !report.stdin.is_empty() {
+        Some(PathBuf::from(&report.stdin))
+    } else {
+        report
+            .proc_cmdline
+            .split_whitespace()
+            .skip(1)
+            .map(Path::new)
+            .find(|path| path.is_file())
+            .map(Path::to_path_buf)
+    }
+}
+
+/// Get target command from report
+///
+/// # Arguments
+///
+/// * `report` - CASR report
+///
+/// * `input` - crashing input that replaces recorded one (see `report_input`)
+///
+/// # Return value
+///
+/// Target argument vector and stdin file
+pub fn report_command(
+    report: &CrashReport,
+    input: Option<&Path>,
+) -> Result<(Vec<String>, Option<PathBuf>)> {
+    let mut argv: Vec<String> = report
+        .proc_cmdline
+        .split_whitespace()
+        .map(|arg| arg.to_string())
+        .collect();
+    if argv.is_empty() {
+        bail!("Report has no command line");
+    }
+    let mut stdin = (!report.stdin.is_empty()).then(|| PathBuf::from(&report.stdin));
+    let Some(input) = input else {
+        return Ok((argv, stdin));
+    };
+    let Some(recorded) = report_input(report) else {
+        bail!("Report has no crashing input");
+    };
+    if input == recorded {
+        return Ok((argv, stdin));
+    }
+    let mut found = false;
+    if stdin.as_deref() == Some(recorded.as_path()) {
+        stdin = Some(input.to_path_buf());
+        found = true;
+    }
+    for arg in argv.iter_mut().skip(1) {
+        // Recorded input path is canonical, while argument may be relative or
+        // point to removed file.
+        let is_input = fs::canonicalize(arg.as_str()).map_or_else(
+            |_| Path::new(arg.as_str()).file_name() == recorded.file_name(),
+            |path| path == recorded,
+        );
+        if is_input {
+            *arg = input.display().to_string();
+            found = true;
+        }
+    }
+    if !found {
+        bail!(
+            "Couldn't find crashing input {} in target command",
+            recorded.display()
+        );
+    }
+    Ok((argv, stdin))
+}
+
+/// Re-run target with casr tool and get new report
+///
+/// # Arguments
+///
+/// * `tool` - path to casr tool
+///
+/// * `argv` - target argument vector
+///
+/// * `stdin` - stdin file for target
+///
+/// * `timeout` - target timeout (in seconds), 0 value means that timeout is
+///   disabled
+///
+/// # Return value
+///
+/// New report, None if target doesn't crash, hangs, or runs out of memory
+pub fn rerun_report(
+    tool: &Path,
+    argv: &[String],
+    stdin: Option<&Path>,
+    timeout: u64,
+) -> Result<Option<CrashReport>> {
+    let mut cmd = Command::new(tool);
+    cmd.arg("--stdout");
+    if let Some(stdin) = stdin {
+        cmd.arg("--stdin").arg(stdin);
+    }
+    if timeout != 0 {
+        cmd.args(["-t", &timeout.to_string()]);
+    }
+    cmd.arg("--").args(argv);
+    debug!("{:?}", cmd);
+    let output = cmd
+        .output()
+        .with_context(|| format!("Couldn't launch {cmd:?}"))?;
+    if output.status.success() {
+        return Ok(Some(CrashReport::from_json(&String::from_utf8_lossy(
+            &output.stdout,
+        ))?));
+    }
+    let err = String::from_utf8_lossy(&output.stderr);
+    if ["Program terminated (no crash)", "Timeout", "Out of memory"]
+        .iter()
+        .any(|message| err.contains(message))
+    {
+        Ok(None)
+    } else {
+        bail!("{}", err.trim());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_tool_name() {
+        let mut report = CrashReport::new();
+        assert_eq!(report_tool_name(&report), "casr-gdb");
+        report.gwp_asan_report = vec!["*** GWP-ASan detected a memory error ***".to_string()];
+        assert_eq!(report_tool_name(&report), "casr-san");
+        report.python_report = vec!["Traceback (most recent call last):".to_string()];
+        assert_eq!(report_tool_name(&report), "casr-python");
+    }
+}
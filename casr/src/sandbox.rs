@@ -0,0 +1,206 @@
+//! Sandbox module re-executes CASR tool inside bubblewrap (bwrap) sandbox with
+//! fresh mount, PID, network, IPC, and UTS namespaces, new session, read-only
+//! root, and tmpfs current directory, so that untrusted crashing inputs can't
+//! clobber the host.
+use anyhow::{bail, Context, Result};
+use clap::{Arg, ArgAction, ArgMatches};
+use log::debug;
+
+use crate::config;
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Environment variable that is set for CASR tool inside sandbox.
+const SANDBOXED_ENV: &str = "CASR_SANDBOXED";
+
+/// Get sandbox options: `--sandbox`, `--bind`, and `--ro-bind`.
+pub fn sandbox_args() -> [Arg; 3] {
+    [
+        Arg::new("sandbox")
+            .long("sandbox")
+            .env("CASR_SANDBOX")
+            .action(ArgAction::SetTrue)
+            .value_parser(clap::builder::FalseyValueParser::new())
+            .help("Run target in bwrap sandbox with fresh mount/PID/network/IPC/UTS namespaces, new session, read-only root, and tmpfs current directory"),
+        Arg::new("bind")
+            .long("bind")
+            .action(ArgAction::Append)
+            .requires("sandbox")
+            .value_name("SRC[:DST]")
+            .help("Writable bind mount for sandbox"),
+        Arg::new("ro-bind")
+            .long("ro-bind")
+            .action(ArgAction::Append)
+            .requires("sandbox")
+            .value_name("SRC[:DST]")
+            .help("Read-only bind mount for sandbox"),
+    ]
+}
+
+/// Sandbox settings.
+#[derive(Clone, Debug, Default)]
+pub struct Sandbox {
+    /// Writable bind mounts (source, destination).
+    pub binds: Vec<(PathBuf, PathBuf)>,
+    /// Read-only bind mounts (source, destination).
+    pub ro_binds: Vec<(PathBuf, PathBuf)>,
+}
+
+impl Sandbox {
+    /// Get sandbox settings from tool arguments
+    ///
+    /// # Arguments
+    ///
+    /// * `matches` - tool arguments
+    ///
+    /// # Return value
+    ///
+    /// Sandbox settings or None if `--sandbox` is not specified or tool is
+    /// already running inside sandbox
+    pub fn from_matches(matches: &ArgMatches) -> Result<Option<Self>> {
+        if !matches.get_flag("sandbox") || std::env::var_os(SANDBOXED_ENV).is_some() {
+            return Ok(None);
+        }
+        let parse = |id: &str| -> Result<Vec<(PathBuf, PathBuf)>> {
+            matches
+                .get_many::<String>(id)
+                .unwrap_or_default()
+                .map(|bind| {
+                    let (src, dst) = bind.split_once(':').unwrap_or((bind, bind));
+                    let src = Path::new(src)
+                        .canonicalize()
+                        .with_context(|| format!("Couldn't find bind mount source {src}"))?;
+                    Ok((src, absolute(Path::new(dst))?))
+                })
+                .collect()
+        };
+        Ok(Some(Sandbox {
+            binds: parse("bind")?,
+            ro_binds: parse("ro-bind")?,
+        }))
+    }
+
+    /// Re-execute current CASR tool with the same arguments inside sandbox
+    /// and exit with its exit code. Existing files from tool arguments (e.g.,
+    /// target binary and input), stdin and configuration files are bound
+    /// read-only, while report path is writable.
+    ///
+    /// # Arguments
+    ///
+    /// * `matches` - tool arguments
+    pub fn reexec(&self, matches: &ArgMatches) -> Result<()> {
+        let bwrap = which::which("bwrap").with_context(|| "No bwrap in PATH")?;
+        let cwd = std::env::current_dir()?;
+        if cwd == Path::new("/") {
+            bail!("Sandbox can't be run from root directory, because tmpfs would hide it");
+        }
+        let mut cmd = Command::new(bwrap);
+        // New session detaches target from controlling terminal, so it can't
+        // inject input into it with TIOCSTI (CVE-2017-5226).
+        cmd.args(["--die-with-parent", "--new-session"])
+            .args([
+                "--unshare-pid",
+                "--unshare-net",
+                "--unshare-ipc",
+                "--unshare-uts",
+            ])
+            .args(["--ro-bind", "/", "/"])
+            .args(["--dev", "/dev", "--proc", "/proc", "--tmpfs", "/tmp"])
+            .arg("--tmpfs")
+            .arg(&cwd);
+        // Files from tool arguments (including `--opt=FILE` values) and
+        // configuration files may be hidden by tmpfs, so they are bound back.
+        let args: Vec<OsString> = std::env::args_os().collect();
+        let values = args.iter().skip(1).flat_map(|arg| {
+            let value = arg
+                .to_str()
+                .and_then(|arg| arg.split_once('='))
+                .map(|(_, value)| PathBuf::from(value));
+            [Some(PathBuf::from(arg)), value]
+        });
+        let stdin = matches
+            .try_get_one::<PathBuf>("stdin")
+            .ok()
+            .flatten()
+            .cloned();
+        let configs = config::config_paths().into_iter().map(Some);
+        for path in values.chain([stdin]).chain(configs).flatten() {
+            if !path.exists() {
+                continue;
+            }
+            let path = absolute(&path)?;
+            // Binding tmpfs directory or its parent would cancel tmpfs.
+            if covers_tmpfs(&cwd, &path) {
+                continue;
+            }
+            cmd.arg("--ro-bind").arg(&path).arg(&path);
+        }
+        if let Some(output) = matches.get_one::<PathBuf>("output") {
+            let output = absolute(output)?;
+            // Writable bind of tmpfs directory or its parent would make host
+            // directory writable.
+            if covers_tmpfs(&cwd, &output) {
+                bail!(
+                    "Report path {} can't be current directory, /tmp, or their parent in sandbox",
+                    output.display()
+                );
+            }
+            if !output.exists() {
+                // Report is written to bound file.
+                fs::write(&output, "")
+                    .with_context(|| format!("Couldn't create {}", output.display()))?;
+            }
+            cmd.arg("--bind").arg(&output).arg(&output);
+        }
+        for (src, dst) in &self.ro_binds {
+            cmd.arg("--ro-bind").arg(src).arg(dst);
+        }
+        for (src, dst) in &self.binds {
+            cmd.arg("--bind").arg(src).arg(dst);
+        }
+        cmd.arg("--chdir")
+            .arg(&cwd)
+            .args(["--setenv", SANDBOXED_ENV, "1", "--"])
+            .arg(std::env::current_exe()?)
+            .args(&args[1..]);
+        debug!("Running {:?}", cmd);
+        let status = cmd
+            .status()
+            .with_context(|| format!("Couldn't launch {cmd:?}"))?;
+        let Some(code) = status.code() else {
+            bail!("Sandbox is terminated: {status}");
+        };
+        std::process::exit(code);
+    }
+}
+
+/// Check whether bind mount of path would cover tmpfs directory (current
+/// directory or /tmp), i.e., path is tmpfs directory or its parent. Path is
+/// resolved (`..` and symlinks) if it exists.
+///
+/// # Arguments
+///
+/// * `cwd` - current directory
+///
+/// * `path` - absolute bind mount path
+fn covers_tmpfs(cwd: &Path, path: &Path) -> bool {
+    let real = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    [path, real.as_path()]
+        .iter()
+        .any(|path| cwd.starts_with(path) || Path::new("/tmp").starts_with(path))
+}
+
+/// Get absolute path without resolving symlinks
+///
+/// # Arguments
+///
+/// * `path` - path
+fn absolute(path: &Path) -> Result<PathBuf> {
+    if path.is_absolute() {
+        Ok(path.to_path_buf())
+    } else {
+        Ok(std::env::current_dir()?.join(path))
+    }
+}
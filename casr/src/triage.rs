@@ -1,12 +1,15 @@
 //! Post-fuzzing crash analysis module: create, deduplicate, cluster CASR reports
 //! and print overall summary.
-use crate::util::{get_path, initialize_dirs, log_progress};
+use crate::util::{
+    forward_input_args, forward_source_args, get_path, initialize_dirs, log_progress,
+};
 
 use std::collections::HashMap;
 use std::fs;
 use std::os::fd::AsFd;
 use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::RwLock;
 
 use anyhow::{bail, Context, Result};
@@ -14,6 +17,105 @@ use log::{debug, error, info, warn};
 use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
 use walkdir::WalkDir;
 
+/// Result of running casr tool on crash input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RunStatus {
+    /// Report is created.
+    Crash,
+    /// Target execution timed out.
+    Timeout,
+    /// Target ran out of memory.
+    Oom,
+    /// Target terminated without crash.
+    NoCrash,
+    /// Casr tool failed to create report.
+    Error,
+}
+
+/// Counters of crash inputs processing results.
+#[derive(Debug, Default)]
+pub struct TriageProgress {
+    /// Number of processed inputs.
+    done: AtomicUsize,
+    /// Number of created reports.
+    crashes: AtomicUsize,
+    /// Number of timeouts.
+    timeouts: AtomicUsize,
+    /// Number of out of memory errors.
+    ooms: AtomicUsize,
+    /// Number of inputs without crash.
+    no_crash: AtomicUsize,
+    /// Number of inputs casr tool failed on.
+    errors: AtomicUsize,
+}
+
+impl TriageProgress {
+    /// Count processed input.
+    ///
+    /// # Arguments
+    ///
+    /// * `status` - result of running casr tool on input
+    pub fn add(&self, status: RunStatus) {
+        let counter = match status {
+            RunStatus::Crash => &self.crashes,
+            RunStatus::Timeout => &self.timeouts,
+            RunStatus::Oom => &self.ooms,
+            RunStatus::NoCrash => &self.no_crash,
+            RunStatus::Error => &self.errors,
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+        self.done.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Get number of processed inputs.
+    pub fn done(&self) -> usize {
+        self.done.load(Ordering::Relaxed)
+    }
+
+    /// Stop progress logging, e.g., on error.
+    ///
+    /// # Arguments
+    ///
+    /// * `total` - total number of inputs
+    pub fn finish(&self, total: usize) {
+        self.done.store(total, Ordering::Relaxed);
+    }
+
+    /// Log progress each second until all inputs are processed.
+    ///
+    /// # Arguments
+    ///
+    /// * `total` - total number of inputs
+    pub fn log(&self, total: usize) {
+        let mut cnt = 0;
+        loop {
+            let current = self.done();
+            if current >= total {
+                return;
+            }
+            if current > cnt {
+                info!("Progress: {}/{} ({})", current, total, self);
+            }
+            cnt = current;
+            std::thread::sleep(std::time::Duration::from_millis(1000));
+        }
+    }
+}
+
+impl std::fmt::Display for TriageProgress {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "crashes: {}, timeouts: {}, oom: {}, no crash: {}, errors: {}",
+            self.crashes.load(Ordering::Relaxed),
+            self.timeouts.load(Ordering::Relaxed),
+            self.ooms.load(Ordering::Relaxed),
+            self.no_crash.load(Ordering::Relaxed),
+            self.errors.load(Ordering::Relaxed)
+        )
+    }
+}
+
 #[derive(Debug, Clone, Default)]
 /// Information about crash to reproduce it.
 pub struct CrashInfo {
@@ -27,9 +129,18 @@ pub struct CrashInfo {
     pub at_index: Option<usize>,
     /// Casr tool that should be run on this crash.
     pub casr_tool: PathBuf,
+    /// Name for report and saved crash, crash input file name if None.
+    pub name: Option<String>,
 }
 
 impl<'a> CrashInfo {
+    /// Get crash name that is used for report and saved crash file names.
+    pub fn name(&self) -> String {
+        self.name
+            .clone()
+            .unwrap_or_else(|| self.path.file_name().unwrap().to_string_lossy().to_string())
+    }
+
     /// Generate Casr report for crash.
     ///
     /// # Arguments
@@ -37,12 +148,20 @@ impl<'a> CrashInfo {
     /// * `output_dir` - save report to specified directory or use the same directory as crash
     ///
     /// * `timeout` - target program timeout (in seconds)
-    pub fn run_casr<T: Into<Option<&'a Path>>>(&self, output_dir: T, timeout: u64) -> Result<()> {
+    ///
+    /// # Return value
+    ///
+    /// Result of running casr tool on crash input
+    pub fn run_casr<T: Into<Option<&'a Path>>>(
+        &self,
+        output_dir: T,
+        timeout: u64,
+    ) -> Result<RunStatus> {
         let tool = &self.casr_tool;
         let tool_name = tool.file_name().unwrap().to_str().unwrap();
         let mut args: Vec<String> = vec!["-o".to_string()];
         let (report_path, output_dir) = if let Some(out) = output_dir.into() {
-            (out.join(self.path.file_name().unwrap()), out)
+            (out.join(self.name()), out)
         } else {
             (self.path.clone(), self.path.parent().unwrap())
         };
@@ -94,16 +213,13 @@ impl<'a> CrashInfo {
             .output()
             .with_context(|| format!("Couldn't launch {casr_cmd:?}"))?;
 
-        if !casr_output.status.success() {
+        let status = if casr_output.status.success() {
+            debug!("{tool_name}: created report for {}", self.path.display());
+            RunStatus::Crash
+        } else {
             let err = String::from_utf8_lossy(&casr_output.stderr);
             if err.contains("Timeout") {
-                let mut timeout_name = self
-                    .path
-                    .file_name()
-                    .unwrap()
-                    .to_os_string()
-                    .into_string()
-                    .unwrap();
+                let mut timeout_name = self.name();
                 if let Some(idx) = timeout_name.find('-') {
                     timeout_name.replace_range(..idx, "timeout");
                 }
@@ -111,14 +227,9 @@ impl<'a> CrashInfo {
                 if fs::copy(&self.path, timeout_path).is_err() {
                     error!("Error occurred while copying the file: {:?}", self.path);
                 }
+                RunStatus::Timeout
             } else if err.contains("Out of memory") {
-                let mut oom_name = self
-                    .path
-                    .file_name()
-                    .unwrap()
-                    .to_os_string()
-                    .into_string()
-                    .unwrap();
+                let mut oom_name = self.name();
                 if let Some(idx) = oom_name.find('-') {
                     oom_name.replace_range(..idx, "oom");
                 }
@@ -126,14 +237,17 @@ impl<'a> CrashInfo {
                 if fs::copy(&self.path, oom_path).is_err() {
                     error!("Error occurred while copying the file: {:?}", self.path);
                 }
+                RunStatus::Oom
             } else if err.contains("Program terminated (no crash)") {
                 warn!("{}: No crash on input {}", tool_name, self.path.display());
+                RunStatus::NoCrash
             } else {
                 error!("{} for input: {}", err.trim(), self.path.display());
+                RunStatus::Error
             }
-        }
+        };
 
-        Ok(())
+        Ok(status)
     }
 }
 
@@ -144,7 +258,7 @@ impl<'a> CrashInfo {
 /// * `matches` - casr-afl/casr-libfuzzer arguments
 ///
 /// * `crashes` - map of crashes, specified as a HashMap, where
-///               key is crash input file name and value is CrashInfo structure
+///               key is crash name and value is CrashInfo structure
 ///
 /// * `gdb_args` - casr-gdb target arguments. If they are empty, casr-gdb won't be launched.
 pub fn fuzzing_crash_triage_pipeline(
@@ -161,6 +275,10 @@ pub fn fuzzing_crash_triage_pipeline(
 
     let output_dir = initialize_dirs(matches)?;
 
+    // Save crashing inputs and source contexts to reports.
+    forward_input_args(matches);
+    forward_source_args(matches);
+
     // Get timeout
     let timeout = *matches.get_one::<u64>("timeout").unwrap();
 
@@ -183,24 +301,27 @@ pub fn fuzzing_crash_triage_pipeline(
     // Generate CASR reports.
     info!("Generating CASR reports...");
     info!("Using {} threads", num_of_threads - 1);
-    let counter = RwLock::new(0_usize);
+    let progress = TriageProgress::default();
     let total = crashes.len();
     custom_pool
         .join(
             || {
                 crashes.par_iter().try_for_each(|(_, crash)| {
-                    if let Err(e) = crash.run_casr(output_dir.as_path(), timeout) {
-                        // Disable util::log_progress
-                        *counter.write().unwrap() = total;
-                        bail!(e);
-                    };
-                    *counter.write().unwrap() += 1;
+                    match crash.run_casr(output_dir.as_path(), timeout) {
+                        Ok(status) => progress.add(status),
+                        Err(e) => {
+                            // Disable progress logging
+                            progress.finish(total);
+                            bail!(e);
+                        }
+                    }
                     Ok::<(), anyhow::Error>(())
                 })
             },
-            || log_progress(&counter, total),
+            || progress.log(total),
         )
         .0?;
+    info!("Processed {} inputs ({})", total, progress);
 
     // Deduplicate reports.
     if output_dir.read_dir()?.count() < 2 {
@@ -208,9 +329,16 @@ pub fn fuzzing_crash_triage_pipeline(
         return summarize_results(matches, crashes, gdb_args);
     }
     info!("Deduplicating CASR reports...");
-    let casr_cluster_d = Command::new(&casr_cluster)
+    let mut casr_cluster_d = Command::new(&casr_cluster);
+    casr_cluster_d
         .arg("-d")
-        .arg(output_dir.clone().into_os_string())
+        .arg(output_dir.clone().into_os_string());
+    // Reports of known bugs are moved to "known" directory before
+    // deduplication, so they don't get into clusters.
+    if let Some(known_bugs) = matches.get_one::<PathBuf>("known-bugs") {
+        casr_cluster_d.arg("--known-bugs").arg(known_bugs);
+    }
+    let casr_cluster_d = casr_cluster_d
         .output()
         .with_context(|| format!("Couldn't launch {casr_cluster:?}"))?;
 
@@ -338,6 +466,7 @@ fn summarize_results(
                                 envs: HashMap::new(),
                                 at_index,
                                 casr_tool: casr_gdb.clone(),
+                                name: None,
                             })
                             .run_casr(None, timeout)
                             {
@@ -352,7 +481,11 @@ fn summarize_results(
                     || log_progress(&counter, total),
                 )
                 .0?;
+        } else {
+            debug!("casr-gdb: all crashes already have reports, skipping");
         }
+    } else {
+        debug!("casr-gdb is skipped: no uninstrumented target is specified in ARGS");
     }
 
     let casr_cli = get_path("casr-cli")?;
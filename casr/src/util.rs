@@ -1,22 +1,30 @@
 //! Common utility functions.
 extern crate libcasr;
 
+use crate::gdb::GdbCommand;
+use libcasr::execution_class::{set_near_null_threshold, DEFAULT_NEAR_NULL_THRESHOLD};
+use libcasr::gdb::exploitable::MachineInfo;
 use libcasr::report::CrashReport;
 use libcasr::stacktrace::{
     STACK_FRAME_FILEPATH_IGNORE_REGEXES, STACK_FRAME_FUNCTION_IGNORE_REGEXES,
 };
 
 use anyhow::{bail, Context, Result};
-use clap::ArgMatches;
-use log::{info, warn};
+use clap::{Arg, ArgAction, ArgMatches};
+use gdb_command::mappings::MappedFiles;
+use gdb_command::ExecType;
+use goblin::container::Endian;
+use goblin::elf::{header, Elf};
+use log::{debug, info, warn};
+use regex::Regex;
 use simplelog::*;
-use std::collections::HashSet;
+use std::collections::{HashSet, VecDeque};
 use std::fs::{self, OpenOptions};
 use std::io::Write;
-use std::io::{BufRead, BufReader};
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom};
 use std::path::{Path, PathBuf};
-use std::process::{Command, Output, Stdio};
-use std::sync::RwLock;
+use std::process::{Child, Command, Output, Stdio};
+use std::sync::{Mutex, RwLock};
 use std::time::Duration;
 
 use is_executable::IsExecutable;
@@ -45,6 +53,9 @@ pub fn call_casr_san(matches: &ArgMatches, argv: &[&str], name: &str) -> Result<
     if let Some(path) = matches.get_one::<String>("ignore") {
         cmd.args(["--ignore", path]);
     }
+    if let Some(aslr) = matches.get_one::<String>("aslr") {
+        cmd.args(["--aslr", aslr]);
+    }
     cmd.arg("--").args(argv);
 
     let output = cmd
@@ -70,7 +81,23 @@ pub fn call_casr_san(matches: &ArgMatches, argv: &[&str], name: &str) -> Result<
 ///
 /// * `argv` - executable file options
 pub fn output_report(report: &CrashReport, matches: &ArgMatches, argv: &[&str]) -> Result<()> {
-    // Convert report to string.
+    // Compute severity score and stack hash, store parsed frames, crash frame
+    // inline chain, and module bases, and convert report to string.
+    let mut report = report.clone();
+    report.severity_score = report.execution_class.score();
+    report.set_frames();
+    report.set_inline_frames();
+    report.set_stack_hash();
+    if report.module_bases.is_empty() {
+        report.set_module_bases();
+    }
+    attach_input(&mut report, matches, argv)?;
+    if matches.contains_id("source-frames") {
+        report.set_source_contexts(
+            *matches.get_one::<usize>("source-frames").unwrap(),
+            *matches.get_one::<usize>("source-radius").unwrap(),
+        );
+    }
     let repstr = serde_json::to_string_pretty(&report).unwrap();
 
     if matches.contains_id("stdout") && matches.get_flag("stdout") {
@@ -118,6 +145,45 @@ pub fn output_report(report: &CrashReport, matches: &ArgMatches, argv: &[&str])
     Ok(())
 }
 
+/// Save crashing input path, hash, and optional base64 copy to report if
+/// `--attach-input` or `--inline-input` is specified. Crashing input is stdin
+/// file or the first target argument that is an existing file.
+///
+/// # Arguments
+///
+/// * `report` - report to fill
+///
+/// * `matches` - casr options
+///
+/// * `argv` - executable file options
+fn attach_input(report: &mut CrashReport, matches: &ArgMatches, argv: &[&str]) -> Result<()> {
+    let inline_limit = if matches.contains_id("inline-input") {
+        Some((*matches.get_one::<u64>("inline-input").unwrap() << 10) as usize)
+    } else {
+        None
+    };
+    if inline_limit.is_none()
+        && !(matches.contains_id("attach-input") && matches.get_flag("attach-input"))
+    {
+        return Ok(());
+    }
+    let input = if !report.stdin.is_empty() {
+        Some(PathBuf::from(&report.stdin))
+    } else {
+        argv.iter()
+            .skip(1)
+            .map(Path::new)
+            .find(|path| path.is_file())
+            .map(Path::to_path_buf)
+    };
+    if let Some(input) = input {
+        report.set_input(&input, inline_limit.unwrap_or_default())?;
+    } else {
+        warn!("Couldn't find crashing input to attach to report");
+    }
+    Ok(())
+}
+
 /// Add custom regex for frames from user that should be ignored during analysis
 ///
 /// # Arguments
@@ -182,13 +248,426 @@ pub fn stdin_from_matches(matches: &ArgMatches) -> Result<Option<PathBuf>> {
     }
 }
 
-/// Initialize logging with level from command line arguments (debug or info).
-pub fn initialize_logging(matches: &ArgMatches) {
-    let log_level = if matches.get_one::<String>("log-level").unwrap() == "debug" {
-        LevelFilter::Debug
+/// Get common logging options: `-v/--verbose`, `-q/--quiet`, and `--log-format`.
+pub fn logging_args() -> [Arg; 3] {
+    [
+        Arg::new("verbose")
+            .short('v')
+            .long("verbose")
+            .action(ArgAction::Count)
+            .conflicts_with("quiet")
+            .help("Increase logging verbosity (-vv prints trace messages)"),
+        Arg::new("quiet")
+            .short('q')
+            .long("quiet")
+            .action(ArgAction::Count)
+            .help("Decrease logging verbosity (-qq prints only errors, -qqq disables logging)"),
+        Arg::new("log-format")
+            .long("log-format")
+            .env("CASR_LOG_FORMAT")
+            .action(ArgAction::Set)
+            .default_value("text")
+            .value_parser(["text", "json"])
+            .help("Log output format, json prints one JSON object per message"),
+    ]
+}
+
+/// Get crashing input options: `--attach-input` and `--inline-input`.
+pub fn input_args() -> [Arg; 2] {
+    [
+        Arg::new("attach-input")
+            .long("attach-input")
+            .env("CASR_ATTACH_INPUT")
+            .action(ArgAction::SetTrue)
+            .value_parser(clap::builder::FalseyValueParser::new())
+            .help("Save crashing input path and SHA-256 hash to report, crashing input is stdin file or the first target argument that is an existing file"),
+        Arg::new("inline-input")
+            .long("inline-input")
+            .env("CASR_INLINE_INPUT")
+            .action(ArgAction::Set)
+            .value_name("KB")
+            .value_parser(clap::value_parser!(u64))
+            .help("Save base64 copy of crashing input to report as well if input isn't larger than KB (implies --attach-input)"),
+    ]
+}
+
+/// Pass crashing input options to sub tools.
+///
+/// # Arguments
+///
+/// * `matches` - tool arguments
+pub fn forward_input_args(matches: &ArgMatches) {
+    if matches.get_flag("attach-input") {
+        std::env::set_var("CASR_ATTACH_INPUT", "true");
+    }
+    if let Some(size) = matches.get_one::<u64>("inline-input") {
+        std::env::set_var("CASR_INLINE_INPUT", size.to_string());
+    }
+}
+
+/// Get source context arguments that are shared by tools creating reports.
+pub fn source_args() -> [Arg; 2] {
+    [
+        Arg::new("source-frames")
+            .long("source-frames")
+            .env("CASR_SOURCE_FRAMES")
+            .action(ArgAction::Set)
+            .value_name("N")
+            .value_parser(clap::value_parser!(usize))
+            .help("Save source code fragments around locations of the top N stack trace frames with available sources to report"),
+        Arg::new("source-radius")
+            .long("source-radius")
+            .env("CASR_SOURCE_RADIUS")
+            .action(ArgAction::Set)
+            .default_value("5")
+            .value_name("LINES")
+            .value_parser(clap::value_parser!(usize))
+            .help("Number of source lines saved before and after frame line for --source-frames"),
+    ]
+}
+
+/// Pass source context options to sub tools.
+///
+/// # Arguments
+///
+/// * `matches` - tool arguments
+pub fn forward_source_args(matches: &ArgMatches) {
+    if let Some(frames) = matches.get_one::<usize>("source-frames") {
+        std::env::set_var("CASR_SOURCE_FRAMES", frames.to_string());
+        let radius = matches.get_one::<usize>("source-radius").unwrap();
+        std::env::set_var("CASR_SOURCE_RADIUS", radius.to_string());
+    }
+}
+
+/// Get debugger timeout argument that is shared by tools launching gdb.
+pub fn debugger_timeout_arg() -> Arg {
+    Arg::new("debugger-timeout")
+        .long("debugger-timeout")
+        .env("CASR_DEBUGGER_TIMEOUT")
+        .action(ArgAction::Set)
+        .default_value("300")
+        .value_name("SECONDS")
+        .value_parser(clap::value_parser!(u64))
+        .help("Timeout (in seconds) for debugger, hung debugger is killed and relaunched to get only stack trace, 0 value means that timeout is disabled")
+}
+
+/// Get near null threshold argument that is shared by tools estimating severity.
+pub fn near_null_arg() -> Arg {
+    Arg::new("near-null")
+        .long("near-null")
+        .env("CASR_NEAR_NULL")
+        .action(ArgAction::Set)
+        .value_name("BYTES")
+        .value_parser(|arg: &str| {
+            if let Some(hex) = arg.strip_prefix("0x") {
+                u64::from_str_radix(hex, 16)
+            } else {
+                arg.parse::<u64>()
+            }
+        })
+        .help("Memory addresses less than this value (decimal or hex) are considered near null [default: max of 64 KiB, page size, and vm.mmap_min_addr]")
+}
+
+/// Set near null threshold for severity estimation from `--near-null` option.
+/// By default, threshold is the maximum of 64 KiB, page size, and
+/// `vm.mmap_min_addr` because lower addresses couldn't be mapped.
+///
+/// # Arguments
+///
+/// * `matches` - tool arguments
+pub fn init_near_null_threshold(matches: &ArgMatches) {
+    if let Some(threshold) = matches.get_one::<u64>("near-null") {
+        set_near_null_threshold(*threshold);
+        // Pass threshold to sub tools.
+        std::env::set_var("CASR_NEAR_NULL", threshold.to_string());
+        return;
+    }
+    let mut threshold = DEFAULT_NEAR_NULL_THRESHOLD;
+    #[cfg(unix)]
+    {
+        let page_size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) };
+        if page_size > 0 {
+            threshold = threshold.max(page_size as u64);
+        }
+    }
+    if let Some(mmap_min_addr) = fs::read_to_string("/proc/sys/vm/mmap_min_addr")
+        .ok()
+        .and_then(|value| value.trim().parse::<u64>().ok())
+    {
+        threshold = threshold.max(mmap_min_addr);
+    }
+    set_near_null_threshold(threshold);
+}
+
+/// Get `--aslr` option that is shared by tools running target.
+pub fn aslr_arg() -> Arg {
+    Arg::new("aslr")
+        .long("aslr")
+        .env("CASR_ASLR")
+        .action(ArgAction::Set)
+        .value_name("MODE")
+        .value_parser(["on", "off"])
+        .help("Enable or disable ASLR for target, setting is recorded in report [default: off for casr-san and casr-gdb, system setting otherwise]")
+}
+
+/// Get ASLR setting from `--aslr` option
+///
+/// # Arguments
+///
+/// * `matches` - tool arguments
+///
+/// # Return value
+///
+/// true if ASLR is enabled, false if it is disabled, None if option is not
+/// specified
+pub fn aslr_from_matches(matches: &ArgMatches) -> Option<bool> {
+    matches.get_one::<String>("aslr").map(|mode| mode == "on")
+}
+
+/// Disable ASLR for target command: personality is set on Linux and procctl
+/// is called on FreeBSD (macOS doesn't support it, see
+/// `get_output_without_aslr`)
+///
+/// # Arguments
+///
+/// * `command` - target command
+pub fn disable_aslr(command: &mut Command) {
+    #[cfg(target_os = "linux")]
+    unsafe {
+        use std::os::unix::process::CommandExt;
+        command.pre_exec(|| {
+            if linux_personality::personality(linux_personality::ADDR_NO_RANDOMIZE).is_err() {
+                panic!("Cannot set personality");
+            }
+            Ok(())
+        });
+    }
+    #[cfg(target_os = "freebsd")]
+    unsafe {
+        use std::os::unix::process::CommandExt;
+        command.pre_exec(|| {
+            let mut arg = libc::PROC_ASLR_FORCE_DISABLE;
+            if libc::procctl(
+                libc::P_PID,
+                0,
+                libc::PROC_ASLR_CTL,
+                &mut arg as *mut _ as *mut libc::c_void,
+            ) != 0
+            {
+                panic!("Cannot disable ASLR");
+            }
+            Ok(())
+        });
+    }
+    #[cfg(not(any(target_os = "linux", target_os = "freebsd")))]
+    {
+        let _ = command;
+        warn!("Disabling ASLR is not supported on this platform");
+    }
+}
+
+/// Add gdb command that enables ASLR for target (gdb disables it by default),
+/// it should be added before `run`
+///
+/// # Arguments
+///
+/// * `gdb_command` - gdb command
+///
+/// * `aslr` - enable ASLR
+///
+/// # Return value
+///
+/// Number of added commands, their results should be skipped
+pub fn set_gdb_aslr(gdb_command: &mut GdbCommand, aslr: bool) -> usize {
+    if aslr {
+        gdb_command.ex("set disable-randomization off");
+    }
+    aslr as usize
+}
+
+/// Get target environment options: `--env`, `--inherit-env`, and `--clear-env`.
+pub fn target_env_args() -> [Arg; 3] {
+    [
+        Arg::new("env")
+            .long("env")
+            .action(ArgAction::Append)
+            .value_name("KEY=VAL")
+            .value_parser(|arg: &str| {
+                arg.split_once('=')
+                    .filter(|(key, _)| !key.is_empty())
+                    .map(|(key, value)| (key.to_string(), value.to_string()))
+                    .ok_or(format!("{arg} is not KEY=VAL"))
+            })
+            .help("Set environment variable for target (may be repeated)"),
+        Arg::new("inherit-env")
+            .long("inherit-env")
+            .action(ArgAction::SetTrue)
+            .conflicts_with("clear-env")
+            .help("Target inherits environment of CASR tool (default)"),
+        Arg::new("clear-env")
+            .long("clear-env")
+            .action(ArgAction::SetTrue)
+            .help("Target environment contains only variables from --env and options set by CASR tool (e.g., ASAN_OPTIONS)"),
+    ]
+}
+
+/// Target program environment that is separate from CASR tool environment.
+#[derive(Clone, Debug, Default)]
+pub struct TargetEnv {
+    /// Target doesn't inherit CASR tool environment.
+    pub clear: bool,
+    /// Variables that are set for target.
+    pub vars: Vec<(String, String)>,
+}
+
+impl TargetEnv {
+    /// Get target environment from tool arguments
+    ///
+    /// # Arguments
+    ///
+    /// * `matches` - tool arguments
+    pub fn from_matches(matches: &ArgMatches) -> Self {
+        TargetEnv {
+            clear: matches.get_flag("clear-env"),
+            vars: matches
+                .get_many::<(String, String)>("env")
+                .unwrap_or_default()
+                .cloned()
+                .collect(),
+        }
+    }
+
+    /// Get target environment variable value
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - variable name
+    pub fn var(&self, key: &str) -> Option<String> {
+        if let Some((_, value)) = self.vars.iter().rev().find(|(name, _)| name == key) {
+            Some(value.clone())
+        } else if self.clear {
+            None
+        } else {
+            std::env::var(key).ok()
+        }
+    }
+
+    /// Set target environment variable
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - variable name
+    ///
+    /// * `value` - variable value
+    pub fn set_var(&mut self, key: &str, value: &str) {
+        self.vars.retain(|(name, _)| name != key);
+        self.vars.push((key.to_string(), value.to_string()));
+    }
+
+    /// Set target environment for command
+    ///
+    /// # Arguments
+    ///
+    /// * `command` - target command
+    pub fn apply(&self, command: &mut Command) {
+        if self.clear {
+            command.env_clear();
+        }
+        command.envs(self.vars.iter().map(|(key, value)| (key, value)));
+    }
+
+    /// Add gdb commands that set target environment, they should be added
+    /// before `run`
+    ///
+    /// # Arguments
+    ///
+    /// * `gdb_command` - gdb command
+    ///
+    /// # Return value
+    ///
+    /// Number of added commands, their results should be skipped
+    pub fn set_gdb_env(&self, gdb_command: &mut GdbCommand) -> usize {
+        if self.clear {
+            gdb_command.ex("unset environment");
+        }
+        for (key, value) in &self.vars {
+            gdb_command.ex(format!("set environment {key}={value}"));
+        }
+        self.vars.len() + self.clear as usize
+    }
+
+    /// Update environment list (`KEY=VAL`) from report with target variables
+    ///
+    /// # Arguments
+    ///
+    /// * `environ` - environment list
+    pub fn update_environ(&self, environ: &mut Vec<String>) {
+        if self.clear {
+            environ.clear();
+        }
+        for (key, value) in &self.vars {
+            let prefix = format!("{key}=");
+            environ.retain(|var| !var.starts_with(&prefix));
+            environ.push(format!("{prefix}{value}"));
+        }
+    }
+}
+
+/// Get logging level from `log-level`, `verbose`, and `quiet` options.
+///
+/// # Arguments
+///
+/// * `matches` - tool arguments
+pub fn log_level(matches: &ArgMatches) -> LevelFilter {
+    let levels = [
+        LevelFilter::Off,
+        LevelFilter::Error,
+        LevelFilter::Warn,
+        LevelFilter::Info,
+        LevelFilter::Debug,
+        LevelFilter::Trace,
+    ];
+    let base = if let Ok(Some(level)) = matches.try_get_one::<String>("log-level") {
+        if level == "debug" {
+            4
+        } else {
+            3
+        }
     } else {
-        LevelFilter::Info
+        3
     };
+    let verbose = matches
+        .try_get_one::<u8>("verbose")
+        .ok()
+        .flatten()
+        .map_or(0, |v| *v as usize);
+    let quiet = matches
+        .try_get_one::<u8>("quiet")
+        .ok()
+        .flatten()
+        .map_or(0, |q| *q as usize);
+    levels[(base + verbose).saturating_sub(quiet).min(levels.len() - 1)]
+}
+
+/// Check whether logs should be printed in JSON format.
+///
+/// # Arguments
+///
+/// * `matches` - tool arguments
+pub fn is_json_log(matches: &ArgMatches) -> bool {
+    matches!(
+        matches.try_get_one::<String>("log-format"),
+        Ok(Some(format)) if format == "json"
+    )
+}
+
+/// Initialize logging with level and format from command line arguments.
+pub fn initialize_logging(matches: &ArgMatches) {
+    let log_level = log_level(matches);
+    if is_json_log(matches) {
+        let _ = JsonLogger::init(log_level, Box::new(std::io::stderr()));
+        return;
+    }
     let _ = TermLogger::init(
         log_level,
         ConfigBuilder::new()
@@ -200,16 +679,72 @@ pub fn initialize_logging(matches: &ArgMatches) {
     );
 }
 
+/// Logger that prints each message as a JSON object on a separate line.
+pub struct JsonLogger {
+    /// Maximum logging level.
+    level: LevelFilter,
+    /// Log output.
+    output: Mutex<Box<dyn Write + Send>>,
+}
+
+impl JsonLogger {
+    /// Set JSON logger as a global logger.
+    ///
+    /// # Arguments
+    ///
+    /// * `level` - maximum logging level
+    ///
+    /// * `output` - log output
+    pub fn init(level: LevelFilter, output: Box<dyn Write + Send>) -> Result<()> {
+        let logger = Box::leak(Box::new(JsonLogger {
+            level,
+            output: Mutex::new(output),
+        }));
+        if log::set_logger(logger).is_err() {
+            bail!("Logger is already initialized");
+        }
+        log::set_max_level(level);
+        Ok(())
+    }
+}
+
+impl log::Log for JsonLogger {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        metadata.level() <= self.level
+    }
+
+    fn log(&self, record: &log::Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        let message = serde_json::json!({
+            "time": chrono::Local::now().to_rfc3339(),
+            "level": record.level().to_string(),
+            "target": record.target(),
+            "message": record.args().to_string(),
+        });
+        if let Ok(mut output) = self.output.lock() {
+            let _ = writeln!(output, "{message}");
+        }
+    }
+
+    fn flush(&self) {
+        if let Ok(mut output) = self.output.lock() {
+            let _ = output.flush();
+        }
+    }
+}
+
 /// Parse CASR report from file.
 ///
 /// # Arguments
 ///
 /// * `path` - path to CASR report.
 pub fn report_from_file(path: &Path) -> Result<CrashReport> {
-    let Ok(file) = std::fs::File::open(path) else {
+    let Ok(json) = std::fs::read_to_string(path) else {
         bail!("Error with opening Casr report: {}", path.display());
     };
-    let report: Result<CrashReport, _> = serde_json::from_reader(BufReader::new(file));
+    let report = CrashReport::from_json(&json);
     if let Err(e) = report {
         bail!("Error with parsing JSON {}: {}", path.display(), e);
     }
@@ -283,6 +818,537 @@ pub fn get_output(command: &mut Command, timeout: u64, error_on_timeout: bool) -
     }
 }
 
+/// Read output stream keeping only the first sanitizer report and the last
+/// `max_size` bytes of output. Skipped lines are replaced with a note.
+///
+/// # Arguments
+///
+/// * `stream` - output stream
+///
+/// * `max_size` - maximum size (in bytes) of output tail and sanitizer report
+///
+/// # Return value
+///
+/// Captured output
+pub fn read_capped(stream: impl Read, max_size: usize) -> Vec<u8> {
+    let report_start = Regex::new(
+        r"==\d+==\s*ERROR: |WARNING: (?:ThreadSanitizer|MemorySanitizer): |ERROR: HWAddressSanitizer: |\*\*\* GWP-ASan detected",
+    )
+    .unwrap();
+    let max_size = max_size.max(1);
+    let mut reader = BufReader::new(stream);
+    // Numbered lines of the first sanitizer report and output tail.
+    let mut report: Vec<(usize, Vec<u8>)> = Vec::new();
+    let mut report_size = 0;
+    let mut report_done = false;
+    let mut tail: VecDeque<(usize, Vec<u8>)> = VecDeque::new();
+    let mut tail_size = 0;
+    let mut number = 0;
+    loop {
+        let mut line = Vec::new();
+        // Long lines are split.
+        match reader
+            .by_ref()
+            .take(max_size as u64)
+            .read_until(b'\n', &mut line)
+        {
+            Ok(0) | Err(_) => break,
+            Ok(_) => {}
+        }
+        if !report_done {
+            let text = String::from_utf8_lossy(&line);
+            if !report.is_empty() || report_start.is_match(&text) {
+                if report_size + line.len() > max_size {
+                    report_done = true;
+                } else {
+                    report_size += line.len();
+                    report.push((number, line.clone()));
+                    report_done = text.starts_with("SUMMARY: ");
+                }
+            }
+        }
+        tail_size += line.len();
+        tail.push_back((number, line));
+        while tail_size > max_size {
+            let Some((_, line)) = tail.pop_front() else {
+                break;
+            };
+            tail_size -= line.len();
+        }
+        number += 1;
+    }
+
+    let tail_start = tail.front().map(|(number, _)| *number).unwrap_or(number);
+    let mut output = Vec::new();
+    let mut next = 0;
+    for (number, line) in report
+        .into_iter()
+        .filter(|(number, _)| *number < tail_start)
+        .chain(tail)
+    {
+        if number > next {
+            output.extend_from_slice(
+                format!("[CASR: {} lines of output are skipped]\n", number - next).as_bytes(),
+            );
+        }
+        output.extend_from_slice(&line);
+        next = number + 1;
+    }
+    output
+}
+
+/// Target stdout and stderr that are read in background threads while target
+/// is running, so that huge output isn't buffered in memory
+pub struct CappedOutput {
+    /// Stdout reader
+    stdout: std::thread::JoinHandle<Vec<u8>>,
+    /// Stderr reader
+    stderr: std::thread::JoinHandle<Vec<u8>>,
+}
+
+impl CappedOutput {
+    /// Start reading output of spawned target (stdout and stderr should be
+    /// piped)
+    ///
+    /// # Arguments
+    ///
+    /// * `child` - spawned target
+    ///
+    /// * `max_size` - maximum size (in bytes) of each output stream tail
+    pub fn capture(child: &mut Child, max_size: usize) -> Result<Self> {
+        let (Some(stdout), Some(stderr)) = (child.stdout.take(), child.stderr.take()) else {
+            bail!("Target output isn't piped");
+        };
+        Ok(CappedOutput {
+            stdout: std::thread::spawn(move || read_capped(stdout, max_size)),
+            stderr: std::thread::spawn(move || read_capped(stderr, max_size)),
+        })
+    }
+
+    /// Wait for target termination and get its output
+    ///
+    /// # Arguments
+    ///
+    /// * `child` - spawned target
+    pub fn wait(self, child: &mut Child) -> Result<Output> {
+        let status = child.wait()?;
+        let join = |reader: std::thread::JoinHandle<Vec<u8>>| {
+            reader
+                .join()
+                .map_err(|_| anyhow::anyhow!("Couldn't read target output"))
+        };
+        Ok(Output {
+            status,
+            stdout: join(self.stdout)?,
+            stderr: join(self.stderr)?,
+        })
+    }
+}
+
+/// Get output of target command with specified timeout. Output is read while
+/// target is running, and only the first sanitizer report and the last
+/// `max_size` bytes of stdout and stderr are kept.
+///
+/// # Arguments
+///
+/// * `command` - target command with args
+///
+/// * `timeout` - target command timeout (in seconds), 0 value means that
+///   timeout is disabled
+///
+/// * `max_size` - maximum size (in bytes) of each output stream tail
+///
+/// # Return value
+///
+/// Command output
+pub fn get_capped_output(command: &mut Command, timeout: u64, max_size: usize) -> Result<Output> {
+    let mut child = command
+        .stderr(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("Failed to start command: {command:?}"))?;
+    let output = CappedOutput::capture(&mut child, max_size)?;
+    if timeout != 0
+        && child
+            .wait_timeout(Duration::from_secs(timeout))
+            .unwrap()
+            .is_none()
+    {
+        let _ = child.kill();
+        let _ = output.wait(&mut child);
+        bail!("Timeout: {:?}", command);
+    }
+    output.wait(&mut child)
+}
+
+/// Set resource limits for target command
+///
+/// # Arguments
+///
+/// * `command` - target command
+///
+/// * `memory_limit` - address space limit (in MB), 0 value means that limit is not set
+///
+/// * `core_limit` - core file size limit (in MB), 0 value disables core dumps
+#[cfg(unix)]
+pub fn set_resource_limits(command: &mut Command, memory_limit: u64, core_limit: Option<u64>) {
+    use std::os::unix::process::CommandExt;
+
+    if memory_limit == 0 && core_limit.is_none() {
+        return;
+    }
+    unsafe {
+        command.pre_exec(move || {
+            // Soft limit couldn't be greater than hard limit.
+            let set_limit = |resource, value: u64| {
+                let mut limit = libc::rlimit {
+                    rlim_cur: 0,
+                    rlim_max: 0,
+                };
+                if libc::getrlimit(resource, &mut limit) != 0 {
+                    return Err(std::io::Error::last_os_error());
+                }
+                limit.rlim_cur = (value as libc::rlim_t).min(limit.rlim_max);
+                if libc::setrlimit(resource, &limit) != 0 {
+                    return Err(std::io::Error::last_os_error());
+                }
+                Ok(())
+            };
+            if memory_limit != 0 {
+                set_limit(libc::RLIMIT_AS, memory_limit << 20)?;
+            }
+            if let Some(core_limit) = core_limit {
+                set_limit(libc::RLIMIT_CORE, core_limit << 20)?;
+            }
+            Ok(())
+        });
+    }
+}
+
+/// Get resource usage of terminated child processes that have been waited for
+///
+/// # Return value
+///
+/// User CPU time (in milliseconds), system CPU time (in milliseconds), and
+/// maximum resident set size (in KiB)
+#[cfg(unix)]
+pub fn children_resource_usage() -> (u64, u64, u64) {
+    let mut usage: libc::rusage = unsafe { std::mem::zeroed() };
+    unsafe {
+        libc::getrusage(libc::RUSAGE_CHILDREN, &mut usage);
+    }
+    let ms = |time: libc::timeval| time.tv_sec as u64 * 1000 + time.tv_usec as u64 / 1000;
+    // Maximum resident set size is measured in bytes on macOS.
+    #[cfg(target_os = "macos")]
+    let max_rss = usage.ru_maxrss as u64 / 1024;
+    #[cfg(not(target_os = "macos"))]
+    let max_rss = usage.ru_maxrss as u64;
+    (ms(usage.ru_utime), ms(usage.ru_stime), max_rss)
+}
+
+/// Get output of target command with specified timeout. If timeout happens,
+/// attach gdb to the hung target and get its stack trace before killing it.
+///
+/// # Arguments
+///
+/// * `command` - target command with args
+///
+/// * `timeout` - target command timeout (in seconds)
+///
+/// * `max_size` - maximum size (in bytes) of each output stream tail, 0 value
+///   means that output isn't limited (see [`get_capped_output`])
+///
+/// # Return value
+///
+/// Command output and stack trace of the hung target if timeout happens.
+/// An error is returned if timeout happens and stack trace is unavailable.
+pub fn get_output_with_hang_stack(
+    command: &mut Command,
+    timeout: u64,
+    max_size: usize,
+) -> Result<(Output, Option<Vec<String>>)> {
+    if timeout == 0 {
+        let output = if max_size != 0 {
+            get_capped_output(command, timeout, max_size)?
+        } else {
+            get_output(command, timeout, false)?
+        };
+        return Ok((output, None));
+    }
+    let mut child = command
+        .stderr(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("Failed to start command: {command:?}"))?;
+    let capture = if max_size != 0 {
+        Some(CappedOutput::capture(&mut child, max_size)?)
+    } else {
+        None
+    };
+    let mut hang_stack = None;
+    if child
+        .wait_timeout(Duration::from_secs(timeout))
+        .unwrap()
+        .is_none()
+    {
+        warn!("Timeout: {:?}", command);
+        let pid = child.id().to_string();
+        let exectype = ExecType::Remote(&pid);
+        let mut gdb_command = GdbCommand::new(&exectype);
+        gdb_command.bt();
+        let result = gdb_output(&gdb_command, timeout).and_then(|output| {
+            let Some(output) = output else {
+                bail!("Debugger timeout");
+            };
+            Ok(gdb_command.parse(String::from_utf8_lossy(&output))?)
+        });
+        match result {
+            Ok(result) => {
+                let frame = Regex::new(r"^ *#[0-9]+").unwrap();
+                let stacktrace: Vec<String> = result[0]
+                    .split('\n')
+                    .filter(|x| frame.is_match(x))
+                    .map(|x| x.to_string())
+                    .collect();
+                if !stacktrace.is_empty() {
+                    hang_stack = Some(stacktrace);
+                }
+            }
+            Err(error) => warn!("Couldn't get stack trace of hung target: {error}"),
+        }
+        let _ = child.kill();
+        if hang_stack.is_none() {
+            bail!("Timeout: {:?}", command);
+        }
+    }
+    let output = match capture {
+        Some(capture) => capture.wait(&mut child)?,
+        None => child.wait_with_output()?,
+    };
+    Ok((output, hang_stack))
+}
+
+/// Launch gdb and get its raw output (stdout followed by stderr). Gdb is
+/// launched in its own process group, so hung gdb is killed together with
+/// traced target when timeout expires.
+///
+/// # Arguments
+///
+/// * `command` - gdb command
+///
+/// * `timeout` - debugger timeout (in seconds), 0 value means that timeout is
+///   disabled, then target timeout of `command` is used
+///
+/// # Return value
+///
+/// Raw gdb output, None if gdb was killed by timeout
+pub fn gdb_output(command: &GdbCommand, timeout: u64) -> Result<Option<Vec<u8>>> {
+    // Debugger timeout already includes target timeout.
+    let timeout = timeout.max(command.get_timeout());
+    // Output is written to files, so that it isn't lost when gdb is killed and
+    // gdb isn't blocked on full pipe.
+    let mut stdout = tempfile::tempfile()?;
+    let mut stderr = tempfile::tempfile()?;
+    let mut gdb = command.command();
+    gdb.stdin(Stdio::null())
+        .stdout(stdout.try_clone()?)
+        .stderr(stderr.try_clone()?);
+    #[cfg(unix)]
+    std::os::unix::process::CommandExt::process_group(&mut gdb, 0);
+    debug!("Running {:?}", gdb);
+    let mut child = gdb.spawn().with_context(|| "Couldn't launch gdb")?;
+    if timeout != 0 && child.wait_timeout(Duration::from_secs(timeout))?.is_none() {
+        warn!("Debugger timeout ({timeout} s), killing gdb");
+        #[cfg(unix)]
+        unsafe {
+            libc::killpg(child.id() as libc::pid_t, libc::SIGKILL);
+        }
+        let _ = child.kill();
+        let _ = child.wait();
+        return Ok(None);
+    }
+    child.wait()?;
+    let mut output = Vec::new();
+    for file in [&mut stdout, &mut stderr] {
+        file.seek(SeekFrom::Start(0))?;
+        file.read_to_end(&mut output)?;
+    }
+    check_gdb_output(&String::from_utf8_lossy(&output))?;
+    Ok(Some(output))
+}
+
+/// Check that gdb output can be parsed, i.e. it isn't localized.
+///
+/// # Arguments
+///
+/// * `output` - raw gdb output
+fn check_gdb_output(output: &str) -> Result<()> {
+    let markers = [
+        "Program received signal",
+        "Program terminated with signal",
+        "exited normally",
+        "exited with code",
+        "No stack.",
+    ];
+    if markers.iter().any(|marker| output.contains(marker)) {
+        return Ok(());
+    }
+    // English gdb messages are missing, while there are non-ASCII words.
+    if output.chars().any(|c| c.is_alphabetic() && !c.is_ascii()) {
+        bail!(
+            "Unexpected gdb output, it seems to be localized. \
+            Check that gdb is launched with LANG=C and without custom gdbinit."
+        );
+    }
+    Ok(())
+}
+
+/// Run target with disabled ASLR (macOS doesn't have personality syscall,
+/// so posix_spawn attribute is used) and get its output
+///
+/// # Arguments
+///
+/// * `argv` - target program argument vector
+///
+/// * `stdin` - stdin file for target program
+///
+/// * `timeout` - target program timeout (in seconds), 0 value means that
+///   timeout is disabled
+///
+/// # Return value
+///
+/// Target program output
+#[cfg(target_os = "macos")]
+pub fn get_output_without_aslr(
+    argv: &[&str],
+    stdin: &Option<PathBuf>,
+    timeout: u64,
+) -> Result<Output> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+    use std::os::unix::io::FromRawFd;
+    use std::os::unix::process::ExitStatusExt;
+    use std::process::ExitStatus;
+    use std::time::Instant;
+
+    // Private flag from <spawn.h>.
+    const POSIX_SPAWN_DISABLE_ASLR: libc::c_short = 0x0100;
+
+    let args = argv
+        .iter()
+        .map(|arg| CString::new(*arg))
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    let mut args_ptr: Vec<*mut libc::c_char> =
+        args.iter().map(|arg| arg.as_ptr() as *mut _).collect();
+    args_ptr.push(std::ptr::null_mut());
+    let stdin = stdin
+        .as_ref()
+        .map(|path| CString::new(path.as_os_str().as_bytes()))
+        .transpose()?;
+
+    let mut stdout_fds = [0; 2];
+    let mut stderr_fds = [0; 2];
+    let mut pid: libc::pid_t = 0;
+    unsafe {
+        if libc::pipe(stdout_fds.as_mut_ptr()) != 0 || libc::pipe(stderr_fds.as_mut_ptr()) != 0 {
+            bail!("Couldn't create pipes: {}", std::io::Error::last_os_error());
+        }
+        let mut actions: libc::posix_spawn_file_actions_t = std::ptr::null_mut();
+        let mut attrs: libc::posix_spawnattr_t = std::ptr::null_mut();
+        libc::posix_spawn_file_actions_init(&mut actions);
+        libc::posix_spawnattr_init(&mut attrs);
+        libc::posix_spawnattr_setflags(&mut attrs, POSIX_SPAWN_DISABLE_ASLR);
+        if let Some(stdin) = &stdin {
+            libc::posix_spawn_file_actions_addopen(
+                &mut actions,
+                0,
+                stdin.as_ptr(),
+                libc::O_RDONLY,
+                0,
+            );
+        }
+        libc::posix_spawn_file_actions_adddup2(&mut actions, stdout_fds[1], 1);
+        libc::posix_spawn_file_actions_adddup2(&mut actions, stderr_fds[1], 2);
+        for fd in stdout_fds.iter().chain(stderr_fds.iter()) {
+            libc::posix_spawn_file_actions_addclose(&mut actions, *fd);
+        }
+        let ret = libc::posix_spawnp(
+            &mut pid,
+            args[0].as_ptr(),
+            &actions,
+            &attrs,
+            args_ptr.as_ptr(),
+            environ(),
+        );
+        libc::posix_spawn_file_actions_destroy(&mut actions);
+        libc::posix_spawnattr_destroy(&mut attrs);
+        libc::close(stdout_fds[1]);
+        libc::close(stderr_fds[1]);
+        if ret != 0 {
+            libc::close(stdout_fds[0]);
+            libc::close(stderr_fds[0]);
+            bail!(
+                "Couldn't launch {:?}: {}",
+                argv,
+                std::io::Error::from_raw_os_error(ret)
+            );
+        }
+    }
+
+    // Read output in separate threads to avoid pipe overflow.
+    let read_pipe = |fd| {
+        std::thread::spawn(move || {
+            let mut buffer = Vec::new();
+            let mut file = unsafe { fs::File::from_raw_fd(fd) };
+            let _ = file.read_to_end(&mut buffer);
+            buffer
+        })
+    };
+    let stdout = read_pipe(stdout_fds[0]);
+    let stderr = read_pipe(stderr_fds[0]);
+
+    let start = Instant::now();
+    let mut status = 0;
+    loop {
+        let ret = unsafe { libc::waitpid(pid, &mut status, libc::WNOHANG) };
+        if ret == pid {
+            break;
+        }
+        if ret < 0 {
+            bail!(
+                "Couldn't wait for {:?}: {}",
+                argv,
+                std::io::Error::last_os_error()
+            );
+        }
+        if timeout != 0 && start.elapsed() >= Duration::from_secs(timeout) {
+            unsafe {
+                libc::kill(pid, libc::SIGKILL);
+                libc::waitpid(pid, &mut status, 0);
+            }
+            bail!("Timeout: {:?}", argv);
+        }
+        std::thread::sleep(Duration::from_millis(10));
+    }
+
+    Ok(Output {
+        status: ExitStatus::from_raw(status),
+        stdout: stdout.join().unwrap_or_default(),
+        stderr: stderr.join().unwrap_or_default(),
+    })
+}
+
+#[cfg(target_os = "macos")]
+extern "C" {
+    fn _NSGetEnviron() -> *mut *const *const libc::c_char;
+}
+
+/// Get environment of the current process.
+#[cfg(target_os = "macos")]
+fn environ() -> *const *mut libc::c_char {
+    unsafe { *_NSGetEnviron() as *const *mut libc::c_char }
+}
+
 /// Get Atheris asan_with_fuzzer library path.
 pub fn get_atheris_lib() -> Result<String> {
     let mut cmd = Command::new("python3");
@@ -361,6 +1427,7 @@ pub fn symbols_list(path: &Path) -> Result<HashSet<&str>> {
                 "__msan",
                 "__llvm_profile",
                 "runtime.go",
+                "LLVMFuzzerTestOneInput",
             ];
             for sym in elf.syms.iter() {
                 if let Some(name) = elf.strtab.get_at(sym.st_name) {
@@ -411,3 +1478,89 @@ pub fn get_path(tool: &str) -> Result<PathBuf> {
         );
     }
 }
+
+/// Get target machine information from ELF header
+///
+/// # Arguments
+///
+/// * `target_path` - path to target binary
+///
+/// # Return value
+///
+/// Architecture, endianness and byte width of target binary
+pub fn machine_info(target_path: &Path) -> Result<MachineInfo> {
+    let mut header = vec![0u8; 64];
+    // The ELF header is 52 or 64 bytes long for 32-bit and 64-bit binaries respectively.
+    let mut file = fs::File::open(target_path)
+        .with_context(|| format!("Couldn't open target binary: {}", target_path.display()))?;
+    file.read_exact(&mut header).with_context(|| {
+        format!(
+            "Couldn't read target binary header: {}",
+            target_path.display()
+        )
+    })?;
+    let elf_h = Elf::parse_header(&header).with_context(|| {
+        format!(
+            "Couldn't header for target binary: {}",
+            target_path.display()
+        )
+    })?;
+
+    let mut machine = MachineInfo {
+        arch: header::EM_X86_64,
+        endianness: Endian::Little,
+        byte_width: 8,
+    };
+
+    // Type should be executable or shared object.
+    if elf_h.e_type != header::ET_EXEC && elf_h.e_type != header::ET_DYN {
+        bail!("Target binary type should be executable or shared object");
+    }
+
+    match elf_h.e_ident[4] {
+        1 => machine.byte_width = 4,
+        2 => machine.byte_width = 8,
+        _ => {
+            bail!("Couldn't determine byte_width: {}", elf_h.e_ident[4]);
+        }
+    }
+
+    if let Ok(endianness) = elf_h.endianness() {
+        machine.endianness = endianness;
+    } else {
+        bail!("Couldn't get endianness from target binary");
+    }
+
+    match elf_h.e_machine {
+        header::EM_386
+        | header::EM_ARM
+        | header::EM_X86_64
+        | header::EM_AARCH64
+        | header::EM_RISCV => machine.arch = elf_h.e_machine,
+        _ => {
+            bail!("Unsupported architecture: {}", elf_h.e_machine);
+        }
+    }
+    Ok(machine)
+}
+
+/// Convert memory mappings to gdb proc mappings format
+///
+/// # Arguments
+///
+/// * `mappings` - memory mappings
+pub fn proc_maps(mappings: &MappedFiles) -> Vec<String> {
+    let mut proc_maps =
+        vec!["          Start Addr           End Addr       Size     Offset objfile".to_string()];
+    for file in mappings.iter() {
+        // Mappings from corrupted core files may be invalid.
+        let Some(size) = file.end.checked_sub(file.start) else {
+            continue;
+        };
+        proc_maps.push(format!(
+            "{:#20x} {:#18x} {:#10x} {:#10x} {}",
+            file.start, file.end, size, file.offset, file.name
+        ));
+    }
+    proc_maps
+}
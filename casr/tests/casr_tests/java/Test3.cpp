@@ -0,0 +1,6 @@
+#include <jni.h>
+
+extern "C" JNIEXPORT void JNICALL Java_Test3_crash(JNIEnv *, jobject) {
+    volatile int *p = nullptr;
+    *p = 1;
+}
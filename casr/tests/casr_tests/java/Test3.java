@@ -0,0 +1,12 @@
+public class Test3 {
+
+    static {
+        System.loadLibrary("crash");
+    }
+
+    public static void main(String[] args) {
+        new Test3().crash();
+    }
+
+    private native void crash();
+}
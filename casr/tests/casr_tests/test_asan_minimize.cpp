@@ -0,0 +1,24 @@
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+int
+main(int argc, char** argv)
+{
+    char buf[256] = {0};
+    FILE* f = fopen(argv[1], "rb");
+    if (f == NULL)
+    {
+        return 1;
+    }
+    fread(buf, 1, sizeof(buf) - 1, f);
+    fclose(f);
+    // Only "CRASH" substring matters, the rest of input may be removed.
+    if (strstr(buf, "CRASH") != NULL)
+    {
+        char* p = (char*)malloc(8);
+        p[8] = 1;
+        free(p);
+    }
+    return 0;
+}
@@ -0,0 +1,13 @@
+#include <cstdio>
+#include <cstdlib>
+
+int main()
+{
+    // Flood stderr before the crash.
+    for (int i = 0; i < 100000; i++)
+        fprintf(stderr, "noise line %d: aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa\n", i);
+    char *buf = (char *)malloc(16);
+    free(buf);
+    free(buf);
+    return 0;
+}
@@ -0,0 +1,10 @@
+#include <stdlib.h>
+
+int main(int argc, char **argv) {
+    int shift = 31 + argc;
+    int value = 1 << shift;
+    char *buf = malloc(16);
+    buf[16 + argc] = value;
+    free(buf);
+    return 0;
+}
@@ -0,0 +1,21 @@
+#include <stdio.h>
+
+/* Crash on every third run, run counter is kept in file argv[1]. */
+int main(int argc, char **argv) {
+    int runs = 0;
+    FILE *f = fopen(argv[1], "r");
+    if (f) {
+        if (fscanf(f, "%d", &runs) != 1)
+            runs = 0;
+        fclose(f);
+    }
+    runs++;
+    f = fopen(argv[1], "w");
+    if (!f)
+        return 1;
+    fprintf(f, "%d", runs);
+    fclose(f);
+    if (runs % 3 == 0)
+        *(volatile int *)0 = runs;
+    return 0;
+}
@@ -0,0 +1,9 @@
+#include <unistd.h>
+
+int
+main(int argc, char **argv)
+{
+    for (;;)
+        pause();
+    return 0;
+}
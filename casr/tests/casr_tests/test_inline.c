@@ -0,0 +1,16 @@
+#include <stddef.h>
+
+int *volatile ptr = NULL;
+
+static inline __attribute__((always_inline)) void
+store(int *p, int value)
+{
+    *p = value;
+}
+
+int
+main(int argc, char **argv)
+{
+    store(ptr, argc);
+    return 0;
+}
@@ -0,0 +1,11 @@
+#include <stddef.h>
+#include <stdint.h>
+
+int LLVMFuzzerTestOneInput(const uint8_t *data, size_t size)
+{
+    if (size > 0 && data[0] == 'W') {
+        // Write far from null, libFuzzer reports only deadly signal.
+        *(volatile int *)0x1000000 = size;
+    }
+    return 0;
+}
@@ -0,0 +1,19 @@
+#include <cstdint>
+#include <cstdlib>
+#include <cstring>
+#include <unistd.h>
+
+extern "C" int LLVMFuzzerTestOneInput(const uint8_t *data, size_t size)
+{
+    if (size > 0 && data[0] == 'O') {
+        // Single allocation exceeds -rss_limit_mb.
+        char *buf = (char *)malloc(1024 * 1024 * 1024);
+        memset(buf, 1, 1024 * 1024 * 1024);
+        free(buf);
+    }
+    if (size > 0 && data[0] == 'T') {
+        while (true)
+            sleep(1);
+    }
+    return 0;
+}
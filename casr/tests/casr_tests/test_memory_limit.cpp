@@ -0,0 +1,9 @@
+#include <cstring>
+#include <vector>
+
+int main()
+{
+    std::vector<char> buf(1024 * 1024 * 1024);
+    memset(buf.data(), 1, buf.size());
+    return buf[0];
+}
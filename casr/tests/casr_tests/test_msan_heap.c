@@ -0,0 +1,11 @@
+#include <stdio.h>
+#include <stdlib.h>
+
+int main() {
+    int *a = (int *)malloc(10 * sizeof(int));
+    a[5] = 0;
+    if (a[2])
+        printf("%d\n", a[5]);
+    free(a);
+    return 0;
+}
@@ -0,0 +1,9 @@
+#include <stdio.h>
+
+int main(int argc, char **argv) {
+    printf("target stdout line\n");
+    fflush(stdout);
+    fprintf(stderr, "target stderr line\n");
+    *(volatile int *)0 = argc;
+    return 0;
+}
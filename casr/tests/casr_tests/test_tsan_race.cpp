@@ -0,0 +1,18 @@
+#include <pthread.h>
+#include <stdio.h>
+
+int Global;
+
+void *Thread1(void *x) {
+    Global++;
+    return NULL;
+}
+
+int main() {
+    pthread_t t;
+    pthread_create(&t, NULL, Thread1, NULL);
+    Global--;
+    pthread_join(t, NULL);
+    printf("%d\n", Global);
+    return 0;
+}
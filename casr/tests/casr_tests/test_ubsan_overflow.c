@@ -0,0 +1,8 @@
+#include <stdio.h>
+
+int main(int argc, char **argv) {
+    int a = 2147483647;
+    int b = a + argc;
+    printf("%d\n", b);
+    return 0;
+}
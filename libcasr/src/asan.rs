@@ -8,6 +8,9 @@ use crate::severity::Severity;
 use crate::stacktrace::ParseStacktrace;
 use crate::stacktrace::*;
 
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
 /// Structure provides an interface for processing the stack trace.
 pub struct AsanStacktrace;
 
@@ -132,6 +135,13 @@ impl ParseStacktrace for AsanStacktrace {
         // file[:line[:column]]
         // TODO: path may contain :
         if !location.is_empty() {
+            // Windows path starts with drive letter, e.g. C:\src\test.cpp:5
+            let rdrive = Regex::new(r"^[A-Za-z]:[\\/]").unwrap();
+            let (drive, location) = if rdrive.is_match(location) {
+                location.split_at(2)
+            } else {
+                ("", location)
+            };
             let source: Vec<&str> = location.rsplitn(3, ':').collect();
             if source.iter().any(|x| x.is_empty()) {
                 return Err(Error::Casr(format!(
@@ -139,7 +149,7 @@ impl ParseStacktrace for AsanStacktrace {
                 )));
             }
             // Get source file.
-            stentry.debug.file = source.last().unwrap().trim().to_string();
+            stentry.debug.file = drive.to_string() + source.last().unwrap().trim();
             // Get source line (optional).
             if source.len() > 1 {
                 let num = source[source.len() - 2];
@@ -164,9 +174,110 @@ impl ParseStacktrace for AsanStacktrace {
     }
 }
 
+/// Extract AddressSanitizer (LeakSanitizer, libFuzzer) error reports from
+/// program output. Targets built with `-fsanitize-recover=address` and run with
+/// `halt_on_error=0` print several reports.
+///
+/// # Arguments
+///
+/// * `stream` - program output lines
+///
+/// # Return value
+///
+/// Reports from ERROR line to the next report (trailing empty lines and
+/// separators are removed), the last report lasts to the last non-empty line
+pub fn extract_asan_reports(stream: &[String]) -> Vec<Vec<String>> {
+    split_reports(
+        stream,
+        r"==\d+==\s*ERROR: (LeakSanitizer|AddressSanitizer|libFuzzer):",
+    )
+}
+
+/// Extract recoverable AddressSanitizer warnings from program output. Targets
+/// built with `-fsanitize-recover=address` and run with `halt_on_error=0` may
+/// print WARNING reports instead of ERROR ones and continue execution.
+///
+/// # Arguments
+///
+/// * `stream` - program output lines
+///
+/// # Return value
+///
+/// Reports from WARNING line to the next report (trailing empty lines and
+/// separators are removed), the last report lasts to the last non-empty line
+pub fn extract_asan_warnings(stream: &[String]) -> Vec<Vec<String>> {
+    split_reports(stream, r"==\d+==\s*WARNING: AddressSanitizer:")
+}
+
+/// Split program output into sanitizer reports
+///
+/// # Arguments
+///
+/// * `stream` - program output lines
+///
+/// * `header` - regular expression for report first line
+fn split_reports(stream: &[String], header: &str) -> Vec<Vec<String>> {
+    let rasan_start = Regex::new(header).unwrap();
+    let starts: Vec<usize> = stream
+        .iter()
+        .enumerate()
+        .filter(|(_, line)| rasan_start.is_match(line))
+        .map(|(i, _)| i)
+        .collect();
+    starts
+        .iter()
+        .enumerate()
+        .map(|(i, &start)| {
+            let end = starts.get(i + 1).copied().unwrap_or(stream.len());
+            let end = stream[start..end]
+                .iter()
+                .rposition(|line| {
+                    let line = line.trim();
+                    !line.is_empty() && !line.chars().all(|c| c == '=')
+                })
+                .map_or(start + 1, |pos| start + pos + 1);
+            stream[start..end]
+                .iter()
+                .map(|line| line.trim_end().to_string())
+                .collect()
+        })
+        .collect()
+}
+
 /// Information about sanitizer crash state.
 pub struct AsanContext(pub Vec<String>);
 
+impl AsanContext {
+    /// Get stack trace where memory was allocated (e.g., for heap-use-after-free
+    /// or heap-buffer-overflow)
+    pub fn allocation_stacktrace(&self) -> Vec<String> {
+        self.stacktrace(r"^(previously )?allocated by thread T\d+.* here:$")
+    }
+
+    /// Get stack trace where memory was deallocated (e.g., for heap-use-after-free
+    /// or double-free)
+    pub fn deallocation_stacktrace(&self) -> Vec<String> {
+        self.stacktrace(r"^(freed|previously freed) by thread T\d+.* here:$")
+    }
+
+    /// Get stack trace that follows header line
+    ///
+    /// # Arguments
+    ///
+    /// * `header` - regular expression for header line
+    fn stacktrace(&self, header: &str) -> Vec<String> {
+        let header = Regex::new(header).unwrap();
+        let Some(start) = self.0.iter().position(|line| header.is_match(line.trim())) else {
+            return Vec::new();
+        };
+        self.0[start + 1..]
+            .iter()
+            .take_while(|line| line.trim_start().starts_with('#'))
+            .cloned()
+            .collect()
+    }
+}
+
 impl Severity for AsanContext {
     fn severity(&self) -> Result<ExecutionClass> {
         let asan_report = &self.0;
@@ -188,7 +299,12 @@ impl Severity for AsanContext {
             };
             // Match Sanitizer.
             match caps.get(1).unwrap().as_str() {
-                "libFuzzer" => ExecutionClass::san_find(caps.get(2).unwrap().as_str(), None, false),
+                "libFuzzer" => {
+                    if let Some(oom) = OutOfMemory::extract(asan_report) {
+                        return oom.execution_class();
+                    }
+                    ExecutionClass::san_find(caps.get(2).unwrap().as_str(), None, false)
+                }
                 _ => {
                     // AddressSanitizer
                     let san_type = caps.get(2).unwrap().as_str();
@@ -229,9 +345,322 @@ impl Severity for AsanContext {
     }
 }
 
+/// LeakSanitizer leak record.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LeakRecord {
+    /// Leak kind: Direct or Indirect.
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename(serialize = "Kind", deserialize = "Kind"))
+    )]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub kind: String,
+    /// Number of leaked bytes.
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename(serialize = "Bytes", deserialize = "Bytes"))
+    )]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub bytes: u64,
+    /// Number of leaked objects.
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename(serialize = "Objects", deserialize = "Objects"))
+    )]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub objects: u64,
+    /// Allocation stack trace.
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename(serialize = "Stacktrace", deserialize = "Stacktrace"))
+    )]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub stacktrace: Vec<String>,
+}
+
+impl LeakRecord {
+    /// Extract all leak records from LeakSanitizer report
+    ///
+    /// # Arguments
+    ///
+    /// * `asan_report` - LeakSanitizer report lines
+    ///
+    /// # Return value
+    ///
+    /// Leak records in the order of appearance in report
+    pub fn extract_leaks(asan_report: &[String]) -> Vec<LeakRecord> {
+        let rleak = Regex::new(
+            r"(Direct|Indirect) leak of (\d+) byte\(s\) in (\d+) object\(s\) allocated from:",
+        )
+        .unwrap();
+        let mut leaks = Vec::new();
+        let mut lines = asan_report.iter().peekable();
+        while let Some(line) = lines.next() {
+            let Some(caps) = rleak.captures(line) else {
+                continue;
+            };
+            let mut leak = LeakRecord {
+                kind: caps.get(1).unwrap().as_str().to_string(),
+                bytes: caps.get(2).unwrap().as_str().parse::<u64>().unwrap_or(0),
+                objects: caps.get(3).unwrap().as_str().parse::<u64>().unwrap_or(0),
+                stacktrace: Vec::new(),
+            };
+            // Allocation stack trace is split by empty line.
+            while let Some(frame) = lines.next_if(|l| !l.trim().is_empty()) {
+                leak.stacktrace.push(frame.trim_end().to_string());
+            }
+            leaks.push(leak);
+        }
+        leaks
+    }
+
+    /// Get total number of leaked bytes and objects
+    ///
+    /// # Arguments
+    ///
+    /// * `leaks` - leak records
+    pub fn total(leaks: &[LeakRecord]) -> (u64, u64) {
+        leaks.iter().fold((0, 0), |(bytes, objects), leak| {
+            (bytes + leak.bytes, objects + leak.objects)
+        })
+    }
+
+    /// Get the largest leak (the first one if several leaks have the same size)
+    ///
+    /// # Arguments
+    ///
+    /// * `leaks` - leak records
+    pub fn largest(leaks: &[LeakRecord]) -> Option<&LeakRecord> {
+        leaks.iter().rev().max_by_key(|leak| leak.bytes)
+    }
+}
+
+/// Live heap allocation record from libFuzzer memory profile.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct HeapAllocation {
+    /// Number of allocated bytes.
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename(serialize = "Bytes", deserialize = "Bytes"))
+    )]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub bytes: u64,
+    /// Number of allocations.
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename(serialize = "Count", deserialize = "Count"))
+    )]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub count: u64,
+    /// Allocation stack trace.
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename(serialize = "Stacktrace", deserialize = "Stacktrace"))
+    )]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub stacktrace: Vec<String>,
+}
+
+/// libFuzzer out-of-memory details.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct OutOfMemory {
+    /// Size of single allocation that exceeds malloc limit.
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename(serialize = "MallocSize", deserialize = "MallocSize"))
+    )]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub malloc_size: Option<u64>,
+    /// Used memory (in MB) when rss limit is exceeded.
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename(serialize = "UsedMb", deserialize = "UsedMb"))
+    )]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub used_mb: Option<u64>,
+    /// Exceeded rss limit (in MB).
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename(serialize = "RssLimitMb", deserialize = "RssLimitMb"))
+    )]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub rss_limit_mb: Option<u64>,
+    /// Total size of live heap allocations.
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename(serialize = "LiveHeapBytes", deserialize = "LiveHeapBytes"))
+    )]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub live_heap_bytes: u64,
+    /// The largest live heap allocations from memory profile.
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename(serialize = "LiveHeap", deserialize = "LiveHeap"))
+    )]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub live_heap: Vec<HeapAllocation>,
+}
+
+impl OutOfMemory {
+    /// Extract out-of-memory details from libFuzzer report
+    ///
+    /// # Arguments
+    ///
+    /// * `asan_report` - libFuzzer report lines
+    ///
+    /// # Return value
+    ///
+    /// Out-of-memory details or None if it is not out-of-memory report
+    pub fn extract(asan_report: &[String]) -> Option<OutOfMemory> {
+        let header = asan_report.first()?;
+        if !header.contains("libFuzzer: out-of-memory") {
+            return None;
+        }
+        let mut oom = OutOfMemory::default();
+        let rmalloc = Regex::new(r"out-of-memory \(malloc\((\d+)\)\)").unwrap();
+        let rrss = Regex::new(r"out-of-memory \(used: (\d+)Mb; exceeds: (\d+)Mb\)").unwrap();
+        if let Some(caps) = rmalloc.captures(header) {
+            oom.malloc_size = caps.get(1).unwrap().as_str().parse::<u64>().ok();
+        } else if let Some(caps) = rrss.captures(header) {
+            oom.used_mb = caps.get(1).unwrap().as_str().parse::<u64>().ok();
+            oom.rss_limit_mb = caps.get(2).unwrap().as_str().parse::<u64>().ok();
+        }
+
+        // Memory profile is printed for gradual out-of-memory.
+        let rlive = Regex::new(r"Live Heap Allocations: (\d+) bytes").unwrap();
+        let rallocation =
+            Regex::new(r"^(\d+) byte\(s\) \(\d+%\) in (\d+) allocation\(s\)").unwrap();
+        let mut lines = asan_report.iter().peekable();
+        while let Some(line) = lines.next() {
+            if let Some(caps) = rlive.captures(line) {
+                oom.live_heap_bytes = caps.get(1).unwrap().as_str().parse::<u64>().unwrap_or(0);
+                continue;
+            }
+            let Some(caps) = rallocation.captures(line.trim()) else {
+                continue;
+            };
+            let mut allocation = HeapAllocation {
+                bytes: caps.get(1).unwrap().as_str().parse::<u64>().unwrap_or(0),
+                count: caps.get(2).unwrap().as_str().parse::<u64>().unwrap_or(0),
+                stacktrace: Vec::new(),
+            };
+            while let Some(frame) = lines.next_if(|l| l.trim_start().starts_with('#')) {
+                allocation.stacktrace.push(frame.trim_end().to_string());
+            }
+            oom.live_heap.push(allocation);
+        }
+        Some(oom)
+    }
+
+    /// Check whether out-of-memory is caused by single allocation that
+    /// exceeds malloc limit rather than by gradual memory growth
+    pub fn is_single_allocation(&self) -> bool {
+        self.malloc_size.is_some()
+    }
+
+    /// Get execution class: `out-of-memory(malloc)` for single allocation or
+    /// `out-of-memory(rss)` for gradual memory growth
+    pub fn execution_class(&self) -> Result<ExecutionClass> {
+        if self.is_single_allocation() {
+            ExecutionClass::find("out-of-memory(malloc)")
+        } else {
+            ExecutionClass::find("out-of-memory(rss)")
+        }
+    }
+}
+
+/// Global variable from AddressSanitizer odr-violation or
+/// initialization-order-fiasco report.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AsanGlobal {
+    /// Global variable name.
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename(serialize = "Name", deserialize = "Name"))
+    )]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub name: String,
+    /// Global variable size.
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename(serialize = "Size", deserialize = "Size"))
+    )]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub size: u64,
+    /// Global variable definition (source location and module).
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename(serialize = "Location", deserialize = "Location"))
+    )]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub location: String,
+}
+
+impl AsanGlobal {
+    /// Extract global variables from AddressSanitizer report: both
+    /// definitions for odr-violation or accessed global variable for
+    /// initialization-order-fiasco
+    ///
+    /// # Arguments
+    ///
+    /// * `asan_report` - AddressSanitizer report lines
+    ///
+    /// # Return value
+    ///
+    /// Global variables or empty vector for other errors
+    pub fn extract_globals(asan_report: &[String]) -> Vec<AsanGlobal> {
+        let Some(header) = asan_report.first() else {
+            return Vec::new();
+        };
+        if header.contains("AddressSanitizer: odr-violation") {
+            // [1] size=4 'global' a.cc:1:5 in /tmp/module.so
+            let rdefinition = Regex::new(r"^\[\d+\] size=(\d+) '(.+?)' (.+)$").unwrap();
+            asan_report
+                .iter()
+                .filter_map(|line| rdefinition.captures(line.trim()))
+                .map(|caps| AsanGlobal {
+                    name: caps.get(2).unwrap().as_str().to_string(),
+                    size: caps.get(1).unwrap().as_str().parse::<u64>().unwrap_or(0),
+                    location: caps.get(3).unwrap().as_str().trim().to_string(),
+                })
+                .collect()
+        } else if header.contains("AddressSanitizer: initialization-order-fiasco") {
+            let rglobal = Regex::new(
+                r"global variable '(.+?)' defined in '(.+?)' \(0x[0-9a-f]+\) of size (\d+)",
+            )
+            .unwrap();
+            asan_report
+                .iter()
+                .find_map(|line| rglobal.captures(line))
+                .map(|caps| AsanGlobal {
+                    name: caps.get(1).unwrap().as_str().to_string(),
+                    size: caps.get(3).unwrap().as_str().parse::<u64>().unwrap_or(0),
+                    location: caps.get(2).unwrap().as_str().to_string(),
+                })
+                .into_iter()
+                .collect()
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+impl CrashLineExt for LeakRecord {
+    fn crash_line(&self) -> Result<CrashLine> {
+        let mut stacktrace = AsanStacktrace::parse_stacktrace(&self.stacktrace)?;
+        stacktrace.filter();
+        stacktrace.crash_line()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::stacktrace::tests::safe_init_ignore_stack_frames;
 
     #[test]
     fn test_asan_stacktrace() {
@@ -426,4 +855,315 @@ mod tests {
                 .to_string()
         );
     }
+
+    #[test]
+    fn test_asan_windows() {
+        let report: Vec<String> = [
+            "==7236==ERROR: AddressSanitizer: access-violation on unknown address 0x000000000010 (pc 0x7ff7a1b2106d bp 0x00a17c6ff6f0 sp 0x00a17c6ff6a0 T0)",
+            "==7236==The signal is caused by a WRITE memory access.",
+            "==7236==Hint: address points to the zero page.",
+            "    #0 0x7ff7a1b2106d in main C:\\src\\test.cpp:5",
+            "    #1 0x7ff7a1b21a0b in __scrt_common_main_seh d:\\a01\\_work\\12\\s\\src\\vctools\\crt\\vcstartup\\src\\startup\\exe_common.inl:288",
+            "    #2 0x7ffc7e3e7033 in BaseThreadInitThunk+0x13 (C:\\Windows\\System32\\KERNEL32.DLL+0x180017033)",
+            "    #3 0x7ffc7f6a2650 in RtlUserThreadStart+0x20 (C:\\Windows\\SYSTEM32\\ntdll.dll+0x180052650)",
+            "",
+            "AddressSanitizer can not provide additional info.",
+            "SUMMARY: AddressSanitizer: access-violation C:\\src\\test.cpp:5 in main",
+        ]
+        .iter()
+        .map(|l| l.to_string())
+        .collect();
+
+        let raw_stacktrace = AsanStacktrace::extract_stacktrace(&report.join("\n"));
+        let Ok(raw_stacktrace) = raw_stacktrace else {
+            panic!("{}", raw_stacktrace.err().unwrap());
+        };
+        let stacktrace = AsanStacktrace::parse_stacktrace(&raw_stacktrace);
+        let Ok(stacktrace) = stacktrace else {
+            panic!("{}", stacktrace.err().unwrap());
+        };
+        assert_eq!(stacktrace[0].function, "main");
+        assert_eq!(stacktrace[0].debug.file, "C:\\src\\test.cpp");
+        assert_eq!(stacktrace[0].debug.line, 5);
+        assert_eq!(stacktrace[2].module, "C:\\Windows\\System32\\KERNEL32.DLL");
+        assert_eq!(stacktrace[2].offset, 0x180017033);
+
+        safe_init_ignore_stack_frames();
+        let mut filtered = stacktrace.clone();
+        filtered.filter();
+        assert_eq!(filtered.len(), 1);
+
+        let class = AsanContext(report).severity();
+        let Ok(class) = class else {
+            panic!("{}", class.err().unwrap());
+        };
+        assert_eq!(class.short_description, "DestAvNearNull");
+    }
+
+    #[test]
+    fn test_asan_leaks() {
+        let report: Vec<String> = [
+            "==1234==ERROR: LeakSanitizer: detected memory leaks",
+            "",
+            "Direct leak of 7 byte(s) in 1 object(s) allocated from:",
+            "    #0 0x4af01b in __interceptor_malloc /llvm/compiler-rt/lib/asan/asan_malloc_linux.cpp:145:3",
+            "    #1 0x4da26a in main /tmp/test_leak.c:6:25",
+            "",
+            "Indirect leak of 40 byte(s) in 2 object(s) allocated from:",
+            "    #0 0x4af01b in __interceptor_malloc /llvm/compiler-rt/lib/asan/asan_malloc_linux.cpp:145:3",
+            "    #1 0x4da2c1 in foo /tmp/test_leak.c:12:13",
+            "    #2 0x4da26a in main /tmp/test_leak.c:7:5",
+            "",
+            "SUMMARY: AddressSanitizer: 47 byte(s) leaked in 3 allocation(s).",
+        ]
+        .iter()
+        .map(|l| l.to_string())
+        .collect();
+
+        let leaks = LeakRecord::extract_leaks(&report);
+        assert_eq!(leaks.len(), 2);
+        assert_eq!(leaks[0].kind, "Direct");
+        assert_eq!(leaks[0].bytes, 7);
+        assert_eq!(leaks[0].objects, 1);
+        assert_eq!(leaks[0].stacktrace.len(), 2);
+        assert_eq!(leaks[1].kind, "Indirect");
+        assert_eq!(leaks[1].bytes, 40);
+        assert_eq!(leaks[1].objects, 2);
+        assert_eq!(leaks[1].stacktrace.len(), 3);
+        assert_eq!(
+            leaks[1].stacktrace[1],
+            "    #1 0x4da2c1 in foo /tmp/test_leak.c:12:13"
+        );
+        assert_eq!(LeakRecord::total(&leaks), (47, 3));
+        assert_eq!(LeakRecord::largest(&leaks), Some(&leaks[1]));
+        assert_eq!(LeakRecord::largest(&leaks[..1]), Some(&leaks[0]));
+        assert!(LeakRecord::largest(&[]).is_none());
+
+        safe_init_ignore_stack_frames();
+        let Ok(crashline) = leaks[1].crash_line() else {
+            panic!("Couldn't get crash line for leak");
+        };
+        assert_eq!(crashline.to_string(), "/tmp/test_leak.c:12:13");
+    }
+
+    #[test]
+    fn test_libfuzzer_oom() {
+        let report: Vec<String> = [
+            "==2345== ERROR: libFuzzer: out-of-memory (malloc(4294967296))",
+            "   To change the out-of-memory limit use -rss_limit_mb=<N>",
+            "",
+            "    #0 0x52c6d1 in __sanitizer_print_stack_trace /llvm/compiler-rt/lib/asan/asan_stack.cpp:87:3",
+            "    #1 0x477948 in fuzzer::PrintStackTrace() /llvm/compiler-rt/lib/fuzzer/FuzzerUtil.cpp:210:5",
+            "    #2 0x45c2d6 in fuzzer::Fuzzer::HandleMalloc(unsigned long) /llvm/compiler-rt/lib/fuzzer/FuzzerLoop.cpp:131:3",
+            "    #3 0x4a0fbd in malloc /llvm/compiler-rt/lib/asan/asan_malloc_linux.cpp:69:3",
+            "    #4 0x55a2e1 in LLVMFuzzerTestOneInput /tmp/fuzz_oom.c:9:15",
+            "",
+            "SUMMARY: libFuzzer: out-of-memory (malloc(4294967296))",
+        ]
+        .iter()
+        .map(|l| l.to_string())
+        .collect();
+        let Some(oom) = OutOfMemory::extract(&report) else {
+            panic!("Couldn't extract out-of-memory details");
+        };
+        assert!(oom.is_single_allocation());
+        assert_eq!(oom.malloc_size, Some(4294967296));
+        assert!(oom.live_heap.is_empty());
+        let Ok(class) = AsanContext(report).severity() else {
+            panic!("Couldn't estimate severity");
+        };
+        assert_eq!(class.short_description, "out-of-memory(malloc)");
+
+        let report: Vec<String> = [
+            "==2346== ERROR: libFuzzer: out-of-memory (used: 2050Mb; exceeds: 2048Mb)",
+            "   To change the out-of-memory limit use -rss_limit_mb=<N>",
+            "",
+            "Live Heap Allocations: 2147483712 bytes in 5 chunks; quarantined: 0 bytes in 0 chunks; 12 other chunks; total chunks: 17; showing top 95% (at most 8 unique contexts)",
+            "2147483648 byte(s) (99%) in 4 allocation(s)",
+            "    #0 0x4a0fbd in malloc /llvm/compiler-rt/lib/asan/asan_malloc_linux.cpp:69:3",
+            "    #1 0x55a2e1 in grow /tmp/fuzz_oom.c:4:12",
+            "    #2 0x55a3f2 in LLVMFuzzerTestOneInput /tmp/fuzz_oom.c:12:9",
+            "",
+            "64 byte(s) (0%) in 1 allocation(s)",
+            "    #0 0x4a0fbd in malloc /llvm/compiler-rt/lib/asan/asan_malloc_linux.cpp:69:3",
+            "    #1 0x55a3e0 in LLVMFuzzerTestOneInput /tmp/fuzz_oom.c:10:9",
+            "",
+            "MS: 0 ; base unit: 0000000000000000000000000000000000000000",
+            "SUMMARY: libFuzzer: out-of-memory",
+        ]
+        .iter()
+        .map(|l| l.to_string())
+        .collect();
+        let Some(oom) = OutOfMemory::extract(&report) else {
+            panic!("Couldn't extract out-of-memory details");
+        };
+        assert!(!oom.is_single_allocation());
+        assert_eq!(oom.used_mb, Some(2050));
+        assert_eq!(oom.rss_limit_mb, Some(2048));
+        assert_eq!(oom.live_heap_bytes, 2147483712);
+        assert_eq!(oom.live_heap.len(), 2);
+        assert_eq!(oom.live_heap[0].bytes, 2147483648);
+        assert_eq!(oom.live_heap[0].count, 4);
+        assert_eq!(oom.live_heap[0].stacktrace.len(), 3);
+        assert_eq!(oom.live_heap[1].stacktrace.len(), 2);
+        let Ok(class) = AsanContext(report).severity() else {
+            panic!("Couldn't estimate severity");
+        };
+        assert_eq!(class.short_description, "out-of-memory(rss)");
+
+        let report = vec!["==2347== ERROR: libFuzzer: timeout after 1 seconds".to_string()];
+        assert!(OutOfMemory::extract(&report).is_none());
+    }
+
+    #[test]
+    fn test_asan_globals() {
+        let report: Vec<String> = [
+            "==1234==ERROR: AddressSanitizer: odr-violation (0x000000601f00):",
+            "  [1] size=4 'counter' /tmp/odr/a.cc:1:5 in /tmp/odr/liba.so",
+            "  [2] size=8 'counter' /tmp/odr/b.cc:3:6 in /tmp/odr/test",
+            "These globals were registered at these points:",
+            "  [1]:",
+            "    #0 0x41c2a7 in __asan_register_globals /llvm/compiler-rt/lib/asan/asan_globals.cpp:360:3",
+            "    #1 0x7f1f2e4a5b4e in asan.module_ctor (/tmp/odr/liba.so+0xb4e)",
+            "",
+            "  [2]:",
+            "    #0 0x41c2a7 in __asan_register_globals /llvm/compiler-rt/lib/asan/asan_globals.cpp:360:3",
+            "    #1 0x4f6d2e in asan.module_ctor (/tmp/odr/test+0x4f6d2e)",
+            "",
+            "==1234==HINT: if you don't care about these errors you may set ASAN_OPTIONS=detect_odr_violation=0",
+            "SUMMARY: AddressSanitizer: odr-violation: global 'counter' at /tmp/odr/a.cc:1:5 in /tmp/odr/liba.so",
+        ]
+        .iter()
+        .map(|l| l.to_string())
+        .collect();
+        let globals = AsanGlobal::extract_globals(&report);
+        assert_eq!(globals.len(), 2);
+        assert_eq!(globals[0].name, "counter");
+        assert_eq!(globals[0].size, 4);
+        assert_eq!(globals[0].location, "/tmp/odr/a.cc:1:5 in /tmp/odr/liba.so");
+        assert_eq!(globals[1].size, 8);
+        assert_eq!(globals[1].location, "/tmp/odr/b.cc:3:6 in /tmp/odr/test");
+        let Ok(class) = AsanContext(report).severity() else {
+            panic!("Couldn't estimate severity");
+        };
+        assert_eq!(class.short_description, "odr-violation");
+
+        let report: Vec<String> = [
+            "==5678==ERROR: AddressSanitizer: initialization-order-fiasco on address 0x000001b4b5a0 at pc 0x0000004f6c8b bp 0x7ffd2d6c1b30 sp 0x7ffd2d6c1b28",
+            "READ of size 4 at 0x000001b4b5a0 thread T0",
+            "    #0 0x4f6c8a in read_extern_global() /tmp/init/b.cc:3:10",
+            "    #1 0x4f6d1e in __cxx_global_var_init /tmp/init/b.cc:5:9",
+            "",
+            "0x000001b4b5a0 is located 0 bytes inside of global variable 'extern_global' defined in '/tmp/init/a.cc:1:5' (0x1b4b5a0) of size 4",
+            "  registered at:",
+            "    #0 0x41c2a7 in __asan_register_globals /llvm/compiler-rt/lib/asan/asan_globals.cpp:360:3",
+            "SUMMARY: AddressSanitizer: initialization-order-fiasco /tmp/init/b.cc:3:10 in read_extern_global()",
+        ]
+        .iter()
+        .map(|l| l.to_string())
+        .collect();
+        let globals = AsanGlobal::extract_globals(&report);
+        assert_eq!(globals.len(), 1);
+        assert_eq!(globals[0].name, "extern_global");
+        assert_eq!(globals[0].size, 4);
+        assert_eq!(globals[0].location, "/tmp/init/a.cc:1:5");
+
+        let report = vec!["==2347==ERROR: AddressSanitizer: SEGV on unknown address".to_string()];
+        assert!(AsanGlobal::extract_globals(&report).is_empty());
+    }
+
+    #[test]
+    fn test_asan_multiple_reports() {
+        let output: Vec<String> = [
+            "Running: crash",
+            "=================================================================",
+            "==4321==ERROR: AddressSanitizer: stack-buffer-overflow on address 0x7ffd0c9d1f30 at pc 0x4da4c9 bp 0x7ffd0c9d1ef0 sp 0x7ffd0c9d1ee8",
+            "READ of size 4 at 0x7ffd0c9d1f30 thread T0",
+            "    #0 0x4da4c8 in read_value /tmp/test_recover.c:5:12",
+            "    #1 0x4da5f1 in main /tmp/test_recover.c:12:5",
+            "",
+            "SUMMARY: AddressSanitizer: stack-buffer-overflow /tmp/test_recover.c:5:12 in read_value",
+            "==4321==ABORTING",
+            "",
+            "=================================================================",
+            "==4321==ERROR: AddressSanitizer: heap-buffer-overflow on address 0x602000000018 at pc 0x4da54e bp 0x7ffd0c9d1ef0 sp 0x7ffd0c9d1ee8",
+            "WRITE of size 8 at 0x602000000018 thread T0",
+            "    #0 0x4da54d in write_value /tmp/test_recover.c:8:12",
+            "    #1 0x4da612 in main /tmp/test_recover.c:13:5",
+            "",
+            "SUMMARY: AddressSanitizer: heap-buffer-overflow /tmp/test_recover.c:8:12 in write_value",
+            "",
+        ]
+        .iter()
+        .map(|l| l.to_string())
+        .collect();
+
+        let reports = extract_asan_reports(&output);
+        assert_eq!(reports.len(), 2);
+        assert_eq!(reports[0].len(), 7);
+        assert!(reports[0][0].contains("stack-buffer-overflow"));
+        assert_eq!(reports[0].last().unwrap(), "==4321==ABORTING");
+        assert_eq!(reports[1].len(), 6);
+        assert!(reports[1][0].contains("heap-buffer-overflow"));
+        assert!(reports[1].last().unwrap().starts_with("SUMMARY"));
+        assert!(extract_asan_reports(&output[..2]).is_empty());
+
+        let severity: Vec<String> = reports
+            .iter()
+            .map(|report| {
+                let Ok(class) = AsanContext(report.clone()).severity() else {
+                    panic!("Couldn't estimate severity");
+                };
+                class.short_description
+            })
+            .collect();
+        assert_eq!(
+            severity,
+            ["stack-buffer-overflow(read)", "heap-buffer-overflow(write)"]
+        );
+    }
+
+    #[test]
+    fn test_asan_alloc_free_stacks() {
+        let report: Vec<String> = [
+            "==5678==ERROR: AddressSanitizer: heap-use-after-free on address 0x602000000010 at pc 0x4da2f1 bp 0x7ffc6f3b5e50 sp 0x7ffc6f3b5e48",
+            "READ of size 4 at 0x602000000010 thread T0",
+            "    #0 0x4da2f0 in main /tmp/test_uaf.c:8:12",
+            "",
+            "0x602000000010 is located 0 bytes inside of 4-byte region [0x602000000010,0x602000000014)",
+            "freed by thread T0 here:",
+            "    #0 0x4a0d52 in free /llvm/compiler-rt/lib/asan/asan_malloc_linux.cpp:52:3",
+            "    #1 0x4da2b8 in main /tmp/test_uaf.c:7:5",
+            "",
+            "previously allocated by thread T0 here:",
+            "    #0 0x4a0fbd in malloc /llvm/compiler-rt/lib/asan/asan_malloc_linux.cpp:69:3",
+            "    #1 0x4da28a in alloc /tmp/test_uaf.c:3:12",
+            "    #2 0x4da2a8 in main /tmp/test_uaf.c:6:14",
+            "",
+            "SUMMARY: AddressSanitizer: heap-use-after-free /tmp/test_uaf.c:8:12 in main",
+        ]
+        .iter()
+        .map(|l| l.to_string())
+        .collect();
+
+        let context = AsanContext(report);
+        let free = context.deallocation_stacktrace();
+        assert_eq!(free.len(), 2);
+        assert_eq!(free[1], "    #1 0x4da2b8 in main /tmp/test_uaf.c:7:5");
+        let alloc = context.allocation_stacktrace();
+        assert_eq!(alloc.len(), 3);
+        assert_eq!(alloc[1], "    #1 0x4da28a in alloc /tmp/test_uaf.c:3:12");
+        let Ok(stacktrace) = AsanStacktrace::parse_stacktrace(&alloc) else {
+            panic!("Couldn't parse allocation stack trace");
+        };
+        assert_eq!(stacktrace[1].function, "alloc");
+
+        let context = AsanContext(vec![
+            "==5678==ERROR: AddressSanitizer: heap-buffer-overflow on address 0x602000000014 at pc 0x4da2f1 bp 0x7ffc6f3b5e50 sp 0x7ffc6f3b5e48".to_string(),
+            "allocated by thread T0 here:".to_string(),
+            "    #0 0x4a0fbd in malloc /llvm/compiler-rt/lib/asan/asan_malloc_linux.cpp:69:3".to_string(),
+        ]);
+        assert_eq!(context.allocation_stacktrace().len(), 1);
+        assert!(context.deallocation_stacktrace().is_empty());
+    }
 }
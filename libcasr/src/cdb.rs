@@ -0,0 +1,343 @@
+//! Cdb module implements `ParseStacktrace` and `Severity` traits for Windows
+//! console debugger (cdb.exe) output.
+use regex::Regex;
+
+use crate::error::*;
+use crate::execution_class::{is_near_null, ExecutionClass};
+use crate::severity::Severity;
+use crate::stacktrace::*;
+
+use gdb_command::registers::Registers;
+
+/// Structure provides an interface for processing the stack trace.
+pub struct CdbStacktrace;
+
+impl ParseStacktrace for CdbStacktrace {
+    fn extract_stacktrace(stream: &str) -> Result<Vec<String>> {
+        let lines: Vec<&str> = stream.split('\n').map(|l| l.trim_end()).collect();
+
+        // " # Child-SP          RetAddr               Call Site"
+        let Some(first) = lines
+            .iter()
+            .position(|l| l.trim_start().starts_with('#') && l.contains("Call Site"))
+        else {
+            return Err(Error::Casr(
+                "Couldn't find stack trace in cdb output".to_string(),
+            ));
+        };
+
+        let frame = Regex::new(r"^[0-9a-f]{2,} ").unwrap();
+        Ok(lines[first + 1..]
+            .iter()
+            .take_while(|l| frame.is_match(l))
+            .map(|l| l.to_string())
+            .collect())
+    }
+
+    fn parse_stacktrace_entry(entry: &str) -> Result<StacktraceEntry> {
+        let mut stentry = StacktraceEntry::default();
+
+        // 00 00000042`5c8ff6d8 00007ff6`1c2a1234     test!main+0x1d [C:\src\test.cpp @ 5]
+        let re = Regex::new(r"^[0-9a-f]+ +[0-9a-f`]+ +[0-9a-f`]+ +(.+)$").unwrap();
+        let Some(caps) = re.captures(entry.trim()) else {
+            return Err(Error::Casr(format!(
+                "Couldn't parse cdb stack trace entry: {entry}"
+            )));
+        };
+        let mut location = caps.get(1).unwrap().as_str().trim();
+
+        // [file @ line]
+        let re = Regex::new(r"\[(.+) @ ([0-9]+)\]$").unwrap();
+        if let Some(caps) = re.captures(location) {
+            stentry.debug.file = caps.get(1).unwrap().as_str().trim().to_string();
+            stentry.debug.line = caps.get(2).unwrap().as_str().parse::<u64>().unwrap_or(0);
+            location = location[..caps.get(0).unwrap().start()].trim();
+        }
+
+        // Frame without symbols: 0x00007ff6`1c2a1234
+        if let Some(num) = location.strip_prefix("0x") {
+            let num = num.replace('`', "");
+            let Ok(address) = u64::from_str_radix(&num, 16) else {
+                return Err(Error::Casr(format!("Couldn't parse address: {num}")));
+            };
+            stentry.address = address;
+            return Ok(stentry);
+        }
+
+        // module!function+0x1d or module+0x1d
+        let (name, offset) = location.rsplit_once("+0x").unwrap_or((location, "0"));
+        let Ok(offset) = u64::from_str_radix(offset, 16) else {
+            return Err(Error::Casr(format!("Couldn't parse offset: {offset}")));
+        };
+        stentry.offset = offset;
+        if let Some((module, function)) = name.split_once('!') {
+            stentry.module = module.to_string();
+            stentry.function = function.to_string();
+        } else {
+            stentry.module = name.to_string();
+        }
+
+        Ok(stentry)
+    }
+}
+
+/// Access violation type.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Access {
+    /// Read from invalid address.
+    Read,
+    /// Write to invalid address.
+    Write,
+    /// Execution of non-executable memory (DEP violation).
+    Execute,
+}
+
+/// Information about Windows exception from cdb output: exception record
+/// (`.exr -1`), registers (`r`), and, optionally, `!analyze -v` results.
+pub struct CdbContext(pub Vec<String>);
+
+impl CdbContext {
+    /// Get exception code (NTSTATUS) from exception record or `!analyze -v`
+    /// output
+    pub fn exception_code(&self) -> Option<u32> {
+        // ExceptionCode: c0000005 (Access violation)
+        // EXCEPTION_CODE_STR:  c0000005
+        // EXCEPTION_CODE: (NTSTATUS) 0xc0000005 - The instruction at ...
+        let rcode = Regex::new(
+            r"(?:ExceptionCode:|EXCEPTION_CODE_STR:|EXCEPTION_CODE: \(\w+\)) +(?:0x)?([0-9a-fA-F]{8})",
+        )
+        .unwrap();
+        self.0
+            .iter()
+            .find_map(|l| rcode.captures(l))
+            .and_then(|caps| u32::from_str_radix(caps.get(1).unwrap().as_str(), 16).ok())
+    }
+
+    /// Get access violation type and invalid address from exception record
+    /// or `!analyze -v` output
+    pub fn access_violation(&self) -> Option<(Access, u64)> {
+        let parse_hex = |s: &str| u64::from_str_radix(&s.replace('`', ""), 16).ok();
+        let raccess = Regex::new(
+            r"Attempt to (read from|write to|execute non-executable) address ([0-9a-fA-F`]+)",
+        )
+        .unwrap();
+        if let Some(caps) = self.0.iter().find_map(|l| raccess.captures(l)) {
+            let access = match caps.get(1).unwrap().as_str() {
+                "read from" => Access::Read,
+                "write to" => Access::Write,
+                _ => Access::Execute,
+            };
+            return Some((access, parse_hex(caps.get(2).unwrap().as_str())?));
+        }
+        // First exception parameter is access type, second one is address.
+        let rparam =
+            Regex::new(r"(?:Parameter\[([01])\]:|EXCEPTION_PARAMETER([12]):) +([0-9a-fA-F`]+)")
+                .unwrap();
+        let mut params = [None, None];
+        for caps in self.0.iter().filter_map(|l| rparam.captures(l)) {
+            let idx = if let Some(idx) = caps.get(1) {
+                idx.as_str() == "1"
+            } else {
+                caps.get(2).unwrap().as_str() == "2"
+            };
+            params[idx as usize] = parse_hex(caps.get(3).unwrap().as_str());
+        }
+        let access = match params[0]? {
+            0 => Access::Read,
+            1 => Access::Write,
+            8 => Access::Execute,
+            _ => return None,
+        };
+        Some((access, params[1]?))
+    }
+
+    /// Get registers from `r` cdb command output
+    pub fn registers(&self) -> Registers {
+        // rax=0000000000000000 rbx=000001f2c4a0a8c0 rcx=0000000000000001
+        let rreg = Regex::new(r"\b([a-z][a-z0-9]{1,3})=([0-9a-f]{8}|[0-9a-f]{16})\b").unwrap();
+        let mut registers = Registers::new();
+        for line in self.0.iter() {
+            for caps in rreg.captures_iter(line) {
+                if let Ok(value) = u64::from_str_radix(caps.get(2).unwrap().as_str(), 16) {
+                    registers.insert(caps.get(1).unwrap().as_str().to_string(), value);
+                }
+            }
+        }
+        registers
+    }
+}
+
+impl Severity for CdbContext {
+    fn severity(&self) -> Result<ExecutionClass> {
+        let Some(code) = self.exception_code() else {
+            return Err(Error::Casr(
+                "Couldn't find exception code in cdb output".to_string(),
+            ));
+        };
+        match code {
+            // STATUS_ACCESS_VIOLATION
+            0xc0000005 => {
+                let Some((access, address)) = self.access_violation() else {
+                    return ExecutionClass::find("AccessViolation");
+                };
+                match (access, is_near_null(address)) {
+                    (Access::Read, false) => ExecutionClass::find("SourceAv"),
+                    (Access::Read, true) => ExecutionClass::find("SourceAvNearNull"),
+                    (Access::Write, false) => ExecutionClass::find("DestAv"),
+                    (Access::Write, true) => ExecutionClass::find("DestAvNearNull"),
+                    (Access::Execute, false) => ExecutionClass::find("SegFaultOnPc"),
+                    (Access::Execute, true) => ExecutionClass::find("SegFaultOnPcNearNull"),
+                }
+            }
+            // STATUS_STACK_OVERFLOW
+            0xc00000fd => ExecutionClass::find("StackOverflow"),
+            // STATUS_HEAP_CORRUPTION
+            0xc0000374 => ExecutionClass::find("HeapError"),
+            // STATUS_STACK_BUFFER_OVERRUN (/GS check or fail fast)
+            0xc0000409 => ExecutionClass::find("StackGuard"),
+            // STATUS_ILLEGAL_INSTRUCTION, STATUS_PRIVILEGED_INSTRUCTION
+            0xc000001d | 0xc0000096 => ExecutionClass::find("BadInstruction"),
+            // STATUS_FLOAT_* and STATUS_INTEGER_*
+            0xc000008d..=0xc0000095 => ExecutionClass::find("FPE"),
+            // STATUS_BREAKPOINT
+            0x80000003 => ExecutionClass::find("TrapSignal"),
+            // STATUS_FATAL_APP_EXIT (abort)
+            0x40000015 => ExecutionClass::find("AbortSignal"),
+            _ => Err(Error::Casr(format!(
+                "Unsupported exception code: {code:#x}"
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stacktrace::tests::safe_init_ignore_stack_frames;
+
+    #[test]
+    fn test_cdb_stacktrace() {
+        let output = r" # Child-SP          RetAddr               Call Site
+00 00000042`5c8ff6d8 00007ff6`1c2a1234     ntdll!RtlpHeapHandleError+0x12
+01 00000042`5c8ff6e0 00007ff6`1c2a1234     test!main+0x1d [C:\src\test.cpp @ 5]
+02 00000042`5c8ff710 00007ffc`7e3e7034     test!__scrt_common_main_seh+0x10c [d:\a01\_work\12\s\src\vctools\crt\vcstartup\src\startup\exe_common.inl @ 288]
+03 00000042`5c8ff750 00007ffc`7f6a2651     KERNEL32!BaseThreadInitThunk+0x14
+04 00000042`5c8ff780 00000000`00000000     0x00007ff6`1c2a1000
+0:000> q";
+
+        let raw_stacktrace = CdbStacktrace::extract_stacktrace(output);
+        let Ok(raw_stacktrace) = raw_stacktrace else {
+            panic!("{}", raw_stacktrace.err().unwrap());
+        };
+        assert_eq!(raw_stacktrace.len(), 5);
+
+        let stacktrace = CdbStacktrace::parse_stacktrace(&raw_stacktrace);
+        let Ok(stacktrace) = stacktrace else {
+            panic!("{}", stacktrace.err().unwrap());
+        };
+        assert_eq!(stacktrace[1].module, "test");
+        assert_eq!(stacktrace[1].function, "main");
+        assert_eq!(stacktrace[1].offset, 0x1d);
+        assert_eq!(stacktrace[1].debug.file, r"C:\src\test.cpp");
+        assert_eq!(stacktrace[1].debug.line, 5);
+        assert_eq!(stacktrace[3].module, "KERNEL32");
+        assert_eq!(stacktrace[3].function, "BaseThreadInitThunk");
+        assert_eq!(stacktrace[4].address, 0x7ff61c2a1000);
+
+        safe_init_ignore_stack_frames();
+        let Ok(crashline) = stacktrace.crash_line() else {
+            panic!("Couldn't get crash line");
+        };
+        assert_eq!(crashline.to_string(), r"C:\src\test.cpp:5");
+    }
+
+    #[test]
+    fn test_cdb_severity() {
+        let exr = |lines: &[&str]| CdbContext(lines.iter().map(|l| l.to_string()).collect());
+        let context = exr(&[
+            "ExceptionAddress: 00007ff61c2a101d (test!main+0x000000000000001d)",
+            "   ExceptionCode: c0000005 (Access violation)",
+            "  ExceptionFlags: 00000000",
+            "NumberParameters: 2",
+            "   Parameter[0]: 0000000000000001",
+            "   Parameter[1]: 0000000000000010",
+            "Attempt to write to address 0000000000000010",
+        ]);
+        let class = context.severity();
+        let Ok(class) = class else {
+            panic!("{}", class.err().unwrap());
+        };
+        assert_eq!(class.short_description, "DestAvNearNull");
+
+        let context = exr(&[
+            "   ExceptionCode: c0000005 (Access violation)",
+            "Attempt to read from address 00000241`deadbeef",
+        ]);
+        let Ok(class) = context.severity() else {
+            panic!("Couldn't estimate severity");
+        };
+        assert_eq!(class.short_description, "SourceAv");
+
+        let context = exr(&["   ExceptionCode: c00000fd (Stack overflow)"]);
+        let Ok(class) = context.severity() else {
+            panic!("Couldn't estimate severity");
+        };
+        assert_eq!(class.short_description, "StackOverflow");
+
+        let context = exr(&["   ExceptionCode: c0000374"]);
+        let Ok(class) = context.severity() else {
+            panic!("Couldn't estimate severity");
+        };
+        assert_eq!(class.short_description, "HeapError");
+
+        // DEP violation from exception parameters.
+        let context = exr(&[
+            "   ExceptionCode: c0000005 (Access violation)",
+            "   Parameter[0]: 0000000000000008",
+            "   Parameter[1]: 00000241deadbeef",
+        ]);
+        assert_eq!(
+            context.access_violation(),
+            Some((Access::Execute, 0x241deadbeef))
+        );
+        let Ok(class) = context.severity() else {
+            panic!("Couldn't estimate severity");
+        };
+        assert_eq!(class.short_description, "SegFaultOnPc");
+    }
+
+    #[test]
+    fn test_cdb_analyze() {
+        let output = r"rax=0000000000000000 rbx=00000241deadbeef rcx=0000000000000001
+rip=00007ff61c2a101d rsp=000000425c8ff6d8 rbp=0000000000000000
+iopl=0         nv up ei pl zr na po nc
+cs=0033  ss=002b  ds=002b  es=002b  fs=0053  gs=002b             efl=00010246
+EXCEPTION_RECORD:  (.exr -1)
+EXCEPTION_CODE_STR:  c0000005
+EXCEPTION_PARAMETER1:  0000000000000001
+EXCEPTION_PARAMETER2:  00000241deadbeef
+FAILURE_BUCKET_ID:  INVALID_POINTER_WRITE_c0000005_test.exe!main";
+        let context = CdbContext(output.lines().map(|l| l.to_string()).collect());
+        assert_eq!(context.exception_code(), Some(0xc0000005));
+        assert_eq!(
+            context.access_violation(),
+            Some((Access::Write, 0x241deadbeef))
+        );
+        let Ok(class) = context.severity() else {
+            panic!("Couldn't estimate severity");
+        };
+        assert_eq!(class.short_description, "DestAv");
+
+        let registers = context.registers();
+        assert_eq!(registers.get("rbx"), Some(&0x241deadbeef));
+        assert_eq!(registers.get("rip"), Some(&0x7ff61c2a101d));
+        assert_eq!(registers.get("efl"), Some(&0x10246));
+        assert!(!registers.contains_key("cs"));
+
+        let context = CdbContext(vec![
+            "EXCEPTION_CODE: (NTSTATUS) 0xc00000fd - A new guard page for the stack cannot be created."
+                .to_string(),
+        ]);
+        assert_eq!(context.exception_code(), Some(0xc00000fd));
+    }
+}
@@ -146,6 +146,7 @@ pub const STACK_FRAME_FUNCTION_IGNORE_REGEXES_CPP: &[&str] = &[
     r"^android\.os\.Parcel\.",
     r"^art::Thread::CreateNativeThread",
     r"^asan_",
+    r"^asm_exc_",
     r"^asan\.module_ctor",
     r"^asan\.module_dtor",
     r"^calloc",
@@ -155,12 +156,15 @@ pub const STACK_FRAME_FUNCTION_IGNORE_REGEXES_CPP: &[&str] = &[
     r"^demangling_terminate_handler",
     r"^dump_backtrace",
     r"^dump_stack",
+    r"^exc_invalid_op",
+    r"^exc_page_fault",
     r"^exit_or_terminate_process",
     r"^fpehandler\(",
     r"^free",
     r"^g_log",
     r"^generic_cpp_",
     r"^gsignal",
+    r"^handle_bug",
     r"^kasan_",
     // LibFuzzer
     r"^fuzzer::",
@@ -171,6 +175,9 @@ pub const STACK_FRAME_FUNCTION_IGNORE_REGEXES_CPP: &[&str] = &[
     r"^new",
     r"^object_err",
     r"^operator",
+    r"^page_fault_oops",
+    r"^print_address_description",
+    r"^print_report",
     r"^print_trailer",
     r"^realloc",
     r"^scanf",
@@ -311,6 +318,20 @@ pub const STACK_FRAME_FILEPATH_IGNORE_REGEXES_CPP: &[&str] = &[
     r".*libasan\.so",
     r".*libubsan\.so",
     r".*asan_with_fuzzer\.so",
+    // Valgrind
+    r".*/vgpreload_.*\.so",
+    r"^vg_replace_.*\.c$",
+    // Windows runtime libraries.
+    r"(?i).*\\windows\\system32\\",
+    r"(?i)^(ntdll|kernel32|kernelbase|ucrtbased?|vcruntime140d?|msvcrt)$",
+    r"(?i).*clang_rt\.asan.*\.dll",
+    r".*\\minkernel\\crts\\",
+    r".*\\vctools\\crt\\",
+    // macOS system libraries.
+    r"^/usr/lib/system/",
+    r"^/usr/lib/libc\+\+",
+    r"^(dyld|libdyld\.dylib|libsystem_.*\.dylib|libc\+\+(abi)?\.(1\.)?dylib)$",
+    r".*libclang_rt\.asan_osx_dynamic\.dylib",
 ];
 
 // Signal numbers
@@ -323,3 +344,6 @@ pub const SIGINFO_SIGSEGV: u32 = 11;
 pub const SIGINFO_SIGSYS: u32 = 31;
 
 pub const SI_KERNEL: u32 = 0x80;
+// ARM MTE asynchronous and synchronous tag check faults
+pub const SEGV_MTEAERR: u32 = 8;
+pub const SEGV_MTESERR: u32 = 9;
@@ -0,0 +1,446 @@
+//! Debug module resolves function names and source locations for stack trace
+//! entries from DWARF debug information without running gdb or llvm-symbolizer.
+//! Debug information for stripped modules is searched by build ID in
+//! `/usr/lib/debug/.build-id` and downloaded from debuginfod servers
+//! (`DEBUGINFOD_URLS`) with `debuginfod` feature.
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use addr2line::object::{self, Object, ObjectSection, ObjectSegment};
+use addr2line::ObjectContext;
+use regex::Regex;
+
+use crate::error::*;
+use crate::stacktrace::*;
+
+/// DWARF debug information and loadable segments of ELF module.
+pub struct DwarfSymbols {
+    /// Address to source location lookup context.
+    context: ObjectContext,
+    /// Loadable segments (file offset, virtual address, file size).
+    segments: Vec<(u64, u64, u64)>,
+}
+
+impl DwarfSymbols {
+    /// Read DWARF debug information from ELF file.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - path to ELF module
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let buffer = fs::read(path)?;
+        Self::from_bytes(&buffer)
+    }
+
+    /// Read DWARF debug information from ELF module contents. If module is
+    /// stripped, separate debug information file is searched by build ID.
+    ///
+    /// # Arguments
+    ///
+    /// * `buffer` - ELF module contents
+    pub fn from_bytes(buffer: &[u8]) -> Result<Self> {
+        let file = object::File::parse(buffer)
+            .map_err(|e| Error::Casr(format!("Couldn't parse object file: {e}")))?;
+        let context = if has_dwarf(&file) {
+            ObjectContext::new(&file)
+        } else {
+            let Some(build_id) = file.build_id().ok().flatten() else {
+                return Err(Error::Casr(
+                    "No DWARF debug information and build ID in module".to_string(),
+                ));
+            };
+            let build_id: String = build_id.iter().map(|b| format!("{b:02x}")).collect();
+            let debug_buffer = fs::read(find_debug_file(&build_id)?)?;
+            let debug_file = object::File::parse(&*debug_buffer)
+                .map_err(|e| Error::Casr(format!("Couldn't parse debug file: {e}")))?;
+            if !has_dwarf(&debug_file) {
+                return Err(Error::Casr(format!(
+                    "No DWARF debug information in debug file for build ID {build_id}"
+                )));
+            }
+            ObjectContext::new(&debug_file)
+        }
+        .map_err(|e| Error::Casr(format!("Couldn't read DWARF debug information: {e}")))?;
+        // Separate debug file may have different file layout, so segments are
+        // always taken from module.
+        let segments = file
+            .segments()
+            .map(|segment| {
+                let (offset, size) = segment.file_range();
+                (offset, segment.address(), size)
+            })
+            .collect();
+        Ok(DwarfSymbols { context, segments })
+    }
+
+    /// Find function and source location for file offset. Inlined functions
+    /// are resolved to the innermost one.
+    ///
+    /// # Arguments
+    ///
+    /// * `offset` - offset in ELF module file
+    ///
+    /// # Return value
+    ///
+    /// Function name (may be empty) and source location
+    pub fn lookup(&self, offset: u64) -> Option<(String, DebugInfo)> {
+        self.lookup_inline(offset).into_iter().next()
+    }
+
+    /// Find inline chain for file offset: functions and source locations from
+    /// the innermost inlined function to the out-of-line one. Each location
+    /// is the place in the function where the next function in chain is
+    /// inlined (or the offset itself for the innermost one).
+    ///
+    /// # Arguments
+    ///
+    /// * `offset` - offset in ELF module file
+    ///
+    /// # Return value
+    ///
+    /// Function names (may be empty) and source locations
+    pub fn lookup_inline(&self, offset: u64) -> Vec<(String, DebugInfo)> {
+        let Some((file_offset, vaddr, _)) = self
+            .segments
+            .iter()
+            .find(|(start, _, size)| (*start..start + size).contains(&offset))
+        else {
+            return Vec::new();
+        };
+        let address = offset - file_offset + vaddr;
+        let Ok(mut frames) = self.context.find_frames(address).skip_all_loads() else {
+            return Vec::new();
+        };
+        let mut chain = Vec::new();
+        while let Ok(Some(frame)) = frames.next() {
+            let Some(location) = frame.location else {
+                continue;
+            };
+            let Some(file) = location.file else {
+                continue;
+            };
+            let debug = DebugInfo {
+                file: file.to_string(),
+                line: location.line.unwrap_or(0) as u64,
+                column: location.column.unwrap_or(0) as u64,
+            };
+            let function = frame
+                .function
+                .and_then(|name| name.demangle().ok().map(|name| name.to_string()))
+                .unwrap_or_default();
+            chain.push((function, debug));
+        }
+        chain
+    }
+}
+
+/// Check that object file has DWARF debug information
+///
+/// # Arguments
+///
+/// * `file` - object file
+fn has_dwarf(file: &object::File) -> bool {
+    file.section_by_name(".debug_info")
+        .is_some_and(|section| section.size() != 0)
+}
+
+/// Find separate debug information file by build ID. Debug files installed
+/// by package manager are checked first, then debuginfod servers are queried.
+///
+/// # Arguments
+///
+/// * `build_id` - hex build ID of module
+///
+/// # Return value
+///
+/// Path to debug information file
+pub fn find_debug_file(build_id: &str) -> Result<PathBuf> {
+    if build_id.len() > 2 {
+        let path = Path::new("/usr/lib/debug/.build-id")
+            .join(&build_id[..2])
+            .join(format!("{}.debug", &build_id[2..]));
+        if path.is_file() {
+            return Ok(path);
+        }
+    }
+    #[cfg(feature = "debuginfod")]
+    if let Ok(path) = debuginfod::fetch(build_id) {
+        return Ok(path);
+    }
+    Err(Error::Casr(format!(
+        "Couldn't find debug file for build ID {build_id}"
+    )))
+}
+
+/// Debuginfod client that downloads debug information files by build ID and
+/// caches them in the same layout as elfutils client does, so both caches may
+/// be shared.
+#[cfg(feature = "debuginfod")]
+pub mod debuginfod {
+    use std::collections::HashSet;
+    use std::env;
+    use std::fs;
+    use std::io::Read;
+    use std::path::PathBuf;
+    use std::sync::Mutex;
+    use std::time::Duration;
+
+    use crate::error::*;
+
+    /// Default server response timeout in seconds. It is much shorter than
+    /// elfutils one, because modules of each crash are symbolized.
+    const DEFAULT_TIMEOUT: u64 = 5;
+
+    /// Build IDs that weren't found on servers, so they aren't requested again.
+    static MISSES: Mutex<Option<HashSet<String>>> = Mutex::new(None);
+
+    /// Get debuginfod cache directory: `DEBUGINFOD_CACHE_PATH`,
+    /// `$XDG_CACHE_HOME/debuginfod_client`, or `~/.cache/debuginfod_client`.
+    pub fn cache_dir() -> Option<PathBuf> {
+        if let Some(path) = env::var_os("DEBUGINFOD_CACHE_PATH") {
+            return Some(PathBuf::from(path));
+        }
+        if let Some(path) = env::var_os("XDG_CACHE_HOME") {
+            return Some(PathBuf::from(path).join("debuginfod_client"));
+        }
+        env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache/debuginfod_client"))
+    }
+
+    /// Get debug information file URLs for build ID
+    ///
+    /// # Arguments
+    ///
+    /// * `servers` - space separated server URLs (`DEBUGINFOD_URLS` value)
+    ///
+    /// * `build_id` - hex build ID of module
+    pub fn urls(servers: &str, build_id: &str) -> Vec<String> {
+        servers
+            .split_whitespace()
+            .map(|server| {
+                format!(
+                    "{}/buildid/{build_id}/debuginfo",
+                    server.trim_end_matches('/')
+                )
+            })
+            .collect()
+    }
+
+    /// Get debug information file from cache or download it from servers
+    /// listed in `DEBUGINFOD_URLS`.
+    ///
+    /// # Arguments
+    ///
+    /// * `build_id` - hex build ID of module
+    ///
+    /// # Return value
+    ///
+    /// Path to cached debug information file
+    pub fn fetch(build_id: &str) -> Result<PathBuf> {
+        let Some(cache) = cache_dir() else {
+            return Err(Error::Casr(
+                "Couldn't determine debuginfod cache directory".to_string(),
+            ));
+        };
+        let path = cache.join(build_id).join("debuginfo");
+        if path.is_file() {
+            return Ok(path);
+        }
+        if MISSES
+            .lock()
+            .unwrap()
+            .as_ref()
+            .is_some_and(|misses| misses.contains(build_id))
+        {
+            return Err(Error::Casr(format!(
+                "Debug file for build ID {build_id} wasn't found on debuginfod servers"
+            )));
+        }
+        let servers = env::var("DEBUGINFOD_URLS").unwrap_or_default();
+        let timeout = env::var("DEBUGINFOD_TIMEOUT")
+            .ok()
+            .and_then(|timeout| timeout.parse::<u64>().ok())
+            .unwrap_or(DEFAULT_TIMEOUT);
+        // Large debug files may be downloaded longer than timeout, so it
+        // limits connection and each read rather than the whole request.
+        let agent = ureq::AgentBuilder::new()
+            .timeout_connect(Duration::from_secs(timeout))
+            .timeout_read(Duration::from_secs(timeout))
+            .build();
+        for url in urls(&servers, build_id) {
+            let Ok(response) = agent.get(&url).call() else {
+                continue;
+            };
+            let mut data = Vec::new();
+            if response.into_reader().read_to_end(&mut data).is_err() {
+                continue;
+            }
+            fs::create_dir_all(path.parent().unwrap())?;
+            // Parallel CASR tools shouldn't read partially written file.
+            let tmp = path.with_extension(format!("{}.tmp", std::process::id()));
+            fs::write(&tmp, &data)?;
+            fs::rename(&tmp, &path)?;
+            return Ok(path);
+        }
+        MISSES
+            .lock()
+            .unwrap()
+            .get_or_insert_with(HashSet::new)
+            .insert(build_id.to_string());
+        Err(Error::Casr(format!(
+            "Couldn't download debug file for build ID {build_id} from debuginfod"
+        )))
+    }
+}
+
+/// Resolve function names and source locations for stack trace entries that have
+/// module offset but no debug information. Module offsets should be computed
+/// before (e.g., via `compute_module_offsets`). Modules without DWARF debug
+/// information are skipped.
+///
+/// # Arguments
+///
+/// * `stacktrace` - stack trace to symbolize
+///
+/// # Return value
+///
+/// Resolved entries indices
+pub fn symbolize_stacktrace(stacktrace: &mut Stacktrace) -> Vec<usize> {
+    let mut modules: HashMap<String, Option<DwarfSymbols>> = HashMap::new();
+    let mut resolved = Vec::new();
+    for (idx, entry) in stacktrace.iter_mut().enumerate() {
+        if !entry.debug.file.is_empty() || entry.module.is_empty() || entry.offset == 0 {
+            continue;
+        }
+        let symbols = modules
+            .entry(entry.module.clone())
+            .or_insert_with(|| DwarfSymbols::from_file(Path::new(&entry.module)).ok());
+        // Return address points to the instruction after call.
+        let offset = if idx == 0 {
+            entry.offset
+        } else {
+            entry.offset - 1
+        };
+        let Some((function, debug)) = symbols.as_ref().and_then(|s| s.lookup(offset)) else {
+            continue;
+        };
+        if !function.is_empty() {
+            entry.function = function;
+        }
+        entry.debug = debug;
+        resolved.push(idx);
+    }
+    resolved
+}
+
+/// Insert resolved function name and source location into raw gdb or sanitizer
+/// stack trace line, e.g.,
+/// "#0 0x555555555149 in ?? ()" -> "#0 0x555555555149 in crash () at /src/test.c:5",
+/// "#1 0x55555555516d  (/tmp/test+0x116d)" ->
+/// "#1 0x55555555516d in main /src/test.c:10:3 (/tmp/test+0x116d)".
+///
+/// # Arguments
+///
+/// * `line` - stack trace line
+///
+/// * `entry` - resolved stack trace entry
+pub fn insert_location(line: &str, entry: &StacktraceEntry) -> String {
+    let function = if entry.function.is_empty() {
+        "??"
+    } else {
+        entry.function.as_str()
+    };
+    let mut location = format!("{}:{}", entry.debug.file, entry.debug.line);
+    let sanitizer =
+        Regex::new(r"^( *#[0-9]+ +0x[0-9a-f]+)(?: in .+?)? +(\([^()]+\+0x[0-9a-f]+\).*)$").unwrap();
+    if sanitizer.is_match(line) {
+        if entry.debug.column != 0 {
+            location += &format!(":{}", entry.debug.column);
+        }
+        return sanitizer
+            .replace(line, format!("$1 in {function} {location} $2").as_str())
+            .to_string();
+    }
+    let gdb = Regex::new(r"^( *#[0-9]+ +0x[0-9a-f]+)(?: in [^(]+)? *\(.*\)(?: from .+)?$").unwrap();
+    gdb.replace(line, format!("$1 in {function} () at {location}").as_str())
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert_location() {
+        let entry = StacktraceEntry {
+            function: "crash".to_string(),
+            debug: DebugInfo {
+                file: "/src/test.c".to_string(),
+                line: 5,
+                column: 10,
+            },
+            ..Default::default()
+        };
+        assert_eq!(
+            insert_location("#0  0x0000555555555149 in ?? ()", &entry),
+            "#0  0x0000555555555149 in crash () at /src/test.c:5"
+        );
+        assert_eq!(
+            insert_location(
+                "#1  0x00007ffff7829d90 in ?? () from /tmp/libtest.so",
+                &entry
+            ),
+            "#1  0x00007ffff7829d90 in crash () at /src/test.c:5"
+        );
+        assert_eq!(
+            insert_location("    #1 0x55555555516d  (/tmp/test+0x116d)", &entry),
+            "    #1 0x55555555516d in crash /src/test.c:5:10 (/tmp/test+0x116d)"
+        );
+        assert_eq!(
+            insert_location(
+                "    #2 0x55555555516d in crash+0x10 (/tmp/test+0x116d) (BuildId: 1234)",
+                &entry
+            ),
+            "    #2 0x55555555516d in crash /src/test.c:5:10 (/tmp/test+0x116d) (BuildId: 1234)"
+        );
+
+        let Ok(stacktrace) = crate::asan::AsanStacktrace::parse_stacktrace(&[
+            "    #1 0x55555555516d in crash /src/test.c:5:10 (/tmp/test+0x116d)".to_string(),
+        ]) else {
+            panic!("Couldn't parse stack trace");
+        };
+        assert_eq!(stacktrace[0].function, "crash");
+        assert_eq!(stacktrace[0].debug.file, "/src/test.c");
+        assert_eq!(stacktrace[0].debug.line, 5);
+        assert_eq!(stacktrace[0].offset, 0x116d);
+    }
+
+    #[cfg(feature = "debuginfod")]
+    #[test]
+    fn test_debuginfod_urls() {
+        assert_eq!(
+            debuginfod::urls(
+                "https://debuginfod.debian.net/ https://debuginfod.elfutils.org",
+                "1c2e4f"
+            ),
+            vec![
+                "https://debuginfod.debian.net/buildid/1c2e4f/debuginfo",
+                "https://debuginfod.elfutils.org/buildid/1c2e4f/debuginfo"
+            ]
+        );
+        assert!(debuginfod::urls("", "1c2e4f").is_empty());
+
+        // Missing build ID is requested only once.
+        let cache = std::env::temp_dir().join("casr_debuginfod_test");
+        std::env::set_var("DEBUGINFOD_CACHE_PATH", &cache);
+        std::env::set_var("DEBUGINFOD_URLS", "http://127.0.0.1:1");
+        let Err(error) = debuginfod::fetch("1c2e4f") else {
+            panic!("Debug file is downloaded from closed port");
+        };
+        assert!(error.to_string().contains("Couldn't download"));
+        let Err(error) = debuginfod::fetch("1c2e4f") else {
+            panic!("Debug file is downloaded from closed port");
+        };
+        assert!(error.to_string().contains("wasn't found"));
+    }
+}
@@ -3,6 +3,7 @@
 use crate::error;
 
 use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
 
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
@@ -40,7 +41,7 @@ pub struct ExecutionClass {
 /// Instances of `ExecutionClass` structure.
 /// Add new classes to the end of array.
 /// TODO: Think about adding some ID for array element.
-pub const CLASSES: &[(&str, &str, &str, &str); 71] = &[
+pub const CLASSES: &[(&str, &str, &str, &str); 115] = &[
     ("EXPLOITABLE", "SegFaultOnPc", "Segmentation fault on program counter", "The target tried to access data at an address that matches the program counter. This likely indicates that the program counter contents are tainted and can be controlled by an attacker."),
     ("EXPLOITABLE", "ReturnAv", "Access violation during return instruction", "The target crashed on a return instruction, which likely indicates stack corruption."),
     ("EXPLOITABLE", "BranchAv", "Access violation during branch instruction", "The target crashed on a branch instruction, which may indicate that the control flow is tainted."),
@@ -112,6 +113,50 @@ pub const CLASSES: &[(&str, &str, &str, &str); 71] = &[
     ("NOT_EXPLOITABLE", "fuzz target exited", "Fuzz target exited", "Fuzz target exited."),
     ("NOT_EXPLOITABLE", "timeout", "Target timeout expired", "Timeout after several seconds."),
     ("PROBABLY_EXPLOITABLE", "overwrites-const-input", "Attempt to overwrite constant input", "Fuzz target overwrites its constant input."),
+    ("NOT_EXPLOITABLE", "lock-order-inversion", "Lock order inversion (potential deadlock)", "Threads acquire the same mutexes in different order, which may lead to a deadlock."),
+    ("NOT_EXPLOITABLE", "data-race", "Data race", "Two threads access the same memory location concurrently without synchronization and at least one of the accesses is a write."),
+    ("PROBABLY_EXPLOITABLE", "data-race-vptr", "Data race on vptr", "Virtual function is called concurrently with object construction or destruction, so wrong function may be called."),
+    ("NOT_EXPLOITABLE", "thread-leak", "Thread leak", "Thread is finished but neither joined nor detached, so its resources are not released."),
+    ("NOT_EXPLOITABLE", "mutex-destroy-locked", "Destroy of locked mutex", "Mutex is destroyed while it is locked."),
+    ("NOT_EXPLOITABLE", "mutex-double-lock", "Double lock of mutex", "Non-recursive mutex is locked twice by the same thread, which leads to a deadlock."),
+    ("NOT_EXPLOITABLE", "mutex-invalid-access", "Use of invalid mutex", "Uninitialized or destroyed mutex is used."),
+    ("NOT_EXPLOITABLE", "mutex-bad-unlock", "Unlock of unlocked mutex", "Mutex is unlocked while it is not locked or by a wrong thread."),
+    ("NOT_EXPLOITABLE", "mutex-bad-read-lock", "Read lock of write locked mutex", "Mutex is read locked while it is write locked by the same thread."),
+    ("NOT_EXPLOITABLE", "mutex-bad-read-unlock", "Read unlock of write locked mutex", "Mutex is read unlocked while it is write locked."),
+    ("NOT_EXPLOITABLE", "mutex-held-wrong-context", "Mutex held in wrong context", "Mutex is held in the context where it must not be held."),
+    ("NOT_EXPLOITABLE", "signal-unsafe-call", "Signal-unsafe call in signal handler", "Async-signal-unsafe function (e.g., malloc) is called inside of a signal handler."),
+    ("NOT_EXPLOITABLE", "errno-in-signal-handler", "Signal handler spoils errno", "Signal handler changes errno value, which may break the interrupted code."),
+    ("NOT_EXPLOITABLE", "use-of-uninitialized-value", "Use of uninitialized value", "The target uses a value that was not initialized, which may lead to nondeterministic behavior or information disclosure."),
+    ("NOT_EXPLOITABLE", "uninit-heap-value", "Use of uninitialized heap value", "The target uses a value from heap allocation that was not initialized."),
+    ("NOT_EXPLOITABLE", "uninit-stack-value", "Use of uninitialized stack value", "The target uses a value from stack variable that was not initialized."),
+    ("PROBABLY_EXPLOITABLE", "use-after-dtor", "Use after destructor", "The target uses member fields of an object after its destructor was called."),
+    ("PROBABLY_EXPLOITABLE", "uninit-bytes-leak", "Uninitialized bytes passed to library call", "Uninitialized bytes are passed to library function or system call (e.g., write), which may disclose memory contents."),
+    ("PROBABLY_EXPLOITABLE", "kernel-infoleak", "Kernel information leak", "Uninitialized kernel memory is copied to user space, which discloses kernel memory contents."),
+    ("NOT_EXPLOITABLE", "signed-integer-overflow", "Signed integer overflow", "The result of signed integer arithmetic operation cannot be represented in its type."),
+    ("NOT_EXPLOITABLE", "shift-exponent", "Invalid shift exponent", "Shift exponent is negative or too large for the type of shifted value."),
+    ("NOT_EXPLOITABLE", "shift-base", "Invalid shift base", "Negative value is shifted left or the shift result cannot be represented in its type."),
+    ("NOT_EXPLOITABLE", "null-pointer-use", "Null pointer use", "The target loads, stores or accesses a member through null pointer."),
+    ("NOT_EXPLOITABLE", "misaligned-pointer-use", "Misaligned pointer use", "The target accesses memory through pointer that is not properly aligned for its type."),
+    ("NOT_EXPLOITABLE", "invalid-enum-load", "Invalid enum load", "The target loads a value that is not valid for enumeration type."),
+    ("NOT_EXPLOITABLE", "invalid-bool-load", "Invalid bool load", "The target loads a value that is neither true nor false for bool type."),
+    ("NOT_EXPLOITABLE", "integer-divide-by-zero", "Integer division by zero", "The target divides integer by zero."),
+    ("PROBABLY_EXPLOITABLE", "out-of-bounds-index", "Out-of-bounds array index", "Array index is out of bounds of statically sized array, which may lead to memory corruption."),
+    ("PROBABLY_EXPLOITABLE", "pointer-overflow", "Pointer overflow", "Pointer arithmetic wraps around the address space, which may lead to memory corruption."),
+    ("NOT_EXPLOITABLE", "index-out-of-bounds", "Index out of bounds", "Program panicked on out-of-bounds index or range while indexing array, slice, vector, or string."),
+    ("NOT_EXPLOITABLE", "unwrap-on-none", "Unwrap on None", "Rust program panicked on unwrapping Option that holds None value."),
+    ("NOT_EXPLOITABLE", "unwrap-on-err", "Unwrap on Err", "Rust program panicked on unwrapping Result that holds Err value."),
+    ("NOT_EXPLOITABLE", "capacity-overflow", "Capacity overflow", "Program panicked because requested collection capacity or length exceeds the maximum, which may be caused by uncontrolled allocation size."),
+    ("NOT_EXPLOITABLE", "arithmetic-overflow", "Arithmetic overflow", "Rust program panicked on integer overflow in arithmetic operation (debug assertions are enabled)."),
+    ("NOT_EXPLOITABLE", "assertion-failed", "Assertion failed", "Rust program panicked on failed assertion."),
+    ("NOT_EXPLOITABLE", "nil-pointer-dereference", "Nil pointer dereference", "Go program panicked on nil pointer dereference."),
+    ("NOT_EXPLOITABLE", "concurrent-map-write", "Concurrent map write", "Go runtime detected that map is written concurrently with another write, read, or iteration."),
+    ("NOT_EXPLOITABLE", "deadlock", "Deadlock", "All goroutines are blocked, so the program cannot make progress."),
+    ("NOT_EXPLOITABLE", "kernel-bug", "Kernel BUG", "Linux kernel assertion (BUG_ON) failed."),
+    ("NOT_EXPLOITABLE", "kernel-warning", "Kernel warning", "Linux kernel detected unexpected condition (WARN_ON)."),
+    ("PROBABLY_EXPLOITABLE", "TagCheckFailure", "Memory tag check failure", "The target accessed memory through a pointer whose tag doesn't match the memory tag (ARM MTE tag check fault). This probably indicates use-after-free or buffer overflow."),
+    ("NOT_EXPLOITABLE", "out-of-memory(malloc)", "Memory limit exceeded by single allocation", "The target requested single allocation that exceeds the malloc limit (-malloc_limit_mb, equal to -rss_limit_mb by default)."),
+    ("NOT_EXPLOITABLE", "out-of-memory(rss)", "Memory limit exceeded", "The target gradually exceeded the rss limit (-rss_limit_mb)."),
+    ("PROBABLY_EXPLOITABLE", "SourceAvTainted", "Access violation on source operand from input-controlled address", "The target crashed on an access violation at an address matching the source operand of the current instruction. The address contains bytes of the crashing input, so the attacker may control the read address (e.g., to leak memory)."),
 ];
 
 impl ExecutionClass {
@@ -145,6 +190,48 @@ impl ExecutionClass {
         )))
     }
 
+    /// Compute numeric severity score (from 0.0 to 10.0) that orders crashes
+    /// even within the same severity type. Base score is defined by severity
+    /// type and it is adjusted for program counter control, memory write,
+    /// stack or heap memory corruption, and near null access.
+    pub fn score(&self) -> f64 {
+        let class = self.short_description.as_str();
+        let mut score: f64 = match self.severity.as_str() {
+            "EXPLOITABLE" => 7.0,
+            "PROBABLY_EXPLOITABLE" => 4.0,
+            "NOT_EXPLOITABLE" => 1.0,
+            _ => 0.0,
+        };
+        // Control of program counter.
+        if ["SegFaultOnPc", "ReturnAv", "BranchAv", "CallAv"]
+            .iter()
+            .any(|pc| class.starts_with(pc))
+        {
+            score += 2.0;
+        }
+        // Memory write.
+        if class.starts_with("DestAv")
+            || class.ends_with("(write)")
+            || ["double-free", "bad-free", "param-overlap"].contains(&class)
+        {
+            score += 1.0;
+        }
+        // Access address is tainted by input.
+        if class.ends_with("Tainted") {
+            score += 0.5;
+        }
+        // Stack memory corruption may overwrite return address.
+        if class.starts_with("stack-") || class == "StackGuard" {
+            score += 0.5;
+        } else if class.starts_with("heap-") || class == "HeapError" {
+            score += 0.3;
+        }
+        if class.ends_with("NearNull") {
+            score -= 2.0;
+        }
+        (score.clamp(0.0, 10.0) * 10.0).round() / 10.0
+    }
+
     /// Return `ExecutionClass` structure by short description and access information.
     ///
     /// # Arguments
@@ -159,7 +246,7 @@ impl ExecutionClass {
             return ExecutionClass::find("param-overlap");
         }
         match short_desc {
-            "SEGV" | "BUS" => match (rw.unwrap_or("UNDEF"), near_null) {
+            "SEGV" | "BUS" | "access-violation" => match (rw.unwrap_or("UNDEF"), near_null) {
                 ("READ", false) => ExecutionClass::find("SourceAv"),
                 ("READ", true) => ExecutionClass::find("SourceAvNearNull"),
                 ("WRITE", false) => ExecutionClass::find("DestAv"),
@@ -209,11 +296,66 @@ impl Default for ExecutionClass {
     }
 }
 
-/// Check if value is near null (less than 64*1024).
+/// Default threshold for near null addresses (64 KiB).
+pub const DEFAULT_NEAR_NULL_THRESHOLD: u64 = 64 * 1024;
+
+/// Addresses that are less than this value are considered near null.
+static NEAR_NULL_THRESHOLD: AtomicU64 = AtomicU64::new(DEFAULT_NEAR_NULL_THRESHOLD);
+
+/// Set threshold for near null addresses. It is used by all severity
+/// estimators, e.g., on embedded targets null pointer dereference may access
+/// addresses beyond 64 KiB.
+///
+///  # Arguments
+///
+/// * `threshold` - addresses less than threshold are considered near null.
+pub fn set_near_null_threshold(threshold: u64) {
+    NEAR_NULL_THRESHOLD.store(threshold, Ordering::Relaxed);
+}
+
+/// Get current threshold for near null addresses.
+pub fn near_null_threshold() -> u64 {
+    NEAR_NULL_THRESHOLD.load(Ordering::Relaxed)
+}
+
+/// Check if value is near null (less than near null threshold, 64*1024 by
+/// default).
 ///
 ///  # Arguments
 ///
 /// * `value` -  address value to check.
 pub fn is_near_null(value: u64) -> bool {
-    value < 64 * 1024
+    value < near_null_threshold()
+}
+
+/// Get ARM MTE tag (bits 56-59) of address.
+///
+///  # Arguments
+///
+/// * `address` - tagged address.
+pub fn address_tag(address: u64) -> u8 {
+    ((address >> 56) & 0xf) as u8
+}
+
+/// Get execution class for ARM MTE tag check fault. Description contains
+/// fault address tag for synchronous fault, while asynchronous fault doesn't
+/// provide fault address.
+///
+///  # Arguments
+///
+/// * `si_addr` - fault address.
+///
+/// * `sync` - fault is synchronous (SEGV_MTESERR), otherwise asynchronous
+///   (SEGV_MTEAERR).
+pub fn tag_check_failure(si_addr: u64, sync: bool) -> error::Result<ExecutionClass> {
+    let mut class = ExecutionClass::find("TagCheckFailure")?;
+    class.description = if sync {
+        format!(
+            "Memory tag check failure (address tag 0x{:x})",
+            address_tag(si_addr)
+        )
+    } else {
+        "Asynchronous memory tag check failure".to_string()
+    };
+    Ok(class)
 }
@@ -24,13 +24,17 @@ use goblin::container::Endian;
 use goblin::elf::header;
 
 use crate::constants::{
-    SIGINFO_SIGABRT, SIGINFO_SIGBUS, SIGINFO_SIGFPE, SIGINFO_SIGILL, SIGINFO_SIGSEGV,
-    SIGINFO_SIGSYS, SIGINFO_SIGTRAP, SI_KERNEL,
+    SEGV_MTEAERR, SEGV_MTESERR, SIGINFO_SIGABRT, SIGINFO_SIGBUS, SIGINFO_SIGFPE, SIGINFO_SIGILL,
+    SIGINFO_SIGSEGV, SIGINFO_SIGSYS, SIGINFO_SIGTRAP, SI_KERNEL,
 };
 use crate::error::*;
-use crate::execution_class::{is_near_null, ExecutionClass};
+use crate::execution_class::{is_near_null, tag_check_failure, ExecutionClass};
 use crate::severity::Severity;
 
+/// Maximum distance between faulting address and register with input bytes,
+/// so that register is considered to be base of memory operand.
+const INPUT_ADDRESS_DISTANCE: u64 = 0x1000;
+
 #[derive(Clone, Default)]
 /// Information about machine.
 pub struct MachineInfo {
@@ -87,6 +91,16 @@ impl Severity for GdbContext {
             SIGINFO_SIGTRAP => ExecutionClass::find("TrapSignal"),
             SIGINFO_SIGILL | SIGINFO_SIGSYS => ExecutionClass::find("BadInstruction"),
             SIGINFO_SIGSEGV | SIGINFO_SIGFPE | SIGINFO_SIGBUS => {
+                // Check for ARM MTE tag check fault.
+                if self.siginfo.si_signo == SIGINFO_SIGSEGV
+                    && matches!(self.siginfo.si_code, SEGV_MTEAERR | SEGV_MTESERR)
+                {
+                    return tag_check_failure(
+                        self.siginfo.si_addr,
+                        self.siginfo.si_code == SEGV_MTESERR,
+                    );
+                }
+
                 // Get program counter.
                 let pc = self.pc();
 
@@ -101,6 +115,11 @@ impl Severity for GdbContext {
                     || self.siginfo.si_signo == SIGINFO_SIGBUS)
                     && *pc == self.siginfo.si_addr
                 {
+                    if matches!(self.machine.arch, header::EM_AARCH64 | header::EM_RISCV) {
+                        if let Some(class) = self.analyze_branch_target() {
+                            return class;
+                        }
+                    }
                     if is_near_null(self.siginfo.si_addr) {
                         return ExecutionClass::find("SegFaultOnPcNearNull");
                     } else {
@@ -171,7 +190,13 @@ impl Severity for GdbContext {
                                 ))
                             }
                         };
-                        Capstone::new().riscv().mode(mode).detail(true).build()
+                        // RV64GC code contains compressed instructions.
+                        Capstone::new()
+                            .riscv()
+                            .mode(mode)
+                            .extra_mode([arch::riscv::ArchExtraMode::RiscVC].iter().copied())
+                            .detail(true)
+                            .build()
                     }
                     _ => {
                         return Err(Error::Casr(format!(
@@ -233,6 +258,94 @@ impl GdbContext {
         }
     }
 
+    /// Get registers that contain bytes of crashing input.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - crashing input.
+    ///
+    /// # Return value
+    ///
+    /// Register names with values sorted by name.
+    pub fn input_registers(&self, input: &[u8]) -> Vec<(String, u64)> {
+        let mut registers: Vec<(String, u64)> = self
+            .registers
+            .iter()
+            .filter(|(name, _)| !["pc", "rip", "eip", "sp", "rsp", "esp"].contains(&name.as_str()))
+            .filter(|(_, value)| self.is_input_value(**value, input))
+            .map(|(name, value)| (name.clone(), *value))
+            .collect();
+        registers.sort();
+        registers
+    }
+
+    /// Check whether faulting address is controlled by crashing input: the
+    /// address itself or a register near it (base register of memory operand)
+    /// contains bytes of input.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - crashing input.
+    pub fn is_input_address(&self, input: &[u8]) -> bool {
+        let address = self.siginfo.si_addr;
+        self.is_input_value(address, input)
+            || self
+                .input_registers(input)
+                .iter()
+                .any(|(_, value)| value.abs_diff(address) < INPUT_ADDRESS_DISTANCE)
+    }
+
+    /// Upgrade execution class when faulting address is controlled by
+    /// crashing input, e.g., `SourceAv` becomes `SourceAvTainted`, and `DestAv`
+    /// becomes `DestAvTainted`.
+    ///
+    /// # Arguments
+    ///
+    /// * `class` - execution class estimated from crash state.
+    ///
+    /// * `input` - crashing input.
+    pub fn refine_with_input(&self, class: ExecutionClass, input: &[u8]) -> Result<ExecutionClass> {
+        let tainted = match class.short_description.as_str() {
+            "SourceAv" => "SourceAvTainted",
+            "DestAv" => "DestAvTainted",
+            "BranchAv" => "BranchAvTainted",
+            "CallAv" => "CallAvTainted",
+            _ => return Ok(class),
+        };
+        if self.is_input_address(input) {
+            ExecutionClass::find(tainted)
+        } else {
+            Ok(class)
+        }
+    }
+
+    /// Check whether value bytes (without leading zero bytes) are found in
+    /// crashing input. Near null values and values shorter than 4 bytes are
+    /// skipped, because they match input by accident.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - register value or address.
+    ///
+    /// * `input` - crashing input.
+    fn is_input_value(&self, value: u64, input: &[u8]) -> bool {
+        if is_near_null(value) {
+            return false;
+        }
+        let width = (self.machine.byte_width as usize).clamp(1, 8);
+        let significant = 8 - (value.leading_zeros() / 8) as usize;
+        let len = significant.min(width);
+        if len < 4 {
+            return false;
+        }
+        let pattern = if self.machine.endianness == Endian::Little {
+            value.to_le_bytes()[..len].to_vec()
+        } else {
+            value.to_be_bytes()[8 - len..].to_vec()
+        };
+        input.windows(len).any(|window| window == pattern)
+    }
+
     /// Analyze crash instruction and return ExecutionClass or error.
     ///
     /// # Arguments
@@ -252,8 +365,8 @@ impl GdbContext {
                 Self::analyze_instructions_x86(cs, insns, context)
             }
             header::EM_ARM => Self::analyze_instructions_arm(cs, insns, &context.siginfo),
-            header::EM_AARCH64 => Self::analyze_instructions_arm64(cs, insns, &context.siginfo),
-            header::EM_RISCV => Self::analyze_instructions_riscv(cs, insns, &context.siginfo),
+            header::EM_AARCH64 => Self::analyze_instructions_arm64(cs, insns, context),
+            header::EM_RISCV => Self::analyze_instructions_riscv(cs, insns, context),
             _ => Err(Error::Casr(format!(
                 "Unsupported machine arch: {}",
                 context.machine.arch
@@ -261,6 +374,59 @@ impl GdbContext {
         }
     }
 
+    /// Analyze aarch64/riscv branch target when program counter is invalid.
+    /// BR/BLR/RET (JALR/RET) instructions don't fault themselves, the fault
+    /// occurs on the target address. So, branch type is determined by registers
+    /// that hold the target: link register for return and general-purpose
+    /// registers for indirect branch. Near null targets are not analyzed
+    /// because registers often contain small values by accident.
+    ///
+    /// # Return value
+    ///
+    /// ExecutionClass if branch type is determined.
+    fn analyze_branch_target(&self) -> Option<Result<ExecutionClass>> {
+        let target = self.siginfo.si_addr;
+        if is_near_null(target) {
+            return None;
+        }
+        let link = match self.machine.arch {
+            header::EM_AARCH64 => ["x30", "lr"],
+            header::EM_RISCV => ["ra", "x1"],
+            _ => return None,
+        };
+        if link
+            .iter()
+            .any(|lr| self.registers.get(*lr) == Some(&target))
+        {
+            return Some(ExecutionClass::find("ReturnAv"));
+        }
+        if self
+            .registers
+            .iter()
+            .any(|(name, value)| *value == target && !["pc", "sp"].contains(&name.as_str()))
+        {
+            return Some(ExecutionClass::find("BranchAv"));
+        }
+        None
+    }
+
+    /// Check that memory access relative to stack pointer faults near it,
+    /// e.g., "stp x29, x30, [sp, #-0x30]!" or "sd ra, 24(sp)" in function
+    /// prologue.
+    ///
+    /// # Arguments
+    ///
+    /// * `cs` - reference to capstone.
+    ///
+    /// * `base` - base register of memory operand.
+    fn is_stack_overflow(&self, cs: &Capstone, base: RegId) -> bool {
+        if cs.reg_name(base).as_deref() != Some("sp") {
+            return false;
+        }
+        self.sp()
+            .is_some_and(|sp| sp.abs_diff(self.siginfo.si_addr) < 0x10000)
+    }
+
     /// Analyze x86 crash instruction.
     ///
     /// # Arguments
@@ -441,12 +607,13 @@ impl GdbContext {
     ///
     /// * `insns` - reference to disassembled instructions.
     ///
-    /// * `info` - reference to signal information structure.
+    /// * `context` - crash context.
     fn analyze_instructions_arm64(
         cs: &Capstone,
         insns: &Instructions,
-        info: &Siginfo,
+        context: &GdbContext,
     ) -> Result<ExecutionClass> {
+        let info = &context.siginfo;
         // Get first instruction.
         let Some(insn) = insns.iter().next() else {
             return Err(Error::Casr(
@@ -467,7 +634,11 @@ impl GdbContext {
                 return Err(Error::Casr("Couldn't get instruction operands".to_string()));
             };
             // Check mem operand.
-            if let capstone::arch::arm64::Arm64OperandType::Mem(_) = operand.op_type {
+            if let capstone::arch::arm64::Arm64OperandType::Mem(mem) = operand.op_type {
+                // Check for exceeded stack.
+                if context.is_stack_overflow(cs, mem.base()) {
+                    return ExecutionClass::find("StackOverflow");
+                }
                 return classify_memory_operation(
                     cs,
                     insns,
@@ -489,12 +660,13 @@ impl GdbContext {
     ///
     /// * `insns` - reference to disassembled instructions.
     ///
-    /// * `info` - reference to signal information structure.
+    /// * `context` - crash context.
     fn analyze_instructions_riscv(
         cs: &Capstone,
         insns: &Instructions,
-        info: &Siginfo,
+        context: &GdbContext,
     ) -> Result<ExecutionClass> {
+        let info = &context.siginfo;
         // Get first instruction.
         let Some(insn) = insns.iter().next() else {
             return Err(Error::Casr(
@@ -507,6 +679,12 @@ impl GdbContext {
         };
 
         let id = insn.id();
+        // Compressed and floating-point loads/stores, e.g., "c.ld", "c.sdsp", "fsd".
+        let m = insn
+            .mnemonic()
+            .unwrap_or_default()
+            .trim_start_matches("c.")
+            .trim_start_matches('f');
 
         let ops = detail.arch_detail().operands();
         for op in ops.iter() {
@@ -515,13 +693,17 @@ impl GdbContext {
                 return Err(Error::Casr("Couldn't get instruction operands".to_string()));
             };
             // Check mem operand.
-            if let capstone::arch::riscv::RiscVOperand::Mem(_) = operand {
+            if let capstone::arch::riscv::RiscVOperand::Mem(mem) = operand {
+                // Check for exceeded stack.
+                if context.is_stack_overflow(cs, mem.base()) {
+                    return ExecutionClass::find("StackOverflow");
+                }
                 return classify_memory_operation(
                     cs,
                     insns,
                     info.si_code,
-                    is_store_riscv(id),
-                    is_load_riscv(id),
+                    is_store_riscv(id) || m.starts_with('s'),
+                    is_load_riscv(id) || m.starts_with('l'),
                     is_near_null(info.si_addr),
                 );
             }
@@ -1238,6 +1420,10 @@ fn is_load_arm64(id: InsnId) -> bool {
             | ARM64_INS_LDURSH
             | ARM64_INS_LDURSW
             | ARM64_INS_LDP
+            | ARM64_INS_LDNP
+            | ARM64_INS_LDAR
+            | ARM64_INS_LDAXR
+            | ARM64_INS_LDXR
     )
 }
 
@@ -1259,6 +1445,10 @@ fn is_store_arm64(id: InsnId) -> bool {
             | ARM64_INS_STUR
             | ARM64_INS_STURB
             | ARM64_INS_STURH
+            | ARM64_INS_STNP
+            | ARM64_INS_STLR
+            | ARM64_INS_STLXR
+            | ARM64_INS_STXR
     )
 }
 
@@ -1438,6 +1628,14 @@ const ARM64_INS_LDURSH: InsnId = InsnId(Arm64Insn::ARM64_INS_LDURSH as u32);
 const ARM64_INS_LDURSW: InsnId = InsnId(Arm64Insn::ARM64_INS_LDURSW as u32);
 
 const ARM64_INS_LDP: InsnId = InsnId(Arm64Insn::ARM64_INS_LDP as u32);
+const ARM64_INS_LDNP: InsnId = InsnId(Arm64Insn::ARM64_INS_LDNP as u32);
+const ARM64_INS_LDAR: InsnId = InsnId(Arm64Insn::ARM64_INS_LDAR as u32);
+const ARM64_INS_LDAXR: InsnId = InsnId(Arm64Insn::ARM64_INS_LDAXR as u32);
+const ARM64_INS_LDXR: InsnId = InsnId(Arm64Insn::ARM64_INS_LDXR as u32);
+const ARM64_INS_STNP: InsnId = InsnId(Arm64Insn::ARM64_INS_STNP as u32);
+const ARM64_INS_STLR: InsnId = InsnId(Arm64Insn::ARM64_INS_STLR as u32);
+const ARM64_INS_STLXR: InsnId = InsnId(Arm64Insn::ARM64_INS_STLXR as u32);
+const ARM64_INS_STXR: InsnId = InsnId(Arm64Insn::ARM64_INS_STXR as u32);
 const ARM64_INS_MOV: InsnId = InsnId(Arm64Insn::ARM64_INS_MOV as u32);
 const ARM64_INS_ADR: InsnId = InsnId(Arm64Insn::ARM64_INS_ADR as u32);
 
@@ -1840,6 +2038,84 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_stack_overflow_arm64() {
+        let sig = Siginfo {
+            si_signo: SIGINFO_SIGSEGV,
+            si_code: 2,
+            si_errno: 0,
+            si_addr: 0x7fffefd0,
+        };
+        let machine = MachineInfo {
+            byte_width: 8,
+            endianness: Endian::Little,
+            arch: header::EM_AARCH64,
+        };
+        let mut registers = Registers::new();
+        registers.insert("sp".to_string(), 0x7ffff000);
+        registers.insert("pc".to_string(), 0x400000);
+        let context = GdbContext {
+            siginfo: sig,
+            registers,
+            mappings: MappedFiles::new(),
+            pc_memory: MemoryObject {
+                address: 0x400000,
+                // rz-asm -a arm -b 64 'stp x29, x30, [sp, -0x30]!'
+                data: vec![0xfd, 0x7b, 0xbd, 0xa9],
+            },
+            machine,
+            stacktrace: Vec::new(),
+        };
+        let Ok(class) = context.severity() else {
+            panic!("Couldn't estimate severity");
+        };
+        assert_eq!(class.short_description, "StackOverflow");
+    }
+
+    #[test]
+    fn test_branch_target() {
+        let context = |register: &str| {
+            let mut registers = Registers::new();
+            registers.insert("pc".to_string(), 0x4141414141414141);
+            registers.insert("x30".to_string(), 0x400100);
+            registers.insert(register.to_string(), 0x4141414141414141);
+            GdbContext {
+                siginfo: Siginfo {
+                    si_signo: SIGINFO_SIGSEGV,
+                    si_code: 1,
+                    si_errno: 0,
+                    si_addr: 0x4141414141414141,
+                },
+                registers,
+                machine: MachineInfo {
+                    byte_width: 8,
+                    endianness: Endian::Little,
+                    arch: header::EM_AARCH64,
+                },
+                ..Default::default()
+            }
+        };
+        let Ok(class) = context("x30").severity() else {
+            panic!("Couldn't estimate severity");
+        };
+        assert_eq!(class.short_description, "ReturnAv");
+        let Ok(class) = context("x8").severity() else {
+            panic!("Couldn't estimate severity");
+        };
+        assert_eq!(class.short_description, "BranchAv");
+        let Ok(class) = context("sp").severity() else {
+            panic!("Couldn't estimate severity");
+        };
+        assert_eq!(class.short_description, "SegFaultOnPc");
+
+        let mut context = context("ra");
+        context.machine.arch = header::EM_RISCV;
+        let Ok(class) = context.severity() else {
+            panic!("Couldn't estimate severity");
+        };
+        assert_eq!(class.short_description, "ReturnAv");
+    }
+
     #[test]
     fn test_ldr_arm64() {
         // rz-asm -a arm -b 64 'ldr     x8, [x0]; ldr     x8, [x8]; ret'
@@ -2016,6 +2292,46 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_riscv_compressed() {
+        let context = |data: Vec<u8>, si_addr: u64| {
+            let mut registers = Registers::new();
+            registers.insert("sp".to_string(), 0x3ffffff000);
+            registers.insert("pc".to_string(), 0x10000);
+            GdbContext {
+                siginfo: Siginfo {
+                    si_signo: SIGINFO_SIGSEGV,
+                    si_code: 1,
+                    si_errno: 0,
+                    si_addr,
+                },
+                registers,
+                mappings: MappedFiles::new(),
+                pc_memory: MemoryObject {
+                    address: 0x10000,
+                    data,
+                },
+                machine: MachineInfo {
+                    byte_width: 8,
+                    endianness: Endian::Little,
+                    arch: header::EM_RISCV,
+                },
+                stacktrace: Vec::new(),
+            }
+        };
+        // 'c.ld a0, 0(a0)'
+        let Ok(class) = context(vec![0x08, 0x61], 0xdeadbeef).severity() else {
+            panic!("Couldn't estimate severity");
+        };
+        assert_eq!(class.short_description, "SourceAv");
+
+        // 'sd ra, 24(sp)'
+        let Ok(class) = context(vec![0x23, 0x3c, 0x11, 0x00], 0x3ffffff018).severity() else {
+            panic!("Couldn't estimate severity");
+        };
+        assert_eq!(class.short_description, "StackOverflow");
+    }
+
     #[test]
     fn test_call_av_riscv() {
         let machine = MachineInfo {
@@ -2053,4 +2369,95 @@ mod tests {
             unreachable!();
         }
     }
+
+    #[test]
+    fn test_refine_with_input() {
+        let mut registers = Registers::new();
+        registers.insert("rax".to_string(), 0x4141414142424242);
+        registers.insert("rbx".to_string(), 0x10);
+        registers.insert("rcx".to_string(), 0x7fffffffe000);
+        registers.insert("rip".to_string(), 0x401000);
+        let context = GdbContext {
+            siginfo: Siginfo {
+                si_signo: SIGINFO_SIGSEGV,
+                si_code: 1,
+                si_errno: 0,
+                si_addr: 0x4141414142424252,
+            },
+            registers,
+            mappings: MappedFiles::new(),
+            pc_memory: MemoryObject {
+                address: 0x401000,
+                data: Vec::new(),
+            },
+            machine: MachineInfo {
+                byte_width: 8,
+                endianness: Endian::Little,
+                arch: header::EM_X86_64,
+            },
+            stacktrace: Vec::new(),
+        };
+        let input = b"header BBBBAAAA \x10\x00\x00\x00";
+        assert_eq!(
+            context.input_registers(input),
+            vec![("rax".to_string(), 0x4141414142424242)]
+        );
+        // Base register of memory operand contains input bytes.
+        assert!(context.is_input_address(input));
+        assert!(!context.is_input_address(b"no input bytes"));
+
+        let source_av = ExecutionClass::find("SourceAv").unwrap();
+        let Ok(class) = context.refine_with_input(source_av.clone(), input) else {
+            unreachable!();
+        };
+        assert_eq!(class.short_description, "SourceAvTainted");
+        let Ok(class) = context.refine_with_input(source_av, b"AAAA") else {
+            unreachable!();
+        };
+        assert_eq!(class.short_description, "SourceAv");
+        let Ok(class) = context.refine_with_input(ExecutionClass::find("DestAv").unwrap(), input)
+        else {
+            unreachable!();
+        };
+        assert_eq!(class.short_description, "DestAvTainted");
+        let near_null = ExecutionClass::find("DestAvNearNull").unwrap();
+        let Ok(class) = context.refine_with_input(near_null.clone(), input) else {
+            unreachable!();
+        };
+        assert_eq!(class, near_null);
+    }
+
+    #[test]
+    fn test_mte_tag_check_fault() {
+        let mut context = GdbContext {
+            siginfo: Siginfo {
+                si_signo: SIGINFO_SIGSEGV,
+                si_code: SEGV_MTESERR,
+                si_errno: 0,
+                si_addr: 0x0b00_ffff_8a40_0010,
+            },
+            machine: MachineInfo {
+                byte_width: 8,
+                endianness: Endian::Little,
+                arch: header::EM_AARCH64,
+            },
+            ..Default::default()
+        };
+        let Ok(class) = context.severity() else {
+            unreachable!();
+        };
+        assert_eq!(class.short_description, "TagCheckFailure");
+        assert_eq!(
+            class.description,
+            "Memory tag check failure (address tag 0xb)"
+        );
+
+        context.siginfo.si_code = SEGV_MTEAERR;
+        context.siginfo.si_addr = 0;
+        let Ok(class) = context.severity() else {
+            unreachable!();
+        };
+        assert_eq!(class.short_description, "TagCheckFailure");
+        assert_eq!(class.description, "Asynchronous memory tag check failure");
+    }
 }
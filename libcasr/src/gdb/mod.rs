@@ -5,9 +5,75 @@ use regex::Regex;
 use super::error::*;
 use super::stacktrace::*;
 
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
 #[cfg(feature = "exploitable")]
 pub mod exploitable;
 
+/// Stack trace of one thread from gdb "thread apply all bt" output.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ThreadStacktrace {
+    /// Thread description, e.g. "Thread 2 (Thread 0x7ffff7a4d640 (LWP 4242))".
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename(serialize = "Description", deserialize = "Description"))
+    )]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub description: String,
+    /// Thread received the signal that crashed the program.
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename(serialize = "Crashed", deserialize = "Crashed"))
+    )]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub crashed: bool,
+    /// Thread stack trace.
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename(serialize = "Stacktrace", deserialize = "Stacktrace"))
+    )]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub stacktrace: Vec<String>,
+}
+
+/// Extract stack traces of all threads from gdb "thread apply all bt" output
+///
+/// # Arguments
+///
+/// * `stream` - gdb output
+///
+/// * `crashed` - stack trace of crashed thread (gdb "bt" output)
+///
+/// # Return value
+///
+/// Thread stack traces in gdb order. Thread with the same stack trace as
+/// `crashed` is marked as crashed one.
+pub fn extract_threads(stream: &str, crashed: &[String]) -> Vec<ThreadStacktrace> {
+    let header = Regex::new(r"^Thread [0-9]+ \(.*\):$").unwrap();
+    let frame = Regex::new(r"^ *#[0-9]+").unwrap();
+    let mut threads: Vec<ThreadStacktrace> = Vec::new();
+    for line in stream.lines().map(|l| l.trim_end()) {
+        if header.is_match(line) {
+            threads.push(ThreadStacktrace {
+                description: line.trim_end_matches(':').to_string(),
+                ..Default::default()
+            });
+        } else if let Some(thread) = threads.last_mut().filter(|_| frame.is_match(line)) {
+            thread.stacktrace.push(line.to_string());
+        }
+    }
+    let crashed: Vec<&str> = crashed.iter().map(|l| l.trim_end()).collect();
+    if let Some(thread) = threads
+        .iter_mut()
+        .find(|thread| thread.stacktrace == crashed)
+    {
+        thread.crashed = true;
+    }
+    threads
+}
+
 /// Structure provides an interface for processing the stack trace.
 pub struct GdbStacktrace;
 
@@ -29,3 +95,41 @@ impl ParseStacktrace for GdbStacktrace {
         Ok(Stacktrace::from_gdb(entries.join("\n"))?)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_threads() {
+        let output = "
+Thread 2 (Thread 0x7ffff7a4d640 (LWP 4243) \"worker\"):
+#0  0x00007ffff7c91117 in __futex_abstimed_wait_common () from /lib/x86_64-linux-gnu/libc.so.6
+#1  0x00007ffff7c93a41 in pthread_cond_wait () from /lib/x86_64-linux-gnu/libc.so.6
+#2  0x0000555555555209 in worker (arg=0x0) at test_threads.c:12
+
+Thread 1 (Thread 0x7ffff7a4e740 (LWP 4242)):
+#0  0x00005555555551c9 in crash (p=0x0) at test_threads.c:6
+#1  0x0000555555555262 in main () at test_threads.c:23
+";
+        let crashed = vec![
+            "#0  0x00005555555551c9 in crash (p=0x0) at test_threads.c:6".to_string(),
+            "#1  0x0000555555555262 in main () at test_threads.c:23".to_string(),
+        ];
+        let threads = extract_threads(output, &crashed);
+        assert_eq!(threads.len(), 2);
+        assert_eq!(
+            threads[0].description,
+            "Thread 2 (Thread 0x7ffff7a4d640 (LWP 4243) \"worker\")"
+        );
+        assert_eq!(threads[0].stacktrace.len(), 3);
+        assert!(!threads[0].crashed);
+        assert_eq!(threads[1].stacktrace, crashed);
+        assert!(threads[1].crashed);
+
+        let Ok(stacktrace) = GdbStacktrace::parse_stacktrace(&threads[0].stacktrace) else {
+            panic!("Couldn't parse thread stack trace");
+        };
+        assert_eq!(stacktrace[2].function, "worker");
+    }
+}
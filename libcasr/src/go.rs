@@ -17,11 +17,20 @@ impl ParseStacktrace for GoStacktrace {
             .map(|l| l.to_string())
             .collect::<Vec<String>>();
 
-        let re = Regex::new(r"goroutine [0-9]+ (?:\[running.*\]|\[syscall\]):").unwrap();
+        // Failing goroutine is running, e.g. "goroutine 1 [running]:". If there is no
+        // running goroutine (e.g., on deadlock), the first one is used.
+        let re = Regex::new(r"goroutine [0-9]+ (?:.*? )?\[(?:running|syscall)[^\]]*\]:").unwrap();
+        let rany = Regex::new(r"goroutine [0-9]+ (?:.*? )?\[[^\]]+\]:").unwrap();
         let Some(goroutine_idx) = lines
             .iter()
             .enumerate()
             .position(|(i, line)| re.is_match(line) && i < lines.len() - 1)
+            .or_else(|| {
+                lines
+                    .iter()
+                    .enumerate()
+                    .position(|(i, line)| rany.is_match(line) && i < lines.len() - 1)
+            })
         else {
             return Err(Error::Casr(
                 "Couldn't find start of stacktrace in Go panic output".to_string(),
@@ -34,6 +43,13 @@ impl ParseStacktrace for GoStacktrace {
         } else {
             lines
         };
+        // Skip "...additional frames elided..." and similar lines.
+        let lines: Vec<String> = lines
+            .iter()
+            .filter(|l| !l.trim_start().starts_with("..."))
+            .cloned()
+            .collect();
+        let lines = &lines[..];
 
         let re = Regex::new(r"==\d+== ERROR: ").unwrap();
         let lines = if let Some(libfuzzer_idx) = lines.iter().position(|line| re.is_match(line)) {
@@ -93,14 +109,46 @@ impl Exception for GoPanic {
             .split('\n')
             .map(|l| l.trim_end().to_string())
             .collect();
-        stderr_list.iter().find_map(|x| re.captures(x)).map(|cap| {
-            ExecutionClass::new((
-                "NOT_EXPLOITABLE",
-                "GoPanic",
-                cap.get(2).unwrap().as_str(),
-                "",
-            ))
-        })
+        let message = stderr_list
+            .iter()
+            .find_map(|x| re.captures(x))?
+            .get(2)
+            .unwrap()
+            .as_str();
+        // Classify common runtime errors by their messages.
+        let kinds = [
+            (
+                r"invalid memory address or nil pointer dereference",
+                "nil-pointer-dereference",
+            ),
+            (
+                r"index out of range|slice bounds out of range",
+                "index-out-of-bounds",
+            ),
+            (
+                r"^concurrent map (writes|read and map write|iteration and map write)",
+                "concurrent-map-write",
+            ),
+            (r"out of memory", "out-of-memory"),
+            (r"integer divide by zero", "integer-divide-by-zero"),
+            (r"^all goroutines are asleep - deadlock", "deadlock"),
+            (r"^stack overflow", "StackOverflow"),
+            (r"makeslice: (len|cap) out of range", "capacity-overflow"),
+        ];
+        if let Some(mut class) = kinds
+            .iter()
+            .find(|(re, _)| Regex::new(re).unwrap().is_match(message))
+            .and_then(|(_, kind)| ExecutionClass::find(kind).ok())
+        {
+            class.description = message.to_string();
+            return Some(class);
+        }
+        Some(ExecutionClass::new((
+            "NOT_EXPLOITABLE",
+            "GoPanic",
+            message,
+            "",
+        )))
     }
 }
 
@@ -139,6 +187,90 @@ mod tests {
         };
 
         assert_eq!(class.description, "index out of range [0] with length 0");
+        assert_eq!(class.short_description, "index-out-of-bounds");
+
+        let panics = [
+            ("fatal error: runtime: out of memory", "out-of-memory"),
+            ("panic: runtime error: last name cannot be nil", "GoPanic"),
+            (
+                "panic: runtime error: slice bounds out of range [:5] with capacity 3",
+                "index-out-of-bounds",
+            ),
+            ("fatal error: concurrent map writes", "concurrent-map-write"),
+            (
+                "fatal error: concurrent map read and map write",
+                "concurrent-map-write",
+            ),
+            (
+                "panic: runtime error: integer divide by zero",
+                "integer-divide-by-zero",
+            ),
+            (
+                "fatal error: all goroutines are asleep - deadlock!",
+                "deadlock",
+            ),
+            (
+                "panic: runtime error: makeslice: len out of range",
+                "capacity-overflow",
+            ),
+        ];
+        for (panic_info, kind) in panics {
+            let Some(class) = GoPanic::parse_exception(panic_info) else {
+                panic!("Couldn't get Go panic: {panic_info}");
+            };
+            assert_eq!(class.short_description, kind);
+        }
+    }
+
+    #[test]
+    fn test_go_nil_dereference() {
+        let output = "panic: runtime error: invalid memory address or nil pointer dereference
+[signal SIGSEGV: segmentation violation code=0x1 addr=0x0 pc=0x48f0b6]
+
+goroutine 1 [running]:
+main.parse(...)
+\t/home/user/fuzz/parse.go:12
+main.Fuzz({0x7f1b2c3d4000, 0x5, 0x5})
+\t/home/user/fuzz/fuzz.go:7 +0x16
+...additional frames elided...
+
+goroutine 6 [chan receive]:
+main.worker()
+\t/home/user/fuzz/worker.go:20 +0x25
+";
+        let Some(class) = GoPanic::parse_exception(output) else {
+            panic!("Couldn't get Go panic");
+        };
+        assert_eq!(class.short_description, "nil-pointer-dereference");
+        assert_eq!(
+            class.description,
+            "runtime error: invalid memory address or nil pointer dereference"
+        );
+
+        let raw_stacktrace = GoStacktrace::extract_stacktrace(output);
+        let Ok(raw_stacktrace) = raw_stacktrace else {
+            panic!("{}", raw_stacktrace.err().unwrap());
+        };
+        assert_eq!(raw_stacktrace.len(), 2);
+        let stacktrace = GoStacktrace::parse_stacktrace(&raw_stacktrace);
+        let Ok(stacktrace) = stacktrace else {
+            panic!("{}", stacktrace.err().unwrap());
+        };
+        assert_eq!(stacktrace[0].function, "main.parse(...)");
+        assert_eq!(stacktrace[0].debug.file, "/home/user/fuzz/parse.go");
+        assert_eq!(stacktrace[0].debug.line, 12);
+
+        let output = "fatal error: all goroutines are asleep - deadlock!
+
+goroutine 1 [chan receive]:
+main.main()
+\t/home/user/deadlock/main.go:5 +0x28
+";
+        let raw_stacktrace = GoStacktrace::extract_stacktrace(output);
+        let Ok(raw_stacktrace) = raw_stacktrace else {
+            panic!("{}", raw_stacktrace.err().unwrap());
+        };
+        assert_eq!(raw_stacktrace.len(), 1);
     }
 
     #[test]
@@ -0,0 +1,192 @@
+//! GWP-ASan module implements `Severity` trait and stack traces extraction for
+//! GWP-ASan reports (sampling heap memory error detector, e.g., in Scudo allocator).
+use regex::Regex;
+
+use crate::error::*;
+use crate::execution_class::ExecutionClass;
+use crate::severity::Severity;
+
+/// Extract GWP-ASan report from program output
+///
+/// # Arguments
+///
+/// * `stream` - program output lines
+///
+/// # Return value
+///
+/// Report lines from "GWP-ASan detected a memory error" to "End GWP-ASan report" line
+pub fn extract_gwp_asan_report(stream: &[String]) -> Option<Vec<String>> {
+    let start = stream
+        .iter()
+        .position(|line| line.contains("*** GWP-ASan detected a memory error ***"))?;
+    let end = stream[start..]
+        .iter()
+        .position(|line| line.contains("*** End GWP-ASan report ***"))
+        .map(|pos| start + pos + 1)
+        .unwrap_or(stream.len());
+    Some(
+        stream[start..end]
+            .iter()
+            .map(|line| line.trim_end().to_string())
+            .collect(),
+    )
+}
+
+/// Information about GWP-ASan report.
+pub struct GwpAsanContext(pub Vec<String>);
+
+impl GwpAsanContext {
+    /// Get stack trace of invalid memory access (or invalid deallocation)
+    pub fn access_stacktrace(&self) -> Vec<String> {
+        self.stacktrace(r" at 0x[0-9a-f]+ .*by thread \d+ here:")
+    }
+
+    /// Get stack trace where memory was deallocated
+    pub fn deallocation_stacktrace(&self) -> Vec<String> {
+        self.stacktrace(r"^0x[0-9a-f]+ was deallocated by thread \d+ here:")
+    }
+
+    /// Get stack trace where memory was allocated
+    pub fn allocation_stacktrace(&self) -> Vec<String> {
+        self.stacktrace(r"^0x[0-9a-f]+ was allocated by thread \d+ here:")
+    }
+
+    /// Get stack trace that follows header line. Frames printed via
+    /// backtrace_symbols(3) are converted to sanitizer format, e.g.,
+    /// "#0 ./test(+0x1149) [0x555555555149]" -> "#0 0x555555555149  (./test+0x1149)".
+    ///
+    /// # Arguments
+    ///
+    /// * `header` - regular expression for header line
+    fn stacktrace(&self, header: &str) -> Vec<String> {
+        let header = Regex::new(header).unwrap();
+        let rframe =
+            Regex::new(r"^( *#[0-9]+) +(.*)\((?:(.*?)\+0x([0-9a-f]+))?\) +\[0x([0-9a-f]+)\]$")
+                .unwrap();
+        let Some(start) = self.0.iter().position(|line| header.is_match(line)) else {
+            return Vec::new();
+        };
+        self.0[start + 1..]
+            .iter()
+            .take_while(|line| line.trim_start().starts_with('#'))
+            .map(|line| {
+                let Some(caps) = rframe.captures(line) else {
+                    return line.to_string();
+                };
+                let frame = caps.get(1).unwrap().as_str();
+                let module = caps.get(2).unwrap().as_str().trim();
+                let function = caps.get(3).map(|f| f.as_str()).unwrap_or_default();
+                let address = caps.get(5).unwrap().as_str();
+                match caps.get(4) {
+                    Some(_) if !function.is_empty() => format!("{frame} 0x{address} in {function}"),
+                    Some(offset) => {
+                        format!("{frame} 0x{address}  ({module}+0x{})", offset.as_str())
+                    }
+                    None => format!("{frame} 0x{address}"),
+                }
+            })
+            .collect()
+    }
+}
+
+impl Severity for GwpAsanContext {
+    fn severity(&self) -> Result<ExecutionClass> {
+        let rerror = Regex::new(r"^(.+?) at 0x[0-9a-f]+ .*by thread \d+ here:").unwrap();
+        let Some(caps) = self.0.iter().find_map(|line| rerror.captures(line)) else {
+            return Err(Error::Casr(
+                "Cannot find error description in GWP-ASan report".to_string(),
+            ));
+        };
+        let error = caps.get(1).unwrap().as_str().to_lowercase();
+        match error.as_str() {
+            "use after free" => ExecutionClass::find("heap-use-after-free"),
+            "buffer overflow" | "buffer underflow" => ExecutionClass::find("heap-buffer-overflow"),
+            "double free" => ExecutionClass::find("double-free"),
+            "invalid (wild) free" => ExecutionClass::find("bad-free"),
+            _ => ExecutionClass::find("AccessViolation"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::asan::AsanStacktrace;
+    use crate::stacktrace::ParseStacktrace;
+
+    #[test]
+    fn test_gwp_asan_report() {
+        let output: Vec<String> = [
+            "Reading input",
+            "",
+            "*** GWP-ASan detected a memory error ***",
+            "Use After Free at 0x7f8a3c4d6ff0 (0 bytes into a 16-byte allocation at 0x7f8a3c4d6ff0) by thread 31027 here:",
+            "  #0 ./test_gwp_asan(+0x1169) [0x558c5a6f4169]",
+            "  #1 /lib/x86_64-linux-gnu/libc.so.6(__libc_start_main+0xf3) [0x7f8a3c8c9083]",
+            "  #2 ./test_gwp_asan(+0x108e) [0x558c5a6f408e]",
+            "0x7f8a3c4d6ff0 was deallocated by thread 31027 here:",
+            "  #0 ./test_gwp_asan(+0x115d) [0x558c5a6f415d]",
+            "0x7f8a3c4d6ff0 was allocated by thread 31027 here:",
+            "  #0 ./test_gwp_asan(+0x1149) [0x558c5a6f4149]",
+            "  #1 ./test_gwp_asan() [0x558c5a6f4150]",
+            "*** End GWP-ASan report ***",
+            "Segmentation fault",
+        ]
+        .iter()
+        .map(|l| l.to_string())
+        .collect();
+
+        let Some(report) = extract_gwp_asan_report(&output) else {
+            panic!("Couldn't extract GWP-ASan report");
+        };
+        assert_eq!(report.len(), 11);
+        assert!(extract_gwp_asan_report(&output[..2]).is_none());
+
+        let context = GwpAsanContext(report);
+        let access = context.access_stacktrace();
+        assert_eq!(
+            access,
+            vec![
+                "  #0 0x558c5a6f4169  (./test_gwp_asan+0x1169)".to_string(),
+                "  #1 0x7f8a3c8c9083 in __libc_start_main".to_string(),
+                "  #2 0x558c5a6f408e  (./test_gwp_asan+0x108e)".to_string(),
+            ]
+        );
+        let Ok(stacktrace) = AsanStacktrace::parse_stacktrace(&access) else {
+            panic!("Couldn't parse GWP-ASan stack trace");
+        };
+        assert_eq!(stacktrace[0].module, "./test_gwp_asan");
+        assert_eq!(stacktrace[0].offset, 0x1169);
+        assert_eq!(stacktrace[1].function, "__libc_start_main");
+        assert_eq!(context.deallocation_stacktrace().len(), 1);
+        assert_eq!(
+            context.allocation_stacktrace(),
+            vec![
+                "  #0 0x558c5a6f4149  (./test_gwp_asan+0x1149)".to_string(),
+                "  #1 0x558c5a6f4150".to_string(),
+            ]
+        );
+
+        let class = context.severity();
+        let Ok(class) = class else {
+            panic!("{}", class.err().unwrap());
+        };
+        assert_eq!(class.short_description, "heap-use-after-free");
+
+        for (error, class) in [
+            ("Buffer Overflow", "heap-buffer-overflow"),
+            ("Buffer Underflow", "heap-buffer-overflow"),
+            ("Double Free", "double-free"),
+            ("Invalid (Wild) Free", "bad-free"),
+            ("Unknown", "AccessViolation"),
+        ] {
+            let context = GwpAsanContext(vec![format!(
+                "{error} at 0x7f8a3c4d6ff0 (a 16-byte allocation at 0x7f8a3c4d6ff0) by thread 1 here:"
+            )]);
+            let Ok(severity) = context.severity() else {
+                panic!("Couldn't estimate severity for {error}");
+            };
+            assert_eq!(severity.short_description, class);
+        }
+    }
+}
@@ -0,0 +1,225 @@
+//! HWASan module implements `Severity` trait and report extraction for
+//! HWAddressSanitizer (hardware-assisted AddressSanitizer, e.g., on Android
+//! aarch64) reports.
+use regex::Regex;
+
+use crate::error::*;
+use crate::execution_class::{is_near_null, ExecutionClass};
+use crate::severity::Severity;
+
+/// Extract HWAddressSanitizer report from program output
+///
+/// # Arguments
+///
+/// * `stream` - program output lines
+///
+/// # Return value
+///
+/// Report lines from ERROR to SUMMARY line
+pub fn extract_hwasan_report(stream: &[String]) -> Option<Vec<String>> {
+    let rerror = Regex::new(r"ERROR: HWAddressSanitizer:").unwrap();
+    let start = stream.iter().position(|line| rerror.is_match(line))?;
+    let end = stream[start..]
+        .iter()
+        .position(|line| line.contains("SUMMARY: HWAddressSanitizer: "))
+        .map(|pos| start + pos + 1)
+        .unwrap_or(stream.len());
+    Some(
+        stream[start..end]
+            .iter()
+            .map(|line| line.trim_end().to_string())
+            .collect(),
+    )
+}
+
+/// Information about HWAddressSanitizer report.
+pub struct HwasanContext(pub Vec<String>);
+
+impl HwasanContext {
+    /// Get the most probable cause of tag mismatch, e.g., "use-after-free" or
+    /// "heap-buffer-overflow". HWASan may print several potential causes, the
+    /// first one is the most probable.
+    pub fn cause(&self) -> Option<String> {
+        let rcause = Regex::new(r"^(?:Potential )?Cause: (.+)$").unwrap();
+        self.0
+            .iter()
+            .find_map(|line| rcause.captures(line.trim()))
+            .map(|caps| caps.get(1).unwrap().as_str().trim().to_string())
+    }
+
+    /// Get pointer and memory tags of invalid access, e.g., ("2f", "00")
+    pub fn tags(&self) -> Option<(String, String)> {
+        let rtags = Regex::new(r"tags: ([0-9a-f]+)/([0-9a-f]+)").unwrap();
+        self.0
+            .iter()
+            .find_map(|line| rtags.captures(line))
+            .map(|caps| {
+                (
+                    caps.get(1).unwrap().as_str().to_string(),
+                    caps.get(2).unwrap().as_str().to_string(),
+                )
+            })
+    }
+}
+
+impl Severity for HwasanContext {
+    fn severity(&self) -> Result<ExecutionClass> {
+        let hwasan_report = &self.0;
+        let rerror = Regex::new(r"ERROR: HWAddressSanitizer: ([A-Za-z_\-]+)").unwrap();
+        let Some(caps) = hwasan_report.iter().find_map(|s| rerror.captures(s)) else {
+            return Err(Error::Casr(
+                "Cannot find ERROR in HWAddressSanitizer report".to_string(),
+            ));
+        };
+        let error = caps.get(1).unwrap().as_str();
+        let raccess = Regex::new(r"^(READ|WRITE) of size").unwrap();
+        let access = hwasan_report
+            .iter()
+            .find_map(|line| raccess.captures(line.trim()))
+            .map(|caps| caps.get(1).unwrap().as_str());
+        let cause = self.cause();
+        match error {
+            "tag-mismatch" => {
+                let class = match cause.as_deref() {
+                    Some("use-after-free") => "heap-use-after-free",
+                    Some("heap-buffer-overflow") => "heap-buffer-overflow",
+                    Some("stack-buffer-overflow") | Some("stack tag-mismatch") => {
+                        "stack-buffer-overflow"
+                    }
+                    Some("use-after-scope") => "stack-use-after-scope",
+                    Some("global-overflow") => "global-buffer-overflow",
+                    // Invalid access without known cause.
+                    _ => "SEGV",
+                };
+                ExecutionClass::san_find(class, access, false)
+            }
+            "invalid-free" => {
+                if cause.as_deref() == Some("use-after-free") {
+                    ExecutionClass::find("double-free")
+                } else {
+                    ExecutionClass::find("bad-free")
+                }
+            }
+            "allocation-tail-overwritten" => ExecutionClass::find("heap-buffer-overflow(write)"),
+            _ => {
+                let raddress = Regex::new(r"on.*address 0x([0-9a-f]+)").unwrap();
+                let near_null = raddress
+                    .captures(&hwasan_report[0])
+                    .and_then(|caps| u64::from_str_radix(caps.get(1).unwrap().as_str(), 16).ok())
+                    .is_some_and(is_near_null);
+                ExecutionClass::san_find(error, access, near_null)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::asan::{AsanContext, AsanStacktrace};
+    use crate::stacktrace::ParseStacktrace;
+
+    #[test]
+    fn test_hwasan_report() {
+        let output: Vec<String> = [
+            "Running: crash",
+            "==4242==ERROR: HWAddressSanitizer: tag-mismatch on address 0x003d557e0010 at pc 0x0055c3bd6f98",
+            "READ of size 1 at 0x003d557e0010 tags: 2f/00 (ptr/mem) in thread T0",
+            "    #0 0x55c3bd6f94 in main /tmp/test_hwasan.c:10:10",
+            "    #1 0x7f9a3b2d3c in __libc_start_main (/lib/aarch64-linux-gnu/libc.so.6+0x20d3c)",
+            "",
+            "[0x003d557e0000,0x003d557e0020) is a small unallocated heap chunk; size: 32 offset: 16",
+            "Cause: use-after-free",
+            "0x003d557e0010 is located 0 bytes inside a 10-byte region [0x003d557e0010,0x003d557e001a)",
+            "freed by thread T0 here:",
+            "    #0 0x7f9a5c1e34 in free (/usr/lib/libclang_rt.hwasan-aarch64.so+0x21e34)",
+            "    #1 0x55c3bd6f60 in main /tmp/test_hwasan.c:9:3",
+            "",
+            "previously allocated by thread T0 here:",
+            "    #0 0x7f9a5c1f80 in malloc (/usr/lib/libclang_rt.hwasan-aarch64.so+0x21f80)",
+            "    #1 0x55c3bd6f40 in main /tmp/test_hwasan.c:8:20",
+            "",
+            "Memory tags around the buggy address (one tag corresponds to 16 bytes):",
+            "=>0x003d557e0000: 08 [00] 00 00 00 00 00 00 00 00 00 00 00 00 00 00",
+            "SUMMARY: HWAddressSanitizer: tag-mismatch /tmp/test_hwasan.c:10:10 in main",
+            "Aborted",
+        ]
+        .iter()
+        .map(|l| l.to_string())
+        .collect();
+
+        let Some(report) = extract_hwasan_report(&output) else {
+            panic!("Couldn't extract HWAddressSanitizer report");
+        };
+        assert_eq!(report.len(), 19);
+        assert!(extract_hwasan_report(&output[..1]).is_none());
+
+        let context = HwasanContext(report.clone());
+        assert_eq!(context.cause(), Some("use-after-free".to_string()));
+        assert_eq!(context.tags(), Some(("2f".to_string(), "00".to_string())));
+        let class = context.severity();
+        let Ok(class) = class else {
+            panic!("{}", class.err().unwrap());
+        };
+        assert_eq!(class.short_description, "heap-use-after-free(read)");
+
+        let Ok(stacktrace) = AsanStacktrace::extract_stacktrace(&report.join("\n")) else {
+            panic!("Couldn't extract HWAddressSanitizer stack trace");
+        };
+        assert_eq!(stacktrace.len(), 2);
+        let Ok(stacktrace) = AsanStacktrace::parse_stacktrace(&stacktrace) else {
+            panic!("Couldn't parse HWAddressSanitizer stack trace");
+        };
+        assert_eq!(stacktrace[0].function, "main");
+        assert_eq!(stacktrace[0].debug.line, 10);
+        let context = AsanContext(report);
+        assert_eq!(context.deallocation_stacktrace().len(), 2);
+        assert_eq!(context.allocation_stacktrace().len(), 2);
+
+        for (error, access, cause, class) in [
+            (
+                "tag-mismatch",
+                "WRITE",
+                "heap-buffer-overflow",
+                "heap-buffer-overflow(write)",
+            ),
+            (
+                "tag-mismatch",
+                "READ",
+                "stack tag-mismatch",
+                "stack-buffer-overflow(read)",
+            ),
+            (
+                "tag-mismatch",
+                "WRITE",
+                "use-after-scope",
+                "stack-use-after-scope(write)",
+            ),
+            (
+                "tag-mismatch",
+                "READ",
+                "global-overflow",
+                "global-buffer-overflow(read)",
+            ),
+            ("tag-mismatch", "WRITE", "unknown", "DestAv"),
+            ("invalid-free", "", "use-after-free", "double-free"),
+            ("invalid-free", "", "unknown", "bad-free"),
+            (
+                "allocation-tail-overwritten",
+                "",
+                "",
+                "heap-buffer-overflow(write)",
+            ),
+        ] {
+            let context = HwasanContext(vec![
+                format!("==1==ERROR: HWAddressSanitizer: {error} on address 0x003d557e0010 at pc 0x0055c3bd6f98"),
+                format!("{access} of size 4 at 0x003d557e0010 tags: 2f/00 (ptr/mem) in thread T0"),
+                format!("Potential Cause: {cause}"),
+            ]);
+            let Ok(severity) = context.severity() else {
+                panic!("Couldn't estimate severity for {error} ({cause})");
+            };
+            assert_eq!(severity.short_description, class);
+        }
+    }
+}
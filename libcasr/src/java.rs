@@ -1,7 +1,9 @@
-//! Java module implements `ParseStacktrace` and `Exception` traits for Java reports.
+//! Java module implements `ParseStacktrace`, `Exception`, and `Severity` traits for Java
+//! reports and JVM fatal error logs.
 use crate::error::*;
 use crate::exception::Exception;
-use crate::execution_class::ExecutionClass;
+use crate::execution_class::{is_near_null, ExecutionClass};
+use crate::severity::Severity;
 use crate::stacktrace::ParseStacktrace;
 use crate::stacktrace::*;
 
@@ -102,6 +104,29 @@ impl ParseStacktrace for JavaStacktrace {
     }
 }
 
+/// Execution classes for Jazzer security issues and common java exceptions:
+/// severity, exception class name, and explanation.
+const JAVA_EXCEPTIONS: &[(&str, &str, &str)] = &[
+    ("EXPLOITABLE", "FuzzerSecurityIssueCritical", "Jazzer bug detector found critical security issue (e.g., remote code execution, OS command injection, or unsafe deserialization)."),
+    ("EXPLOITABLE", "FuzzerSecurityIssueHigh", "Jazzer bug detector found high severity security issue (e.g., SQL injection or file path traversal)."),
+    ("PROBABLY_EXPLOITABLE", "FuzzerSecurityIssueMedium", "Jazzer bug detector found medium severity security issue."),
+    ("NOT_EXPLOITABLE", "FuzzerSecurityIssueLow", "Jazzer bug detector found low severity issue (e.g., regular expression denial of service)."),
+    ("NOT_EXPLOITABLE", "OutOfMemoryError", "Java virtual machine cannot allocate an object because it is out of memory, which may be caused by uncontrolled allocation size."),
+    ("NOT_EXPLOITABLE", "StackOverflowError", "Stack overflow occurs because the target recurses too deeply."),
+    ("NOT_EXPLOITABLE", "NullPointerException", "The target attempts to use null reference where an object is required."),
+    ("NOT_EXPLOITABLE", "ArrayIndexOutOfBoundsException", "Array is accessed with an illegal index."),
+    ("NOT_EXPLOITABLE", "StringIndexOutOfBoundsException", "String is accessed with an illegal index."),
+    ("NOT_EXPLOITABLE", "IndexOutOfBoundsException", "Index of some sort (e.g., to an array, to a string, or to a vector) is out of range."),
+    ("NOT_EXPLOITABLE", "NegativeArraySizeException", "The target attempts to create an array with negative size."),
+    ("NOT_EXPLOITABLE", "ClassCastException", "The target attempts to cast an object to a subclass of which it is not an instance."),
+    ("NOT_EXPLOITABLE", "ArithmeticException", "Exceptional arithmetic condition has occurred (e.g., integer division by zero)."),
+    ("NOT_EXPLOITABLE", "IllegalArgumentException", "Method has been passed an illegal or inappropriate argument."),
+    ("NOT_EXPLOITABLE", "IllegalStateException", "Method has been invoked at an illegal or inappropriate time."),
+    ("NOT_EXPLOITABLE", "NumberFormatException", "The target attempts to convert a string with inappropriate format to a numeric type."),
+    ("NOT_EXPLOITABLE", "ConcurrentModificationException", "Object is concurrently modified when such modification is not permissible."),
+    ("NOT_EXPLOITABLE", "UnsupportedOperationException", "Requested operation is not supported."),
+];
+
 /// Structure provides an interface for parsing java exception message.
 pub struct JavaException;
 
@@ -113,24 +138,190 @@ impl Exception for JavaException {
         )
         .unwrap();
         re.captures(&description).map(|cap| {
-            ExecutionClass::new((
-                "NOT_EXPLOITABLE",
-                if let Some(class) = cap.get(1) {
-                    class.as_str()
-                } else {
-                    cap.get(2).unwrap().as_str()
-                },
-                if cap.get(1).is_some() {
-                    cap.get(2).unwrap().as_str()
-                } else {
-                    ""
-                },
-                "",
-            ))
+            let (class, message) = if let Some(class) = cap.get(1) {
+                (class.as_str(), cap.get(2).unwrap().as_str())
+            } else {
+                (cap.get(2).unwrap().as_str(), "")
+            };
+            // Match by simple class name, e.g. "java.lang.NullPointerException".
+            let name = class.rsplit(['.', '$']).next().unwrap_or(class);
+            let (severity, explanation) = JAVA_EXCEPTIONS
+                .iter()
+                .find(|(_, exception, _)| *exception == name)
+                .map(|(severity, _, explanation)| (*severity, *explanation))
+                .unwrap_or(("NOT_EXPLOITABLE", ""));
+            ExecutionClass::new((severity, class, message, explanation))
         })
     }
 }
 
+/// Structure provides an interface for processing the stack trace from JVM fatal
+/// error log (hs_err_pid*.log).
+pub struct HsErrStacktrace;
+
+impl ParseStacktrace for HsErrStacktrace {
+    fn extract_stacktrace(stream: &str) -> Result<Vec<String>> {
+        // Native frames include both native and java frames, while java frames
+        // are printed when native stack couldn't be walked.
+        let lines: Vec<&str> = stream.split('\n').map(|l| l.trim_end()).collect();
+        let Some(first) = lines
+            .iter()
+            .position(|l| l.starts_with("Native frames:"))
+            .or_else(|| lines.iter().position(|l| l.starts_with("Java frames:")))
+        else {
+            return Err(Error::Casr(
+                "Couldn't find stack trace in JVM fatal error log".to_string(),
+            ));
+        };
+        let stacktrace: Vec<String> = lines[first + 1..]
+            .iter()
+            .take_while(|l| !l.is_empty())
+            .filter(|l| !l.starts_with("..."))
+            .map(|l| l.to_string())
+            .collect();
+        if stacktrace.is_empty() {
+            return Err(Error::Casr(
+                "Empty stack trace in JVM fatal error log".to_string(),
+            ));
+        }
+        Ok(stacktrace)
+    }
+
+    fn parse_stacktrace_entry(entry: &str) -> Result<StacktraceEntry> {
+        let mut stentry = StacktraceEntry::default();
+        let Some((kind, location)) = entry.split_once(' ') else {
+            return Err(Error::Casr(format!(
+                "Couldn't parse JVM stack trace entry: {entry}"
+            )));
+        };
+        let location = location.trim();
+        match kind {
+            // Native and VM frames:
+            // C  [libnative.so+0x1139]  Java_Test_crash+0x10
+            // C  0x0000000000000000
+            "C" | "V" => {
+                if let Some(num) = location.strip_prefix("0x") {
+                    let Ok(address) = u64::from_str_radix(num, 16) else {
+                        return Err(Error::Casr(format!("Couldn't parse address: {num}")));
+                    };
+                    stentry.address = address;
+                    return Ok(stentry);
+                }
+                let re = Regex::new(r"^\[(.+)\+0x([0-9a-f]+)\](?:\s+(.+?))?(?:\+0x[0-9a-f]+)?$")
+                    .unwrap();
+                let Some(caps) = re.captures(location) else {
+                    return Err(Error::Casr(format!(
+                        "Couldn't parse JVM stack trace entry: {entry}"
+                    )));
+                };
+                stentry.module = caps.get(1).unwrap().as_str().to_string();
+                let num = caps.get(2).unwrap().as_str();
+                let Ok(offset) = u64::from_str_radix(num, 16) else {
+                    return Err(Error::Casr(format!("Couldn't parse offset: {num}")));
+                };
+                stentry.offset = offset;
+                if let Some(function) = caps.get(3) {
+                    stentry.function = function.as_str().to_string();
+                }
+            }
+            // Interpreted and compiled java frames:
+            // j  Test.crash()V+0
+            // J 123 c1 Test.crash()V (10 bytes) @ 0x00007f5c6d01a2b4 [0x00007f5c6d01a220+0x94]
+            "j" | "J" => {
+                let re = Regex::new(r"([\w$./<>]+)\(").unwrap();
+                let Some(caps) = re.captures(location) else {
+                    return Err(Error::Casr(format!(
+                        "Couldn't parse JVM stack trace entry: {entry}"
+                    )));
+                };
+                stentry.function = caps.get(1).unwrap().as_str().to_string();
+                let re = Regex::new(r"@ 0x([0-9a-f]+)").unwrap();
+                if let Some(caps) = re.captures(location) {
+                    stentry.address =
+                        u64::from_str_radix(caps.get(1).unwrap().as_str(), 16).unwrap_or_default();
+                }
+            }
+            // VM generated stubs: v  ~StubRoutines::call_stub
+            "v" => stentry.function = location.to_string(),
+            _ => {
+                return Err(Error::Casr(format!(
+                    "Unknown JVM stack trace entry type: {entry}"
+                )))
+            }
+        }
+        Ok(stentry)
+    }
+}
+
+/// Information about JVM fatal error from hs_err_pid*.log header and siginfo.
+pub struct HsErrContext(pub Vec<String>);
+
+impl Severity for HsErrContext {
+    fn severity(&self) -> Result<ExecutionClass> {
+        if self
+            .0
+            .iter()
+            .any(|l| l.contains("There is insufficient memory for the Java Runtime Environment"))
+        {
+            return ExecutionClass::find("out-of-memory");
+        }
+        // Failed assert or guarantee inside JVM.
+        if self
+            .0
+            .iter()
+            .any(|l| l.starts_with("#  Internal Error") || l.starts_with("#  fatal error"))
+        {
+            return ExecutionClass::find("AbortSignal");
+        }
+        let rsignal = Regex::new(r"^#\s+([A-Z_]+) \(0x[0-9a-f]+\) at pc=0x([0-9a-f]+)").unwrap();
+        let Some(caps) = self.0.iter().find_map(|l| rsignal.captures(l)) else {
+            return Err(Error::Casr(
+                "Couldn't find signal in JVM fatal error log".to_string(),
+            ));
+        };
+        match caps.get(1).unwrap().as_str() {
+            "SIGSEGV" | "SIGBUS" | "EXCEPTION_ACCESS_VIOLATION" => {
+                let raddr = Regex::new(r"si_addr: (?:0x)?([0-9a-f]+)").unwrap();
+                let Some(address) = self
+                    .0
+                    .iter()
+                    .find_map(|l| raddr.captures(l))
+                    .and_then(|caps| u64::from_str_radix(caps.get(1).unwrap().as_str(), 16).ok())
+                else {
+                    return ExecutionClass::find("AccessViolation");
+                };
+                let pc = u64::from_str_radix(caps.get(2).unwrap().as_str(), 16).ok();
+                match (pc == Some(address), is_near_null(address)) {
+                    (true, false) => ExecutionClass::find("SegFaultOnPc"),
+                    (true, true) => ExecutionClass::find("SegFaultOnPcNearNull"),
+                    (false, _) => ExecutionClass::find("AccessViolation"),
+                }
+            }
+            "SIGILL" | "EXCEPTION_ILLEGAL_INSTRUCTION" => ExecutionClass::find("BadInstruction"),
+            "SIGFPE" | "EXCEPTION_INT_DIVIDE_BY_ZERO" => ExecutionClass::find("FPE"),
+            "SIGABRT" => ExecutionClass::find("AbortSignal"),
+            "SIGTRAP" => ExecutionClass::find("TrapSignal"),
+            "EXCEPTION_STACK_OVERFLOW" => ExecutionClass::find("StackOverflow"),
+            signal => Err(Error::Casr(format!(
+                "Unsupported JVM fatal signal: {signal}"
+            ))),
+        }
+    }
+}
+
+/// Get path to JVM fatal error log from JVM output, e.g.
+/// "# An error report file with more information is saved as:\n# /tmp/hs_err_pid123.log".
+///
+/// # Arguments
+///
+/// * `output` - JVM stdout or stderr
+pub fn extract_hs_err_path(output: &str) -> Option<String> {
+    let re = Regex::new(r"(?m)^# An error report file with more information is saved as:\n# (.+)$")
+        .unwrap();
+    re.captures(output)
+        .map(|caps| caps.get(1).unwrap().as_str().trim().to_string())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -229,4 +420,104 @@ mod tests {
         assert_eq!(stacktrace[10].debug.line, 24);
         assert_eq!(stacktrace[10].function, "Reproducer.main".to_string());
     }
+
+    #[test]
+    fn test_java_exception() {
+        let report = "== Java Exception: com.code_intelligence.jazzer.api.FuzzerSecurityIssueCritical: OS Command Injection
+Executing OS commands with attacker-controlled data can lead to remote code execution.
+        at com.code_intelligence.jazzer.sanitizers.OsCommandInjection.processImplStartHook(OsCommandInjection.kt:59)
+        at java.base/java.lang.ProcessBuilder.start(ProcessBuilder.java:1143)
+        at OsCommandInjectionFuzzer.fuzzerTestOneInput(OsCommandInjectionFuzzer.java:12)";
+        let Some(class) = JavaException::parse_exception(report) else {
+            panic!("Couldn't parse exception");
+        };
+        assert_eq!(class.severity, "EXPLOITABLE");
+        assert_eq!(
+            class.short_description,
+            "com.code_intelligence.jazzer.api.FuzzerSecurityIssueCritical"
+        );
+        assert_eq!(class.description, "OS Command Injection");
+        assert!(!class.explanation.is_empty());
+
+        let report = "Exception in thread \"main\" java.lang.NullPointerException: Cannot invoke \"String.length()\" because \"s\" is null
+        at Test.main(Test.java:5)";
+        let Some(class) = JavaException::parse_exception(report) else {
+            panic!("Couldn't parse exception");
+        };
+        assert_eq!(class.severity, "NOT_EXPLOITABLE");
+        assert_eq!(class.short_description, "java.lang.NullPointerException");
+        assert!(!class.explanation.is_empty());
+    }
+
+    #[test]
+    fn test_hs_err() {
+        let output = "#
+# A fatal error has been detected by the Java Runtime Environment:
+#
+#  SIGSEGV (0xb) at pc=0x00007f5c8c6d2f1e, pid=12345, tid=12346
+#
+# JRE version: OpenJDK Runtime Environment (17.0.2+8) (build 17.0.2+8-86)
+# Problematic frame:
+# C  [libnative.so+0x1f1e]  Java_Test3_crash+0x1e
+#
+# An error report file with more information is saved as:
+# /tmp/hs_err_pid12345.log
+#";
+        assert_eq!(
+            extract_hs_err_path(output),
+            Some("/tmp/hs_err_pid12345.log".to_string())
+        );
+
+        let log = format!(
+            "{output}
+---------------  T H R E A D  ---------------
+
+Current thread (0x00007f5c8402a0b0):  JavaThread \"main\" [_thread_in_native, id=12346]
+
+Stack: [0x00007f5c8bd00000,0x00007f5c8be00000],  sp=0x00007f5c8bdfe5d0,  free space=1017k
+Native frames: (J=compiled Java code, j=interpreted, Vv=VM code, C=native code)
+C  [libnative.so+0x1f1e]  Java_Test3_crash+0x1e
+j  Test3.crash()V+0
+J 123 c1 Test3.run(I)V (10 bytes) @ 0x00007f5c6d01a2b4 [0x00007f5c6d01a220+0x0000000000000094]
+j  Test3.main([Ljava/lang/String;)V+5
+v  ~StubRoutines::call_stub
+V  [libjvm.so+0x8a2e85]  JavaCalls::call_helper(JavaValue*, methodHandle const&, JavaCallArguments*, JavaThread*)+0x315
+C  [libc.so.6+0x94b43]
+
+Java frames: (J=compiled Java code, j=interpreted, Vv=VM code)
+j  Test3.crash()V+0
+
+siginfo: si_signo: 11 (SIGSEGV), si_code: 1 (SEGV_MAPERR), si_addr: 0x0000000000000000"
+        );
+
+        let raw_stacktrace = HsErrStacktrace::extract_stacktrace(&log);
+        let Ok(raw_stacktrace) = raw_stacktrace else {
+            panic!("{}", raw_stacktrace.err().unwrap());
+        };
+        assert_eq!(raw_stacktrace.len(), 7);
+
+        let stacktrace = HsErrStacktrace::parse_stacktrace(&raw_stacktrace);
+        let Ok(stacktrace) = stacktrace else {
+            panic!("{}", stacktrace.err().unwrap());
+        };
+        assert_eq!(stacktrace[0].module, "libnative.so");
+        assert_eq!(stacktrace[0].offset, 0x1f1e);
+        assert_eq!(stacktrace[0].function, "Java_Test3_crash");
+        assert_eq!(stacktrace[1].function, "Test3.crash");
+        assert_eq!(stacktrace[2].function, "Test3.run");
+        assert_eq!(stacktrace[2].address, 0x7f5c6d01a2b4);
+        assert_eq!(stacktrace[4].function, "~StubRoutines::call_stub");
+        assert_eq!(
+            stacktrace[5].function,
+            "JavaCalls::call_helper(JavaValue*, methodHandle const&, JavaCallArguments*, JavaThread*)"
+        );
+        assert_eq!(stacktrace[6].module, "libc.so.6");
+        assert_eq!(stacktrace[6].function, "");
+
+        let class = HsErrContext(log.split('\n').map(|l| l.to_string()).collect()).severity();
+        let Ok(class) = class else {
+            panic!("{}", class.err().unwrap());
+        };
+        assert_eq!(class.short_description, "AccessViolation");
+    }
 }
@@ -0,0 +1,536 @@
+//! Kernel module implements `ParseStacktrace` and `Severity` traits for Linux kernel
+//! sanitizer reports (KASAN, KCSAN, KMSAN) and kernel oops (BUG, general protection
+//! fault, WARNING) from dmesg, serial console, or syzkaller crash logs.
+use gdb_command::registers::Registers;
+use regex::Regex;
+
+use crate::error::*;
+use crate::execution_class::{is_near_null, ExecutionClass};
+use crate::severity::Severity;
+use crate::stacktrace::*;
+
+/// Structure provides an interface for processing the stack trace.
+pub struct KernelStacktrace;
+
+/// Remove dmesg timestamp and caller id prefix from log line, e.g. "[   10.123456][ T487] ".
+///
+/// # Arguments
+///
+/// * `line` - kernel log line
+pub fn strip_dmesg_prefix(line: &str) -> &str {
+    let re = Regex::new(r"^(\[ *[0-9]+\.[0-9]+\]|\[ *[CT][0-9]+\])+ ?").unwrap();
+    let end = re.find(line).map(|m| m.end()).unwrap_or(0);
+    &line[end..]
+}
+
+/// Extract kernel crash report from kernel log. Log lines are stripped of dmesg
+/// prefixes.
+///
+/// # Arguments
+///
+/// * `stream` - kernel log lines
+///
+/// # Return value
+///
+/// Report lines from BUG (oops, WARNING) line (or preceding "=====" delimiter) to
+/// the end of report: closing delimiter, end trace marker, or kernel panic line
+pub fn extract_kernel_report(stream: &[String]) -> Option<Vec<String>> {
+    let lines: Vec<&str> = stream
+        .iter()
+        .map(|l| strip_dmesg_prefix(l).trim_end())
+        .collect();
+    let rstart = Regex::new(
+        r"^(BUG: |kernel BUG at |general protection fault|WARNING: |Oops: |Unable to handle kernel )",
+    )
+    .unwrap();
+    let rend = Regex::new(r"^(={20,}|---\[ end trace|Kernel panic - not syncing)").unwrap();
+    let mut start = lines.iter().position(|l| rstart.is_match(l))?;
+    if start > 0 && lines[start - 1].starts_with("=====") {
+        start -= 1;
+    }
+    let end = lines[start + 1..]
+        .iter()
+        .position(|l| rend.is_match(l))
+        .map(|pos| start + pos + 2)
+        .unwrap_or(lines.len());
+    Some(lines[start..end].iter().map(|l| l.to_string()).collect())
+}
+
+impl ParseStacktrace for KernelStacktrace {
+    fn extract_stacktrace(stream: &str) -> Result<Vec<String>> {
+        let lines: Vec<&str> = stream
+            .split('\n')
+            .map(|l| strip_dmesg_prefix(l).trim_end())
+            .collect();
+        let frame = Regex::new(r"^ *(\? +)?[^ +]+\+0x[0-9a-f]+/0x[0-9a-f]+").unwrap();
+
+        let Some(first) = lines.iter().position(|l| frame.is_match(l)) else {
+            return Err(Error::Casr(
+                "Couldn't find stack trace in kernel report".to_string(),
+            ));
+        };
+
+        // Skip unreliable frames marked with '?' and task markers.
+        Ok(lines[first..]
+            .iter()
+            .take_while(|l| frame.is_match(l) || l.trim().starts_with('<'))
+            .filter(|l| frame.is_match(l) && !l.trim_start().starts_with('?'))
+            .map(|l| l.to_string())
+            .collect())
+    }
+
+    fn parse_stacktrace_entry(entry: &str) -> Result<StacktraceEntry> {
+        let mut stentry = StacktraceEntry::default();
+
+        // function+0x1d/0x30 [module] file:line
+        let re = Regex::new(
+            r"^ *(?:\? +)?([^ +]+)\+0x([0-9a-f]+)/0x[0-9a-f]+(?: +\[([^\]]+)\])?(?: +\(?([^ ()]+?):([0-9]+)(?::([0-9]+))?\)?)?",
+        )
+        .unwrap();
+        let Some(caps) = re.captures(entry) else {
+            return Err(Error::Casr(format!(
+                "Couldn't parse kernel stack trace entry: {entry}"
+            )));
+        };
+        stentry.function = caps.get(1).unwrap().as_str().to_string();
+        let num = caps.get(2).unwrap().as_str();
+        let Ok(offset) = u64::from_str_radix(num, 16) else {
+            return Err(Error::Casr(format!(
+                "Couldn't parse function offset: {num}"
+            )));
+        };
+        stentry.offset = offset;
+        stentry.module = caps
+            .get(3)
+            .map(|m| m.as_str().to_string())
+            .unwrap_or_else(|| "vmlinux".to_string());
+        if let Some(file) = caps.get(4) {
+            stentry.debug.file = file.as_str().to_string();
+            stentry.debug.line = caps.get(5).unwrap().as_str().parse::<u64>().unwrap_or(0);
+            if let Some(column) = caps.get(6) {
+                stentry.debug.column = column.as_str().parse::<u64>().unwrap_or(0);
+            }
+        }
+
+        Ok(stentry)
+    }
+}
+
+/// Information about kernel sanitizer report or kernel oops.
+pub struct KernelContext(pub Vec<String>);
+
+impl KernelContext {
+    /// Get faulting instruction pointer frame from oops, e.g.,
+    /// "RIP: 0010:test_func+0x12/0x30 [test]" -> " test_func+0x12/0x30 [test]".
+    pub fn rip_frame(&self) -> Option<String> {
+        let rrip = Regex::new(
+            r"^(?:RIP: [0-9a-f]{4}:|pc : )([^ +]+\+0x[0-9a-f]+/0x[0-9a-f]+(?: \[[^\]]+\])?)",
+        )
+        .unwrap();
+        self.0
+            .iter()
+            .find_map(|l| rrip.captures(strip_dmesg_prefix(l)))
+            .map(|caps| format!(" {}", caps.get(1).unwrap().as_str()))
+    }
+
+    /// Get stack trace: faulting instruction pointer frame (if any) followed by
+    /// call trace.
+    pub fn extract_stacktrace(&self) -> Result<Vec<String>> {
+        let mut stacktrace = KernelStacktrace::extract_stacktrace(&self.0.join("\n"))?;
+        if let Some(rip) = self.rip_frame() {
+            if stacktrace.first().map(|frame| frame.trim()) != Some(rip.trim()) {
+                stacktrace.insert(0, rip);
+            }
+        }
+        Ok(stacktrace)
+    }
+
+    /// Get registers from oops register dump, e.g.,
+    /// "RAX: 0000000000000000 RBX: ffff888007a3c000 RCX: 0000000000000001".
+    pub fn registers(&self) -> Registers {
+        let rreg =
+            Regex::new(r"\b([A-Z][A-Z0-9]{1,5}): (?:[0-9a-f]{4}:)?([0-9a-f]{8,16})\b").unwrap();
+        let mut registers = Registers::new();
+        for line in self.0.iter().map(|l| strip_dmesg_prefix(l)) {
+            if line.starts_with("RIP: ") || line.starts_with("Code: ") {
+                continue;
+            }
+            for caps in rreg.captures_iter(line) {
+                if let Ok(value) = u64::from_str_radix(caps.get(2).unwrap().as_str(), 16) {
+                    registers.insert(caps.get(1).unwrap().as_str().to_lowercase(), value);
+                }
+            }
+        }
+        registers
+    }
+
+    /// Get kernel release from "CPU: 0 PID: 1 Comm: swapper/0 Not tainted 6.1.0 #1" line.
+    pub fn kernel_release(&self) -> Option<String> {
+        let rrelease =
+            Regex::new(r"^CPU: \d+ .*(?:Not tainted|Tainted: [A-Z ]+?) +([0-9][^ ]*)").unwrap();
+        self.0
+            .iter()
+            .find_map(|l| rrelease.captures(strip_dmesg_prefix(l)))
+            .map(|caps| caps.get(1).unwrap().as_str().to_string())
+    }
+
+    /// Get memory access type from KASAN ("Read of size 8 at addr") or page fault
+    /// ("#PF: supervisor write access in kernel mode") lines.
+    fn access_type(&self) -> Option<&str> {
+        let raccess =
+            Regex::new(r"(?:^(Read|Write) of size \d+|^#PF: \w+ (read|write) access)").unwrap();
+        self.0.iter().find_map(|l| {
+            let caps = raccess.captures(strip_dmesg_prefix(l))?;
+            match caps.get(1).or(caps.get(2)).unwrap().as_str() {
+                "Read" | "read" => Some("READ"),
+                _ => Some("WRITE"),
+            }
+        })
+    }
+
+    /// Estimate severity of KASAN report
+    ///
+    /// # Arguments
+    ///
+    /// * `bug_type` - KASAN bug type, e.g., "slab-out-of-bounds"
+    fn kasan_severity(&self, bug_type: &str) -> Result<ExecutionClass> {
+        let rw = self.access_type();
+        match bug_type {
+            "slab-out-of-bounds" | "vmalloc-out-of-bounds" | "out-of-bounds" => {
+                ExecutionClass::san_find("heap-buffer-overflow", rw, false)
+            }
+            "use-after-free" | "slab-use-after-free" => {
+                ExecutionClass::san_find("heap-use-after-free", rw, false)
+            }
+            "global-out-of-bounds" => ExecutionClass::san_find("global-buffer-overflow", rw, false),
+            "stack-out-of-bounds" => ExecutionClass::san_find("stack-buffer-overflow", rw, false),
+            "double-free" | "double-free-or-invalid-free" => ExecutionClass::find("double-free"),
+            "invalid-free" => ExecutionClass::find("bad-free"),
+            "null-ptr-deref" => ExecutionClass::san_find("SEGV", rw, true),
+            "wild-memory-access" | "user-memory-access" => {
+                ExecutionClass::san_find("SEGV", rw, false)
+            }
+            _ => ExecutionClass::san_find(bug_type, rw, false),
+        }
+    }
+}
+
+impl Severity for KernelContext {
+    fn severity(&self) -> Result<ExecutionClass> {
+        let lines: Vec<&str> = self.0.iter().map(|l| strip_dmesg_prefix(l)).collect();
+        let rbug = Regex::new(r"BUG: (KASAN|KCSAN|KMSAN): ([A-Za-z_\-]+)").unwrap();
+        if let Some(caps) = lines.iter().find_map(|l| rbug.captures(l)) {
+            let bug_type = caps.get(2).unwrap().as_str();
+            return match (caps.get(1).unwrap().as_str(), bug_type) {
+                ("KASAN", _) => self.kasan_severity(bug_type),
+                ("KCSAN", "data-race") => ExecutionClass::find("data-race"),
+                ("KMSAN", "uninit-value") => ExecutionClass::find("use-of-uninitialized-value"),
+                ("KMSAN", "kernel-infoleak") => ExecutionClass::find("kernel-infoleak"),
+                (_, _) => ExecutionClass::find(bug_type),
+            };
+        }
+
+        // Kernel oops.
+        let raddress =
+            Regex::new(r"^(?:BUG: unable to handle|Unable to handle kernel) .*(?:address:? |at virtual address )([0-9a-f]+)")
+                .unwrap();
+        if lines
+            .iter()
+            .any(|l| l.starts_with("BUG: kernel NULL pointer dereference"))
+        {
+            ExecutionClass::san_find("SEGV", self.access_type(), true)
+        } else if let Some(caps) = lines.iter().find_map(|l| raddress.captures(l)) {
+            let address = u64::from_str_radix(caps.get(1).unwrap().as_str(), 16).unwrap_or(0);
+            ExecutionClass::san_find("SEGV", self.access_type(), is_near_null(address))
+        } else if lines
+            .iter()
+            .any(|l| l.starts_with("general protection fault"))
+        {
+            // KASAN shadow check may find out that address is near null.
+            let near_null = lines.iter().any(|l| l.starts_with("KASAN: null-ptr-deref"));
+            ExecutionClass::san_find("SEGV", None, near_null)
+        } else if lines.iter().any(|l| l.starts_with("kernel BUG at ")) {
+            ExecutionClass::find("kernel-bug")
+        } else if lines.iter().any(|l| l.starts_with("WARNING: ")) {
+            ExecutionClass::find("kernel-warning")
+        } else {
+            Err(Error::Casr(
+                "Couldn't find BUG line in kernel report".to_string(),
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kcsan() {
+        let report: Vec<String> = [
+            "[   10.123456] ==================================================================",
+            "[   10.123457] BUG: KCSAN: data-race in test_kernel_read / test_kernel_write",
+            "[   10.123458] ",
+            "[   10.123459] write to 0xffffffffc009a628 of 8 bytes by task 487 on cpu 0:",
+            "[   10.123460]  test_kernel_write+0x1d/0x30 [kcsan_test]",
+            "[   10.123461]  access_thread+0x89/0xd0 kernel/kcsan/kcsan_test.c:123",
+            "[   10.123462]  ? kthread_park+0x10/0x60",
+            "[   10.123463]  kthread+0x23e/0x260",
+            "[   10.123464]  ret_from_fork+0x22/0x30",
+            "[   10.123465] ",
+            "[   10.123466] read to 0xffffffffc009a628 of 8 bytes by task 488 on cpu 6:",
+            "[   10.123467]  test_kernel_read+0x10/0x20 [kcsan_test]",
+            "[   10.123468]  access_thread+0x89/0xd0",
+            "[   10.123469] ==================================================================",
+        ]
+        .iter()
+        .map(|l| l.to_string())
+        .collect();
+
+        let raw_stacktrace = KernelStacktrace::extract_stacktrace(&report.join("\n"));
+        let Ok(raw_stacktrace) = raw_stacktrace else {
+            panic!("{}", raw_stacktrace.err().unwrap());
+        };
+        assert_eq!(raw_stacktrace.len(), 4);
+
+        let stacktrace = KernelStacktrace::parse_stacktrace(&raw_stacktrace);
+        let Ok(stacktrace) = stacktrace else {
+            panic!("{}", stacktrace.err().unwrap());
+        };
+        assert_eq!(stacktrace[0].function, "test_kernel_write");
+        assert_eq!(stacktrace[0].offset, 0x1d);
+        assert_eq!(stacktrace[0].module, "kcsan_test");
+        assert_eq!(stacktrace[1].function, "access_thread");
+        assert_eq!(stacktrace[1].module, "vmlinux");
+        assert_eq!(stacktrace[1].debug.file, "kernel/kcsan/kcsan_test.c");
+        assert_eq!(stacktrace[1].debug.line, 123);
+        assert_eq!(stacktrace[2].function, "kthread");
+
+        let class = KernelContext(report).severity();
+        let Ok(class) = class else {
+            panic!("{}", class.err().unwrap());
+        };
+        assert_eq!(class.short_description, "data-race");
+    }
+
+    #[test]
+    fn test_kmsan() {
+        let report: Vec<String> = [
+            "=====================================================",
+            "BUG: KMSAN: uninit-value in test_uninit_kmalloc+0xc2/0x150 [kmsan_test]",
+            " test_uninit_kmalloc+0xc2/0x150 [kmsan_test]",
+            " kunit_run_case_internal+0x1f4/0x2e0",
+            "",
+            "Uninit was created at:",
+            " __kmalloc+0x37c/0x640",
+            "",
+            "CPU: 1 PID: 3221 Comm: kunit_try_catch Not tainted 6.1.0-rc1",
+        ]
+        .iter()
+        .map(|l| l.to_string())
+        .collect();
+
+        let raw_stacktrace = KernelStacktrace::extract_stacktrace(&report.join("\n"));
+        let Ok(raw_stacktrace) = raw_stacktrace else {
+            panic!("{}", raw_stacktrace.err().unwrap());
+        };
+        assert_eq!(raw_stacktrace.len(), 2);
+
+        let class = KernelContext(report).severity();
+        let Ok(class) = class else {
+            panic!("{}", class.err().unwrap());
+        };
+        assert_eq!(class.short_description, "use-of-uninitialized-value");
+    }
+
+    #[test]
+    fn test_kasan() {
+        let log: Vec<String> = [
+            "[   42.118210][ T3051] loop0: detected capacity change from 0 to 512",
+            "[   42.131542][ T3051] ==================================================================",
+            "[   42.133071][ T3051] BUG: KASAN: slab-out-of-bounds in ext4_read_inline_data+0x2a6/0x330",
+            "[   42.134402][ T3051] Read of size 60 at addr ffff88801c7a3f5c by task syz-executor.0/3051",
+            "[   42.136010][ T3051] CPU: 1 PID: 3051 Comm: syz-executor.0 Not tainted 6.1.0-rc4-syzkaller #0",
+            "[   42.138802][ T3051] Call Trace:",
+            "[   42.139291][ T3051]  <TASK>",
+            "[   42.139721][ T3051]  dump_stack_lvl+0xcd/0x134",
+            "[   42.140412][ T3051]  print_report+0x15e/0x45d",
+            "[   42.141096][ T3051]  ? ext4_read_inline_data+0x2a6/0x330",
+            "[   42.142561][ T3051]  kasan_report+0xbf/0x1f0",
+            "[   42.144671][ T3051]  ext4_read_inline_data+0x2a6/0x330 fs/ext4/inline.c:207",
+            "[   42.152941][ T3051]  entry_SYSCALL_64_after_hwframe+0x63/0xcd",
+            "[   42.153752][ T3051] RIP: 0033:0x7f3a1c68c0d9",
+            "[   42.156839][ T3051]  </TASK>",
+            "[   42.157585][ T3051] Allocated by task 3051:",
+            "[   42.158221][ T3051]  kasan_save_stack+0x22/0x40 mm/kasan/common.c:45",
+            "[   42.163072][ T3051] ==================================================================",
+            "[   42.164510][ T3051] Kernel panic - not syncing: KASAN: panic_on_warn set ...",
+        ]
+        .iter()
+        .map(|l| l.to_string())
+        .collect();
+
+        let Some(report) = extract_kernel_report(&log) else {
+            panic!("Couldn't extract kernel report");
+        };
+        assert_eq!(report.len(), 17);
+        assert!(report[0].starts_with("====="));
+        assert!(report[1].starts_with("BUG: KASAN: slab-out-of-bounds"));
+        assert!(extract_kernel_report(&log[..1]).is_none());
+
+        let context = KernelContext(report);
+        assert_eq!(
+            context.kernel_release(),
+            Some("6.1.0-rc4-syzkaller".to_string())
+        );
+        assert!(context.rip_frame().is_none());
+        let stacktrace = context.extract_stacktrace();
+        let Ok(stacktrace) = stacktrace else {
+            panic!("{}", stacktrace.err().unwrap());
+        };
+        assert_eq!(stacktrace.len(), 5);
+        assert_eq!(
+            stacktrace[3],
+            " ext4_read_inline_data+0x2a6/0x330 fs/ext4/inline.c:207"
+        );
+
+        let class = context.severity();
+        let Ok(class) = class else {
+            panic!("{}", class.err().unwrap());
+        };
+        assert_eq!(class.short_description, "heap-buffer-overflow(read)");
+
+        for (lines, class) in [
+            (
+                [
+                    "BUG: KASAN: slab-use-after-free in foo+0x1/0x2",
+                    "Write of size 8 at addr ffff888000000000 by task a/1",
+                ],
+                "heap-use-after-free(write)",
+            ),
+            (
+                [
+                    "BUG: KASAN: global-out-of-bounds in foo+0x1/0x2",
+                    "Read of size 1 at addr ffffffff80000000 by task a/1",
+                ],
+                "global-buffer-overflow(read)",
+            ),
+            (
+                [
+                    "BUG: KASAN: stack-out-of-bounds in foo+0x1/0x2",
+                    "Write of size 4 at addr ffffc90000000000 by task a/1",
+                ],
+                "stack-buffer-overflow(write)",
+            ),
+            (
+                ["BUG: KASAN: double-free in foo+0x1/0x2", ""],
+                "double-free",
+            ),
+            (["BUG: KASAN: invalid-free in foo+0x1/0x2", ""], "bad-free"),
+            (
+                [
+                    "BUG: KASAN: null-ptr-deref in foo+0x1/0x2",
+                    "Read of size 8 at addr 0000000000000010 by task a/1",
+                ],
+                "SourceAvNearNull",
+            ),
+            (
+                [
+                    "BUG: KASAN: wild-memory-access in foo+0x1/0x2",
+                    "Write of size 8 at addr dead000000000100 by task a/1",
+                ],
+                "DestAv",
+            ),
+        ] {
+            let context = KernelContext(lines.iter().map(|l| l.to_string()).collect());
+            let Ok(severity) = context.severity() else {
+                panic!("Couldn't estimate severity for {}", lines[0]);
+            };
+            assert_eq!(severity.short_description, class);
+        }
+    }
+
+    #[test]
+    fn test_oops() {
+        let log: Vec<String> = [
+            "[  110.235371] test_module: loading out-of-tree module taints kernel.",
+            "[  110.236548] BUG: kernel NULL pointer dereference, address: 0000000000000008",
+            "[  110.237102] #PF: supervisor write access in kernel mode",
+            "[  110.237502] #PF: error_code(0x0002) - not-present page",
+            "[  110.237904] PGD 0 P4D 0 ",
+            "[  110.238126] Oops: 0002 [#1] PREEMPT SMP NOPTI",
+            "[  110.238470] CPU: 0 PID: 1733 Comm: insmod Tainted: G           O       6.2.0 #1",
+            "[  110.239067] RIP: 0010:test_write+0x12/0x30 [test_module]",
+            "[  110.239497] Code: 00 00 00 00 00 0f 1f 44 00 00 55 48 89 e5 c7 04 25 08 00 00 00 01 00 00 00",
+            "[  110.240310] RSP: 0018:ffffc90000d3fc48 EFLAGS: 00010246",
+            "[  110.240716] RAX: 0000000000000000 RBX: ffff888007a3c000 RCX: 0000000000000001",
+            "[  110.241296] Call Trace:",
+            "[  110.241521]  <TASK>",
+            "[  110.241721]  test_init+0x1b/0x1000 [test_module]",
+            "[  110.242093]  do_one_initcall+0x5b/0x220",
+            "[  110.242392]  do_init_module+0x4a/0x200",
+            "[  110.242738]  </TASK>",
+            "[  110.242940] ---[ end trace 0000000000000000 ]---",
+            "[  110.243310] RIP: 0010:test_write+0x12/0x30 [test_module]",
+        ]
+        .iter()
+        .map(|l| l.to_string())
+        .collect();
+
+        let Some(report) = extract_kernel_report(&log) else {
+            panic!("Couldn't extract kernel report");
+        };
+        assert_eq!(report.len(), 17);
+        assert!(report[0].starts_with("BUG: kernel NULL pointer dereference"));
+
+        let context = KernelContext(report);
+        assert_eq!(context.kernel_release(), Some("6.2.0".to_string()));
+        let registers = context.registers();
+        assert_eq!(registers.get("rsp"), Some(&0xffffc90000d3fc48));
+        assert_eq!(registers.get("eflags"), Some(&0x10246));
+        assert_eq!(registers.get("rbx"), Some(&0xffff888007a3c000));
+        assert_eq!(registers.get("rcx"), Some(&1));
+
+        let stacktrace = context.extract_stacktrace();
+        let Ok(stacktrace) = stacktrace else {
+            panic!("{}", stacktrace.err().unwrap());
+        };
+        assert_eq!(stacktrace.len(), 4);
+        assert_eq!(stacktrace[0], " test_write+0x12/0x30 [test_module]");
+        let stacktrace = KernelStacktrace::parse_stacktrace(&stacktrace);
+        let Ok(stacktrace) = stacktrace else {
+            panic!("{}", stacktrace.err().unwrap());
+        };
+        assert_eq!(stacktrace[0].function, "test_write");
+        assert_eq!(stacktrace[0].module, "test_module");
+        assert_eq!(stacktrace[1].function, "test_init");
+
+        let class = context.severity();
+        let Ok(class) = class else {
+            panic!("{}", class.err().unwrap());
+        };
+        assert_eq!(class.short_description, "DestAvNearNull");
+
+        for (line, class) in [
+            (
+                "BUG: unable to handle page fault for address: ffff888100000000",
+                "AccessViolation",
+            ),
+            (
+                "general protection fault, probably for non-canonical address 0xdffffc0000000002: 0000 [#1] PREEMPT SMP KASAN",
+                "AccessViolation",
+            ),
+            ("kernel BUG at mm/slub.c:379!", "kernel-bug"),
+            (
+                "WARNING: CPU: 0 PID: 1 at kernel/locking/lockdep.c:231 __lock_acquire+0x1/0x2",
+                "kernel-warning",
+            ),
+        ] {
+            let context = KernelContext(vec![line.to_string()]);
+            let Ok(severity) = context.severity() else {
+                panic!("Couldn't estimate severity for {line}");
+            };
+            assert_eq!(severity.short_description, class);
+        }
+    }
+}
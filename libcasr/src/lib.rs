@@ -6,8 +6,17 @@
 //! It can analyze crashes from different sources:
 //!
 //! * AddressSanitizer
+//! * ThreadSanitizer
+//! * MemorySanitizer
+//! * GWP-ASan
+//! * HWAddressSanitizer
+//! * Valgrind Memcheck logs (text and XML)
 //! * UndefinedBehaviorSanitizer
+//! * Linux kernel sanitizers (KASAN, KCSAN, KMSAN) and oops
 //! * Gdb output
+//! * Cdb (Windows debugger) output
+//! * Lldb output (macOS)
+//! * macOS crash reports (`.ips` and `.crash`)
 //!
 //! and program languages:
 //!
@@ -20,22 +29,51 @@
 //!
 //! It could be built with `exploitable` feature for severity estimation crashes
 //! collected from gdb. To save crash reports as json (.casrep/.sarif) use `serde` feature.
+//! `debuginfod` feature allows downloading debug information for stripped modules
+//! from debuginfod servers to symbolize stack traces.
+//!
+//! Crashes may be triaged programmatically without `casr-cluster` via
+//! `stacktrace` module: `similarity` and `is_similar` compare two stack traces,
+//! `dedup` returns indices of unique stack traces, and `cluster_stacktraces`
+//! splits stack traces into clusters. Clustering threshold and linkage strategy
+//! are set by `set_cluster_threshold` and `set_cluster_linkage`.
+//!
+//! Reports may be created without CASR tools via `CrashReport::builder()`, and
+//! `CrashReport::set_from_san_log` fills report from captured sanitizer log.
+//! With `serde` feature `schema` module provides JSON Schema of reports and
+//! validates reports against it.
 
 pub mod asan;
+pub mod cdb;
 pub mod constants;
 pub mod cpp;
+#[cfg(feature = "exploitable")]
+pub mod debug;
 pub mod error;
 pub mod exception;
 pub mod execution_class;
 pub mod gdb;
 pub mod go;
+pub mod gwp_asan;
+pub mod hwasan;
 pub mod java;
 pub mod js;
+pub mod kernel;
+pub mod lldb;
+#[cfg(feature = "serde")]
+pub mod macos;
+pub mod msan;
 pub mod python;
 pub mod report;
 pub mod rust;
 #[cfg(feature = "serde")]
 pub mod sarif;
+#[cfg(feature = "serde")]
+pub mod schema;
 pub mod severity;
 pub mod stacktrace;
+#[cfg(feature = "exploitable")]
+pub mod symbols;
+pub mod tsan;
 pub mod ubsan;
+pub mod valgrind;
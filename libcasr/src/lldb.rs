@@ -0,0 +1,505 @@
+//! Lldb module implements `ParseStacktrace` and `Severity` traits for lldb
+//! output (macOS).
+use regex::Regex;
+
+use crate::constants::{
+    SEGV_MTEAERR, SEGV_MTESERR, SIGINFO_SIGABRT, SIGINFO_SIGBUS, SIGINFO_SIGFPE, SIGINFO_SIGILL,
+    SIGINFO_SIGSEGV, SIGINFO_SIGSYS, SIGINFO_SIGTRAP, SI_KERNEL,
+};
+use crate::error::*;
+use crate::execution_class::{is_near_null, tag_check_failure, ExecutionClass};
+use crate::severity::Severity;
+use crate::stacktrace::*;
+
+use gdb_command::mappings::{File, MappedFiles};
+use gdb_command::memory::MemoryObject;
+use gdb_command::registers::Registers;
+use gdb_command::siginfo::Siginfo;
+
+/// Structure provides an interface for processing the stack trace.
+pub struct LldbStacktrace;
+
+/// Remove arguments from function name, e.g. "main(argc=1, argv=0x7ff7bfeff8d8)".
+fn strip_function_args(function: &str) -> &str {
+    if !function.ends_with(')') {
+        return function;
+    }
+    let mut depth = 0;
+    for (i, c) in function.char_indices().rev() {
+        match c {
+            ')' => depth += 1,
+            '(' => {
+                depth -= 1;
+                if depth == 0 {
+                    return if i == 0 { function } else { &function[..i] };
+                }
+            }
+            _ => {}
+        }
+    }
+    function
+}
+
+impl ParseStacktrace for LldbStacktrace {
+    fn extract_stacktrace(stream: &str) -> Result<Vec<String>> {
+        let frame = Regex::new(r"^[ *]*frame #[0-9]+: ").unwrap();
+        let stacktrace: Vec<String> = stream
+            .split('\n')
+            .map(|l| l.trim_end())
+            .skip_while(|l| !frame.is_match(l))
+            .take_while(|l| frame.is_match(l))
+            .map(|l| l.to_string())
+            .collect();
+        if stacktrace.is_empty() {
+            return Err(Error::Casr(
+                "Couldn't find stack trace in lldb output".to_string(),
+            ));
+        }
+        Ok(stacktrace)
+    }
+
+    fn parse_stacktrace_entry(entry: &str) -> Result<StacktraceEntry> {
+        let mut stentry = StacktraceEntry::default();
+
+        // * frame #0: 0x0000000100003f74 test`main(argc=1) at test.c:5:10
+        let re = Regex::new(r"^[ *]*frame #[0-9]+: 0x([0-9a-f]+)(?: +(.*))?$").unwrap();
+        let Some(caps) = re.captures(entry) else {
+            return Err(Error::Casr(format!(
+                "Couldn't parse lldb stack trace entry: {entry}"
+            )));
+        };
+        let num = caps.get(1).unwrap().as_str();
+        let Ok(address) = u64::from_str_radix(num, 16) else {
+            return Err(Error::Casr(format!("Couldn't parse address: {num}")));
+        };
+        stentry.address = address;
+        let Some(location) = caps.get(2) else {
+            return Ok(stentry);
+        };
+        let mut location = location.as_str().trim();
+
+        // at file:line[:column]
+        let re = Regex::new(r" at (.+?):([0-9]+)(?::([0-9]+))?$").unwrap();
+        if let Some(caps) = re.captures(location) {
+            stentry.debug.file = caps.get(1).unwrap().as_str().to_string();
+            stentry.debug.line = caps.get(2).unwrap().as_str().parse::<u64>().unwrap_or(0);
+            if let Some(column) = caps.get(3) {
+                stentry.debug.column = column.as_str().parse::<u64>().unwrap_or(0);
+            }
+            location = location[..caps.get(0).unwrap().start()].trim();
+        }
+
+        // module`function + offset
+        let re = Regex::new(r" \+ ([0-9]+)$").unwrap();
+        if let Some(caps) = re.captures(location) {
+            stentry.offset = caps.get(1).unwrap().as_str().parse::<u64>().unwrap_or(0);
+            location = location[..caps.get(0).unwrap().start()].trim();
+        }
+        if let Some((module, function)) = location.split_once('`') {
+            stentry.module = module.to_string();
+            stentry.function = strip_function_args(function).to_string();
+        } else {
+            stentry.module = location.to_string();
+        }
+
+        Ok(stentry)
+    }
+}
+
+/// Get memory access type for `EXC_BAD_ACCESS` from exception state
+/// registers: WnR bit of data abort syndrome (`esr`) on aarch64 or page fault
+/// error code (`err`) on x86\_64
+///
+/// # Arguments
+///
+/// * `registers` - registers including exception state
+///
+/// # Return value
+///
+/// True for write access, false for read access, None if access type is unknown
+pub fn is_write_access(registers: &Registers) -> Option<bool> {
+    if let Some(esr) = registers.get("esr") {
+        // Data abort from lower or current exception level.
+        return matches!(esr >> 26, 0x24 | 0x25).then_some(esr & (1 << 6) != 0);
+    }
+    if registers.get("trapno").is_some_and(|trapno| *trapno != 14) {
+        // Not a page fault.
+        return None;
+    }
+    registers.get("err").map(|err| err & 2 != 0)
+}
+
+/// Get execution class for `EXC_BAD_ACCESS` Mach exception
+///
+/// # Arguments
+///
+/// * `address` - faulting address
+///
+/// * `pc` - program counter
+///
+/// * `write` - memory access type (see `is_write_access`)
+pub fn bad_access_class(
+    address: u64,
+    pc: Option<u64>,
+    write: Option<bool>,
+) -> Result<ExecutionClass> {
+    let near_null = is_near_null(address);
+    if pc == Some(address) {
+        return if near_null {
+            ExecutionClass::find("SegFaultOnPcNearNull")
+        } else {
+            ExecutionClass::find("SegFaultOnPc")
+        };
+    }
+    match (write, near_null) {
+        (Some(false), false) => ExecutionClass::find("SourceAv"),
+        (Some(false), true) => ExecutionClass::find("SourceAvNearNull"),
+        (Some(true), false) => ExecutionClass::find("DestAv"),
+        (Some(true), true) => ExecutionClass::find("DestAvNearNull"),
+        (None, _) => ExecutionClass::find("AccessViolation"),
+    }
+}
+
+/// Information about stop reason and stack trace from lldb output.
+pub struct LldbContext(pub Vec<String>);
+
+impl LldbContext {
+    /// Get registers from `register read` lldb command output
+    pub fn registers(&self) -> Registers {
+        //        x0 = 0x0000000000000000
+        //        pc = 0x0000000100003f74  test`crash + 20 at test.c:5:10
+        let rreg = Regex::new(r"^\s*([a-z][a-z0-9]*) = 0x([0-9a-f]+)\b").unwrap();
+        let mut registers = Registers::new();
+        for caps in self.0.iter().filter_map(|l| rreg.captures(l)) {
+            if let Ok(value) = u64::from_str_radix(caps.get(2).unwrap().as_str(), 16) {
+                registers.insert(caps.get(1).unwrap().as_str().to_string(), value);
+            }
+        }
+        registers
+    }
+
+    /// Get signal information from stop reason on Linux, e.g.,
+    /// "signal SIGSEGV: address not mapped to object (fault address: 0x0)"
+    pub fn siginfo(&self) -> Option<Siginfo> {
+        let rstop = Regex::new(r"stop reason = signal (SIG[A-Z]+)(?::\s*(.*))?$").unwrap();
+        let caps = self.0.iter().find_map(|l| rstop.captures(l))?;
+        let si_signo = match caps.get(1).unwrap().as_str() {
+            "SIGSEGV" => SIGINFO_SIGSEGV,
+            "SIGBUS" => SIGINFO_SIGBUS,
+            "SIGILL" => SIGINFO_SIGILL,
+            "SIGFPE" => SIGINFO_SIGFPE,
+            "SIGABRT" => SIGINFO_SIGABRT,
+            "SIGTRAP" => SIGINFO_SIGTRAP,
+            "SIGSYS" => SIGINFO_SIGSYS,
+            _ => return None,
+        };
+        let details = caps.get(2).map(|m| m.as_str()).unwrap_or_default();
+        let si_code = match si_signo {
+            SIGINFO_SIGSEGV if details.contains("async tag check fault") => SEGV_MTEAERR,
+            SIGINFO_SIGSEGV if details.contains("sync tag check fault") => SEGV_MTESERR,
+            SIGINFO_SIGSEGV if details.contains("permissions") => 2,
+            SIGINFO_SIGSEGV
+                if details.contains("kernel") || details.contains("general protection") =>
+            {
+                SI_KERNEL
+            }
+            SIGINFO_SIGSEGV => 1,
+            SIGINFO_SIGBUS if details.contains("nonexistent") => 2,
+            SIGINFO_SIGBUS if details.contains("hardware") => 3,
+            SIGINFO_SIGBUS => 1,
+            _ => 0,
+        };
+        let raddress = Regex::new(r"fault address[:=] ?0x([0-9a-f]+)").unwrap();
+        let si_addr = raddress
+            .captures(details)
+            .and_then(|caps| u64::from_str_radix(caps.get(1).unwrap().as_str(), 16).ok())
+            .unwrap_or_default();
+        Some(Siginfo {
+            si_signo,
+            si_code,
+            si_errno: 0,
+            si_addr,
+        })
+    }
+
+    /// Get memory mappings from `memory region --all` lldb command output.
+    /// Lldb doesn't print file offsets, so they are computed from the first
+    /// region of the same file.
+    pub fn mappings(&self) -> MappedFiles {
+        // [0x0000555555554000-0x0000555555555000) r-- /tmp/test PT_LOAD[0]
+        let rregion =
+            Regex::new(r"^\[0x([0-9a-f]+)-0x([0-9a-f]+)\) [r-][w-][x-](?: (\S+))?").unwrap();
+        let mut mappings = MappedFiles::new();
+        let mut bases: Vec<(String, u64)> = Vec::new();
+        for caps in self.0.iter().filter_map(|l| rregion.captures(l)) {
+            let Some(name) = caps.get(3).map(|m| m.as_str()) else {
+                continue;
+            };
+            let (Ok(start), Ok(end)) = (
+                u64::from_str_radix(caps.get(1).unwrap().as_str(), 16),
+                u64::from_str_radix(caps.get(2).unwrap().as_str(), 16),
+            ) else {
+                continue;
+            };
+            let base = if let Some((_, base)) = bases.iter().find(|(path, _)| path == name) {
+                *base
+            } else {
+                bases.push((name.to_string(), start));
+                start
+            };
+            mappings.push(File::new(start, end, start - base, name));
+        }
+        mappings
+    }
+
+    /// Get memory at pc from `memory read -s1 -fx` lldb command output
+    pub fn pc_memory(&self) -> Result<MemoryObject> {
+        // 0x555555555149: 0xc7 0x00 0x00 0x00 0x00 0x00 0x00 0x00
+        let rmemory = Regex::new(r"^0x([0-9a-f]+): ((?:0x[0-9a-f]{2}\s*)+)").unwrap();
+        let mut memory = MemoryObject::default();
+        for caps in self.0.iter().filter_map(|l| rmemory.captures(l)) {
+            let address = u64::from_str_radix(caps.get(1).unwrap().as_str(), 16).unwrap_or(0);
+            if memory.data.is_empty() {
+                memory.address = address;
+            } else if address != memory.address + memory.data.len() as u64 {
+                break;
+            }
+            memory.data.extend(
+                caps.get(2)
+                    .unwrap()
+                    .as_str()
+                    .split_whitespace()
+                    .filter_map(|byte| u8::from_str_radix(byte.trim_start_matches("0x"), 16).ok()),
+            );
+        }
+        if memory.data.is_empty() {
+            return Err(Error::Casr(
+                "Couldn't find memory at pc in lldb output".to_string(),
+            ));
+        }
+        Ok(memory)
+    }
+}
+
+impl Severity for LldbContext {
+    fn severity(&self) -> Result<ExecutionClass> {
+        let rstop = Regex::new(r"stop reason = (?:signal )?([A-Z_]+)(?: \((.*)\))?").unwrap();
+        let Some(caps) = self.0.iter().find_map(|l| rstop.captures(l)) else {
+            return Err(Error::Casr(
+                "Couldn't find stop reason in lldb output".to_string(),
+            ));
+        };
+        let details = caps.get(2).map(|m| m.as_str()).unwrap_or_default();
+        match caps.get(1).unwrap().as_str() {
+            "EXC_BAD_ACCESS" => {
+                let raddress = Regex::new(r"address=0x([0-9a-f]+)").unwrap();
+                let Some(address) = raddress
+                    .captures(details)
+                    .and_then(|caps| u64::from_str_radix(caps.get(1).unwrap().as_str(), 16).ok())
+                else {
+                    return ExecutionClass::find("AccessViolation");
+                };
+                // Mach exception doesn't tell access type, it is taken from
+                // exception state registers if they are read. Jump to bad
+                // address is detected by pc.
+                let rpc = Regex::new(r"frame #0: 0x([0-9a-f]+)").unwrap();
+                let pc = self
+                    .0
+                    .iter()
+                    .find_map(|l| rpc.captures(l))
+                    .and_then(|caps| u64::from_str_radix(caps.get(1).unwrap().as_str(), 16).ok());
+                bad_access_class(address, pc, is_write_access(&self.registers()))
+            }
+            "EXC_BAD_INSTRUCTION" | "SIGILL" => ExecutionClass::find("BadInstruction"),
+            "EXC_ARITHMETIC" | "SIGFPE" => ExecutionClass::find("FPE"),
+            "EXC_BREAKPOINT" | "SIGTRAP" => ExecutionClass::find("TrapSignal"),
+            "SIGABRT" => ExecutionClass::find("AbortSignal"),
+            "EXC_CRASH" if details.contains("SIGABRT") => ExecutionClass::find("AbortSignal"),
+            "SIGSEGV" | "SIGBUS" => match self.siginfo() {
+                // ARM MTE tag check fault.
+                Some(siginfo) if matches!(siginfo.si_code, SEGV_MTEAERR | SEGV_MTESERR) => {
+                    tag_check_failure(siginfo.si_addr, siginfo.si_code == SEGV_MTESERR)
+                }
+                _ => ExecutionClass::find("AccessViolation"),
+            },
+            reason => Err(Error::Casr(format!("Unsupported stop reason: {reason}"))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stacktrace::tests::safe_init_ignore_stack_frames;
+
+    #[test]
+    fn test_lldb_stacktrace() {
+        let output = "(lldb) bt
+* thread #1, queue = 'com.apple.main-thread', stop reason = EXC_BAD_ACCESS (code=1, address=0x0)
+  * frame #0: 0x0000000100003f74 test`crash(p=0x0000000000000000) at test.c:5:10
+    frame #1: 0x0000000100003f9c test`main(argc=1, argv=0x00007ff7bfeff8d8) at test.c:10:3
+    frame #2: 0x00007ff80a6f241f dyld`start + 1903
+    frame #3: 0x0000000100003fa0
+(lldb) quit";
+
+        let raw_stacktrace = LldbStacktrace::extract_stacktrace(output);
+        let Ok(raw_stacktrace) = raw_stacktrace else {
+            panic!("{}", raw_stacktrace.err().unwrap());
+        };
+        assert_eq!(raw_stacktrace.len(), 4);
+
+        let stacktrace = LldbStacktrace::parse_stacktrace(&raw_stacktrace);
+        let Ok(stacktrace) = stacktrace else {
+            panic!("{}", stacktrace.err().unwrap());
+        };
+        assert_eq!(stacktrace[0].address, 0x100003f74);
+        assert_eq!(stacktrace[0].module, "test");
+        assert_eq!(stacktrace[0].function, "crash");
+        assert_eq!(stacktrace[0].debug.file, "test.c");
+        assert_eq!(stacktrace[0].debug.line, 5);
+        assert_eq!(stacktrace[0].debug.column, 10);
+        assert_eq!(stacktrace[2].module, "dyld");
+        assert_eq!(stacktrace[2].function, "start");
+        assert_eq!(stacktrace[2].offset, 1903);
+        assert_eq!(stacktrace[3].address, 0x100003fa0);
+
+        safe_init_ignore_stack_frames();
+        let Ok(crashline) = stacktrace.crash_line() else {
+            panic!("Couldn't get crash line");
+        };
+        assert_eq!(crashline.to_string(), "test.c:5:10");
+
+        let class = LldbContext(output.split('\n').map(|l| l.to_string()).collect()).severity();
+        let Ok(class) = class else {
+            panic!("{}", class.err().unwrap());
+        };
+        assert_eq!(class.short_description, "AccessViolation");
+    }
+
+    #[test]
+    fn test_lldb_severity() {
+        let context = |lines: &[&str]| LldbContext(lines.iter().map(|l| l.to_string()).collect());
+        let class = context(&[
+            "* thread #1, stop reason = EXC_BAD_ACCESS (code=1, address=0x10)",
+            "  * frame #0: 0x0000000000000010",
+        ])
+        .severity();
+        let Ok(class) = class else {
+            panic!("{}", class.err().unwrap());
+        };
+        assert_eq!(class.short_description, "SegFaultOnPcNearNull");
+
+        let class = context(&[
+            "* thread #1, stop reason = EXC_BAD_INSTRUCTION (code=EXC_I386_INVOP, subcode=0x0)",
+        ])
+        .severity();
+        let Ok(class) = class else {
+            panic!("{}", class.err().unwrap());
+        };
+        assert_eq!(class.short_description, "BadInstruction");
+
+        let class = context(&["* thread #1, stop reason = signal SIGABRT"]).severity();
+        let Ok(class) = class else {
+            panic!("{}", class.err().unwrap());
+        };
+        assert_eq!(class.short_description, "AbortSignal");
+
+        // Access type from exception state registers.
+        let output = context(&[
+            "* thread #1, stop reason = EXC_BAD_ACCESS (code=1, address=0x8)",
+            "  * frame #0: 0x0000000100003f74 test`crash(p=0x0000000000000000) at test.c:5:10",
+            "General Purpose Registers:",
+            "        x0 = 0x0000000000000000",
+            "        pc = 0x0000000100003f74  test`crash + 20 at test.c:5:10",
+            "Exception State Registers:",
+            "       far = 0x0000000000000008",
+            "       esr = 0x92000046",
+        ]);
+        let registers = output.registers();
+        assert_eq!(registers.get("pc"), Some(&0x100003f74));
+        assert_eq!(registers.get("esr"), Some(&0x92000046));
+        assert_eq!(is_write_access(&registers), Some(true));
+        let Ok(class) = output.severity() else {
+            panic!("Couldn't estimate severity");
+        };
+        assert_eq!(class.short_description, "DestAvNearNull");
+
+        let mut registers = Registers::new();
+        registers.insert("trapno".to_string(), 14);
+        registers.insert("err".to_string(), 4);
+        assert_eq!(is_write_access(&registers), Some(false));
+        registers.insert("trapno".to_string(), 13);
+        assert_eq!(is_write_access(&registers), None);
+        let Ok(class) = bad_access_class(0xdeadbeef, Some(0x100003f74), Some(false)) else {
+            panic!("Couldn't estimate severity");
+        };
+        assert_eq!(class.short_description, "SourceAv");
+    }
+
+    #[test]
+    fn test_lldb_linux_context() {
+        let output = "(lldb) bt
+* thread #1, name = 'test_destAv', stop reason = signal SIGSEGV: address not mapped to object (fault address: 0x10)
+  * frame #0: 0x0000555555555149 test_destAv`crash at test.c:3:8
+    frame #1: 0x0000555555555170 test_destAv`main at test.c:8:3
+(lldb) memory region --all
+[0x0000000000000000-0x0000555555554000) ---
+[0x0000555555554000-0x0000555555555000) r-- /tmp/test_destAv PT_LOAD[0]
+[0x0000555555555000-0x0000555555556000) r-x /tmp/test_destAv PT_LOAD[1]
+[0x00007ffffffde000-0x00007ffffffff000) rw- [stack]
+(lldb) memory read -s1 -fx -c16 $pc
+0x555555555149: 0xc7 0x00 0x2a 0x00 0x00 0x00 0x90 0x5d
+0x555555555151: 0xc3 0x55 0x48 0x89 0xe5 0x48 0x83 0xec
+(lldb) quit";
+        let context = LldbContext(output.lines().map(|l| l.to_string()).collect());
+
+        let Some(siginfo) = context.siginfo() else {
+            panic!("Couldn't get siginfo");
+        };
+        assert_eq!(siginfo.si_signo, SIGINFO_SIGSEGV);
+        assert_eq!(siginfo.si_code, 1);
+        assert_eq!(siginfo.si_addr, 0x10);
+
+        let mappings = context.mappings();
+        assert_eq!(mappings.len(), 3);
+        assert_eq!(mappings[1].start, 0x555555555000);
+        assert_eq!(mappings[1].offset, 0x1000);
+        assert_eq!(mappings[1].name, "/tmp/test_destAv");
+        assert_eq!(mappings[2].name, "[stack]");
+
+        let memory = context.pc_memory();
+        let Ok(memory) = memory else {
+            panic!("{}", memory.err().unwrap());
+        };
+        assert_eq!(memory.address, 0x555555555149);
+        assert_eq!(memory.data.len(), 16);
+        assert_eq!(&memory.data[..3], &[0xc7, 0x00, 0x2a]);
+
+        let context = LldbContext(vec![
+            "* thread #1, stop reason = signal SIGSEGV: sent by kernel (fault address=0x0)"
+                .to_string(),
+        ]);
+        let Some(siginfo) = context.siginfo() else {
+            panic!("Couldn't get siginfo");
+        };
+        assert_eq!(siginfo.si_code, SI_KERNEL);
+
+        let context = LldbContext(vec![
+            "* thread #1, stop reason = signal SIGSEGV: sync tag check fault (fault address: 0xa00ffff8a400010)"
+                .to_string(),
+        ]);
+        let Some(siginfo) = context.siginfo() else {
+            panic!("Couldn't get siginfo");
+        };
+        assert_eq!(siginfo.si_code, SEGV_MTESERR);
+        assert_eq!(siginfo.si_addr, 0xa00ffff8a400010);
+        let Ok(class) = context.severity() else {
+            panic!("Couldn't estimate severity");
+        };
+        assert_eq!(class.short_description, "TagCheckFailure");
+        assert_eq!(
+            class.description,
+            "Memory tag check failure (address tag 0xa)"
+        );
+    }
+}
@@ -0,0 +1,485 @@
+//! Macos module parses macOS ReportCrash crash reports: `.ips` JSON reports
+//! (macOS 12+) and legacy `.crash` text reports.
+use regex::Regex;
+use serde_json::Value;
+
+use crate::error::*;
+use crate::execution_class::ExecutionClass;
+use crate::lldb::{bad_access_class, is_write_access};
+use crate::severity::Severity;
+
+use gdb_command::registers::Registers;
+
+/// Loaded image (executable or library) from crash report.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MacImage {
+    /// Image path.
+    pub path: String,
+    /// Load address.
+    pub base: u64,
+    /// Image size.
+    pub size: u64,
+}
+
+/// Information about crash from macOS crash report.
+#[derive(Clone, Debug, Default)]
+pub struct MacCrash {
+    /// Crashed process path.
+    pub process_path: String,
+    /// OS version, e.g., "macOS 13.4 (22F66)".
+    pub os_version: String,
+    /// CPU type, e.g., "ARM-64" or "X86-64".
+    pub architecture: String,
+    /// Mach exception type, e.g., "EXC_BAD_ACCESS".
+    pub exception_type: String,
+    /// Signal name, e.g., "SIGSEGV".
+    pub signal: String,
+    /// Exception subtype, e.g., "KERN_INVALID_ADDRESS at 0x0000000000000000".
+    pub exception_subtype: String,
+    /// Stack trace of crashed thread in gdb format.
+    pub stacktrace: Vec<String>,
+    /// Registers of crashed thread including exception state (esr, far, err).
+    pub registers: Registers,
+    /// Loaded images.
+    pub images: Vec<MacImage>,
+}
+
+impl MacCrash {
+    /// Check that data looks like macOS crash report
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - crash report content
+    pub fn is_mac_crash(data: &str) -> bool {
+        let header = data.lines().next().unwrap_or_default();
+        (header.starts_with('{') && header.contains("\"bug_type\""))
+            || data.lines().any(|l| l.starts_with("Exception Type:"))
+    }
+
+    /// Parse `.ips` or `.crash` report
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - crash report content
+    pub fn parse(data: &str) -> Result<Self> {
+        if data.trim_start().starts_with('{') {
+            Self::from_ips(data)
+        } else {
+            Self::from_crash(data)
+        }
+    }
+
+    /// Parse `.ips` report: JSON header line followed by JSON body
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - crash report content
+    pub fn from_ips(data: &str) -> Result<Self> {
+        let (header, body) = data.trim_start().split_once('\n').unwrap_or(("{}", data));
+        let header: Value = serde_json::from_str(header)
+            .map_err(|e| Error::Casr(format!("Couldn't parse .ips header: {e}")))?;
+        let body: Value = serde_json::from_str(body)
+            .map_err(|e| Error::Casr(format!("Couldn't parse .ips body: {e}")))?;
+
+        let string = |value: &Value| value.as_str().unwrap_or_default().to_string();
+        let mut crash = MacCrash {
+            process_path: string(&body["procPath"]),
+            architecture: string(&body["cpuType"]),
+            exception_type: string(&body["exception"]["type"]),
+            signal: string(&body["exception"]["signal"]),
+            exception_subtype: string(&body["exception"]["subtype"]),
+            ..Default::default()
+        };
+        crash.os_version = if let Some(os) = header["os_version"].as_str() {
+            os.to_string()
+        } else {
+            format!(
+                "{} ({})",
+                string(&body["osVersion"]["train"]),
+                string(&body["osVersion"]["build"])
+            )
+        };
+        if crash.exception_type.is_empty() {
+            return Err(Error::Casr(
+                "Couldn't find exception in .ips report".to_string(),
+            ));
+        }
+
+        let images = body["usedImages"].as_array().cloned().unwrap_or_default();
+        crash.images = images
+            .iter()
+            .map(|image| MacImage {
+                path: image["path"]
+                    .as_str()
+                    .or(image["name"].as_str())
+                    .unwrap_or("???")
+                    .to_string(),
+                base: image["base"].as_u64().unwrap_or_default(),
+                size: image["size"].as_u64().unwrap_or_default(),
+            })
+            .collect();
+
+        let threads = body["threads"].as_array().cloned().unwrap_or_default();
+        let thread = if let Some(idx) = body["faultingThread"].as_u64() {
+            threads.get(idx as usize)
+        } else {
+            threads.iter().find(|thread| thread["triggered"] == true)
+        };
+        let Some(thread) = thread else {
+            return Err(Error::Casr(
+                "Couldn't find crashed thread in .ips report".to_string(),
+            ));
+        };
+
+        // Registers: {"rax": {"value": 0}, ...} on x86_64 and
+        // {"x": [{"value": 0}, ...], "pc": {"value": ...}, ...} on aarch64.
+        if let Some(state) = thread["threadState"].as_object() {
+            for (name, value) in state {
+                if let Some(value) = value["value"].as_u64() {
+                    crash.registers.insert(name.clone(), value);
+                } else if let Some(values) = value.as_array() {
+                    for (i, value) in values.iter().enumerate() {
+                        if let Some(value) = value["value"].as_u64() {
+                            crash.registers.insert(format!("{name}{i}"), value);
+                        }
+                    }
+                }
+            }
+        }
+
+        let frames = thread["frames"].as_array().cloned().unwrap_or_default();
+        for (i, frame) in frames.iter().enumerate() {
+            let image = frame["imageIndex"]
+                .as_u64()
+                .and_then(|idx| crash.images.get(idx as usize));
+            let address = image.map(|image| image.base).unwrap_or_default()
+                + frame["imageOffset"].as_u64().unwrap_or_default();
+            crash.stacktrace.push(gdb_frame(
+                i,
+                address,
+                frame["symbol"].as_str(),
+                frame["sourceFile"]
+                    .as_str()
+                    .zip(frame["sourceLine"].as_u64()),
+                image.map(|image| image.path.as_str()),
+            ));
+        }
+
+        Ok(crash)
+    }
+
+    /// Parse legacy `.crash` text report
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - crash report content
+    pub fn from_crash(data: &str) -> Result<Self> {
+        let field = |name: &str| {
+            data.lines()
+                .find_map(|l| l.strip_prefix(name))
+                .map(|value| value.trim().to_string())
+                .unwrap_or_default()
+        };
+        let mut crash = MacCrash {
+            process_path: field("Path:"),
+            os_version: field("OS Version:"),
+            exception_subtype: field("Exception Codes:"),
+            ..Default::default()
+        };
+        crash.architecture = field("Code Type:")
+            .split_whitespace()
+            .next()
+            .unwrap_or_default()
+            .to_string();
+        // Exception Type:        EXC_BAD_ACCESS (SIGSEGV)
+        let exception = field("Exception Type:");
+        let (exception_type, signal) = exception
+            .split_once(' ')
+            .unwrap_or((exception.as_str(), ""));
+        crash.exception_type = exception_type.to_string();
+        crash.signal = signal
+            .trim()
+            .trim_start_matches('(')
+            .trim_end_matches(')')
+            .to_string();
+        if crash.exception_type.is_empty() {
+            return Err(Error::Casr(
+                "Couldn't find exception in .crash report".to_string(),
+            ));
+        }
+
+        // 0x10c4e3000 -        0x10c4e3fff +test (0) <5D4B...> /Users/user/test
+        let rimage =
+            Regex::new(r"^\s*0x([0-9a-f]+)\s*-\s*0x([0-9a-f]+)\s+.*<[0-9A-Fa-f-]+>\s+(.+)$")
+                .unwrap();
+        crash.images = data
+            .lines()
+            .skip_while(|l| !l.starts_with("Binary Images:"))
+            .filter_map(|l| rimage.captures(l))
+            .filter_map(|caps| {
+                let base = u64::from_str_radix(caps.get(1).unwrap().as_str(), 16).ok()?;
+                let end = u64::from_str_radix(caps.get(2).unwrap().as_str(), 16).ok()?;
+                Some(MacImage {
+                    path: caps.get(3).unwrap().as_str().trim().to_string(),
+                    base,
+                    size: end.saturating_sub(base) + 1,
+                })
+            })
+            .collect();
+
+        // 0   test                          0x000000010c4e3f74 crash + 20 (test.c:5)
+        let rframe = Regex::new(r"^[0-9]+\s+(\S+)\s+0x([0-9a-f]+) (.*)$").unwrap();
+        let rsource = Regex::new(r" \((.+):([0-9]+)\)$").unwrap();
+        let rsymbol = Regex::new(r"^(.+?) \+ [0-9]+$").unwrap();
+        let frames = data
+            .lines()
+            .skip_while(|l| !(l.starts_with("Thread ") && l.contains(" Crashed")))
+            .skip(1)
+            .take_while(|l| !l.trim().is_empty());
+        for (i, line) in frames.enumerate() {
+            let Some(caps) = rframe.captures(line) else {
+                continue;
+            };
+            let module = caps.get(1).unwrap().as_str();
+            let Ok(address) = u64::from_str_radix(caps.get(2).unwrap().as_str(), 16) else {
+                continue;
+            };
+            let mut location = caps.get(3).unwrap().as_str().trim();
+            let mut source = None;
+            if let Some(caps) = rsource.captures(location) {
+                source = Some((
+                    caps.get(1).unwrap().as_str(),
+                    caps.get(2).unwrap().as_str().parse::<u64>().unwrap_or(0),
+                ));
+                location = &location[..caps.get(0).unwrap().start()];
+            }
+            // Frames without symbols: 0x10c4e3000 + 3956
+            let symbol = rsymbol
+                .captures(location)
+                .map(|caps| caps.get(1).unwrap().as_str())
+                .filter(|symbol| !symbol.starts_with("0x"));
+            let path = crash
+                .images
+                .iter()
+                .find(|image| image.base <= address && address < image.base + image.size)
+                .map(|image| image.path.as_str())
+                .unwrap_or(module);
+            crash
+                .stacktrace
+                .push(gdb_frame(i, address, symbol, source, Some(path)));
+        }
+
+        // Thread 0 crashed with ARM Thread State (64-bit):
+        //     x0: 0x0000000000000000   x1: 0x000000016fdff4a8
+        //    far: 0x0000000000000000  esr: 0x92000046 (Data Abort) byte write Translation fault
+        // Error Code:      0x00000006 (no mapping for user data write)
+        // Trap Number:     14
+        let rreg = Regex::new(r"\b([a-z][a-z0-9]*): 0x([0-9a-f]+)\b").unwrap();
+        for line in data
+            .lines()
+            .skip_while(|l| !l.contains("crashed with") || !l.contains("Thread State"))
+            .skip(1)
+            .take_while(|l| !l.trim().is_empty())
+        {
+            for caps in rreg.captures_iter(line) {
+                if let Ok(value) = u64::from_str_radix(caps.get(2).unwrap().as_str(), 16) {
+                    crash
+                        .registers
+                        .insert(caps.get(1).unwrap().as_str().to_string(), value);
+                }
+            }
+        }
+        let hex = |s: &str| {
+            let s = s.split_whitespace().next().unwrap_or_default();
+            u64::from_str_radix(s.trim_start_matches("0x"), 16).ok()
+        };
+        if let Some(err) = hex(&field("Error Code:")) {
+            crash.registers.insert("err".to_string(), err);
+        }
+        if let Ok(trapno) = field("Trap Number:").parse::<u64>() {
+            crash.registers.insert("trapno".to_string(), trapno);
+        }
+
+        Ok(crash)
+    }
+
+    /// Get faulting address from exception subtype
+    pub fn address(&self) -> Option<u64> {
+        let raddress = Regex::new(r"at 0x([0-9a-fA-F]+)").unwrap();
+        raddress
+            .captures(&self.exception_subtype)
+            .and_then(|caps| u64::from_str_radix(caps.get(1).unwrap().as_str(), 16).ok())
+    }
+
+    /// Get program counter of crashed thread
+    pub fn pc(&self) -> Option<u64> {
+        ["pc", "rip"]
+            .iter()
+            .find_map(|name| self.registers.get(*name).copied())
+    }
+}
+
+impl Severity for MacCrash {
+    fn severity(&self) -> Result<ExecutionClass> {
+        match self.exception_type.as_str() {
+            "EXC_BAD_ACCESS" => {
+                let Some(address) = self.address() else {
+                    return ExecutionClass::find("AccessViolation");
+                };
+                bad_access_class(address, self.pc(), is_write_access(&self.registers))
+            }
+            "EXC_BAD_INSTRUCTION" => ExecutionClass::find("BadInstruction"),
+            "EXC_ARITHMETIC" => ExecutionClass::find("FPE"),
+            "EXC_BREAKPOINT" => ExecutionClass::find("TrapSignal"),
+            "EXC_CRASH" if self.signal.contains("SIGABRT") => ExecutionClass::find("AbortSignal"),
+            exception => Err(Error::Casr(format!(
+                "Unsupported exception type: {exception} ({})",
+                self.signal
+            ))),
+        }
+    }
+}
+
+/// Format stack trace frame in gdb format
+///
+/// # Arguments
+///
+/// * `num` - frame number
+///
+/// * `address` - frame address
+///
+/// * `symbol` - function name
+///
+/// * `source` - source file and line
+///
+/// * `module` - module path
+fn gdb_frame(
+    num: usize,
+    address: u64,
+    symbol: Option<&str>,
+    source: Option<(&str, u64)>,
+    module: Option<&str>,
+) -> String {
+    let mut frame = format!("#{num}  {address:#018x} in {} ()", symbol.unwrap_or("??"));
+    if let Some((file, line)) = source {
+        frame.push_str(&format!(" at {file}:{line}"));
+    } else if let Some(module) = module {
+        frame.push_str(&format!(" from {module}"));
+    }
+    frame
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gdb::GdbStacktrace;
+    use crate::stacktrace::ParseStacktrace;
+
+    #[test]
+    fn test_ips_report() {
+        let ips = r#"{"app_name":"test","timestamp":"2023-06-01 12:00:00.00 +0300","bug_type":"309","os_version":"macOS 13.4 (22F66)","name":"test"}
+{
+  "procPath" : "/Users/user/test",
+  "cpuType" : "ARM-64",
+  "exception" : {"codes":"0x0000000000000001, 0x0000000000000000","rawCodes":[1,0],"type":"EXC_BAD_ACCESS","signal":"SIGSEGV","subtype":"KERN_INVALID_ADDRESS at 0x0000000000000000"},
+  "faultingThread" : 0,
+  "threads" : [{"triggered":true,"id":1234,"threadState":{"x":[{"value":0},{"value":6171915432}],"fp":{"value":6171915408},"lr":{"value":4294983580},"sp":{"value":6171915392},"pc":{"value":4294983540,"matchesCrashFrame":1},"cpsr":{"value":1610616832},"far":{"value":0},"esr":{"value":2449473606,"description":"(Data Abort) byte write Translation fault"},"flavor":"ARM_THREAD_STATE64"},"frames":[{"imageOffset":16244,"sourceLine":5,"sourceFile":"test.c","symbol":"crash","imageIndex":0,"symbolLocation":20},{"imageOffset":16284,"symbol":"main","imageIndex":0,"symbolLocation":28},{"imageOffset":21536,"symbol":"start","imageIndex":1,"symbolLocation":2544}]}],
+  "usedImages" : [{"source":"P","arch":"arm64","base":4294967296,"size":16384,"path":"/Users/user/test","name":"test"},{"source":"P","arch":"arm64e","base":6442450944,"size":566448,"path":"/usr/lib/dyld","name":"dyld"}]
+}"#;
+        assert!(MacCrash::is_mac_crash(ips));
+        let crash = MacCrash::parse(ips);
+        let Ok(crash) = crash else {
+            panic!("{}", crash.err().unwrap());
+        };
+        assert_eq!(crash.process_path, "/Users/user/test");
+        assert_eq!(crash.os_version, "macOS 13.4 (22F66)");
+        assert_eq!(crash.architecture, "ARM-64");
+        assert_eq!(crash.signal, "SIGSEGV");
+        assert_eq!(crash.address(), Some(0));
+        assert_eq!(crash.pc(), Some(0x100003f74));
+        assert_eq!(crash.registers.get("x1"), Some(&6171915432));
+        assert_eq!(crash.images.len(), 2);
+        assert_eq!(
+            crash.stacktrace,
+            vec![
+                "#0  0x0000000100003f74 in crash () at test.c:5",
+                "#1  0x0000000100003f9c in main () from /Users/user/test",
+                "#2  0x0000000180005420 in start () from /usr/lib/dyld",
+            ]
+        );
+        let Ok(stacktrace) = GdbStacktrace::parse_stacktrace(&crash.stacktrace) else {
+            panic!("Couldn't parse stack trace");
+        };
+        assert_eq!(stacktrace[0].function, "crash");
+        assert_eq!(stacktrace[0].debug.line, 5);
+
+        let Ok(class) = crash.severity() else {
+            panic!("Couldn't estimate severity");
+        };
+        assert_eq!(class.short_description, "DestAvNearNull");
+    }
+
+    #[test]
+    fn test_crash_report() {
+        let report = "Process:               test [12345]
+Path:                  /Users/user/test
+Code Type:             X86-64 (Native)
+OS Version:            macOS 11.6 (20G165)
+
+Exception Type:        EXC_BAD_ACCESS (SIGSEGV)
+Exception Codes:       KERN_INVALID_ADDRESS at 0x00000000deadbeef
+Exception Note:        EXC_CORPSE_NOTIFY
+
+Thread 0 Crashed::  Dispatch queue: com.apple.main-thread
+0   test                          0x000000010c4e3f74 crash + 20 (test.c:5)
+1   test                          0x000000010c4e3f9c 0x10c4e3000 + 3996
+2   libdyld.dylib                 0x00007fff2035bf3d start + 1
+
+Thread 0 crashed with X86 Thread State (64-bit):
+  rax: 0x00000000deadbeef  rbx: 0x0000000000000000  rcx: 0x0000000000000001
+  rip: 0x000000010c4e3f74  rfl: 0x0000000000010246  cr2: 0x00000000deadbeef
+
+Logical CPU:     0
+Error Code:      0x00000004 (no mapping for user data read)
+Trap Number:     14
+
+Binary Images:
+       0x10c4e3000 -        0x10c4e3fff +test (0) <5D4B5F3E-1C2A-3B4C-8D9E-0F1A2B3C4D5E> /Users/user/test
+    0x7fff20358000 -     0x7fff2038bfff libdyld.dylib (852.2) <A1B2C3D4-E5F6-4789-8ABC-DEF012345678> /usr/lib/system/libdyld.dylib
+";
+        assert!(MacCrash::is_mac_crash(report));
+        let crash = MacCrash::parse(report);
+        let Ok(crash) = crash else {
+            panic!("{}", crash.err().unwrap());
+        };
+        assert_eq!(crash.exception_type, "EXC_BAD_ACCESS");
+        assert_eq!(crash.signal, "SIGSEGV");
+        assert_eq!(crash.architecture, "X86-64");
+        assert_eq!(crash.pc(), Some(0x10c4e3f74));
+        assert_eq!(crash.registers.get("err"), Some(&4));
+        assert_eq!(crash.images[1].path, "/usr/lib/system/libdyld.dylib");
+        assert_eq!(
+            crash.stacktrace,
+            vec![
+                "#0  0x000000010c4e3f74 in crash () at test.c:5",
+                "#1  0x000000010c4e3f9c in ?? () from /Users/user/test",
+                "#2  0x00007fff2035bf3d in start () from /usr/lib/system/libdyld.dylib",
+            ]
+        );
+        let Ok(class) = crash.severity() else {
+            panic!("Couldn't estimate severity");
+        };
+        assert_eq!(class.short_description, "SourceAv");
+
+        let crash = MacCrash::parse(
+            "Exception Type:        EXC_BAD_INSTRUCTION (SIGILL)\nException Codes:       0x0000000000000001, 0x0000000000000000\n",
+        );
+        let Ok(crash) = crash else {
+            panic!("{}", crash.err().unwrap());
+        };
+        let Ok(class) = crash.severity() else {
+            panic!("Couldn't estimate severity");
+        };
+        assert_eq!(class.short_description, "BadInstruction");
+    }
+}
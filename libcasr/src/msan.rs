@@ -0,0 +1,308 @@
+//! Msan module implements `Severity` trait and origin chain extraction for
+//! MemorySanitizer reports.
+use regex::Regex;
+
+use crate::asan::AsanStacktrace;
+use crate::error::*;
+use crate::execution_class::ExecutionClass;
+use crate::severity::Severity;
+use crate::stacktrace::*;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Origin of uninitialized value from MemorySanitizer report
+/// (target should be built with `-fsanitize-memory-track-origins`).
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MsanOrigin {
+    /// How uninitialized value was created, e.g. "a heap allocation".
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename(serialize = "Description", deserialize = "Description"))
+    )]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub description: String,
+    /// Stack trace where uninitialized value was created.
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename(serialize = "Stacktrace", deserialize = "Stacktrace"))
+    )]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub stacktrace: Vec<String>,
+    /// Stack traces of intermediate stores of uninitialized value
+    /// (from the latest to the earliest one).
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename(serialize = "Copies", deserialize = "Copies"))
+    )]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub copies: Vec<Vec<String>>,
+}
+
+impl MsanOrigin {
+    /// Extract origin chain from MemorySanitizer report
+    ///
+    /// # Arguments
+    ///
+    /// * `msan_report` - MemorySanitizer report lines
+    ///
+    /// # Return value
+    ///
+    /// Origin of uninitialized value if it is present in report
+    pub fn extract_origin(msan_report: &[String]) -> Option<MsanOrigin> {
+        let rstore = Regex::new(r"^\s*Uninitialized value was stored to memory at").unwrap();
+        let rcreate = Regex::new(
+            r"^\s*(?:Uninitialized value was created by|Memory was marked as uninitialized)(.*)$",
+        )
+        .unwrap();
+        let mut origin = MsanOrigin::default();
+        let mut found = false;
+        let mut lines = msan_report.iter().peekable();
+        while let Some(line) = lines.next() {
+            let is_store = rstore.is_match(line);
+            let created = rcreate.captures(line);
+            if !is_store && created.is_none() {
+                continue;
+            }
+            // Stack trace is split by empty line.
+            let mut stacktrace = Vec::new();
+            while let Some(frame) = lines.next_if(|l| !l.trim().is_empty()) {
+                if frame.trim_start().starts_with('#') {
+                    stacktrace.push(frame.trim_end().to_string());
+                }
+            }
+            found = true;
+            if let Some(caps) = created {
+                origin.description = caps.get(1).unwrap().as_str().trim().to_string();
+                if origin.description.is_empty() {
+                    origin.description = "memory marked as uninitialized".to_string();
+                }
+                origin.stacktrace = stacktrace;
+            } else {
+                origin.copies.push(stacktrace);
+            }
+        }
+        found.then_some(origin)
+    }
+}
+
+impl CrashLineExt for MsanOrigin {
+    fn crash_line(&self) -> Result<CrashLine> {
+        let mut stacktrace = AsanStacktrace::parse_stacktrace(&self.stacktrace)?;
+        stacktrace.filter();
+        stacktrace.crash_line()
+    }
+}
+
+/// Extract MemorySanitizer report from program output
+///
+/// # Arguments
+///
+/// * `stream` - program output lines
+///
+/// # Return value
+///
+/// Report lines from WARNING (or preceding "Uninitialized bytes" line) to SUMMARY line
+pub fn extract_msan_report(stream: &[String]) -> Option<Vec<String>> {
+    let rwarning = Regex::new(r"==\d+==\s*WARNING: MemorySanitizer:").unwrap();
+    let mut start = stream.iter().position(|line| rwarning.is_match(line))?;
+    // Interceptor check prints location of uninitialized bytes before WARNING.
+    if start > 0 && stream[start - 1].starts_with("Uninitialized bytes in ") {
+        start -= 1;
+    }
+    let end = stream[start..]
+        .iter()
+        .position(|line| line.contains("SUMMARY: MemorySanitizer: "))
+        .map(|pos| start + pos + 1)
+        .unwrap_or(stream.len());
+    Some(
+        stream[start..end]
+            .iter()
+            .map(|line| line.trim_end().to_string())
+            .collect(),
+    )
+}
+
+/// Information about MemorySanitizer report.
+pub struct MsanContext(pub Vec<String>);
+
+impl Severity for MsanContext {
+    fn severity(&self) -> Result<ExecutionClass> {
+        let msan_report = &self.0;
+        let rwarning = Regex::new(r"WARNING: MemorySanitizer: ([A-Za-z_\-]+)").unwrap();
+        let Some(caps) = msan_report.iter().find_map(|s| rwarning.captures(s)) else {
+            return Err(Error::Casr(
+                "Cannot find WARNING in MemorySanitizer report".to_string(),
+            ));
+        };
+        let bug_type = caps.get(1).unwrap().as_str();
+        if bug_type != "use-of-uninitialized-value" {
+            return ExecutionClass::find(bug_type);
+        }
+        // Refine class by origin of uninitialized value.
+        if msan_report.iter().any(|line| {
+            line.contains("Member fields were destroyed")
+                || line.contains("__sanitizer_dtor_callback")
+        }) {
+            return ExecutionClass::find("use-after-dtor");
+        }
+        if msan_report
+            .first()
+            .is_some_and(|line| line.starts_with("Uninitialized bytes in "))
+        {
+            return ExecutionClass::find("uninit-bytes-leak");
+        }
+        match MsanOrigin::extract_origin(msan_report) {
+            Some(origin) if origin.description.contains("heap allocation") => {
+                ExecutionClass::find("uninit-heap-value")
+            }
+            Some(origin) if origin.description.contains("in the stack frame") => {
+                ExecutionClass::find("uninit-stack-value")
+            }
+            _ => ExecutionClass::find(bug_type),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stacktrace::tests::safe_init_ignore_stack_frames;
+
+    #[test]
+    fn test_msan_origin() {
+        let report: Vec<String> = [
+            "==5174==WARNING: MemorySanitizer: use-of-uninitialized-value",
+            "    #0 0x4a0ad3 in main /tmp/test_msan.c:14:7",
+            "    #1 0x7f14a5e9b082 in __libc_start_main /build/glibc/csu/../csu/libc-start.c:308:16",
+            "",
+            "  Uninitialized value was stored to memory at",
+            "    #0 0x4a0a8e in copy /tmp/test_msan.c:6:8",
+            "    #1 0x4a0ac7 in main /tmp/test_msan.c:13:3",
+            "",
+            "  Uninitialized value was stored to memory at",
+            "    #0 0x4a0a3c in init /tmp/test_msan.c:3:6",
+            "",
+            "  Uninitialized value was created by a heap allocation",
+            "    #0 0x42a5bd in malloc /llvm/compiler-rt/lib/msan/msan_interceptors.cpp:1007:3",
+            "    #1 0x4a0aa9 in main /tmp/test_msan.c:11:20",
+            "",
+            "SUMMARY: MemorySanitizer: use-of-uninitialized-value /tmp/test_msan.c:14:7 in main",
+        ]
+        .iter()
+        .map(|l| l.to_string())
+        .collect();
+
+        let Some(origin) = MsanOrigin::extract_origin(&report) else {
+            panic!("Couldn't extract origin");
+        };
+        assert_eq!(origin.description, "a heap allocation");
+        assert_eq!(origin.stacktrace.len(), 2);
+        assert_eq!(origin.copies.len(), 2);
+        assert_eq!(
+            origin.copies[0],
+            vec![
+                "    #0 0x4a0a8e in copy /tmp/test_msan.c:6:8".to_string(),
+                "    #1 0x4a0ac7 in main /tmp/test_msan.c:13:3".to_string()
+            ]
+        );
+        assert_eq!(origin.copies[1].len(), 1);
+
+        safe_init_ignore_stack_frames();
+        let Ok(crashline) = origin.crash_line() else {
+            panic!("Couldn't get origin crash line");
+        };
+        assert_eq!(crashline.to_string(), "/tmp/test_msan.c:11:20");
+
+        let report: Vec<String> = [
+            "==5174==WARNING: MemorySanitizer: use-of-uninitialized-value",
+            "    #0 0x4a0ad3 in main /tmp/test_msan.c:14:7",
+            "",
+            "  Uninitialized value was created by an allocation of 'buf' in the stack frame of function 'main'",
+            "    #0 0x4a0a10 in main /tmp/test_msan.c:9",
+            "",
+        ]
+        .iter()
+        .map(|l| l.to_string())
+        .collect();
+        let Some(origin) = MsanOrigin::extract_origin(&report) else {
+            panic!("Couldn't extract origin");
+        };
+        assert_eq!(
+            origin.description,
+            "an allocation of 'buf' in the stack frame of function 'main'"
+        );
+        assert!(origin.copies.is_empty());
+        assert_eq!(origin.stacktrace.len(), 1);
+
+        assert!(MsanOrigin::extract_origin(&report[..2]).is_none());
+
+        let class = MsanContext(report[..2].to_vec()).severity();
+        let Ok(class) = class else {
+            panic!("{}", class.err().unwrap());
+        };
+        assert_eq!(class.short_description, "use-of-uninitialized-value");
+        let class = MsanContext(report).severity();
+        let Ok(class) = class else {
+            panic!("{}", class.err().unwrap());
+        };
+        assert_eq!(class.short_description, "uninit-stack-value");
+    }
+
+    #[test]
+    fn test_msan_report() {
+        let output: Vec<String> = [
+            "Reading input",
+            "Uninitialized bytes in __interceptor_write at offset 0 inside [0x7ffc4a2e1a50, 4)",
+            "==7212==WARNING: MemorySanitizer: use-of-uninitialized-value",
+            "    #0 0x4a0b1d in main /tmp/test_msan.c:8:3",
+            "",
+            "  Uninitialized value was created by an allocation of 'buf' in the stack frame of function 'main'",
+            "    #0 0x4a0a10 in main /tmp/test_msan.c:5",
+            "",
+            "SUMMARY: MemorySanitizer: use-of-uninitialized-value /tmp/test_msan.c:8:3 in main",
+            "Exiting",
+        ]
+        .iter()
+        .map(|l| l.to_string())
+        .collect();
+
+        let Some(report) = extract_msan_report(&output) else {
+            panic!("Couldn't extract MemorySanitizer report");
+        };
+        assert_eq!(report.len(), 8);
+        assert!(report[0].starts_with("Uninitialized bytes in"));
+        assert!(report[7].starts_with("SUMMARY: MemorySanitizer"));
+        assert!(extract_msan_report(&output[..2]).is_none());
+
+        let class = MsanContext(report.clone()).severity();
+        let Ok(class) = class else {
+            panic!("{}", class.err().unwrap());
+        };
+        assert_eq!(class.short_description, "uninit-bytes-leak");
+
+        let raw_stacktrace = AsanStacktrace::extract_stacktrace(&report.join("\n"));
+        let Ok(raw_stacktrace) = raw_stacktrace else {
+            panic!("{}", raw_stacktrace.err().unwrap());
+        };
+        assert_eq!(raw_stacktrace.len(), 1);
+
+        let report: Vec<String> = [
+            "==7301==WARNING: MemorySanitizer: use-of-uninitialized-value",
+            "    #0 0x4a0c2e in main /tmp/test_dtor.cpp:15:10",
+            "",
+            "  Member fields were destroyed",
+            "    #0 0x42b6dd in __sanitizer_dtor_callback_fields /llvm/compiler-rt/lib/msan/msan_interceptors.cpp:1025:5",
+            "    #1 0x4a0d4f in Foo::~Foo() /tmp/test_dtor.cpp:5:3",
+        ]
+        .iter()
+        .map(|l| l.to_string())
+        .collect();
+        let class = MsanContext(report).severity();
+        let Ok(class) = class else {
+            panic!("{}", class.err().unwrap());
+        };
+        assert_eq!(class.short_description, "use-after-dtor");
+    }
+}
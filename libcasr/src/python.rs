@@ -95,6 +95,28 @@ impl ParseStacktrace for PythonStacktrace {
     }
 }
 
+/// Explanations for common python exceptions.
+const PYTHON_EXCEPTIONS: &[(&str, &str)] = &[
+    ("IndexError", "Sequence subscript is out of range."),
+    ("KeyError", "Mapping key is not found in the set of existing keys."),
+    ("MemoryError", "Operation ran out of memory, which may be caused by uncontrolled allocation size."),
+    ("RecursionError", "Maximum recursion depth is exceeded, which usually indicates uncontrolled recursion."),
+    ("ZeroDivisionError", "Second argument of division or modulo operation is zero."),
+    ("OverflowError", "Result of arithmetic operation is too large to be represented."),
+    ("ValueError", "Operation or function receives an argument that has the right type but an inappropriate value."),
+    ("TypeError", "Operation or function is applied to an object of inappropriate type."),
+    ("AttributeError", "Attribute reference or assignment fails."),
+    ("AssertionError", "Assert statement fails."),
+    ("UnicodeDecodeError", "Error occurs during decoding of bytes to string."),
+    ("UnicodeEncodeError", "Error occurs during encoding of string to bytes."),
+    ("NameError", "Local or global name is not found."),
+    ("RuntimeError", "Error is detected that doesn't fall in any of the other categories."),
+    ("NotImplementedError", "Abstract method or unimplemented feature is called."),
+    ("SystemError", "Interpreter detects an internal error, which may be caused by a bug in C extension."),
+    ("EOFError", "Input function hits end-of-file condition without reading any data."),
+    ("StopIteration", "Next item is requested from exhausted iterator."),
+];
+
 /// Structure provides an interface for parsing python exception message.
 pub struct PythonException;
 
@@ -105,18 +127,32 @@ impl Exception for PythonException {
             .map(|l| l.trim_end().to_string())
             .collect();
         let re = Regex::new(r"([\w]+): (.+)").unwrap();
-        stderr_list
+        // Exceptions may be raised without message, e.g. "MemoryError".
+        let re_bare =
+            Regex::new(r"^(?:[\w.]+\.)?(\w+(?:Error|Exception|Exit|Interrupt))$").unwrap();
+        let (name, message) = stderr_list
             .iter()
             .rev()
             .find_map(|x| re.captures(x))
-            .map(|cap| {
-                ExecutionClass::new((
-                    "NOT_EXPLOITABLE",
-                    cap.get(1).unwrap().as_str(),
-                    cap.get(2).unwrap().as_str(),
-                    "",
-                ))
-            })
+            .map(|cap| (cap.get(1).unwrap().as_str(), cap.get(2).unwrap().as_str()))
+            .or_else(|| {
+                stderr_list
+                    .iter()
+                    .rev()
+                    .find_map(|x| re_bare.captures(x))
+                    .map(|cap| (cap.get(1).unwrap().as_str(), ""))
+            })?;
+        let explanation = PYTHON_EXCEPTIONS
+            .iter()
+            .find(|(exception, _)| *exception == name)
+            .map(|(_, explanation)| *explanation)
+            .unwrap_or_default();
+        Some(ExecutionClass::new((
+            "NOT_EXPLOITABLE",
+            name,
+            message,
+            explanation,
+        )))
     }
 }
 
@@ -124,6 +160,44 @@ impl Exception for PythonException {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_python_exception() {
+        let Some(class) =
+            PythonException::parse_exception("RecursionError: maximum recursion depth exceeded")
+        else {
+            panic!("Couldn't parse exception");
+        };
+        assert_eq!(class.severity, "NOT_EXPLOITABLE");
+        assert_eq!(class.short_description, "RecursionError");
+        assert_eq!(class.description, "maximum recursion depth exceeded");
+        assert!(!class.explanation.is_empty());
+
+        let Some(class) = PythonException::parse_exception(
+            "Traceback (most recent call last):\n  File \"fuzz.py\", line 5, in TestOneInput\n    data[10]\nIndexError: list index out of range\n",
+        ) else {
+            panic!("Couldn't parse exception");
+        };
+        assert_eq!(class.short_description, "IndexError");
+        assert_eq!(class.description, "list index out of range");
+
+        let Some(class) = PythonException::parse_exception("MemoryError") else {
+            panic!("Couldn't parse exception");
+        };
+        assert_eq!(class.short_description, "MemoryError");
+        assert_eq!(class.description, "");
+        assert!(!class.explanation.is_empty());
+
+        let Some(class) = PythonException::parse_exception(
+            "yaml.scanner.ScannerError: mapping values are not allowed here",
+        ) else {
+            panic!("Couldn't parse exception");
+        };
+        assert_eq!(class.short_description, "ScannerError");
+        assert_eq!(class.explanation, "");
+
+        assert!(PythonException::parse_exception("Executed crash in 1 ms").is_none());
+    }
+
     #[test]
     fn test_python_stacktrace() {
         let raw_stacktrace = &[
@@ -1,30 +1,58 @@
 //! Report contains the main struct `CrashReport` with all information about crash.
-use crate::asan::AsanStacktrace;
+use crate::asan::{
+    extract_asan_reports, extract_asan_warnings, AsanContext, AsanGlobal, AsanStacktrace,
+    LeakRecord, OutOfMemory,
+};
+use crate::cpp::CppException;
 use crate::error;
 use crate::error::*;
+use crate::exception::Exception;
 use crate::execution_class::*;
-use crate::gdb::GdbStacktrace;
-use crate::go::GoStacktrace;
+use crate::gdb::{GdbStacktrace, ThreadStacktrace};
+use crate::go::{GoPanic, GoStacktrace};
+use crate::gwp_asan::{extract_gwp_asan_report, GwpAsanContext};
+use crate::hwasan::{extract_hwasan_report, HwasanContext};
 use crate::java::JavaStacktrace;
 use crate::js::JsStacktrace;
+use crate::kernel::KernelStacktrace;
+use crate::lldb::LldbStacktrace;
+use crate::msan::{extract_msan_report, MsanContext, MsanOrigin};
 use crate::python::PythonStacktrace;
-use crate::rust::RustStacktrace;
+use crate::rust::{RustPanic, RustStacktrace};
+use crate::severity::Severity;
 use crate::stacktrace::*;
+use crate::tsan::{
+    extract_mutex_acquisitions, extract_tsan_report, race_stacktrace, MutexAcquisition,
+    TsanContext, TsanStacktrace,
+};
+use crate::ubsan::extract_ubsan_warnings;
+use crate::valgrind::{extract_valgrind_errors, ValgrindContext, ValgrindStacktrace};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
 use chrono::prelude::*;
 use gdb_command::mappings::{MappedFiles, MappedFilesExt};
 use gdb_command::registers::Registers;
 use gdb_command::stacktrace::StacktraceExt;
 use regex::Regex;
+use sha2::{Digest, Sha256};
 use std::fmt;
 use std::fs;
 use std::fs::File;
 use std::io::prelude::*;
 use std::io::BufReader;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
+#[cfg(feature = "serde")]
+use serde_json::{Map, Value};
+
+/// Current version of report format. Reports without version were created
+/// before report versioning and have version 0.
+pub const REPORT_VERSION: u32 = 2;
+
+/// Maximum number of stack trace frames in Markdown bug description.
+const MARKDOWN_FRAMES: usize = 16;
 
 /// Represents the information about program termination.
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -33,6 +61,13 @@ pub struct CrashReport {
     /// Pid of crashed process.
     #[cfg_attr(feature = "serde", serde(skip))]
     pub pid: i32,
+    /// Version of report format.
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename(serialize = "ReportVersion", deserialize = "ReportVersion"))
+    )]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub report_version: u32,
     /// Date and time of the problem report in ISO format. (see asctime(3)).
     #[cfg_attr(
         feature = "serde",
@@ -93,6 +128,55 @@ pub struct CrashReport {
     )]
     #[cfg_attr(feature = "serde", serde(default))]
     pub stdin: String,
+    /// Path to crashing input.
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename(serialize = "InputPath", deserialize = "InputPath"))
+    )]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub input_path: String,
+    /// SHA-256 hash of crashing input.
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename(serialize = "InputSha256", deserialize = "InputSha256"))
+    )]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub input_sha256: String,
+    /// Base64 copy of crashing input (saved only for small inputs).
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename(serialize = "InputData", deserialize = "InputData"))
+    )]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub input_data: String,
+    /// Target stdout.
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename(serialize = "Stdout", deserialize = "Stdout"))
+    )]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub stdout: Vec<String>,
+    /// Target stderr (including sanitizer report).
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename(serialize = "Stderr", deserialize = "Stderr"))
+    )]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub stderr: Vec<String>,
+    /// Target exit status, None if target is killed by signal.
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename(serialize = "ExitStatus", deserialize = "ExitStatus"))
+    )]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub exit_status: Option<i32>,
+    /// Signal that terminated target.
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename(serialize = "Signal", deserialize = "Signal"))
+    )]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub signal: Option<i32>,
     /// Contents of /proc/pid/status.
     #[cfg_attr(
         feature = "serde",
@@ -107,6 +191,20 @@ pub struct CrashReport {
     )]
     #[cfg_attr(feature = "serde", serde(default))]
     pub proc_maps: Vec<String>,
+    /// ASLR setting for target execution ("on" or "off"), empty if unknown.
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename(serialize = "ASLR", deserialize = "ASLR"))
+    )]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub aslr: String,
+    /// Base addresses of binary modules: "base module".
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename(serialize = "ModuleBases", deserialize = "ModuleBases"))
+    )]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub module_bases: Vec<String>,
     /// Opend files at crash : ls -lah /proc/\<pid\>/fd.
     #[cfg_attr(
         feature = "serde",
@@ -128,6 +226,13 @@ pub struct CrashReport {
     )]
     #[cfg_attr(feature = "serde", serde(default))]
     pub execution_class: ExecutionClass,
+    /// Numeric severity score (from 0.0 to 10.0) to order crashes.
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename(serialize = "SeverityScore", deserialize = "SeverityScore"))
+    )]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub severity_score: f64,
     /// Stack trace for crashed thread.
     #[cfg_attr(
         feature = "serde",
@@ -135,6 +240,49 @@ pub struct CrashReport {
     )]
     #[cfg_attr(feature = "serde", serde(default))]
     pub stacktrace: Vec<String>,
+    /// Parsed stack trace frames for crashed thread.
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename(serialize = "Frames", deserialize = "Frames"))
+    )]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub frames: Vec<StackFrame>,
+    /// Inline chain of crash frame from DWARF debug information: frames from
+    /// the innermost inlined function to the out-of-line one.
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename(serialize = "InlineFrames", deserialize = "InlineFrames"))
+    )]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub inline_frames: Vec<StackFrame>,
+    /// Fuzzy hash of top normalized stack trace frames for fast deduplication.
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename(serialize = "StackHash", deserialize = "StackHash"))
+    )]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub stack_hash: String,
+    /// Stack traces of all threads (crashed thread is marked).
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename(serialize = "Threads", deserialize = "Threads"))
+    )]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub threads: Vec<ThreadStacktrace>,
+    /// Stack trace where memory was allocated (ASAN, HWASAN, GWP-ASan).
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename(serialize = "AllocStacktrace", deserialize = "AllocStacktrace"))
+    )]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub alloc_stacktrace: Vec<String>,
+    /// Stack trace where memory was deallocated (ASAN, HWASAN, GWP-ASan).
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename(serialize = "FreeStacktrace", deserialize = "FreeStacktrace"))
+    )]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub free_stacktrace: Vec<String>,
     /// Registers state for crashed thread.
     #[cfg_attr(
         feature = "serde",
@@ -142,6 +290,13 @@ pub struct CrashReport {
     )]
     #[cfg_attr(feature = "serde", serde(default))]
     pub registers: Registers,
+    /// Registers that contain bytes of crashing input.
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename(serialize = "InputRegisters", deserialize = "InputRegisters"))
+    )]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub input_registers: Vec<String>,
     /// Disassembly for crashed state (16 instructions).
     #[cfg_attr(
         feature = "serde",
@@ -149,6 +304,35 @@ pub struct CrashReport {
     )]
     #[cfg_attr(feature = "serde", serde(default))]
     pub disassembly: Vec<String>,
+    /// Reason why debugger data is partial (e.g., debugger timeout).
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename(serialize = "PartialData", deserialize = "PartialData"))
+    )]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub partial_data: String,
+    /// Observed crash probability when target is re-run until it crashes
+    /// (e.g., casr-gdb `--retries`): 1 / number of runs.
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename(serialize = "CrashProbability", deserialize = "CrashProbability"))
+    )]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub crash_probability: Option<f64>,
+    /// Resource usage of target process.
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename(serialize = "ResourceUsage", deserialize = "ResourceUsage"))
+    )]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub resource_usage: ResourceUsage,
+    /// Result of re-running target to check whether crash reproduces.
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename(serialize = "Verification", deserialize = "Verification"))
+    )]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub verification: Verification,
     /// Package name.
     #[cfg_attr(
         feature = "serde",
@@ -184,6 +368,94 @@ pub struct CrashReport {
     )]
     #[cfg_attr(feature = "serde", serde(default))]
     pub asan_report: Vec<String>,
+    /// LeakSanitizer leak records.
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename(serialize = "Leaks", deserialize = "Leaks"))
+    )]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub leaks: Vec<LeakRecord>,
+    /// libFuzzer out-of-memory details.
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename(serialize = "OutOfMemory", deserialize = "OutOfMemory"))
+    )]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub oom: Option<OutOfMemory>,
+    /// Global variables from AddressSanitizer odr-violation or
+    /// initialization-order-fiasco report.
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename(serialize = "Globals", deserialize = "Globals"))
+    )]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub globals: Vec<AsanGlobal>,
+    /// Secondary sanitizer error reports printed in the same run
+    /// (e.g., with halt_on_error=0).
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename(serialize = "OtherErrors", deserialize = "OtherErrors"))
+    )]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub other_errors: Vec<String>,
+    /// Sanitizer reports are recoverable warnings (e.g., with
+    /// `-fsanitize-recover=address` and halt_on_error=0), target execution
+    /// wasn't terminated.
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename(serialize = "Recovered", deserialize = "Recovered"))
+    )]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub recovered: bool,
+    /// Msan report.
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename(serialize = "MsanReport", deserialize = "MsanReport"))
+    )]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub msan_report: Vec<String>,
+    /// MemorySanitizer origin of uninitialized value.
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename(serialize = "MsanOrigin", deserialize = "MsanOrigin"))
+    )]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub msan_origin: MsanOrigin,
+    /// GWP-ASan report.
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename(serialize = "GwpAsanReport", deserialize = "GwpAsanReport"))
+    )]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub gwp_asan_report: Vec<String>,
+    /// HWAddressSanitizer report.
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename(serialize = "HwasanReport", deserialize = "HwasanReport"))
+    )]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub hwasan_report: Vec<String>,
+    /// Valgrind Memcheck error.
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename(serialize = "ValgrindReport", deserialize = "ValgrindReport"))
+    )]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub valgrind_report: Vec<String>,
+    /// Linux kernel crash report (KASAN, KCSAN, KMSAN, oops).
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename(serialize = "KernelReport", deserialize = "KernelReport"))
+    )]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub kernel_report: Vec<String>,
+    /// Reproducer program (e.g., syzkaller C or syz program).
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename(serialize = "Reproducer", deserialize = "Reproducer"))
+    )]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub reproducer: Vec<String>,
     /// Ubsan report.
     #[cfg_attr(
         feature = "serde",
@@ -191,6 +463,27 @@ pub struct CrashReport {
     )]
     #[cfg_attr(feature = "serde", serde(default))]
     pub ubsan_report: Vec<String>,
+    /// Ubsan warnings printed before fatal sanitizer error.
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename(serialize = "UbsanWarnings", deserialize = "UbsanWarnings"))
+    )]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub ubsan_warnings: Vec<String>,
+    /// Tsan report.
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename(serialize = "TsanReport", deserialize = "TsanReport"))
+    )]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub tsan_report: Vec<String>,
+    /// Mutex acquisitions from ThreadSanitizer lock-order-inversion report.
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename(serialize = "MutexAcquisitions", deserialize = "MutexAcquisitions"))
+    )]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub mutex_acquisitions: Vec<MutexAcquisition>,
     /// Python report.
     #[cfg_attr(
         feature = "serde",
@@ -240,6 +533,13 @@ pub struct CrashReport {
     )]
     #[cfg_attr(feature = "serde", serde(default))]
     pub source: Vec<String>,
+    /// Source code fragments around the top stack trace frames.
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename(serialize = "SourceContexts", deserialize = "SourceContexts"))
+    )]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub source_contexts: Vec<SourceContext>,
 }
 
 impl CrashReport {
@@ -248,9 +548,25 @@ impl CrashReport {
         let mut report: CrashReport = Default::default();
         let local: DateTime<Local> = Local::now();
         report.date = local.to_rfc3339_opts(SecondsFormat::Micros, false);
+        report.report_version = REPORT_VERSION;
         report
     }
 
+    /// Deserialize report from JSON. Reports of older format versions are
+    /// upgraded to the current version.
+    ///
+    /// # Arguments
+    ///
+    /// * `json` - report in JSON format
+    #[cfg(feature = "serde")]
+    pub fn from_json(json: &str) -> Result<Self> {
+        let mut value: Value = serde_json::from_str(json)
+            .map_err(|e| Error::Casr(format!("Couldn't parse report JSON: {e}")))?;
+        upgrade_report(&mut value)?;
+        serde_json::from_value(value)
+            .map_err(|e| Error::Casr(format!("Couldn't deserialize report: {e}")))
+    }
+
     /// Add information about opened network connections
     pub fn add_network_connections(&mut self) -> error::Result<()> {
         let mut ss_cmd = Command::new("ss");
@@ -288,6 +604,18 @@ impl CrashReport {
                 });
             }
         }
+        // Fallback for systems without lsb_release (e.g. FreeBSD).
+        if self.os.is_empty() {
+            let uname_out = Command::new("uname").arg("-sr").output()?;
+            if uname_out.status.success() {
+                if let Ok(uname) = String::from_utf8(uname_out.stdout) {
+                    if let Some((os, release)) = uname.trim().split_once(' ') {
+                        self.os = os.to_string();
+                        self.os_release = release.to_string();
+                    }
+                }
+            }
+        }
         // Get uname -a.
         let mut uname_cmd = Command::new("sh");
         uname_cmd.arg("-c").arg("uname -a");
@@ -306,6 +634,14 @@ impl CrashReport {
                 self.architecture = dpkg.trim().to_string();
             }
         }
+        if self.architecture.is_empty() {
+            let arch_out = Command::new("uname").arg("-m").output()?;
+            if arch_out.status.success() {
+                if let Ok(arch) = String::from_utf8(arch_out.stdout) {
+                    self.architecture = arch.trim().to_string();
+                }
+            }
+        }
 
         Ok(())
     }
@@ -494,22 +830,31 @@ impl CrashReport {
     ///
     /// * 'debug' - debug information
     pub fn sources(debug: &DebugInfo) -> Option<Vec<String>> {
+        CrashReport::source_lines(debug, 4, 5)
+    }
+
+    /// Get source code fragment around line, which is marked with "--->"
+    ///
+    /// # Arguments
+    ///
+    /// * `debug` - debug information
+    ///
+    /// * `before` - number of lines before line
+    ///
+    /// * `after` - number of lines after line
+    fn source_lines(debug: &DebugInfo, before: usize, after: usize) -> Option<Vec<String>> {
         if debug.line == 0 {
             return None;
         }
 
         if let Ok(file) = std::fs::File::open(&debug.file) {
             let file = BufReader::new(file);
-            let start: usize = if debug.line > 5 {
-                debug.line as usize - 5
-            } else {
-                0
-            };
+            let start = (debug.line as usize).saturating_sub(before + 1);
             let mut lines: Vec<String> = file
                 .lines()
                 .skip(start)
                 .enumerate()
-                .take_while(|(i, _)| *i < 10)
+                .take_while(|(i, _)| *i <= before + after)
                 .map(|(i, l)| {
                     if let Ok(l) = l {
                         format!("    {:<6} {}", start + i + 1, l.trim_end())
@@ -528,6 +873,56 @@ impl CrashReport {
         None
     }
 
+    /// Set source code fragments around locations of the top frames from
+    /// filtered stack trace, so that report may be reviewed without source
+    /// checkout. Frames without available source files are skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `frames` - maximum number of frames
+    ///
+    /// * `radius` - number of lines before and after frame line
+    pub fn set_source_contexts(&mut self, frames: usize, radius: usize) {
+        self.source_contexts.clear();
+        let Ok(stacktrace) = self.filtered_stacktrace() else {
+            return;
+        };
+        for entry in stacktrace.iter() {
+            if self.source_contexts.len() >= frames {
+                break;
+            }
+            let debug = &entry.debug;
+            // Recursive frames point to the same location.
+            if self
+                .source_contexts
+                .iter()
+                .any(|context| context.file == debug.file && context.line == debug.line)
+            {
+                continue;
+            }
+            if let Some(source) = CrashReport::source_lines(debug, radius, radius) {
+                self.source_contexts.push(SourceContext {
+                    function: entry.function.clone(),
+                    file: debug.file.clone(),
+                    line: debug.line,
+                    source,
+                });
+            }
+        }
+    }
+
+    /// Get registers in natural order of their names (e.g., "r8" goes before
+    /// "r10") for pretty view.
+    pub fn sorted_registers(&self) -> Vec<(&String, &u64)> {
+        let mut registers: Vec<(&String, &u64)> = self.registers.iter().collect();
+        registers.sort_by_key(|&(name, _)| {
+            let prefix = name.trim_end_matches(|c: char| c.is_ascii_digit());
+            let number = name[prefix.len()..].parse::<u64>().unwrap_or(0);
+            (prefix, number, name.as_str())
+        });
+        registers
+    }
+
     /// Add disassembly as strings
     ///
     /// # Arguments
@@ -541,10 +936,191 @@ impl CrashReport {
         self.disassembly = disassembly.split('\n').map(|x| x.to_string()).collect();
     }
 
+    /// Resolve function names and source locations from DWARF debug information for
+    /// gdb or AddressSanitizer stack trace frames without source locations (e.g.,
+    /// when llvm-symbolizer is not available). Remaining frames without function
+    /// names (e.g., in stripped PIE binaries) get `function+offset` names from ELF
+    /// symbol tables.
+    /// Runtime addresses are rebased against module load bases from proc maps.
+    #[cfg(feature = "exploitable")]
+    pub fn symbolize_stacktrace(&mut self) {
+        let parsed = if !self.asan_report.is_empty()
+            || !self.msan_report.is_empty()
+            || !self.gwp_asan_report.is_empty()
+            || !self.hwasan_report.is_empty()
+        {
+            AsanStacktrace::parse_stacktrace(&self.stacktrace)
+        } else if self.proc_maps.is_empty() {
+            return;
+        } else {
+            GdbStacktrace::parse_stacktrace(&self.stacktrace)
+        };
+        let Ok(mut stacktrace) = parsed else {
+            return;
+        };
+        if !self.proc_maps.is_empty() {
+            if let Ok(mappings) = MappedFiles::from_gdb(self.proc_maps.join("\n")) {
+                stacktrace.compute_module_offsets(&mappings);
+            }
+        }
+        // Parsed stack trace may skip unparsable lines.
+        if stacktrace.len() != self.stacktrace.len() {
+            return;
+        }
+        // Source locations from DWARF are preferred to bare function names.
+        for idx in crate::debug::symbolize_stacktrace(&mut stacktrace) {
+            self.stacktrace[idx] =
+                crate::debug::insert_location(&self.stacktrace[idx], &stacktrace[idx]);
+        }
+        for (idx, function) in crate::symbols::symbolize_stacktrace(&mut stacktrace) {
+            self.stacktrace[idx] =
+                crate::symbols::insert_function(&self.stacktrace[idx], &function);
+        }
+    }
+
+    /// Store inline chain of crash frame from DWARF debug information. With
+    /// heavy inlining stack trace may point at the out-of-line caller, so crash
+    /// line is set to the innermost inlined location that isn't ignored.
+    /// Nothing is stored if crash frame location isn't inlined.
+    #[cfg(feature = "exploitable")]
+    pub fn set_inline_frames(&mut self) {
+        self.inline_frames.clear();
+        let (Ok(rawtrace), Ok(stacktrace)) = (self.parsed_stacktrace(), self.filtered_stacktrace())
+        else {
+            return;
+        };
+        // Frames inlined by gdb have no addresses.
+        let Some(entry) = stacktrace
+            .iter()
+            .find(|entry| !entry.module.is_empty() && entry.offset != 0)
+        else {
+            return;
+        };
+        // Modules without debug information aren't searched for it again.
+        if entry.debug.file.is_empty() {
+            return;
+        }
+        let Ok(symbols) = crate::debug::DwarfSymbols::from_file(Path::new(&entry.module)) else {
+            return;
+        };
+        // Return address points to the instruction after call.
+        let top = rawtrace
+            .iter()
+            .find(|frame| frame.address != 0)
+            .is_some_and(|frame| frame.address == entry.address);
+        let offset = if top { entry.offset } else { entry.offset - 1 };
+        let chain = symbols.lookup_inline(offset);
+        if chain.len() < 2 {
+            return;
+        }
+        self.inline_frames = chain
+            .into_iter()
+            .map(|(function, debug)| StackFrame {
+                address: entry.address,
+                module: entry.module.clone(),
+                offset: entry.offset,
+                function,
+                file: debug.file,
+                line: debug.line,
+                column: debug.column,
+            })
+            .collect();
+
+        let chain: Stacktrace = self
+            .inline_frames
+            .iter()
+            .map(StacktraceEntry::from)
+            .collect();
+        let Ok(CrashLine::Source(debug)) = chain.crash_line() else {
+            return;
+        };
+        // Crash line is kept if stack trace already contains inlined frames.
+        let crash_debug = &stacktrace[0].debug;
+        if debug.line != crash_debug.line
+            || Path::new(&debug.file).file_name() != Path::new(&crash_debug.file).file_name()
+        {
+            self.source.clear();
+            self.set_crash_line(CrashLine::Source(debug));
+        }
+    }
+
     /// Filter frames from the stack trace that are not related to analyzed code containing crash
     /// and return it as `Stacktrace` struct
     pub fn filtered_stacktrace(&self) -> Result<Stacktrace> {
-        let mut rawtrace = if !self.asan_report.is_empty() {
+        // Data races are identified by both racing stack traces.
+        if !self.tsan_report.is_empty() {
+            if let Ok(trace) = race_stacktrace(&self.tsan_report) {
+                if !trace.is_empty() {
+                    return Ok(trace);
+                }
+            }
+        }
+
+        let mut rawtrace = self.parsed_stacktrace()?;
+        rawtrace.filter();
+
+        if rawtrace.is_empty() {
+            return Err(Error::Casr(
+                "Current stack trace length is null".to_string(),
+            ));
+        }
+
+        Ok(rawtrace)
+    }
+
+    /// Filter frames from stack traces of all threads: crashed thread stack
+    /// trace is followed by stack traces of other threads. Falls back to crashed
+    /// thread stack trace when there is no information about threads.
+    pub fn filtered_threads_stacktrace(&self) -> Result<Stacktrace> {
+        let mut rawtrace = self.filtered_stacktrace()?;
+        for thread in self.threads.iter().filter(|thread| !thread.crashed) {
+            let Ok(mut trace) = GdbStacktrace::parse_stacktrace(&thread.stacktrace) else {
+                continue;
+            };
+            if !self.proc_maps.is_empty() {
+                let mappings = MappedFiles::from_gdb(self.proc_maps.join("\n"))?;
+                trace.compute_module_offsets(&mappings);
+            }
+            trace.filter();
+            rawtrace.extend(trace);
+        }
+        Ok(rawtrace)
+    }
+
+    /// Get filtered stack trace normalized for comparison (deduplication and
+    /// clustering): frame addresses are rewritten as module offsets computed
+    /// from process memory mappings, so that stack traces without symbols
+    /// don't depend on ASLR (see `normalize_addresses`). For odr-violation and
+    /// initialization-order-fiasco global variable name is added as the top
+    /// frame, because different variables have similar registration and
+    /// initialization stack traces.
+    pub fn normalized_stacktrace(&self) -> Result<Stacktrace> {
+        let mut stacktrace = self.filtered_stacktrace()?;
+        normalize_addresses(&mut stacktrace);
+        if let Some(global) = self.globals.first() {
+            stacktrace.insert(
+                0,
+                StacktraceEntry {
+                    function: global.name.clone(),
+                    ..Default::default()
+                },
+            );
+        }
+        Ok(stacktrace)
+    }
+
+    /// Parse the stack trace and compute module offsets for its frames without
+    /// filtering. Parsed frames stored in report are used if they are present.
+    pub fn parsed_stacktrace(&self) -> Result<Stacktrace> {
+        if !self.frames.is_empty() {
+            return Ok(self.frames.iter().map(StacktraceEntry::from).collect());
+        }
+        let mut rawtrace = if !self.asan_report.is_empty()
+            || !self.msan_report.is_empty()
+            || !self.gwp_asan_report.is_empty()
+            || !self.hwasan_report.is_empty()
+            || !self.ubsan_report.is_empty()
+        {
             AsanStacktrace::parse_stacktrace(&self.stacktrace)?
         } else if !self.python_report.is_empty() {
             PythonStacktrace::parse_stacktrace(&self.stacktrace)?
@@ -556,6 +1132,19 @@ impl CrashReport {
             RustStacktrace::parse_stacktrace(&self.stacktrace)?
         } else if !self.js_report.is_empty() {
             JsStacktrace::parse_stacktrace(&self.stacktrace)?
+        } else if !self.tsan_report.is_empty() {
+            TsanStacktrace::parse_stacktrace(&self.stacktrace)?
+        } else if !self.kernel_report.is_empty() {
+            KernelStacktrace::parse_stacktrace(&self.stacktrace)?
+        } else if !self.valgrind_report.is_empty() {
+            ValgrindStacktrace::parse_stacktrace(&self.stacktrace)?
+        } else if self
+            .stacktrace
+            .first()
+            .is_some_and(|frame| frame.trim_start_matches([' ', '*']).starts_with("frame #"))
+        {
+            // Stack trace from lldb (macOS).
+            LldbStacktrace::parse_stacktrace(&self.stacktrace)?
         } else {
             GdbStacktrace::parse_stacktrace(&self.stacktrace)?
         };
@@ -566,24 +1155,853 @@ impl CrashReport {
             rawtrace.compute_module_offsets(&mappings);
         }
 
-        rawtrace.filter();
-
-        if rawtrace.is_empty() {
-            return Err(Error::Casr(
-                "Current stack trace length is null".to_string(),
-            ));
-        }
-
         Ok(rawtrace)
     }
 }
 
-impl fmt::Display for CrashReport {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let mut report = String::new();
-        // CrashLine
-        if !self.crashline.is_empty() {
-            report += &format!("CrashLine: {}\n\n", &self.crashline);
+impl CrashReport {
+    /// Create `CrashReportBuilder` for a new report
+    pub fn builder() -> CrashReportBuilder {
+        CrashReportBuilder::default()
+    }
+
+    /// Fill report with sanitizer report, execution class, and stack trace
+    /// from program output
+    ///
+    /// # Arguments
+    ///
+    /// * `output` - program stderr with sanitizer report (ASAN, MSAN, TSAN,
+    ///   UBSAN, GWP-ASan, HWASan), Valgrind Memcheck log, or runtime error (Go and Rust panics)
+    ///
+    /// * `crashed` - program is terminated by signal, so that UBSAN runtime
+    ///   errors and recoverable ASAN warnings are not the reason of crash
+    ///
+    /// # Return value
+    ///
+    /// Parsed stack trace or None if output doesn't contain known error report
+    pub fn set_from_san_log(&mut self, output: &str, crashed: bool) -> Result<Option<Stacktrace>> {
+        let output_lines: Vec<String> = output.split('\n').map(|l| l.to_string()).collect();
+        let mut valgrind_errors = extract_valgrind_errors(&output_lines);
+        let stacktrace = if let Some(tsan_report) = extract_tsan_report(&output_lines) {
+            // ThreadSanitizer report: data race, deadlock, mutex misuse, etc.
+            self.tsan_report = tsan_report;
+            self.execution_class = TsanContext(self.tsan_report.clone()).severity()?;
+            self.mutex_acquisitions = extract_mutex_acquisitions(&self.tsan_report);
+            // The first stack trace is the current memory access or mutex operation.
+            self.stacktrace = TsanStacktrace::extract_stacktrace(&self.tsan_report.join("\n"))?;
+            TsanStacktrace::parse_stacktrace(&self.stacktrace)?
+        } else if let Some(msan_report) = extract_msan_report(&output_lines) {
+            // MemorySanitizer report: use of uninitialized value.
+            self.msan_report = msan_report;
+            self.execution_class = MsanContext(self.msan_report.clone()).severity()?;
+            if let Some(origin) = MsanOrigin::extract_origin(&self.msan_report) {
+                self.msan_origin = origin;
+            }
+            // Stack trace of uninitialized value use.
+            self.stacktrace = AsanStacktrace::extract_stacktrace(&self.msan_report.join("\n"))?;
+            #[cfg(feature = "exploitable")]
+            self.symbolize_stacktrace();
+            AsanStacktrace::parse_stacktrace(&self.stacktrace)?
+        } else if let Some(hwasan_report) = extract_hwasan_report(&output_lines) {
+            // HWAddressSanitizer report: tag mismatch, invalid free, etc.
+            self.hwasan_report = hwasan_report;
+            self.execution_class = HwasanContext(self.hwasan_report.clone()).severity()?;
+            // Allocation and deallocation stack traces are printed like in ASAN.
+            let context = AsanContext(self.hwasan_report.clone());
+            self.alloc_stacktrace = context.allocation_stacktrace();
+            self.free_stacktrace = context.deallocation_stacktrace();
+            self.stacktrace = AsanStacktrace::extract_stacktrace(&self.hwasan_report.join("\n"))?;
+            #[cfg(feature = "exploitable")]
+            self.symbolize_stacktrace();
+            AsanStacktrace::parse_stacktrace(&self.stacktrace)?
+        } else if let Some(gwp_asan_report) = extract_gwp_asan_report(&output_lines) {
+            // GWP-ASan report: sampled heap memory error (e.g., in Scudo allocator).
+            self.gwp_asan_report = gwp_asan_report;
+            let context = GwpAsanContext(self.gwp_asan_report.clone());
+            self.execution_class = context.severity()?;
+            // Stack trace of invalid access, allocation, and deallocation.
+            self.stacktrace = context.access_stacktrace();
+            self.alloc_stacktrace = context.allocation_stacktrace();
+            self.free_stacktrace = context.deallocation_stacktrace();
+            #[cfg(feature = "exploitable")]
+            self.symbolize_stacktrace();
+            AsanStacktrace::parse_stacktrace(&self.stacktrace)?
+        } else if !valgrind_errors.is_empty() {
+            // Valgrind Memcheck log: the most severe error is the main one.
+            let main = valgrind_errors
+                .iter()
+                .enumerate()
+                .map(|(i, error)| {
+                    let score = ValgrindContext(error.clone())
+                        .severity()
+                        .map_or(-1.0, |class| class.score());
+                    (i, score)
+                })
+                .fold(
+                    (0, f64::MIN),
+                    |max, cur| if cur.1 > max.1 { cur } else { max },
+                )
+                .0;
+            self.valgrind_report = valgrind_errors.remove(main);
+            self.other_errors = valgrind_errors.concat();
+            let context = ValgrindContext(self.valgrind_report.clone());
+            self.execution_class = context.severity()?;
+            self.alloc_stacktrace = context.allocation_stacktrace();
+            self.free_stacktrace = context.deallocation_stacktrace();
+            self.stacktrace =
+                ValgrindStacktrace::extract_stacktrace(&self.valgrind_report.join("\n"))?;
+            ValgrindStacktrace::parse_stacktrace(&self.stacktrace)?
+        } else if let Ok(raw_stacktrace) = GoStacktrace::extract_stacktrace(output) {
+            // If it is possible to extract Go stacktrace, it is Go.
+            self.stacktrace = raw_stacktrace;
+            self.go_report = output
+                .split('\n')
+                .map(|l| l.trim_end().to_string())
+                .collect();
+            if let Some(exception) = GoPanic::parse_exception(output) {
+                self.execution_class = exception;
+            }
+            GoStacktrace::parse_stacktrace(&self.stacktrace)?
+        } else if let Ok(raw_stacktrace) = RustStacktrace::extract_stacktrace(output) {
+            // If it is possible to extract Rust stacktrace, it is Rust.
+            self.stacktrace = raw_stacktrace;
+            self.rust_report = output
+                .split('\n')
+                .map(|l| l.trim_end().to_string())
+                .collect();
+            RustStacktrace::parse_stacktrace(&self.stacktrace)?
+        } else {
+            // Get ASAN report.
+            let san_output_list: Vec<String> = output
+                .split('\n')
+                .map(|l| l.trim_end().to_string())
+                .collect();
+            let rasan_start =
+                Regex::new(r"==\d+==\s*ERROR: (LeakSanitizer|AddressSanitizer|libFuzzer):")
+                    .unwrap();
+            let rasan_warning = Regex::new(r"==\d+==\s*WARNING: AddressSanitizer:").unwrap();
+            let report_start = san_output_list
+                .iter()
+                .position(|line| rasan_start.is_match(line));
+            // Recoverable warnings are considered only if target wasn't
+            // terminated by signal, otherwise crash is analyzed with debugger.
+            let warning_start = san_output_list
+                .iter()
+                .position(|line| rasan_warning.is_match(line))
+                .filter(|_| report_start.is_none() && !crashed);
+            if let Some(report_start) = report_start.or(warning_start) {
+                // Set the most severe ASAN report in casr report, while other
+                // reports (e.g., with halt_on_error=0) are kept as secondary errors.
+                self.recovered = warning_start.is_some();
+                let mut asan_reports = if self.recovered {
+                    extract_asan_warnings(&san_output_list)
+                } else {
+                    extract_asan_reports(&san_output_list)
+                };
+                let main = asan_reports
+                    .iter()
+                    .enumerate()
+                    .map(|(i, asan_report)| {
+                        let score = AsanContext(asan_report.clone())
+                            .severity()
+                            .map_or(-1.0, |class| class.score());
+                        (i, score)
+                    })
+                    .fold(
+                        (0, f64::MIN),
+                        |max, cur| if cur.1 > max.1 { cur } else { max },
+                    )
+                    .0;
+                self.asan_report = asan_reports.remove(main);
+                self.other_errors = asan_reports.concat();
+                self.leaks = LeakRecord::extract_leaks(&san_output_list[report_start..]);
+                // Attach UBSAN warnings printed before fatal error as secondary findings.
+                self.ubsan_warnings =
+                    extract_ubsan_warnings(&san_output_list[..report_start].join("\n"))
+                        .iter()
+                        .flat_map(|warning| warning.ubsan_report())
+                        .collect();
+                let context = AsanContext(self.asan_report.clone());
+                self.execution_class = context.severity()?;
+                self.alloc_stacktrace = context.allocation_stacktrace();
+                self.free_stacktrace = context.deallocation_stacktrace();
+                self.oom = OutOfMemory::extract(&self.asan_report);
+                self.globals = AsanGlobal::extract_globals(&self.asan_report);
+                if let Some(oom) = &self.oom {
+                    // Malloc stack trace of single allocation or the largest
+                    // live heap allocation for gradual out-of-memory.
+                    self.alloc_stacktrace = if oom.is_single_allocation() {
+                        AsanStacktrace::extract_stacktrace(&self.asan_report.join("\n"))
+                            .unwrap_or_default()
+                    } else {
+                        oom.live_heap
+                            .first()
+                            .map(|allocation| allocation.stacktrace.clone())
+                            .unwrap_or_default()
+                    };
+                }
+                // Leak report contains several allocation stack traces, so the
+                // largest leak is used instead of the first one.
+                let largest_leak = LeakRecord::largest(&self.leaks)
+                    .filter(|_| self.asan_report[0].contains("LeakSanitizer"));
+                self.stacktrace = if let Some(leak) = largest_leak {
+                    leak.stacktrace.clone()
+                } else {
+                    AsanStacktrace::extract_stacktrace(&self.asan_report.join("\n"))?
+                };
+                #[cfg(feature = "exploitable")]
+                self.symbolize_stacktrace();
+                AsanStacktrace::parse_stacktrace(&self.stacktrace)?
+            } else if let Some(warning) =
+                extract_ubsan_warnings(output).first().filter(|_| !crashed)
+            {
+                // Standalone UBSAN prints runtime errors without sanitizer error header.
+                self.ubsan_report = warning.ubsan_report();
+                self.execution_class = warning.severity()?;
+                if let Ok(crash_line) = warning.crash_line() {
+                    self.set_crash_line(crash_line);
+                }
+                // Stack trace is printed only with UBSAN_OPTIONS=print_stacktrace=1.
+                self.stacktrace = warning.extract_stacktrace().unwrap_or_default();
+                AsanStacktrace::parse_stacktrace(&self.stacktrace)?
+            } else {
+                return Ok(None);
+            }
+        };
+        Ok(Some(stacktrace))
+    }
+
+    /// Set execution class for uncaught C++ exception or Rust panic, and get
+    /// crash line with source code
+    ///
+    /// # Arguments
+    ///
+    /// * `stacktrace` - parsed stack trace
+    ///
+    /// * `output` - program stderr
+    pub fn complete_from_output(&mut self, stacktrace: &Stacktrace, output: &str) {
+        // Check for exceptions
+        if let Some(class) = [CppException::parse_exception, RustPanic::parse_exception]
+            .iter()
+            .find_map(|parse| parse(output))
+        {
+            self.execution_class = class;
+        }
+
+        // Get crash line.
+        if let Ok(crash_line) = stacktrace.crash_line() {
+            self.set_crash_line(crash_line);
+        }
+    }
+
+    /// Store parsed stack trace frames in report, frames are cleared if stack
+    /// trace couldn't be parsed
+    pub fn set_frames(&mut self) {
+        self.frames.clear();
+        self.frames = self
+            .parsed_stacktrace()
+            .map(|stacktrace| stacktrace.iter().map(StackFrame::from).collect())
+            .unwrap_or_default();
+    }
+
+    /// Store base addresses of binary modules in report: start addresses of
+    /// mappings with zero file offset or frame addresses minus module offsets
+    /// when memory mappings are unavailable (e.g., sanitizer reports)
+    pub fn set_module_bases(&mut self) {
+        let mut bases: Vec<(String, u64)> = Vec::new();
+        let mut add = |module: &str, base: u64| {
+            if !module.is_empty()
+                && !module.starts_with('[')
+                && !bases.iter().any(|(name, _)| name == module)
+            {
+                bases.push((module.to_string(), base));
+            }
+        };
+        if let Ok(mappings) = MappedFiles::from_gdb(self.proc_maps.join("\n")) {
+            for file in mappings.iter().filter(|file| file.offset == 0) {
+                add(&file.name, file.start);
+            }
+        }
+        if bases.is_empty() {
+            if let Ok(stacktrace) = self.parsed_stacktrace() {
+                for entry in stacktrace
+                    .iter()
+                    .filter(|entry| entry.offset != 0 && entry.address > entry.offset)
+                {
+                    add(&entry.module, entry.address - entry.offset);
+                }
+            }
+        }
+        self.module_bases = bases
+            .into_iter()
+            .map(|(module, base)| format!("{base:#x} {module}"))
+            .collect();
+    }
+
+    /// Store fuzzy stack hash in report (see `stack_hash`), hash is empty if
+    /// stack trace couldn't be parsed or has no frames after filtering
+    pub fn set_stack_hash(&mut self) {
+        self.stack_hash = self
+            .normalized_stacktrace()
+            .map(|stacktrace| stack_hash(&stacktrace))
+            .unwrap_or_default();
+    }
+
+    /// Store crashing input path and its SHA-256 hash in report, so that
+    /// report isn't separated from its input. Base64 copy of input is stored
+    /// as well if input isn't larger than `inline_limit`.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - path to crashing input
+    ///
+    /// * `inline_limit` - maximum input size (in bytes) to store its copy in
+    ///   report, 0 value means that copy isn't stored
+    pub fn set_input(&mut self, path: &Path, inline_limit: usize) -> Result<()> {
+        let data = fs::read(path)
+            .map_err(|e| Error::Casr(format!("Couldn't read input {}: {e}", path.display())))?;
+        self.input_path = fs::canonicalize(path)
+            .unwrap_or_else(|_| path.to_path_buf())
+            .display()
+            .to_string();
+        self.input_sha256 = sha256(&data);
+        self.input_data = if !data.is_empty() && data.len() <= inline_limit {
+            BASE64.encode(data)
+        } else {
+            String::new()
+        };
+        Ok(())
+    }
+
+    /// Get crashing input that matches input hash stored in report. Input is
+    /// restored from its base64 copy if recorded input file is missing or
+    /// changed.
+    ///
+    /// # Arguments
+    ///
+    /// * `dir` - directory to save restored input
+    ///
+    /// # Return value
+    ///
+    /// Path to crashing input
+    pub fn restore_input(&self, dir: &Path) -> Result<PathBuf> {
+        let path = Path::new(&self.input_path);
+        if let Ok(data) = fs::read(path) {
+            if sha256(&data) == self.input_sha256.to_lowercase() {
+                return Ok(path.to_path_buf());
+            }
+        }
+        let data = self.input()?;
+        let restored = dir.join(path.file_name().unwrap_or("input".as_ref()));
+        fs::write(&restored, data)?;
+        Ok(restored)
+    }
+
+    /// Check that other report describes the same crash: execution class and
+    /// crash line (stack hash if there is no crash line) are the same
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - report to compare with
+    pub fn is_same_crash(&self, other: &CrashReport) -> bool {
+        let same_place = if self.crashline.is_empty() {
+            let hash = |report: &CrashReport| {
+                report
+                    .normalized_stacktrace()
+                    .map(|stacktrace| stack_hash(&stacktrace))
+                    .ok()
+            };
+            hash(self).is_some() && hash(self) == hash(other)
+        } else {
+            self.crashline == other.crashline
+        };
+        same_place
+            && self.execution_class.short_description == other.execution_class.short_description
+    }
+
+    /// Store verification verdict in report by comparing it with report
+    /// collected on target re-run (see `is_same_crash`)
+    ///
+    /// # Arguments
+    ///
+    /// * `rerun` - report collected on re-run, None if target doesn't crash
+    pub fn set_verification(&mut self, rerun: Option<&CrashReport>) {
+        let local: DateTime<Local> = Local::now();
+        let mut verification = Verification {
+            date: local.to_rfc3339_opts(SecondsFormat::Micros, false),
+            verdict: "NotReproduced".to_string(),
+            ..Default::default()
+        };
+        if let Some(rerun) = rerun {
+            verification.verdict = if self.is_same_crash(rerun) {
+                "Reproduced".to_string()
+            } else {
+                "Changed".to_string()
+            };
+            verification.short_description = rerun.execution_class.short_description.clone();
+            verification.crashline = rerun.crashline.clone();
+        }
+        self.verification = verification;
+    }
+
+    /// Get crashing input from its base64 copy stored in report
+    ///
+    /// # Return value
+    ///
+    /// Input data, error if report has no input copy or copy doesn't match
+    /// input hash
+    pub fn input(&self) -> Result<Vec<u8>> {
+        if self.input_sha256.is_empty() {
+            return Err(Error::Casr("Report has no crashing input".to_string()));
+        }
+        let data = BASE64
+            .decode(&self.input_data)
+            .map_err(|e| Error::Casr(format!("Couldn't decode input copy: {e}")))?;
+        if sha256(&data) != self.input_sha256.to_lowercase() {
+            return Err(Error::Casr(if self.input_data.is_empty() {
+                format!("Report has no copy of input {}", self.input_path)
+            } else {
+                format!("Copy of input {} doesn't match its hash", self.input_path)
+            }));
+        }
+        Ok(data)
+    }
+
+    /// Set crash line and source code fragment around it
+    ///
+    /// # Arguments
+    ///
+    /// * `crash_line` - crash line
+    pub fn set_crash_line(&mut self, crash_line: CrashLine) {
+        self.crashline = crash_line.to_string();
+        if let CrashLine::Source(debug) = crash_line {
+            if let Some(sources) = CrashReport::sources(&debug) {
+                self.source = sources;
+            }
+        }
+    }
+
+    /// Render report as Markdown bug description: summary line, crash line,
+    /// filtered stack trace, environment, and reproduction command
+    pub fn to_markdown(&self) -> String {
+        use std::fmt::Write as _;
+
+        let class = &self.execution_class;
+        let stacktrace = self.filtered_stacktrace().unwrap_or_default();
+        let mut d = format!("## {}", class.description);
+        if let Some(function) = stacktrace
+            .first()
+            .map(|entry| entry.function.as_str())
+            .filter(|function| !function.is_empty())
+        {
+            let _ = write!(d, " in {function}");
+        }
+        if !self.crashline.is_empty() {
+            let _ = write!(d, " at {}", self.crashline);
+        }
+        let _ = write!(
+            d,
+            "\n\n**Severity:** {}: {}",
+            class.severity, class.short_description
+        );
+        if self.severity_score > 0.0 {
+            let _ = write!(d, " (score {:.1})", self.severity_score);
+        }
+        d += "\n\n";
+        if !class.explanation.is_empty() {
+            let _ = write!(d, "{}\n\n", class.explanation);
+        }
+        if !self.crashline.is_empty() {
+            let _ = write!(d, "**Crash line:** `{}`\n\n", self.crashline);
+        }
+
+        if !stacktrace.is_empty() {
+            d += "### Stack trace\n\n```\n";
+            for (i, entry) in stacktrace.iter().take(MARKDOWN_FRAMES).enumerate() {
+                let _ = write!(d, "#{i} 0x{:x}", entry.address);
+                if !entry.function.is_empty() {
+                    let _ = write!(d, " in {}", entry.function);
+                }
+                if !entry.debug.file.is_empty() {
+                    let _ = write!(d, " {}", entry.debug.file);
+                    if entry.debug.line != 0 {
+                        let _ = write!(d, ":{}", entry.debug.line);
+                    }
+                    if entry.debug.column != 0 {
+                        let _ = write!(d, ":{}", entry.debug.column);
+                    }
+                } else if !entry.module.is_empty() {
+                    let _ = write!(d, " ({}+0x{:x})", entry.module, entry.offset);
+                }
+                d += "\n";
+            }
+            if stacktrace.len() > MARKDOWN_FRAMES {
+                let _ = writeln!(d, "... {} more frames", stacktrace.len() - MARKDOWN_FRAMES);
+            }
+            d += "```\n\n";
+        }
+
+        d += "### Environment\n\n";
+        for (name, value) in [
+            ("OS", format!("{} {}", self.os, self.os_release)),
+            ("Architecture", self.architecture.clone()),
+            ("Executable", self.executable_path.clone()),
+            (
+                "Package",
+                format!("{} {}", self.package, self.package_version),
+            ),
+        ] {
+            let value = value.trim();
+            if !value.is_empty() {
+                let _ = writeln!(d, "- **{name}:** {value}");
+            }
+        }
+        // Sanitizer options affect reproduction.
+        for var in self.proc_environ.iter().filter(|var| {
+            var.split_once('=')
+                .is_some_and(|(k, _)| k.ends_with("SAN_OPTIONS"))
+        }) {
+            let _ = writeln!(d, "- `{var}`");
+        }
+
+        if !self.proc_cmdline.is_empty() {
+            let _ = write!(d, "\n### How to reproduce\n\n```\n{}", self.proc_cmdline);
+            if !self.stdin.is_empty() {
+                let _ = write!(d, " < {}", self.stdin);
+            }
+            d += "\n```\n";
+        }
+        if !self.input_sha256.is_empty() {
+            let _ = writeln!(
+                d,
+                "\nCrashing input: `{}` (SHA-256 `{}`)",
+                self.input_path, self.input_sha256
+            );
+        }
+        d
+    }
+}
+
+/// Builder for `CrashReport`. Setters don't consume builder, so that it may be
+/// filled step by step or in one chain:
+///
+/// ```
+/// use libcasr::report::CrashReport;
+///
+/// let report = CrashReport::builder()
+///     .executable("/bin/target")
+///     .cmdline(&["/bin/target", "input"])
+///     .stacktrace(vec!["#0 0x401000 in main /src/target.c:5:3".to_string()])
+///     .build();
+/// assert_eq!(report.proc_cmdline, "/bin/target input");
+/// ```
+#[derive(Clone, Debug)]
+pub struct CrashReportBuilder {
+    report: CrashReport,
+}
+
+impl Default for CrashReportBuilder {
+    fn default() -> Self {
+        CrashReportBuilder {
+            report: CrashReport::new(),
+        }
+    }
+}
+
+impl CrashReportBuilder {
+    /// Set executable path
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - executable path
+    pub fn executable(&mut self, path: &str) -> &mut Self {
+        self.report.executable_path = path.to_string();
+        self
+    }
+
+    /// Set program command line
+    ///
+    /// # Arguments
+    ///
+    /// * `argv` - program argument vector
+    pub fn cmdline(&mut self, argv: &[&str]) -> &mut Self {
+        self.report.proc_cmdline = argv.join(" ");
+        self
+    }
+
+    /// Set stdin file path
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - stdin file path
+    pub fn stdin(&mut self, path: &str) -> &mut Self {
+        self.report.stdin = path.to_string();
+        self
+    }
+
+    /// Set program stdout
+    ///
+    /// # Arguments
+    ///
+    /// * `output` - program stdout
+    pub fn stdout(&mut self, output: &str) -> &mut Self {
+        self.report.stdout = output.lines().map(|l| l.to_string()).collect();
+        self
+    }
+
+    /// Set program stderr, it is analyzed by `classify`
+    ///
+    /// # Arguments
+    ///
+    /// * `output` - program stderr
+    pub fn stderr(&mut self, output: &str) -> &mut Self {
+        self.report.stderr = output.lines().map(|l| l.to_string()).collect();
+        self
+    }
+
+    /// Set termination signal
+    ///
+    /// # Arguments
+    ///
+    /// * `signal` - signal number
+    pub fn signal(&mut self, signal: i32) -> &mut Self {
+        self.report.signal = Some(signal);
+        self
+    }
+
+    /// Set exit status
+    ///
+    /// # Arguments
+    ///
+    /// * `code` - exit code
+    pub fn exit_status(&mut self, code: i32) -> &mut Self {
+        self.report.exit_status = Some(code);
+        self
+    }
+
+    /// Set raw stack trace
+    ///
+    /// # Arguments
+    ///
+    /// * `stacktrace` - stack trace lines
+    pub fn stacktrace(&mut self, stacktrace: Vec<String>) -> &mut Self {
+        self.report.stacktrace = stacktrace;
+        self
+    }
+
+    /// Set execution class
+    ///
+    /// # Arguments
+    ///
+    /// * `class` - execution class
+    pub fn execution_class(&mut self, class: ExecutionClass) -> &mut Self {
+        self.report.execution_class = class;
+        self
+    }
+
+    /// Add OS information (errors are ignored)
+    pub fn os_info(&mut self) -> &mut Self {
+        let _ = self.report.add_os_info();
+        self
+    }
+
+    /// Add environment of current process (errors are ignored)
+    pub fn proc_environ(&mut self) -> &mut Self {
+        let _ = self.report.add_proc_environ();
+        self
+    }
+
+    /// Estimate execution class and crash line. Sanitizer report or runtime
+    /// error from stderr is used if it is found, otherwise crash line is
+    /// obtained from stack trace.
+    pub fn classify(&mut self) -> Result<&mut Self> {
+        let output = self.report.stderr.join("\n");
+        let crashed = self.report.signal.is_some();
+        let stacktrace = if let Some(stacktrace) = self.report.set_from_san_log(&output, crashed)? {
+            stacktrace
+        } else if !self.report.stacktrace.is_empty() {
+            self.report.parsed_stacktrace()?
+        } else {
+            return Err(Error::Casr(
+                "Couldn't find sanitizer report or runtime error, and stack trace is empty"
+                    .to_string(),
+            ));
+        };
+        self.report.complete_from_output(&stacktrace, &output);
+        Ok(self)
+    }
+
+    /// Get report with severity score computed from execution class and
+    /// parsed stack trace frames
+    pub fn build(&self) -> CrashReport {
+        let mut report = self.report.clone();
+        report.severity_score = report.execution_class.score();
+        report.set_frames();
+        report.set_stack_hash();
+        report
+    }
+}
+
+/// Resource usage of target process.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ResourceUsage {
+    /// Wall clock time (in milliseconds).
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename(serialize = "WallTime", deserialize = "WallTime"))
+    )]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub wall_time: u64,
+    /// User CPU time (in milliseconds).
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename(serialize = "UserTime", deserialize = "UserTime"))
+    )]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub user_time: u64,
+    /// System CPU time (in milliseconds).
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename(serialize = "SystemTime", deserialize = "SystemTime"))
+    )]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub system_time: u64,
+    /// Maximum resident set size (in KiB).
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename(serialize = "MaxRss", deserialize = "MaxRss"))
+    )]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub max_rss: u64,
+}
+
+impl ResourceUsage {
+    /// Check that resource usage is not measured
+    pub fn is_empty(&self) -> bool {
+        *self == ResourceUsage::default()
+    }
+}
+
+impl fmt::Display for ResourceUsage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "WallTime: {} ms\nUserTime: {} ms\nSystemTime: {} ms\nMaxRss: {} KiB",
+            self.wall_time, self.user_time, self.system_time, self.max_rss
+        )
+    }
+}
+
+/// Result of re-running target with crashing input.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Verification {
+    /// Verification date.
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename(serialize = "Date", deserialize = "Date"))
+    )]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub date: String,
+    /// `Reproduced` if crash has the same execution class and crash line,
+    /// `Changed` if target crashes differently, or `NotReproduced`.
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename(serialize = "Verdict", deserialize = "Verdict"))
+    )]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub verdict: String,
+    /// Execution class short description on re-run.
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename(serialize = "ShortDescription", deserialize = "ShortDescription"))
+    )]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub short_description: String,
+    /// Crash line on re-run.
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename(serialize = "CrashLine", deserialize = "CrashLine"))
+    )]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub crashline: String,
+}
+
+impl Verification {
+    /// Check that crash reproduction isn't verified
+    pub fn is_empty(&self) -> bool {
+        *self == Verification::default()
+    }
+}
+
+impl fmt::Display for Verification {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Date: {}\nVerdict: {}", self.date, self.verdict)?;
+        if !self.short_description.is_empty() {
+            write!(f, "\nShortDescription: {}", self.short_description)?;
+        }
+        if !self.crashline.is_empty() {
+            write!(f, "\nCrashLine: {}", self.crashline)?;
+        }
+        Ok(())
+    }
+}
+
+/// Source code fragment around stack trace frame location.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SourceContext {
+    /// Frame function name.
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename(serialize = "Function", deserialize = "Function"))
+    )]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub function: String,
+    /// Source file.
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename(serialize = "File", deserialize = "File"))
+    )]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub file: String,
+    /// Frame line.
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename(serialize = "Line", deserialize = "Line"))
+    )]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub line: u64,
+    /// Source code fragment, frame line is marked with "--->".
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename(serialize = "Source", deserialize = "Source"))
+    )]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub source: Vec<String>,
+}
+
+impl fmt::Display for SourceContext {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.function.is_empty() {
+            write!(f, "{}:{}", self.file, self.line)?;
+        } else {
+            write!(f, "{} at {}:{}", self.function, self.file, self.line)?;
+        }
+        for line in self.source.iter() {
+            write!(f, "\n{line}")?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for CrashReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut report = String::new();
+        // CrashLine
+        if !self.crashline.is_empty() {
+            report += &format!("CrashLine: {}\n\n", &self.crashline);
         }
 
         // Date
@@ -632,6 +2050,48 @@ impl fmt::Display for CrashReport {
             report += &format!("\nStdin: {}\n", &self.stdin);
         }
 
+        // InputPath
+        if !self.input_path.is_empty() {
+            report += &format!("\nInputPath: {}\n", &self.input_path);
+        }
+
+        // InputSha256
+        if !self.input_sha256.is_empty() {
+            report += &format!("InputSha256: {}\n", &self.input_sha256);
+        }
+
+        // InputData
+        if !self.input_data.is_empty() {
+            report += &format!("InputData: {}\n", &self.input_data);
+        }
+
+        // ExitStatus
+        if let Some(status) = self.exit_status {
+            report += &format!("\nExitStatus: {status}\n");
+        }
+
+        // Signal
+        if let Some(signal) = self.signal {
+            report += &format!("\nSignal: {signal}\n");
+        }
+
+        // Recovered
+        if self.recovered {
+            report += "\nRecovered: true\n";
+        }
+
+        // Stdout
+        if !self.stdout.is_empty() {
+            report += "\n===Stdout===\n";
+            report += &(self.stdout.join("\n") + "\n");
+        }
+
+        // Stderr
+        if !self.stderr.is_empty() {
+            report += "\n===Stderr===\n";
+            report += &(self.stderr.join("\n") + "\n");
+        }
+
         // ProcStatus
         if !self.proc_status.is_empty() {
             report += "\n===ProcStatus===\n";
@@ -644,6 +2104,17 @@ impl fmt::Display for CrashReport {
             report += &(self.proc_maps.join("\n") + "\n");
         }
 
+        // ASLR
+        if !self.aslr.is_empty() {
+            report += &format!("\nASLR: {}\n", &self.aslr);
+        }
+
+        // ModuleBases
+        if !self.module_bases.is_empty() {
+            report += "\n===ModuleBases===\n";
+            report += &(self.module_bases.join("\n") + "\n");
+        }
+
         // NetworkConnections
         if !self.proc_fd.is_empty() {
             report += "\n===NetworkConnections===\n";
@@ -658,10 +2129,66 @@ impl fmt::Display for CrashReport {
             report += &(self.stacktrace.join("\n") + "\n");
         }
 
+        // InlineFrames
+        if !self.inline_frames.is_empty() {
+            report += "\n===InlineFrames===\n";
+            for frame in self.inline_frames.iter() {
+                let debug = StacktraceEntry::from(frame).debug;
+                report += &format!("{} at {}\n", frame.function, CrashLine::Source(debug));
+            }
+        }
+
+        // StackHash
+        if !self.stack_hash.is_empty() {
+            report += &format!("\nStackHash: {}\n", &self.stack_hash);
+        }
+
+        // Threads
+        if !self.threads.is_empty() {
+            report += "\n===Threads===\n";
+            for thread in self.threads.iter() {
+                let crashed = if thread.crashed { " (crashed)" } else { "" };
+                report += &format!("{}{crashed}\n", thread.description);
+                report += &(thread.stacktrace.join("\n") + "\n");
+            }
+        }
+
+        // AllocStacktrace
+        if !self.alloc_stacktrace.is_empty() {
+            report += "\n===AllocStacktrace===\n";
+            report += &(self.alloc_stacktrace.join("\n") + "\n");
+        }
+
+        // FreeStacktrace
+        if !self.free_stacktrace.is_empty() {
+            report += "\n===FreeStacktrace===\n";
+            report += &(self.free_stacktrace.join("\n") + "\n");
+        }
+
+        // PartialData
+        if !self.partial_data.is_empty() {
+            report += &format!("\nPartialData: {}\n", &self.partial_data);
+        }
+
+        // CrashProbability
+        if let Some(probability) = self.crash_probability {
+            report += &format!("\nCrashProbability: {probability:.2}\n");
+        }
+
+        // ResourceUsage
+        if !self.resource_usage.is_empty() {
+            report += &format!("\n===ResourceUsage===\n{}\n", self.resource_usage);
+        }
+
+        // Verification
+        if !self.verification.is_empty() {
+            report += &format!("\n===Verification===\n{}\n", self.verification);
+        }
+
         // Registers
         if !self.registers.is_empty() {
             report += "\n===CrashState===\n";
-            for (reg, value) in &self.registers {
+            for (reg, value) in self.sorted_registers() {
                 report += &format!("{reg}:    0x{value:x}\n");
             }
 
@@ -673,6 +2200,12 @@ impl fmt::Display for CrashReport {
             }
         }
 
+        // InputRegisters
+        if !self.input_registers.is_empty() {
+            report += "\n===InputRegisters===\n";
+            report += &(self.input_registers.join("\n") + "\n");
+        }
+
         // Package
         if !self.package.is_empty() {
             report += &format!("\nPackage: {}\n", &self.package);
@@ -699,12 +2232,147 @@ impl fmt::Display for CrashReport {
             report += &(self.asan_report.join("\n") + "\n");
         }
 
+        // Leaks
+        if !self.leaks.is_empty() {
+            report += "\n===Leaks===\n";
+            for leak in self.leaks.iter() {
+                report += &format!(
+                    "{} leak of {} byte(s) in {} object(s)",
+                    leak.kind, leak.bytes, leak.objects
+                );
+                if let Ok(crashline) = leak.crash_line() {
+                    report += &format!(" at {crashline}");
+                }
+                report += "\n";
+            }
+            let (bytes, objects) = LeakRecord::total(&self.leaks);
+            report += &format!("Total: {bytes} byte(s) in {objects} object(s)\n");
+        }
+
+        // OutOfMemory
+        if let Some(oom) = &self.oom {
+            report += "\n===OutOfMemory===\n";
+            if let Some(size) = oom.malloc_size {
+                report += &format!("Malloc size: {size}\n");
+            }
+            if let (Some(used), Some(limit)) = (oom.used_mb, oom.rss_limit_mb) {
+                report += &format!("Used: {used} MB, rss limit: {limit} MB\n");
+            }
+            if !oom.live_heap.is_empty() {
+                report += &format!("Live heap: {} byte(s)\n", oom.live_heap_bytes);
+            }
+            for allocation in oom.live_heap.iter() {
+                report += &format!(
+                    "{} byte(s) in {} allocation(s)\n",
+                    allocation.bytes, allocation.count
+                );
+            }
+        }
+
+        // Globals
+        if !self.globals.is_empty() {
+            report += "\n===Globals===\n";
+            for global in self.globals.iter() {
+                report += &format!(
+                    "'{}' of size {} defined in {}\n",
+                    global.name, global.size, global.location
+                );
+            }
+        }
+
+        // OtherErrors
+        if !self.other_errors.is_empty() {
+            report += "\n===OtherErrors===\n";
+            report += &(self.other_errors.join("\n") + "\n");
+        }
+
+        // MSANreport
+        if !self.msan_report.is_empty() {
+            report += "\n===MsanReport===\n";
+            report += &(self.msan_report.join("\n") + "\n");
+        }
+
+        // MsanOrigin
+        if !self.msan_origin.stacktrace.is_empty() {
+            report += "\n===MsanOrigin===\n";
+            report += &format!(
+                "Uninitialized value was created by {}\n",
+                self.msan_origin.description
+            );
+            report += &(self.msan_origin.stacktrace.join("\n") + "\n");
+            for copy in self.msan_origin.copies.iter() {
+                report += "Uninitialized value was stored to memory at\n";
+                report += &(copy.join("\n") + "\n");
+            }
+        }
+
+        // GWP-ASan report
+        if !self.gwp_asan_report.is_empty() {
+            report += "\n===GwpAsanReport===\n";
+            report += &(self.gwp_asan_report.join("\n") + "\n");
+        }
+
+        // HWASan report
+        if !self.hwasan_report.is_empty() {
+            report += "\n===HwasanReport===\n";
+            report += &(self.hwasan_report.join("\n") + "\n");
+        }
+
+        // Valgrind report
+        if !self.valgrind_report.is_empty() {
+            report += "\n===ValgrindReport===\n";
+            report += &(self.valgrind_report.join("\n") + "\n");
+        }
+
+        // Kernel report
+        if !self.kernel_report.is_empty() {
+            report += "\n===KernelReport===\n";
+            report += &(self.kernel_report.join("\n") + "\n");
+        }
+
+        // Reproducer
+        if !self.reproducer.is_empty() {
+            report += "\n===Reproducer===\n";
+            report += &(self.reproducer.join("\n") + "\n");
+        }
+
         // UBSANreport
         if !self.ubsan_report.is_empty() {
             report += "\n===UbsanReport===\n";
             report += &(self.ubsan_report.join("\n") + "\n");
         }
 
+        // UBSAN warnings
+        if !self.ubsan_warnings.is_empty() {
+            report += "\n===UbsanWarnings===\n";
+            report += &(self.ubsan_warnings.join("\n") + "\n");
+        }
+
+        // TSANreport
+        if !self.tsan_report.is_empty() {
+            report += "\n===TsanReport===\n";
+            report += &(self.tsan_report.join("\n") + "\n");
+        }
+
+        // MutexAcquisitions
+        if !self.mutex_acquisitions.is_empty() {
+            report += "\n===MutexAcquisitions===\n";
+            for acquisition in self.mutex_acquisitions.iter() {
+                if acquisition.held_mutex.is_empty() {
+                    report += &format!(
+                        "Mutex {} acquired in {}\n",
+                        acquisition.mutex, acquisition.thread
+                    );
+                } else {
+                    report += &format!(
+                        "Mutex {} acquired while holding mutex {} in {}\n",
+                        acquisition.mutex, acquisition.held_mutex, acquisition.thread
+                    );
+                }
+                report += &(acquisition.stacktrace.join("\n") + "\n");
+            }
+        }
+
         // PythonReport
         if !self.python_report.is_empty() {
             report += "\n===PythonReport===\n";
@@ -751,6 +2419,14 @@ impl fmt::Display for CrashReport {
             report += &(self.source.join("\n") + "\n");
         }
 
+        // SourceContexts
+        if !self.source_contexts.is_empty() {
+            report += "\n===SourceContexts===\n";
+            for context in self.source_contexts.iter() {
+                report += &format!("{context}\n");
+            }
+        }
+
         write!(f, "{}", report.trim())
     }
 }
@@ -768,7 +2444,7 @@ impl fmt::Display for CrashReport {
 pub fn dedup_reports(casreps: &[CrashReport]) -> Result<Vec<bool>> {
     let traces: Vec<Stacktrace> = casreps
         .iter()
-        .map(|report| report.filtered_stacktrace())
+        .map(|report| report.normalized_stacktrace())
         .collect::<Result<_>>()?;
 
     Ok(dedup_stacktraces(&traces))
@@ -787,12 +2463,100 @@ pub fn dedup_reports(casreps: &[CrashReport]) -> Result<Vec<bool>> {
 pub fn cluster_reports(casreps: &[CrashReport]) -> Result<Vec<usize>> {
     let traces: Vec<Stacktrace> = casreps
         .iter()
-        .map(|report| report.filtered_stacktrace())
+        .map(|report| report.normalized_stacktrace())
         .collect::<Result<_>>()?;
 
     cluster_stacktraces(&traces)
 }
 
+/// Upgrade report in JSON format from older format version to the current one.
+/// Each format version change is handled by its own upgrade step.
+///
+/// # Arguments
+///
+/// * `report` - report in JSON format
+///
+/// # Return value
+///
+/// true if report is upgraded, false if report already has current version
+#[cfg(feature = "serde")]
+pub fn upgrade_report(report: &mut Value) -> Result<bool> {
+    let Some(fields) = report.as_object_mut() else {
+        return Err(Error::Casr("Report is not a JSON object".to_string()));
+    };
+    let version = match fields.get("ReportVersion") {
+        None => 0,
+        Some(version) => version
+            .as_u64()
+            .ok_or_else(|| Error::Casr(format!("Invalid report version: {version}")))?,
+    };
+    if version > REPORT_VERSION as u64 {
+        return Err(Error::Casr(format!(
+            "Report version {version} is newer than supported version {REPORT_VERSION}"
+        )));
+    }
+    if version == REPORT_VERSION as u64 {
+        return Ok(false);
+    }
+    let steps: [fn(&mut Map<String, Value>); REPORT_VERSION as usize] =
+        [upgrade_report_v0, upgrade_report_v1];
+    for step in &steps[version as usize..] {
+        step(fields);
+    }
+    fields.insert("ReportVersion".to_string(), Value::from(REPORT_VERSION));
+    Ok(true)
+}
+
+/// Upgrade report from version 0 (unversioned reports) to version 1. Registers
+/// were saved in "Prstatus" object by older CASR versions.
+///
+/// # Arguments
+///
+/// * `report` - report fields
+#[cfg(feature = "serde")]
+fn upgrade_report_v0(report: &mut Map<String, Value>) {
+    if let Some(mut prstatus) = report.remove("Prstatus") {
+        if let Some(registers) = prstatus.get_mut("registers").map(Value::take) {
+            report.entry("Registers").or_insert(registers);
+        }
+    }
+}
+
+/// Upgrade report from version 1 to version 2. Severity score is computed
+/// from crash classification.
+///
+/// # Arguments
+///
+/// * `report` - report fields
+#[cfg(feature = "serde")]
+fn upgrade_report_v1(report: &mut Map<String, Value>) {
+    let Some(class) = report
+        .get("CrashSeverity")
+        .and_then(|class| serde_json::from_value::<ExecutionClass>(class.clone()).ok())
+    else {
+        return;
+    };
+    report
+        .entry("SeverityScore")
+        .or_insert(Value::from(class.score()));
+}
+
+/// Compute SHA-256 hash
+///
+/// # Arguments
+///
+/// * `data` - data to hash
+///
+/// # Return value
+///
+/// Hash as hex string
+fn sha256(data: &[u8]) -> String {
+    Sha256::digest(data)
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -823,19 +2587,44 @@ mod tests {
             "      0x555555554000     0x555555556000     0x2000        0x0 /usr/local/bin/tiff2pdf"
                 .to_string(),
         ];
+        report.aslr = "off".to_string();
+        report.module_bases = vec!["0x555555554000 /usr/local/bin/tiff2pdf".to_string()];
         report.stacktrace = vec![
                 "    #0 0x4ca0e0 in std::__cxx11::basic_string<char, std::char_traits<char>, std::allocator<char>>::_M_data() const /gcc-build/x86_64-pc-linux-gnu/libstdc++-v3/include/bits/basic_string.h:187:28".to_string(),
                 "    #1 0x4ca0e0 in std::__cxx11::basic_string<char, std::char_traits<char>, std::allocator<char>>::_M_is_local() const /gcc-build/x86_64-pc-linux-gnu/libstdc++-v3/include/bits/basic_string.h:222:26".to_string(),
             ];
+        report.stack_hash = "af63ad4c86019ee1".to_string();
         report.disassembly = vec!["==> 0x5e425d: mov eax, dword ptr [rax]".to_string()];
+        report.partial_data = "gdb timeout (300 s), only stack trace is collected".to_string();
+        report.crash_probability = Some(0.25);
         report.asan_report = vec![
                 "==363912==ERROR: AddressSanitizer: SEGV on unknown address 0xffffffffffffffe0 (pc 0x0000004ca0e0 bp 0x7fffffff9980 sp 0x7fffffff9928 T0)".to_string(),
                 "==363912==The signal is caused by a READ memory access.".to_string(),
             ];
+        report.leaks = vec![LeakRecord {
+            kind: "Direct".to_string(),
+            bytes: 7,
+            objects: 1,
+            stacktrace: vec![],
+        }];
+        report.globals = vec![AsanGlobal {
+            name: "counter".to_string(),
+            size: 4,
+            location: "/tmp/odr/a.cc:1:5".to_string(),
+        }];
+        report.msan_report = vec![
+            "==5174==WARNING: MemorySanitizer: use-of-uninitialized-value".to_string(),
+            "    #0 0x4a0ad3 in main /tmp/test_msan.c:14:7".to_string(),
+        ];
         report.ubsan_report = vec![
                 "/home/hkctkuy/github/casr/casr/tests/tmp_tests_casr/test_casr_ubsan/test_ubsan.cpp:4:29: runtime error: signed integer overflow: 65535 * 32769 cannot be represented in type 'int'".to_string(),
                 "SUMMARY: UndefinedBehaviorSanitizer: signed-integer-overflow /home/hkctkuy/github/casr/casr/tests/tmp_tests_casr/test_casr_ubsan/test_ubsan.cpp:4:29 in".to_string(),
             ];
+        report.ubsan_warnings = vec![
+            "/tmp/test.c:7:10: runtime error: shift exponent 32 is too large for 32-bit type 'int'"
+                .to_string(),
+            "SUMMARY: UndefinedBehaviorSanitizer: shift-exponent /tmp/test.c:7:10 in".to_string(),
+        ];
         report.python_report = vec![
             " === Uncaught Python exception: ===".to_string(),
             "TypeError: unhashable type: 'list'".to_string(),
@@ -898,6 +2687,11 @@ mod tests {
             "===ProcFiles===".to_string(),
             "      0x555555554000     0x555555556000     0x2000        0x0 /usr/local/bin/tiff2pdf".to_string(),
             "".to_string(),
+            "ASLR: off".to_string(),
+            "".to_string(),
+            "===ModuleBases===".to_string(),
+            "0x555555554000 /usr/local/bin/tiff2pdf".to_string(),
+            "".to_string(),
             "===CrashSeverity===".to_string(),
             "Severity: NOT_EXPLOITABLE".to_string(),
             "Short description: SourceAv".to_string(),
@@ -908,14 +2702,35 @@ mod tests {
             "    #0 0x4ca0e0 in std::__cxx11::basic_string<char, std::char_traits<char>, std::allocator<char>>::_M_data() const /gcc-build/x86_64-pc-linux-gnu/libstdc++-v3/include/bits/basic_string.h:187:28".to_string(),
             "    #1 0x4ca0e0 in std::__cxx11::basic_string<char, std::char_traits<char>, std::allocator<char>>::_M_is_local() const /gcc-build/x86_64-pc-linux-gnu/libstdc++-v3/include/bits/basic_string.h:222:26".to_string(),
             "".to_string(),
+            "StackHash: af63ad4c86019ee1".to_string(),
+            "".to_string(),
+            "PartialData: gdb timeout (300 s), only stack trace is collected".to_string(),
+            "".to_string(),
+            "CrashProbability: 0.25".to_string(),
+            "".to_string(),
             "===AsanReport===".to_string(),
             "==363912==ERROR: AddressSanitizer: SEGV on unknown address 0xffffffffffffffe0 (pc 0x0000004ca0e0 bp 0x7fffffff9980 sp 0x7fffffff9928 T0)".to_string(),
             "==363912==The signal is caused by a READ memory access.".to_string(),
             "".to_string(),
+            "===Leaks===".to_string(),
+            "Direct leak of 7 byte(s) in 1 object(s)".to_string(),
+            "Total: 7 byte(s) in 1 object(s)".to_string(),
+            "".to_string(),
+            "===Globals===".to_string(),
+            "'counter' of size 4 defined in /tmp/odr/a.cc:1:5".to_string(),
+            "".to_string(),
+            "===MsanReport===".to_string(),
+            "==5174==WARNING: MemorySanitizer: use-of-uninitialized-value".to_string(),
+            "    #0 0x4a0ad3 in main /tmp/test_msan.c:14:7".to_string(),
+            "".to_string(),
             "===UbsanReport===".to_string(),
             "/home/hkctkuy/github/casr/casr/tests/tmp_tests_casr/test_casr_ubsan/test_ubsan.cpp:4:29: runtime error: signed integer overflow: 65535 * 32769 cannot be represented in type 'int'".to_string(),
             "SUMMARY: UndefinedBehaviorSanitizer: signed-integer-overflow /home/hkctkuy/github/casr/casr/tests/tmp_tests_casr/test_casr_ubsan/test_ubsan.cpp:4:29 in".to_string(),
             "".to_string(),
+            "===UbsanWarnings===".to_string(),
+            "/tmp/test.c:7:10: runtime error: shift exponent 32 is too large for 32-bit type 'int'".to_string(),
+            "SUMMARY: UndefinedBehaviorSanitizer: shift-exponent /tmp/test.c:7:10 in".to_string(),
+            "".to_string(),
             "===PythonReport===".to_string(),
             " === Uncaught Python exception: ===".to_string(),
             "TypeError: unhashable type: 'list'".to_string(),
@@ -992,4 +2807,519 @@ mod tests {
         assert_eq!(res[0], 1);
         assert_eq!(res[1], 1);
     }
+
+    #[test]
+    fn test_sorted_registers() {
+        let mut report = CrashReport::new();
+        for (name, value) in [("rip", 3), ("r10", 2), ("rax", 0), ("r8", 1), ("eflags", 4)] {
+            report.registers.insert(name.to_string(), value);
+        }
+        let names: Vec<&str> = report
+            .sorted_registers()
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect();
+        assert_eq!(names, vec!["eflags", "r8", "r10", "rax", "rip"]);
+    }
+
+    #[test]
+    fn test_severity_score() {
+        for (class, rw, score) in [
+            ("SegFaultOnPc", None, 9.0),
+            ("DestAvNearNull", None, 3.0),
+            ("heap-buffer-overflow", Some("WRITE"), 8.3),
+            ("stack-buffer-overflow", Some("READ"), 1.5),
+            ("SourceAv", None, 1.0),
+        ] {
+            let Ok(class) = ExecutionClass::san_find(class, rw, false) else {
+                panic!("Couldn't find class {class}");
+            };
+            assert_eq!(class.score(), score, "{}", class.short_description);
+        }
+        assert_eq!(ExecutionClass::default().score(), 0.0);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_report_upgrade() {
+        let json = r##"{
+            "Date": "2022-09-28T14:36:55.238287+03:00",
+            "Stacktrace": ["#0  0x0000555555555149 in main ()"],
+            "Prstatus": {"registers": {"rip": 93824992235849}}
+        }"##;
+        let mut value: Value = serde_json::from_str(json).unwrap();
+        let Ok(upgraded) = upgrade_report(&mut value) else {
+            panic!("Couldn't upgrade report");
+        };
+        assert!(upgraded);
+        assert_eq!(value["ReportVersion"], REPORT_VERSION);
+        assert!(value.get("Prstatus").is_none());
+        let Ok(upgraded) = upgrade_report(&mut value) else {
+            panic!("Couldn't upgrade report");
+        };
+        assert!(!upgraded);
+
+        let Ok(report) = CrashReport::from_json(json) else {
+            panic!("Couldn't parse report");
+        };
+        assert_eq!(report.report_version, REPORT_VERSION);
+        assert_eq!(report.registers.get("rip"), Some(&0x555555555149));
+
+        let json = r#"{
+            "ReportVersion": 1,
+            "CrashSeverity": {
+                "Type": "EXPLOITABLE",
+                "ShortDescription": "heap-buffer-overflow(write)",
+                "Description": "Heap buffer overflow",
+                "Explanation": ""
+            }
+        }"#;
+        let Ok(report) = CrashReport::from_json(json) else {
+            panic!("Couldn't parse report");
+        };
+        assert_eq!(report.severity_score, 8.3);
+
+        let json = r#"{"ReportVersion": 1000}"#;
+        assert!(CrashReport::from_json(json).is_err());
+    }
+
+    #[test]
+    fn test_report_builder() {
+        safe_init_ignore_stack_frames();
+        let stderr = [
+            "Reading input",
+            "=================================================================",
+            "==5678==ERROR: AddressSanitizer: heap-use-after-free on address 0x602000000010 at pc 0x4da2f1 bp 0x7ffc6f3b5e50 sp 0x7ffc6f3b5e48",
+            "READ of size 4 at 0x602000000010 thread T0",
+            "    #0 0x4da2f0 in main /tmp/test_uaf.c:8:12",
+            "",
+            "0x602000000010 is located 0 bytes inside of 4-byte region [0x602000000010,0x602000000014)",
+            "freed by thread T0 here:",
+            "    #0 0x4a0d52 in free /llvm/compiler-rt/lib/asan/asan_malloc_linux.cpp:52:3",
+            "    #1 0x4da2b8 in main /tmp/test_uaf.c:7:5",
+            "",
+            "previously allocated by thread T0 here:",
+            "    #0 0x4a0fbd in malloc /llvm/compiler-rt/lib/asan/asan_malloc_linux.cpp:69:3",
+            "    #1 0x4da2a8 in main /tmp/test_uaf.c:6:14",
+            "",
+            "SUMMARY: AddressSanitizer: heap-use-after-free /tmp/test_uaf.c:8:12 in main",
+        ]
+        .join("\n");
+
+        let mut builder = CrashReport::builder();
+        builder
+            .executable("/tmp/test_uaf")
+            .cmdline(&["/tmp/test_uaf", "input"])
+            .stderr(&stderr)
+            .exit_status(1);
+        let Ok(builder) = builder.classify() else {
+            panic!("Couldn't classify report");
+        };
+        let report = builder.build();
+        assert_eq!(report.proc_cmdline, "/tmp/test_uaf input");
+        assert_eq!(report.exit_status, Some(1));
+        assert_eq!(report.stderr.len(), 16);
+        assert_eq!(
+            report.execution_class.short_description,
+            "heap-use-after-free(read)"
+        );
+        assert_eq!(report.severity_score, report.execution_class.score());
+        assert_eq!(report.asan_report[0], "==5678==ERROR: AddressSanitizer: heap-use-after-free on address 0x602000000010 at pc 0x4da2f1 bp 0x7ffc6f3b5e50 sp 0x7ffc6f3b5e48");
+        assert_eq!(report.stacktrace.len(), 1);
+        assert_eq!(report.free_stacktrace.len(), 2);
+        assert_eq!(report.alloc_stacktrace.len(), 2);
+        assert_eq!(report.crashline, "/tmp/test_uaf.c:8:12");
+        assert_eq!(
+            report.stack_hash,
+            stack_hash(&report.normalized_stacktrace().unwrap())
+        );
+        assert_eq!(report.stack_hash.len(), 16);
+
+        // Crash line is obtained from stack trace without sanitizer report.
+        let report = CrashReport::builder()
+            .stacktrace(vec![
+                "#0  0x0000555555555149 in crash (p=0x0) at /tmp/test.c:3".to_string(),
+                "#1  0x0000555555555170 in main () at /tmp/test.c:8".to_string(),
+            ])
+            .signal(11)
+            .classify()
+            .map(|builder| builder.build());
+        let Ok(report) = report else {
+            panic!("Couldn't classify report");
+        };
+        assert_eq!(report.crashline, "/tmp/test.c:3");
+        assert_eq!(report.signal, Some(11));
+
+        assert!(CrashReport::builder().classify().is_err());
+    }
+
+    #[test]
+    fn test_set_from_san_log() {
+        safe_init_ignore_stack_frames();
+        let mut report = CrashReport::new();
+        let output = "no sanitizer report";
+        assert!(matches!(report.set_from_san_log(output, false), Ok(None)));
+
+        let output = [
+            "/tmp/test.c:5:10: runtime error: signed integer overflow: 2147483647 + 1 cannot be represented in type 'int'",
+            "SUMMARY: UndefinedBehaviorSanitizer: signed-integer-overflow /tmp/test.c:5:10",
+        ]
+        .join("\n");
+        // Runtime error is not the reason of crash.
+        assert!(matches!(report.set_from_san_log(&output, true), Ok(None)));
+        let Ok(Some(stacktrace)) = report.set_from_san_log(&output, false) else {
+            panic!("Couldn't find UBSAN runtime error");
+        };
+        assert!(stacktrace.is_empty());
+        assert_eq!(report.crashline, "/tmp/test.c:5:10");
+        assert_eq!(report.ubsan_report.len(), 2);
+    }
+
+    #[test]
+    fn test_largest_leak_stacktrace() {
+        safe_init_ignore_stack_frames();
+        let output = [
+            "==1234==ERROR: LeakSanitizer: detected memory leaks",
+            "",
+            "Direct leak of 7 byte(s) in 1 object(s) allocated from:",
+            "    #0 0x4af01b in __interceptor_malloc /llvm/compiler-rt/lib/asan/asan_malloc_linux.cpp:145:3",
+            "    #1 0x4da26a in main /tmp/test_leak.c:6:25",
+            "",
+            "Direct leak of 40 byte(s) in 2 object(s) allocated from:",
+            "    #0 0x4af01b in __interceptor_malloc /llvm/compiler-rt/lib/asan/asan_malloc_linux.cpp:145:3",
+            "    #1 0x4da2c1 in foo /tmp/test_leak.c:12:13",
+            "    #2 0x4da26a in main /tmp/test_leak.c:7:5",
+            "",
+            "SUMMARY: AddressSanitizer: 47 byte(s) leaked in 3 allocation(s).",
+        ]
+        .join("\n");
+        let mut report = CrashReport::new();
+        let Ok(Some(stacktrace)) = report.set_from_san_log(&output, false) else {
+            panic!("Couldn't find LeakSanitizer report");
+        };
+        assert_eq!(report.execution_class.short_description, "memory-leaks");
+        assert_eq!(report.leaks.len(), 2);
+        assert_eq!(report.stacktrace, report.leaks[1].stacktrace);
+        assert_eq!(stacktrace.len(), 3);
+        assert_eq!(stacktrace[1].function, "foo");
+    }
+
+    #[test]
+    fn test_odr_violation_dedup() {
+        safe_init_ignore_stack_frames();
+        let output = |name: &str| {
+            [
+                "==1234==ERROR: AddressSanitizer: odr-violation (0x000000601f00):".to_string(),
+                format!("  [1] size=4 '{name}' /tmp/odr/a.cc:1:5"),
+                format!("  [2] size=4 '{name}' /tmp/odr/b.cc:1:5"),
+                "These globals were registered at these points:".to_string(),
+                "  [1]:".to_string(),
+                "    #0 0x41c2a7 in __asan_register_globals /llvm/compiler-rt/lib/asan/asan_globals.cpp:360:3".to_string(),
+                "    #1 0x4f6d2e in asan.module_ctor (/tmp/odr/test+0x4f6d2e)".to_string(),
+                "".to_string(),
+                format!("SUMMARY: AddressSanitizer: odr-violation: global '{name}' at /tmp/odr/a.cc:1:5"),
+            ]
+            .join("\n")
+        };
+        let reports: Vec<CrashReport> = ["first", "second", "first"]
+            .iter()
+            .map(|name| {
+                let mut report = CrashReport::new();
+                let Ok(Some(_)) = report.set_from_san_log(&output(name), false) else {
+                    panic!("Couldn't find AddressSanitizer report");
+                };
+                report
+            })
+            .collect();
+        assert_eq!(
+            reports[0].execution_class.short_description,
+            "odr-violation"
+        );
+        assert_eq!(reports[1].globals.len(), 2);
+        assert_eq!(reports[1].globals[0].name, "second");
+        let Ok(stacktrace) = reports[1].normalized_stacktrace() else {
+            panic!("Couldn't get normalized stack trace");
+        };
+        assert_eq!(stacktrace[0].function, "second");
+        assert_eq!(dedup_reports(&reports).unwrap(), vec![true, true, false]);
+    }
+
+    #[test]
+    fn test_recovered_asan_warnings() {
+        safe_init_ignore_stack_frames();
+        let output = [
+            "=================================================================",
+            "==4321==WARNING: AddressSanitizer: heap-buffer-overflow on address 0x602000000018 at pc 0x4da54e bp 0x7ffd0c9d1ef0 sp 0x7ffd0c9d1ee8",
+            "WRITE of size 8 at 0x602000000018 thread T0",
+            "    #0 0x4da54d in write_value /tmp/test_recover.c:8:12",
+            "    #1 0x4da612 in main /tmp/test_recover.c:13:5",
+            "",
+            "SUMMARY: AddressSanitizer: heap-buffer-overflow /tmp/test_recover.c:8:12 in write_value",
+            "=================================================================",
+            "==4321==WARNING: AddressSanitizer: stack-buffer-overflow on address 0x7ffd0c9d1f30 at pc 0x4da4c9 bp 0x7ffd0c9d1ef0 sp 0x7ffd0c9d1ee8",
+            "READ of size 4 at 0x7ffd0c9d1f30 thread T0",
+            "    #0 0x4da4c8 in read_value /tmp/test_recover.c:5:12",
+            "    #1 0x4da5f1 in main /tmp/test_recover.c:14:5",
+            "",
+            "SUMMARY: AddressSanitizer: stack-buffer-overflow /tmp/test_recover.c:5:12 in read_value",
+        ]
+        .join("\n");
+        // Warnings are not the reason of crash.
+        let mut report = CrashReport::new();
+        assert!(matches!(report.set_from_san_log(&output, true), Ok(None)));
+
+        let Ok(Some(stacktrace)) = report.set_from_san_log(&output, false) else {
+            panic!("Couldn't find recoverable ASAN warnings");
+        };
+        assert!(report.recovered);
+        assert_eq!(stacktrace.len(), 2);
+        assert_eq!(
+            report.execution_class.short_description,
+            "heap-buffer-overflow(write)"
+        );
+        assert_eq!(report.asan_report.len(), 6);
+        assert!(report.other_errors[0].contains("stack-buffer-overflow"));
+
+        // Fatal errors are preferred to warnings.
+        let output = output.replacen("WARNING", "ERROR", 1);
+        let mut report = CrashReport::new();
+        let Ok(Some(_)) = report.set_from_san_log(&output, false) else {
+            panic!("Couldn't find ASAN report");
+        };
+        assert!(!report.recovered);
+        assert!(report.other_errors.is_empty());
+    }
+
+    #[test]
+    fn test_report_markdown() {
+        safe_init_ignore_stack_frames();
+        let mut report = CrashReport::new();
+        report.execution_class = ExecutionClass::find("DestAv").unwrap();
+        report.crashline = "/tmp/test.c:3".to_string();
+        report.stacktrace = vec![
+            "#0  0x0000555555555149 in crash (p=0x0) at /tmp/test.c:3".to_string(),
+            "#1  0x0000555555555170 in main () at /tmp/test.c:8".to_string(),
+        ];
+        report.os = "ubuntu".to_string();
+        report.os_release = "22.04".to_string();
+        report.architecture = "amd64".to_string();
+        report.proc_cmdline = "./test input".to_string();
+        report.proc_environ = vec![
+            "HOME=/root".to_string(),
+            "ASAN_OPTIONS=detect_leaks=0".to_string(),
+        ];
+        let markdown = report.to_markdown();
+        assert!(markdown.starts_with(&format!(
+            "## {} in crash at /tmp/test.c:3\n",
+            report.execution_class.description
+        )));
+        assert!(markdown.contains("**Severity:** EXPLOITABLE: DestAv"));
+        assert!(markdown.contains("#0 0x555555555149 in crash /tmp/test.c:3\n"));
+        assert!(markdown.contains("#1 0x555555555170 in main /tmp/test.c:8\n"));
+        assert!(markdown.contains("- **OS:** ubuntu 22.04\n"));
+        assert!(markdown.contains("- `ASAN_OPTIONS=detect_leaks=0`\n"));
+        assert!(!markdown.contains("HOME"));
+        assert!(!markdown.contains("Package"));
+        assert!(markdown.ends_with("### How to reproduce\n\n```\n./test input\n```\n"));
+    }
+
+    #[test]
+    fn test_normalized_stacktrace() {
+        safe_init_ignore_stack_frames();
+        // The same crash in binary without symbols loaded at different bases.
+        let report = |base: u64| {
+            let mut report = CrashReport::new();
+            report.stacktrace = vec![
+                format!("#0  {:#018x} in crash ()", base + 0x1149),
+                format!("#1  {:#018x} in parse ()", base + 0x1180),
+            ];
+            report.proc_maps = vec![format!(
+                "{:#20x} {:#18x} {:#10x} {:#10x} /tmp/target",
+                base + 0x1000,
+                base + 0x2000,
+                0x1000,
+                0x1000
+            )];
+            report
+        };
+        let reports = [report(0x555555554000), report(0x5615e2a3a000)];
+        let Ok(stacktrace) = reports[1].normalized_stacktrace() else {
+            panic!("Couldn't get normalized stack trace");
+        };
+        assert_eq!(stacktrace.len(), 2);
+        assert_eq!(stacktrace[0].address, 0x1149);
+        assert_eq!(stacktrace[0].module, "target");
+        assert_eq!(stacktrace[1].offset, 0x1180);
+        assert_eq!(dedup_reports(&reports).unwrap(), vec![true, false]);
+    }
+
+    #[test]
+    fn test_report_frames() {
+        let mut report = CrashReport::new();
+        report.stacktrace = vec![
+            "#0  0x0000555555555149 in crash (p=0x0) at /tmp/test.c:3".to_string(),
+            "#1  0x0000555555555170 in main () at /tmp/test.c:8".to_string(),
+        ];
+        report.set_frames();
+        assert_eq!(report.frames.len(), 2);
+        assert_eq!(report.frames[0].address, 0x555555555149);
+        assert_eq!(report.frames[0].function, "crash");
+        assert_eq!(report.frames[0].file, "/tmp/test.c");
+        assert_eq!(report.frames[0].line, 3);
+        assert_eq!(report.frames[1].function, "main");
+
+        // Stored frames are used instead of parsing raw lines.
+        report.stacktrace = vec!["unparsable".to_string()];
+        let Ok(stacktrace) = report.parsed_stacktrace() else {
+            panic!("Couldn't get parsed stack trace");
+        };
+        assert_eq!(stacktrace.len(), 2);
+        assert_eq!(stacktrace[1].debug.line, 8);
+        assert_eq!(StackFrame::from(&stacktrace[0]), report.frames[0]);
+
+        // Frames are cleared when stack trace couldn't be parsed.
+        report.frames.clear();
+        report.set_frames();
+        assert!(report.frames.is_empty());
+
+        // Lldb stack trace (macOS).
+        report.stacktrace = vec![
+            "  * frame #0: 0x0000000100003f74 test`crash(p=0x0000000000000000) at test.c:5:10"
+                .to_string(),
+            "    frame #1: 0x0000000100003f9c test`main(argc=1) at test.c:10:3".to_string(),
+        ];
+        report.set_frames();
+        assert_eq!(report.frames.len(), 2);
+        assert_eq!(report.frames[0].function, "crash");
+        assert_eq!(report.frames[1].line, 10);
+    }
+
+    #[test]
+    fn test_report_input() {
+        assert_eq!(
+            sha256(b""),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+        assert_eq!(
+            sha256(b"abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+
+        let path = std::env::temp_dir().join(format!("casr_input_{}", std::process::id()));
+        fs::write(&path, b"foobar").unwrap();
+        let mut report = CrashReport::new();
+        assert!(report.input().is_err());
+        report.set_input(&path, 0).unwrap();
+        assert!(report
+            .input_path
+            .ends_with(&format!("casr_input_{}", std::process::id())));
+        assert_eq!(report.input_sha256, sha256(b"foobar"));
+        assert!(report.input_data.is_empty());
+        assert!(report.input().is_err());
+        report.set_input(&path, 5).unwrap();
+        assert!(report.input_data.is_empty());
+        report.set_input(&path, 6).unwrap();
+        assert_eq!(report.input_data, "Zm9vYmFy");
+        assert_eq!(report.input().unwrap(), b"foobar");
+        report.input_data = "Zm9v".to_string();
+        assert!(report.input().is_err());
+        report.input_data = "Zm9v!".to_string();
+        assert!(report.input().is_err());
+
+        // Changed input is restored from its copy.
+        report.input_data = "Zm9vYmFy".to_string();
+        assert_eq!(
+            report.restore_input(Path::new("/nonexistent")).unwrap(),
+            PathBuf::from(&report.input_path)
+        );
+        fs::write(&path, b"baz").unwrap();
+        let dir = std::env::temp_dir().join(format!("casr_restore_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let restored = report.restore_input(&dir).unwrap();
+        assert_eq!(restored.parent().unwrap(), dir);
+        assert_eq!(fs::read(&restored).unwrap(), b"foobar");
+        fs::remove_dir_all(&dir).unwrap();
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_report_verification() {
+        safe_init_ignore_stack_frames();
+        let mut report = CrashReport::new();
+        report.execution_class = ExecutionClass::find("DestAv").unwrap();
+        report.crashline = "/tmp/test.c:3".to_string();
+        report.set_verification(None);
+        assert_eq!(report.verification.verdict, "NotReproduced");
+        assert!(report.verification.crashline.is_empty());
+
+        let mut rerun = report.clone();
+        report.set_verification(Some(&rerun));
+        assert_eq!(report.verification.verdict, "Reproduced");
+        assert_eq!(report.verification.crashline, "/tmp/test.c:3");
+        assert_eq!(report.verification.short_description, "DestAv");
+
+        rerun.crashline = "/tmp/test.c:4".to_string();
+        report.set_verification(Some(&rerun));
+        assert_eq!(report.verification.verdict, "Changed");
+        rerun.crashline = report.crashline.clone();
+        rerun.execution_class = ExecutionClass::find("SourceAv").unwrap();
+        report.set_verification(Some(&rerun));
+        assert_eq!(report.verification.verdict, "Changed");
+
+        // Stack hashes are compared when there is no crash line.
+        report.crashline.clear();
+        report.stacktrace =
+            vec!["#0  0x0000555555555149 in crash (p=0x0) at /tmp/test.c:3".to_string()];
+        let mut rerun = report.clone();
+        report.set_verification(Some(&rerun));
+        assert_eq!(report.verification.verdict, "Reproduced");
+        rerun.stacktrace = vec!["#0  0x0000555555555170 in parse () at /tmp/test.c:8".to_string()];
+        report.set_verification(Some(&rerun));
+        assert_eq!(report.verification.verdict, "Changed");
+        assert!(report.to_string().contains("===Verification===\nDate: "));
+    }
+
+    #[test]
+    fn test_report_source_contexts() {
+        safe_init_ignore_stack_frames();
+        let path = std::env::temp_dir().join(format!("casr_source_{}.c", std::process::id()));
+        let source: String = (1..=20).map(|i| format!("line {i}\n")).collect();
+        fs::write(&path, source).unwrap();
+        let file = path.display();
+        let mut report = CrashReport::new();
+        report.stacktrace = vec![
+            format!("#0  0x0000555555555149 in crash (p=0x0) at {file}:3"),
+            "#1  0x0000555555555160 in parse () at /nonexistent/parse.c:10".to_string(),
+            format!("#2  0x0000555555555170 in main () at {file}:12"),
+            format!("#3  0x0000555555555170 in main () at {file}:12"),
+            format!("#4  0x0000555555555180 in run () at {file}:20"),
+        ];
+
+        report.set_source_contexts(2, 1);
+        assert_eq!(report.source_contexts.len(), 2);
+        assert_eq!(report.source_contexts[0].function, "crash");
+        assert_eq!(report.source_contexts[0].line, 3);
+        assert_eq!(
+            report.source_contexts[0].source,
+            vec![
+                "    2      line 2",
+                "--->3      line 3",
+                "    4      line 4"
+            ]
+        );
+        assert_eq!(report.source_contexts[1].function, "main");
+        assert_eq!(report.source_contexts[1].line, 12);
+        assert!(report
+            .to_string()
+            .contains(&format!("===SourceContexts===\ncrash at {file}:3\n")));
+
+        // Recursive frames are captured once.
+        report.set_source_contexts(10, 0);
+        assert_eq!(report.source_contexts.len(), 3);
+        assert_eq!(report.source_contexts[2].source, vec!["--->20     line 20"]);
+
+        report.set_source_contexts(0, 5);
+        assert!(report.source_contexts.is_empty());
+        fs::remove_file(&path).unwrap();
+    }
 }
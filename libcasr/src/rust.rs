@@ -21,6 +21,36 @@ impl Exception for RustPanic {
         } else {
             captures.get(2).unwrap().as_str()
         };
+        // Classify common panics from standard library by their messages.
+        let kinds = [
+            (
+                r"^index out of bounds|^range (start|end) index .* out of range|^slice index starts at|^byte index .* (is out of bounds|is not a char boundary)",
+                "index-out-of-bounds",
+            ),
+            (
+                r"^called `Option::unwrap\(\)` on a `None` value",
+                "unwrap-on-none",
+            ),
+            (
+                r"^called `Result::unwrap\(\)` on an `Err` value",
+                "unwrap-on-err",
+            ),
+            (r"^capacity overflow", "capacity-overflow"),
+            (r"^attempt to [a-z ]+ with overflow", "arithmetic-overflow"),
+            (
+                r"^attempt to (divide by zero|calculate the remainder with a divisor of zero)",
+                "integer-divide-by-zero",
+            ),
+            (r"^assertion( `.+`)? failed", "assertion-failed"),
+        ];
+        if let Some(mut class) = kinds
+            .iter()
+            .find(|(re, _)| Regex::new(re).unwrap().is_match(message))
+            .and_then(|(_, kind)| ExecutionClass::find(kind).ok())
+        {
+            class.description = message.to_string();
+            return Some(class);
+        }
         Some(ExecutionClass::new((
             "NOT_EXPLOITABLE",
             "RustPanic",
@@ -290,9 +320,52 @@ stack backtrace:"#;
             panic!("Couldn't get rust panic");
         };
 
+        assert_eq!(class.short_description, "index-out-of-bounds");
         assert_eq!(
             class.description,
             "index out of bounds: the len is 0 but the index is 10"
         );
+
+        let panics = [
+            (
+                "thread 'main' panicked at src/main.rs:3:37:\ncalled `Option::unwrap()` on a `None` value",
+                "unwrap-on-none",
+            ),
+            (
+                "thread 'main' panicked at 'called `Result::unwrap()` on an `Err` value: ParseIntError { kind: InvalidDigit }', src/main.rs:2:5",
+                "unwrap-on-err",
+            ),
+            (
+                "thread '<unnamed>' panicked at library/alloc/src/raw_vec.rs:25:5:\ncapacity overflow",
+                "capacity-overflow",
+            ),
+            (
+                "thread 'main' panicked at src/main.rs:4:5:\nattempt to add with overflow",
+                "arithmetic-overflow",
+            ),
+            (
+                "thread 'main' panicked at src/main.rs:4:5:\nattempt to divide by zero",
+                "integer-divide-by-zero",
+            ),
+            (
+                "thread 'main' panicked at src/main.rs:4:5:\nassertion `left == right` failed\n  left: 1\n right: 2",
+                "assertion-failed",
+            ),
+            (
+                "thread 'main' panicked at src/main.rs:4:5:\nrange end index 10 out of range for slice of length 4",
+                "index-out-of-bounds",
+            ),
+            (
+                "thread 'main' panicked at src/main.rs:4:5:\nPanicMessage",
+                "RustPanic",
+            ),
+        ];
+        for (panic_info, kind) in panics {
+            let Some(class) = RustPanic::parse_exception(panic_info) else {
+                panic!("Couldn't get rust panic: {panic_info}");
+            };
+            assert_eq!(class.short_description, kind);
+            assert_eq!(class.severity, "NOT_EXPLOITABLE");
+        }
     }
 }
@@ -0,0 +1,527 @@
+//! Schema module provides JSON Schema of CASR reports (.casrep) and validates
+//! reports against it, so that third-party tools may rely on report format.
+use crate::execution_class::CLASSES;
+use crate::report::REPORT_VERSION;
+
+use serde_json::{json, Map, Value};
+
+/// Schema of string
+fn string() -> Value {
+    json!({ "type": "string" })
+}
+
+/// Schema of unsigned integer
+fn unsigned() -> Value {
+    json!({ "type": "integer", "minimum": 0 })
+}
+
+/// Schema of array
+///
+/// # Arguments
+///
+/// * `items` - schema of array items
+fn array(items: Value) -> Value {
+    json!({ "type": "array", "items": items })
+}
+
+/// Schema of string array (e.g., stack trace or report lines)
+fn strings() -> Value {
+    array(string())
+}
+
+/// Schema of value that may be null
+///
+/// # Arguments
+///
+/// * `schema` - schema of non-null value
+fn nullable(mut schema: Value) -> Value {
+    let kind = schema["type"].clone();
+    schema["type"] = json!([kind, "null"]);
+    schema
+}
+
+/// Schema of object without unknown fields
+///
+/// # Arguments
+///
+/// * `properties` - field names with their schemas
+///
+/// * `required` - required field names
+fn object(properties: Vec<(&str, Value)>, required: &[&str]) -> Value {
+    let properties: Map<String, Value> = properties
+        .into_iter()
+        .map(|(name, schema)| (name.to_string(), schema))
+        .collect();
+    json!({
+        "type": "object",
+        "properties": properties,
+        "required": required,
+        "additionalProperties": false,
+    })
+}
+
+/// Get JSON Schema (draft 2020-12) of CASR report in current format version
+pub fn report_schema() -> Value {
+    let mut severities: Vec<&str> = CLASSES.iter().map(|class| class.0).collect();
+    severities.sort();
+    severities.dedup();
+    let execution_class = object(
+        vec![
+            ("Type", json!({ "type": "string", "enum": severities })),
+            ("ShortDescription", string()),
+            ("Description", string()),
+            ("Explanation", string()),
+        ],
+        &["Type", "ShortDescription"],
+    );
+    let frame = object(
+        vec![
+            ("Address", unsigned()),
+            ("Module", string()),
+            ("Offset", unsigned()),
+            ("Function", string()),
+            ("File", string()),
+            ("Line", unsigned()),
+            ("Column", unsigned()),
+        ],
+        &[],
+    );
+    let thread = object(
+        vec![
+            ("Description", string()),
+            ("Crashed", json!({ "type": "boolean" })),
+            ("Stacktrace", strings()),
+        ],
+        &[],
+    );
+    let registers = json!({ "type": "object", "additionalProperties": unsigned() });
+    let resource_usage = object(
+        vec![
+            ("WallTime", unsigned()),
+            ("UserTime", unsigned()),
+            ("SystemTime", unsigned()),
+            ("MaxRss", unsigned()),
+        ],
+        &[],
+    );
+    let verification = object(
+        vec![
+            ("Date", string()),
+            ("Verdict", string()),
+            ("ShortDescription", string()),
+            ("CrashLine", string()),
+        ],
+        &[],
+    );
+    let source_context = object(
+        vec![
+            ("Function", string()),
+            ("File", string()),
+            ("Line", unsigned()),
+            ("Source", strings()),
+        ],
+        &[],
+    );
+    let leak = object(
+        vec![
+            ("Kind", string()),
+            ("Bytes", unsigned()),
+            ("Objects", unsigned()),
+            ("Stacktrace", strings()),
+        ],
+        &[],
+    );
+    let heap_allocation = object(
+        vec![
+            ("Bytes", unsigned()),
+            ("Count", unsigned()),
+            ("Stacktrace", strings()),
+        ],
+        &[],
+    );
+    let oom = nullable(object(
+        vec![
+            ("MallocSize", nullable(unsigned())),
+            ("UsedMb", nullable(unsigned())),
+            ("RssLimitMb", nullable(unsigned())),
+            ("LiveHeapBytes", unsigned()),
+            ("LiveHeap", array(heap_allocation)),
+        ],
+        &[],
+    ));
+    let global = object(
+        vec![
+            ("Name", string()),
+            ("Size", unsigned()),
+            ("Location", string()),
+        ],
+        &[],
+    );
+    let msan_origin = object(
+        vec![
+            ("Description", string()),
+            ("Stacktrace", strings()),
+            ("Copies", array(strings())),
+        ],
+        &[],
+    );
+    let mutex_acquisition = object(
+        vec![
+            ("Mutex", string()),
+            ("HeldMutex", string()),
+            ("Thread", string()),
+            ("Stacktrace", strings()),
+        ],
+        &[],
+    );
+
+    let mut schema = object(
+        vec![
+            (
+                "ReportVersion",
+                json!({ "type": "integer", "const": REPORT_VERSION }),
+            ),
+            ("Date", string()),
+            ("Uname", string()),
+            ("OS", string()),
+            ("OSRelease", string()),
+            ("Architecture", string()),
+            ("ExecutablePath", string()),
+            ("ProcEnviron", strings()),
+            ("ProcCmdline", string()),
+            ("Stdin", string()),
+            ("InputPath", string()),
+            ("InputSha256", string()),
+            ("InputData", string()),
+            ("Stdout", strings()),
+            ("Stderr", strings()),
+            ("ExitStatus", nullable(json!({ "type": "integer" }))),
+            ("Signal", nullable(json!({ "type": "integer" }))),
+            ("ProcStatus", strings()),
+            ("ProcMaps", strings()),
+            ("ASLR", string()),
+            ("ModuleBases", strings()),
+            ("ProcFiles", strings()),
+            ("NetworkConnections", strings()),
+            ("CrashSeverity", execution_class),
+            ("SeverityScore", json!({ "type": "number" })),
+            ("Stacktrace", strings()),
+            ("Frames", array(frame.clone())),
+            ("InlineFrames", array(frame)),
+            ("StackHash", string()),
+            ("Threads", array(thread)),
+            ("AllocStacktrace", strings()),
+            ("FreeStacktrace", strings()),
+            ("Registers", registers),
+            ("InputRegisters", strings()),
+            ("Disassembly", strings()),
+            ("PartialData", string()),
+            ("CrashProbability", nullable(json!({ "type": "number" }))),
+            ("ResourceUsage", resource_usage),
+            ("Verification", verification),
+            ("Package", string()),
+            ("PackageVersion", string()),
+            ("PackageArchitecture", string()),
+            ("PackageDescription", string()),
+            ("AsanReport", strings()),
+            ("Leaks", array(leak)),
+            ("OutOfMemory", oom),
+            ("Globals", array(global)),
+            ("OtherErrors", strings()),
+            ("Recovered", json!({ "type": "boolean" })),
+            ("MsanReport", strings()),
+            ("MsanOrigin", msan_origin),
+            ("GwpAsanReport", strings()),
+            ("HwasanReport", strings()),
+            ("ValgrindReport", strings()),
+            ("KernelReport", strings()),
+            ("Reproducer", strings()),
+            ("UbsanReport", strings()),
+            ("UbsanWarnings", strings()),
+            ("TsanReport", strings()),
+            ("MutexAcquisitions", array(mutex_acquisition)),
+            ("PythonReport", strings()),
+            ("GoReport", strings()),
+            ("JavaReport", strings()),
+            ("RustReport", strings()),
+            ("JsReport", strings()),
+            ("CrashLine", string()),
+            ("Source", strings()),
+            ("SourceContexts", array(source_context)),
+        ],
+        &["ReportVersion", "CrashSeverity", "Stacktrace"],
+    );
+    let fields = schema.as_object_mut().unwrap();
+    fields.insert(
+        "$schema".to_string(),
+        json!("https://json-schema.org/draft/2020-12/schema"),
+    );
+    fields.insert("title".to_string(), json!("CASR crash report"));
+    schema
+}
+
+/// Validate CASR report against report schema
+///
+/// # Arguments
+///
+/// * `report` - report in JSON format
+///
+/// # Return value
+///
+/// Validation errors with JSON pointers to invalid values
+pub fn validate_report(report: &Value) -> Vec<String> {
+    let mut errors = Vec::new();
+    validate(&report_schema(), report, "", &mut errors);
+    errors
+}
+
+/// Get JSON type name of value
+///
+/// # Arguments
+///
+/// * `value` - JSON value
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(number) if number.is_f64() => "number",
+        Value::Number(_) => "integer",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+/// Validate value against schema (keywords used by report schema are
+/// supported only)
+///
+/// # Arguments
+///
+/// * `schema` - JSON Schema
+///
+/// * `value` - JSON value
+///
+/// * `path` - JSON pointer to value
+///
+/// * `errors` - validation errors
+fn validate(schema: &Value, value: &Value, path: &str, errors: &mut Vec<String>) {
+    let location = if path.is_empty() { "/" } else { path };
+    let actual = type_name(value);
+    if let Some(kind) = schema.get("type") {
+        let kinds: Vec<&str> = match kind {
+            Value::Array(kinds) => kinds.iter().filter_map(Value::as_str).collect(),
+            kind => kind.as_str().into_iter().collect(),
+        };
+        // Integer is a number as well.
+        if !kinds
+            .iter()
+            .any(|kind| *kind == actual || (*kind == "number" && actual == "integer"))
+        {
+            errors.push(format!(
+                "{location}: expected {}, got {actual}",
+                kinds.join(" or ")
+            ));
+            return;
+        }
+    }
+    if let Some(expected) = schema.get("const") {
+        if value != expected {
+            errors.push(format!("{location}: expected {expected}, got {value}"));
+        }
+    }
+    if let Some(Value::Array(variants)) = schema.get("enum") {
+        if !variants.contains(value) {
+            let variants: Vec<String> = variants.iter().map(Value::to_string).collect();
+            errors.push(format!(
+                "{location}: {value} is not one of {}",
+                variants.join(", ")
+            ));
+        }
+    }
+    if let (Some(minimum), Some(number)) = (
+        schema.get("minimum").and_then(Value::as_f64),
+        value.as_f64(),
+    ) {
+        if number < minimum {
+            errors.push(format!("{location}: {value} is less than {minimum}"));
+        }
+    }
+    if let (Some(items), Value::Array(array)) = (schema.get("items"), value) {
+        for (i, item) in array.iter().enumerate() {
+            validate(items, item, &format!("{path}/{i}"), errors);
+        }
+    }
+    let Value::Object(fields) = value else {
+        return;
+    };
+    if let Some(Value::Array(required)) = schema.get("required") {
+        for name in required.iter().filter_map(Value::as_str) {
+            if !fields.contains_key(name) {
+                errors.push(format!("{location}: missing required field {name}"));
+            }
+        }
+    }
+    let properties = schema.get("properties").and_then(Value::as_object);
+    for (name, field) in fields {
+        // JSON pointer escaping.
+        let field_path = format!("{path}/{}", name.replace('~', "~0").replace('/', "~1"));
+        match (
+            properties.and_then(|properties| properties.get(name)),
+            schema.get("additionalProperties"),
+        ) {
+            (Some(field_schema), _) => validate(field_schema, field, &field_path, errors),
+            (None, Some(Value::Bool(false))) => {
+                errors.push(format!("{field_path}: unknown field"));
+            }
+            (None, Some(additional)) => validate(additional, field, &field_path, errors),
+            (None, None) => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::asan::{AsanGlobal, HeapAllocation, LeakRecord, OutOfMemory};
+    use crate::execution_class::ExecutionClass;
+    use crate::gdb::ThreadStacktrace;
+    use crate::msan::MsanOrigin;
+    use crate::report::{CrashReport, SourceContext};
+    use crate::stacktrace::StackFrame;
+    use crate::tsan::MutexAcquisition;
+
+    #[test]
+    fn test_report_schema() {
+        // Every report field is described by schema and vice versa.
+        let schema = report_schema();
+        let properties = schema["properties"].as_object().unwrap();
+        let report = serde_json::to_value(CrashReport::new()).unwrap();
+        let fields = report.as_object().unwrap();
+        for name in fields.keys() {
+            assert!(properties.contains_key(name), "{name} isn't in schema");
+        }
+        for name in properties.keys() {
+            assert!(fields.contains_key(name), "{name} isn't in report");
+        }
+        assert!(validate_report(&report).is_empty());
+
+        // Published schema is up to date.
+        let published = std::fs::read_to_string(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/../docs/casrep.schema.json"
+        ))
+        .unwrap();
+        assert_eq!(
+            serde_json::from_str::<Value>(&published).unwrap(),
+            schema,
+            "Update docs/casrep.schema.json with `casr-cli --schema`"
+        );
+
+        // Report with all nested objects filled.
+        let mut report = CrashReport::new();
+        report.execution_class = ExecutionClass::find("DestAv").unwrap();
+        report.severity_score = report.execution_class.score();
+        report.exit_status = Some(-1);
+        report.signal = Some(11);
+        report.crash_probability = Some(0.5);
+        report.stacktrace = vec!["#0  0x0000555555555149 in main () at test.c:5".to_string()];
+        report.frames = vec![StackFrame {
+            address: 0x555555555149,
+            function: "main".to_string(),
+            file: "test.c".to_string(),
+            line: 5,
+            ..Default::default()
+        }];
+        report.inline_frames = report.frames.clone();
+        report.threads = vec![ThreadStacktrace {
+            description: "Thread 1".to_string(),
+            crashed: true,
+            stacktrace: report.stacktrace.clone(),
+        }];
+        report.registers.insert("rip".to_string(), 0x555555555149);
+        report.leaks = vec![LeakRecord {
+            kind: "Direct".to_string(),
+            bytes: 16,
+            objects: 1,
+            stacktrace: report.stacktrace.clone(),
+        }];
+        report.oom = Some(OutOfMemory {
+            malloc_size: Some(1 << 30),
+            used_mb: None,
+            rss_limit_mb: Some(2048),
+            live_heap_bytes: 16,
+            live_heap: vec![HeapAllocation {
+                bytes: 16,
+                count: 1,
+                stacktrace: report.stacktrace.clone(),
+            }],
+        });
+        report.globals = vec![AsanGlobal {
+            name: "g".to_string(),
+            size: 4,
+            location: "test.c:1:5".to_string(),
+        }];
+        report.msan_origin = MsanOrigin {
+            description: "Uninitialized value was created by a heap allocation".to_string(),
+            stacktrace: report.stacktrace.clone(),
+            copies: vec![report.stacktrace.clone()],
+        };
+        report.set_verification(None);
+        report.source_contexts = vec![SourceContext {
+            function: "main".to_string(),
+            file: "test.c".to_string(),
+            line: 5,
+            source: vec!["--->5      abort();".to_string()],
+        }];
+        report.mutex_acquisitions = vec![MutexAcquisition {
+            mutex: "M1".to_string(),
+            held_mutex: "M2".to_string(),
+            thread: "T1".to_string(),
+            stacktrace: report.stacktrace.clone(),
+        }];
+        let report = serde_json::to_value(report).unwrap();
+        let errors = validate_report(&report);
+        assert!(errors.is_empty(), "{errors:?}");
+    }
+
+    #[test]
+    fn test_validate_report() {
+        let mut report = serde_json::to_value(CrashReport::new()).unwrap();
+        report["ReportVersion"] = json!(1);
+        report["Stacktrace"] = json!(["#0  0x0 in main ()", 1]);
+        report["CrashSeverity"]["Type"] = json!("CRITICAL");
+        report["Frames"] = json!([{ "Line": -1 }]);
+        report["Registers"] = json!({ "rip": "0x0" });
+        report["ExitStatus"] = Value::Null;
+        report["Prstatus"] = json!({});
+        report.as_object_mut().unwrap().remove("Date");
+        let errors = validate_report(&report);
+        for error in [
+            format!("/ReportVersion: expected {REPORT_VERSION}, got 1").as_str(),
+            "/Stacktrace/1: expected string, got integer",
+            "/CrashSeverity/Type: \"CRITICAL\" is not one of",
+            "/Frames/0/Line: -1 is less than 0",
+            "/Registers/rip: expected integer, got string",
+            "/Prstatus: unknown field",
+        ] {
+            assert!(
+                errors.iter().any(|e| e.starts_with(error)),
+                "{error} not in {errors:?}"
+            );
+        }
+        assert_eq!(errors.len(), 6, "{errors:?}");
+
+        let errors = validate_report(&json!({ "Stacktrace": [] }));
+        assert_eq!(
+            errors,
+            vec![
+                "/: missing required field ReportVersion",
+                "/: missing required field CrashSeverity",
+            ]
+        );
+        assert_eq!(
+            validate_report(&json!([])),
+            vec!["/: expected object, got array"]
+        );
+    }
+}
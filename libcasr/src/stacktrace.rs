@@ -11,12 +11,19 @@ use crate::constants::{
     STACK_FRAME_FUNCTION_IGNORE_REGEXES_PYTHON, STACK_FRAME_FUNCTION_IGNORE_REGEXES_RUST,
 };
 use crate::error::*;
-use kodama::{linkage, Method};
+use kodama::{linkage, Dendrogram, Method};
 use regex::Regex;
+use std::collections::hash_map::DefaultHasher;
 use std::collections::{HashMap, HashSet};
 use std::fmt::{self, Write};
+use std::hash::{Hash, Hasher};
+use std::path::Path;
+use std::str::FromStr;
 use std::sync::RwLock;
 
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
 // Re-export types from gdb_command for convenient use from Casr library
 /// Represents the information about stack trace.
 pub type Stacktrace = gdb_command::stacktrace::Stacktrace;
@@ -34,6 +41,93 @@ lazy_static::lazy_static! {
         Vec::new());
 }
 
+/// Parsed stack trace frame that is stored in report alongside raw stack trace
+/// lines, so that filtering, crash line extraction, and clustering don't
+/// re-parse them.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct StackFrame {
+    /// Frame address.
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename(serialize = "Address", deserialize = "Address"))
+    )]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub address: u64,
+    /// Path to binary module.
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename(serialize = "Module", deserialize = "Module"))
+    )]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub module: String,
+    /// Offset in binary module.
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename(serialize = "Offset", deserialize = "Offset"))
+    )]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub offset: u64,
+    /// Function name.
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename(serialize = "Function", deserialize = "Function"))
+    )]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub function: String,
+    /// Source file path.
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename(serialize = "File", deserialize = "File"))
+    )]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub file: String,
+    /// Source line number (0 if unknown).
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename(serialize = "Line", deserialize = "Line"))
+    )]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub line: u64,
+    /// Source column number (0 if unknown).
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename(serialize = "Column", deserialize = "Column"))
+    )]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub column: u64,
+}
+
+impl From<&StacktraceEntry> for StackFrame {
+    fn from(entry: &StacktraceEntry) -> Self {
+        StackFrame {
+            address: entry.address,
+            module: entry.module.clone(),
+            offset: entry.offset,
+            function: entry.function.clone(),
+            file: entry.debug.file.clone(),
+            line: entry.debug.line,
+            column: entry.debug.column,
+        }
+    }
+}
+
+impl From<&StackFrame> for StacktraceEntry {
+    fn from(frame: &StackFrame) -> Self {
+        let mut entry = StacktraceEntry {
+            address: frame.address,
+            module: frame.module.clone(),
+            offset: frame.offset,
+            function: frame.function.clone(),
+            ..Default::default()
+        };
+        entry.debug.file = frame.file.clone();
+        entry.debug.line = frame.line;
+        entry.debug.column = frame.column;
+        entry
+    }
+}
+
 /// This macro updates variables used to remove trusted functions from stack trace
 #[macro_export]
 macro_rules! init_ignored_frames {
@@ -168,6 +262,205 @@ pub fn similarity(first: &Stacktrace, second: &Stacktrace) -> f64 {
     simatrix[n - 1][k - 1] / sum
 }
 
+/// Default maximum dissimilarity between stack traces in one cluster
+pub const CLUSTER_THRESHOLD: f64 = 0.3;
+
+/// Maximum dissimilarity between stack traces in one cluster.
+static CLUSTER_DISTANCE: RwLock<f64> = RwLock::new(CLUSTER_THRESHOLD);
+
+/// Linkage strategy for hierarchical clustering.
+static CLUSTER_LINKAGE: RwLock<Linkage> = RwLock::new(Linkage::Complete);
+
+/// Linkage strategy for hierarchical clustering: how dissimilarity between
+/// clusters is computed from dissimilarities between their stack traces
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Linkage {
+    /// Minimum dissimilarity (clusters are chained by close stack traces)
+    Single,
+    /// Maximum dissimilarity (all stack traces in cluster are close)
+    #[default]
+    Complete,
+    /// Average dissimilarity
+    Average,
+}
+
+impl Linkage {
+    /// Get dissimilarity between stack trace and cluster
+    ///
+    /// # Arguments
+    ///
+    /// * `distances` - dissimilarities between stack trace and cluster stack
+    ///   traces
+    ///
+    /// # Return value
+    ///
+    /// Dissimilarity or None if cluster is empty
+    pub fn cluster_distance(&self, distances: &[f64]) -> Option<f64> {
+        if distances.is_empty() {
+            return None;
+        }
+        Some(match self {
+            Linkage::Single => distances.iter().copied().fold(f64::INFINITY, f64::min),
+            Linkage::Complete => distances.iter().copied().fold(0.0, f64::max),
+            Linkage::Average => distances.iter().sum::<f64>() / distances.len() as f64,
+        })
+    }
+
+    /// Get kodama linkage method
+    fn method(&self) -> Method {
+        match self {
+            Linkage::Single => Method::Single,
+            Linkage::Complete => Method::Complete,
+            Linkage::Average => Method::Average,
+        }
+    }
+}
+
+impl fmt::Display for Linkage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Linkage::Single => "single",
+            Linkage::Complete => "complete",
+            Linkage::Average => "average",
+        };
+        write!(f, "{name}")
+    }
+}
+
+impl FromStr for Linkage {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "single" => Ok(Linkage::Single),
+            "complete" => Ok(Linkage::Complete),
+            "average" => Ok(Linkage::Average),
+            _ => Err(Error::Casr(format!("Unknown linkage: {s}"))),
+        }
+    }
+}
+
+/// Set maximum dissimilarity between stack traces in one cluster. It is used
+/// by clustering and `is_similar`.
+///
+/// # Arguments
+///
+/// * `threshold` - stack traces are put in one cluster if their
+///   dissimilarity is less than threshold
+pub fn set_cluster_threshold(threshold: f64) {
+    *CLUSTER_DISTANCE.write().unwrap() = threshold;
+}
+
+/// Get current maximum dissimilarity between stack traces in one cluster
+/// (`CLUSTER_THRESHOLD` by default).
+pub fn cluster_threshold() -> f64 {
+    *CLUSTER_DISTANCE.read().unwrap()
+}
+
+/// Set linkage strategy for hierarchical clustering.
+///
+/// # Arguments
+///
+/// * `linkage` - linkage strategy
+pub fn set_cluster_linkage(linkage: Linkage) {
+    *CLUSTER_LINKAGE.write().unwrap() = linkage;
+}
+
+/// Get current linkage strategy for hierarchical clustering (complete by
+/// default).
+pub fn cluster_linkage() -> Linkage {
+    *CLUSTER_LINKAGE.read().unwrap()
+}
+
+/// Check that 2 stack traces are similar enough to be put in one cluster
+///
+/// # Arguments
+///
+/// * `first` - first stack trace
+///
+/// * `second` - second stack trace
+///
+/// # Return value
+///
+/// true if dissimilarity between stack traces is less than `cluster_threshold`
+pub fn is_similar(first: &Stacktrace, second: &Stacktrace) -> bool {
+    1.0 - similarity(first, second) < cluster_threshold()
+}
+
+/// Normalize stack trace for deduplication and clustering: addresses of frames
+/// with known module offsets are replaced with these offsets and module paths
+/// are replaced with file names, so that stack traces don't depend on ASLR and
+/// binary location (e.g., when reports are collected on different machines)
+///
+/// # Arguments
+///
+/// * `stacktrace` - stack trace to normalize
+pub fn normalize_addresses(stacktrace: &mut Stacktrace) {
+    for entry in stacktrace
+        .iter_mut()
+        .filter(|entry| !entry.module.is_empty() && entry.offset != 0)
+    {
+        entry.address = entry.offset;
+        if let Some(name) = Path::new(&entry.module).file_name() {
+            entry.module = name.to_string_lossy().to_string();
+        }
+    }
+}
+
+/// Number of top stack trace frames that are used for stack hash
+pub const STACK_HASH_FRAMES: usize = 5;
+
+/// Compute fuzzy stack hash of filtered and normalized stack trace (see
+/// `normalize_addresses`): 64-bit FNV-1a hash of top `STACK_HASH_FRAMES`
+/// frames. Frames are identified by function names (source lines are ignored,
+/// so that hash doesn't change after unrelated code changes), frames without
+/// symbols are identified by module and offset. Reports with equal stack
+/// hashes are deduplicated in linear time.
+///
+/// # Arguments
+///
+/// * `stacktrace` - filtered and normalized stack trace
+///
+/// # Return value
+///
+/// Stack hash as 16 hex digits
+pub fn stack_hash(stacktrace: &Stacktrace) -> String {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for entry in stacktrace.iter().take(STACK_HASH_FRAMES) {
+        let frame = if !entry.function.is_empty() && entry.function != "??" {
+            entry.function.clone()
+        } else if !entry.module.is_empty() && entry.offset != 0 {
+            format!("{}+{:#x}", entry.module, entry.offset)
+        } else {
+            format!("{:#x}", entry.address)
+        };
+        // Frames are separated by newline.
+        for byte in frame.bytes().chain(std::iter::once(b'\n')) {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+    }
+    format!("{hash:016x}")
+}
+
+/// Deduplicate stack traces
+///
+/// # Arguments
+///
+/// * `stacktraces` - slice of `Stacktrace` structures
+///
+/// # Return value
+///
+/// Indices of unique stack traces in `stacktraces` (the first stack trace of
+/// duplicates is kept) in ascending order.
+pub fn dedup(stacktraces: &[Stacktrace]) -> Vec<usize> {
+    dedup_stacktraces(stacktraces)
+        .into_iter()
+        .enumerate()
+        .filter_map(|(i, unique)| unique.then_some(i))
+        .collect()
+}
+
 /// Deduplicate stack traces
 ///
 /// # Arguments
@@ -179,10 +472,53 @@ pub fn similarity(first: &Stacktrace, second: &Stacktrace) -> f64 {
 /// A vector of the same length as `stacktraces`.
 /// Vec\[i\] is false, if original stacktrace i is a duplicate of any element of `stacktraces`.
 pub fn dedup_stacktraces(stacktraces: &[Stacktrace]) -> Vec<bool> {
-    let mut traces = HashSet::new();
+    let hashes: Vec<u64> = stacktraces.iter().map(stacktrace_hash).collect();
+    dedup_hashed(stacktraces, &hashes)
+}
+
+/// Get hash of all stack trace frames, so that stack traces may be hashed
+/// independently (e.g., in parallel) before deduplication
+///
+/// # Arguments
+///
+/// * `stacktrace` - stack trace
+///
+/// # Return value
+///
+/// Hash of stack trace
+pub fn stacktrace_hash(stacktrace: &Stacktrace) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    stacktrace.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Deduplicate stack traces by precomputed hashes. Stack traces are compared
+/// only if their hashes are equal.
+///
+/// # Arguments
+///
+/// * `stacktraces` - slice of `Stacktrace` structures
+///
+/// * `hashes` - hashes of `stacktraces` computed by `stacktrace_hash`
+///
+/// # Return value
+///
+/// A vector of the same length as `stacktraces`.
+/// Vec\[i\] is false, if original stacktrace i is a duplicate of any element of `stacktraces`.
+pub fn dedup_hashed(stacktraces: &[Stacktrace], hashes: &[u64]) -> Vec<bool> {
+    let mut buckets: HashMap<u64, Vec<usize>> = HashMap::new();
     stacktraces
         .iter()
-        .map(|trace| traces.insert(trace))
+        .zip(hashes)
+        .enumerate()
+        .map(|(i, (trace, hash))| {
+            let bucket = buckets.entry(*hash).or_default();
+            if bucket.iter().any(|&j| stacktraces[j] == *trace) {
+                return false;
+            }
+            bucket.push(i);
+            true
+        })
         .collect()
 }
 
@@ -197,26 +533,215 @@ pub fn dedup_stacktraces(stacktraces: &[Stacktrace]) -> Vec<bool> {
 /// A vector of the same length as `stacktraces`.
 /// Vec\[i\] is the flat cluster number to which original stack trace i belongs.
 pub fn cluster_stacktraces(stacktraces: &[Stacktrace]) -> Result<Vec<usize>> {
-    // Writing distance matrix
-    // Only the values in the upper triangle are explicitly represented,
-    // not including the diagonal
-    let len = stacktraces.len();
-    let mut condensed_dissimilarity_matrix = vec![];
-    for i in 0..len {
-        for j in i + 1..len {
-            condensed_dissimilarity_matrix.push(1.0 - similarity(&stacktraces[i], &stacktraces[j]));
+    cluster_blocks(
+        stacktraces,
+        CLUSTER_BLOCK_SIZE,
+        |traces, matrix| {
+            for (i, row) in condensed_rows(matrix, traces.len()).into_iter().enumerate() {
+                dissimilarity_row(traces, i, row);
+            }
+        },
+        |cluster, clusters| {
+            clusters
+                .iter()
+                .map(|other| cluster_dissimilarity(stacktraces, cluster, other))
+                .collect()
+        },
+    )
+}
+
+/// Maximum number of stack traces that are clustered by one dissimilarity
+/// matrix (about 256 MiB). Larger sets are clustered block by block.
+pub const CLUSTER_BLOCK_SIZE: usize = 8192;
+
+/// Perform the clustering of stack traces block by block, so that memory is
+/// bounded by dissimilarity matrix of one block. Each block is clustered
+/// hierarchically, then its clusters join the closest clusters of previous
+/// blocks if dissimilarity between clusters (by current linkage strategy) is
+/// less than `cluster_threshold`, or form new clusters otherwise. If there is
+/// only one block, result is the same as for one matrix.
+///
+/// # Arguments
+///
+/// * `stacktraces` - slice of `Stacktrace` structures
+///
+/// * `block_size` - maximum number of stack traces in block
+///
+/// * `fill` - fills condensed dissimilarity matrix of block stack traces
+///
+/// * `distances` - gets dissimilarities between cluster (stack trace indices)
+///   and each of clusters of previous blocks, see `cluster_dissimilarity`
+///
+/// # Return value
+///
+/// A vector of the same length as `stacktraces`.
+/// Vec\[i\] is the flat cluster number to which original stack trace i belongs.
+pub fn cluster_blocks<F, D>(
+    stacktraces: &[Stacktrace],
+    block_size: usize,
+    fill: F,
+    distances: D,
+) -> Result<Vec<usize>>
+where
+    F: Fn(&[Stacktrace], &mut [f64]),
+    D: Fn(&[usize], &[Vec<usize>]) -> Vec<f64>,
+{
+    if block_size == 0 {
+        return Err(Error::Casr("Cluster block size can't be zero".to_string()));
+    }
+    let threshold = cluster_threshold();
+    let mut clusters: Vec<Vec<usize>> = Vec::new();
+    for (block, traces) in stacktraces.chunks(block_size).enumerate() {
+        let offset = block * block_size;
+        let len = traces.len();
+        let numbers = if len < 2 {
+            vec![1; len]
+        } else {
+            let mut matrix = vec![0.0; len * (len - 1) / 2];
+            fill(traces, &mut matrix);
+            cluster_dissimilarity_matrix(&mut matrix, len)?
+        };
+        let count = numbers.iter().copied().max().unwrap_or(0);
+        let mut groups = vec![Vec::new(); count];
+        for (i, number) in numbers.into_iter().enumerate() {
+            groups[number - 1].push(offset + i);
+        }
+
+        // Clusters of one block are dissimilar, so they are compared only with
+        // clusters of previous blocks.
+        let previous = clusters.len();
+        let closest: Vec<Option<usize>> = groups
+            .iter()
+            .map(|group| {
+                distances(group, &clusters[..previous])
+                    .into_iter()
+                    .enumerate()
+                    .filter(|&(_, d)| d < threshold)
+                    .min_by(|a, b| a.1.total_cmp(&b.1))
+                    .map(|(j, _)| j)
+            })
+            .collect();
+        for (group, closest) in groups.into_iter().zip(closest) {
+            if let Some(j) = closest {
+                clusters[j].extend(group);
+            } else {
+                clusters.push(group);
+            }
+        }
+    }
+
+    let mut flat_clusters = vec![0; stacktraces.len()];
+    for (i, cluster) in clusters.into_iter().enumerate() {
+        for num in cluster {
+            flat_clusters[num] = i + 1; // Number clusters from 1, not 0
+        }
+    }
+    Ok(flat_clusters)
+}
+
+/// Get dissimilarity between clusters of stack traces by current linkage
+/// strategy without storing pairwise dissimilarities. For complete linkage
+/// comparison stops as soon as dissimilarity reaches `cluster_threshold`.
+///
+/// # Arguments
+///
+/// * `stacktraces` - slice of `Stacktrace` structures
+///
+/// * `first` - indices of first cluster stack traces
+///
+/// * `second` - indices of second cluster stack traces
+///
+/// # Return value
+///
+/// Dissimilarity between clusters (infinity if any cluster is empty)
+pub fn cluster_dissimilarity(stacktraces: &[Stacktrace], first: &[usize], second: &[usize]) -> f64 {
+    if first.is_empty() || second.is_empty() {
+        return f64::INFINITY;
+    }
+    let linkage = cluster_linkage();
+    let threshold = cluster_threshold();
+    let (mut min, mut max, mut sum) = (f64::INFINITY, 0.0f64, 0.0);
+    for &i in first {
+        for &j in second {
+            let d = 1.0 - similarity(&stacktraces[i], &stacktraces[j]);
+            if linkage == Linkage::Complete && d >= threshold {
+                return d;
+            }
+            min = min.min(d);
+            max = max.max(d);
+            sum += d;
         }
     }
+    match linkage {
+        Linkage::Single => min,
+        Linkage::Complete => max,
+        Linkage::Average => sum / (first.len() * second.len()) as f64,
+    }
+}
 
+/// Split condensed dissimilarity matrix into rows. Only the values in the upper
+/// triangle are explicitly represented, not including the diagonal, so row i
+/// contains distances from stack trace i to stack traces i+1..len.
+/// Rows may be filled independently (e.g., in parallel) without allocating
+/// additional memory.
+///
+/// # Arguments
+///
+/// * `matrix` - condensed matrix of `len * (len - 1) / 2` elements
+///
+/// * `len` - number of stack traces
+///
+/// # Return value
+///
+/// Mutable slices for each matrix row
+pub fn condensed_rows(matrix: &mut [f64], len: usize) -> Vec<&mut [f64]> {
+    let mut rows = Vec::with_capacity(len);
+    let mut rest = matrix;
+    for i in 0..len {
+        let (row, tail) = rest.split_at_mut(len - i - 1);
+        rows.push(row);
+        rest = tail;
+    }
+    rows
+}
+
+/// Fill row of condensed dissimilarity matrix
+///
+/// # Arguments
+///
+/// * `stacktraces` - slice of `Stacktrace` structures
+///
+/// * `i` - row number
+///
+/// * `row` - row slice returned by `condensed_rows`
+pub fn dissimilarity_row(stacktraces: &[Stacktrace], i: usize, row: &mut [f64]) {
+    for (distance, trace) in row.iter_mut().zip(&stacktraces[i + 1..]) {
+        *distance = 1.0 - similarity(&stacktraces[i], trace);
+    }
+}
+
+/// Perform the clustering by condensed dissimilarity matrix
+///
+/// # Arguments
+///
+/// * `matrix` - condensed dissimilarity matrix filled by `dissimilarity_row`
+///
+/// * `len` - number of stack traces
+///
+/// # Return value
+///
+/// A vector of length `len`.
+/// Vec\[i\] is the flat cluster number to which original stack trace i belongs.
+pub fn cluster_dissimilarity_matrix(matrix: &mut [f64], len: usize) -> Result<Vec<usize>> {
     // Get hierarchical clustering binary tree
-    let dendrogram = linkage(&mut condensed_dissimilarity_matrix, len, Method::Complete);
+    let dendrogram = dendrogram(matrix, len)?;
 
     // Iterate through merging step until threshold is reached
     // at the beginning every node is in its own cluster
     let mut clusters = (0..len).map(|x| (x, vec![x])).collect::<HashMap<_, _>>();
 
     // Set threshold
-    let distance = 0.3;
+    let distance = cluster_threshold();
 
     // Counter for new clusters, which are formed as unions of previous ones
     let mut counter = len;
@@ -254,6 +779,208 @@ pub fn cluster_stacktraces(stacktraces: &[Stacktrace]) -> Result<Vec<usize>> {
     Ok(flat_clusters)
 }
 
+/// Get dissimilarities of hierarchical clustering merge steps, so that
+/// clustering threshold may be calibrated: cutting dendrogram at threshold `t`
+/// leaves `len - k` clusters, where `k` is the number of dissimilarities less
+/// than `t`
+///
+/// # Arguments
+///
+/// * `matrix` - condensed dissimilarity matrix filled by `dissimilarity_row`
+///
+/// * `len` - number of stack traces
+///
+/// # Return value
+///
+/// Dissimilarities of merge steps in ascending order
+pub fn merge_dissimilarities(matrix: &mut [f64], len: usize) -> Result<Vec<f64>> {
+    Ok(dendrogram(matrix, len)?
+        .steps()
+        .iter()
+        .map(|step| step.dissimilarity)
+        .collect())
+}
+
+/// Build hierarchical clustering binary tree with current linkage strategy
+///
+/// # Arguments
+///
+/// * `matrix` - condensed dissimilarity matrix
+///
+/// * `len` - number of stack traces
+fn dendrogram(matrix: &mut [f64], len: usize) -> Result<Dendrogram<f64>> {
+    check_matrix_size(matrix, len)?;
+    Ok(linkage(matrix, len, cluster_linkage().method()))
+}
+
+/// Check that condensed dissimilarity matrix size matches number of stack
+/// traces
+///
+/// # Arguments
+///
+/// * `matrix` - condensed dissimilarity matrix
+///
+/// * `len` - number of stack traces
+fn check_matrix_size(matrix: &[f64], len: usize) -> Result<()> {
+    if matrix.len() != len * len.saturating_sub(1) / 2 {
+        return Err(Error::Casr(format!(
+            "Condensed matrix size {} doesn't match {} stack traces",
+            matrix.len(),
+            len
+        )));
+    }
+    Ok(())
+}
+
+/// Perform density-based clustering (DBSCAN) by condensed dissimilarity
+/// matrix. Stack traces are neighbours if their dissimilarity is less than
+/// `cluster_threshold`. Clusters grow from core stack traces that have dense
+/// neighbourhood, while stack traces that aren't reachable from core ones are
+/// noise. Unlike hierarchical clustering, dissimilar stack traces aren't
+/// merged into clusters.
+///
+/// # Arguments
+///
+/// * `matrix` - condensed dissimilarity matrix filled by `dissimilarity_row`
+///
+/// * `len` - number of stack traces
+///
+/// * `min_size` - minimum number of stack traces in dense neighbourhood
+///   (including stack trace itself)
+///
+/// # Return value
+///
+/// A vector of length `len`.
+/// Vec\[i\] is the flat cluster number to which original stack trace i belongs,
+/// 0 for noise.
+pub fn dbscan_dissimilarity_matrix(
+    matrix: &[f64],
+    len: usize,
+    min_size: usize,
+) -> Result<Vec<usize>> {
+    check_matrix_size(matrix, len)?;
+    let threshold = cluster_threshold();
+    let distance = |i: usize, j: usize| {
+        let (i, j) = if i < j { (i, j) } else { (j, i) };
+        matrix[len * i - i * (i + 1) / 2 + j - i - 1]
+    };
+    Ok(dbscan(len, min_size, |i| {
+        (0..len)
+            .filter(|&j| j != i && distance(i, j) < threshold)
+            .collect()
+    }))
+}
+
+/// Perform density-based clustering (DBSCAN) without dissimilarity matrix, so
+/// that memory doesn't grow quadratically with the number of stack traces
+///
+/// # Arguments
+///
+/// * `len` - number of stack traces
+///
+/// * `min_size` - minimum number of stack traces in dense neighbourhood
+///   (including stack trace itself)
+///
+/// * `neighbours` - gets indices of stack traces which dissimilarity to stack
+///   trace i is less than `cluster_threshold` (excluding i itself)
+///
+/// # Return value
+///
+/// A vector of length `len`.
+/// Vec\[i\] is the flat cluster number to which original stack trace i belongs,
+/// 0 for noise.
+pub fn dbscan<N>(len: usize, min_size: usize, neighbours: N) -> Vec<usize>
+where
+    N: Fn(usize) -> Vec<usize>,
+{
+    const UNVISITED: usize = usize::MAX;
+    let mut clusters = vec![UNVISITED; len];
+    let mut counter = 0;
+    for i in 0..len {
+        if clusters[i] != UNVISITED {
+            continue;
+        }
+        let mut queue = neighbours(i);
+        if queue.len() + 1 < min_size {
+            // Noise may become border of cluster later.
+            clusters[i] = 0;
+            continue;
+        }
+        counter += 1;
+        clusters[i] = counter;
+        while let Some(j) = queue.pop() {
+            if clusters[j] == 0 {
+                // Border stack trace isn't expanded.
+                clusters[j] = counter;
+            }
+            if clusters[j] != UNVISITED {
+                continue;
+            }
+            clusters[j] = counter;
+            let next = neighbours(j);
+            if next.len() + 1 >= min_size {
+                queue.extend(next);
+            }
+        }
+    }
+
+    clusters
+}
+
+/// Get crash line bucket of stack trace: source file and line (column is
+/// dropped) or top user frame function (module and offset if function is
+/// unknown) when crash line doesn't point to sources
+///
+/// # Arguments
+///
+/// * `stacktrace` - stack trace
+///
+/// # Return value
+///
+/// Crash line bucket or empty string if stack trace has no frames after filtering
+pub fn crashline_bucket(stacktrace: &Stacktrace) -> String {
+    if let Ok(CrashLine::Source(debug)) = stacktrace.crash_line() {
+        if debug.line != 0 {
+            return format!("{}:{}", debug.file, debug.line);
+        }
+    }
+    let mut trace = stacktrace.clone();
+    trace.filter();
+    let Some(entry) = trace.first() else {
+        return String::new();
+    };
+    if !entry.function.is_empty() {
+        entry.function.clone()
+    } else if !entry.module.is_empty() {
+        format!("{}+{:#x}", entry.module, entry.offset)
+    } else {
+        format!("{:#x}", entry.address)
+    }
+}
+
+/// Perform the clustering of stack traces by crash line: stack traces with the
+/// same crash line bucket (see `crashline_bucket`) form one cluster
+///
+/// # Arguments
+///
+/// * `stacktraces` - slice of `Stacktrace` structures
+///
+/// # Return value
+///
+/// A vector of the same length as `stacktraces`.
+/// Vec\[i\] is the flat cluster number to which original stack trace i belongs.
+/// Clusters are numbered in order of first occurrence.
+pub fn cluster_crashlines(stacktraces: &[Stacktrace]) -> Vec<usize> {
+    let mut buckets: HashMap<String, usize> = HashMap::new();
+    stacktraces
+        .iter()
+        .map(|trace| {
+            let cluster = buckets.len() + 1;
+            *buckets.entry(crashline_bucket(trace)).or_insert(cluster)
+        })
+        .collect()
+}
+
 /// Perform crashline deduplication for each cluster:
 /// Reset Vec\[i\] to 0 if report crashline is duplicate of some other.
 ///
@@ -402,6 +1129,35 @@ impl Filter for Stacktrace {
     }
 }
 
+/// Get frames that are removed from the stack trace by `Filter::filter`, e.g.,
+/// to check ignore rules
+///
+/// # Arguments
+///
+/// * `stacktrace` - stack trace before filtering
+///
+/// # Return value
+///
+/// Indices of removed frames in ascending order
+pub fn filtered_out_frames(stacktrace: &Stacktrace) -> Vec<usize> {
+    let mut filtered = stacktrace.clone();
+    filtered.filter();
+    // Filtered stack trace is a subsequence of the original one.
+    let mut kept = filtered.iter().peekable();
+    stacktrace
+        .iter()
+        .enumerate()
+        .filter_map(|(i, entry)| {
+            if kept.peek() == Some(&entry) {
+                kept.next();
+                None
+            } else {
+                Some(i)
+            }
+        })
+        .collect()
+}
+
 /// Find repeating intervals in sequence
 ///
 /// # Arguments
@@ -457,6 +1213,266 @@ pub mod tests {
         }
     }
 
+    #[test]
+    fn test_condensed_rows() {
+        let mut matrix: Vec<f64> = (0..6).map(|x| x as f64).collect();
+        let rows = condensed_rows(&mut matrix, 4);
+        assert_eq!(rows.len(), 4);
+        assert_eq!(rows[0], &[0.0, 1.0, 2.0]);
+        assert_eq!(rows[1], &[3.0, 4.0]);
+        assert_eq!(rows[2], &[5.0]);
+        assert!(rows[3].is_empty());
+
+        let entry = |function: &str| StacktraceEntry {
+            function: function.to_string(),
+            ..Default::default()
+        };
+        let traces: Vec<Stacktrace> = vec![
+            vec![entry("a"), entry("b")],
+            vec![entry("c"), entry("d")],
+            vec![entry("a"), entry("b")],
+        ];
+        let Ok(clusters) = cluster_stacktraces(&traces) else {
+            panic!("Couldn't cluster stack traces");
+        };
+        assert_eq!(clusters[0], clusters[2]);
+        assert_ne!(clusters[0], clusters[1]);
+
+        let mut matrix = vec![0.0; 2];
+        assert!(cluster_dissimilarity_matrix(&mut matrix, 3).is_err());
+    }
+
+    #[test]
+    fn test_cluster_blocks() {
+        let entry = |function: &str| StacktraceEntry {
+            function: function.to_string(),
+            ..Default::default()
+        };
+        let traces: Vec<Stacktrace> = vec![
+            vec![entry("a"), entry("b")],
+            vec![entry("c"), entry("d")],
+            vec![entry("a"), entry("b")],
+            vec![entry("c"), entry("d")],
+            vec![entry("a"), entry("b")],
+        ];
+        assert!(cluster_dissimilarity(&traces, &[0, 2], &[4]) < 1e-9);
+        assert_eq!(cluster_dissimilarity(&traces, &[0], &[1, 3]), 1.0);
+        assert_eq!(cluster_dissimilarity(&traces, &[0], &[]), f64::INFINITY);
+
+        // Clusters of blocks {0, 1}, {2, 3}, and {4} are merged.
+        let fill = |traces: &[Stacktrace], matrix: &mut [f64]| {
+            for (i, row) in condensed_rows(matrix, traces.len()).into_iter().enumerate() {
+                dissimilarity_row(traces, i, row);
+            }
+        };
+        let distances = |cluster: &[usize], clusters: &[Vec<usize>]| -> Vec<f64> {
+            clusters
+                .iter()
+                .map(|other| cluster_dissimilarity(&traces, cluster, other))
+                .collect()
+        };
+        let Ok(clusters) = cluster_blocks(&traces, 2, fill, distances) else {
+            panic!("Couldn't cluster stack traces");
+        };
+        assert_eq!(clusters, vec![1, 2, 1, 2, 1]);
+        assert!(cluster_blocks(&traces, 0, fill, distances).is_err());
+
+        assert_eq!(
+            dedup_stacktraces(&traces),
+            vec![true, true, false, false, false]
+        );
+    }
+
+    #[test]
+    fn test_cluster_linkage() {
+        assert_eq!("single".parse::<Linkage>().unwrap(), Linkage::Single);
+        assert_eq!("average".parse::<Linkage>().unwrap(), Linkage::Average);
+        assert!("ward".parse::<Linkage>().is_err());
+        assert_eq!(Linkage::default().to_string(), "complete");
+
+        let distances = [0.1, 0.5, 0.3];
+        assert_eq!(Linkage::Single.cluster_distance(&distances), Some(0.1));
+        assert_eq!(Linkage::Complete.cluster_distance(&distances), Some(0.5));
+        let average = Linkage::Average.cluster_distance(&distances).unwrap();
+        assert!((average - 0.3).abs() < 1e-9);
+        assert!(Linkage::Single.cluster_distance(&[]).is_none());
+
+        // Distances: 0-1 0.1, 0-2 0.9, 1-2 0.2
+        let mut matrix = vec![0.1, 0.9, 0.2];
+        let Ok(steps) = merge_dissimilarities(&mut matrix, 3) else {
+            panic!("Couldn't build dendrogram");
+        };
+        assert_eq!(steps, vec![0.1, 0.9]);
+        let mut matrix = vec![0.0; 2];
+        assert!(merge_dissimilarities(&mut matrix, 3).is_err());
+    }
+
+    #[test]
+    fn test_dbscan() {
+        // Stack traces 0, 1, and 2 form a chain, 3 is far from others.
+        // Distances: 0-1, 0-2, 0-3, 1-2, 1-3, 2-3
+        let matrix = vec![0.1, 0.4, 0.9, 0.2, 0.9, 0.9];
+        let Ok(clusters) = dbscan_dissimilarity_matrix(&matrix, 4, 2) else {
+            panic!("Couldn't cluster stack traces");
+        };
+        assert_eq!(clusters, vec![1, 1, 1, 0]);
+        let Ok(clusters) = dbscan_dissimilarity_matrix(&matrix, 4, 3) else {
+            panic!("Couldn't cluster stack traces");
+        };
+        // Only 1 has 2 neighbours, 0 and 2 are its borders.
+        assert_eq!(clusters, vec![1, 1, 1, 0]);
+        let Ok(clusters) = dbscan_dissimilarity_matrix(&matrix, 4, 4) else {
+            panic!("Couldn't cluster stack traces");
+        };
+        assert_eq!(clusters, vec![0, 0, 0, 0]);
+        let Ok(clusters) = dbscan_dissimilarity_matrix(&matrix, 4, 1) else {
+            panic!("Couldn't cluster stack traces");
+        };
+        assert_eq!(clusters, vec![1, 1, 1, 2]);
+
+        assert!(dbscan_dissimilarity_matrix(&[0.0; 2], 3, 2).is_err());
+        assert!(dbscan_dissimilarity_matrix(&[], 0, 2).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_similarity_api() {
+        let entry = |function: &str| StacktraceEntry {
+            function: function.to_string(),
+            ..Default::default()
+        };
+        let traces: Vec<Stacktrace> = vec![
+            vec![entry("a"), entry("b"), entry("c")],
+            vec![entry("d"), entry("e")],
+            vec![entry("a"), entry("b"), entry("c")],
+            vec![entry("a"), entry("b"), entry("f")],
+        ];
+        assert!((similarity(&traces[0], &traces[2]) - 1.0).abs() < f64::EPSILON);
+        assert_eq!(similarity(&traces[0], &traces[1]), 0.0);
+        assert!(is_similar(&traces[0], &traces[2]));
+        assert!(!is_similar(&traces[0], &traces[1]));
+        assert!(similarity(&traces[0], &traces[3]) < 1.0);
+        assert_eq!(dedup(&traces), vec![0, 1, 3]);
+        assert!(dedup(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_cluster_crashlines() {
+        safe_init_ignore_stack_frames();
+        let entry = |function: &str, file: &str, line: u64, column: u64| StacktraceEntry {
+            function: function.to_string(),
+            debug: DebugInfo {
+                file: file.to_string(),
+                line,
+                column,
+            },
+            ..Default::default()
+        };
+        let traces: Vec<Stacktrace> = vec![
+            vec![
+                entry("crash", "/src/a.c", 10, 5),
+                entry("main", "/src/main.c", 3, 1),
+            ],
+            vec![
+                entry("crash", "/src/a.c", 10, 12),
+                entry("foo", "/src/b.c", 7, 1),
+            ],
+            vec![entry("crash", "/src/a.c", 11, 5)],
+            vec![
+                entry("__interceptor_memcpy", "", 0, 0),
+                entry("parse", "", 0, 0),
+            ],
+            vec![StacktraceEntry {
+                module: "/bin/target".to_string(),
+                offset: 0x1234,
+                ..Default::default()
+            }],
+        ];
+        assert_eq!(crashline_bucket(&traces[0]), "/src/a.c:10");
+        assert_eq!(crashline_bucket(&traces[3]), "parse");
+        assert_eq!(crashline_bucket(&traces[4]), "/bin/target+0x1234");
+        assert_eq!(cluster_crashlines(&traces), vec![1, 1, 2, 3, 4]);
+        assert!(cluster_crashlines(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_normalize_addresses() {
+        let entry = |address: u64, module: &str, offset: u64| StacktraceEntry {
+            address,
+            module: module.to_string(),
+            offset,
+            ..Default::default()
+        };
+        let mut first: Stacktrace = vec![
+            entry(0x555555555149, "/home/user/bin/target", 0x1149),
+            entry(0x7ffff7829d90, "/lib/x86_64-linux-gnu/libc.so.6", 0x29d90),
+            entry(0x401000, "", 0),
+        ];
+        let mut second: Stacktrace = vec![
+            entry(0x5615e2a3b149, "/tmp/target", 0x1149),
+            entry(
+                0x7f3c1a229d90,
+                "/usr/lib/x86_64-linux-gnu/libc.so.6",
+                0x29d90,
+            ),
+            entry(0x401000, "", 0),
+        ];
+        normalize_addresses(&mut first);
+        normalize_addresses(&mut second);
+        assert_eq!(first[0].address, 0x1149);
+        assert_eq!(first[0].module, "target");
+        assert_eq!(first[1].module, "libc.so.6");
+        assert_eq!(first[2].address, 0x401000);
+        assert_eq!(dedup(&[first, second]), vec![0]);
+    }
+
+    #[test]
+    fn test_stack_hash() {
+        let entry = |function: &str, line: u64| StacktraceEntry {
+            function: function.to_string(),
+            debug: DebugInfo {
+                file: "/src/a.c".to_string(),
+                line,
+                column: 0,
+            },
+            ..Default::default()
+        };
+        let first: Stacktrace = (0..7).map(|i| entry(&format!("f{i}"), i)).collect();
+        let mut second = first.clone();
+        // Source lines and frames below top ones don't change hash.
+        second[0].debug.line = 100;
+        second[6].function = "g".to_string();
+        assert_eq!(stack_hash(&first), stack_hash(&second));
+        assert_eq!(stack_hash(&first).len(), 16);
+        second[1].function = "g".to_string();
+        assert_ne!(stack_hash(&first), stack_hash(&second));
+
+        let unsymbolized: Stacktrace = vec![StacktraceEntry {
+            address: 0x1149,
+            module: "target".to_string(),
+            offset: 0x1149,
+            ..Default::default()
+        }];
+        assert_ne!(stack_hash(&unsymbolized), stack_hash(&Stacktrace::new()));
+        assert_eq!(stack_hash(&Stacktrace::new()), "cbf29ce484222325");
+    }
+
+    #[test]
+    fn test_filtered_out_frames() {
+        safe_init_ignore_stack_frames();
+        let entry = |function: &str| StacktraceEntry {
+            function: function.to_string(),
+            ..Default::default()
+        };
+        let trace: Stacktrace = vec![
+            entry("__interceptor_memcpy"),
+            entry("crash"),
+            entry("LLVMFuzzerTestOneInput"),
+            entry("fuzzer::Fuzzer::ExecuteCallback"),
+            entry("main"),
+        ];
+        assert_eq!(filtered_out_frames(&trace), vec![0, 3, 4]);
+    }
+
     #[test]
     fn test_main_lorentz() {
         let tests = [
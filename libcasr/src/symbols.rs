@@ -0,0 +1,185 @@
+//! Symbols module resolves function names for stack trace entries without debug
+//! information (e.g., frames from stripped PIE binaries) via ELF symbol tables.
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use goblin::elf::program_header::PT_LOAD;
+use goblin::elf::Elf;
+use regex::Regex;
+
+use crate::error::*;
+use crate::stacktrace::*;
+
+/// Function symbols and loadable segments of ELF module.
+#[derive(Clone, Debug, Default)]
+pub struct ElfSymbols {
+    /// Function symbols (virtual address, size, name) sorted by address.
+    functions: Vec<(u64, u64, String)>,
+    /// Loadable segments (file offset, virtual address, file size).
+    segments: Vec<(u64, u64, u64)>,
+}
+
+impl ElfSymbols {
+    /// Read function symbols from ELF file.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - path to ELF module
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let buffer = fs::read(path)?;
+        Self::from_bytes(&buffer)
+    }
+
+    /// Read function symbols from ELF module contents.
+    ///
+    /// # Arguments
+    ///
+    /// * `buffer` - ELF module contents
+    pub fn from_bytes(buffer: &[u8]) -> Result<Self> {
+        let elf = Elf::parse(buffer)?;
+        let mut symbols = ElfSymbols {
+            segments: elf
+                .program_headers
+                .iter()
+                .filter(|header| header.p_type == PT_LOAD)
+                .map(|header| (header.p_offset, header.p_vaddr, header.p_filesz))
+                .collect(),
+            ..Default::default()
+        };
+        for (syms, strtab) in [(&elf.syms, &elf.strtab), (&elf.dynsyms, &elf.dynstrtab)] {
+            symbols.functions.extend(
+                syms.iter()
+                    .filter(|sym| sym.is_function() && sym.st_value != 0)
+                    .filter_map(|sym| {
+                        let name = strtab.get_at(sym.st_name)?;
+                        (!name.is_empty()).then(|| (sym.st_value, sym.st_size, name.to_string()))
+                    }),
+            );
+        }
+        symbols.functions.sort();
+        symbols.functions.dedup_by_key(|function| function.0);
+        if symbols.functions.is_empty() {
+            return Err(Error::Casr("No function symbols in ELF module".to_string()));
+        }
+        Ok(symbols)
+    }
+
+    /// Find function that contains file offset.
+    ///
+    /// # Arguments
+    ///
+    /// * `offset` - offset in ELF module file
+    ///
+    /// # Return value
+    ///
+    /// Function name and offset in function
+    pub fn lookup(&self, offset: u64) -> Option<(&str, u64)> {
+        let (file_offset, vaddr, _) = self
+            .segments
+            .iter()
+            .find(|(start, _, size)| (*start..start + size).contains(&offset))?;
+        let address = offset - file_offset + vaddr;
+        let idx = self
+            .functions
+            .partition_point(|(start, _, _)| *start <= address)
+            .checked_sub(1)?;
+        let (start, size, name) = &self.functions[idx];
+        // Symbols without size cover only their start address.
+        if address - start < (*size).max(1) {
+            Some((name, address - start))
+        } else {
+            None
+        }
+    }
+}
+
+/// Resolve function names for stack trace entries that have module offset but
+/// neither function name nor debug information. Module offsets should be computed
+/// before (e.g., via `compute_module_offsets`).
+///
+/// # Arguments
+///
+/// * `stacktrace` - stack trace to symbolize
+///
+/// # Return value
+///
+/// Resolved entries indices and their `function+offset` names
+pub fn symbolize_stacktrace(stacktrace: &mut Stacktrace) -> Vec<(usize, String)> {
+    let mut modules: HashMap<String, Option<ElfSymbols>> = HashMap::new();
+    let mut resolved = Vec::new();
+    for (idx, entry) in stacktrace.iter_mut().enumerate() {
+        if !(entry.function.is_empty() || entry.function == "??")
+            || !entry.debug.file.is_empty()
+            || entry.module.is_empty()
+            || entry.offset == 0
+        {
+            continue;
+        }
+        let symbols = modules
+            .entry(entry.module.clone())
+            .or_insert_with(|| ElfSymbols::from_file(Path::new(&entry.module)).ok());
+        let Some((function, offset)) = symbols.as_ref().and_then(|s| s.lookup(entry.offset)) else {
+            continue;
+        };
+        entry.function = function.to_string();
+        resolved.push((idx, format!("{function}+{offset:#x}")));
+    }
+    resolved
+}
+
+/// Insert resolved function name into raw gdb or sanitizer stack trace line, e.g.,
+/// "#0 0x555555555149 in ?? ()" -> "#0 0x555555555149 in main+0x10 ()".
+///
+/// # Arguments
+///
+/// * `line` - stack trace line
+///
+/// * `function` - resolved `function+offset` name
+pub fn insert_function(line: &str, function: &str) -> String {
+    let re = Regex::new(r"^( *#[0-9]+ +0x[0-9a-f]+)(?: in \?\?)? +\(").unwrap();
+    re.replace(line, format!("$1 in {function} (").as_str())
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_elf_symbols_lookup() {
+        let symbols = ElfSymbols {
+            functions: vec![
+                (0x1040, 0x26, "_start".to_string()),
+                (0x1139, 0x20, "crash".to_string()),
+                (0x1159, 0x0, "main".to_string()),
+            ],
+            segments: vec![(0, 0, 0x618), (0x1000, 0x1000, 0x1c5)],
+        };
+        assert_eq!(symbols.lookup(0x1149), Some(("crash", 0x10)));
+        assert_eq!(symbols.lookup(0x1040), Some(("_start", 0)));
+        assert_eq!(symbols.lookup(0x1159), Some(("main", 0)));
+        assert_eq!(symbols.lookup(0x115a), None);
+        assert_eq!(symbols.lookup(0x1100), None);
+        assert_eq!(symbols.lookup(0x2000), None);
+    }
+
+    #[test]
+    fn test_insert_function() {
+        assert_eq!(
+            insert_function("#0  0x0000555555555149 in ?? ()", "crash+0x10"),
+            "#0  0x0000555555555149 in crash+0x10 ()"
+        );
+        assert_eq!(
+            insert_function("    #1 0x55555555516d  (/tmp/test+0x116d)", "main+0x14"),
+            "    #1 0x55555555516d in main+0x14 (/tmp/test+0x116d)"
+        );
+        assert_eq!(
+            insert_function(
+                "#2  0x00007ffff7829d90 in __libc_start_call_main () from /lib/libc.so.6",
+                "f+0x1"
+            ),
+            "#2  0x00007ffff7829d90 in __libc_start_call_main () from /lib/libc.so.6"
+        );
+    }
+}
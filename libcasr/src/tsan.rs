@@ -0,0 +1,529 @@
+//! Tsan module implements `ParseStacktrace` and `Severity` traits for ThreadSanitizer
+//! reports and normalization of data race stack traces.
+use regex::Regex;
+
+use crate::error::*;
+use crate::execution_class::ExecutionClass;
+use crate::severity::Severity;
+use crate::stacktrace::*;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Structure provides an interface for processing the stack trace.
+pub struct TsanStacktrace;
+
+impl ParseStacktrace for TsanStacktrace {
+    fn extract_stacktrace(stream: &str) -> Result<Vec<String>> {
+        let lines: Vec<&str> = stream.split('\n').collect();
+        let frame = Regex::new(r"^ *#[0-9]+ ").unwrap();
+
+        let Some(first) = lines.iter().position(|x| frame.is_match(x)) else {
+            return Err(Error::Casr(
+                "Couldn't find stack trace in ThreadSanitizer report".to_string(),
+            ));
+        };
+
+        // Stack trace is split by empty line.
+        Ok(lines[first..]
+            .iter()
+            .take_while(|l| !l.trim().is_empty())
+            .map(|l| l.trim_end().to_string())
+            .collect())
+    }
+
+    fn parse_stacktrace_entry(entry: &str) -> Result<StacktraceEntry> {
+        let mut stentry = StacktraceEntry::default();
+
+        // #0 function file:line:column (module+0xdeadbeef) (BuildId: ...)
+        let re = Regex::new(r"^ *#[0-9]+ +").unwrap();
+        let Some(caps) = re.captures(entry) else {
+            return Err(Error::Casr(format!(
+                "Couldn't parse frame in stack trace entry: {entry}"
+            )));
+        };
+        let mut location = entry[caps.get(0).unwrap().end()..].trim();
+
+        // Cut BuildId.
+        if let Some(pos) = location.rfind(" (BuildId: ") {
+            location = location[..pos].trim();
+        }
+
+        // (module+0xdeadbeef)
+        let re = Regex::new(r"\(([^()]+)\+0x([0-9a-f]+)\)$").unwrap();
+        if let Some(caps) = re.captures(location) {
+            stentry.module = caps.get(1).unwrap().as_str().trim().to_string();
+            let num = caps.get(2).unwrap().as_str();
+            let Ok(offset) = u64::from_str_radix(num, 16) else {
+                return Err(Error::Casr(format!("Couldn't parse module offset: {num}")));
+            };
+            stentry.offset = offset;
+            location = location[..caps.get(0).unwrap().start()].trim();
+        }
+
+        // function file[:line[:column]]
+        // Both function (e.g., C++ templates) and path may contain spaces, so
+        // absolute path is searched from the start. Relative paths with spaces
+        // aren't supported.
+        let (function, source) = if let Some(pos) = location.find(" /") {
+            (&location[..pos], &location[pos + 1..])
+        } else {
+            location.rsplit_once(' ').unwrap_or((location, ""))
+        };
+        if function != "<null>" {
+            stentry.function = function.trim().to_string();
+        }
+        if !source.is_empty() && source != "<null>" {
+            let source: Vec<&str> = source.rsplitn(3, ':').collect();
+            if source.iter().any(|x| x.is_empty()) {
+                return Err(Error::Casr(format!(
+                    "Couldn't parse source file path, line, or column: {entry}"
+                )));
+            }
+            stentry.debug.file = source.last().unwrap().to_string();
+            if source.len() > 1 {
+                let num = source[source.len() - 2];
+                let Ok(line) = num.parse::<u64>() else {
+                    return Err(Error::Casr(format!("Couldn't parse source line: {num}")));
+                };
+                stentry.debug.line = line;
+            }
+            if source.len() == 3 {
+                let num = source[0];
+                let Ok(column) = num.parse::<u64>() else {
+                    return Err(Error::Casr(format!("Couldn't parse source column: {num}")));
+                };
+                stentry.debug.column = column;
+            }
+        }
+
+        Ok(stentry)
+    }
+}
+
+/// Extract stack traces of two racing memory accesses from ThreadSanitizer data race report
+///
+/// # Arguments
+///
+/// * `tsan_report` - ThreadSanitizer report lines
+///
+/// # Return value
+///
+/// Stack traces of current and previous memory accesses
+pub fn racing_stacktraces(tsan_report: &[String]) -> Result<(Vec<String>, Vec<String>)> {
+    let raccess =
+        Regex::new(r"(?i)^\s*(previous\s+)?(atomic\s+)?(read|write) of size \d+ at ").unwrap();
+    let stacks: Vec<Vec<String>> = tsan_report
+        .iter()
+        .enumerate()
+        .filter(|(_, line)| raccess.is_match(line))
+        .filter_map(|(i, _)| {
+            TsanStacktrace::extract_stacktrace(&tsan_report[i + 1..].join("\n")).ok()
+        })
+        .take(2)
+        .collect();
+    if stacks.len() < 2 {
+        return Err(Error::Casr(
+            "Couldn't find racing memory accesses in ThreadSanitizer report".to_string(),
+        ));
+    }
+    Ok((stacks[0].clone(), stacks[1].clone()))
+}
+
+/// Get normalized stack trace for ThreadSanitizer data race. Both racing
+/// stack traces are filtered and concatenated in a fixed order, so the same
+/// race reported from either side produces the same stack trace.
+///
+/// # Arguments
+///
+/// * `tsan_report` - ThreadSanitizer report lines
+pub fn race_stacktrace(tsan_report: &[String]) -> Result<Stacktrace> {
+    let (first, second) = racing_stacktraces(tsan_report)?;
+    let mut first = TsanStacktrace::parse_stacktrace(&first)?;
+    first.filter();
+    let mut second = TsanStacktrace::parse_stacktrace(&second)?;
+    second.filter();
+
+    let key = |trace: &Stacktrace| {
+        trace
+            .iter()
+            .map(|e| {
+                (
+                    e.function.clone(),
+                    e.debug.file.clone(),
+                    e.debug.line,
+                    e.module.clone(),
+                    e.offset,
+                )
+            })
+            .collect::<Vec<_>>()
+    };
+    if key(&second) < key(&first) {
+        std::mem::swap(&mut first, &mut second);
+    }
+    first.extend(second);
+    Ok(first)
+}
+
+/// Mutex acquisition from ThreadSanitizer lock-order-inversion report.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MutexAcquisition {
+    /// Acquired mutex, e.g. M0.
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename(serialize = "Mutex", deserialize = "Mutex"))
+    )]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub mutex: String,
+    /// Mutex that is held while acquiring, empty if mutex is acquired first.
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename(serialize = "HeldMutex", deserialize = "HeldMutex"))
+    )]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub held_mutex: String,
+    /// Thread that acquires mutex, e.g. "thread T1" or "main thread".
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename(serialize = "Thread", deserialize = "Thread"))
+    )]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub thread: String,
+    /// Acquisition stack trace.
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename(serialize = "Stacktrace", deserialize = "Stacktrace"))
+    )]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub stacktrace: Vec<String>,
+}
+
+/// Extract mutex acquisitions from ThreadSanitizer lock-order-inversion report
+///
+/// # Arguments
+///
+/// * `tsan_report` - ThreadSanitizer report lines
+///
+/// # Return value
+///
+/// Mutex acquisitions in the order of appearance in report
+pub fn extract_mutex_acquisitions(tsan_report: &[String]) -> Vec<MutexAcquisition> {
+    let rholding =
+        Regex::new(r"Mutex (\S+) acquired here while holding mutex (\S+) in (.+):$").unwrap();
+    let rprevious =
+        Regex::new(r"Mutex (\S+) previously acquired by the same thread here:").unwrap();
+    let mut acquisitions: Vec<MutexAcquisition> = Vec::new();
+    for (i, line) in tsan_report.iter().enumerate() {
+        let mut acquisition = if let Some(caps) = rholding.captures(line.trim_end()) {
+            MutexAcquisition {
+                mutex: caps.get(1).unwrap().as_str().to_string(),
+                held_mutex: caps.get(2).unwrap().as_str().to_string(),
+                thread: caps.get(3).unwrap().as_str().to_string(),
+                stacktrace: Vec::new(),
+            }
+        } else if let Some(caps) = rprevious.captures(line) {
+            MutexAcquisition {
+                mutex: caps.get(1).unwrap().as_str().to_string(),
+                held_mutex: String::new(),
+                thread: acquisitions
+                    .last()
+                    .map(|a| a.thread.clone())
+                    .unwrap_or_default(),
+                stacktrace: Vec::new(),
+            }
+        } else {
+            continue;
+        };
+        if let Ok(stacktrace) = TsanStacktrace::extract_stacktrace(&tsan_report[i + 1..].join("\n"))
+        {
+            acquisition.stacktrace = stacktrace;
+        }
+        acquisitions.push(acquisition);
+    }
+    acquisitions
+}
+
+/// Extract the first ThreadSanitizer report from program output. ThreadSanitizer
+/// continues execution after report by default, so output may contain several
+/// reports.
+///
+/// # Arguments
+///
+/// * `stream` - program output lines
+///
+/// # Return value
+///
+/// Report lines from WARNING to SUMMARY line
+pub fn extract_tsan_report(stream: &[String]) -> Option<Vec<String>> {
+    let start = stream
+        .iter()
+        .position(|line| line.contains("WARNING: ThreadSanitizer: "))?;
+    let end = stream[start..]
+        .iter()
+        .position(|line| line.contains("SUMMARY: ThreadSanitizer: "))
+        .map(|pos| start + pos + 1)
+        .unwrap_or(stream.len());
+    Some(
+        stream[start..end]
+            .iter()
+            .map(|line| line.trim_end().to_string())
+            .collect(),
+    )
+}
+
+/// Information about ThreadSanitizer report.
+pub struct TsanContext(pub Vec<String>);
+
+impl Severity for TsanContext {
+    fn severity(&self) -> Result<ExecutionClass> {
+        let tsan_report = &self.0;
+        let rwarning =
+            Regex::new(r"(?:WARNING|SUMMARY): *ThreadSanitizer: (.+?)(?: \(pid=[0-9]+\))?$")
+                .unwrap();
+        let Some(caps) = tsan_report
+            .iter()
+            .find_map(|s| rwarning.captures(s.trim_end()))
+        else {
+            return Err(Error::Casr(
+                "Cannot find WARNING or SUMMARY in ThreadSanitizer report".to_string(),
+            ));
+        };
+        let description = caps.get(1).unwrap().as_str();
+        // Report type descriptions from tsan_report.cpp
+        let Some((_, class)) = [
+            ("data race on vptr", "data-race-vptr"),
+            ("data race", "data-race"),
+            ("heap-use-after-free", "heap-use-after-free"),
+            ("thread leak", "thread-leak"),
+            ("destroy of a locked mutex", "mutex-destroy-locked"),
+            ("double lock of a mutex", "mutex-double-lock"),
+            ("use of an invalid mutex", "mutex-invalid-access"),
+            ("unlock of an unlocked mutex", "mutex-bad-unlock"),
+            ("read lock of a write locked mutex", "mutex-bad-read-lock"),
+            (
+                "read unlock of a write locked mutex",
+                "mutex-bad-read-unlock",
+            ),
+            (
+                "mutex held in the wrong context",
+                "mutex-held-wrong-context",
+            ),
+            (
+                "signal-unsafe call inside of a signal",
+                "signal-unsafe-call",
+            ),
+            ("signal handler spoils errno", "errno-in-signal-handler"),
+            ("lock-order-inversion", "lock-order-inversion"),
+        ]
+        .iter()
+        .find(|(prefix, _)| description.starts_with(prefix)) else {
+            return Err(Error::Casr(format!(
+                "Unsupported ThreadSanitizer report type: {description}"
+            )));
+        };
+        ExecutionClass::find(class)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stacktrace::tests::safe_init_ignore_stack_frames;
+
+    fn race_report(swap: bool) -> Vec<String> {
+        let mut first = vec![
+            "  Write of size 4 at 0x7fe3c3075190 by thread T1:",
+            "    #0 Thread1(void*) /tmp/race.cpp:7:10 (race+0xd1c8e) (BuildId: 2a52e1c5)",
+            "    #1 <null> <null> (libc.so.6+0x94ac2)",
+            "",
+        ];
+        let mut second = vec![
+            "  Previous read of size 4 at 0x7fe3c3075190 by main thread:",
+            "    #0 main /tmp/race.cpp:14:3 (race+0xd1d0e)",
+            "",
+        ];
+        if swap {
+            first[0] = "  Previous write of size 4 at 0x7fe3c3075190 by thread T1:";
+            second[0] = "  Read of size 4 at 0x7fe3c3075190 by main thread:";
+            std::mem::swap(&mut first, &mut second);
+        }
+        let mut report = vec![
+            "==================",
+            "WARNING: ThreadSanitizer: data race (pid=9337)",
+        ];
+        report.extend(first);
+        report.extend(second);
+        report.extend([
+            "SUMMARY: ThreadSanitizer: data race /tmp/race.cpp:7:10 in Thread1(void*)",
+            "==================",
+        ]);
+        report.iter().map(|l| l.to_string()).collect()
+    }
+
+    #[test]
+    fn test_tsan_stacktrace() {
+        let report = race_report(false);
+        let raw_stacktrace = TsanStacktrace::extract_stacktrace(&report.join("\n"));
+        let Ok(raw_stacktrace) = raw_stacktrace else {
+            panic!("{}", raw_stacktrace.err().unwrap());
+        };
+        assert_eq!(raw_stacktrace.len(), 2);
+
+        let stacktrace = TsanStacktrace::parse_stacktrace(&raw_stacktrace);
+        let Ok(stacktrace) = stacktrace else {
+            panic!("{}", stacktrace.err().unwrap());
+        };
+        assert_eq!(stacktrace[0].function, "Thread1(void*)");
+        assert_eq!(stacktrace[0].debug.file, "/tmp/race.cpp");
+        assert_eq!(stacktrace[0].debug.line, 7);
+        assert_eq!(stacktrace[0].debug.column, 10);
+        assert_eq!(stacktrace[0].module, "race");
+        assert_eq!(stacktrace[0].offset, 0xd1c8e);
+        assert!(stacktrace[1].function.is_empty());
+        assert!(stacktrace[1].debug.file.is_empty());
+        assert_eq!(stacktrace[1].module, "libc.so.6");
+        assert_eq!(stacktrace[1].offset, 0x94ac2);
+
+        let stacktrace = TsanStacktrace::parse_stacktrace(&[
+            "    #0 foo<int, char>(int) /tmp/my dir/race.cpp:7:10 (my race+0xd1c8e)".to_string(),
+        ]);
+        let Ok(stacktrace) = stacktrace else {
+            panic!("{}", stacktrace.err().unwrap());
+        };
+        assert_eq!(stacktrace[0].function, "foo<int, char>(int)");
+        assert_eq!(stacktrace[0].debug.file, "/tmp/my dir/race.cpp");
+        assert_eq!(stacktrace[0].debug.line, 7);
+        assert_eq!(stacktrace[0].debug.column, 10);
+        assert_eq!(stacktrace[0].module, "my race");
+    }
+
+    #[test]
+    fn test_tsan_race_stacktrace() {
+        safe_init_ignore_stack_frames();
+        let report = race_report(false);
+        let Ok((first, second)) = racing_stacktraces(&report) else {
+            panic!("Couldn't extract racing stack traces");
+        };
+        assert_eq!(first.len(), 2);
+        assert_eq!(second.len(), 1);
+
+        let trace = race_stacktrace(&report);
+        let Ok(trace) = trace else {
+            panic!("{}", trace.err().unwrap());
+        };
+        let swapped = race_stacktrace(&race_report(true));
+        let Ok(swapped) = swapped else {
+            panic!("{}", swapped.err().unwrap());
+        };
+        assert_eq!(trace, swapped);
+        assert_eq!(dedup_stacktraces(&[trace, swapped]), vec![true, false]);
+
+        assert!(racing_stacktraces(&report[..4]).is_err());
+    }
+
+    #[test]
+    fn test_tsan_report() {
+        let mut output: Vec<String> = vec!["Starting threads".to_string()];
+        output.extend(race_report(false));
+        output.extend(race_report(true));
+        output.push("ThreadSanitizer: reported 2 warnings".to_string());
+
+        let Some(report) = extract_tsan_report(&output) else {
+            panic!("Couldn't extract ThreadSanitizer report");
+        };
+        assert_eq!(report.len(), 9);
+        assert!(report[0].starts_with("WARNING: ThreadSanitizer: data race"));
+        assert!(report[8].starts_with("SUMMARY: ThreadSanitizer: data race"));
+        assert!(extract_tsan_report(&output[..1]).is_none());
+
+        let class = TsanContext(report).severity();
+        let Ok(class) = class else {
+            panic!("{}", class.err().unwrap());
+        };
+        assert_eq!(class.short_description, "data-race");
+
+        let class = |warning: &str| {
+            TsanContext(vec![warning.to_string()])
+                .severity()
+                .map(|class| class.short_description)
+                .unwrap_or_default()
+        };
+        assert_eq!(
+            class(
+                "WARNING: ThreadSanitizer: data race on vptr (ctor/dtor vs virtual call) (pid=1)"
+            ),
+            "data-race-vptr"
+        );
+        assert_eq!(
+            class("WARNING: ThreadSanitizer: thread leak (pid=1)"),
+            "thread-leak"
+        );
+        assert_eq!(
+            class("WARNING: ThreadSanitizer: unlock of an unlocked mutex (or by a wrong thread) (pid=1)"),
+            "mutex-bad-unlock"
+        );
+        assert_eq!(
+            class("WARNING: ThreadSanitizer: signal-unsafe call inside of a signal (pid=1)"),
+            "signal-unsafe-call"
+        );
+        assert_eq!(
+            class(
+                "SUMMARY: ThreadSanitizer: heap-use-after-free (virtual call vs free) a.cpp:5 in f"
+            ),
+            "heap-use-after-free"
+        );
+        assert!(class("WARNING: ThreadSanitizer: unknown (pid=1)").is_empty());
+    }
+
+    #[test]
+    fn test_tsan_deadlock() {
+        let report: Vec<String> = [
+            "WARNING: ThreadSanitizer: lock-order-inversion (potential deadlock) (pid=3014)",
+            "  Cycle in lock order graph: M0 (0x55d1c2f0c0a0) => M1 (0x55d1c2f0c0e0) => M0",
+            "",
+            "  Mutex M1 acquired here while holding mutex M0 in thread T1:",
+            "    #0 pthread_mutex_lock /llvm/compiler-rt/lib/tsan/rtl/tsan_interceptors_posix.cpp:1339:3 (deadlock+0x6b5ab)",
+            "    #1 first(void*) /tmp/deadlock.cpp:9:3 (deadlock+0xd1c8e)",
+            "",
+            "  Mutex M0 previously acquired by the same thread here:",
+            "    #0 pthread_mutex_lock /llvm/compiler-rt/lib/tsan/rtl/tsan_interceptors_posix.cpp:1339:3 (deadlock+0x6b5ab)",
+            "    #1 first(void*) /tmp/deadlock.cpp:8:3 (deadlock+0xd1c7e)",
+            "",
+            "  Mutex M0 acquired here while holding mutex M1 in main thread:",
+            "    #0 pthread_mutex_lock /llvm/compiler-rt/lib/tsan/rtl/tsan_interceptors_posix.cpp:1339:3 (deadlock+0x6b5ab)",
+            "    #1 main /tmp/deadlock.cpp:18:3 (deadlock+0xd1d3e)",
+            "",
+            "  Mutex M1 previously acquired by the same thread here:",
+            "    #0 pthread_mutex_lock /llvm/compiler-rt/lib/tsan/rtl/tsan_interceptors_posix.cpp:1339:3 (deadlock+0x6b5ab)",
+            "    #1 main /tmp/deadlock.cpp:17:3 (deadlock+0xd1d2e)",
+            "",
+            "SUMMARY: ThreadSanitizer: lock-order-inversion (potential deadlock) /tmp/deadlock.cpp:9:3 in first(void*)",
+        ]
+        .iter()
+        .map(|l| l.to_string())
+        .collect();
+
+        let acquisitions = extract_mutex_acquisitions(&report);
+        assert_eq!(acquisitions.len(), 4);
+        assert_eq!(acquisitions[0].mutex, "M1");
+        assert_eq!(acquisitions[0].held_mutex, "M0");
+        assert_eq!(acquisitions[0].thread, "thread T1");
+        assert_eq!(acquisitions[0].stacktrace.len(), 2);
+        assert_eq!(acquisitions[1].mutex, "M0");
+        assert!(acquisitions[1].held_mutex.is_empty());
+        assert_eq!(acquisitions[1].thread, "thread T1");
+        assert_eq!(acquisitions[2].thread, "main thread");
+        assert_eq!(acquisitions[3].thread, "main thread");
+        assert_eq!(
+            acquisitions[3].stacktrace[1],
+            "    #1 main /tmp/deadlock.cpp:17:3 (deadlock+0xd1d2e)"
+        );
+
+        let class = TsanContext(report).severity();
+        let Ok(class) = class else {
+            panic!("{}", class.err().unwrap());
+        };
+        assert_eq!(class.short_description, "lock-order-inversion");
+    }
+}
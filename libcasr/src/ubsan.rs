@@ -19,10 +19,12 @@ impl UbsanWarning {
     pub fn extract_stacktrace(&self) -> Result<Vec<String>> {
         AsanStacktrace::extract_stacktrace(&self.message)
     }
+
     /// Transform stack trace line into StacktraceEntry type.
     pub fn parse_stacktrace_entry(entry: &str) -> Result<StacktraceEntry> {
         AsanStacktrace::parse_stacktrace_entry(entry)
     }
+
     /// Get ubsan runtime error message as a vector of lines.
     pub fn ubsan_report(&self) -> Vec<String> {
         self.message
@@ -30,6 +32,34 @@ impl UbsanWarning {
             .map(|s| s.trim_end().to_string())
             .collect()
     }
+
+    /// Get ubsan error kind from summary line, e.g. "signed-integer-overflow".
+    pub fn error_kind(&self) -> Result<String> {
+        let re = Regex::new(r"SUMMARY: UndefinedBehaviorSanitizer: ([A-Za-z_\-\(\)]+)").unwrap();
+        let Some(cap) = re.captures(&self.message) else {
+            return Err(Error::Casr(format!(
+                "Couldn't find ubsan summary: {}",
+                self.message
+            )));
+        };
+        Ok(cap.get(1).unwrap().as_str().to_string())
+    }
+
+    /// Get deduplication key for ubsan warning: source location without
+    /// column number combined with error kind.
+    ///
+    /// # Arguments
+    ///
+    /// * `crashline` - warning crash line
+    pub fn dedup_key(&self, crashline: &CrashLine) -> String {
+        let location = if let CrashLine::Source(debug) = crashline {
+            format!("{}:{}", debug.file, debug.line)
+        } else {
+            crashline.to_string()
+        };
+        let kind = self.error_kind().unwrap_or_default();
+        format!("{location} {kind}")
+    }
 }
 
 impl Severity for UbsanWarning {
@@ -57,6 +87,16 @@ impl Severity for UbsanWarning {
         };
         let short_description = cap.get(1).unwrap().as_str().to_string();
 
+        // Older runtimes prefix shift errors with "invalid-".
+        let kind = short_description
+            .strip_prefix("invalid-shift-")
+            .map(|kind| format!("shift-{kind}"))
+            .unwrap_or_else(|| short_description.clone());
+        if let Ok(mut class) = ExecutionClass::find(&kind) {
+            class.description = description;
+            return Ok(class);
+        }
+
         Ok(ExecutionClass::new((
             "NOT_EXPLOITABLE",
             &short_description,
@@ -267,6 +307,10 @@ SUMMARY: UndefinedBehaviorSanitizer: unsigned-integer-overflow /openjpeg/src/lib
                 crash_line.to_string(),
                 "/tarantool/src/box/sql/build.c:263:17"
             );
+            assert_eq!(
+                warning.dedup_key(&crash_line),
+                "/tarantool/src/box/sql/build.c:263 undefined-behavior"
+            );
         } else {
             panic!("{}", crash_line.err().unwrap());
         }
@@ -378,4 +422,59 @@ SUMMARY: UndefinedBehaviorSanitizer: unsigned-integer-overflow /openjpeg/src/lib
             panic!("{}", crash_line.err().unwrap());
         }
     }
+
+    #[test]
+    fn test_ubsan_standalone() {
+        let stderr = "test.c:5:14: runtime error: signed integer overflow: 2147483647 + 1 cannot be represented in type 'int'
+SUMMARY: UndefinedBehaviorSanitizer: signed-integer-overflow test.c:5:14 in
+test.c:9:12: runtime error: shift exponent 40 is too large for 32-bit type 'int'
+    #0 0x4a2b1c in shift /tmp/test.c:9:12
+    #1 0x4a2c3d in main /tmp/test.c:15:5
+    #2 0x7ffff7829d8f  (/lib/x86_64-linux-gnu/libc.so.6+0x29d8f)
+
+SUMMARY: UndefinedBehaviorSanitizer: invalid-shift-exponent test.c:9:12 in
+test.c:12:10: runtime error: load of misaligned address 0x7ffd2e1c0a21 for type 'int', which requires 4 byte alignment
+0x7ffd2e1c0a21: note: pointer points here
+ 00 00 00  01 02 03 04 00 00 00 00
+              ^
+SUMMARY: UndefinedBehaviorSanitizer: misaligned-pointer-use test.c:12:10 in";
+        let warnings = extract_ubsan_warnings(stderr);
+        assert_eq!(warnings.len(), 3, "{:?}", warnings);
+
+        let execution_class = warnings[0].severity();
+        let Ok(execution_class) = execution_class else {
+            panic!("{}", execution_class.err().unwrap());
+        };
+        assert_eq!(execution_class.short_description, "signed-integer-overflow");
+        assert_eq!(
+            execution_class.description,
+            "signed integer overflow: 2147483647 + 1 cannot be represented in type 'int'"
+        );
+        assert!(!execution_class.explanation.is_empty());
+        assert!(warnings[0].extract_stacktrace().is_err());
+        let Ok(crash_line) = warnings[0].crash_line() else {
+            panic!("Couldn't get crash line");
+        };
+        assert_eq!(crash_line.to_string(), "test.c:5:14");
+
+        let Ok(execution_class) = warnings[1].severity() else {
+            panic!("Couldn't estimate severity");
+        };
+        assert_eq!(execution_class.short_description, "shift-exponent");
+        let stacktrace = warnings[1].extract_stacktrace();
+        let Ok(stacktrace) = stacktrace else {
+            panic!("{}", stacktrace.err().unwrap());
+        };
+        assert_eq!(stacktrace.len(), 3);
+        let Ok(crash_line) = warnings[1].crash_line() else {
+            panic!("Couldn't get crash line");
+        };
+        assert_eq!(crash_line.to_string(), "/tmp/test.c:9:12");
+
+        let Ok(execution_class) = warnings[2].severity() else {
+            panic!("Couldn't estimate severity");
+        };
+        assert_eq!(execution_class.severity, "NOT_EXPLOITABLE");
+        assert_eq!(execution_class.short_description, "misaligned-pointer-use");
+    }
 }
@@ -0,0 +1,500 @@
+//! Valgrind module implements `ParseStacktrace` and `Severity` traits for
+//! Valgrind Memcheck errors from text (`--log-file`) or XML (`--xml=yes`) logs.
+use regex::Regex;
+
+use crate::error::*;
+use crate::execution_class::{is_near_null, ExecutionClass};
+use crate::severity::Severity;
+use crate::stacktrace::ParseStacktrace;
+use crate::stacktrace::*;
+
+/// Structure provides an interface for processing the stack trace.
+pub struct ValgrindStacktrace;
+
+impl ParseStacktrace for ValgrindStacktrace {
+    fn extract_stacktrace(stream: &str) -> Result<Vec<String>> {
+        let rframe = Regex::new(r"^\s*(?:==\d+==\s*)?(?:at|by) 0x[0-9A-Fa-f]+: ").unwrap();
+        let stacktrace: Vec<String> = stream
+            .split('\n')
+            .skip_while(|line| !rframe.is_match(line))
+            .take_while(|line| rframe.is_match(line))
+            .map(|line| line.trim_end().to_string())
+            .collect();
+        if stacktrace.is_empty() {
+            return Err(Error::Casr(
+                "Couldn't find stack trace in Valgrind report".to_string(),
+            ));
+        }
+        Ok(stacktrace)
+    }
+
+    fn parse_stacktrace_entry(entry: &str) -> Result<StacktraceEntry> {
+        // ==123==    at 0x109162: main (test.c:7)
+        // ==123==    by 0x4848899: ??? (in /usr/lib/libfoo.so)
+        let re = Regex::new(r"^\s*(?:==\d+==\s*)?(?:at|by) 0x([0-9A-Fa-f]+): (.*)$").unwrap();
+        let Some(caps) = re.captures(entry) else {
+            return Err(Error::Casr(format!(
+                "Couldn't parse Valgrind stack trace entry: {entry}"
+            )));
+        };
+        let mut stentry = StacktraceEntry::default();
+        let num = caps.get(1).unwrap().as_str();
+        let Ok(address) = u64::from_str_radix(num, 16) else {
+            return Err(Error::Casr(format!("Couldn't parse address: {num}")));
+        };
+        stentry.address = address;
+
+        let rest = caps.get(2).unwrap().as_str().trim();
+        let (function, location) = match rest.rfind(" (") {
+            Some(pos) if rest.ends_with(')') => {
+                (&rest[..pos], Some(&rest[pos + 2..rest.len() - 1]))
+            }
+            _ => (rest, None),
+        };
+        if function != "???" {
+            stentry.function = function.to_string();
+        }
+        if let Some(location) = location {
+            if let Some(module) = location.strip_prefix("in ") {
+                stentry.module = module.to_string();
+            } else if let Some((file, line)) = location.rsplit_once(':') {
+                stentry.debug.file = file.to_string();
+                let Ok(line) = line.parse::<u64>() else {
+                    return Err(Error::Casr(format!("Couldn't parse source line: {line}")));
+                };
+                stentry.debug.line = line;
+            } else {
+                stentry.debug.file = location.to_string();
+            }
+        }
+        Ok(stentry)
+    }
+}
+
+/// Extract Valgrind Memcheck errors from text or XML log. XML errors are
+/// converted to text format.
+///
+/// # Arguments
+///
+/// * `stream` - Valgrind log lines
+///
+/// # Return value
+///
+/// Errors with stack traces (e.g., invalid accesses, leaks, and fatal
+/// signals), "==pid==" prefix is removed from lines
+pub fn extract_valgrind_errors(stream: &[String]) -> Vec<Vec<String>> {
+    if stream.iter().any(|line| line.contains("<valgrindoutput>")) {
+        return xml_errors(stream);
+    }
+    let rprefix = Regex::new(r"^==\d+==( ?)(.*)$").unwrap();
+    let mut errors = Vec::new();
+    let mut error: Vec<String> = Vec::new();
+    for line in stream {
+        let Some(caps) = rprefix.captures(line.trim_end()) else {
+            continue;
+        };
+        let line = caps.get(2).unwrap().as_str();
+        if line.trim().is_empty() {
+            errors.push(std::mem::take(&mut error));
+        } else {
+            error.push(line.to_string());
+        }
+    }
+    errors.push(error);
+    errors.retain(|error| {
+        ValgrindStacktrace::extract_stacktrace(&error.join("\n")).is_ok()
+            && !error[0].starts_with(' ')
+    });
+    errors
+}
+
+/// Convert errors and fatal signal from Valgrind XML log to text format
+///
+/// # Arguments
+///
+/// * `stream` - Valgrind XML log lines
+fn xml_errors(stream: &[String]) -> Vec<Vec<String>> {
+    let rtag = Regex::new(r"^<([a-z_]+)>(.*)</[a-z_]+>$").unwrap();
+    let mut errors = Vec::new();
+    let mut error: Option<Vec<String>> = None;
+    let mut frame: Vec<(String, String)> = Vec::new();
+    let mut first_frame = false;
+    let mut signo = String::new();
+    for line in stream.iter().map(|line| line.trim()) {
+        match line {
+            "<error>" | "<fatal_signal>" => error = Some(Vec::new()),
+            "</error>" | "</fatal_signal>" => errors.extend(error.take()),
+            "<stack>" => first_frame = true,
+            "<frame>" => frame.clear(),
+            "</frame>" => {
+                let Some(error) = error.as_mut() else {
+                    continue;
+                };
+                let value = |tag: &str| {
+                    frame
+                        .iter()
+                        .find(|(name, _)| name == tag)
+                        .map(|(_, value)| value.as_str())
+                };
+                let location = match (value("file"), value("line"), value("obj")) {
+                    (Some(file), Some(line), _) => {
+                        let path = value("dir")
+                            .map(|dir| format!("{dir}/{file}"))
+                            .unwrap_or_else(|| file.to_string());
+                        format!(" ({path}:{line})")
+                    }
+                    (_, _, Some(obj)) => format!(" (in {obj})"),
+                    _ => String::new(),
+                };
+                error.push(format!(
+                    "   {} {}: {}{location}",
+                    if first_frame { "at" } else { "by" },
+                    value("ip").unwrap_or("0x0"),
+                    value("fn").unwrap_or("???"),
+                ));
+                first_frame = false;
+            }
+            _ => {
+                let (Some(error), Some(caps)) = (error.as_mut(), rtag.captures(line)) else {
+                    continue;
+                };
+                let value = unescape_xml(caps.get(2).unwrap().as_str());
+                match caps.get(1).unwrap().as_str() {
+                    // Leak records have <xwhat><text>...</text></xwhat>.
+                    "what" | "text" if error.is_empty() => error.push(value),
+                    "auxwhat" => error.push(format!(" {value}")),
+                    "signo" => signo = value,
+                    "signame" => error.push(format!(
+                        "Process terminating with default action of signal {signo} ({value})"
+                    )),
+                    "siaddr" => error.push(format!(" Access at address {value}")),
+                    tag @ ("ip" | "obj" | "fn" | "dir" | "file" | "line") => {
+                        frame.push((tag.to_string(), value))
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+    errors
+}
+
+/// Replace XML entities with characters
+///
+/// # Arguments
+///
+/// * `text` - XML text
+fn unescape_xml(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+/// Information about Valgrind Memcheck error.
+pub struct ValgrindContext(pub Vec<String>);
+
+impl ValgrindContext {
+    /// Get stack trace where memory was allocated (e.g., for invalid access
+    /// outside of heap block)
+    pub fn allocation_stacktrace(&self) -> Vec<String> {
+        self.stacktrace(r"^(Address 0x[0-9a-fA-F]+ is .* alloc'd|Block was alloc'd at)$")
+    }
+
+    /// Get stack trace where memory was deallocated (e.g., for use after free
+    /// or double free)
+    pub fn deallocation_stacktrace(&self) -> Vec<String> {
+        self.stacktrace(r"^Address 0x[0-9a-fA-F]+ is .* free'd$")
+    }
+
+    /// Get stack trace that follows auxiliary header line
+    ///
+    /// # Arguments
+    ///
+    /// * `header` - regular expression for header line
+    fn stacktrace(&self, header: &str) -> Vec<String> {
+        let header = Regex::new(header).unwrap();
+        let Some(start) = self.0.iter().position(|line| header.is_match(line.trim())) else {
+            return Vec::new();
+        };
+        ValgrindStacktrace::extract_stacktrace(&self.0[start + 1..].join("\n")).unwrap_or_default()
+    }
+
+    /// Get address description of invalid access, e.g., "Address 0x4a4a040
+    /// is 0 bytes inside a block of size 4 free'd"
+    fn address(&self) -> Option<(u64, &str)> {
+        let raddress = Regex::new(r"(?:Address|at address) 0x([0-9a-fA-F]+)").unwrap();
+        self.0.iter().skip(1).find_map(|line| {
+            let caps = raddress.captures(line)?;
+            let address = u64::from_str_radix(caps.get(1).unwrap().as_str(), 16).ok()?;
+            Some((address, line.trim()))
+        })
+    }
+}
+
+impl Severity for ValgrindContext {
+    fn severity(&self) -> Result<ExecutionClass> {
+        let Some(header) = self.0.first().map(|line| line.trim()) else {
+            return Err(Error::Casr(
+                "Cannot estimate severity: Valgrind report is empty.".to_string(),
+            ));
+        };
+        let raccess = Regex::new(r"^Invalid (read|write) of size \d+").unwrap();
+        let rsignal =
+            Regex::new(r"^Process terminating with default action of signal \d+ \((SIG[A-Z]+)\)")
+                .unwrap();
+        let address = self.address();
+        if let Some(caps) = raccess.captures(header) {
+            let access = caps.get(1).unwrap().as_str().to_uppercase();
+            let (class, near_null) = match address {
+                Some((_, description)) if description.ends_with("free'd") => {
+                    ("heap-use-after-free", false)
+                }
+                Some((_, description)) if description.ends_with("alloc'd") => {
+                    ("heap-buffer-overflow", false)
+                }
+                Some((_, description))
+                    if description.contains("stack") && !description.contains("not stack'd") =>
+                {
+                    ("stack-buffer-overflow", false)
+                }
+                Some((address, _)) => ("SEGV", is_near_null(address)),
+                None => ("SEGV", false),
+            };
+            return ExecutionClass::san_find(class, Some(&access), near_null);
+        }
+        if header.starts_with("Invalid free()") {
+            return if address.is_some_and(|(_, description)| description.ends_with("free'd")) {
+                ExecutionClass::find("double-free")
+            } else {
+                ExecutionClass::find("bad-free")
+            };
+        }
+        if header.starts_with("Mismatched free()") {
+            return ExecutionClass::find("alloc-dealloc-mismatch");
+        }
+        if header.starts_with("Conditional jump or move depends on uninitialised value")
+            || header.starts_with("Use of uninitialised value")
+        {
+            return ExecutionClass::find("use-of-uninitialized-value");
+        }
+        if header.starts_with("Syscall param") && header.contains("uninitialised byte") {
+            return ExecutionClass::find("uninit-bytes-leak");
+        }
+        if header.starts_with("Source and destination overlap") {
+            return ExecutionClass::find("param-overlap");
+        }
+        if header.contains("has a fishy (possibly negative) value") {
+            return ExecutionClass::find("negative-size-param");
+        }
+        if header.contains(" lost in loss record ") {
+            return ExecutionClass::find("memory-leaks");
+        }
+        if let Some(caps) = rsignal.captures(header) {
+            if self
+                .0
+                .iter()
+                .any(|line| line.contains("Stack overflow in thread"))
+            {
+                return ExecutionClass::find("StackOverflow");
+            }
+            return match caps.get(1).unwrap().as_str() {
+                "SIGABRT" => ExecutionClass::find("AbortSignal"),
+                "SIGFPE" => ExecutionClass::find("FPE"),
+                "SIGILL" => ExecutionClass::find("BadInstruction"),
+                "SIGTRAP" => ExecutionClass::find("TrapSignal"),
+                _ => ExecutionClass::find("AccessViolation"),
+            };
+        }
+        Err(Error::Casr(format!("Unknown Valgrind error: {header}")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_valgrind_text() {
+        let log: Vec<String> = [
+            "==4242== Memcheck, a memory error detector",
+            "==4242== Command: ./test_uaf",
+            "==4242==",
+            "==4242== Invalid read of size 4",
+            "==4242==    at 0x10917A: main (test_uaf.c:8)",
+            "==4242==  Address 0x4a4a040 is 0 bytes inside a block of size 4 free'd",
+            "==4242==    at 0x483CA3F: free (vg_replace_malloc.c:540)",
+            "==4242==    by 0x109175: main (test_uaf.c:7)",
+            "==4242==  Block was alloc'd at",
+            "==4242==    at 0x483B7F3: malloc (vg_replace_malloc.c:309)",
+            "==4242==    by 0x109165: main (test_uaf.c:6)",
+            "==4242==",
+            "==4242== Conditional jump or move depends on uninitialised value(s)",
+            "==4242==    at 0x4848899: ??? (in /usr/lib/libfoo.so)",
+            "==4242==    by 0x109190: foo(int) (test_uaf.c:12)",
+            "==4242==",
+            "==4242== HEAP SUMMARY:",
+            "==4242==     in use at exit: 0 bytes in 0 blocks",
+            "==4242==",
+            "==4242== ERROR SUMMARY: 2 errors from 2 contexts (suppressed: 0 from 0)",
+        ]
+        .iter()
+        .map(|l| l.to_string())
+        .collect();
+        let errors = extract_valgrind_errors(&log);
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors[0][0], "Invalid read of size 4");
+        assert_eq!(errors[0].len(), 8);
+
+        let context = ValgrindContext(errors[0].clone());
+        let Ok(class) = context.severity() else {
+            panic!("Couldn't estimate severity");
+        };
+        assert_eq!(class.short_description, "heap-use-after-free(read)");
+        assert_eq!(context.deallocation_stacktrace().len(), 2);
+        assert_eq!(context.allocation_stacktrace().len(), 2);
+
+        let Ok(stacktrace) = ValgrindStacktrace::extract_stacktrace(&errors[1].join("\n")) else {
+            panic!("Couldn't extract Valgrind stack trace");
+        };
+        let Ok(stacktrace) = ValgrindStacktrace::parse_stacktrace(&stacktrace) else {
+            panic!("Couldn't parse Valgrind stack trace");
+        };
+        assert_eq!(stacktrace.len(), 2);
+        assert_eq!(stacktrace[0].address, 0x4848899);
+        assert!(stacktrace[0].function.is_empty());
+        assert_eq!(stacktrace[0].module, "/usr/lib/libfoo.so");
+        assert_eq!(stacktrace[1].function, "foo(int)");
+        assert_eq!(stacktrace[1].debug.file, "test_uaf.c");
+        assert_eq!(stacktrace[1].debug.line, 12);
+        let Ok(class) = ValgrindContext(errors[1].clone()).severity() else {
+            panic!("Couldn't estimate severity");
+        };
+        assert_eq!(class.short_description, "use-of-uninitialized-value");
+
+        for (error, address, class) in [
+            (
+                "Invalid write of size 1",
+                "Address 0x4a4a044 is 0 bytes after a block of size 4 alloc'd",
+                "heap-buffer-overflow(write)",
+            ),
+            (
+                "Invalid write of size 4",
+                "Address 0x0 is not stack'd, malloc'd or (recently) free'd",
+                "DestAvNearNull",
+            ),
+            (
+                "Invalid free() / delete / delete[] / realloc()",
+                "Address 0x4a4a040 is 0 bytes inside a block of size 4 free'd",
+                "double-free",
+            ),
+            (
+                "Invalid free() / delete / delete[] / realloc()",
+                "Address 0x1ffefffd4c is on thread 1's stack",
+                "bad-free",
+            ),
+            (
+                "Mismatched free() / delete / delete []",
+                "Address 0x4a4a040 is 0 bytes inside a block of size 4 alloc'd",
+                "alloc-dealloc-mismatch",
+            ),
+            (
+                "Process terminating with default action of signal 11 (SIGSEGV)",
+                "Access not within mapped region at address 0x0",
+                "AccessViolation",
+            ),
+        ] {
+            let context = ValgrindContext(vec![
+                error.to_string(),
+                "   at 0x10913D: main (test.c:4)".to_string(),
+                format!(" {address}"),
+            ]);
+            let Ok(severity) = context.severity() else {
+                panic!("Couldn't estimate severity for {error}");
+            };
+            assert_eq!(severity.short_description, class);
+        }
+    }
+
+    #[test]
+    fn test_valgrind_xml() {
+        let log: Vec<String> = [
+            "<?xml version=\"1.0\"?>",
+            "<valgrindoutput>",
+            "<error>",
+            "  <unique>0x0</unique>",
+            "  <tid>1</tid>",
+            "  <kind>InvalidWrite</kind>",
+            "  <what>Invalid write of size 4</what>",
+            "  <stack>",
+            "    <frame>",
+            "      <ip>0x10916B</ip>",
+            "      <obj>/tmp/test_hbo</obj>",
+            "      <fn>main</fn>",
+            "      <dir>/tmp</dir>",
+            "      <file>test_hbo.c</file>",
+            "      <line>7</line>",
+            "    </frame>",
+            "  </stack>",
+            "  <auxwhat>Address 0x4a4a068 is 0 bytes after a block of size 40 alloc&apos;d</auxwhat>",
+            "  <stack>",
+            "    <frame>",
+            "      <ip>0x483B7F3</ip>",
+            "      <obj>/usr/lib/valgrind/vgpreload_memcheck-amd64-linux.so</obj>",
+            "      <fn>malloc</fn>",
+            "    </frame>",
+            "    <frame>",
+            "      <ip>0x109156</ip>",
+            "      <obj>/tmp/test_hbo</obj>",
+            "      <fn>main</fn>",
+            "      <dir>/tmp</dir>",
+            "      <file>test_hbo.c</file>",
+            "      <line>6</line>",
+            "    </frame>",
+            "  </stack>",
+            "</error>",
+            "<error>",
+            "  <kind>Leak_DefinitelyLost</kind>",
+            "  <xwhat>",
+            "    <text>40 bytes in 1 blocks are definitely lost in loss record 1 of 1</text>",
+            "    <leakedbytes>40</leakedbytes>",
+            "  </xwhat>",
+            "  <stack>",
+            "    <frame>",
+            "      <ip>0x483B7F3</ip>",
+            "      <fn>malloc</fn>",
+            "    </frame>",
+            "  </stack>",
+            "</error>",
+            "</valgrindoutput>",
+        ]
+        .iter()
+        .map(|l| l.to_string())
+        .collect();
+        let errors = extract_valgrind_errors(&log);
+        assert_eq!(errors.len(), 2);
+        assert_eq!(
+            errors[0],
+            vec![
+                "Invalid write of size 4".to_string(),
+                "   at 0x10916B: main (/tmp/test_hbo.c:7)".to_string(),
+                " Address 0x4a4a068 is 0 bytes after a block of size 40 alloc'd".to_string(),
+                "   at 0x483B7F3: malloc (in /usr/lib/valgrind/vgpreload_memcheck-amd64-linux.so)"
+                    .to_string(),
+                "   by 0x109156: main (/tmp/test_hbo.c:6)".to_string(),
+            ]
+        );
+        let context = ValgrindContext(errors[0].clone());
+        let Ok(class) = context.severity() else {
+            panic!("Couldn't estimate severity");
+        };
+        assert_eq!(class.short_description, "heap-buffer-overflow(write)");
+        assert_eq!(context.allocation_stacktrace().len(), 2);
+        assert!(context.deallocation_stacktrace().is_empty());
+        let Ok(class) = ValgrindContext(errors[1].clone()).severity() else {
+            panic!("Couldn't estimate severity");
+        };
+        assert_eq!(class.short_description, "memory-leaks");
+    }
+}